@@ -1,3 +1,5 @@
 fn main() {
-    slint_build::compile("ui/app-window.slint").expect("Slint build failed");
+    // Translations of the @tr strings, lang/<locale>/LC_MESSAGES/taxstud.po
+    let config = slint_build::CompilerConfiguration::new().with_bundled_translations("lang");
+    slint_build::compile_with_config("ui/app-window.slint", config).expect("Slint build failed");
 }
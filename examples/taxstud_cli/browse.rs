@@ -0,0 +1,399 @@
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::HashMap;
+use std::io;
+use taxstud_core::*;
+
+/// A hierarchy node flattened for display in the tree pane
+struct TreeRow {
+    species: String,
+    depth: usize,
+}
+
+/// Which part of the browser receives key presses
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Focus {
+    Tree,
+    Items,
+    FacetInput,
+}
+
+/// Terminal browser state
+struct BrowseApp {
+    data: TaxonomyData,
+    schema: TaxonomySchema,
+    tree: Vec<TreeRow>,
+    tree_state: ListState,
+    /// Indices of the items shown, after filtering and sorting
+    displayed: Vec<usize>,
+    items_state: ListState,
+    filters: Filters,
+    sort_by_name: bool,
+    focus: Focus,
+    facet_input: String,
+    status: String,
+    quit: bool,
+}
+
+/// Run the interactive browser until the user quits
+pub fn run(data: TaxonomyData, schema: TaxonomySchema) -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let mut app = BrowseApp::new(data, schema);
+    let result = app.event_loop(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl BrowseApp {
+    fn new(data: TaxonomyData, schema: TaxonomySchema) -> Self {
+        let mut tree = vec![TreeRow {
+            species: schema.classical_hierarchy.root.clone(),
+            depth: 0,
+        }];
+        tree.extend(
+            schema
+                .classical_hierarchy
+                .iter_preorder()
+                .map(|(depth, node)| TreeRow {
+                    species: node.species.clone(),
+                    depth,
+                }),
+        );
+
+        let mut app = Self {
+            data,
+            schema,
+            tree,
+            tree_state: ListState::default().with_selected(Some(0)),
+            displayed: Vec::new(),
+            items_state: ListState::default(),
+            // Selecting a tree node should cover everything beneath it
+            filters: Filters {
+                include_descendants: true,
+                ..Filters::default()
+            },
+            sort_by_name: false,
+            focus: Focus::Tree,
+            facet_input: String::new(),
+            status: String::new(),
+            quit: false,
+        };
+        app.refresh_items();
+        app
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    self.handle_key(key);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Re-apply filters and sorting to the item list
+    fn refresh_items(&mut self) {
+        self.displayed = filter_indices_with_schema(&self.data.items, &self.filters, &self.schema);
+        if self.sort_by_name {
+            sort_indices_with_options(
+                &self.data.items,
+                &mut self.displayed,
+                "name",
+                None,
+                &SortOptions::default(),
+            );
+        }
+
+        let selected = match self.items_state.selected() {
+            _ if self.displayed.is_empty() => None,
+            Some(idx) => Some(idx.min(self.displayed.len() - 1)),
+            None => Some(0),
+        };
+        self.items_state.select(selected);
+
+        self.status = format!(
+            "{} of {} items shown",
+            self.displayed.len(),
+            self.data.items.len()
+        );
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        if self.focus == Focus::FacetInput {
+            self.handle_facet_input_key(key);
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Tab | KeyCode::BackTab => {
+                self.focus = if self.focus == Focus::Tree {
+                    Focus::Items
+                } else {
+                    Focus::Tree
+                };
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Enter if self.focus == Focus::Tree => self.toggle_genus_filter(),
+            KeyCode::Char('/') => {
+                self.facet_input = format_facet_filters(&self.filters.facets);
+                self.focus = Focus::FacetInput;
+            }
+            KeyCode::Char('c') => {
+                self.filters.genera.clear();
+                self.filters.facets.clear();
+                self.refresh_items();
+            }
+            KeyCode::Char('s') => {
+                self.sort_by_name = !self.sort_by_name;
+                self.refresh_items();
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_facet_input_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter => {
+                let facet_strings: Vec<String> = self
+                    .facet_input
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect();
+                self.filters.facets = parse_facet_filters(&facet_strings);
+                self.focus = Focus::Items;
+                self.refresh_items();
+            }
+            KeyCode::Esc => self.focus = Focus::Items,
+            KeyCode::Backspace => {
+                self.facet_input.pop();
+            }
+            KeyCode::Char(c) => self.facet_input.push(c),
+            _ => {}
+        }
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let (state, len) = match self.focus {
+            Focus::Tree => (&mut self.tree_state, self.tree.len()),
+            _ => (&mut self.items_state, self.displayed.len()),
+        };
+        if len == 0 {
+            return;
+        }
+
+        let current = state.selected().unwrap_or(0) as isize;
+        let next = (current + delta).clamp(0, len as isize - 1);
+        state.select(Some(next as usize));
+    }
+
+    /// Add or remove the selected hierarchy node from the genus filter
+    fn toggle_genus_filter(&mut self) {
+        let Some(row) = self
+            .tree_state
+            .selected()
+            .and_then(|idx| self.tree.get(idx))
+        else {
+            return;
+        };
+
+        let species = row.species.clone();
+        if let Some(pos) = self.filters.genera.iter().position(|g| *g == species) {
+            self.filters.genera.remove(pos);
+        } else {
+            self.filters.genera.push(species);
+        }
+        self.refresh_items();
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let outer = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(5), Constraint::Length(3)])
+            .split(frame.area());
+
+        let panes = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+            .split(outer[0]);
+
+        let right = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+            .split(panes[1]);
+
+        self.draw_tree(frame, panes[0]);
+        self.draw_items(frame, right[0]);
+        self.draw_details(frame, right[1]);
+        self.draw_footer(frame, outer[1]);
+    }
+
+    fn draw_tree(&mut self, frame: &mut Frame, area: Rect) {
+        let rows: Vec<ListItem> = self
+            .tree
+            .iter()
+            .map(|row| {
+                let marker = if self.filters.genera.contains(&row.species) {
+                    "● "
+                } else {
+                    ""
+                };
+                let prefix = if row.depth == 0 {
+                    String::new()
+                } else {
+                    format!("{}├─ ", "  ".repeat(row.depth - 1))
+                };
+                ListItem::new(format!("{}{}{}", prefix, marker, row.species))
+            })
+            .collect();
+
+        let title = format!("Classification · {}", self.schema.title);
+        let list = List::new(rows)
+            .block(pane_block(&title, self.focus == Focus::Tree))
+            .highlight_style(highlight_style());
+        frame.render_stateful_widget(list, area, &mut self.tree_state);
+    }
+
+    fn draw_items(&mut self, frame: &mut Frame, area: Rect) {
+        let rows: Vec<ListItem> = self
+            .displayed
+            .iter()
+            .map(|&index| ListItem::new(self.data.items[index].name.clone()))
+            .collect();
+
+        let title = format!("Items ({})", self.displayed.len());
+        let list = List::new(rows)
+            .block(pane_block(&title, self.focus == Focus::Items))
+            .highlight_style(highlight_style());
+        frame.render_stateful_widget(list, area, &mut self.items_state);
+    }
+
+    fn draw_details(&self, frame: &mut Frame, area: Rect) {
+        let selected = self
+            .items_state
+            .selected()
+            .and_then(|idx| self.displayed.get(idx))
+            .map(|&index| &self.data.items[index]);
+
+        let lines = match selected {
+            Some(item) => {
+                let mut lines = vec![
+                    Line::from(Span::styled(
+                        item.name.clone(),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    )),
+                    Line::from(format!("Path: {}", item.classical_path.join(" → "))),
+                    Line::from(""),
+                ];
+
+                let mut facet_names: Vec<_> = item.facets.keys().collect();
+                facet_names.sort();
+                for facet_name in facet_names {
+                    if let Some(value) = item.get_facet_as_string(facet_name) {
+                        lines.push(Line::from(format!("• {}: {}", facet_name, value)));
+                    }
+                }
+                lines
+            }
+            None => vec![Line::from("No item selected")],
+        };
+
+        let details = Paragraph::new(lines)
+            .block(pane_block("Details", false))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(details, area);
+    }
+
+    fn draw_footer(&self, frame: &mut Frame, area: Rect) {
+        let line = if self.focus == Focus::FacetInput {
+            Line::from(vec![
+                Span::styled("Facets: ", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(self.facet_input.clone()),
+                Span::styled("█", Style::default().fg(Color::Gray)),
+            ])
+        } else {
+            let mut spans = vec![Span::raw(self.status.clone())];
+            if has_filters(&self.filters) {
+                spans.push(Span::styled(
+                    format!("  [{}]", describe_filters(&self.filters)),
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+            if self.sort_by_name {
+                spans.push(Span::raw("  sorted by name"));
+            }
+            Line::from(spans)
+        };
+
+        let help = if self.focus == Focus::FacetInput {
+            "Enter apply · Esc cancel · format: name=value, name=value"
+        } else {
+            "Tab pane · ↑/↓ move · Enter filter genus · / facets · c clear · s sort · q quit"
+        };
+
+        let footer = Paragraph::new(vec![line]).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(help)
+                .border_style(Style::default().fg(Color::DarkGray)),
+        );
+        frame.render_widget(footer, area);
+    }
+}
+
+/// Format facet filters back into the editable "name=value, name>=10" form
+fn format_facet_filters(facets: &HashMap<String, Vec<FacetPredicate>>) -> String {
+    let mut parts: Vec<String> = facets
+        .iter()
+        .flat_map(|(name, predicates)| predicates.iter().map(|p| p.to_filter_string(name)))
+        .collect();
+    parts.sort();
+    parts.join(", ")
+}
+
+/// Short human-readable description of the active filters
+fn describe_filters(filters: &Filters) -> String {
+    let mut parts = Vec::new();
+    if !filters.genera.is_empty() {
+        parts.push(format!("genus: {}", filters.genera.join(" OR ")));
+    }
+
+    let mut facet_names: Vec<_> = filters.facets.keys().collect();
+    facet_names.sort();
+    for facet_name in facet_names {
+        parts.push(format!(
+            "{}: {}",
+            facet_name,
+            describe_facet_predicates(&filters.facets[facet_name])
+        ));
+    }
+    parts.join("; ")
+}
+
+fn pane_block(title: &str, focused: bool) -> Block<'static> {
+    let border_color = if focused {
+        Color::Cyan
+    } else {
+        Color::DarkGray
+    };
+    Block::default()
+        .borders(Borders::ALL)
+        .title(title.to_string())
+        .border_style(Style::default().fg(border_color))
+}
+
+fn highlight_style() -> Style {
+    Style::default()
+        .add_modifier(Modifier::REVERSED)
+        .add_modifier(Modifier::BOLD)
+}
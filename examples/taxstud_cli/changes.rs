@@ -0,0 +1,57 @@
+use colored::Colorize;
+use std::fs;
+use std::path::Path;
+use taxstud_core::*;
+
+/// Print computed changes one per line, colored by kind
+/// Mutating commands print these, and with --dry-run print only these
+pub fn print(changes: &[Change]) {
+    if changes.is_empty() {
+        println!("  (no changes)");
+    }
+    for change in changes {
+        let line = format!("  {}", change);
+        match change.kind {
+            ChangeKind::Added => println!("{}", line.green()),
+            ChangeKind::Removed => println!("{}", line.red()),
+            ChangeKind::Changed => println!("{}", line.yellow()),
+        }
+    }
+}
+
+/// Print what writing `items` to the data file at `path` would change: its items against
+/// the ones the file has now, or all of them when there is no such data file yet
+pub fn print_data_file(path: &Path, items: &[Item]) {
+    let current = fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str::<TaxonomyData>(&contents).ok());
+    print_file_header(path, current.is_some());
+    let current = current.map(|data| data.items).unwrap_or_default();
+    print(&diff_items(&current, items));
+}
+
+/// Print what writing `schema` to `path` would change: its nodes and facets against the
+/// schema there now, or all of them when there is no such schema yet
+pub fn print_schema_file(path: &Path, schema: &TaxonomySchema) {
+    let current = load_schema(path).ok();
+    print_file_header(path, current.is_some());
+    // A new schema is compared with one of just its root, so the root isn't a change
+    let current = current.unwrap_or_else(|| {
+        TaxonomySchemaBuilder::new(&schema.title)
+            .hierarchy(
+                HierarchyBuilder::new()
+                    .root(&schema.classical_hierarchy.root)
+                    .build(),
+            )
+            .build()
+    });
+    print(&diff_schemas(&current, schema));
+}
+
+fn print_file_header(path: &Path, exists: bool) {
+    if exists {
+        println!("{}:", path.display());
+    } else {
+        println!("{} (new):", path.display());
+    }
+}
@@ -0,0 +1,61 @@
+use serde_json::json;
+use std::io::{self, BufRead, Write};
+use taxstud_core::*;
+
+/// Suggest classical paths for new item names, best first
+/// With no names given, reads them one per line from stdin (blank lines are skipped)
+pub fn run(
+    data: &TaxonomyData,
+    schema: &TaxonomySchema,
+    names: &[String],
+    top: usize,
+    json: bool,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let names = if names.is_empty() {
+        read_names(io::stdin().lock())?
+    } else {
+        names.to_vec()
+    };
+    let hierarchy = &schema.classical_hierarchy;
+
+    let mut results = Vec::new();
+    for name in &names {
+        let mut suggestions = suggest_path(name, hierarchy, &data.items);
+        suggestions.truncate(top);
+
+        if json {
+            let suggestions: Vec<_> = suggestions
+                .iter()
+                .map(|(path, score)| json!({ "path": path, "score": score }))
+                .collect();
+            results.push(json!({ "name": name, "suggestions": suggestions }));
+            continue;
+        }
+
+        writeln!(out, "{}", name)?;
+        if suggestions.is_empty() {
+            writeln!(out, "  (no suggestion)")?;
+        }
+        for (path, score) in &suggestions {
+            writeln!(out, "  {:>3.0}%  {}", score * 100.0, path.join(" → "))?;
+        }
+    }
+
+    if json {
+        writeln!(out, "{}", serde_json::Value::Array(results))?;
+    }
+    Ok(())
+}
+
+fn read_names(reader: impl BufRead) -> io::Result<Vec<String>> {
+    let mut names = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let name = line.trim();
+        if !name.is_empty() {
+            names.push(name.to_string());
+        }
+    }
+    Ok(names)
+}
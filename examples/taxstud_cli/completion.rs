@@ -0,0 +1,131 @@
+use clap::CommandFactory;
+use clap_complete::engine::CompletionCandidate;
+use clap_complete::env::Shells;
+use clap_complete::Shell;
+use std::ffi::OsStr;
+use std::io;
+use std::path::Path;
+use taxstud_core::*;
+
+use crate::Cli;
+
+/// Environment variable the shell sets when asking for completions
+const COMPLETE_VAR: &str = "COMPLETE";
+
+/// Answer a completion request from the shell, if this process was started for one
+/// Must run before anything is written to stdout
+pub fn handle_completion_request() {
+    clap_complete::CompleteEnv::with_factory(Cli::command)
+        .var(COMPLETE_VAR)
+        .complete();
+}
+
+/// Print the registration script that wires `shell` up to dynamic completions
+pub fn print_registration(shell: Shell) -> io::Result<()> {
+    let shell_name = shell.to_string();
+    let shells = Shells::builtins();
+    let completer = shells
+        .completer(&shell_name)
+        .ok_or_else(|| io::Error::other(format!("Unsupported shell '{}'", shell_name)))?;
+
+    let completer_path = std::env::current_exe()?;
+    let bin = std::env::args()
+        .next()
+        .as_deref()
+        .and_then(|arg0| Path::new(arg0).file_name())
+        .and_then(|name| name.to_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| Cli::command().get_name().to_string());
+
+    completer.write_registration(
+        COMPLETE_VAR,
+        Cli::command().get_name(),
+        &bin,
+        &completer_path.to_string_lossy(),
+        &mut io::stdout(),
+    )
+}
+
+/// Complete `--facet` values: facet names first, then `name=value` pairs
+pub fn complete_facet(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(schema) = schema_from_command_line() else {
+        return Vec::new();
+    };
+    let current = current.to_string_lossy();
+
+    let mut candidates: Vec<String> = match current.split_once('=') {
+        Some((facet_name, _)) => schema
+            .faceted_dimensions
+            .get(facet_name)
+            .map(|definition| {
+                definition
+                    .value_choices()
+                    .iter()
+                    .map(|value| format!("{}={}", facet_name, value))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        None => schema
+            .faceted_dimensions
+            .keys()
+            .map(|facet_name| format!("{}=", facet_name))
+            .collect(),
+    };
+
+    candidates.retain(|candidate| candidate.starts_with(current.as_ref()));
+    candidates.sort();
+    candidates
+        .into_iter()
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// Complete `--genus` values from the species (and their synonyms and codes) in the hierarchy
+pub fn complete_genus(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(schema) = schema_from_command_line() else {
+        return Vec::new();
+    };
+    let current = current.to_string_lossy();
+
+    let hierarchy = &schema.classical_hierarchy;
+    let mut names = vec![hierarchy.root.clone()];
+    // Synonyms and codes resolve to their species when filtering
+    names.extend(
+        hierarchy
+            .iter_preorder()
+            .flat_map(|(_, node)| node.names())
+            .map(str::to_string),
+    );
+
+    names.retain(|name| name.starts_with(current.as_ref()));
+    names.sort();
+    names.into_iter().map(CompletionCandidate::new).collect()
+}
+
+/// Complete field names accepted by `--sort` and `--group-by`
+pub fn complete_field(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(schema) = schema_from_command_line() else {
+        return Vec::new();
+    };
+    let current = current.to_string_lossy();
+
+    let mut fields: Vec<String> = schema.faceted_dimensions.keys().cloned().collect();
+    fields.push("name".to_string());
+
+    fields.retain(|field| field.starts_with(current.as_ref()));
+    fields.sort();
+    fields.into_iter().map(CompletionCandidate::new).collect()
+}
+
+/// Find the data file on the command line being completed and load its schema
+/// The shell passes the partial command line after `--`; the first existing JSON
+/// file among those words is treated as the data file.
+fn schema_from_command_line() -> Option<TaxonomySchema> {
+    let data_file = std::env::args_os()
+        .skip_while(|arg| arg != "--")
+        .map(std::path::PathBuf::from)
+        .find(|path| path.extension().is_some_and(|ext| ext == "json") && path.is_file())?;
+
+    let schema_path = resolve_schema_path(&data_file).ok()?;
+    load_schema(schema_path).ok()
+}
@@ -0,0 +1,42 @@
+use crate::changes;
+use colored::Colorize;
+use std::error::Error;
+use std::path::Path;
+use taxstud_core::*;
+
+/// Import a controlled vocabulary into a schema's faceted dimension
+/// Prints the changes to the schema, and writes it back unless `dry_run` is set
+pub fn import(
+    schema_path: &Path,
+    dimension: &str,
+    vocabulary: &Path,
+    dry_run: bool,
+) -> Result<(), Box<dyn Error>> {
+    let mut schema = load_schema(schema_path)?;
+    let before = schema.clone();
+    let terms = read_vocabulary(vocabulary)?;
+    let existed = schema.faceted_dimensions.contains_key(dimension);
+    let report = schema.add_facet_values(dimension, terms)?;
+
+    if !report.added.is_empty() && !dry_run {
+        save_schema(&schema, schema_path)?;
+    }
+
+    let verb = if dry_run { "Would import" } else { "Imported" };
+    let target = if existed { "" } else { " (new dimension)" };
+    println!(
+        "{} {} value(s) into '{}'{}: {} duplicate(s) skipped, {} conflict(s)",
+        verb,
+        report.added.len(),
+        dimension,
+        target,
+        report.duplicates.len(),
+        report.conflicts.len()
+    );
+    changes::print(&diff_schemas(&before, &schema));
+    for conflict in &report.conflicts {
+        println!("  {} {}", "conflict:".yellow(), conflict);
+    }
+
+    Ok(())
+}
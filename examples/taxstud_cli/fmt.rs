@@ -0,0 +1,41 @@
+use colored::Colorize;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use taxstud_core::*;
+
+/// Rewrite data files in the canonical format, or with `check` only report the ones
+/// that aren't in it
+/// Returns true if every file was (or, with `check`, already is) formatted
+pub fn run(files: &[PathBuf], options: &SaveOptions, check: bool) -> Result<bool, Box<dyn Error>> {
+    let mut all_formatted = true;
+    for path in files {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) => {
+                eprintln!("{}: {}", path.display(), err);
+                all_formatted = false;
+                continue;
+            }
+        };
+        let formatted = match reformat_data(&contents, options) {
+            Ok(formatted) => formatted,
+            Err(err) => {
+                eprintln!("{}: {}", path.display(), err);
+                all_formatted = false;
+                continue;
+            }
+        };
+        if formatted == contents {
+            continue;
+        }
+        if check {
+            println!("{} {}", "Not formatted:".yellow(), path.display());
+            all_formatted = false;
+        } else {
+            fs::write(path, formatted)?;
+            println!("Formatted {}", path.display());
+        }
+    }
+    Ok(all_formatted)
+}
@@ -0,0 +1,62 @@
+use crate::changes;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use taxstud_core::*;
+
+/// File names written by `generate`
+const SCHEMA_FILE: &str = "schema.json";
+const DATA_FILE: &str = "data.json";
+
+/// Write a generated schema.json + data.json pair, for load testing
+/// With `dry_run`, prints what writing them would change instead
+pub fn run(
+    dir: &Path,
+    seed: u64,
+    size: &SizeParams,
+    force: bool,
+    dry_run: bool,
+) -> Result<(), Box<dyn Error>> {
+    let schema_path = dir.join(SCHEMA_FILE);
+    let data_path = dir.join(DATA_FILE);
+
+    if dry_run {
+        let (schema, data) = random_taxonomy(seed, size);
+        changes::print_schema_file(&schema_path, &schema);
+        changes::print_data_file(&data_path, &data.items);
+        println!("Would generate {} items (seed {})", data.items.len(), seed);
+        return Ok(());
+    }
+
+    if !force {
+        for path in [&schema_path, &data_path] {
+            if path.exists() {
+                return Err(format!(
+                    "'{}' already exists (use --force to overwrite)",
+                    path.display()
+                )
+                .into());
+            }
+        }
+    }
+
+    let (schema, data) = random_taxonomy(seed, size);
+    fs::create_dir_all(dir)?;
+    save_schema(&schema, &schema_path)?;
+    save_data(&data, &data_path)?;
+
+    println!(
+        "Generated {} items under {} genera (seed {}):",
+        data.items.len(),
+        schema
+            .classical_hierarchy
+            .children
+            .as_ref()
+            .map_or(0, Vec::len),
+        seed
+    );
+    println!("  {}", schema_path.display());
+    println!("  {}", data_path.display());
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use crate::changes;
+use colored::Colorize;
+use std::error::Error;
+use std::path::Path;
+use taxstud_core::*;
+
+/// Importer recorded in the items' provenance
+const IMPORTER: &str = "taxstud import";
+
+/// Import the items of a table file into a data file
+/// The format comes from `format`, or is detected from the file's extension and contents.
+/// Prints what happened to each row and the items it adds, and saves the data file unless `dry_run` is set
+pub fn run(
+    data_path: &Path,
+    source: &Path,
+    format: Option<&str>,
+    dry_run: bool,
+) -> Result<(), Box<dyn Error>> {
+    let mut store = TaxonomyStore::load(data_path)?;
+    let registry = ImporterRegistry::default();
+    let contents = std::fs::read(source)?;
+    let table = match format {
+        Some(id) => registry
+            .get(id)
+            .ok_or_else(|| format!("Unknown import format '{}'", id))?
+            .read(&contents)?,
+        None => registry.read(Some(source), &contents)?,
+    };
+
+    let mut import = ItemImport::new(table, store.schema());
+    import.source = Some(Provenance::now(source.display().to_string(), IMPORTER));
+    let report = import.report(store.schema(), store.items());
+
+    for row in &report.skipped {
+        println!(
+            "  {} row {} ({}): {}",
+            "skipped:".yellow(),
+            row.row,
+            row.item.name,
+            row.issues.join("; ")
+        );
+    }
+    for row in &report.errored {
+        println!(
+            "  {} row {} ({}): {}",
+            "error:".red(),
+            row.row,
+            row.item.name,
+            row.issues.join("; ")
+        );
+    }
+
+    let before = store.items().to_vec();
+    for item in report.items() {
+        store.add_item(item.clone());
+    }
+
+    if !report.created.is_empty() && !dry_run {
+        // Rows only get the schema's hierarchy and facet checks; the data file must still load
+        if let Err(errors) = store.validate() {
+            for error in &errors {
+                println!("  {} {}", "error:".red(), error);
+            }
+            return Err("the imported items leave the data invalid; nothing was saved".into());
+        }
+        crate::sync::save(&mut store)?;
+    }
+
+    let verb = if dry_run { "Would import" } else { "Imported" };
+    println!(
+        "{} {} item(s) from {}: {} skipped, {} with errors",
+        verb,
+        report.created.len(),
+        source.display(),
+        report.skipped.len(),
+        report.errored.len()
+    );
+    changes::print(&diff_items(&before, store.items()));
+    Ok(())
+}
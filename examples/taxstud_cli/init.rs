@@ -0,0 +1,66 @@
+use crate::changes;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use taxstud_core::*;
+
+/// File names written by `init`
+const SCHEMA_FILE: &str = "schema.json";
+const DATA_FILE: &str = "data.json";
+
+/// Scaffold a schema.json + data.json pair from a built-in template
+/// With `dry_run`, prints what writing them would change instead
+pub fn run(
+    template_id: &str,
+    dir: &Path,
+    force: bool,
+    dry_run: bool,
+) -> Result<(), Box<dyn Error>> {
+    let template = find_template(template_id).ok_or_else(|| {
+        let ids: Vec<_> = available_templates().iter().map(|t| t.id).collect();
+        format!(
+            "Unknown template '{}'. Available templates: {}",
+            template_id,
+            ids.join(", ")
+        )
+    })?;
+
+    let schema_path = dir.join(SCHEMA_FILE);
+    let data_path = dir.join(DATA_FILE);
+
+    if dry_run {
+        let schema = build_schema_from_json(template.schema_json())?;
+        changes::print_schema_file(&schema_path, &schema);
+        changes::print_data_file(&data_path, &template.empty_data(SCHEMA_FILE).items);
+        println!("Would create '{}' taxonomy", template.id);
+        return Ok(());
+    }
+
+    if !force {
+        for path in [&schema_path, &data_path] {
+            if path.exists() {
+                return Err(format!(
+                    "'{}' already exists (use --force to overwrite)",
+                    path.display()
+                )
+                .into());
+            }
+        }
+    }
+
+    fs::create_dir_all(dir)?;
+    fs::write(
+        &schema_path,
+        serde_json::to_string_pretty(&template.schema_json())?,
+    )?;
+    save_data(&template.empty_data(SCHEMA_FILE), &data_path)?;
+
+    // Make sure the pair we just wrote loads cleanly
+    load_data_with_auto_schema(&data_path)?;
+
+    println!("Created '{}' taxonomy:", template.id);
+    println!("  {}", schema_path.display());
+    println!("  {}", data_path.display());
+
+    Ok(())
+}
@@ -0,0 +1,1071 @@
+mod browse;
+mod changes;
+mod classify;
+mod completion;
+mod facet;
+mod fmt;
+mod generate;
+mod import;
+mod init;
+mod query;
+mod script;
+mod split;
+mod stats;
+mod sync;
+mod validate;
+mod watch;
+
+use clap::builder::{PossibleValue, PossibleValuesParser};
+use clap::{Args, Parser, Subcommand, ValueHint};
+use clap_complete::engine::ArgValueCompleter;
+use clap_complete::Shell;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use taxstud_core::*;
+
+/// Hybrid Taxonomy Browser - Filter and display items using faceted search
+///
+/// Examples:
+///   # Display all items
+///   taxstud taxonomy.json
+///
+///   # Filter by genus (OR within genus)
+///   taxstud taxonomy.json --genus Coffee --genus Tea
+///
+///   # Filter by genus, including everything under its descendant species
+///   taxstud taxonomy.json --genus "Hot Beverage" --descendants
+///
+///   # Filter by facet (OR within same facet name)
+///   taxstud taxonomy.json --facet temperature=hot --facet temperature=iced
+///
+///   # Ignore case and accents in filter values ("cafe" matches "Café")
+///   taxstud taxonomy.json --facet origin=peru --ignore-case --ignore-accents
+///
+///   # Numeric comparisons on a facet (comparisons on the same facet form a range)
+///   taxstud taxonomy.json --facet "price>=10" --facet "price<20"
+///
+///   # Filter by an additional field or by part of the name
+///   taxstud taxonomy.json --field sku=ABC123 --name-contains latte
+///
+///   # Combine filters (AND between different types)
+///   taxstud taxonomy.json --genus Coffee --facet caffeine_content=high
+///
+///   # Exclude items (exclusions win over inclusions)
+///   taxstud taxonomy.json --genus Coffee --not-facet temperature=iced --not-genus Espresso
+///
+///   # Sort results by name
+///   taxstud taxonomy.json --sort name
+///
+///   # Group results by a facet
+///   taxstud taxonomy.json --group-by primary_theme
+///
+///   # Combine filtering, sorting, and grouping
+///   taxstud taxonomy.json --genus Coffee --sort name --group-by temperature
+///
+///   # Count matching items, or count them per facet value as JSON
+///   taxstud taxonomy.json --genus Coffee --count
+///   taxstud taxonomy.json --summarize temperature --json
+///
+///   # Lay regional additions over the master file (items with the same ID are replaced)
+///   taxstud master.json --overlay eu.json --overlay eu-fr.json
+///
+///   # Print names and facet values in German where the schema has translations
+///   taxstud taxonomy.json --lang de
+///
+///   # Read a taxonomy published over HTTPS (cached, and revalidated with its ETag)
+///   taxstud https://example.com/taxonomies/menu.json --genus Coffee
+///
+///   # Read from stdin and write to a file ('-' means stdin/stdout)
+///   cat taxonomy.json | taxstud - --schema schema.json --genus Coffee -o coffee.md
+///
+///   # Browse interactively in the terminal
+///   taxstud browse taxonomy.json
+///
+///   # Validate every data file under a directory
+///   taxstud validate --recursive taxonomies/
+///
+///   # Also report keys repeated within an object, e.g. a facet given twice
+///   taxstud validate --strict taxonomy.json
+///
+///   # Query a file only if no object in it repeats a key
+///   taxstud taxonomy.json --strict --genus Coffee
+///
+///   # Re-validate on every save
+///   taxstud validate --watch taxonomy.json
+///
+///   # Validate every taxonomy of a project, and its shared schemas
+///   taxstud validate --project menu.taxproj
+///
+///   # Item counts, hierarchy size and facet coverage of each project member
+///   taxstud stats --project menu.taxproj
+///
+///   # Add the terms of a CSV or SKOS vocabulary to a facet's allowed values
+///   taxstud facet import schema.json region regions.csv
+///
+///   # Write one data file per species under Beverage, all referencing the same schema
+///   taxstud split taxonomy.json --by genus Beverage --out parts/
+///
+///   # Export the hierarchy as a SKOS concept scheme
+///   taxstud export --format skos taxonomy.json -o taxonomy.rdf
+///
+///   # Write the items, with their attachments, as an HTML page
+///   taxstud export --format html taxonomy.json -o taxonomy.html
+///
+///   # Draw the hierarchy as a treemap sized by item counts (.png needs the png feature)
+///   taxstud export --format treemap taxonomy.json -o treemap.svg
+///
+///   # Suggest where new items belong in the hierarchy (names are read from stdin if none are given)
+///   taxstud classify taxonomy.json "Iced Vanilla Latte" "Green Tea"
+///   cut -d, -f1 new-items.csv | taxstud classify taxonomy.json --json
+///
+///   # Check in CI that data files are in the canonical format
+///   taxstud fmt --check taxonomies/*.json
+///
+///   # Scaffold a new schema + data pair
+///   taxstud init --template products mydir/
+///
+///   # Enable tab completion (facet names and values are read from the schema)
+///   source <(taxstud completions bash)
+#[derive(Parser, Debug)]
+#[command(name = "taxstud")]
+#[command(author, version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+#[command(after_help = "Filtering Logic:\n  \
+    - Multiple --genus values are combined with OR\n  \
+    - Multiple --facet values for the SAME facet name are combined with OR\n  \
+    - Numeric comparisons (<, <=, >, >=) on the SAME facet name are combined with AND\n  \
+    - Different filter types (genus vs facets) are combined with AND\n  \
+    - Different facet names are combined with AND\n  \
+    - --field filters work like --facet, over additional item fields\n  \
+    - Items matching any --not-genus or --not-facet are always excluded\n  \
+    - Deprecated items (\"deprecated\": true) are left out unless --include-deprecated\n\n\
+Sorting Options:\n  \
+    - name: Sort alphabetically by item name\n  \
+    - Any facet name: Sort by that facet's value\n\n\
+Grouping:\n  \
+    - Group results by any facet name\n  \
+    - Items with multiple values for the grouping facet appear in multiple groups\n  \
+    - Items without a value go to an \"Unspecified\" group (see --missing)")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    query: QueryArgs,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Browse a taxonomy interactively in the terminal
+    ///
+    /// Keys: Tab switches panes, arrows/j/k move, Enter toggles a genus filter
+    /// on the selected hierarchy node, / edits facet filters, c clears filters,
+    /// s toggles sorting by name, q quits.
+    Browse {
+        /// Path to the taxonomy data JSON file, or an http(s) URL
+        #[arg(value_name = "FILE", value_hint = ValueHint::FilePath)]
+        file: String,
+
+        /// Schema file to validate against (required when FILE is '-')
+        #[arg(long, value_name = "SCHEMA", value_hint = ValueHint::FilePath)]
+        schema: Option<PathBuf>,
+    },
+
+    /// Validate data files against their schemas and print a summary table
+    ///
+    /// Exits with status 1 if any file fails. House-style rules from the schema's
+    /// "x-rules" and a rules.json beside the schema are checked too, as are item
+    /// relations to items or nodes in any of the files validated. With --watch,
+    /// keeps running and re-validates whenever a data file, the schema it references
+    /// or its rules.json changes.
+    Validate {
+        /// Descend into directories, validating every JSON file that references a schema
+        #[arg(short, long)]
+        recursive: bool,
+
+        /// Re-run validation whenever the data or schema files change
+        #[arg(short, long)]
+        watch: bool,
+
+        /// Also report keys an object repeats, e.g. an item's facet given twice
+        /// (parsing otherwise keeps the last value without a word)
+        #[arg(long)]
+        strict: bool,
+
+        /// Also validate the member taxonomies and shared schemas of this .taxproj project
+        #[arg(long, value_name = "PROJECT", value_hint = ValueHint::FilePath)]
+        project: Option<PathBuf>,
+
+        /// Data files (or directories, with --recursive) to validate
+        #[arg(value_name = "PATH", required_unless_present = "project", value_hint = ValueHint::AnyPath)]
+        paths: Vec<PathBuf>,
+    },
+
+    /// Print item counts, hierarchy size and facet coverage of data files
+    ///
+    /// Exits with status 1 if any file fails to load.
+    Stats {
+        /// Also include the member taxonomies of this .taxproj project
+        #[arg(long, value_name = "PROJECT", value_hint = ValueHint::FilePath)]
+        project: Option<PathBuf>,
+
+        /// Print the statistics as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Data files to describe
+        #[arg(value_name = "FILE", required_unless_present = "project", value_hint = ValueHint::FilePath)]
+        files: Vec<PathBuf>,
+    },
+
+    /// Split a data file into one file per child species of a genus
+    ///
+    /// Each part holds the items under one child subtree; items under none of them
+    /// (at the genus itself, or outside it) are listed and left out. Every part
+    /// references the input's schema (relative to --out), or embeds it when the input does.
+    Split {
+        /// Path to the taxonomy data JSON file
+        #[arg(value_name = "FILE", value_hint = ValueHint::FilePath)]
+        file: PathBuf,
+
+        /// What to split by: "genus" and the genus whose species each get a file
+        #[arg(long, num_args = 2, value_names = ["KIND", "NAME"], required = true)]
+        by: Vec<String>,
+
+        /// Directory to write the parts to
+        #[arg(short, long, value_name = "DIR", value_hint = ValueHint::DirPath)]
+        out: PathBuf,
+
+        /// Format the parts with the "save" settings of this .taxproj project
+        #[arg(long, value_name = "PROJECT", value_hint = ValueHint::FilePath)]
+        project: Option<PathBuf>,
+
+        /// Overwrite existing files
+        #[arg(long)]
+        force: bool,
+
+        /// Show what each part would hold, against any file already there, without writing
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Manage faceted dimensions of a schema
+    Facet {
+        #[command(subcommand)]
+        command: FacetCommand,
+    },
+
+    /// Run a Rhai script against a data file's items
+    ///
+    /// The script sees items() (each with name, path, facet(), set_facet(), is_under() ...),
+    /// item(name), add_item(name, path), root(), facets() and facet_values(facet). The data
+    /// file is saved afterwards if the script changed anything and the data still validates;
+    /// a sync.json beside it publishes the save to a webhook.
+    #[command(
+        after_help = "Example:\n  taxstud_cli script data.json -e 'for item in items() { if item.is_under(\"Coffee\") && !item.has_facet(\"origin\") { item.set_facet(\"origin\", \"unknown\") } }'"
+    )]
+    Script {
+        /// Path to the taxonomy data JSON file
+        #[arg(value_name = "FILE", value_hint = ValueHint::FilePath)]
+        file: PathBuf,
+
+        /// Script file to run
+        #[arg(
+            value_name = "SCRIPT",
+            value_hint = ValueHint::FilePath,
+            required_unless_present = "eval",
+            conflicts_with = "eval"
+        )]
+        script: Option<PathBuf>,
+
+        /// Run this script text instead of a file
+        #[arg(short, long, value_name = "CODE")]
+        eval: Option<String>,
+
+        /// Show what the script would change without saving the data file
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Import items from a CSV, TSV, JSON or spreadsheet file into a data file
+    ///
+    /// The format is detected from the file's extension and contents unless --format
+    /// names it. Columns are matched to "name", "path" and facets by name; other columns
+    /// become additional fields. Rows whose name is taken are skipped, and rows that don't
+    /// fit the schema are reported and left out. A sync.json beside the data file
+    /// publishes the save to a webhook.
+    Import {
+        /// Path to the taxonomy data JSON file to add the items to
+        #[arg(value_name = "FILE", value_hint = ValueHint::FilePath)]
+        file: PathBuf,
+
+        /// File of items to import
+        #[arg(value_name = "SOURCE", value_hint = ValueHint::FilePath)]
+        source: PathBuf,
+
+        /// Format of SOURCE, one of the registered importers
+        #[arg(short, long, value_parser = import_formats())]
+        format: Option<String>,
+
+        /// Show what would be imported without changing the data file
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Export the taxonomy in another format
+    Export {
+        /// Output format, one of the registered exporters
+        #[arg(short, long, value_parser = export_formats())]
+        format: String,
+
+        /// Path to the taxonomy data JSON file ('-' reads from stdin), or an http(s) URL
+        #[arg(value_name = "FILE", value_hint = ValueHint::FilePath)]
+        file: String,
+
+        /// Schema file to use (required when FILE is '-')
+        #[arg(long, value_name = "SCHEMA", value_hint = ValueHint::FilePath)]
+        schema: Option<PathBuf>,
+
+        /// Write output to a file instead of stdout ('-' means stdout)
+        #[arg(short, long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+        output: Option<PathBuf>,
+
+        /// Include deprecated items, which are left out otherwise
+        #[arg(long)]
+        include_deprecated: bool,
+
+        /// Write names in this language where translated
+        #[arg(long, value_name = "LANG")]
+        lang: Option<String>,
+
+        /// Width of drawings (treemap, sunburst)
+        #[arg(long, value_name = "PX")]
+        width: Option<f64>,
+
+        /// Height of drawings (treemap, sunburst)
+        #[arg(long, value_name = "PX")]
+        height: Option<f64>,
+    },
+
+    /// Render a report from a template of your own
+    ///
+    /// The template uses Jinja2 syntax (MiniJinja) and gets the schema's title and
+    /// description, the items, the groups of --group-by and statistics: see
+    /// `report_context` for the names. Values are HTML-escaped when the template's file
+    /// name ends in .html or .htm.
+    Report {
+        /// Path to the taxonomy data JSON file ('-' reads from stdin), or an http(s) URL
+        #[arg(value_name = "FILE", value_hint = ValueHint::FilePath)]
+        file: String,
+
+        /// Template file to render
+        #[arg(short, long, value_name = "TEMPLATE", value_hint = ValueHint::FilePath)]
+        template: PathBuf,
+
+        /// Schema file to use (required when FILE is '-')
+        #[arg(long, value_name = "SCHEMA", value_hint = ValueHint::FilePath)]
+        schema: Option<PathBuf>,
+
+        /// Write the report to a file instead of stdout ('-' means stdout)
+        #[arg(short, long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+        out: Option<PathBuf>,
+
+        /// Group the items by this facet in the template's `groups`
+        #[arg(short = 'G', long = "group-by", value_name = "FACET")]
+        group_by: Option<String>,
+
+        /// Include deprecated items, which are left out otherwise
+        #[arg(long)]
+        include_deprecated: bool,
+    },
+
+    /// Suggest classical paths for new item names
+    ///
+    /// Each name is matched word by word against species names and synonyms, and
+    /// against the names of items already classified. With no NAME arguments, names
+    /// are read one per line from stdin, e.g. to triage a bulk import.
+    Classify {
+        /// Path to the taxonomy data JSON file, or an http(s) URL
+        #[arg(value_name = "FILE", value_hint = ValueHint::FilePath)]
+        file: String,
+
+        /// Names of the new items
+        #[arg(value_name = "NAME")]
+        names: Vec<String>,
+
+        /// Schema file to use instead of the one the data file references
+        #[arg(long, value_name = "SCHEMA", value_hint = ValueHint::FilePath)]
+        schema: Option<PathBuf>,
+
+        /// Number of suggestions to show per name
+        #[arg(short = 'n', long, value_name = "N", default_value_t = 3)]
+        top: usize,
+
+        /// Print the suggestions as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Write output to a file instead of stdout ('-' means stdout)
+        #[arg(short, long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+        output: Option<PathBuf>,
+    },
+
+    /// Create a schema.json and empty data.json from a built-in template
+    Init {
+        /// Template to start from
+        #[arg(short, long, default_value = DEFAULT_TEMPLATE, value_parser = template_ids())]
+        template: String,
+
+        /// Overwrite existing files
+        #[arg(long)]
+        force: bool,
+
+        /// Show what the files would hold, against any already there, without writing them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Directory to create the files in
+        #[arg(value_name = "DIR", value_hint = ValueHint::DirPath)]
+        dir: PathBuf,
+    },
+
+    /// Rewrite data files in the canonical format
+    ///
+    /// Map keys are sorted, items are ordered by --sort-items-by and the values of
+    /// multi-valued facets alphabetically, with --indent spaces per level. A schema
+    /// embedded in the file is kept. With --check, nothing is written: files that
+    /// would change are listed and the exit status is 1. Set a project's "save"
+    /// settings to match, so the GUI saves member files in the same format.
+    Fmt {
+        /// List files that aren't formatted instead of rewriting them
+        #[arg(long)]
+        check: bool,
+
+        /// Spaces per level of indentation (0 writes each file on one line)
+        #[arg(long, default_value_t = 2)]
+        indent: usize,
+
+        /// Field to order the items by ("name" or a facet)
+        #[arg(long, value_name = "FIELD", default_value = "name")]
+        sort_items_by: String,
+
+        /// Leave the items in the order they are in
+        #[arg(long)]
+        keep_order: bool,
+
+        /// End each file with a newline
+        #[arg(long)]
+        trailing_newline: bool,
+
+        /// Data files to format
+        #[arg(value_name = "FILE", required = true, value_hint = ValueHint::FilePath)]
+        files: Vec<PathBuf>,
+    },
+
+    /// Create a schema.json and data.json of random items, for load testing
+    ///
+    /// Beverages under a two-level hierarchy, with enum, multi-valued, number and date
+    /// facets. The same seed and sizes always give the same files, and the data
+    /// validates against the schema.
+    Generate {
+        /// Number of items
+        #[arg(long, default_value_t = 1_000)]
+        items: usize,
+
+        /// Genera under the root
+        #[arg(long, default_value_t = SizeParams::default().genera)]
+        genera: usize,
+
+        /// Species under each genus
+        #[arg(long, default_value_t = SizeParams::default().species_per_genus)]
+        species: usize,
+
+        /// Seed for the random items
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+
+        /// Overwrite existing files
+        #[arg(long)]
+        force: bool,
+
+        /// Show what the files would hold, against any already there, without writing them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Directory to create the files in
+        #[arg(value_name = "DIR", value_hint = ValueHint::DirPath)]
+        dir: PathBuf,
+    },
+
+    /// Print a shell completion script
+    ///
+    /// Completions are dynamic: facet names and values, genera, and sort fields
+    /// are looked up in the schema of the data file already on the command line.
+    Completions {
+        /// Shell to generate the script for
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
+
+/// The registered importers as --format values
+fn import_formats() -> PossibleValuesParser {
+    let formats: Vec<PossibleValue> = ImporterRegistry::default()
+        .iter()
+        .map(|importer| PossibleValue::new(importer.id()).help(importer.name()))
+        .collect();
+    PossibleValuesParser::new(formats)
+}
+
+/// The registered exporters as --format values, with their descriptions as help
+fn export_formats() -> PossibleValuesParser {
+    let formats: Vec<PossibleValue> = ExporterRegistry::default()
+        .iter()
+        .map(|exporter| PossibleValue::new(exporter.id()).help(exporter.description()))
+        .collect();
+    PossibleValuesParser::new(formats)
+}
+
+/// How --group-by treats items without a value for the grouping facet
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default)]
+enum MissingMode {
+    /// Collect them in a group named by --missing-label
+    #[default]
+    Bucket,
+    /// Leave them out
+    Skip,
+    /// Fail, listing the items
+    Error,
+}
+
+#[derive(Subcommand, Debug)]
+enum FacetCommand {
+    /// Add the terms of a controlled vocabulary to a dimension's allowed values
+    ///
+    /// Reads CSV/TSV (value, label, description columns; an optional header row names
+    /// them) or SKOS RDF/XML. Terms already present are skipped; terms that clash with
+    /// an existing value are reported and not added. A missing dimension is created.
+    /// The changes to the schema are printed as +/-/~ lines; --dry-run prints them
+    /// without writing the schema.
+    Import {
+        /// Schema file to update
+        #[arg(value_name = "SCHEMA", value_hint = ValueHint::FilePath)]
+        schema: PathBuf,
+
+        /// Faceted dimension to add the terms to
+        #[arg(value_name = "DIMENSION")]
+        dimension: String,
+
+        /// Vocabulary file (.csv, .tsv, .txt, .rdf, .xml or .skos)
+        #[arg(value_name = "VOCABULARY", value_hint = ValueHint::FilePath)]
+        vocabulary: PathBuf,
+
+        /// Print the changes without writing the schema
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// Arguments for the default query mode (print, filter, sort, group)
+#[derive(Args, Debug)]
+struct QueryArgs {
+    /// Path to the hybrid taxonomy JSON file ('-' reads from stdin), or an http(s) URL
+    #[arg(value_name = "FILE", required = true, value_hint = ValueHint::FilePath)]
+    file: Option<String>,
+
+    /// Schema file to validate against (required when FILE is '-')
+    #[arg(long, value_name = "SCHEMA", value_hint = ValueHint::FilePath)]
+    schema: Option<PathBuf>,
+
+    /// Data file whose items add to or replace (by ID) those of FILE; repeat to stack overlays
+    #[arg(long = "overlay", value_name = "FILE", conflicts_with = "schema", value_hint = ValueHint::FilePath)]
+    overlays: Vec<PathBuf>,
+
+    /// Write output to a file instead of stdout ('-' means stdout)
+    #[arg(short, long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+    output: Option<PathBuf>,
+
+    /// Refuse FILE if an object in it repeats a key, instead of keeping the last value
+    #[arg(long, conflicts_with = "overlays")]
+    strict: bool,
+
+    /// Filter by genus/species (can be specified multiple times for OR logic)
+    #[arg(short, long = "genus", value_name = "NAME", add = ArgValueCompleter::new(completion::complete_genus))]
+    genera: Vec<String>,
+
+    /// Let --genus also match items under descendant species in the hierarchy
+    #[arg(short = 'd', long = "descendants")]
+    include_descendants: bool,
+
+    /// Filter by facet (format: facet_name=value or facet_name>=10, can be specified multiple times)
+    #[arg(short, long = "facet", value_name = "NAME=VALUE", add = ArgValueCompleter::new(completion::complete_facet))]
+    facets: Vec<String>,
+
+    /// Filter by an additional item field (format: field_name=value or field_name>=10, can be specified multiple times)
+    #[arg(long = "field", value_name = "NAME=VALUE")]
+    fields: Vec<String>,
+
+    /// Only include items whose name contains this text (case-insensitive)
+    #[arg(long = "name-contains", value_name = "TEXT")]
+    name_contains: Option<String>,
+
+    /// Include deprecated items, which are left out otherwise
+    #[arg(long)]
+    include_deprecated: bool,
+
+    /// Compare --genus, --facet and --field values (and their --not- forms) ignoring case
+    #[arg(short = 'i', long)]
+    ignore_case: bool,
+
+    /// Compare those values ignoring accents, not case: "cafe" matches "café" (add -i for "Café")
+    #[arg(long)]
+    ignore_accents: bool,
+
+    /// Exclude items under this genus/species (can be specified multiple times)
+    #[arg(long = "not-genus", value_name = "NAME", add = ArgValueCompleter::new(completion::complete_genus))]
+    excluded_genera: Vec<String>,
+
+    /// Exclude items with this facet value (format: facet_name=value, can be specified multiple times)
+    #[arg(long = "not-facet", value_name = "NAME=VALUE", add = ArgValueCompleter::new(completion::complete_facet))]
+    excluded_facets: Vec<String>,
+
+    /// Sort results by name or facet (e.g., "name", "temperature", "primary_theme")
+    #[arg(short, long = "sort", value_name = "FIELD", add = ArgValueCompleter::new(completion::complete_field))]
+    sort_by: Option<String>,
+
+    /// Group results by a facet name
+    #[arg(short = 'G', long = "group-by", value_name = "FACET", add = ArgValueCompleter::new(completion::complete_field))]
+    group_by: Option<String>,
+
+    /// What --group-by does with items that have no value for the facet
+    #[arg(long, value_enum, value_name = "MODE", default_value_t = MissingMode::Bucket)]
+    missing: MissingMode,
+
+    /// Name of the group holding items without a value (with --missing bucket)
+    #[arg(long, value_name = "NAME", default_value = UNSPECIFIED_GROUP)]
+    missing_label: String,
+
+    /// List the distinct values of this facet in each --group-by group's header
+    #[arg(long, value_name = "FACET", requires = "group_by", add = ArgValueCompleter::new(completion::complete_field))]
+    distinct: Option<String>,
+
+    /// Show min/max/avg of this numeric facet in each --group-by group's header
+    #[arg(long, value_name = "FACET", requires = "group_by", add = ArgValueCompleter::new(completion::complete_field))]
+    stats: Option<String>,
+
+    /// Show names and values in this language (e.g. "de"), falling back to the canonical names
+    #[arg(long, value_name = "LANG")]
+    lang: Option<String>,
+
+    /// Print only the number of matching items
+    #[arg(long, conflicts_with_all = ["summarize", "group_by"])]
+    count: bool,
+
+    /// Print only the number of matching items per value of this facet
+    #[arg(long, value_name = "FACET", conflicts_with = "group_by", add = ArgValueCompleter::new(completion::complete_field))]
+    summarize: Option<String>,
+
+    /// Print --count / --summarize results as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+fn main() {
+    completion::handle_completion_request();
+
+    let cli = Cli::parse();
+
+    match cli.command {
+        Some(Command::Browse { file, schema }) => {
+            let (data, schema) = load_or_exit(&file, schema.as_deref());
+            if let Err(err) = browse::run(data, schema) {
+                eprintln!("Error running browser: {}", err);
+                process::exit(1);
+            }
+        }
+        Some(Command::Validate {
+            recursive,
+            watch,
+            strict,
+            project,
+            mut paths,
+        }) => {
+            let mut schemas = Vec::new();
+            if let Some(project) = project {
+                let project = load_project_or_exit(&project);
+                paths.extend(project.taxonomy_paths());
+                schemas = project.schema_paths();
+            }
+            let result = if watch {
+                watch::run(&paths, recursive, strict).map(|()| true)
+            } else {
+                validate::run(&paths, recursive, strict, &schemas)
+            };
+            match result {
+                Ok(true) => {}
+                Ok(false) => process::exit(1),
+                Err(err) => {
+                    eprintln!("Error validating: {}", err);
+                    process::exit(1);
+                }
+            }
+        }
+        Some(Command::Stats {
+            project,
+            json,
+            mut files,
+        }) => {
+            if let Some(project) = project {
+                files.extend(load_project_or_exit(&project).taxonomy_paths());
+            }
+            let result = stats::run(&files, json, &mut io::stdout().lock());
+            match result {
+                Ok(true) => {}
+                Ok(false) => process::exit(1),
+                Err(err) if err.kind() == io::ErrorKind::BrokenPipe => {}
+                Err(err) => {
+                    eprintln!("Error printing statistics: {}", err);
+                    process::exit(1);
+                }
+            }
+        }
+        Some(Command::Split {
+            file,
+            by,
+            out,
+            project,
+            force,
+            dry_run,
+        }) => {
+            let options = project
+                .map(|project| load_project_or_exit(&project).settings.save)
+                .unwrap_or_default();
+            if let Err(err) = split::run(&file, &by, &out, &options, force, dry_run) {
+                eprintln!("Error splitting taxonomy: {}", err);
+                process::exit(1);
+            }
+        }
+        Some(Command::Facet {
+            command:
+                FacetCommand::Import {
+                    schema,
+                    dimension,
+                    vocabulary,
+                    dry_run,
+                },
+        }) => {
+            if let Err(err) = facet::import(&schema, &dimension, &vocabulary, dry_run) {
+                eprintln!("Error importing vocabulary: {}", err);
+                process::exit(1);
+            }
+        }
+        Some(Command::Export {
+            format,
+            file,
+            schema,
+            output,
+            include_deprecated,
+            lang,
+            width,
+            height,
+        }) => {
+            let (mut data, schema) = load_or_exit(&file, schema.as_deref());
+            if !include_deprecated {
+                data.items.retain(|item| !item.is_deprecated());
+            }
+            // Local attachments are linked by absolute path, wherever the output goes
+            let data_dir = std::fs::canonicalize(&file)
+                .ok()
+                .and_then(|path| path.parent().map(Path::to_path_buf));
+            let options = ExportOptions {
+                lang,
+                data_dir,
+                width,
+                height,
+            };
+            let registry = ExporterRegistry::default();
+            let exporter = registry
+                .get(&format)
+                .expect("--format is a registered exporter");
+            // Drawings are rasterized for a .png output
+            let png = exporter.extension() == "svg"
+                && output
+                    .as_deref()
+                    .and_then(Path::extension)
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("png"));
+            let result = open_output(output.as_deref()).and_then(|mut out| {
+                if png {
+                    let mut svg = Vec::new();
+                    exporter.export(&schema, &data, &mut svg, &options)?;
+                    out.write_all(&svg_bytes_to_png(&svg)?)?;
+                } else {
+                    exporter.export(&schema, &data, &mut out, &options)?;
+                }
+                out.flush()
+            });
+            if let Err(err) = result {
+                eprintln!("Error exporting: {}", err);
+                process::exit(1);
+            }
+        }
+        Some(Command::Import {
+            file,
+            source,
+            format,
+            dry_run,
+        }) => {
+            if let Err(err) = import::run(&file, &source, format.as_deref(), dry_run) {
+                eprintln!("Error importing items: {}", err);
+                process::exit(1);
+            }
+        }
+        Some(Command::Script {
+            file,
+            script,
+            eval,
+            dry_run,
+        }) => {
+            if let Err(err) = script::run(&file, script.as_deref(), eval.as_deref(), dry_run) {
+                eprintln!("Error running script: {}", err);
+                process::exit(1);
+            }
+        }
+        Some(Command::Report {
+            file,
+            template,
+            schema,
+            out,
+            group_by,
+            include_deprecated,
+        }) => {
+            let (mut data, schema) = load_or_exit(&file, schema.as_deref());
+            if !include_deprecated {
+                data.items.retain(|item| !item.is_deprecated());
+            }
+            let options = ReportOptions {
+                group_by,
+                ..ReportOptions::default()
+            };
+            let name = template
+                .file_name()
+                .map_or_else(|| "report".into(), |name| name.to_string_lossy());
+            let result = std::fs::read_to_string(&template)
+                .map_err(|e| e.into())
+                .and_then(|source| render_report(&name, &source, &schema, &data, &options))
+                .and_then(|report| {
+                    let mut out = open_output(out.as_deref())?;
+                    out.write_all(report.as_bytes())?;
+                    Ok(out.flush()?)
+                });
+            if let Err(err) = result {
+                eprintln!("Error rendering report: {}", err);
+                process::exit(1);
+            }
+        }
+        Some(Command::Classify {
+            file,
+            names,
+            schema,
+            top,
+            json,
+            output,
+        }) => {
+            if file == "-" && names.is_empty() {
+                eprintln!("Error: give the item names as arguments when reading data from stdin");
+                process::exit(1);
+            }
+            let (data, schema) = load_or_exit(&file, schema.as_deref());
+            let result = open_output(output.as_deref()).and_then(|mut out| {
+                classify::run(&data, &schema, &names, top, json, &mut out)?;
+                out.flush()
+            });
+            match result {
+                Err(err) if err.kind() != io::ErrorKind::BrokenPipe => {
+                    eprintln!("Error classifying: {}", err);
+                    process::exit(1);
+                }
+                _ => {}
+            }
+        }
+        Some(Command::Init {
+            template,
+            force,
+            dry_run,
+            dir,
+        }) => {
+            if let Err(err) = init::run(&template, &dir, force, dry_run) {
+                eprintln!("Error initializing taxonomy: {}", err);
+                process::exit(1);
+            }
+        }
+        Some(Command::Fmt {
+            check,
+            indent,
+            sort_items_by,
+            keep_order,
+            trailing_newline,
+            files,
+        }) => {
+            let options = SaveOptions {
+                indent,
+                sort_items_by: (!keep_order).then_some(sort_items_by),
+                trailing_newline,
+                sort_facet_values: true,
+            };
+            match fmt::run(&files, &options, check) {
+                Ok(true) => {}
+                Ok(false) => process::exit(1),
+                Err(err) => {
+                    eprintln!("Error formatting: {}", err);
+                    process::exit(1);
+                }
+            }
+        }
+        Some(Command::Generate {
+            items,
+            genera,
+            species,
+            seed,
+            force,
+            dry_run,
+            dir,
+        }) => {
+            let size = SizeParams {
+                items,
+                genera,
+                species_per_genus: species,
+            };
+            if let Err(err) = generate::run(&dir, seed, &size, force, dry_run) {
+                eprintln!("Error generating taxonomy: {}", err);
+                process::exit(1);
+            }
+        }
+        Some(Command::Completions { shell }) => {
+            if let Err(err) = completion::print_registration(shell) {
+                eprintln!("Error generating completions: {}", err);
+                process::exit(1);
+            }
+        }
+        None => run_query(&cli.query),
+    }
+}
+
+fn template_ids() -> clap::builder::PossibleValuesParser {
+    available_templates()
+        .iter()
+        .map(|t| t.id)
+        .collect::<Vec<_>>()
+        .into()
+}
+
+fn load_or_exit(file: &str, schema: Option<&Path>) -> (TaxonomyData, TaxonomySchema) {
+    load_or_exit_with(file, schema, &LoadOptions::default())
+}
+
+fn load_or_exit_with(
+    file: &str,
+    schema: Option<&Path>,
+    options: &LoadOptions,
+) -> (TaxonomyData, TaxonomySchema) {
+    load_input(file, schema, options).unwrap_or_else(|err| {
+        let source = if file == "-" { "stdin" } else { file };
+        eprintln!("Error loading data from '{}': {}", source, err);
+        process::exit(1);
+    })
+}
+
+fn load_project_or_exit(path: &Path) -> Project {
+    Project::load(path).unwrap_or_else(|err| {
+        eprintln!("Error loading project: {}", err);
+        process::exit(1);
+    })
+}
+
+/// Load the data document from a file, stdin ('-') or an http(s) URL
+/// An explicit schema overrides the one referenced by the data file. A URL is read through
+/// the local cache, revalidated with its ETag, and its copy used when offline
+fn load_input(
+    file: &str,
+    schema_path: Option<&Path>,
+    options: &LoadOptions,
+) -> Result<(TaxonomyData, TaxonomySchema), Box<dyn std::error::Error>> {
+    if is_url(file) {
+        let remote = RemoteCache::default().open(file)?;
+        if remote.freshness == Freshness::Offline {
+            eprintln!(
+                "Warning: {} could not be reached; using the copy cached earlier",
+                file
+            );
+        }
+        return load_input(&remote.data_path.to_string_lossy(), schema_path, options);
+    }
+    match (file, schema_path) {
+        ("-", None) => Err("--schema is required when reading data from stdin".into()),
+        (file, Some(schema_path)) => {
+            let schema = load_schema(schema_path)?;
+            let contents = if file == "-" {
+                io::read_to_string(io::stdin().lock())?
+            } else {
+                fs::read_to_string(file)?
+            };
+            let data = parse_data_with_options(&contents, &schema, options, &mut |_| {})?;
+            Ok((data, schema))
+        }
+        (file, None) => load_data_with_options(file, options, &mut |_| {}),
+    }
+}
+
+#[cfg(feature = "png")]
+fn svg_bytes_to_png(svg: &[u8]) -> io::Result<Vec<u8>> {
+    let svg = String::from_utf8_lossy(svg);
+    viz::svg_to_png(&svg).map_err(|e| io::Error::other(e.to_string()))
+}
+
+#[cfg(not(feature = "png"))]
+fn svg_bytes_to_png(_svg: &[u8]) -> io::Result<Vec<u8>> {
+    Err(io::Error::other(
+        "PNG export needs the 'png' feature (cargo run --features png ...)",
+    ))
+}
+
+/// Open the output destination; `None` or '-' means stdout
+fn open_output(path: Option<&Path>) -> io::Result<Box<dyn Write>> {
+    match path {
+        Some(path) if path != Path::new("-") => Ok(Box::new(BufWriter::new(File::create(path)?))),
+        _ => Ok(Box::new(BufWriter::new(io::stdout().lock()))),
+    }
+}
+
+fn run_query(args: &QueryArgs) {
+    let file = args
+        .file
+        .as_deref()
+        .expect("clap requires FILE when no subcommand is given");
+    let (data, schema) = if args.overlays.is_empty() {
+        let options = LoadOptions {
+            strict_duplicate_keys: args.strict,
+        };
+        load_or_exit_with(file, args.schema.as_deref(), &options)
+    } else {
+        load_with_overlays(file, &args.overlays).unwrap_or_else(|err| {
+            eprintln!("Error loading data from '{}': {}", file, err);
+            process::exit(1);
+        })
+    };
+
+    let result = open_output(args.output.as_deref()).and_then(|mut out| {
+        query::run(args, &data, &schema, &mut out)?;
+        out.flush()
+    });
+
+    match result {
+        // A closed pipe (e.g. `| head`) is not an error
+        Err(err) if err.kind() != io::ErrorKind::BrokenPipe => {
+            eprintln!("Error running query: {}", err);
+            process::exit(1);
+        }
+        _ => {}
+    }
+}
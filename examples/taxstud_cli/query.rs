@@ -0,0 +1,552 @@
+use std::io::{self, Write};
+use taxstud_core::*;
+
+use crate::{MissingMode, QueryArgs};
+
+/// Print the taxonomy, or the filtered/sorted/grouped items, as Markdown
+/// With --count or --summarize, print only totals
+pub fn run(
+    query: &QueryArgs,
+    data: &TaxonomyData,
+    schema: &TaxonomySchema,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let filters = parse_filters(query);
+    let lang = query.lang.as_deref();
+
+    // Deprecated items stay out of the printed taxonomy too, unless asked for
+    let current;
+    let data = if query.include_deprecated || !data.items.iter().any(Item::is_deprecated) {
+        data
+    } else {
+        current = TaxonomyData {
+            items: data
+                .items
+                .iter()
+                .filter(|item| !item.is_deprecated())
+                .cloned()
+                .collect(),
+            ..data.clone()
+        };
+        &current
+    };
+
+    if query.count || query.summarize.is_some() {
+        let items = apply_filters_with_schema(&data.items, &filters, schema);
+        match &query.summarize {
+            Some(facet) => print_summary(out, &items, facet, query),
+            None => print_count(out, items.len(), query.json),
+        }
+    } else if has_filters(&filters) || query.sort_by.is_some() || query.group_by.is_some() {
+        print_filtered_data(out, data, schema, &filters, query)
+    } else {
+        print_data(out, data, schema, lang)
+    }
+}
+
+fn parse_filters(query: &QueryArgs) -> Filters {
+    // Check for invalid facet formats and warn
+    for facet_str in query
+        .facets
+        .iter()
+        .chain(&query.excluded_facets)
+        .chain(&query.fields)
+    {
+        if parse_facet_filter(facet_str).is_none() {
+            eprintln!(
+                "Warning: Invalid filter format '{}'. Expected 'name=value' or a numeric comparison like 'name>=10'",
+                facet_str
+            );
+        }
+    }
+
+    Filters {
+        genera: query.genera.clone(),
+        facets: parse_facet_filters(&query.facets),
+        excluded_genera: query.excluded_genera.clone(),
+        excluded_facets: parse_facet_filters(&query.excluded_facets),
+        fields: parse_facet_filters(&query.fields),
+        name_contains: query.name_contains.clone(),
+        include_descendants: query.include_descendants,
+        include_deprecated: query.include_deprecated,
+        options: FilterOptions {
+            case_insensitive: query.ignore_case,
+            strip_diacritics: query.ignore_accents,
+        },
+    }
+}
+
+fn print_filtered_data(
+    out: &mut dyn Write,
+    data: &TaxonomyData,
+    schema: &TaxonomySchema,
+    filters: &Filters,
+    query: &QueryArgs,
+) -> io::Result<()> {
+    writeln!(out, "# Filtered Results\n")?;
+
+    if has_filters(filters) {
+        writeln!(out, "## Active Filters\n")?;
+
+        if let Some(needle) = &filters.name_contains {
+            writeln!(out, "- **Name contains:** {}", needle)?;
+        }
+
+        if !filters.genera.is_empty() {
+            let scope = if filters.include_descendants {
+                " (and descendants)"
+            } else {
+                ""
+            };
+            writeln!(out, "- **Genus:** {}{}", filters.genera.join(" OR "), scope)?;
+        }
+
+        if !filters.facets.is_empty() {
+            for (facet_name, predicates) in &filters.facets {
+                writeln!(
+                    out,
+                    "- **{}:** {}",
+                    facet_name,
+                    describe_facet_predicates(predicates)
+                )?;
+            }
+        }
+
+        for (field_name, predicates) in &filters.fields {
+            writeln!(
+                out,
+                "- **Field {}:** {}",
+                field_name,
+                describe_facet_predicates(predicates)
+            )?;
+        }
+
+        if !filters.excluded_genera.is_empty() {
+            writeln!(
+                out,
+                "- **Not genus:** {}",
+                filters.excluded_genera.join(", ")
+            )?;
+        }
+
+        for (facet_name, predicates) in &filters.excluded_facets {
+            let values: Vec<String> = predicates.iter().map(|p| p.to_string()).collect();
+            writeln!(out, "- **Not {}:** {}", facet_name, values.join(", "))?;
+        }
+        writeln!(out)?;
+    }
+
+    if let Some(sort_field) = &query.sort_by {
+        writeln!(out, "**Sorted by:** {}\n", sort_field)?;
+    }
+
+    if let Some(group_field) = &query.group_by {
+        writeln!(out, "**Grouped by:** {}\n", group_field)?;
+    }
+
+    let lang = query.lang.as_deref();
+    let mut filtered_items = apply_filters_with_schema(&data.items, filters, schema);
+
+    writeln!(out, "**Matching Items:** {}\n", filtered_items.len())?;
+
+    if filtered_items.is_empty() {
+        writeln!(out, "_No items match the specified filters._\n")?;
+    } else {
+        // Apply sorting
+        if let Some(sort_field) = &query.sort_by {
+            sort_items_with_schema(&mut filtered_items, sort_field, schema);
+        }
+
+        // Apply grouping or direct display
+        if let Some(group_field) = &query.group_by {
+            let options = group_options(query);
+            print_grouped_items(out, &filtered_items, group_field, &options, query, schema)?;
+        } else {
+            for item in filtered_items.iter() {
+                print_example_item(out, item, schema, lang)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_grouped_items(
+    out: &mut dyn Write,
+    items: &[Item],
+    group_field: &str,
+    options: &GroupOptions,
+    query: &QueryArgs,
+    schema: &TaxonomySchema,
+) -> io::Result<()> {
+    let lang = query.lang.as_deref();
+    let groups = group_items_with_options(items, group_field, options).map_err(io::Error::other)?;
+    let summaries = summarize_groups(&groups, query.distinct.as_deref(), query.stats.as_deref());
+    let group_names = get_sorted_group_names(&groups);
+
+    for group_name in group_names {
+        if let Some(group_items) = groups.get(&group_name) {
+            writeln!(
+                out,
+                "## {}: {}\n",
+                facet_name_in(schema, group_field, lang),
+                group_label(schema, group_field, &group_name, lang)
+            )?;
+            print_group_summary(out, &summaries[&group_name], query, schema)?;
+
+            for item in group_items {
+                print_example_item(out, item, schema, lang)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_count(out: &mut dyn Write, count: usize, json: bool) -> io::Result<()> {
+    if json {
+        writeln!(out, "{}", serde_json::json!({ "count": count }))
+    } else {
+        writeln!(out, "{}", count)
+    }
+}
+
+/// Matching items per value of a facet, most common first
+/// Items with several values count once per value; --missing decides about items with none
+fn print_summary(
+    out: &mut dyn Write,
+    items: &[Item],
+    facet: &str,
+    query: &QueryArgs,
+) -> io::Result<()> {
+    let groups =
+        group_items_with_options(items, facet, &group_options(query)).map_err(io::Error::other)?;
+    let mut counts: Vec<(&GroupKey, usize)> = groups
+        .iter()
+        .map(|(value, group)| (value, group.len()))
+        .collect();
+    counts.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+
+    if query.json {
+        let counts: Vec<_> = counts
+            .iter()
+            .map(|(value, count)| {
+                serde_json::json!({
+                    "value": value.name(),
+                    "missing": value.is_missing(),
+                    "count": count,
+                })
+            })
+            .collect();
+        let summary = serde_json::json!({ "facet": facet, "total": items.len(), "counts": counts });
+        return writeln!(out, "{}", summary);
+    }
+
+    writeln!(out, "| {} | Count |", facet)?;
+    writeln!(out, "|---|---:|")?;
+    for (value, count) in counts {
+        writeln!(out, "| {} | {} |", missing_in_italics(value), count)?;
+    }
+    writeln!(out, "\n**Total:** {}", items.len())
+}
+
+/// One italic line under a group heading: item count, then the --distinct and --stats facets
+fn print_group_summary(
+    out: &mut dyn Write,
+    summary: &GroupSummary,
+    query: &QueryArgs,
+    schema: &TaxonomySchema,
+) -> io::Result<()> {
+    let lang = query.lang.as_deref();
+    let mut parts = vec![format!("{} item(s)", summary.count)];
+
+    if let Some(facet) = &query.distinct {
+        let values: Vec<&str> = summary
+            .distinct_values
+            .iter()
+            .map(|value| facet_value_in(schema, facet, value, lang))
+            .collect();
+        let values = if values.is_empty() {
+            "none".to_string()
+        } else {
+            values.join(", ")
+        };
+        parts.push(format!(
+            "{}: {}",
+            facet_name_in(schema, facet, lang),
+            values
+        ));
+    }
+
+    if let Some(facet) = &query.stats {
+        let stats = match &summary.numeric {
+            Some(numeric) => format!(
+                "min {}, max {}, avg {:.2}",
+                numeric.min, numeric.max, numeric.avg
+            ),
+            None => "no numbers".to_string(),
+        };
+        parts.push(format!("{}: {}", facet_name_in(schema, facet, lang), stats));
+    }
+
+    writeln!(out, "_{}_\n", parts.join(" · "))
+}
+
+/// A group's value as the schema labels it; the missing-value bucket in italics
+fn group_label(schema: &TaxonomySchema, facet: &str, key: &GroupKey, lang: Option<&str>) -> String {
+    match key {
+        GroupKey::Value(value) => facet_value_in(schema, facet, value, lang).to_string(),
+        GroupKey::Missing(_) => missing_in_italics(key),
+    }
+}
+
+/// A group's name, in italics for the missing-value bucket so it can't pass for a value
+fn missing_in_italics(key: &GroupKey) -> String {
+    if key.is_missing() {
+        format!("_{}_", key)
+    } else {
+        key.to_string()
+    }
+}
+
+fn group_options(query: &QueryArgs) -> GroupOptions {
+    let missing = match query.missing {
+        MissingMode::Bucket => MissingGroup::Bucket(query.missing_label.clone()),
+        MissingMode::Skip => MissingGroup::Skip,
+        MissingMode::Error => MissingGroup::Error,
+    };
+    GroupOptions { missing }
+}
+
+fn print_data(
+    out: &mut dyn Write,
+    data: &TaxonomyData,
+    schema: &TaxonomySchema,
+    lang: Option<&str>,
+) -> io::Result<()> {
+    writeln!(out, "# Hybrid Taxonomy\n")?;
+
+    if let Some(desc) = &schema.description {
+        writeln!(out, "## Description\n")?;
+        writeln!(out, "{}\n", desc)?;
+    }
+
+    writeln!(out, "## Classical Hierarchy\n")?;
+
+    let hierarchy = &schema.classical_hierarchy;
+    writeln!(
+        out,
+        "**Root:** {}\n",
+        hierarchy.display_name(&hierarchy.root, lang)
+    )?;
+
+    for (depth, node) in hierarchy.iter_preorder() {
+        print_hierarchy_node(out, hierarchy, node, depth, lang)?;
+    }
+
+    writeln!(out, "\n## Faceted Dimensions\n")?;
+
+    let mut facets: Vec<_> = schema.faceted_dimensions.iter().collect();
+    facets.sort_by_key(|(name, _)| *name);
+
+    for (facet_name, definition) in facets {
+        writeln!(out, "### {}\n", definition.display_name(facet_name, lang))?;
+        if definition.kind != FacetKind::Enum {
+            writeln!(out, "_Kind: {}_\n", definition.kind)?;
+        }
+        if definition.required {
+            writeln!(out, "_Required_\n")?;
+        }
+        if let Some(default) = &definition.default {
+            writeln!(out, "_Default: {}_\n", default)?;
+        }
+        for value in definition.allowed_values.iter() {
+            write!(out, "- {}", value)?;
+            if let Some(info) = definition.value_info.get(value) {
+                if let Some(label) = localized_label(&info.labels, lang).or(info.label.as_deref()) {
+                    write!(out, " ({})", label)?;
+                }
+                if let Some(description) = &info.description {
+                    write!(out, ": {}", description)?;
+                }
+                if info.deprecated {
+                    write!(out, " _(deprecated)_")?;
+                }
+            }
+            writeln!(out)?;
+        }
+        writeln!(out)?;
+    }
+
+    if !schema.item_fields.is_empty() {
+        writeln!(out, "## Item Fields\n")?;
+        for (name, field) in schema.sorted_item_fields() {
+            write!(out, "- {} ({})", field.display_name(name), field.kind)?;
+            if field.required {
+                write!(out, " _(required)_")?;
+            }
+            writeln!(out)?;
+        }
+        writeln!(out)?;
+    }
+
+    writeln!(out, "## Items\n")?;
+
+    for item in data.items.iter() {
+        print_example_item(out, item, schema, lang)?;
+    }
+
+    if !data.extra.is_empty() {
+        writeln!(out, "## Additional Information\n")?;
+
+        for (key, value) in &data.extra {
+            writeln!(out, "### {}\n", key)?;
+            print_json_value(out, value, 0)?;
+            writeln!(out)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn print_hierarchy_node(
+    out: &mut dyn Write,
+    hierarchy: &ClassicalHierarchy,
+    node: &HierarchyNode,
+    depth: usize,
+    lang: Option<&str>,
+) -> io::Result<()> {
+    let indent = "  ".repeat(depth);
+
+    writeln!(out, "{}* **{}**", indent, node.display_name(lang))?;
+    writeln!(
+        out,
+        "{}  - Genus: {}",
+        indent,
+        hierarchy.display_name(&node.genus, lang)
+    )?;
+    writeln!(out, "{}  - Differentia: {}", indent, node.differentia)?;
+    if let Some(code) = &node.code {
+        writeln!(out, "{}  - Code: {}", indent, code)?;
+    }
+    if !node.synonyms.is_empty() {
+        writeln!(out, "{}  - Synonyms: {}", indent, node.synonyms.join(", "))?;
+    }
+    let mut external_ids: Vec<_> = node.external_ids.iter().collect();
+    external_ids.sort();
+    for (system, id) in external_ids {
+        writeln!(out, "{}  - {}: {}", indent, system, id)?;
+    }
+    Ok(())
+}
+
+fn print_example_item(
+    out: &mut dyn Write,
+    item: &Item,
+    schema: &TaxonomySchema,
+    lang: Option<&str>,
+) -> io::Result<()> {
+    writeln!(out, "### {}\n", item.display_name(lang))?;
+
+    let path: Vec<&str> = item
+        .classical_path
+        .iter()
+        .map(|segment| schema.classical_hierarchy.display_name(segment, lang))
+        .collect();
+    writeln!(out, "**Path:** {}\n", path.join(" → "))?;
+
+    writeln!(out, "**Facets:**\n")?;
+    let mut facets: Vec<_> = item.facets.keys().collect();
+    facets.sort();
+
+    for facet_name in facets {
+        let raw_values = item.get_facet_as_vec(facet_name);
+        let values: Vec<&str> = raw_values
+            .iter()
+            .map(|value| facet_value_in(schema, facet_name, value, lang))
+            .collect();
+        if !values.is_empty() {
+            writeln!(
+                out,
+                "- {}: {}",
+                facet_name_in(schema, facet_name, lang),
+                values.join(", ")
+            )?;
+        }
+    }
+
+    // Fields the schema declares first, under their labels, then anything else
+    for (name, field) in schema.sorted_item_fields() {
+        if let Some(value) = item.extra.get(name) {
+            let shown = value
+                .as_str()
+                .map_or_else(|| value.to_string(), str::to_string);
+            writeln!(out, "\n**{}:** {}", field.display_name(name), shown)?;
+        }
+    }
+
+    for (key, value) in &item.extra {
+        if key != "name"
+            && key != "classical_path"
+            && key != "facets"
+            && !schema.item_fields.contains_key(key)
+        {
+            writeln!(out, "\n**{}:** {}", key, value)?;
+        }
+    }
+
+    writeln!(out)
+}
+
+/// Facet name in the --lang language, or as written
+fn facet_name_in<'a>(
+    schema: &'a TaxonomySchema,
+    facet_name: &'a str,
+    lang: Option<&str>,
+) -> &'a str {
+    schema
+        .faceted_dimensions
+        .get(facet_name)
+        .map_or(facet_name, |definition| {
+            definition.display_name(facet_name, lang)
+        })
+}
+
+/// Facet value in the --lang language, or as written when no language is given
+fn facet_value_in<'a>(
+    schema: &'a TaxonomySchema,
+    facet_name: &str,
+    value: &'a str,
+    lang: Option<&str>,
+) -> &'a str {
+    match (lang, schema.faceted_dimensions.get(facet_name)) {
+        (Some(_), Some(definition)) => definition.label_for(value, lang),
+        _ => value,
+    }
+}
+
+fn print_json_value(
+    out: &mut dyn Write,
+    value: &serde_json::Value,
+    indent: usize,
+) -> io::Result<()> {
+    let indent_str = "  ".repeat(indent);
+
+    match value {
+        serde_json::Value::Array(arr) => {
+            for item in arr.iter() {
+                match item {
+                    serde_json::Value::String(s) => {
+                        writeln!(out, "{}- {}", indent_str, s)?;
+                    }
+                    _ => {
+                        print_json_value(out, item, indent + 1)?;
+                    }
+                }
+            }
+            Ok(())
+        }
+        serde_json::Value::String(s) => writeln!(out, "{}{}", indent_str, s),
+        _ => writeln!(out, "{}{}", indent_str, value),
+    }
+}
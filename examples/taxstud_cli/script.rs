@@ -0,0 +1,47 @@
+use crate::changes;
+use colored::Colorize;
+use std::error::Error;
+use std::path::Path;
+use taxstud_core::*;
+
+/// Run a Rhai script, the file `script` or the text `eval`, against a data file's items
+/// Prints what the script printed and the changes it made to the items, and saves the data file
+/// unless `dry_run` is set or the changes leave it invalid
+pub fn run(
+    data_path: &Path,
+    script: Option<&Path>,
+    eval: Option<&str>,
+    dry_run: bool,
+) -> Result<(), Box<dyn Error>> {
+    let source = match (eval, script) {
+        (Some(source), _) => source.to_string(),
+        (None, Some(path)) => std::fs::read_to_string(path)?,
+        (None, None) => return Err("no script given".into()),
+    };
+    let mut store = TaxonomyStore::load(data_path)?;
+    let before = store.items().to_vec();
+    let report = store.run_script(&source)?;
+
+    for line in &report.output {
+        println!("{}", line);
+    }
+
+    let changes = report.changed + report.added;
+    if changes > 0 && !dry_run {
+        if let Err(errors) = store.validate() {
+            for error in &errors {
+                println!("  {} {}", "error:".red(), error);
+            }
+            return Err("the script leaves the data invalid; nothing was saved".into());
+        }
+        crate::sync::save(&mut store)?;
+    }
+
+    let verb = if dry_run { "Would change" } else { "Changed" };
+    println!(
+        "{} {} item(s), added {}",
+        verb, report.changed, report.added
+    );
+    changes::print(&diff_items(&before, store.items()));
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use crate::changes;
+use colored::Colorize;
+use std::error::Error;
+use std::path::Path;
+use taxstud_core::*;
+
+/// Items left out of a split that are named before the rest are counted
+const LISTED_UNASSIGNED: usize = 10;
+
+/// Write one data file per child subtree of a genus into `out`, all sharing the input's
+/// schema, and print what went where
+/// Items under no child of the genus are listed and left out. The files are formatted as
+/// `options` say. With `dry_run`, prints what writing each file would change instead
+pub fn run(
+    file: &Path,
+    by: &[String],
+    out: &Path,
+    options: &SaveOptions,
+    force: bool,
+    dry_run: bool,
+) -> Result<(), Box<dyn Error>> {
+    let [kind, genus] = by else {
+        return Err("--by takes a kind and a name, e.g. --by genus Beverage".into());
+    };
+    if kind != "genus" {
+        return Err(format!("Cannot split by '{}' (expected 'genus')", kind).into());
+    }
+
+    let schema_path = resolve_schema_path(file)?;
+    let (data, schema) = load_data_with_auto_schema(file)?;
+    let Split {
+        mut parts,
+        unassigned,
+    } = split_by_genus(&data, &schema.classical_hierarchy, genus)?;
+
+    let paths = if dry_run {
+        let paths: Vec<_> = parts.iter().map(|part| out.join(&part.file_name)).collect();
+        for (part, path) in parts.iter().zip(&paths) {
+            changes::print_data_file(path, &part.data.items);
+        }
+        paths
+    } else {
+        // A data file that embeds its schema gives parts that embed it too
+        let schema_file = (schema_path != file).then_some(schema_path.as_path());
+        write_split(&mut parts, &schema, schema_file, out, options, force)?
+    };
+
+    for (part, path) in parts.iter().zip(&paths) {
+        println!(
+            "{:>6}  {:<24}  {}",
+            part.data.items.len(),
+            part.species,
+            path.display()
+        );
+    }
+    if !unassigned.is_empty() {
+        let mut names: Vec<&str> = unassigned
+            .iter()
+            .take(LISTED_UNASSIGNED)
+            .map(|item| item.name.as_str())
+            .collect();
+        let more = unassigned.len().saturating_sub(LISTED_UNASSIGNED);
+        let more = format!("and {} more", more);
+        if unassigned.len() > LISTED_UNASSIGNED {
+            names.push(&more);
+        }
+        println!(
+            "{} {} item(s) under no species of '{}' left out: {}",
+            "warning:".yellow(),
+            unassigned.len(),
+            genus,
+            names.join(", ")
+        );
+    }
+    let verb = if dry_run { "Would split" } else { "Split" };
+    println!(
+        "\n{} {} item(s) into {} file(s)",
+        verb,
+        data.items.len() - unassigned.len(),
+        paths.len()
+    );
+    Ok(())
+}
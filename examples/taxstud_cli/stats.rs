@@ -0,0 +1,95 @@
+use rayon::prelude::*;
+use serde_json::json;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use taxstud_core::*;
+
+/// Size, depth and facet coverage of one data file
+struct FileStats {
+    path: PathBuf,
+    overview: Result<TaxonomyOverview, String>,
+}
+
+impl FileStats {
+    /// Average share of items with a value, over the faceted dimensions
+    fn coverage(overview: &TaxonomyOverview) -> f64 {
+        let facets = &overview.facet_coverage;
+        if facets.is_empty() {
+            return 0.0;
+        }
+        facets.iter().map(FacetCoverage::ratio).sum::<f64>() / facets.len() as f64
+    }
+}
+
+/// Print a table of item counts, hierarchy size and facet coverage per data file, with
+/// totals; files that fail to load are listed with their error
+/// Returns true if every file loaded
+pub fn run(files: &[PathBuf], json: bool, out: &mut dyn Write) -> io::Result<bool> {
+    let stats: Vec<FileStats> = files.par_iter().map(|path| file_stats(path)).collect();
+
+    if json {
+        let files: Vec<_> = stats
+            .iter()
+            .map(|stats| match &stats.overview {
+                Ok(overview) => json!({
+                    "file": stats.path,
+                    "items": overview.item_count,
+                    "nodes": overview.node_count,
+                    "depth": overview.depth,
+                    "facets": overview.facet_coverage.len(),
+                    "coverage": FileStats::coverage(overview),
+                }),
+                Err(err) => json!({ "file": stats.path, "error": err }),
+            })
+            .collect();
+        writeln!(out, "{}", serde_json::Value::Array(files))?;
+        return Ok(stats.iter().all(|stats| stats.overview.is_ok()));
+    }
+
+    writeln!(
+        out,
+        "{:>6}  {:>5}  {:>5}  {:>6}  {:>8}  FILE",
+        "ITEMS", "NODES", "DEPTH", "FACETS", "COVERAGE"
+    )?;
+    let mut loaded = Vec::new();
+    for stats in &stats {
+        match &stats.overview {
+            Ok(overview) => {
+                writeln!(
+                    out,
+                    "{:>6}  {:>5}  {:>5}  {:>6}  {:>7.0}%  {}",
+                    overview.item_count,
+                    overview.node_count,
+                    overview.depth,
+                    overview.facet_coverage.len(),
+                    FileStats::coverage(overview) * 100.0,
+                    stats.path.display()
+                )?;
+                loaded.push(overview);
+            }
+            Err(err) => writeln!(out, "{:>6}  {}: {}", "ERROR", stats.path.display(), err)?,
+        }
+    }
+
+    let items: usize = loaded.iter().map(|overview| overview.item_count).sum();
+    let nodes: usize = loaded.iter().map(|overview| overview.node_count).sum();
+    writeln!(
+        out,
+        "\n{} file(s): {} item(s), {} hierarchy node(s), {} failed to load",
+        stats.len(),
+        items,
+        nodes,
+        stats.len() - loaded.len()
+    )?;
+    Ok(loaded.len() == stats.len())
+}
+
+fn file_stats(path: &Path) -> FileStats {
+    let overview = load_data_with_auto_schema(path)
+        .map(|(data, schema)| overview(&schema, &data))
+        .map_err(|err| err.to_string());
+    FileStats {
+        path: path.to_path_buf(),
+        overview,
+    }
+}
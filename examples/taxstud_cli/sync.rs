@@ -0,0 +1,25 @@
+use colored::Colorize;
+use std::error::Error;
+use taxstud_core::*;
+
+/// Save the store to its file, then publish the save to the webhook in the file's
+/// sync.json, if it has one
+pub fn save(store: &mut TaxonomyStore) -> Result<(), Box<dyn Error>> {
+    let path = store.data_path().ok_or("No file path set")?.to_path_buf();
+    let publisher = SyncPublisher::before_save(&path)?;
+    store.save()?;
+
+    let Some(publisher) = publisher else {
+        return Ok(());
+    };
+    match publisher.request(store.schema(), store.data()) {
+        Some(request) => {
+            request
+                .send()
+                .map_err(|e| format!("{} was saved, but {}", path.display(), e))?;
+            println!("{} {}", "Published to".green(), request.url);
+        }
+        None => println!("{}", "Nothing to publish: no item changed".dimmed()),
+    }
+    Ok(())
+}
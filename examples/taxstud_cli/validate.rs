@@ -0,0 +1,264 @@
+use colored::Colorize;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::SystemTime;
+use taxstud_core::*;
+use walkdir::WalkDir;
+
+/// Validation outcome for a single data file
+pub struct FileReport {
+    pub path: PathBuf,
+    pub issues: Vec<String>,
+    /// Lint findings (e.g. deprecated facet values) that don't fail the file
+    pub warnings: Vec<String>,
+}
+
+impl FileReport {
+    pub fn passed(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Validate files (and, with `recursive`, every data file under directories), plus
+/// schema files shared by a project's members
+/// Returns true if every file passed
+pub fn run(
+    paths: &[PathBuf],
+    recursive: bool,
+    strict: bool,
+    schemas: &[PathBuf],
+) -> Result<bool, Box<dyn Error>> {
+    let files = data_files(paths, recursive)?;
+    let mut reports = validate_all(&files, strict);
+    check_file_relations(&mut reports);
+    reports.extend(schemas.iter().map(|path| validate_schema(path)));
+
+    print_summary(&reports);
+
+    Ok(reports.iter().all(FileReport::passed))
+}
+
+/// Resolve the command-line paths into the data files to validate
+pub fn data_files(paths: &[PathBuf], recursive: bool) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let files: Vec<PathBuf> = discover_files(paths, recursive)?
+        .into_par_iter()
+        // Discovered files that don't reference a schema aren't taxonomy data
+        .filter(|(path, explicit)| *explicit || is_data_file(path))
+        .map(|(path, _)| path)
+        .collect();
+
+    if files.is_empty() {
+        return Err("No taxonomy data files found".into());
+    }
+
+    Ok(files)
+}
+
+/// Validate files in parallel, returning reports sorted by path
+pub fn validate_all(files: &[PathBuf], strict: bool) -> Vec<FileReport> {
+    let mut reports: Vec<FileReport> = files
+        .par_iter()
+        .map(|path| validate_file(path, strict))
+        .collect();
+    reports.sort_by(|a, b| a.path.cmp(&b.path));
+    reports
+}
+
+/// Validate one data file against its referenced schema
+/// JSON Schema errors are reported first; semantic checks run only once the document parses
+pub fn validate_file(path: &Path, strict: bool) -> FileReport {
+    let (issues, warnings) = match collect_issues(path, strict) {
+        Ok(found) => found,
+        Err(err) => (vec![err.to_string()], Vec::new()),
+    };
+
+    FileReport {
+        path: path.to_path_buf(),
+        issues,
+        warnings,
+    }
+}
+
+/// Report item relations whose target isn't among the files that passed validation
+/// Each broken relation is an issue of the file holding it
+pub fn check_file_relations(reports: &mut [FileReport]) {
+    let taxonomies: Vec<(PathBuf, TaxonomySchema, TaxonomyData)> = reports
+        .par_iter()
+        .filter(|report| report.passed())
+        .filter_map(|report| {
+            let (data, schema) = load_data_with_auto_schema(&report.path).ok()?;
+            Some((report.path.clone(), schema, data))
+        })
+        .collect();
+
+    for broken in check_relations(&taxonomies) {
+        if let Some(report) = reports.iter_mut().find(|r| r.path == broken.file) {
+            report.issues.push(broken.to_string());
+        }
+    }
+}
+
+/// Check that a schema file loads, hierarchy, facets and rules included
+pub fn validate_schema(path: &Path) -> FileReport {
+    let issues = match load_schema(path).and_then(|schema| load_rules(path, &schema)) {
+        Ok(_) => Vec::new(),
+        Err(err) => vec![err.to_string()],
+    };
+
+    FileReport {
+        path: path.to_path_buf(),
+        issues,
+        warnings: Vec::new(),
+    }
+}
+
+/// A loaded schema with the modification time and size of its file then
+type CachedSchema = (SystemTime, u64, Arc<TaxonomySchema>);
+
+/// Schemas loaded for validation, by file; files sharing a schema, and watch mode's
+/// re-runs, compile it only once
+static SCHEMAS: OnceLock<Mutex<HashMap<PathBuf, CachedSchema>>> = OnceLock::new();
+
+/// The schema at `path`, loaded again only if the file has changed since
+fn cached_schema(path: &Path) -> Result<Arc<TaxonomySchema>, Box<dyn Error>> {
+    let metadata = fs::metadata(path)?;
+    let (modified, len) = (metadata.modified()?, metadata.len());
+    let schemas = SCHEMAS.get_or_init(Default::default);
+    if let Some((at, size, schema)) = schemas.lock().unwrap().get(path) {
+        if (*at, *size) == (modified, len) {
+            return Ok(Arc::clone(schema));
+        }
+    }
+
+    let schema = Arc::new(load_schema(path)?);
+    schemas
+        .lock()
+        .unwrap()
+        .insert(path.to_path_buf(), (modified, len, Arc::clone(&schema)));
+    Ok(schema)
+}
+
+/// Returns (issues, warnings); lint, house-style rules and custom validators run only on
+/// documents that parse; `strict` also reports keys an object repeats
+fn collect_issues(path: &Path, strict: bool) -> Result<(Vec<String>, Vec<String>), Box<dyn Error>> {
+    let schema_path = resolve_schema_path(path)?;
+    let schema = cached_schema(&schema_path)?;
+    let rules = load_rules(&schema_path, &schema)?;
+    let contents = fs::read_to_string(path)?;
+    let mut data_value: serde_json::Value = serde_json::from_str(&contents)?;
+    if let Some(fields) = data_value.as_object_mut() {
+        fields.remove(INLINE_SCHEMA_KEY);
+    }
+    let mut issues = if strict {
+        check_duplicate_keys(&contents).err().unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
+    if let Err(errors) = schema.validate_json(&data_value) {
+        issues.extend(errors);
+        return Ok((issues, Vec::new()));
+    }
+
+    let data: TaxonomyData = serde_json::from_value(data_value)?;
+    let report = rules.check(&data.items);
+    let custom = ValidatorRegistry::default().check(&schema, &data);
+    issues.extend(validate_data(&data, &schema).err().unwrap_or_default());
+    issues.extend(report.errors);
+    issues.extend(custom.errors);
+    let mut warnings = lint_data(&data, &schema);
+    warnings.extend(report.warnings);
+    warnings.extend(custom.warnings);
+    Ok((issues, warnings))
+}
+
+/// Expand the command-line paths into (file, given explicitly) pairs
+fn discover_files(
+    paths: &[PathBuf],
+    recursive: bool,
+) -> Result<Vec<(PathBuf, bool)>, Box<dyn Error>> {
+    let mut files = Vec::new();
+
+    for path in paths {
+        if !path.is_dir() {
+            files.push((path.clone(), true));
+            continue;
+        }
+
+        if !recursive {
+            return Err(format!(
+                "'{}' is a directory (use --recursive to validate its contents)",
+                path.display()
+            )
+            .into());
+        }
+
+        for entry in WalkDir::new(path).sort_by_file_name() {
+            let entry = entry?;
+            let is_json = entry.path().extension().is_some_and(|ext| ext == "json");
+            if entry.file_type().is_file() && is_json {
+                files.push((entry.into_path(), false));
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// A data file is any JSON document with a top-level `schema` reference
+fn is_data_file(path: &Path) -> bool {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok())
+        .is_some_and(|value| value.get("schema").is_some_and(|v| v.is_string()))
+}
+
+pub fn print_summary(reports: &[FileReport]) {
+    println!("{:<6}  {:>6}  {:>8}  FILE", "STATUS", "ISSUES", "WARNINGS");
+    for report in reports {
+        let status = if !report.passed() {
+            format!("{:<6}", "FAIL").red()
+        } else if !report.warnings.is_empty() {
+            format!("{:<6}", "WARN").yellow()
+        } else {
+            format!("{:<6}", "PASS").green()
+        };
+        println!(
+            "{}  {:>6}  {:>8}  {}",
+            status,
+            report.issues.len(),
+            report.warnings.len(),
+            report.path.display()
+        );
+    }
+
+    let failed: Vec<_> = reports.iter().filter(|r| !r.passed()).collect();
+    let total_issues: usize = reports.iter().map(|r| r.issues.len()).sum();
+    let total_warnings: usize = reports.iter().map(|r| r.warnings.len()).sum();
+    println!(
+        "\n{} file(s): {} passed, {} failed, {} issue(s), {} warning(s)",
+        reports.len(),
+        reports.len() - failed.len(),
+        failed.len(),
+        total_issues,
+        total_warnings
+    );
+
+    for report in reports {
+        if report.issues.is_empty() && report.warnings.is_empty() {
+            continue;
+        }
+        println!("\n{}:", report.path.display());
+        for issue in &report.issues {
+            // Lines after the first (e.g. a parse error's snippet) line up under the text
+            println!("  - {}", issue.replace('\n', "\n    "));
+        }
+        for warning in &report.warnings {
+            println!("  - {} {}", "warning:".yellow(), warning);
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+use taxstud_core::*;
+
+use crate::validate;
+
+/// Quiet period used to coalesce the burst of events an editor save produces
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Validate once, then re-validate whenever a data file or its schema changes
+/// Only the data files affected by a change are re-validated
+pub fn run(paths: &[PathBuf], recursive: bool, strict: bool) -> Result<(), Box<dyn Error>> {
+    let files = validate::data_files(paths, recursive)?;
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    let mut watched_dirs = HashSet::new();
+
+    let mut dependents = dependency_map(&files);
+    watch_parent_dirs(&mut watcher, &mut watched_dirs, &dependents)?;
+
+    validate::print_summary(&validate::validate_all(&files, strict));
+    println!(
+        "\nWatching {} file(s) for changes (Ctrl-C to stop)...",
+        dependents.len()
+    );
+
+    loop {
+        let changed = next_changes(&rx)?;
+
+        let affected: BTreeSet<&PathBuf> = changed
+            .iter()
+            .filter_map(|path| dependents.get(path))
+            .flatten()
+            .collect();
+        if affected.is_empty() {
+            continue;
+        }
+
+        let names: Vec<_> = changed
+            .iter()
+            .filter(|path| dependents.contains_key(*path))
+            .filter_map(|path| path.file_name())
+            .map(|name| name.to_string_lossy())
+            .collect();
+        println!("\n--- Changed: {} ---\n", names.join(", "));
+
+        let affected: Vec<PathBuf> = affected.into_iter().cloned().collect();
+        validate::print_summary(&validate::validate_all(&affected, strict));
+
+        // A data file may now reference a different schema
+        dependents = dependency_map(&files);
+        watch_parent_dirs(&mut watcher, &mut watched_dirs, &dependents)?;
+    }
+}
+
+/// Map each watched file to the data files that must be re-validated when it changes
+/// A data file depends on itself, on the schema it references and on the rules file beside it
+fn dependency_map(files: &[PathBuf]) -> HashMap<PathBuf, Vec<PathBuf>> {
+    let mut dependents: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+
+    for file in files {
+        if let Some(key) = watch_key(file) {
+            dependents.entry(key).or_default().push(file.clone());
+        }
+        let Ok(schema_path) = resolve_schema_path(file) else {
+            continue;
+        };
+        let rules_path = schema_path.with_file_name(RULES_FILE);
+        for dependency in [&schema_path, &rules_path] {
+            if let Some(key) = watch_key(dependency) {
+                dependents.entry(key).or_default().push(file.clone());
+            }
+        }
+    }
+
+    dependents
+}
+
+/// Normalize a path the way watcher events report it: canonical directory + file name
+/// The file itself isn't canonicalized since it may be mid-save (renamed or removed)
+fn watch_key(path: &Path) -> Option<PathBuf> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    Some(fs::canonicalize(dir).ok()?.join(path.file_name()?))
+}
+
+/// Watch directories rather than files so atomic saves (write + rename) are seen
+fn watch_parent_dirs(
+    watcher: &mut RecommendedWatcher,
+    watched_dirs: &mut HashSet<PathBuf>,
+    dependents: &HashMap<PathBuf, Vec<PathBuf>>,
+) -> notify::Result<()> {
+    for path in dependents.keys() {
+        if let Some(dir) = path.parent() {
+            if watched_dirs.insert(dir.to_path_buf()) {
+                watcher.watch(dir, RecursiveMode::NonRecursive)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Block until something changes, then collect every path touched within the debounce window
+fn next_changes(rx: &Receiver<notify::Result<Event>>) -> Result<HashSet<PathBuf>, Box<dyn Error>> {
+    let mut changed = HashSet::new();
+    let mut event = rx.recv()?;
+
+    loop {
+        match event {
+            Ok(event) => {
+                if matches!(
+                    event.kind,
+                    EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                ) {
+                    changed.extend(event.paths);
+                }
+            }
+            Err(err) => eprintln!("Watch error: {}", err),
+        }
+
+        event = match rx.recv_timeout(DEBOUNCE) {
+            Ok(event) => event,
+            Err(RecvTimeoutError::Timeout) => return Ok(changed),
+            Err(RecvTimeoutError::Disconnected) => return Err("File watcher stopped".into()),
+        };
+    }
+}
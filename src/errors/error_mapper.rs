@@ -1,43 +1,64 @@
 use std::path::Path;
+use taxstud_core::JsonParseError;
+
+use crate::i18n::tr;
 
 /// Map file loading errors to user-friendly messages
 /// Returns (title, message, details)
-pub fn map_file_load_error(error: &dyn std::error::Error, path: &Path) -> (String, String, String) {
+pub fn map_file_load_error(
+    error: &(dyn std::error::Error + 'static),
+    path: &Path,
+) -> (String, String, String) {
     let error_string = error.to_string();
 
-    if error_string.contains("Validation failed") {
+    if let Some(parse_error) = error.downcast_ref::<JsonParseError>() {
+        map_json_parse_error(parse_error)
+    } else if error_string.contains("Validation failed") {
         (
-            "Validation Error".to_string(),
-            "The taxonomy file has validation errors.".to_string(),
+            tr!("Validation Error"),
+            tr!("The taxonomy file has validation errors."),
             error_string,
         )
     } else if error_string.contains("No such file") {
         (
-            "File Not Found".to_string(),
-            "The file could not be found.".to_string(),
-            format!(
+            tr!("File Not Found"),
+            tr!("The file could not be found."),
+            tr!(
                 "Path: {}\n\nPlease verify the file exists and you have permission to read it.",
                 path.display()
             ),
         )
     } else if error_string.contains("Permission denied") {
         (
-            "Permission Denied".to_string(),
-            "Permission denied.".to_string(),
-            format!(
+            tr!("Permission Denied"),
+            tr!("Permission denied."),
+            tr!(
                 "You don't have permission to read this file:\n{}",
                 path.display()
             ),
         )
     } else {
         (
-            "Error Loading File".to_string(),
-            "Failed to load taxonomy file.".to_string(),
+            tr!("Error Loading File"),
+            tr!("Failed to load taxonomy file."),
             error_string,
         )
     }
 }
 
+/// A file that isn't well-formed JSON: where, with the offending line as the details
+fn map_json_parse_error(error: &JsonParseError) -> (String, String, String) {
+    (
+        tr!("Invalid JSON"),
+        tr!(
+            "The file is not valid JSON: line {}, column {}.",
+            error.line,
+            error.column
+        ),
+        format!("{}\n\n{}", error.message, error.snippet),
+    )
+}
+
 /// Map file saving errors to user-friendly messages
 /// Returns (title, message, details)
 pub fn map_file_save_error(
@@ -48,31 +69,27 @@ pub fn map_file_save_error(
 
     if error_string.contains("No file path set") {
         (
-            "No File Path".to_string(),
-            "No file path is set for this taxonomy.".to_string(),
-            "Please use 'Save As...' to choose a location for this file.".to_string(),
+            tr!("No File Path"),
+            tr!("No file path is set for this taxonomy."),
+            tr!("Please use 'Save As...' to choose a location for this file."),
         )
     } else if error_string.contains("Permission denied") {
         let details = if let Some(p) = path {
-            format!("You don't have permission to write to:\n{}", p.display())
+            tr!("You don't have permission to write to:\n{}", p.display())
         } else {
-            "You don't have permission to write to this file.".to_string()
+            tr!("You don't have permission to write to this file.")
         };
-        (
-            "Permission Denied".to_string(),
-            "Permission denied.".to_string(),
-            details,
-        )
+        (tr!("Permission Denied"), tr!("Permission denied."), details)
     } else if error_string.contains("No space left") {
         (
-            "Disk Full".to_string(),
-            "Disk full.".to_string(),
-            "There is no space left on the device to save the file.".to_string(),
+            tr!("Disk Full"),
+            tr!("Disk full."),
+            tr!("There is no space left on the device to save the file."),
         )
     } else {
         (
-            "Error Saving File".to_string(),
-            "Failed to save taxonomy file.".to_string(),
+            tr!("Error Saving File"),
+            tr!("Failed to save taxonomy file."),
             error_string,
         )
     }
@@ -80,37 +97,42 @@ pub fn map_file_save_error(
 
 /// Map revert errors to user-friendly messages
 /// Returns (title, message, details)
-pub fn map_revert_error(error: &dyn std::error::Error, path: &Path) -> (String, String, String) {
+pub fn map_revert_error(
+    error: &(dyn std::error::Error + 'static),
+    path: &Path,
+) -> (String, String, String) {
     let error_string = error.to_string();
 
-    if error_string.contains("Validation failed") {
+    if let Some(parse_error) = error.downcast_ref::<JsonParseError>() {
+        map_json_parse_error(parse_error)
+    } else if error_string.contains("Validation failed") {
         (
-            "Validation Error".to_string(),
-            "The taxonomy file has validation errors.".to_string(),
+            tr!("Validation Error"),
+            tr!("The taxonomy file has validation errors."),
             error_string,
         )
     } else if error_string.contains("No such file") {
         (
-            "File Not Found".to_string(),
-            "The file could not be found.".to_string(),
-            format!(
+            tr!("File Not Found"),
+            tr!("The file could not be found."),
+            tr!(
                 "Path: {}\n\nThe file may have been moved or deleted.",
                 path.display()
             ),
         )
     } else if error_string.contains("Permission denied") {
         (
-            "Permission Denied".to_string(),
-            "Permission denied.".to_string(),
-            format!(
+            tr!("Permission Denied"),
+            tr!("Permission denied."),
+            tr!(
                 "You don't have permission to read this file:\n{}",
                 path.display()
             ),
         )
     } else {
         (
-            "Error Reverting File".to_string(),
-            "Failed to reload taxonomy file.".to_string(),
+            tr!("Error Reverting File"),
+            tr!("Failed to reload taxonomy file."),
             error_string,
         )
     }
@@ -0,0 +1,86 @@
+use serde::Serialize;
+use slint::{ComponentHandle, SharedString};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::i18n::tr;
+use crate::state::AppState;
+use crate::ui::set_status;
+use crate::{MainWindow, StatusLevel};
+
+/// Register the context menus' Copy as JSON handlers
+pub fn register_clipboard_handlers(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    register_copy_item_json(window, app_state);
+    register_copy_node_json(window, app_state);
+    register_copy_facet_value_json(window, app_state);
+}
+
+/// Put `value` on the clipboard as pretty-printed JSON, saying what was copied
+fn copy_json(window: &MainWindow, what: &str, value: &impl Serialize) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => {
+            window.invoke_copy_to_clipboard(SharedString::from(json));
+            set_status(window, tr!("Copied {} as JSON", what), StatusLevel::Info);
+        }
+        Err(e) => set_status(
+            window,
+            tr!("Could not copy {}: {}", what, e),
+            StatusLevel::Danger,
+        ),
+    }
+}
+
+/// Register item list context menu -> Copy as JSON handler
+fn register_copy_item_json(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_copy_item_json(move || {
+        let main_window = main_window_weak.unwrap();
+        let state = app_state.borrow();
+
+        let Some(item) = state.item_at_row(main_window.get_selected_item_index()) else {
+            return;
+        };
+        copy_json(&main_window, &format!("'{}'", item.name), item);
+    });
+}
+
+/// Register hierarchy tree context menu -> Copy as JSON handler
+/// Copies the node with everything under it
+fn register_copy_node_json(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_copy_node_json(move |species| {
+        let main_window = main_window_weak.unwrap();
+        let state = app_state.borrow();
+
+        let Some(node) = state
+            .get_classical_hierarchy()
+            .and_then(|hierarchy| hierarchy.find_node(species.as_str()))
+        else {
+            return;
+        };
+        copy_json(&main_window, &format!("'{}'", species), node);
+    });
+}
+
+/// Register statistics context menu -> Copy as JSON handler
+/// Copies the value keyed by the charted facet, as it appears in an item's facets
+fn register_copy_facet_value_json(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_copy_facet_value_json(move |value| {
+        let main_window = main_window_weak.unwrap();
+
+        let index = main_window.get_statistics_facet_index();
+        let facet_names = app_state.borrow().facet_names();
+        let Some(facet) = usize::try_from(index).ok().and_then(|i| facet_names.get(i)) else {
+            return;
+        };
+        let facet_value = serde_json::json!({ facet.as_str(): value.as_str() });
+        copy_json(&main_window, &format!("{}={}", facet, value), &facet_value);
+    });
+}
@@ -0,0 +1,75 @@
+use slint::ComponentHandle;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::i18n::tr;
+use crate::operations::FileOperations;
+use crate::state::AppState;
+use crate::ui::{set_status, show_error, update_comparison};
+use crate::{MainWindow, StatusLevel};
+
+/// Register all compare view handlers
+pub fn register_compare_handlers(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    register_compare_files(window, app_state);
+    register_compare_with_head(window, app_state);
+    register_compare_row_selected(window, app_state);
+}
+
+/// Register File -> Compare Files handler
+fn register_compare_files(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_compare_files(move || {
+        let main_window = main_window_weak.unwrap();
+        let app_state = app_state.clone();
+        slint::spawn_local(async move {
+            let ops = FileOperations::new(&app_state, &main_window);
+            ops.compare_files().await;
+        })
+        .unwrap();
+    });
+}
+
+/// Register Git -> Compare With HEAD handler
+fn register_compare_with_head(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_compare_with_head(move || {
+        let main_window = main_window_weak.unwrap();
+
+        let compare_result = app_state.borrow_mut().compare_with_head();
+        match compare_result {
+            Ok(()) => {
+                update_comparison(&main_window, &app_state.borrow());
+                main_window.set_main_tab(5);
+                set_status(
+                    &main_window,
+                    tr!("Comparing with the last commit"),
+                    StatusLevel::Info,
+                );
+            }
+            Err(e) => show_error(
+                &main_window,
+                tr!("Compare Error"),
+                tr!("Could not compare the file with its last commit"),
+                e.to_string(),
+            ),
+        }
+    });
+}
+
+/// Register compare view row selection handler (shows the item's differences)
+fn register_compare_row_selected(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_compare_row_selected(move |index| {
+        let main_window = main_window_weak.unwrap();
+        if let Some(ref mut comparison) = app_state.borrow_mut().comparison {
+            comparison.selected = usize::try_from(index).ok();
+        }
+        update_comparison(&main_window, &app_state.borrow());
+    });
+}
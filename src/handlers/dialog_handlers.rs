@@ -3,10 +3,11 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 use crate::errors::map_file_save_error;
-use crate::operations::FileOperations;
+use crate::i18n::tr;
+use crate::operations::{publish_sync, FileOperations};
 use crate::state::{AppState, PendingAction, SimpleConfirmationAction, UiState};
 use crate::ui::{
-    hide_confirmation, hide_error, hide_simple_confirmation, set_status, show_error,
+    hide_confirmation, hide_error, hide_simple_confirmation, set_status, show_error, show_item,
     update_ui_from_state,
 };
 use crate::{MainWindow, StatusLevel};
@@ -46,6 +47,7 @@ fn register_confirmation_save(
                 // Update window title
                 let title = app_state.borrow().get_window_title();
                 main_window.set_window_title(SharedString::from(title));
+                publish_sync(&main_window, &app_state);
 
                 // Hide confirmation dialog
                 hide_confirmation(&main_window);
@@ -107,7 +109,7 @@ fn register_confirmation_cancel(window: &MainWindow, ui_state: &Rc<RefCell<UiSta
         // Clear pending action
         ui_state.borrow_mut().pending_action = None;
 
-        set_status(&main_window, "Action cancelled", StatusLevel::Info);
+        set_status(&main_window, tr!("Action cancelled"), StatusLevel::Info);
     });
 }
 
@@ -143,6 +145,20 @@ fn register_simple_confirmation_ok(
                     })
                     .unwrap();
                 }
+                SimpleConfirmationAction::ShowItem(name) => {
+                    main_window.invoke_clear_filters();
+                    if !show_item(&main_window, &app_state, &name) {
+                        // Only deprecated items stay hidden without filters
+                        set_status(
+                            &main_window,
+                            tr!(
+                                "'{}' is deprecated; View → Show Deprecated Items lists it",
+                                name
+                            ),
+                            StatusLevel::Info,
+                        );
+                    }
+                }
             }
         }
     });
@@ -162,7 +178,7 @@ fn register_simple_confirmation_cancel(window: &MainWindow, ui_state: &Rc<RefCel
         // Clear action
         ui_state.borrow_mut().simple_confirmation_action = None;
 
-        set_status(&main_window, "Action cancelled", StatusLevel::Info);
+        set_status(&main_window, tr!("Action cancelled"), StatusLevel::Info);
     });
 }
 
@@ -201,7 +217,47 @@ fn execute_pending_action(
             let title = app_state.borrow().get_window_title();
             main_window.set_window_title(SharedString::from(title));
             update_ui_from_state(main_window, app_state);
-            set_status(main_window, "New taxonomy created", StatusLevel::Success);
+            set_status(
+                main_window,
+                tr!("New taxonomy created"),
+                StatusLevel::Success,
+            );
+        }
+        PendingAction::OpenProject => {
+            let app_state = app_state.clone();
+            let main_window = main_window.clone_strong();
+            slint::spawn_local(async move {
+                let ops = FileOperations::new(&app_state, &main_window);
+                ops.open_project_dialog_and_load().await;
+            })
+            .unwrap();
+        }
+        PendingAction::OpenProjectMember(index) => {
+            let app_state = app_state.clone();
+            let main_window = main_window.clone_strong();
+            slint::spawn_local(async move {
+                let ops = FileOperations::new(&app_state, &main_window);
+                ops.load_project_member(index).await;
+            })
+            .unwrap();
+        }
+        PendingAction::OpenUrl(url) => {
+            let app_state = app_state.clone();
+            let main_window = main_window.clone_strong();
+            slint::spawn_local(async move {
+                let ops = FileOperations::new(&app_state, &main_window);
+                ops.load_url(&url).await;
+            })
+            .unwrap();
+        }
+        PendingAction::OpenRecent(path) => {
+            let app_state = app_state.clone();
+            let main_window = main_window.clone_strong();
+            slint::spawn_local(async move {
+                let ops = FileOperations::new(&app_state, &main_window);
+                ops.load_file(&path).await;
+            })
+            .unwrap();
         }
         PendingAction::Exit => {
             // Exit the application
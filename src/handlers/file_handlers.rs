@@ -1,7 +1,9 @@
-use slint::{ComponentHandle, SharedString};
+use slint::{CloseRequestResponse, ComponentHandle, SharedString, VecModel};
 use std::cell::RefCell;
 use std::rc::Rc;
+use taxstud_core::is_url;
 
+use crate::i18n::tr;
 use crate::operations::FileOperations;
 use crate::state::{AppState, PendingAction, SimpleConfirmationAction, UiState};
 use crate::ui::{set_status, show_confirmation, show_simple_confirmation, update_ui_from_state};
@@ -14,11 +16,18 @@ pub fn register_file_handlers(
     ui_state: &Rc<RefCell<UiState>>,
 ) {
     register_file_open(window, app_state, ui_state);
+    register_file_open_url(window, app_state, ui_state);
     register_file_save(window, app_state);
     register_file_save_as(window, app_state);
     register_file_new(window, app_state, ui_state);
     register_file_revert(window, app_state, ui_state);
     register_file_exit(window, app_state, ui_state);
+    register_window_close(window);
+    register_file_open_project(window, app_state, ui_state);
+    register_open_project_member(window, app_state, ui_state);
+    register_open_recent(window, app_state, ui_state);
+    register_split_by_genus(window, app_state);
+    register_export_as(window, app_state);
 }
 
 /// Register File -> Open handler
@@ -40,7 +49,7 @@ fn register_file_open(
             ui_state.borrow_mut().pending_action = Some(PendingAction::Open);
             show_confirmation(
                 &main_window,
-                "You have unsaved changes. Do you want to save before opening another file?",
+                tr!("You have unsaved changes. Do you want to save before opening another file?"),
             );
         } else {
             // No unsaved changes, proceed with open using FileOperations
@@ -55,6 +64,48 @@ fn register_file_open(
     });
 }
 
+/// Register File -> Open URL handler
+fn register_file_open_url(
+    window: &MainWindow,
+    app_state: &Rc<RefCell<AppState>>,
+    ui_state: &Rc<RefCell<UiState>>,
+) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+    let ui_state = ui_state.clone();
+
+    window.on_file_open_url(move |url| {
+        let main_window = main_window_weak.unwrap();
+        let url = url.trim().to_string();
+
+        if !is_url(&url) {
+            set_status(
+                &main_window,
+                tr!("Enter an http:// or https:// URL"),
+                StatusLevel::Warning,
+            );
+            return;
+        }
+        main_window.set_show_open_url_dialog(false);
+
+        if app_state.borrow().dirty {
+            ui_state.borrow_mut().pending_action = Some(PendingAction::OpenUrl(url));
+            show_confirmation(
+                &main_window,
+                tr!("You have unsaved changes. Do you want to save before opening another file?"),
+            );
+        } else {
+            let app_state = app_state.clone();
+            let main_window_clone = main_window.clone_strong();
+            slint::spawn_local(async move {
+                let ops = FileOperations::new(&app_state, &main_window_clone);
+                ops.load_url(&url).await;
+            })
+            .unwrap();
+        }
+    });
+}
+
 /// Register File -> Save handler
 fn register_file_save(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
     let main_window_weak = window.as_weak();
@@ -106,7 +157,9 @@ fn register_file_new(
             ui_state.borrow_mut().pending_action = Some(PendingAction::New);
             show_confirmation(
                 &main_window,
-                "You have unsaved changes. Do you want to save before creating a new taxonomy?",
+                tr!(
+                    "You have unsaved changes. Do you want to save before creating a new taxonomy?"
+                ),
             );
         } else {
             // No unsaved changes, proceed with creating new taxonomy
@@ -120,7 +173,11 @@ fn register_file_new(
             // Update UI with new empty taxonomy (borrow immutably)
             update_ui_from_state(&main_window, &app_state);
 
-            set_status(&main_window, "New taxonomy created", StatusLevel::Success);
+            set_status(
+                &main_window,
+                tr!("New taxonomy created"),
+                StatusLevel::Success,
+            );
         }
     });
 }
@@ -150,9 +207,9 @@ fn register_file_revert(
                 Some(SimpleConfirmationAction::Revert);
             show_simple_confirmation(
                 &main_window,
-                "Revert to Saved",
-                "Are you sure you want to revert to the last saved version? All unsaved changes will be lost.",
-                "Revert",
+                tr!("Revert to Saved"),
+                tr!("Are you sure you want to revert to the last saved version? All unsaved changes will be lost."),
+                tr!("Revert"),
             );
         } else {
             // Either no file or no changes
@@ -160,11 +217,11 @@ fn register_file_revert(
             if state_borrow.current_file.is_none() {
                 set_status(
                     &main_window,
-                    "No file to revert to",
+                    tr!("No file to revert to"),
                     StatusLevel::Warning,
                 );
             } else {
-                set_status(&main_window, "No unsaved changes", StatusLevel::Info);
+                set_status(&main_window, tr!("No unsaved changes"), StatusLevel::Info);
             }
         }
     });
@@ -189,7 +246,7 @@ fn register_file_exit(
             ui_state.borrow_mut().pending_action = Some(PendingAction::Exit);
             show_confirmation(
                 &main_window,
-                "You have unsaved changes. Do you want to save before exiting?",
+                tr!("You have unsaved changes. Do you want to save before exiting?"),
             );
         } else {
             // No unsaved changes, exit immediately
@@ -198,3 +255,201 @@ fn register_file_exit(
         }
     });
 }
+
+/// Register the OS window close button handler
+/// Goes through File -> Exit, so unsaved changes get the same prompt
+fn register_window_close(window: &MainWindow) {
+    let main_window_weak = window.as_weak();
+
+    window.window().on_close_requested(move || {
+        let main_window = main_window_weak.unwrap();
+
+        // Exit hides the window itself once nothing is left unsaved
+        main_window.invoke_file_exit();
+        CloseRequestResponse::KeepWindowShown
+    });
+}
+
+/// Register File -> Open Project handler
+fn register_file_open_project(
+    window: &MainWindow,
+    app_state: &Rc<RefCell<AppState>>,
+    ui_state: &Rc<RefCell<UiState>>,
+) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+    let ui_state = ui_state.clone();
+
+    window.on_file_open_project(move || {
+        let main_window = main_window_weak.unwrap();
+
+        if app_state.borrow().dirty {
+            ui_state.borrow_mut().pending_action = Some(PendingAction::OpenProject);
+            show_confirmation(
+                &main_window,
+                tr!("You have unsaved changes. Do you want to save before opening a project?"),
+            );
+        } else {
+            let app_state = app_state.clone();
+            let main_window_clone = main_window.clone_strong();
+            slint::spawn_local(async move {
+                let ops = FileOperations::new(&app_state, &main_window_clone);
+                ops.open_project_dialog_and_load().await;
+            })
+            .unwrap();
+        }
+    });
+}
+
+/// Register the project sidebar's member click handler
+fn register_open_project_member(
+    window: &MainWindow,
+    app_state: &Rc<RefCell<AppState>>,
+    ui_state: &Rc<RefCell<UiState>>,
+) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+    let ui_state = ui_state.clone();
+
+    window.on_open_project_member(move |index| {
+        let main_window = main_window_weak.unwrap();
+        let index = index as usize;
+
+        // Already showing this member
+        if app_state.borrow().project_member_index() == Some(index) {
+            return;
+        }
+
+        if app_state.borrow().dirty {
+            ui_state.borrow_mut().pending_action = Some(PendingAction::OpenProjectMember(index));
+            show_confirmation(
+                &main_window,
+                tr!("You have unsaved changes. Do you want to save before switching taxonomies?"),
+            );
+        } else {
+            let app_state = app_state.clone();
+            let main_window_clone = main_window.clone_strong();
+            slint::spawn_local(async move {
+                let ops = FileOperations::new(&app_state, &main_window_clone);
+                ops.load_project_member(index).await;
+            })
+            .unwrap();
+        }
+    });
+}
+
+/// Register File -> Open Recent handler
+fn register_open_recent(
+    window: &MainWindow,
+    app_state: &Rc<RefCell<AppState>>,
+    ui_state: &Rc<RefCell<UiState>>,
+) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+    let ui_state = ui_state.clone();
+
+    window.on_open_recent(move |index| {
+        let main_window = main_window_weak.unwrap();
+        let path = app_state
+            .borrow()
+            .config
+            .recent_files
+            .get(index as usize)
+            .cloned();
+        let Some(path) = path else {
+            return;
+        };
+
+        if app_state.borrow().dirty {
+            ui_state.borrow_mut().pending_action = Some(PendingAction::OpenRecent(path));
+            show_confirmation(
+                &main_window,
+                tr!("You have unsaved changes. Do you want to save before opening another file?"),
+            );
+        } else {
+            let app_state = app_state.clone();
+            let main_window_clone = main_window.clone_strong();
+            slint::spawn_local(async move {
+                let ops = FileOperations::new(&app_state, &main_window_clone);
+                ops.load_file(&path).await;
+            })
+            .unwrap();
+        }
+    });
+}
+
+/// Register File -> Split by Genus handler
+fn register_split_by_genus(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_split_by_genus(move |genus| {
+        let main_window = main_window_weak.unwrap();
+        let genus = genus.trim().to_string();
+
+        if app_state.borrow().data.is_none() {
+            set_status(
+                &main_window,
+                tr!("No taxonomy loaded"),
+                StatusLevel::Warning,
+            );
+            return;
+        }
+        if genus.is_empty() {
+            set_status(&main_window, tr!("Enter a genus"), StatusLevel::Warning);
+            return;
+        }
+        main_window.set_show_split_dialog(false);
+
+        let app_state = app_state.clone();
+        let main_window = main_window.clone_strong();
+        slint::spawn_local(async move {
+            let ops = FileOperations::new(&app_state, &main_window);
+            ops.split_by_genus(&genus).await;
+        })
+        .unwrap();
+    });
+}
+
+/// Register File -> Export handler, listing the registered exporters in the menu
+fn register_export_as(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let formats: Vec<SharedString> = app_state
+        .borrow()
+        .exporters
+        .iter()
+        .map(|exporter| SharedString::from(format!("{}...", exporter.name())))
+        .collect();
+    window.set_export_formats(Rc::new(VecModel::from(formats)).into());
+
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_export_as(move |index| {
+        let main_window = main_window_weak.unwrap();
+        let id = app_state
+            .borrow()
+            .exporters
+            .iter()
+            .nth(index as usize)
+            .map(|exporter| exporter.id());
+        let Some(id) = id else {
+            return;
+        };
+        if app_state.borrow().data.is_none() {
+            set_status(
+                &main_window,
+                tr!("No taxonomy loaded"),
+                StatusLevel::Warning,
+            );
+            return;
+        }
+
+        let app_state = app_state.clone();
+        let main_window = main_window.clone_strong();
+        slint::spawn_local(async move {
+            let ops = FileOperations::new(&app_state, &main_window);
+            ops.export_as(id).await;
+        })
+        .unwrap();
+    });
+}
@@ -1,36 +1,125 @@
 use slint::{ComponentHandle, SharedString};
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::rc::Rc;
-use taxstud_core::{matches_filters, parse_facet_filters, Filters};
+use taxstud_core::{
+    describe_facet_predicates, parse_facet_filters, FilterOptions, Filters, SOURCE_FIELD,
+};
 
+use crate::i18n::tr;
 use crate::state::AppState;
-use crate::ui::{set_status, update_ui_from_state};
+use crate::ui::{set_status, update_statistics, update_ui_from_state};
 use crate::{MainWindow, StatusLevel};
 
 /// Register all filter and sorting handlers
 pub fn register_filter_handlers(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
-    register_sort_by_name(window, app_state);
+    register_set_sort(window, app_state);
+    register_group_by_changed(window, app_state);
+    register_group_show_missing_changed(window, app_state);
+    register_group_summary_changed(window, app_state);
+    register_item_row_clicked(window, app_state);
     register_apply_filters(window, app_state);
     register_clear_filters(window, app_state);
+    register_filter_by_source(window);
+    register_filter_by_node(window);
+    register_filter_by_item_node(window, app_state);
+    register_statistics_facet_changed(window, app_state);
+    register_statistics_value_clicked(window, app_state);
 }
 
-/// Register sort by name handler
-fn register_sort_by_name(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+/// Register Sort menu (and Sort by Name button) handler
+/// The sort stays through filtering, edits and file loads, and is kept in the config
+fn register_set_sort(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
     let main_window_weak = window.as_weak();
     let app_state = app_state.clone();
 
-    window.on_sort_by_name(move || {
+    window.on_set_sort(move |field, descending| {
         let main_window = main_window_weak.unwrap();
 
-        // Set sort_by in state
-        {
-            let mut state_mut = app_state.borrow_mut();
-            state_mut.sort_by = Some("name".to_string());
-        }
+        let field = (!field.is_empty()).then(|| field.to_string());
+        app_state
+            .borrow_mut()
+            .set_sort(field.clone(), descending && field.is_some());
 
         // Update UI from state (will apply the sort)
         update_ui_from_state(&main_window, &app_state);
-        set_status(&main_window, "Items sorted by name", StatusLevel::Info);
+        let message = match (field.as_deref(), descending) {
+            (None, _) => tr!("Items in file order"),
+            (Some("name"), false) => tr!("Items sorted by name"),
+            (Some("name"), true) => tr!("Items sorted by name, descending"),
+            (Some(facet), false) => tr!("Items sorted by {}", facet),
+            (Some(facet), true) => tr!("Items sorted by {}, descending", facet),
+        };
+        set_status(&main_window, message, StatusLevel::Info);
+    });
+}
+
+/// Register Group by dropdown handler
+/// Choice 0 is no grouping, the others are the facets in the order facet_names gives
+fn register_group_by_changed(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_group_by_changed(move |index| {
+        let main_window = main_window_weak.unwrap();
+
+        let facet = usize::try_from(index - 1)
+            .ok()
+            .and_then(|i| app_state.borrow().facet_names().into_iter().nth(i));
+        app_state.borrow_mut().set_group_by(facet.clone());
+
+        update_ui_from_state(&main_window, &app_state);
+        let message = match facet {
+            Some(facet) => tr!("Items grouped by {}", facet),
+            None => tr!("Items not grouped"),
+        };
+        set_status(&main_window, message, StatusLevel::Info);
+    });
+}
+
+/// Register the group-by view's toggle for the group of items without a value
+fn register_group_show_missing_changed(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_group_show_missing_changed(move |show| {
+        let main_window = main_window_weak.unwrap();
+        app_state.borrow_mut().set_show_missing_group(show);
+        update_ui_from_state(&main_window, &app_state);
+    });
+}
+
+/// Register the group-by view's Summarize dropdown
+/// Choice 0 is just the counts, the others are the facets in the order facet_names gives
+fn register_group_summary_changed(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_group_summary_changed(move |index| {
+        let main_window = main_window_weak.unwrap();
+
+        let facet = usize::try_from(index - 1)
+            .ok()
+            .and_then(|i| app_state.borrow().facet_names().into_iter().nth(i));
+        app_state.borrow_mut().set_group_summary_facet(facet);
+        update_ui_from_state(&main_window, &app_state);
+    });
+}
+
+/// Register item list click handler
+/// Clicking a group header collapses or expands the group
+fn register_item_row_clicked(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_item_row_clicked(move |row| {
+        let main_window = main_window_weak.unwrap();
+
+        let Some(group) = app_state.borrow().group_at_row(row).cloned() else {
+            return;
+        };
+        app_state.borrow_mut().toggle_group(&group);
+        update_ui_from_state(&main_window, &app_state);
     });
 }
 
@@ -60,35 +149,69 @@ fn register_apply_filters(window: &MainWindow, app_state: &Rc<RefCell<AppState>>
             .filter(|s| !s.is_empty())
             .collect();
 
-        let facet_map = parse_facet_filters(&facet_strings);
+        // Names that aren't facets filter by additional fields, e.g. "source.origin_file=..."
+        // Those always narrow the list, even with the exclude toggle on
+        let facet_names = app_state.borrow().facet_names();
+        let (facet_map, field_map): (HashMap<_, _>, HashMap<_, _>) =
+            parse_facet_filters(&facet_strings)
+                .into_iter()
+                .partition(|(name, _)| facet_names.contains(name));
+        let include_descendants = main_window.get_genus_include_descendants();
+        let exclude = main_window.get_filter_exclude();
+        let options = filter_options(&main_window);
 
-        // Update state filters
-        let filtered_count = {
+        // Update state filters (the exclude toggle turns both fields into exclusions)
+        {
             let mut state_mut = app_state.borrow_mut();
-            state_mut.filters.genera = genera.clone();
-            state_mut.filters.facets = facet_map.clone();
-
-            // Count filtered items
-            if let Some(ref data) = state_mut.data {
-                data.items
-                    .iter()
-                    .filter(|item| matches_filters(item, &state_mut.filters))
-                    .count()
+            state_mut.filters = Filters {
+                include_descendants,
+                include_deprecated: state_mut.filters.include_deprecated,
+                fields: field_map.clone(),
+                options,
+                ..Filters::default()
+            };
+            if exclude {
+                state_mut.filters.excluded_genera = genera.clone();
+                state_mut.filters.excluded_facets = facet_map.clone();
             } else {
-                0
+                state_mut.filters.genera = genera.clone();
+                state_mut.filters.facets = facet_map.clone();
             }
-        };
+        }
 
         // Update UI from state (will apply filters and any active sort)
         update_ui_from_state(&main_window, &app_state);
 
         // Update active filters text
+        let negation = if exclude { "NOT " } else { "" };
         let mut filter_parts = Vec::new();
         if !genera.is_empty() {
-            filter_parts.push(format!("Genus: {}", genera.join(" OR ")));
+            let scope = if include_descendants {
+                " (and descendants)"
+            } else {
+                ""
+            };
+            filter_parts.push(format!(
+                "{}Genus: {}{}",
+                negation,
+                genera.join(" OR "),
+                scope
+            ));
         }
         for (facet_name, values) in &facet_map {
-            filter_parts.push(format!("{}: {}", facet_name, values.join(" OR ")));
+            filter_parts.push(format!(
+                "{}{}: {}",
+                negation,
+                facet_name,
+                describe_facet_predicates(values)
+            ));
+        }
+        for (field_name, values) in &field_map {
+            filter_parts.push(format!(
+                "{}: {}",
+                field_name,
+                describe_facet_predicates(values)
+            ));
         }
         let filters_text = if filter_parts.is_empty() {
             String::new()
@@ -97,14 +220,19 @@ fn register_apply_filters(window: &MainWindow, app_state: &Rc<RefCell<AppState>>
         };
         main_window.set_active_filters_text(SharedString::from(filters_text));
 
-        set_status(
-            &main_window,
-            format!("Filters applied: {} items match", filtered_count),
-            StatusLevel::Info,
-        );
+        // The status bar's item count tells how many match
+        set_status(&main_window, tr!("Filters applied"), StatusLevel::Info);
     });
 }
 
+/// How filter values compare, from the panel's toggles
+fn filter_options(window: &MainWindow) -> FilterOptions {
+    FilterOptions {
+        case_insensitive: window.get_filter_ignore_case(),
+        strip_diacritics: window.get_filter_ignore_accents(),
+    }
+}
+
 /// Register clear filters handler
 fn register_clear_filters(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
     let main_window_weak = window.as_weak();
@@ -118,15 +246,105 @@ fn register_clear_filters(window: &MainWindow, app_state: &Rc<RefCell<AppState>>
         main_window.set_facet_filter_text(SharedString::from(""));
         main_window.set_active_filters_text(SharedString::from(""));
 
-        // Clear state filters
+        // Clear state filters (the descendants, deprecated, case and accent toggles are modes,
+        // not filters, so they stay)
         app_state.borrow_mut().filters = Filters {
-            genera: Vec::new(),
-            facets: std::collections::HashMap::new(),
+            include_descendants: main_window.get_genus_include_descendants(),
+            include_deprecated: main_window.get_show_deprecated(),
+            options: filter_options(&main_window),
+            ..Filters::default()
         };
 
         // Reset UI to show all items
         update_ui_from_state(&main_window, &app_state);
 
-        set_status(&main_window, "Filters cleared", StatusLevel::Info);
+        set_status(&main_window, tr!("Filters cleared"), StatusLevel::Info);
+    });
+}
+
+/// Register detail panel source click handler
+/// Lists the items imported from the same file, replacing the facet filter
+fn register_filter_by_source(window: &MainWindow) {
+    let main_window_weak = window.as_weak();
+
+    window.on_filter_by_source(move |origin| {
+        let main_window = main_window_weak.unwrap();
+        let filter = format!("{}.origin_file={}", SOURCE_FIELD, origin);
+        main_window.set_facet_filter_text(SharedString::from(filter));
+        main_window.set_filter_exclude(false);
+        main_window.invoke_apply_filters();
+    });
+}
+
+/// Register hierarchy node filter handler, used by the tree's context menu and the map
+/// Filters the items by the node and everything under it, and shows them
+fn register_filter_by_node(window: &MainWindow) {
+    let main_window_weak = window.as_weak();
+
+    window.on_filter_by_node(move |species| {
+        let main_window = main_window_weak.unwrap();
+
+        // Replace the genus filter, keeping any facet filter
+        main_window.set_genus_filter_text(species);
+        main_window.set_genus_include_descendants(true);
+        main_window.set_filter_exclude(false);
+        main_window.invoke_apply_filters();
+        main_window.set_main_tab(0);
+    });
+}
+
+/// Register item list context menu -> Filter by Its Node handler
+/// Filters by the node the selected item is classified under
+fn register_filter_by_item_node(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_filter_by_item_node(move || {
+        let main_window = main_window_weak.unwrap();
+
+        let species = {
+            let state = app_state.borrow();
+            state
+                .item_at_row(main_window.get_selected_item_index())
+                .and_then(|item| item.classical_path.last().cloned())
+        };
+        let Some(species) = species else {
+            return;
+        };
+        main_window.invoke_filter_by_node(SharedString::from(species));
+    });
+}
+
+/// Register statistics facet choice handler
+fn register_statistics_facet_changed(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_statistics_facet_changed(move || {
+        let main_window = main_window_weak.unwrap();
+        update_statistics(&main_window, &app_state.borrow());
+    });
+}
+
+/// Register statistics chart click handler
+/// Filters the items by the clicked value of the charted facet and shows them
+fn register_statistics_value_clicked(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_statistics_value_clicked(move |value| {
+        let main_window = main_window_weak.unwrap();
+
+        let index = main_window.get_statistics_facet_index();
+        let facet_names = app_state.borrow().facet_names();
+        let Some(facet) = usize::try_from(index).ok().and_then(|i| facet_names.get(i)) else {
+            return;
+        };
+
+        // Replace the facet filter, keeping any genus filter
+        main_window.set_facet_filter_text(SharedString::from(format!("{}={}", facet, value)));
+        main_window.set_filter_exclude(false);
+        main_window.invoke_apply_filters();
+        main_window.set_main_tab(0);
     });
 }
@@ -0,0 +1,113 @@
+use slint::{ComponentHandle, SharedString, VecModel};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::i18n::tr;
+use crate::state::AppState;
+use crate::ui::{create_change_entries, set_status, show_error, update_history};
+use crate::{MainWindow, StatusLevel};
+
+/// Register all Git handlers
+pub fn register_git_handlers(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    register_git_commit_start(window, app_state);
+    register_git_commit(window, app_state);
+    register_history_selected(window, app_state);
+}
+
+/// Register Git -> Commit handler (shows the changes since the last commit)
+fn register_git_commit_start(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_git_commit_start(move || {
+        let main_window = main_window_weak.unwrap();
+
+        if app_state.borrow().dirty {
+            set_status(
+                &main_window,
+                tr!("Save the file before committing"),
+                StatusLevel::Warning,
+            );
+            return;
+        }
+
+        let changes_result = app_state.borrow().uncommitted_changes();
+        match changes_result {
+            Ok(changes) => {
+                let entries = create_change_entries(&changes);
+                main_window.set_commit_changes(Rc::new(VecModel::from(entries)).into());
+                main_window.set_commit_message(SharedString::from(""));
+                main_window.set_show_commit_dialog(true);
+            }
+            Err(e) => show_error(
+                &main_window,
+                tr!("Git Error"),
+                tr!("Could not compare the file with its last commit"),
+                e.to_string(),
+            ),
+        }
+    });
+}
+
+/// Register commit dialog -> Commit handler
+fn register_git_commit(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_git_commit(move |message| {
+        let main_window = main_window_weak.unwrap();
+        let message = message.trim().to_string();
+
+        if message.is_empty() {
+            set_status(
+                &main_window,
+                tr!("Enter a commit message"),
+                StatusLevel::Warning,
+            );
+            return;
+        }
+
+        let commit_result = app_state.borrow_mut().commit(&message);
+        match commit_result {
+            Ok(id) => {
+                main_window.set_show_commit_dialog(false);
+                update_history(&main_window, &app_state.borrow());
+                set_status(
+                    &main_window,
+                    tr!("Committed {}: {}", id, message),
+                    StatusLevel::Success,
+                );
+            }
+            Err(e) => show_error(
+                &main_window,
+                tr!("Git Error"),
+                tr!("Could not commit"),
+                e.to_string(),
+            ),
+        }
+    });
+}
+
+/// Register History tab commit selection handler (loads that version read-only)
+fn register_history_selected(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_history_selected(move |index| {
+        let main_window = main_window_weak.unwrap();
+        if index < 0 {
+            return;
+        }
+
+        let show_result = app_state.borrow_mut().show_revision(index as usize);
+        match show_result {
+            Ok(()) => update_history(&main_window, &app_state.borrow()),
+            Err(e) => show_error(
+                &main_window,
+                tr!("Git Error"),
+                tr!("Could not load this version of the file"),
+                e.to_string(),
+            ),
+        }
+    });
+}
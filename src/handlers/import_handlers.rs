@@ -0,0 +1,121 @@
+use slint::ComponentHandle;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::i18n::tr;
+use crate::operations::FileOperations;
+use crate::state::AppState;
+use crate::ui::{
+    import_target_at, refresh_ui_after_state_change, set_status, update_import_dialog,
+};
+use crate::{MainWindow, StatusLevel};
+
+/// Register all item import handlers
+pub fn register_import_handlers(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    register_import_items(window, app_state);
+    register_import_target_changed(window, app_state);
+    register_import_commit(window, app_state);
+    register_import_cancel(window, app_state);
+}
+
+/// Register File -> Import Items handler
+fn register_import_items(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_import_items(move || {
+        let main_window = main_window_weak.unwrap();
+
+        if app_state.borrow().data.is_none() {
+            set_status(
+                &main_window,
+                tr!("No taxonomy loaded"),
+                StatusLevel::Warning,
+            );
+            return;
+        }
+
+        let app_state = app_state.clone();
+        slint::spawn_local(async move {
+            let ops = FileOperations::new(&app_state, &main_window);
+            ops.import_items().await;
+        })
+        .unwrap();
+    });
+}
+
+/// Register import column target change handler (re-checks every row)
+fn register_import_target_changed(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_import_target_changed(move |column, target| {
+        let main_window = main_window_weak.unwrap();
+        let mut state = app_state.borrow_mut();
+        let facet_names = state.facet_names();
+
+        let Some(import) = state.pending_import.as_mut() else {
+            return;
+        };
+        let column = column as usize;
+        if let Some(name) = import.table.columns.get(column) {
+            import.targets[column] = import_target_at(target as usize, name, &facet_names);
+        }
+
+        update_import_dialog(&main_window, &state);
+    });
+}
+
+/// Register import commit handler
+/// Items with issues are only added when asked for; validation then lists their problems
+fn register_import_commit(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_import_commit(move |include_invalid| {
+        let main_window = main_window_weak.unwrap();
+        let result = app_state.borrow_mut().commit_item_import(include_invalid);
+        main_window.set_show_import_items_dialog(false);
+
+        match result {
+            Ok((0, _)) => set_status(&main_window, tr!("No items imported"), StatusLevel::Warning),
+            Ok((added, 0)) => refresh_ui_after_state_change(
+                &main_window,
+                &app_state,
+                &tr!("Imported {} item(s)", added),
+                StatusLevel::Success,
+            ),
+            Ok((added, flagged)) => {
+                refresh_ui_after_state_change(
+                    &main_window,
+                    &app_state,
+                    &tr!(
+                        "Imported {} item(s); {} with issues are listed on the Dashboard",
+                        added,
+                        flagged
+                    ),
+                    StatusLevel::Warning,
+                );
+                main_window.set_main_tab(1);
+            }
+            Err(e) => set_status(
+                &main_window,
+                tr!("Import failed: {}", e),
+                StatusLevel::Danger,
+            ),
+        }
+    });
+}
+
+/// Register import cancel handler
+fn register_import_cancel(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_import_cancel(move || {
+        let main_window = main_window_weak.unwrap();
+        app_state.borrow_mut().pending_import = None;
+        main_window.set_show_import_items_dialog(false);
+        set_status(&main_window, tr!("Import cancelled"), StatusLevel::Info);
+    });
+}
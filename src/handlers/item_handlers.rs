@@ -1,16 +1,33 @@
-use slint::{ComponentHandle, SharedString, VecModel};
+use slint::{ComponentHandle, Model, ModelRc, SharedString, Timer, TimerMode, VecModel};
 use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
 use std::rc::Rc;
-use taxstud_core::Item;
+use std::time::Duration;
+use taxstud_core::{
+    is_uri, item_links, similar_items, suggest_facets, suggest_path, Item, SimilarityWeights,
+};
 
-use crate::operations::{collect_facets, validate_item_input};
+use crate::i18n::tr;
+use crate::operations::{
+    apply_path_segment, check_item_form, collect_facets, collect_item_fields,
+    mark_constraint_violations, mark_input_issues, parse_classification_path,
+    suggest_path_segments, validate_item_input, FormIssues,
+};
 use crate::state::AppState;
-use crate::ui::{create_facet_inputs, format_facets, refresh_ui_after_state_change, set_status};
-use crate::{MainWindow, StatusLevel};
+use crate::ui::{
+    clear_selected_item, create_attachment_entries, create_breadcrumbs, create_dirty_fields,
+    create_facet_inputs, create_field_inputs, create_link_entries, create_similar_entries,
+    format_facets, format_item_fields, format_provenance, refresh_ui_after_state_change,
+    set_status, show_item,
+};
+use crate::{DirtyField, FacetInput, FormCheck, MainWindow, StatusLevel};
 
 /// Register all item CRUD handlers
 pub fn register_item_handlers(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
     register_item_selected(window, app_state);
+    register_similar_item_clicked(window, app_state);
     register_start_edit(window, app_state);
     register_save_edit(window, app_state);
     register_cancel_edit(window);
@@ -18,8 +35,24 @@ pub fn register_item_handlers(window: &MainWindow, app_state: &Rc<RefCell<AppSta
     register_save_new_item(window, app_state);
     register_cancel_create_item(window);
     register_delete_item(window, app_state);
+    register_duplicate_item(window, app_state);
+    register_toggle_deprecated(window, app_state);
+    register_edit_path_edited(window, app_state);
+    register_create_path_edited(window, app_state);
+    register_pick_edit_path_segment(window, app_state);
+    register_pick_create_path_segment(window, app_state);
+    register_create_name_edited(window, app_state);
+    register_pick_create_path(window, app_state);
+    register_save_as_template(window, app_state);
+    register_apply_item_template(window, app_state);
+    register_open_link(window);
+    register_form_changed(window, app_state);
+    register_discard_edit_field(window, app_state);
 }
 
+/// Most similar items listed in the detail panel
+const MAX_SIMILAR_ITEMS: usize = 5;
+
 /// Register item selection handler
 fn register_item_selected(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
     let main_window_weak = window.as_weak();
@@ -29,17 +62,83 @@ fn register_item_selected(window: &MainWindow, app_state: &Rc<RefCell<AppState>>
         let main_window = main_window_weak.unwrap();
         let state_borrow = app_state.borrow();
 
-        // Use displayed_items which reflects the current sort/filter state
-        if index >= 0 && (index as usize) < state_borrow.displayed_items.len() {
-            let item = &state_borrow.displayed_items[index as usize];
-
+        // List rows map to displayed_items, which reflects the current sort/filter state
+        if let Some(item) = state_borrow.item_at_row(index) {
             // Update selected item properties
+            // The name stays canonical (edits look the item up by it); the label is translated
+            let lang = state_borrow.display_lang.as_deref();
             main_window.set_selected_item_name(SharedString::from(&item.name));
-            main_window.set_selected_item_path(SharedString::from(item.classical_path.join(" → ")));
+            main_window.set_selected_item_label(SharedString::from(item.display_name(lang)));
+            main_window.set_selected_item_deprecated(item.is_deprecated());
+            let breadcrumbs = create_breadcrumbs(
+                &item.classical_path,
+                state_borrow.get_classical_hierarchy(),
+                lang,
+            );
+            main_window.set_selected_item_breadcrumbs(Rc::new(VecModel::from(breadcrumbs)).into());
 
             // Format facets
-            let facets_text = format_facets(&item.facets);
+            let facets_text =
+                format_facets(&item.facets, state_borrow.get_faceted_dimensions(), lang);
             main_window.set_selected_item_facets(SharedString::from(facets_text));
+            let fields_text = state_borrow
+                .schema
+                .as_ref()
+                .map(|schema| format_item_fields(&schema.item_fields, &item.extra))
+                .unwrap_or_default();
+            main_window.set_selected_item_fields(SharedString::from(fields_text));
+            let data_dir = state_borrow
+                .current_file
+                .as_deref()
+                .and_then(|path| path.parent());
+            let attachments = create_attachment_entries(&item.attachments, data_dir);
+            main_window.set_selected_item_attachments(Rc::new(VecModel::from(attachments)).into());
+            let links = state_borrow
+                .schema
+                .as_ref()
+                .map(|schema| item_links(item, schema, lang))
+                .unwrap_or_default();
+            main_window.set_selected_item_links(
+                Rc::new(VecModel::from(create_link_entries(links))).into(),
+            );
+
+            let source = item.source();
+            let source_text = source.as_ref().map(format_provenance).unwrap_or_default();
+            main_window.set_selected_item_source(SharedString::from(source_text));
+            let origin = source.map(|source| source.origin_file).unwrap_or_default();
+            main_window.set_selected_item_origin(SharedString::from(origin));
+
+            // Similar items, from all items regardless of filters
+            let all_items = state_borrow
+                .get_items()
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let mut similar = similar_items(item, all_items, &SimilarityWeights::default());
+            similar.truncate(MAX_SIMILAR_ITEMS);
+            let entries =
+                create_similar_entries(&similar, state_borrow.get_faceted_dimensions(), lang);
+            main_window.set_selected_item_similar(Rc::new(VecModel::from(entries)).into());
+        } else {
+            // A group header has no details
+            clear_selected_item(&main_window);
+        }
+    });
+}
+
+/// Register similar item click handler (selects the item if the list shows it)
+fn register_similar_item_clicked(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_similar_item_clicked(move |name| {
+        let main_window = main_window_weak.unwrap();
+
+        if !show_item(&main_window, &app_state, name.as_str()) {
+            set_status(
+                &main_window,
+                tr!("'{}' is hidden by the current filters", name),
+                StatusLevel::Info,
+            );
         }
     });
 }
@@ -56,22 +155,32 @@ fn register_start_edit(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
         // Get the currently selected item from displayed_items
         if let Some(ref schema) = state_borrow.schema {
             let selected_idx = main_window.get_selected_item_index();
-            if selected_idx >= 0 && (selected_idx as usize) < state_borrow.displayed_items.len() {
-                let item = &state_borrow.displayed_items[selected_idx as usize];
-
+            if let Some(item) = state_borrow.item_at_row(selected_idx) {
                 // Populate edit fields
                 main_window.set_edit_item_name(SharedString::from(&item.name));
-                main_window.set_edit_item_path(SharedString::from(item.classical_path.join(", ")));
+                let path_text = item.classical_path.join(", ");
+                main_window.set_edit_path_suggestions(path_suggestions(&state_borrow, &path_text));
+                main_window.set_edit_item_path(SharedString::from(path_text));
 
                 // Populate facet inputs based on schema dimensions
-                let facet_inputs = create_facet_inputs(&schema.faceted_dimensions, &item.facets);
-                let facet_inputs_model = Rc::new(VecModel::from(facet_inputs));
-                main_window.set_edit_facet_inputs(facet_inputs_model.into());
+                let facet_inputs = create_facet_inputs(
+                    &schema.faceted_dimensions,
+                    &item.facets,
+                    state_borrow.display_lang.as_deref(),
+                );
+                let facet_inputs_model: ModelRc<FacetInput> =
+                    Rc::new(VecModel::from(facet_inputs)).into();
+                mark_constraint_violations(&facet_inputs_model, schema);
+                main_window.set_edit_facet_inputs(facet_inputs_model);
+                let field_inputs = create_field_inputs(&schema.item_fields, &item.extra);
+                main_window.set_edit_field_inputs(Rc::new(VecModel::from(field_inputs)).into());
 
                 // Enter edit mode
                 main_window.set_is_editing(true);
+                main_window.set_edit_form_check(FormCheck::default());
+                main_window.set_edit_dirty_fields(ModelRc::default());
                 main_window.set_validation_error(SharedString::from(""));
-                set_status(&main_window, "Editing item...", StatusLevel::Info);
+                set_status(&main_window, tr!("Editing item..."), StatusLevel::Info);
             }
         }
     });
@@ -92,30 +201,85 @@ fn register_save_edit(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
         let new_name = main_window.get_edit_item_name().to_string();
         let new_path = main_window.get_edit_item_path().to_string();
         let facet_inputs = main_window.get_edit_facet_inputs();
+        let field_inputs = main_window.get_edit_field_inputs();
 
-        // Get the classical hierarchy from the schema
+        // Get the schema for path and facet validation
         let state_borrow = app_state.borrow();
-        let hierarchy = match state_borrow.schema.as_ref() {
-            Some(schema) => &schema.classical_hierarchy,
+        let schema = match state_borrow.schema.as_ref() {
+            Some(schema) => schema,
             None => {
-                main_window.set_validation_error(SharedString::from("No schema loaded"));
+                main_window.set_validation_error(SharedString::from(tr!("No schema loaded")));
                 return;
             }
         };
 
+        // Mark every input with a problem; nothing is saved until they all pass
+        let items = state_borrow
+            .get_items()
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let issues = check_item_form(
+            &new_name,
+            &new_path,
+            &facet_inputs,
+            &field_inputs,
+            schema,
+            items,
+            Some(original_name.as_str()),
+        );
+        show_form_issues(&main_window, ItemForm::Edit, &issues);
+        if !issues.is_empty() {
+            main_window.set_validation_error(SharedString::from(tr!(
+                "{} input(s) need fixing before saving",
+                issues.count()
+            )));
+            return;
+        }
+        main_window.set_validation_error(SharedString::from(""));
+
         // Validate inputs using validation module
-        let (validated_name, classical_path) =
-            match validate_item_input(&new_name, &new_path, hierarchy) {
-                Ok(result) => result,
-                Err(e) => {
-                    main_window.set_validation_error(SharedString::from(e.message));
-                    return;
-                }
-            };
+        let (validated_name, classical_path) = match validate_item_input(
+            &new_name,
+            &new_path,
+            &schema.classical_hierarchy,
+            items,
+            Some(original_name.as_str()),
+        ) {
+            Ok(result) => result,
+            Err(e) => {
+                main_window.set_validation_error(SharedString::from(e.message));
+                return;
+            }
+        };
+
+        // Collect typed facet values from inputs using validation module
+        let facets_map = match collect_facets(&facet_inputs, &schema.faceted_dimensions) {
+            Ok(facets) => facets,
+            Err(e) => {
+                main_window.set_validation_error(SharedString::from(e.message));
+                return;
+            }
+        };
+        let fields_map = match collect_item_fields(&field_inputs, &schema.item_fields) {
+            Ok(fields) => fields,
+            Err(e) => {
+                main_window.set_validation_error(SharedString::from(e.message));
+                return;
+            }
+        };
+        let field_names: Vec<String> = schema.item_fields.keys().cloned().collect();
+        let changed: Vec<String> = edit_dirty_fields(&main_window, &state_borrow)
+            .iter()
+            .map(|dirty| dirty.label.to_string())
+            .collect();
         drop(state_borrow);
 
-        // Collect facets from inputs using validation module
-        let facets_map = collect_facets(&facet_inputs);
+        if changed.is_empty() {
+            main_window.set_is_editing(false);
+            main_window.set_validation_error(SharedString::from(""));
+            set_status(&main_window, tr!("No changes to save"), StatusLevel::Info);
+            return;
+        }
 
         // Find and update the item in the data by original name
         let mut state_mut = app_state.borrow_mut();
@@ -125,9 +289,17 @@ fn register_save_edit(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
                 item.name = validated_name.clone();
                 item.classical_path = classical_path;
                 item.facets = facets_map;
+                // Cleared fields are removed; fields the schema doesn't declare are kept
+                item.extra.retain(|key, _| !field_names.contains(key));
+                item.extra.extend(fields_map);
 
                 // Mark as dirty
-                state_mut.mark_dirty();
+                let change = if original_name == validated_name {
+                    tr!("Edited '{}'", validated_name)
+                } else {
+                    tr!("Renamed '{}' to '{}'", original_name, validated_name)
+                };
+                state_mut.record_change(change);
 
                 // Exit edit mode
                 drop(state_mut);
@@ -137,7 +309,7 @@ fn register_save_edit(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
                 refresh_ui_after_state_change(
                     &main_window,
                     &app_state,
-                    "Item saved successfully",
+                    &tr!("Item saved (changed: {})", changed.join(", ")),
                     StatusLevel::Success,
                 );
             }
@@ -155,7 +327,7 @@ fn register_cancel_edit(window: &MainWindow) {
         // Exit edit mode without saving
         main_window.set_is_editing(false);
         main_window.set_validation_error(SharedString::from(""));
-        set_status(&main_window, "Edit cancelled", StatusLevel::Info);
+        set_status(&main_window, tr!("Edit cancelled"), StatusLevel::Info);
     });
 }
 
@@ -171,19 +343,35 @@ fn register_start_create_item(window: &MainWindow, app_state: &Rc<RefCell<AppSta
         // Clear form fields
         main_window.set_new_item_name(SharedString::from(""));
         main_window.set_new_item_path(SharedString::from(""));
+        main_window.set_create_path_suggestions(path_suggestions(&state_borrow, ""));
+        main_window.set_create_classify_suggestions(ModelRc::default());
         main_window.set_validation_error(SharedString::from(""));
+        main_window.set_create_form_check(FormCheck::default());
+        let template_names: Vec<SharedString> = state_borrow
+            .item_templates()
+            .iter()
+            .map(|template| SharedString::from(&template.name))
+            .collect();
+        main_window.set_item_templates(Rc::new(VecModel::from(template_names)).into());
 
-        // Populate facet inputs based on schema dimensions
+        // Populate facet inputs based on schema dimensions, pre-filled with their defaults
         if let Some(ref schema) = state_borrow.schema {
-            let empty_facets = std::collections::HashMap::new();
-            let facet_inputs = create_facet_inputs(&schema.faceted_dimensions, &empty_facets);
-            let facet_inputs_model = Rc::new(VecModel::from(facet_inputs));
-            main_window.set_create_facet_inputs(facet_inputs_model.into());
+            let facet_inputs = create_facet_inputs(
+                &schema.faceted_dimensions,
+                &schema.default_facets(),
+                state_borrow.display_lang.as_deref(),
+            );
+            let facet_inputs_model: ModelRc<FacetInput> =
+                Rc::new(VecModel::from(facet_inputs)).into();
+            mark_constraint_violations(&facet_inputs_model, schema);
+            main_window.set_create_facet_inputs(facet_inputs_model);
+            let field_inputs = create_field_inputs(&schema.item_fields, &HashMap::new());
+            main_window.set_create_field_inputs(Rc::new(VecModel::from(field_inputs)).into());
         }
 
         // Enter create mode
         main_window.set_is_creating(true);
-        set_status(&main_window, "Creating new item...", StatusLevel::Info);
+        set_status(&main_window, tr!("Creating new item..."), StatusLevel::Info);
     });
 }
 
@@ -199,37 +387,83 @@ fn register_save_new_item(window: &MainWindow, app_state: &Rc<RefCell<AppState>>
         let new_name = main_window.get_new_item_name().to_string();
         let new_path = main_window.get_new_item_path().to_string();
         let facet_inputs = main_window.get_create_facet_inputs();
+        let field_inputs = main_window.get_create_field_inputs();
 
-        // Get the classical hierarchy from the schema
+        // Get the schema for path and facet validation
         let state_borrow = app_state.borrow();
-        let hierarchy = match state_borrow.schema.as_ref() {
-            Some(schema) => &schema.classical_hierarchy,
+        let schema = match state_borrow.schema.as_ref() {
+            Some(schema) => schema,
             None => {
-                main_window.set_validation_error(SharedString::from("No schema loaded"));
+                main_window.set_validation_error(SharedString::from(tr!("No schema loaded")));
                 return;
             }
         };
 
+        // Mark every input with a problem; nothing is saved until they all pass
+        let items = state_borrow
+            .get_items()
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let issues = check_item_form(
+            &new_name,
+            &new_path,
+            &facet_inputs,
+            &field_inputs,
+            schema,
+            items,
+            None,
+        );
+        show_form_issues(&main_window, ItemForm::Create, &issues);
+        if !issues.is_empty() {
+            main_window.set_validation_error(SharedString::from(tr!(
+                "{} input(s) need fixing before saving",
+                issues.count()
+            )));
+            return;
+        }
+        main_window.set_validation_error(SharedString::from(""));
+
         // Validate inputs using validation module
-        let (validated_name, classical_path) =
-            match validate_item_input(&new_name, &new_path, hierarchy) {
-                Ok(result) => result,
-                Err(e) => {
-                    main_window.set_validation_error(SharedString::from(e.message));
-                    return;
-                }
-            };
-        drop(state_borrow);
+        let (validated_name, classical_path) = match validate_item_input(
+            &new_name,
+            &new_path,
+            &schema.classical_hierarchy,
+            items,
+            None,
+        ) {
+            Ok(result) => result,
+            Err(e) => {
+                main_window.set_validation_error(SharedString::from(e.message));
+                return;
+            }
+        };
 
-        // Collect facets from inputs using validation module
-        let facets_map = collect_facets(&facet_inputs);
+        // Collect typed facet values from inputs using validation module
+        let facets_map = match collect_facets(&facet_inputs, &schema.faceted_dimensions) {
+            Ok(facets) => facets,
+            Err(e) => {
+                main_window.set_validation_error(SharedString::from(e.message));
+                return;
+            }
+        };
+        let fields_map = match collect_item_fields(&field_inputs, &schema.item_fields) {
+            Ok(fields) => fields,
+            Err(e) => {
+                main_window.set_validation_error(SharedString::from(e.message));
+                return;
+            }
+        };
+        drop(state_borrow);
 
         // Create new item
         let new_item = Item {
             name: validated_name.clone(),
             classical_path,
             facets: facets_map,
-            extra: std::collections::HashMap::new(),
+            labels: HashMap::new(),
+            relations: Vec::new(),
+            attachments: Vec::new(),
+            extra: fields_map,
         };
 
         // Add to data
@@ -238,7 +472,7 @@ fn register_save_new_item(window: &MainWindow, app_state: &Rc<RefCell<AppState>>
             data.items.push(new_item);
 
             // Mark as dirty
-            state_mut.mark_dirty();
+            state_mut.record_change(tr!("Created '{}'", validated_name));
 
             // Exit create mode
             drop(state_mut);
@@ -248,7 +482,7 @@ fn register_save_new_item(window: &MainWindow, app_state: &Rc<RefCell<AppState>>
             refresh_ui_after_state_change(
                 &main_window,
                 &app_state,
-                &format!("Item '{}' created successfully", validated_name),
+                &tr!("Item '{}' created successfully", validated_name),
                 StatusLevel::Success,
             );
         }
@@ -265,7 +499,7 @@ fn register_cancel_create_item(window: &MainWindow) {
         // Exit create mode without saving
         main_window.set_is_creating(false);
         main_window.set_validation_error(SharedString::from(""));
-        set_status(&main_window, "Create cancelled", StatusLevel::Info);
+        set_status(&main_window, tr!("Create cancelled"), StatusLevel::Info);
     });
 }
 
@@ -278,43 +512,513 @@ fn register_delete_item(window: &MainWindow, app_state: &Rc<RefCell<AppState>>)
         let main_window = main_window_weak.unwrap();
 
         let selected_idx = main_window.get_selected_item_index();
-        if selected_idx < 0 {
-            return;
-        }
 
         // Get item name from displayed_items
-        let item_name = {
-            let state_borrow = app_state.borrow();
-            if (selected_idx as usize) < state_borrow.displayed_items.len() {
-                state_borrow.displayed_items[selected_idx as usize]
-                    .name
-                    .clone()
-            } else {
-                return;
-            }
+        let item_name = match app_state.borrow().item_at_row(selected_idx) {
+            Some(item) => item.name.clone(),
+            None => return,
         };
 
-        // Find and delete the item from data by name
-        let mut state_mut = app_state.borrow_mut();
-        if let Some(ref mut data) = state_mut.data {
-            // Find the item position by name
-            if let Some(pos) = data.items.iter().position(|i| i.name == item_name) {
-                data.items.remove(pos);
+        // Delete the item from data by name, keeping it in the trash
+        let delete_result = app_state.borrow_mut().delete_item(&item_name);
+        if delete_result.is_ok() {
+            refresh_ui_after_state_change(
+                &main_window,
+                &app_state,
+                &tr!(
+                    "Item '{}' deleted (restore it from the Trash tab)",
+                    item_name
+                ),
+                StatusLevel::Success,
+            );
+        }
+    });
+}
 
-                // Mark as dirty
-                state_mut.mark_dirty();
+/// Register item list context menu -> Duplicate handler
+/// The copy is added after the selected item under the next free name
+fn register_duplicate_item(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
 
-                // Exit and update
-                drop(state_mut);
+    window.on_duplicate_item(move || {
+        let main_window = main_window_weak.unwrap();
 
-                // Refresh UI and show success message
+        let selected_idx = main_window.get_selected_item_index();
+        let item_name = match app_state.borrow().item_at_row(selected_idx) {
+            Some(item) => item.name.clone(),
+            None => return,
+        };
+
+        let result = app_state.borrow_mut().duplicate_item(&item_name);
+        match result {
+            Ok(copy_name) => refresh_ui_after_state_change(
+                &main_window,
+                &app_state,
+                &tr!("Item '{}' duplicated as '{}'", item_name, copy_name),
+                StatusLevel::Success,
+            ),
+            Err(e) => set_status(
+                &main_window,
+                tr!("Could not duplicate item: {}", e),
+                StatusLevel::Danger,
+            ),
+        }
+    });
+}
+
+/// Register Deprecate/Restore handler for the selected item
+/// A deprecated item stays in the file; the list hides it unless deprecated items are shown
+fn register_toggle_deprecated(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_toggle_deprecated(move || {
+        let main_window = main_window_weak.unwrap();
+        let name = main_window.get_selected_item_name().to_string();
+        let deprecate = !main_window.get_selected_item_deprecated();
+
+        let result = app_state.borrow_mut().set_item_deprecated(&name, deprecate);
+        match result {
+            Ok(()) => {
+                let message = if deprecate {
+                    tr!("Item '{}' deprecated", name)
+                } else {
+                    tr!("Item '{}' restored", name)
+                };
                 refresh_ui_after_state_change(
                     &main_window,
                     &app_state,
-                    &format!("Item '{}' deleted", item_name),
+                    &message,
                     StatusLevel::Success,
                 );
             }
+            Err(e) => set_status(&main_window, e.to_string(), StatusLevel::Danger),
+        }
+    });
+}
+
+/// Register edit path text change handler (refreshes path suggestions)
+fn register_edit_path_edited(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_edit_path_edited(move |text| {
+        let main_window = main_window_weak.unwrap();
+        main_window.set_edit_path_suggestions(path_suggestions(&app_state.borrow(), &text));
+    });
+}
+
+/// Register create path text change handler (refreshes path suggestions)
+fn register_create_path_edited(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_create_path_edited(move |text| {
+        let main_window = main_window_weak.unwrap();
+        let state_borrow = app_state.borrow();
+        main_window.set_create_path_suggestions(path_suggestions(&state_borrow, &text));
+        mark_facet_suggestions(&main_window.get_create_facet_inputs(), &state_borrow, &text);
+    });
+}
+
+/// Register edit path suggestion pick handler
+fn register_pick_edit_path_segment(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_pick_edit_path_segment(move |segment| {
+        let main_window = main_window_weak.unwrap();
+        let state_borrow = app_state.borrow();
+
+        if let Some(hierarchy) = state_borrow.get_classical_hierarchy() {
+            let text = main_window.get_edit_item_path();
+            let path_text = apply_path_segment(&text, &segment, hierarchy);
+            main_window.set_edit_path_suggestions(path_suggestions(&state_borrow, &path_text));
+            main_window.set_edit_item_path(SharedString::from(path_text));
+        }
+    });
+}
+
+/// Register create path suggestion pick handler
+fn register_pick_create_path_segment(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_pick_create_path_segment(move |segment| {
+        let main_window = main_window_weak.unwrap();
+        let state_borrow = app_state.borrow();
+
+        if let Some(hierarchy) = state_borrow.get_classical_hierarchy() {
+            let text = main_window.get_new_item_path();
+            let path_text = apply_path_segment(&text, &segment, hierarchy);
+            main_window.set_create_path_suggestions(path_suggestions(&state_borrow, &path_text));
+            let facet_inputs = main_window.get_create_facet_inputs();
+            mark_facet_suggestions(&facet_inputs, &state_borrow, &path_text);
+            main_window.set_new_item_path(SharedString::from(path_text));
+        }
+    });
+}
+
+/// Most classification paths suggested from a new item's name
+const MAX_CLASSIFY_SUGGESTIONS: usize = 3;
+
+/// Register create name edit handler
+/// Suggests whole classification paths for the name typed so far
+fn register_create_name_edited(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_create_name_edited(move |name| {
+        let main_window = main_window_weak.unwrap();
+        let state_borrow = app_state.borrow();
+
+        let suggestions: Vec<SharedString> = state_borrow
+            .get_classical_hierarchy()
+            .map(|hierarchy| {
+                let items = state_borrow.get_items().map(Vec::as_slice);
+                suggest_path(&name, hierarchy, items.unwrap_or_default())
+            })
+            .unwrap_or_default()
+            .into_iter()
+            .take(MAX_CLASSIFY_SUGGESTIONS)
+            .map(|(path, _)| SharedString::from(path.join(", ")))
+            .collect();
+        main_window.set_create_classify_suggestions(Rc::new(VecModel::from(suggestions)).into());
+    });
+}
+
+/// Register create classification suggestion pick handler
+/// The picked path replaces whatever was typed in the path field
+fn register_pick_create_path(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_pick_create_path(move |path_text| {
+        let main_window = main_window_weak.unwrap();
+        let state_borrow = app_state.borrow();
+
+        main_window.set_create_path_suggestions(path_suggestions(&state_borrow, &path_text));
+        mark_facet_suggestions(
+            &main_window.get_create_facet_inputs(),
+            &state_borrow,
+            &path_text,
+        );
+        main_window.set_new_item_path(path_text);
+    });
+}
+
+/// Register Save as Template handler (template named after the selected item)
+fn register_save_as_template(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_save_as_template(move || {
+        let main_window = main_window_weak.unwrap();
+        let name = main_window.get_selected_item_name().to_string();
+
+        let result = app_state.borrow_mut().save_item_template(&name);
+        match result {
+            Ok(()) => set_status(
+                &main_window,
+                tr!("Saved '{}' as an item template", name),
+                StatusLevel::Success,
+            ),
+            Err(e) => set_status(&main_window, e.to_string(), StatusLevel::Danger),
+        }
+    });
+}
+
+/// Register create form template picker handler
+/// Fills in the template's path, and its facets over the schema defaults; the name is kept
+fn register_apply_item_template(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_apply_item_template(move |index| {
+        let main_window = main_window_weak.unwrap();
+        let state_borrow = app_state.borrow();
+        let Some(template) = state_borrow.item_templates().get(index as usize) else {
+            return;
+        };
+        let Some(ref schema) = state_borrow.schema else {
+            return;
+        };
+
+        let mut facets = schema.default_facets();
+        facets.extend(template.facets.clone());
+        let facet_inputs = create_facet_inputs(
+            &schema.faceted_dimensions,
+            &facets,
+            state_borrow.display_lang.as_deref(),
+        );
+        let facet_inputs_model: ModelRc<FacetInput> = Rc::new(VecModel::from(facet_inputs)).into();
+        mark_constraint_violations(&facet_inputs_model, schema);
+        main_window.set_create_facet_inputs(facet_inputs_model);
+
+        let path_text = template.classical_path.join(", ");
+        main_window.set_create_path_suggestions(path_suggestions(&state_borrow, &path_text));
+        main_window.set_new_item_path(SharedString::from(path_text));
+        set_status(
+            &main_window,
+            tr!("Filled in from template '{}'", template.name),
+            StatusLevel::Info,
+        );
+    });
+}
+
+/// Delay after the last change to an item form before its inputs are checked
+const FORM_CHECK_DELAY: Duration = Duration::from_millis(300);
+
+/// The item form being checked
+#[derive(Clone, Copy)]
+enum ItemForm {
+    Edit,
+    Create,
+}
+
+/// Register edit/create form change handlers
+/// Each change restarts a short timer; the form is checked when it fires, so inputs are
+/// marked while typing without re-checking on every keystroke
+fn register_form_changed(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let timer = Rc::new(Timer::default());
+
+    for form in [ItemForm::Edit, ItemForm::Create] {
+        let main_window_weak = window.as_weak();
+        let app_state = app_state.clone();
+        let timer = timer.clone();
+        let on_changed = move || {
+            let main_window_weak = main_window_weak.clone();
+            let app_state = app_state.clone();
+            timer.start(TimerMode::SingleShot, FORM_CHECK_DELAY, move || {
+                if let Some(main_window) = main_window_weak.upgrade() {
+                    check_form(&main_window, &app_state.borrow(), form);
+                }
+            });
+        };
+        match form {
+            ItemForm::Edit => window.on_edit_form_changed(on_changed),
+            ItemForm::Create => window.on_create_form_changed(on_changed),
+        }
+    }
+}
+
+/// Check a form's current inputs against the schema and mark them
+fn check_form(main_window: &MainWindow, state: &AppState, form: ItemForm) {
+    let Some(ref schema) = state.schema else {
+        return;
+    };
+    let items = state.get_items().map(Vec::as_slice).unwrap_or_default();
+    let issues = match form {
+        ItemForm::Edit => check_item_form(
+            &main_window.get_edit_item_name(),
+            &main_window.get_edit_item_path(),
+            &main_window.get_edit_facet_inputs(),
+            &main_window.get_edit_field_inputs(),
+            schema,
+            items,
+            Some(main_window.get_selected_item_name().as_str()),
+        ),
+        ItemForm::Create => check_item_form(
+            &main_window.get_new_item_name(),
+            &main_window.get_new_item_path(),
+            &main_window.get_create_facet_inputs(),
+            &main_window.get_create_field_inputs(),
+            schema,
+            items,
+            None,
+        ),
+    };
+    show_form_issues(main_window, form, &issues);
+    if issues.is_empty() {
+        main_window.set_validation_error(SharedString::from(""));
+    }
+    if let ItemForm::Edit = form {
+        show_dirty_fields(main_window, state);
+    }
+}
+
+/// Edit form inputs changed from the stored item, listed by form order or label
+fn edit_dirty_fields(main_window: &MainWindow, state: &AppState) -> Vec<DirtyField> {
+    let original_name = main_window.get_selected_item_name();
+    let (Some(schema), Some(item)) = (
+        state.schema.as_ref(),
+        state.get_items().and_then(|items| {
+            items
+                .iter()
+                .find(|item| item.name == original_name.as_str())
+        }),
+    ) else {
+        return Vec::new();
+    };
+
+    let mut dirty = create_dirty_fields(
+        item,
+        &main_window.get_edit_item_name(),
+        &main_window.get_edit_item_path(),
+        &main_window.get_edit_facet_inputs(),
+        &main_window.get_edit_field_inputs(),
+        schema,
+        state.display_lang.as_deref(),
+    );
+    if main_window.get_edit_dirty_fields_sorted() {
+        dirty.sort_by_key(|field| field.label.to_lowercase());
+    }
+    dirty
+}
+
+/// List the edit form's changed inputs
+fn show_dirty_fields(main_window: &MainWindow, state: &AppState) {
+    let dirty = edit_dirty_fields(main_window, state);
+    main_window.set_edit_dirty_fields(Rc::new(VecModel::from(dirty)).into());
+}
+
+/// Register discard handler of the edit form's changed-field list
+/// The input goes back to the stored item's value and the form is checked again
+fn register_discard_edit_field(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_discard_edit_field(move |index| {
+        let main_window = main_window_weak.unwrap();
+        let Some(dirty) = main_window.get_edit_dirty_fields().row_data(index as usize) else {
+            return;
+        };
+
+        let state = app_state.borrow();
+        match dirty.kind.as_str() {
+            "name" => main_window.set_edit_item_name(dirty.old.clone()),
+            "path" => {
+                main_window.set_edit_path_suggestions(path_suggestions(&state, &dirty.old));
+                main_window.set_edit_item_path(dirty.old.clone());
+            }
+            kind => {
+                let inputs = if kind == "facet" {
+                    main_window.get_edit_facet_inputs()
+                } else {
+                    main_window.get_edit_field_inputs()
+                };
+                let row = inputs.iter().position(|input| input.name == dirty.key);
+                if let Some((row, mut input)) =
+                    row.and_then(|row| Some((row, inputs.row_data(row)?)))
+                {
+                    input.value = dirty.old.clone();
+                    inputs.set_row_data(row, input);
+                }
+            }
+        }
+
+        check_form(&main_window, &state, ItemForm::Edit);
+        set_status(
+            &main_window,
+            tr!("Discarded the change to {}", dirty.label),
+            StatusLevel::Info,
+        );
+    });
+}
+
+/// Mark each input of a form with its problem, and the rest as passing
+fn show_form_issues(main_window: &MainWindow, form: ItemForm, issues: &FormIssues) {
+    let check = FormCheck {
+        checked: true,
+        name_error: issues.name.clone().unwrap_or_default().into(),
+        path_error: issues.path.clone().unwrap_or_default().into(),
+        unique_name: issues.unique_name.clone().unwrap_or_default().into(),
+    };
+    match form {
+        ItemForm::Edit => {
+            mark_input_issues(&main_window.get_edit_facet_inputs(), &issues.facets);
+            mark_input_issues(&main_window.get_edit_field_inputs(), &issues.fields);
+            main_window.set_edit_form_check(check);
+        }
+        ItemForm::Create => {
+            mark_input_issues(&main_window.get_create_facet_inputs(), &issues.facets);
+            mark_input_issues(&main_window.get_create_field_inputs(), &issues.fields);
+            main_window.set_create_form_check(check);
+        }
+    }
+}
+
+/// Register link/attachment click handler: URLs open in the browser, local files in
+/// their default application
+fn register_open_link(window: &MainWindow) {
+    let main_window_weak = window.as_weak();
+
+    window.on_open_link(move |target| {
+        let main_window = main_window_weak.unwrap();
+        if !is_uri(&target) && !Path::new(target.as_str()).exists() {
+            set_status(
+                &main_window,
+                tr!("Cannot open '{}': not a URL or an existing file", target),
+                StatusLevel::Warning,
+            );
+            return;
+        }
+
+        match open_with_system(&target) {
+            Ok(()) => set_status(&main_window, tr!("Opened {}", target), StatusLevel::Info),
+            Err(e) => set_status(
+                &main_window,
+                tr!("Cannot open '{}': {}", target, e),
+                StatusLevel::Danger,
+            ),
         }
     });
 }
+
+/// Hand a URL or file to the system's default handler
+fn open_with_system(target: &str) -> std::io::Result<()> {
+    let opener = if cfg!(target_os = "windows") {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    Command::new(opener).arg(target).spawn().map(|_| ())
+}
+
+/// Build the suggestion model for a classification path field
+fn path_suggestions(state: &AppState, path_text: &str) -> ModelRc<SharedString> {
+    let suggestions: Vec<SharedString> = state
+        .get_classical_hierarchy()
+        .map(|hierarchy| suggest_path_segments(path_text, hierarchy))
+        .unwrap_or_default()
+        .into_iter()
+        .map(SharedString::from)
+        .collect();
+
+    Rc::new(VecModel::from(suggestions)).into()
+}
+
+/// Most suggested values shown per facet in the create form
+const MAX_FACET_SUGGESTIONS: usize = 3;
+
+/// Offer each facet input the values common among items classified like the typed path
+/// Values the input already has are not suggested
+fn mark_facet_suggestions(facet_inputs: &ModelRc<FacetInput>, state: &AppState, path_text: &str) {
+    let path = parse_classification_path(path_text).unwrap_or_default();
+    let items = state.get_items().map(Vec::as_slice).unwrap_or_default();
+    let suggestions = suggest_facets(&path, items);
+
+    let Some(model) = facet_inputs.as_any().downcast_ref::<VecModel<FacetInput>>() else {
+        return;
+    };
+    for row in 0..model.row_count() {
+        let mut input = model.row_data(row).unwrap();
+        let current: Vec<&str> = input.value.split(',').map(str::trim).collect();
+        let values: Vec<SharedString> = suggestions
+            .get(input.name.as_str())
+            .map(|scored| {
+                scored
+                    .iter()
+                    .filter(|(value, _)| !current.contains(&value.as_str()))
+                    .take(MAX_FACET_SUGGESTIONS)
+                    .map(|(value, _)| SharedString::from(value.as_str()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        input.suggestions = Rc::new(VecModel::from(values)).into();
+        model.set_row_data(row, input);
+    }
+}
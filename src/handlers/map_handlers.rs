@@ -0,0 +1,119 @@
+use slint::{ComponentHandle, SharedString};
+use std::cell::RefCell;
+use std::rc::Rc;
+use taxstud_core::viz::{self, node_title, sunburst_at, treemap_at};
+use taxstud_core::{sunburst, treemap};
+
+use crate::operations::FileOperations;
+use crate::state::AppState;
+use crate::MainWindow;
+
+/// Register all hierarchy map handlers
+pub fn register_map_handlers(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    register_map_hovered(window, app_state);
+    register_map_clicked(window, app_state);
+    register_map_export(window, app_state);
+    register_export_image(window, app_state);
+}
+
+/// The hierarchy node under the pointer (as fractions of the drawing), with its item count
+fn node_at(state: &AppState, sunburst_shown: bool, x: f32, y: f32) -> Option<(String, usize)> {
+    let hierarchy = &state.schema.as_ref()?.classical_hierarchy;
+    let items = &state.data.as_ref()?.items;
+    let (x, y) = (x as f64, y as f64);
+
+    if sunburst_shown {
+        let arcs = sunburst(hierarchy, items);
+        let arc = sunburst_at(&arcs, 2.0 * x - 1.0, 2.0 * y - 1.0)?;
+        Some((arc.species.clone(), arc.count))
+    } else {
+        let (width, height) = (viz::TREEMAP_WIDTH, viz::TREEMAP_HEIGHT);
+        let rects = treemap(hierarchy, items, width, height);
+        let rect = treemap_at(&rects, x * width, y * height)?;
+        Some((rect.species.clone(), rect.count))
+    }
+}
+
+/// Register map hover handler (shows the node's name and item count)
+fn register_map_hovered(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_map_hovered(move |x, y| {
+        let main_window = main_window_weak.unwrap();
+        let state = app_state.borrow();
+
+        let text = match node_at(&state, main_window.get_map_show_sunburst(), x, y) {
+            Some((species, count)) => {
+                let hierarchy = &state.schema.as_ref().unwrap().classical_hierarchy;
+                node_title(hierarchy, &species, count, state.display_lang.as_deref())
+            }
+            None => String::new(),
+        };
+        main_window.set_map_hover_text(SharedString::from(text));
+    });
+}
+
+/// Register map click handler
+/// Filters the items by the clicked node and everything under it, and shows them
+fn register_map_clicked(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_map_clicked(move |x, y| {
+        let main_window = main_window_weak.unwrap();
+
+        let node = node_at(
+            &app_state.borrow(),
+            main_window.get_map_show_sunburst(),
+            x,
+            y,
+        );
+        if let Some((species, _)) = node {
+            main_window.invoke_filter_by_node(SharedString::from(species));
+        }
+    });
+}
+
+/// Register map export handler
+fn register_map_export(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_map_export(move || {
+        let main_window = main_window_weak.unwrap();
+        let show_sunburst = main_window.get_map_show_sunburst();
+        let app_state = app_state.clone();
+
+        slint::spawn_local(async move {
+            let ops = FileOperations::new(&app_state, &main_window);
+            ops.export_hierarchy_map(show_sunburst).await;
+        })
+        .unwrap();
+    });
+}
+
+/// Register File -> Export Image... handler
+/// Exports the map as shown on the Map tab, and the hierarchy as a tree diagram elsewhere
+fn register_export_image(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_export_image(move || {
+        let main_window = main_window_weak.unwrap();
+        // The Map tab
+        let map_shown = main_window.get_main_tab() == 3;
+        let show_sunburst = main_window.get_map_show_sunburst();
+        let app_state = app_state.clone();
+
+        slint::spawn_local(async move {
+            let ops = FileOperations::new(&app_state, &main_window);
+            if map_shown {
+                ops.export_hierarchy_map(show_sunburst).await;
+            } else {
+                ops.export_hierarchy_diagram().await;
+            }
+        })
+        .unwrap();
+    });
+}
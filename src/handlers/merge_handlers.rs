@@ -0,0 +1,132 @@
+use slint::ComponentHandle;
+use std::cell::RefCell;
+use std::rc::Rc;
+use taxstud_core::Resolution;
+
+use crate::i18n::tr;
+use crate::operations::FileOperations;
+use crate::state::AppState;
+use crate::ui::{set_status, update_merge_dialog};
+use crate::{MainWindow, StatusLevel};
+
+/// Register all merge handlers
+pub fn register_merge_handlers(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    register_merge_changes(window, app_state);
+    register_merge_choice_changed(window, app_state);
+    register_merge_edited(window, app_state);
+    register_merge_apply(window, app_state);
+    register_merge_cancel(window, app_state);
+}
+
+/// Register File -> Merge Changes handler
+fn register_merge_changes(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_merge_changes(move || {
+        let main_window = main_window_weak.unwrap();
+
+        if app_state.borrow().data.is_none() {
+            set_status(
+                &main_window,
+                tr!("No taxonomy loaded"),
+                StatusLevel::Warning,
+            );
+            return;
+        }
+
+        let app_state = app_state.clone();
+        slint::spawn_local(async move {
+            let ops = FileOperations::new(&app_state, &main_window);
+            ops.merge_changes().await;
+        })
+        .unwrap();
+    });
+}
+
+/// Register merge dialog Ours/Theirs/Edit handler
+/// Editing starts from our value
+fn register_merge_choice_changed(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_merge_choice_changed(move |index, choice| {
+        let main_window = main_window_weak.unwrap();
+        let mut state = app_state.borrow_mut();
+        let Some(merge) = state.pending_merge.as_mut() else {
+            return;
+        };
+        let Some(conflict) = merge.result.conflicts.get(index as usize) else {
+            return;
+        };
+
+        merge.resolutions[index as usize] = match choice {
+            0 => Resolution::Ours,
+            1 => Resolution::Theirs,
+            _ => Resolution::Edited(conflict.ours.clone()),
+        };
+        update_merge_dialog(&main_window, &state);
+    });
+}
+
+/// Register merge dialog edited value handler
+/// The dialog isn't refreshed while typing; text that doesn't parse keeps the last value
+fn register_merge_edited(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_merge_edited(move |index, text| {
+        let main_window = main_window_weak.unwrap();
+        let mut state = app_state.borrow_mut();
+        let Some(merge) = state.pending_merge.as_mut() else {
+            return;
+        };
+        let Some(conflict) = merge.result.conflicts.get(index as usize) else {
+            return;
+        };
+
+        match conflict.field.value_from_text(&text) {
+            Ok(value) => merge.resolutions[index as usize] = Resolution::Edited(value),
+            Err(e) => set_status(&main_window, e, StatusLevel::Warning),
+        }
+    });
+}
+
+/// Register merge dialog Apply handler
+fn register_merge_apply(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_merge_apply(move || {
+        let main_window = main_window_weak.unwrap();
+        let ops = FileOperations::new(&app_state, &main_window);
+        ops.apply_merge();
+    });
+}
+
+/// Register merge dialog Cancel handler
+/// A file left in conflict by Git stays so until a merge is applied
+fn register_merge_cancel(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_merge_cancel(move || {
+        let main_window = main_window_weak.unwrap();
+        let in_git = app_state
+            .borrow_mut()
+            .pending_merge
+            .take()
+            .is_some_and(|merge| merge.in_git);
+        main_window.set_show_merge_dialog(false);
+
+        if in_git {
+            set_status(
+                &main_window,
+                tr!("Merge cancelled; the file is still in conflict in Git"),
+                StatusLevel::Warning,
+            );
+        } else {
+            set_status(&main_window, tr!("Merge cancelled"), StatusLevel::Info);
+        }
+    });
+}
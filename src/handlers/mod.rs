@@ -1,11 +1,39 @@
+pub mod clipboard_handlers;
+pub mod compare_handlers;
 pub mod dialog_handlers;
 pub mod file_handlers;
 pub mod filter_handlers;
+pub mod git_handlers;
+pub mod import_handlers;
 pub mod item_handlers;
+pub mod map_handlers;
+pub mod merge_handlers;
+pub mod quick_open_handlers;
+pub mod reclassify_handlers;
+pub mod rename_handlers;
+pub mod schema_handlers;
+pub mod script_handlers;
+pub mod settings_handlers;
+pub mod trash_handlers;
 pub mod ui_handlers;
 
+pub use clipboard_handlers::register_clipboard_handlers;
+pub use compare_handlers::register_compare_handlers;
 pub use dialog_handlers::register_dialog_handlers;
 pub use file_handlers::register_file_handlers;
 pub use filter_handlers::register_filter_handlers;
+pub use git_handlers::register_git_handlers;
+pub use import_handlers::register_import_handlers;
 pub use item_handlers::register_item_handlers;
+pub use map_handlers::register_map_handlers;
+pub use merge_handlers::register_merge_handlers;
+pub use quick_open_handlers::register_quick_open_handlers;
+pub use reclassify_handlers::register_reclassify_handlers;
+pub use rename_handlers::register_rename_handlers;
+pub use schema_handlers::register_schema_handlers;
+pub use script_handlers::register_script_handlers;
+pub use settings_handlers::{
+    apply_accessibility, register_settings_handlers, theme_choice_for, theme_mode_for,
+};
+pub use trash_handlers::register_trash_handlers;
 pub use ui_handlers::register_ui_handlers;
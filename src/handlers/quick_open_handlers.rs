@@ -0,0 +1,103 @@
+use slint::{ComponentHandle, ModelRc, SharedString, VecModel};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+use taxstud_core::fuzzy_find_items;
+
+use crate::i18n::tr;
+use crate::state::{AppState, SimpleConfirmationAction, UiState};
+use crate::ui::{show_item, show_simple_confirmation};
+use crate::{MainWindow, QuickOpenEntry};
+
+/// Most matches quick open lists
+const MAX_QUICK_OPEN_ENTRIES: usize = 50;
+
+/// Register all quick open (Ctrl+P) handlers
+pub fn register_quick_open_handlers(
+    window: &MainWindow,
+    app_state: &Rc<RefCell<AppState>>,
+    ui_state: &Rc<RefCell<UiState>>,
+) {
+    register_open_quick_open(window, app_state);
+    register_quick_open_edited(window, app_state);
+    register_quick_open_pick(window, app_state, ui_state);
+}
+
+/// Update the quick open matches for `query`, from all items, listed or not
+fn update_quick_open(main_window: &MainWindow, state: &AppState, query: &str) {
+    let Some(items) = state.get_items() else {
+        main_window.set_quick_open_entries(ModelRc::default());
+        return;
+    };
+    let lang = state.display_lang.as_deref();
+    let listed: HashSet<&str> = state
+        .displayed_items()
+        .map(|item| item.name.as_str())
+        .collect();
+
+    let entries: Vec<QuickOpenEntry> = fuzzy_find_items(query, items, lang, MAX_QUICK_OPEN_ENTRIES)
+        .into_iter()
+        .map(|item| QuickOpenEntry {
+            name: SharedString::from(&item.name),
+            label: SharedString::from(item.display_name(lang)),
+            detail: SharedString::from(item.classical_path.join(" → ")),
+            hidden: !listed.contains(item.name.as_str()),
+        })
+        .collect();
+    main_window.set_quick_open_entries(Rc::new(VecModel::from(entries)).into());
+}
+
+/// Register Edit -> Go to Item... (Ctrl+P) handler
+fn register_open_quick_open(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_open_quick_open(move || {
+        let main_window = main_window_weak.unwrap();
+        update_quick_open(&main_window, &app_state.borrow(), "");
+        main_window.set_show_quick_open(true);
+    });
+}
+
+/// Register quick open query typing handler
+fn register_quick_open_edited(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_quick_open_edited(move |query| {
+        let main_window = main_window_weak.unwrap();
+        update_quick_open(&main_window, &app_state.borrow(), &query);
+    });
+}
+
+/// Register quick open pick handler
+/// Selects the item; one the filters hide is shown once the user agrees to clear them
+fn register_quick_open_pick(
+    window: &MainWindow,
+    app_state: &Rc<RefCell<AppState>>,
+    ui_state: &Rc<RefCell<UiState>>,
+) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+    let ui_state = ui_state.clone();
+
+    window.on_quick_open_pick(move |name| {
+        let main_window = main_window_weak.unwrap();
+        main_window.set_show_quick_open(false);
+
+        if show_item(&main_window, &app_state, &name) {
+            return;
+        }
+        ui_state.borrow_mut().simple_confirmation_action =
+            Some(SimpleConfirmationAction::ShowItem(name.to_string()));
+        show_simple_confirmation(
+            &main_window,
+            tr!("Item Hidden by Filters"),
+            tr!(
+                "'{}' is hidden by the current filters. Clear the filters to show it?",
+                name
+            ),
+            tr!("Clear Filters"),
+        );
+    });
+}
@@ -0,0 +1,138 @@
+use slint::{ComponentHandle, SharedString};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::i18n::tr;
+use crate::state::AppState;
+use crate::ui::{refresh_ui_after_state_change, set_status};
+use crate::{MainWindow, StatusLevel};
+
+/// Register all reclassify handlers
+pub fn register_reclassify_handlers(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    register_reclassify_items(window, app_state);
+    register_reclassify_node_picked(window, app_state);
+    register_reclassify_changed(window, app_state);
+    register_reclassify_apply(window, app_state);
+    register_reclassify_cancel(window);
+}
+
+/// Register Edit -> Reclassify Items handler
+fn register_reclassify_items(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_reclassify_items(move || {
+        let main_window = main_window_weak.unwrap();
+
+        if app_state.borrow().data.is_none() {
+            set_status(
+                &main_window,
+                tr!("No taxonomy loaded"),
+                StatusLevel::Warning,
+            );
+            return;
+        }
+
+        // A selected item is what gets moved, unless the user picks the whole list
+        let has_selection = app_state
+            .borrow()
+            .item_at_row(main_window.get_selected_item_index())
+            .is_some();
+        main_window.set_reclassify_scope(i32::from(has_selection));
+        main_window.set_reclassify_node(-1);
+        update_reclassify_preview(&main_window, &app_state.borrow());
+        main_window.set_show_reclassify_dialog(true);
+    });
+}
+
+/// Register reclassify dialog node pick handler
+fn register_reclassify_node_picked(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_reclassify_node_picked(move |index| {
+        let main_window = main_window_weak.unwrap();
+        main_window.set_reclassify_node(index);
+        update_reclassify_preview(&main_window, &app_state.borrow());
+    });
+}
+
+/// Register reclassify dialog scope change handler
+fn register_reclassify_changed(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_reclassify_changed(move || {
+        let main_window = main_window_weak.unwrap();
+        update_reclassify_preview(&main_window, &app_state.borrow());
+    });
+}
+
+/// Register reclassify dialog Reclassify handler
+fn register_reclassify_apply(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_reclassify_apply(move || {
+        let main_window = main_window_weak.unwrap();
+        let selected = reclassify_selection(&main_window, &app_state.borrow());
+
+        let node = usize::try_from(main_window.get_reclassify_node())
+            .ok()
+            .and_then(|index| app_state.borrow().node_at(index));
+        let Some(node) = node else {
+            set_status(
+                &main_window,
+                tr!("Pick a node to move to"),
+                StatusLevel::Warning,
+            );
+            return;
+        };
+
+        let reclassify_result = app_state.borrow_mut().reclassify_items(&node, selected);
+        match reclassify_result {
+            Ok(moved) => {
+                main_window.set_show_reclassify_dialog(false);
+                refresh_ui_after_state_change(
+                    &main_window,
+                    &app_state,
+                    &tr!("Moved {} item(s) to '{}'", moved, node),
+                    StatusLevel::Success,
+                );
+            }
+            Err(e) => set_status(&main_window, e.to_string(), StatusLevel::Danger),
+        }
+    });
+}
+
+/// Register reclassify dialog Cancel handler
+fn register_reclassify_cancel(window: &MainWindow) {
+    let main_window_weak = window.as_weak();
+
+    window.on_reclassify_cancel(move || {
+        let main_window = main_window_weak.unwrap();
+        main_window.set_show_reclassify_dialog(false);
+        set_status(&main_window, tr!("Reclassify cancelled"), StatusLevel::Info);
+    });
+}
+
+/// The displayed item the dialog is limited to, if any
+fn reclassify_selection(main_window: &MainWindow, state: &AppState) -> Option<usize> {
+    (main_window.get_reclassify_scope() == 1)
+        .then(|| state.displayed_index(main_window.get_selected_item_index()))
+        .flatten()
+}
+
+/// Show how many items would move, and the path of the picked node
+fn update_reclassify_preview(main_window: &MainWindow, state: &AppState) {
+    let selected = reclassify_selection(main_window, state);
+    main_window.set_reclassify_count(state.batch_targets(selected).len() as i32);
+
+    let path = usize::try_from(main_window.get_reclassify_node())
+        .ok()
+        .and_then(|index| state.node_at(index))
+        .and_then(|node| state.get_classical_hierarchy()?.path_to(&node))
+        .map(|path| path.join(" → "))
+        .unwrap_or_default();
+    main_window.set_reclassify_path(SharedString::from(path));
+}
@@ -0,0 +1,125 @@
+use slint::{ComponentHandle, VecModel};
+use std::cell::RefCell;
+use std::rc::Rc;
+use taxstud_core::RenamePattern;
+
+use crate::i18n::tr;
+use crate::state::AppState;
+use crate::ui::{create_rename_rows, refresh_ui_after_state_change, set_status};
+use crate::{MainWindow, StatusLevel};
+
+/// Register all batch rename handlers
+pub fn register_rename_handlers(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    register_rename_items(window, app_state);
+    register_rename_changed(window, app_state);
+    register_rename_apply(window, app_state);
+    register_rename_cancel(window);
+}
+
+/// Register Edit -> Rename Items handler
+fn register_rename_items(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_rename_items(move || {
+        let main_window = main_window_weak.unwrap();
+
+        if app_state.borrow().data.is_none() {
+            set_status(
+                &main_window,
+                tr!("No taxonomy loaded"),
+                StatusLevel::Warning,
+            );
+            return;
+        }
+
+        // A selected item is what gets renamed, unless the user picks the whole list
+        let has_selection = app_state
+            .borrow()
+            .item_at_row(main_window.get_selected_item_index())
+            .is_some();
+        main_window.set_rename_scope(i32::from(has_selection));
+        update_rename_preview(&main_window, &app_state.borrow());
+        main_window.set_show_rename_dialog(true);
+    });
+}
+
+/// Register rename dialog input handler (mode, scope, find/replace or template edited)
+fn register_rename_changed(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_rename_changed(move || {
+        let main_window = main_window_weak.unwrap();
+        update_rename_preview(&main_window, &app_state.borrow());
+    });
+}
+
+/// Register rename dialog Rename handler
+fn register_rename_apply(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_rename_apply(move || {
+        let main_window = main_window_weak.unwrap();
+        let (pattern, selected) = rename_input(&main_window, &app_state.borrow());
+
+        let rename_result = app_state
+            .borrow_mut()
+            .apply_batch_rename(&pattern, selected);
+        match rename_result {
+            Ok(renamed) => {
+                main_window.set_show_rename_dialog(false);
+                refresh_ui_after_state_change(
+                    &main_window,
+                    &app_state,
+                    &tr!("Renamed {} item(s)", renamed),
+                    StatusLevel::Success,
+                );
+            }
+            Err(e) => set_status(&main_window, e.to_string(), StatusLevel::Danger),
+        }
+    });
+}
+
+/// Register rename dialog Cancel handler
+fn register_rename_cancel(window: &MainWindow) {
+    let main_window_weak = window.as_weak();
+
+    window.on_rename_cancel(move || {
+        let main_window = main_window_weak.unwrap();
+        main_window.set_show_rename_dialog(false);
+        set_status(&main_window, tr!("Rename cancelled"), StatusLevel::Info);
+    });
+}
+
+/// The rename pattern and the displayed item it is limited to, as entered in the dialog
+fn rename_input(main_window: &MainWindow, state: &AppState) -> (RenamePattern, Option<usize>) {
+    let pattern = if main_window.get_rename_mode() == 0 {
+        RenamePattern::Replace {
+            find: main_window.get_rename_find().to_string(),
+            replace: main_window.get_rename_replace().to_string(),
+        }
+    } else {
+        RenamePattern::Template(main_window.get_rename_template().to_string())
+    };
+    let selected = (main_window.get_rename_scope() == 1)
+        .then(|| state.displayed_index(main_window.get_selected_item_index()))
+        .flatten();
+    (pattern, selected)
+}
+
+/// Show the new names the dialog's input gives, or why it gives none
+fn update_rename_preview(main_window: &MainWindow, state: &AppState) {
+    let (pattern, selected) = rename_input(main_window, state);
+    let (renames, error) = match state.preview_batch_rename(&pattern, selected) {
+        Ok(renames) => (renames, String::new()),
+        Err(e) => (Vec::new(), e),
+    };
+
+    let changes = renames.iter().filter(|rename| rename.changes()).count();
+    let rows = create_rename_rows(&renames);
+    main_window.set_rename_rows(Rc::new(VecModel::from(rows)).into());
+    main_window.set_rename_change_count(changes as i32);
+    main_window.set_rename_error(error.into());
+}
@@ -0,0 +1,72 @@
+use slint::ComponentHandle;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::i18n::tr;
+use crate::operations::FileOperations;
+use crate::state::AppState;
+use crate::ui::{refresh_ui_after_state_change, set_status};
+use crate::{MainWindow, StatusLevel};
+
+/// Register all schema editing handlers
+pub fn register_schema_handlers(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    register_import_vocabulary(window, app_state);
+    register_toggle_embed_schema(window, app_state);
+}
+
+/// Register Schema -> Import Vocabulary handler
+fn register_import_vocabulary(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_import_vocabulary(move |dimension| {
+        let main_window = main_window_weak.unwrap();
+        let dimension = dimension.trim().to_string();
+
+        if app_state.borrow().schema.is_none() {
+            set_status(&main_window, tr!("No schema loaded"), StatusLevel::Warning);
+            return;
+        }
+        if dimension.is_empty() {
+            set_status(
+                &main_window,
+                tr!("Enter a facet dimension"),
+                StatusLevel::Warning,
+            );
+            return;
+        }
+        main_window.set_show_import_vocabulary_dialog(false);
+
+        let app_state = app_state.clone();
+        let main_window = main_window.clone_strong();
+        slint::spawn_local(async move {
+            let ops = FileOperations::new(&app_state, &main_window);
+            ops.import_vocabulary(&dimension).await;
+        })
+        .unwrap();
+    });
+}
+
+/// Register Schema -> Embed Schema in Data File handler
+fn register_toggle_embed_schema(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_toggle_embed_schema(move || {
+        let main_window = main_window_weak.unwrap();
+
+        if app_state.borrow().schema.is_none() {
+            set_status(&main_window, tr!("No schema loaded"), StatusLevel::Warning);
+            return;
+        }
+        let embed = !app_state.borrow().embed_schema;
+        app_state.borrow_mut().set_embed_schema(embed);
+
+        let message = if embed {
+            "The schema will be embedded in the data file when saved"
+        } else {
+            "The data file will reference its schema file when saved"
+        };
+        refresh_ui_after_state_change(&main_window, &app_state, message, StatusLevel::Info);
+    });
+}
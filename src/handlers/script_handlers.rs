@@ -0,0 +1,82 @@
+use slint::ComponentHandle;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::i18n::tr;
+use crate::state::AppState;
+use crate::ui::{refresh_ui_after_state_change, set_status};
+use crate::{MainWindow, StatusLevel};
+
+/// Register all script console handlers
+pub fn register_script_handlers(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    register_open_script_console(window, app_state);
+    register_script_run(window, app_state);
+    register_script_close(window);
+}
+
+/// Register Edit -> Script Console handler
+fn register_open_script_console(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_open_script_console(move || {
+        let main_window = main_window_weak.unwrap();
+
+        if app_state.borrow().data.is_none() {
+            set_status(
+                &main_window,
+                tr!("No taxonomy loaded"),
+                StatusLevel::Warning,
+            );
+            return;
+        }
+
+        // The script stays for the next run; its output is from a run that is over
+        main_window.set_script_output("".into());
+        main_window.set_script_failed(false);
+        main_window.set_show_script_console(true);
+    });
+}
+
+/// Register script console Run handler
+fn register_script_run(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_script_run(move || {
+        let main_window = main_window_weak.unwrap();
+        let source = main_window.get_script();
+
+        let run_result = app_state.borrow_mut().run_script(&source);
+        match run_result {
+            Ok(report) => {
+                let outcome = tr!("Changed {} item(s), added {}", report.changed, report.added);
+                let mut output = report.output;
+                output.push(outcome.clone());
+                main_window.set_script_output(output.join("\n").into());
+                main_window.set_script_failed(false);
+                refresh_ui_after_state_change(
+                    &main_window,
+                    &app_state,
+                    &outcome,
+                    StatusLevel::Success,
+                );
+            }
+            Err(e) => {
+                main_window.set_script_output(e.to_string().into());
+                main_window.set_script_failed(true);
+                set_status(&main_window, tr!("Script failed"), StatusLevel::Danger);
+            }
+        }
+    });
+}
+
+/// Register script console Close handler
+fn register_script_close(window: &MainWindow) {
+    let main_window_weak = window.as_weak();
+
+    window.on_script_close(move || {
+        let main_window = main_window_weak.unwrap();
+        main_window.set_show_script_console(false);
+    });
+}
@@ -0,0 +1,256 @@
+use slint::{ComponentHandle, SharedString, Timer, TimerMode, VecModel};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::i18n::{self, tr, LANGUAGES};
+use crate::operations::publish_sync;
+use crate::state::{AppState, ExportFormat, Settings, ThemeChoice, MAX_BADGE_FACETS};
+use crate::ui::{set_status, update_ui_from_state};
+use crate::{MainWindow, StatusLevel, Style, ThemeMode};
+
+/// Register all settings handlers and start autosaving as the config says
+pub fn register_settings_handlers(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let autosave = Rc::new(Timer::default());
+    let minutes = app_state.borrow().config.settings.autosave_minutes;
+    schedule_autosave(&autosave, window, app_state, minutes);
+
+    register_open_settings(window, app_state);
+    register_settings_apply(window, app_state, &autosave);
+    register_settings_cancel(window);
+}
+
+/// The window's theme mode for a theme kept in the config
+pub fn theme_mode_for(choice: ThemeChoice) -> ThemeMode {
+    match choice {
+        ThemeChoice::System => ThemeMode::System,
+        ThemeChoice::Light => ThemeMode::Light,
+        ThemeChoice::Dark => ThemeMode::Dark,
+    }
+}
+
+/// The theme to keep in the config for the window's theme mode
+pub fn theme_choice_for(mode: ThemeMode) -> ThemeChoice {
+    match mode {
+        ThemeMode::System => ThemeChoice::System,
+        ThemeMode::Light => ThemeChoice::Light,
+        ThemeMode::Dark => ThemeChoice::Dark,
+    }
+}
+
+/// Scale the window's text and pick its palettes as the settings say
+pub fn apply_accessibility(window: &MainWindow, settings: &Settings) {
+    let style = window.global::<Style>();
+    style.set_font_scale(settings.font_scale);
+    style.set_high_contrast(settings.high_contrast);
+}
+
+/// Save the current file every `minutes` while it has unsaved changes; 0 stops autosaving
+fn schedule_autosave(
+    timer: &Timer,
+    window: &MainWindow,
+    app_state: &Rc<RefCell<AppState>>,
+    minutes: u32,
+) {
+    if minutes == 0 {
+        timer.stop();
+        return;
+    }
+
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+    let interval = Duration::from_secs(u64::from(minutes) * 60);
+
+    timer.start(TimerMode::Repeated, interval, move || {
+        let Some(main_window) = main_window_weak.upgrade() else {
+            return;
+        };
+
+        // Nothing to save, nowhere to save it, or a merge still being resolved
+        let due = {
+            let state = app_state.borrow();
+            state.dirty
+                && state.current_file.is_some()
+                && !state.is_read_only()
+                && state.pending_merge.is_none()
+        };
+        if !due {
+            return;
+        }
+
+        let save_result = app_state.borrow_mut().save();
+        match save_result {
+            Ok(()) => {
+                let title = app_state.borrow().get_window_title();
+                main_window.set_window_title(SharedString::from(title));
+                set_status(&main_window, tr!("Autosaved"), StatusLevel::Info);
+                publish_sync(&main_window, &app_state);
+            }
+            Err(e) => set_status(
+                &main_window,
+                tr!("Autosave failed: {}", e),
+                StatusLevel::Warning,
+            ),
+        }
+    });
+}
+
+/// Register Edit -> Settings... handler
+fn register_open_settings(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_open_settings(move || {
+        let main_window = main_window_weak.unwrap();
+        let state = app_state.borrow();
+        let settings = &state.config.settings;
+
+        let theme = match settings.theme {
+            ThemeChoice::System => 0,
+            ThemeChoice::Light => 1,
+            ThemeChoice::Dark => 2,
+        };
+        let export_format = match settings.export_format {
+            ExportFormat::Svg => 0,
+            ExportFormat::Png => 1,
+        };
+        // System, English, then LANGUAGES
+        let mut languages = vec![SharedString::from(tr!("System")), "English".into()];
+        languages.extend(LANGUAGES.iter().map(|(_, name)| SharedString::from(*name)));
+        let language = match settings.language.as_str() {
+            "" => 0,
+            "en" => 1,
+            locale => LANGUAGES
+                .iter()
+                .position(|(known, _)| *known == locale)
+                .map_or(0, |i| i as i32 + 2),
+        };
+        main_window.set_settings_theme(theme);
+        main_window.set_settings_languages(Rc::new(VecModel::from(languages)).into());
+        main_window.set_settings_language(language);
+        main_window.set_settings_font_scale((settings.font_scale * 100.0).round() as i32);
+        main_window.set_settings_high_contrast(settings.high_contrast);
+        main_window.set_settings_strict_duplicate_keys(settings.strict_duplicate_keys);
+        main_window.set_settings_autosave_minutes(settings.autosave_minutes as i32);
+        main_window.set_settings_backup_count(settings.backup_count as i32);
+        main_window.set_settings_export_format(export_format);
+        main_window.set_settings_author(SharedString::from(settings.author.as_str()));
+        main_window.set_settings_articles(SharedString::from(settings.articles.join(" ")));
+        main_window.set_settings_recent_files(settings.recent_files as i32);
+        main_window.set_settings_badge_facets(SharedString::from(settings.badge_facets.join(" ")));
+        let badge_colors: Vec<String> = settings
+            .badge_colors
+            .iter()
+            .map(|(value, color)| format!("{}={}", value, color))
+            .collect();
+        main_window.set_settings_badge_colors(SharedString::from(badge_colors.join(", ")));
+        main_window.set_show_settings_dialog(true);
+    });
+}
+
+/// Register Settings dialog -> Save handler
+fn register_settings_apply(
+    window: &MainWindow,
+    app_state: &Rc<RefCell<AppState>>,
+    autosave: &Rc<Timer>,
+) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+    let autosave = autosave.clone();
+
+    window.on_settings_apply(move || {
+        let main_window = main_window_weak.unwrap();
+
+        let theme = match main_window.get_settings_theme() {
+            1 => ThemeChoice::Light,
+            2 => ThemeChoice::Dark,
+            _ => ThemeChoice::System,
+        };
+        let export_format = match main_window.get_settings_export_format() {
+            1 => ExportFormat::Png,
+            _ => ExportFormat::Svg,
+        };
+        let language = match main_window.get_settings_language() {
+            0 => String::new(),
+            1 => "en".to_string(),
+            i => LANGUAGES
+                .get(i as usize - 2)
+                .map(|(locale, _)| locale.to_string())
+                .unwrap_or_default(),
+        };
+        let settings = Settings {
+            theme,
+            language,
+            font_scale: main_window.get_settings_font_scale().clamp(75, 200) as f32 / 100.0,
+            high_contrast: main_window.get_settings_high_contrast(),
+            strict_duplicate_keys: main_window.get_settings_strict_duplicate_keys(),
+            autosave_minutes: main_window.get_settings_autosave_minutes().max(0) as u32,
+            backup_count: main_window.get_settings_backup_count().max(0) as usize,
+            export_format,
+            author: main_window.get_settings_author().trim().to_string(),
+            articles: main_window
+                .get_settings_articles()
+                .split_whitespace()
+                .map(str::to_string)
+                .collect(),
+            recent_files: main_window.get_settings_recent_files().max(0) as usize,
+            badge_facets: main_window
+                .get_settings_badge_facets()
+                .split_whitespace()
+                .take(MAX_BADGE_FACETS)
+                .map(str::to_string)
+                .collect(),
+            // value=#rrggbb pairs; ones without a value are dropped
+            badge_colors: main_window
+                .get_settings_badge_colors()
+                .split(',')
+                .filter_map(|pair| pair.split_once('='))
+                .map(|(value, color)| (value.trim().to_string(), color.trim().to_string()))
+                .filter(|(value, _)| !value.is_empty())
+                .collect(),
+        };
+
+        let save_result = {
+            let mut state = app_state.borrow_mut();
+            let config = &mut state.config;
+            config.recent_files.truncate(settings.recent_files);
+            config.settings = settings.clone();
+            config.save()
+        };
+
+        main_window.set_show_settings_dialog(false);
+        main_window.set_theme_mode(theme_mode_for(settings.theme));
+        apply_accessibility(&main_window, &settings);
+        i18n::set_language(&settings.language);
+        schedule_autosave(
+            &autosave,
+            &main_window,
+            &app_state,
+            settings.autosave_minutes,
+        );
+        // Sorting, badges and the recent files list follow the new settings
+        update_ui_from_state(&main_window, &app_state);
+
+        match save_result {
+            Ok(()) => set_status(&main_window, tr!("Settings saved"), StatusLevel::Success),
+            Err(e) => set_status(
+                &main_window,
+                tr!(
+                    "Settings apply to this session but could not be saved: {}",
+                    e
+                ),
+                StatusLevel::Warning,
+            ),
+        }
+    });
+}
+
+/// Register Settings dialog -> Cancel handler
+fn register_settings_cancel(window: &MainWindow) {
+    let main_window_weak = window.as_weak();
+
+    window.on_settings_cancel(move || {
+        let main_window = main_window_weak.unwrap();
+        main_window.set_show_settings_dialog(false);
+    });
+}
@@ -0,0 +1,79 @@
+use slint::ComponentHandle;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::i18n::tr;
+use crate::state::AppState;
+use crate::ui::{refresh_ui_after_state_change, set_status};
+use crate::{MainWindow, StatusLevel};
+
+/// Register all trash handlers
+pub fn register_trash_handlers(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    register_trash_restore(window, app_state);
+    register_trash_empty(window, app_state);
+    register_trash_keep_toggled(window, app_state);
+}
+
+/// Register Trash tab -> Restore handler
+fn register_trash_restore(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_trash_restore(move |index| {
+        let main_window = main_window_weak.unwrap();
+        if index < 0 {
+            return;
+        }
+
+        let restore_result = app_state.borrow_mut().restore_from_trash(index as usize);
+        match restore_result {
+            Ok(name) => {
+                refresh_ui_after_state_change(
+                    &main_window,
+                    &app_state,
+                    &tr!("Item '{}' restored", name),
+                    StatusLevel::Success,
+                );
+            }
+            Err(e) => set_status(&main_window, e.to_string(), StatusLevel::Warning),
+        }
+    });
+}
+
+/// Register Trash tab -> Empty Trash handler
+fn register_trash_empty(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_trash_empty(move || {
+        let main_window = main_window_weak.unwrap();
+        let count = app_state.borrow().trash.len();
+
+        app_state.borrow_mut().empty_trash();
+        refresh_ui_after_state_change(
+            &main_window,
+            &app_state,
+            &tr!("Deleted {} item(s) for good", count),
+            StatusLevel::Info,
+        );
+    });
+}
+
+/// Register Trash tab -> Keep trash in data file handler
+fn register_trash_keep_toggled(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_trash_keep_toggled(move |keep| {
+        let main_window = main_window_weak.unwrap();
+
+        app_state.borrow_mut().set_keep_trash(keep);
+
+        let message = if keep {
+            "The trash will be saved in the data file"
+        } else {
+            "The trash will last for this session only"
+        };
+        refresh_ui_after_state_change(&main_window, &app_state, message, StatusLevel::Info);
+    });
+}
@@ -1,14 +1,25 @@
-use slint::{ComponentHandle, Model, VecModel};
+use slint::{ComponentHandle, Model, ModelRc, SharedString, VecModel};
+use std::cell::RefCell;
+use std::rc::Rc;
 
-use crate::ui::set_status;
-use crate::{FacetInput, MainWindow, StatusLevel, Theme};
+use super::theme_choice_for;
+use crate::i18n::tr;
+use crate::state::AppState;
+use crate::ui::{set_status, update_ui_from_state};
+use crate::{FacetInput, MainWindow, StatusLevel, ThemeMode};
 
-/// Register all UI-related handlers (theme, about, facet updates)
-pub fn register_ui_handlers(window: &MainWindow) {
+/// Register all UI-related handlers (theme, language, about, facet updates)
+pub fn register_ui_handlers(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
     register_about(window);
-    register_toggle_theme(window);
+    register_clear_notifications(window);
+    register_set_theme_mode(window, app_state);
+    register_set_display_language(window, app_state);
+    register_toggle_show_deprecated(window, app_state);
     register_update_edit_facet(window);
     register_update_create_facet(window);
+    register_update_edit_field(window);
+    register_update_create_field(window);
+    register_pick_create_facet_suggestion(window, app_state);
 }
 
 /// Register about handler
@@ -20,59 +31,181 @@ fn register_about(window: &MainWindow) {
 
         set_status(
             &main_window,
-            "Taxonomy Studio 0.1.0 by Tony Bierman",
+            tr!("Taxonomy Studio 0.1.0 by Tony Bierman"),
             StatusLevel::Info,
         );
     });
 }
 
-/// Register theme toggle handler
-fn register_toggle_theme(window: &MainWindow) {
+/// Register notification log clear handler
+fn register_clear_notifications(window: &MainWindow) {
     let main_window_weak = window.as_weak();
 
-    window.on_toggle_theme(move || {
+    window.on_clear_notifications(move || {
+        let main_window = main_window_weak.unwrap();
+        main_window.set_notifications(ModelRc::default());
+    });
+}
+
+/// Register View -> Theme handler; the choice is kept in the config
+fn register_set_theme_mode(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_set_theme_mode(move |mode| {
+        let main_window = main_window_weak.unwrap();
+        main_window.set_theme_mode(mode);
+
+        let save_result = {
+            let mut state = app_state.borrow_mut();
+            state.config.settings.theme = theme_choice_for(mode);
+            state.config.save()
+        };
+        let message = match mode {
+            ThemeMode::System => "Theme follows the system",
+            ThemeMode::Light => "Light theme",
+            ThemeMode::Dark => "Dark theme",
+        };
+        match save_result {
+            Ok(()) => set_status(&main_window, message, StatusLevel::Info),
+            Err(e) => set_status(
+                &main_window,
+                tr!("{} (could not be saved: {})", message, e),
+                StatusLevel::Warning,
+            ),
+        }
+    });
+}
+
+/// Register display language handler ("" switches back to the canonical names)
+fn register_set_display_language(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_set_display_language(move |lang| {
+        let main_window = main_window_weak.unwrap();
+
+        let message = if lang.is_empty() {
+            app_state.borrow_mut().display_lang = None;
+            "Showing canonical names".to_string()
+        } else {
+            app_state.borrow_mut().display_lang = Some(lang.to_string());
+            tr!("Showing names in '{}' where translated", lang)
+        };
+
+        update_ui_from_state(&main_window, &app_state);
+        set_status(&main_window, message, StatusLevel::Info);
+    });
+}
+
+/// Register View -> Show Deprecated Items handler
+fn register_toggle_show_deprecated(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_toggle_show_deprecated(move || {
         let main_window = main_window_weak.unwrap();
 
-        // Toggle between light and dark theme
-        let current_theme = main_window.get_theme();
-        let new_theme = if current_theme == Theme::Light {
-            Theme::Dark
+        let show = !app_state.borrow().filters.include_deprecated;
+        app_state.borrow_mut().filters.include_deprecated = show;
+
+        let message = if show {
+            "Showing deprecated items"
         } else {
-            Theme::Light
+            "Hiding deprecated items"
         };
-        main_window.set_theme(new_theme);
+        update_ui_from_state(&main_window, &app_state);
+        set_status(&main_window, message, StatusLevel::Info);
     });
 }
 
 /// Register edit facet value update handler
+/// The form's live check re-marks the inputs once typing pauses
 fn register_update_edit_facet(window: &MainWindow) {
     let main_window_weak = window.as_weak();
 
     window.on_update_edit_facet(move |index, value| {
         let main_window = main_window_weak.unwrap();
-        let facet_inputs = main_window.get_edit_facet_inputs();
-        if let Some(model) = facet_inputs.as_any().downcast_ref::<VecModel<FacetInput>>() {
-            if (index as usize) < model.row_count() {
-                let mut item = model.row_data(index as usize).unwrap();
-                item.value = value;
-                model.set_row_data(index as usize, item);
-            }
-        }
+        set_input_value(&main_window.get_edit_facet_inputs(), index, value);
     });
 }
 
 /// Register create facet value update handler
+/// The form's live check re-marks the inputs once typing pauses
 fn register_update_create_facet(window: &MainWindow) {
     let main_window_weak = window.as_weak();
 
     window.on_update_create_facet(move |index, value| {
         let main_window = main_window_weak.unwrap();
+        set_input_value(&main_window.get_create_facet_inputs(), index, value);
+    });
+}
+
+/// Register edit custom field value update handler
+fn register_update_edit_field(window: &MainWindow) {
+    let main_window_weak = window.as_weak();
+
+    window.on_update_edit_field(move |index, value| {
+        let main_window = main_window_weak.unwrap();
+        set_input_value(&main_window.get_edit_field_inputs(), index, value);
+    });
+}
+
+/// Register create custom field value update handler
+fn register_update_create_field(window: &MainWindow) {
+    let main_window_weak = window.as_weak();
+
+    window.on_update_create_field(move |index, value| {
+        let main_window = main_window_weak.unwrap();
+        set_input_value(&main_window.get_create_field_inputs(), index, value);
+    });
+}
+
+/// Store a typed-in value in an input row
+fn set_input_value(inputs: &ModelRc<FacetInput>, index: i32, value: SharedString) {
+    if let Some(mut input) = inputs.row_data(index as usize) {
+        input.value = value;
+        inputs.set_row_data(index as usize, input);
+    }
+}
+
+/// Register create facet suggestion pick handler
+/// A multi-valued facet gets the value added to its list; others have it replaced
+fn register_pick_create_facet_suggestion(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let main_window_weak = window.as_weak();
+    let app_state = app_state.clone();
+
+    window.on_pick_create_facet_suggestion(move |index, value| {
+        let main_window = main_window_weak.unwrap();
+        let state = app_state.borrow();
+        let Some(ref schema) = state.schema else {
+            return;
+        };
+
         let facet_inputs = main_window.get_create_facet_inputs();
         if let Some(model) = facet_inputs.as_any().downcast_ref::<VecModel<FacetInput>>() {
-            if (index as usize) < model.row_count() {
-                let mut item = model.row_data(index as usize).unwrap();
-                item.value = value;
-                model.set_row_data(index as usize, item);
+            if let Some(mut input) = model.row_data(index as usize) {
+                let multi = schema
+                    .faceted_dimensions
+                    .get(input.name.as_str())
+                    .is_some_and(|definition| definition.multi);
+                let mut values: Vec<String> = input
+                    .value
+                    .split(',')
+                    .map(|v| v.trim().to_string())
+                    .filter(|v| !v.is_empty())
+                    .collect();
+                if !multi {
+                    values.clear();
+                }
+                if !values.iter().any(|v| v == value.as_str()) {
+                    values.push(value.to_string());
+                }
+                input.value = values.join(", ").into();
+                let remaining: Vec<SharedString> =
+                    input.suggestions.iter().filter(|s| *s != value).collect();
+                input.suggestions = Rc::new(VecModel::from(remaining)).into();
+                model.set_row_data(index as usize, input);
             }
         }
     });
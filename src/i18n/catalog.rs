@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Display;
+use std::sync::{OnceLock, RwLock};
+
+/// Languages the window can be shown in besides English: (locale, name in that language)
+pub const LANGUAGES: &[(&str, &str)] = &[("de", "Deutsch")];
+
+/// The gettext catalog of each language in LANGUAGES; the .slint files get the same
+/// ones bundled by build.rs
+const CATALOGS: &[(&str, &str)] = &[("de", include_str!("../../lang/de/LC_MESSAGES/taxstud.po"))];
+
+/// Translations of the messages without a context, which are the Rust side's
+type Catalog = HashMap<String, String>;
+
+static CATALOG_CACHE: OnceLock<HashMap<&'static str, Catalog>> = OnceLock::new();
+static CURRENT: RwLock<Option<&'static str>> = RwLock::new(None);
+
+/// Show the window in `language` (a locale from LANGUAGES, "en", or "" for the OS
+/// preference); languages without a catalog fall back to English
+pub fn set_language(language: &str) {
+    let language = if language.is_empty() {
+        system_language()
+    } else {
+        language.to_string()
+    };
+    let current = LANGUAGES
+        .iter()
+        .map(|(locale, _)| *locale)
+        .find(|locale| *locale == language);
+
+    *CURRENT.write().unwrap() = current;
+    // Only fails for a locale that isn't bundled, which English never is
+    let _ = slint::select_bundled_translation(current.unwrap_or(""));
+}
+
+/// The OS language, from the POSIX locale variables ("de" for `de_DE.UTF-8`)
+fn system_language() -> String {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .map(|value| {
+            value
+                .split(['_', '.', '@'])
+                .next()
+                .unwrap_or_default()
+                .to_lowercase()
+        })
+        .unwrap_or_default()
+}
+
+/// `msgid` in the current language, or `msgid` itself when it has no translation
+pub fn translate(msgid: &str) -> String {
+    let Some(language) = *CURRENT.read().unwrap() else {
+        return msgid.to_string();
+    };
+    let catalogs = CATALOG_CACHE.get_or_init(|| {
+        CATALOGS
+            .iter()
+            .map(|(locale, po)| (*locale, parse_po(po)))
+            .collect()
+    });
+    catalogs
+        .get(language)
+        .and_then(|catalog| catalog.get(msgid))
+        .cloned()
+        .unwrap_or_else(|| msgid.to_string())
+}
+
+/// Fill the `{}` placeholders of `template` with `args` in order, and `{0}`, `{1}`…
+/// with the argument at that index; `{{` and `}}` stand for braces, as in Slint's @tr
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut filled = String::with_capacity(template.len());
+    let mut next = 0;
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                filled.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                filled.push('}');
+            }
+            '{' => {
+                let mut index = String::new();
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                    index.push(c);
+                }
+                let arg = if index.is_empty() {
+                    next += 1;
+                    args.get(next - 1)
+                } else {
+                    index.parse::<usize>().ok().and_then(|i| args.get(i))
+                };
+                if let Some(arg) = arg {
+                    filled.push_str(&arg.to_string());
+                }
+            }
+            c => filled.push(c),
+        }
+    }
+    filled
+}
+
+/// The translated messages of a .po file that have no msgctxt; messages with one
+/// belong to a .slint component
+fn parse_po(po: &str) -> Catalog {
+    #[derive(PartialEq)]
+    enum Field {
+        Context,
+        Id,
+        Str,
+        Other,
+    }
+
+    let mut catalog = Catalog::new();
+    let (mut context, mut id, mut text) = (None::<String>, String::new(), String::new());
+    let mut field = Field::Other;
+
+    let mut finish = |context: &mut Option<String>, id: &mut String, text: &mut String| {
+        if context.is_none() && !id.is_empty() && !text.is_empty() {
+            catalog.insert(std::mem::take(id), std::mem::take(text));
+        }
+        *context = None;
+        id.clear();
+        text.clear();
+    };
+
+    for line in po.lines().map(str::trim) {
+        let (next, rest) = if let Some(rest) = line.strip_prefix("msgctxt ") {
+            (Field::Context, rest)
+        } else if let Some(rest) = line.strip_prefix("msgid ") {
+            (Field::Id, rest)
+        } else if let Some(rest) = line.strip_prefix("msgstr ") {
+            (Field::Str, rest)
+        } else if line.starts_with('"') {
+            // Continues the field above
+            match field {
+                Field::Context => context
+                    .get_or_insert_with(String::new)
+                    .push_str(&unquote(line)),
+                Field::Id => id.push_str(&unquote(line)),
+                Field::Str => text.push_str(&unquote(line)),
+                Field::Other => {}
+            }
+            continue;
+        } else {
+            // Comments, blank lines and plural forms
+            field = Field::Other;
+            continue;
+        };
+
+        // A msgctxt, or a msgid without one, starts the next message
+        if next == Field::Context || (next == Field::Id && field != Field::Context) {
+            finish(&mut context, &mut id, &mut text);
+        }
+        match next {
+            Field::Context => context = Some(unquote(rest)),
+            Field::Id => id = unquote(rest),
+            Field::Str => text = unquote(rest),
+            Field::Other => {}
+        }
+        field = next;
+    }
+    finish(&mut context, &mut id, &mut text);
+    catalog
+}
+
+/// The text of a quoted .po string
+fn unquote(quoted: &str) -> String {
+    let inner = quoted
+        .trim()
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or_default();
+    let mut text = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => text.push('\n'),
+            Some('t') => text.push('\t'),
+            Some(other) => text.push(other),
+            None => {}
+        }
+    }
+    text
+}
@@ -0,0 +1,19 @@
+pub mod catalog;
+
+pub use catalog::{fill, set_language, translate, LANGUAGES};
+
+/// A message in the window's language: `tr!("Saved {}", path)` looks the template up in
+/// the catalog, then fills it in like Slint's @tr
+macro_rules! tr {
+    ($msgid:literal) => {
+        $crate::i18n::fill(&$crate::i18n::translate($msgid), &[])
+    };
+    ($msgid:literal, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::fill(
+            &$crate::i18n::translate($msgid),
+            &[$(&$arg as &dyn std::fmt::Display),+],
+        )
+    };
+}
+
+pub(crate) use tr;
@@ -1,5 +1,6 @@
 mod errors;
 mod handlers;
+mod i18n;
 mod operations;
 mod state;
 mod ui;
@@ -9,19 +10,22 @@ use slint::{ComponentHandle, SharedString};
 use std::cell::RefCell;
 use std::path::PathBuf;
 use std::rc::Rc;
+use taxstud_core::PROJECT_EXTENSION;
 
 use handlers::*;
-use state::{AppState, UiState};
+use i18n::tr;
+use operations::FileOperations;
+use state::{AppState, MissingSchema, UiState};
 use ui::{set_status, update_ui_from_state};
 
-slint::slint!(export { MainWindow } from "ui/app-window.slint";);
+slint::include_modules!();
 
 /// TaxStud - Hybrid Taxonomy Management System
 #[derive(Parser, Debug)]
 #[command(name = "taxstud")]
 #[command(author, version, about = "Hybrid Taxonomy Management System", long_about = None)]
 struct Args {
-    /// Path to taxonomy JSON file to load on startup
+    /// Path to taxonomy JSON file (or .taxproj project) to load on startup
     #[arg(value_name = "FILE")]
     file: Option<PathBuf>,
 }
@@ -33,11 +37,28 @@ pub fn main() {
     let app_state = Rc::new(RefCell::new(AppState::new()));
     let ui_state = Rc::new(RefCell::new(UiState::new()));
 
+    // Menus and messages in the configured language from here on
+    i18n::set_language(&app_state.borrow().config.settings.language);
+
     // Set initial window title
-    main_window.set_window_title(SharedString::from("Taxonomy Studio - No file loaded"));
+    main_window.set_window_title(SharedString::from(tr!("Taxonomy Studio - No file loaded")));
+    main_window.set_theme_mode(theme_mode_for(app_state.borrow().config.settings.theme));
+    apply_accessibility(&main_window, &app_state.borrow().config.settings);
 
-    // Load file from command line if provided
-    if let Some(file_path) = args.file {
+    // Load file (or .taxproj project) from command line if provided
+    let is_project = |path: &PathBuf| {
+        path.extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case(PROJECT_EXTENSION))
+    };
+    if let Some(project_path) = args.file.clone().filter(is_project) {
+        let app_state = app_state.clone();
+        let main_window = main_window.clone_strong();
+        slint::spawn_local(async move {
+            let ops = FileOperations::new(&app_state, &main_window);
+            ops.load_project(&project_path).await;
+        })
+        .unwrap();
+    } else if let Some(file_path) = args.file {
         let load_result = app_state.borrow_mut().load_from_file(file_path.clone());
 
         match load_result {
@@ -51,14 +72,24 @@ pub fn main() {
 
                 set_status(
                     &main_window,
-                    format!("Loaded: {}", file_path.display()),
+                    tr!("Loaded: {}", file_path.display()),
                     StatusLevel::Success,
                 );
             }
+            // Offer to locate a schema that has moved once the window is up
+            Err(e) if e.downcast_ref::<MissingSchema>().is_some() => {
+                let app_state = app_state.clone();
+                let main_window = main_window.clone_strong();
+                slint::spawn_local(async move {
+                    let ops = FileOperations::new(&app_state, &main_window);
+                    ops.load_file(&file_path).await;
+                })
+                .unwrap();
+            }
             Err(e) => {
                 set_status(
                     &main_window,
-                    format!("Error loading file: {}", e),
+                    tr!("Error loading file: {}", e),
                     StatusLevel::Danger,
                 );
             }
@@ -69,8 +100,21 @@ pub fn main() {
     register_item_handlers(&main_window, &app_state);
     register_file_handlers(&main_window, &app_state, &ui_state);
     register_filter_handlers(&main_window, &app_state);
+    register_map_handlers(&main_window, &app_state);
+    register_clipboard_handlers(&main_window, &app_state);
+    register_quick_open_handlers(&main_window, &app_state, &ui_state);
+    register_schema_handlers(&main_window, &app_state);
+    register_import_handlers(&main_window, &app_state);
+    register_merge_handlers(&main_window, &app_state);
+    register_rename_handlers(&main_window, &app_state);
+    register_reclassify_handlers(&main_window, &app_state);
+    register_script_handlers(&main_window, &app_state);
+    register_git_handlers(&main_window, &app_state);
+    register_compare_handlers(&main_window, &app_state);
+    register_trash_handlers(&main_window, &app_state);
     register_dialog_handlers(&main_window, &app_state, &ui_state);
-    register_ui_handlers(&main_window);
+    register_ui_handlers(&main_window, &app_state);
+    register_settings_handlers(&main_window, &app_state);
 
     main_window.run().unwrap();
 }
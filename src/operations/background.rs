@@ -0,0 +1,130 @@
+use slint::{ComponentHandle, SharedString};
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use taxstud_core::{Progress, ProgressFn};
+
+use crate::i18n;
+use crate::MainWindow;
+
+/// What the worker thread, or the Cancel button, hands back to the UI thread
+struct Outcome<T> {
+    result: Option<thread::Result<T>>,
+    cancelled: bool,
+    waker: Option<Waker>,
+}
+
+impl<T> Outcome<T> {
+    fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Resolves once the worker thread has finished (Some) or the user cancelled (None)
+struct Finished<T>(Arc<Mutex<Outcome<T>>>);
+
+impl<T> Future for Finished<T> {
+    type Output = Option<thread::Result<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut outcome = self.0.lock().unwrap();
+        if let Some(result) = outcome.result.take() {
+            return Poll::Ready(Some(result));
+        }
+        if outcome.cancelled {
+            return Poll::Ready(None);
+        }
+        outcome.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+/// Run `work` on a worker thread while the progress dialog shows what it reports, so the
+/// window keeps repainting; resolves to its result back on the UI thread
+/// A panic in `work` is resumed on the UI thread
+pub async fn run_in_background<T, F>(window: &MainWindow, title: &str, work: F) -> T
+where
+    T: Send + 'static,
+    F: FnOnce(&mut ProgressFn) -> T + Send + 'static,
+{
+    run(window, title, false, work)
+        .await
+        .expect("work without a Cancel button runs to the end")
+}
+
+/// Like run_in_background, with a Cancel button in the progress dialog; resolves to None
+/// as soon as it is pressed
+/// A cancelled worker can't be interrupted: it is left to finish and its result dropped
+pub async fn run_cancellable_in_background<T, F>(
+    window: &MainWindow,
+    title: &str,
+    work: F,
+) -> Option<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut ProgressFn) -> T + Send + 'static,
+{
+    run(window, title, true, work).await
+}
+
+async fn run<T, F>(window: &MainWindow, title: &str, cancellable: bool, work: F) -> Option<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut ProgressFn) -> T + Send + 'static,
+{
+    window.set_progress_title(SharedString::from(title));
+    window.set_progress_stage(SharedString::new());
+    window.set_progress_value(-1.0);
+    window.set_progress_cancellable(cancellable);
+    window.set_show_progress_dialog(true);
+
+    let outcome = Arc::new(Mutex::new(Outcome {
+        result: None,
+        cancelled: false,
+        waker: None,
+    }));
+    // Read without locking by the worker, so a cancelled one stops reporting
+    let cancelled = Arc::new(AtomicBool::new(false));
+
+    let cancel_outcome = outcome.clone();
+    let cancel_flag = cancelled.clone();
+    window.on_progress_cancel(move || {
+        cancel_flag.store(true, Ordering::Relaxed);
+        let mut outcome = cancel_outcome.lock().unwrap();
+        outcome.cancelled = true;
+        outcome.wake();
+    });
+
+    let worker_outcome = outcome.clone();
+    let window_weak = window.as_weak();
+    thread::spawn(move || {
+        let mut on_progress = |progress: &Progress| {
+            if cancelled.load(Ordering::Relaxed) {
+                return;
+            }
+            let stage = SharedString::from(i18n::translate(&progress.stage));
+            let value = progress.fraction().unwrap_or(-1.0);
+            // The window may be gone by now; there is nothing left to show then
+            let _ = window_weak.upgrade_in_event_loop(move |w| {
+                w.set_progress_stage(stage);
+                w.set_progress_value(value);
+            });
+        };
+        let result = panic::catch_unwind(AssertUnwindSafe(|| work(&mut on_progress)));
+
+        let mut outcome = worker_outcome.lock().unwrap();
+        outcome.result = Some(result);
+        outcome.wake();
+    });
+
+    let result = Finished(outcome).await;
+    window.set_show_progress_dialog(false);
+    window.on_progress_cancel(|| {});
+    result.map(|result| result.unwrap_or_else(|payload| panic::resume_unwind(payload)))
+}
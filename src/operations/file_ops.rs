@@ -1,11 +1,21 @@
 use slint::SharedString;
 use std::cell::RefCell;
-use std::path::Path;
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use taxstud_core::{
+    read_import_table_with_progress, Freshness, ImporterRegistry, RemoteCache, PROJECT_EXTENSION,
+};
 
+use super::{publish_sync, run_cancellable_in_background, run_in_background};
 use crate::errors::{map_file_load_error, map_file_save_error, map_revert_error};
-use crate::state::AppState;
-use crate::ui::{set_status, show_error, update_ui_from_state};
+use crate::i18n::tr;
+use crate::state::{AppState, ExportFormat, LoadError, LoadedFile, MissingSchema, RemoteSource};
+use crate::ui::{
+    set_status, show_error, update_comparison, update_history, update_import_dialog,
+    update_merge_dialog, update_recent_files, update_ui_from_state,
+};
 use crate::{MainWindow, StatusLevel};
 
 /// File operations orchestration
@@ -24,7 +34,7 @@ impl<'a> FileOperations<'a> {
     pub async fn open_file_dialog_and_load(&self) {
         if let Some(file) = rfd::AsyncFileDialog::new()
             .add_filter("JSON", &["json"])
-            .set_title("Open Taxonomy File")
+            .set_title(tr!("Open Taxonomy File"))
             .pick_file()
             .await
         {
@@ -32,13 +42,114 @@ impl<'a> FileOperations<'a> {
         }
     }
 
+    /// Open file dialog and open the selected .taxproj project
+    pub async fn open_project_dialog_and_load(&self) {
+        if let Some(file) = rfd::AsyncFileDialog::new()
+            .add_filter("TaxStud Project", &[PROJECT_EXTENSION])
+            .set_title(tr!("Open Project"))
+            .pick_file()
+            .await
+        {
+            self.load_project(file.path()).await;
+        }
+    }
+
+    /// Open a .taxproj project and load its first member taxonomy
+    pub async fn load_project(&self, path: &Path) {
+        let open_result = self.state.borrow_mut().open_project(path);
+        if let Err(e) = open_result {
+            show_error(
+                self.window,
+                tr!("Project Error"),
+                tr!("Could not open project '{}'", path.display()),
+                e.to_string(),
+            );
+            return;
+        }
+
+        if self.state.borrow().project_member(0).is_some() {
+            self.load_project_member(0).await;
+        } else {
+            update_ui_from_state(self.window, self.state);
+            set_status(
+                self.window,
+                tr!("The project lists no taxonomies"),
+                StatusLevel::Warning,
+            );
+        }
+    }
+
+    /// Load the open project's member taxonomy at `index`
+    pub async fn load_project_member(&self, index: usize) {
+        let path = self.state.borrow().project_member(index);
+        if let Some(path) = path {
+            self.load_file(&path).await;
+        }
+    }
+
+    /// Read a data file and its schema on a worker thread, with the progress dialog up
+    /// None when the user cancelled; the current file is left as it was
+    async fn read_in_background(
+        &self,
+        path: &Path,
+        schema_locations: HashMap<PathBuf, PathBuf>,
+    ) -> Option<Result<LoadedFile, Box<dyn Error>>> {
+        let path = path.to_path_buf();
+        let options = self.state.borrow().config.settings.load_options();
+        let result =
+            run_cancellable_in_background(self.window, &tr!("Opening file"), move |on_progress| {
+                LoadedFile::read(path, &schema_locations, &options, on_progress)
+            })
+            .await;
+        if result.is_none() {
+            set_status(self.window, tr!("Opening cancelled"), StatusLevel::Info);
+        }
+        result.map(|result| result.map_err(|e| e as Box<dyn Error>))
+    }
+
     /// Load a taxonomy file from the given path
+    /// Reading and validating it runs in the background and can be cancelled
     pub async fn load_file(&self, path: &Path) {
-        // Load the file (borrow mutably, then drop the borrow)
-        let load_result = self.state.borrow_mut().load_from_file(path.to_path_buf());
+        let schema_locations = self.state.borrow().config.schema_locations.clone();
+        let Some(mut load_result) = self
+            .read_in_background(path, schema_locations.clone())
+            .await
+        else {
+            return;
+        };
+
+        // The schema it references has moved: ask where it is and try again
+        let missing = load_result
+            .as_ref()
+            .err()
+            .and_then(|e| e.downcast_ref::<MissingSchema>())
+            .map(|missing| missing.schema_path.clone());
+        if let Some(missing) = missing {
+            set_status(
+                self.window,
+                tr!("Schema file not found: {}", missing.display()),
+                StatusLevel::Warning,
+            );
+            let Some(located) = self.locate_schema(&missing).await else {
+                return;
+            };
+            let mut schema_locations = schema_locations;
+            schema_locations.insert(missing.clone(), located.clone());
+            let Some(result) = self.read_in_background(path, schema_locations).await else {
+                return;
+            };
+            load_result = result;
+            if load_result.is_ok() {
+                self.state
+                    .borrow_mut()
+                    .remember_schema_location(missing, located);
+            }
+        }
 
         match load_result {
-            Ok(_) => {
+            Ok(loaded) => {
+                self.state.borrow_mut().install(loaded);
+
                 // Update window title (borrow immutably)
                 let title = self.state.borrow().get_window_title();
                 self.window.set_window_title(SharedString::from(title));
@@ -46,13 +157,24 @@ impl<'a> FileOperations<'a> {
                 // Update UI with loaded data (borrow immutably)
                 update_ui_from_state(self.window, self.state);
 
+                // Open on the dashboard for an overview of the taxonomy
+                self.window.set_main_tab(1);
+
                 set_status(
                     self.window,
-                    "File loaded successfully",
+                    tr!("File loaded successfully"),
                     StatusLevel::Success,
                 );
             }
             Err(e) => {
+                // Git left the file in conflict: resolve it rather than fail on its markers
+                let conflict_result = self.state.borrow_mut().load_merge_conflict(path);
+                if let Ok(Some(conflicts)) = conflict_result {
+                    update_ui_from_state(self.window, self.state);
+                    self.show_merge(conflicts);
+                    return;
+                }
+
                 // Show enhanced error dialog using error mapper
                 let (title, message, details) = map_file_load_error(&*e, path);
                 show_error(self.window, title, message, details);
@@ -60,6 +182,84 @@ impl<'a> FileOperations<'a> {
         }
     }
 
+    /// Open the taxonomy published at `url`: download it and its schema (or revalidate the
+    /// cached copies) and read them in the background, then load the copy
+    /// It is read-only unless the config lists the URL as writable
+    pub async fn load_url(&self, url: &str) {
+        let url = url.trim().to_string();
+        let writable = self.state.borrow().is_writable_url(&url);
+        let schema_locations = self.state.borrow().config.schema_locations.clone();
+        let options = self.state.borrow().config.settings.load_options();
+        let fetch_url = url.clone();
+        let result =
+            run_cancellable_in_background(self.window, &tr!("Opening URL"), move |on_progress| {
+                let remote = RemoteCache::default()
+                    .open(&fetch_url)
+                    .map_err(|e| e.to_string())?;
+                let loaded =
+                    LoadedFile::read(remote.data_path, &schema_locations, &options, on_progress)?;
+                Ok::<_, LoadError>((remote.freshness, loaded))
+            })
+            .await;
+        let Some(result) = result else {
+            set_status(self.window, tr!("Opening cancelled"), StatusLevel::Info);
+            return;
+        };
+
+        match result {
+            Ok((freshness, loaded)) => {
+                let remote = RemoteSource {
+                    etag: RemoteCache::default().etag(&url),
+                    url: url.clone(),
+                    writable,
+                };
+                self.state.borrow_mut().install_as(loaded, Some(remote));
+
+                let title = self.state.borrow().get_window_title();
+                self.window.set_window_title(SharedString::from(title));
+                update_ui_from_state(self.window, self.state);
+                self.window.set_main_tab(1);
+
+                let (message, level) = match freshness {
+                    Freshness::Offline => (
+                        tr!(
+                            "The server could not be reached; showing the copy of {} saved earlier",
+                            url
+                        ),
+                        StatusLevel::Warning,
+                    ),
+                    _ if !writable => (
+                        tr!("Opened {} read-only; use Save As to keep a local copy", url),
+                        StatusLevel::Info,
+                    ),
+                    _ => (tr!("File loaded successfully"), StatusLevel::Success),
+                };
+                set_status(self.window, message, level);
+            }
+            Err(e) => show_error(
+                self.window,
+                tr!("Open URL Error"),
+                tr!("Could not open '{}'", url),
+                e.to_string(),
+            ),
+        }
+    }
+
+    /// Ask for the file a missing schema moved to, starting in the directory it was expected in
+    async fn locate_schema(&self, missing: &Path) -> Option<PathBuf> {
+        let file_name = missing.file_name().unwrap_or_default().to_string_lossy();
+        let mut dialog = rfd::AsyncFileDialog::new()
+            .add_filter("JSON", &["json"])
+            .set_title(tr!("Locate Schema '{}'", file_name));
+        if let Some(dir) = missing.parent().filter(|dir| dir.is_dir()) {
+            dialog = dialog.set_directory(dir);
+        }
+        dialog
+            .pick_file()
+            .await
+            .map(|file| file.path().to_path_buf())
+    }
+
     /// Save the current taxonomy to its current file
     pub fn save(&self) -> Result<(), String> {
         let save_result = self.state.borrow_mut().save();
@@ -70,7 +270,12 @@ impl<'a> FileOperations<'a> {
                 let title = self.state.borrow().get_window_title();
                 self.window.set_window_title(SharedString::from(title));
 
-                set_status(self.window, "File saved successfully", StatusLevel::Success);
+                set_status(
+                    self.window,
+                    tr!("File saved successfully"),
+                    StatusLevel::Success,
+                );
+                publish_sync(self.window, self.state);
                 Ok(())
             }
             Err(e) => {
@@ -81,8 +286,8 @@ impl<'a> FileOperations<'a> {
                 } else {
                     show_error(
                         self.window,
-                        "Save Error",
-                        "Cannot save: no file path set",
+                        tr!("Save Error"),
+                        tr!("Cannot save: no file path set"),
                         e.to_string(),
                     );
                 }
@@ -95,7 +300,7 @@ impl<'a> FileOperations<'a> {
     pub async fn save_as(&self) {
         if let Some(file) = rfd::AsyncFileDialog::new()
             .add_filter("JSON", &["json"])
-            .set_title("Save Taxonomy As")
+            .set_title(tr!("Save Taxonomy As"))
             .save_file()
             .await
         {
@@ -107,8 +312,15 @@ impl<'a> FileOperations<'a> {
                     // Update window title
                     let title = self.state.borrow().get_window_title();
                     self.window.set_window_title(SharedString::from(title));
+                    update_history(self.window, &self.state.borrow());
+                    update_recent_files(self.window, &self.state.borrow());
 
-                    set_status(self.window, "File saved successfully", StatusLevel::Success);
+                    set_status(
+                        self.window,
+                        tr!("File saved successfully"),
+                        StatusLevel::Success,
+                    );
+                    publish_sync(self.window, self.state);
                 }
                 Err(e) => {
                     let (title, message, details) = map_file_save_error(&*e, Some(&path));
@@ -118,16 +330,377 @@ impl<'a> FileOperations<'a> {
         }
     }
 
+    /// Pick a file and export the hierarchy map (treemap, or sunburst) to it
+    pub async fn export_hierarchy_map(&self, sunburst: bool) {
+        let Some(file) = self
+            .pick_export_file(tr!("Export Hierarchy Map"), "hierarchy-map", ("SVG", "svg"))
+            .await
+        else {
+            return;
+        };
+
+        let export_result = self
+            .state
+            .borrow()
+            .export_hierarchy_map(file.path(), sunburst);
+        let exported = tr!("Hierarchy map exported to {}", file.path().display());
+        self.report_export(file.path(), exported, export_result);
+    }
+
+    /// Pick a file and export the classical hierarchy to it as a tree diagram
+    pub async fn export_hierarchy_diagram(&self) {
+        let Some(file) = self
+            .pick_export_file(tr!("Export Image"), "hierarchy", ("SVG", "svg"))
+            .await
+        else {
+            return;
+        };
+
+        let export_result = self.state.borrow().export_hierarchy_diagram(file.path());
+        let exported = tr!("Hierarchy diagram exported to {}", file.path().display());
+        self.report_export(file.path(), exported, export_result);
+    }
+
+    /// Pick a file and export the current items to it with the exporter registered as `id`
+    pub async fn export_as(&self, id: &str) {
+        let (format, stem) = {
+            let state = self.state.borrow();
+            let Some(exporter) = state.exporters.get(id) else {
+                return;
+            };
+            let stem = state
+                .current_file
+                .as_deref()
+                .and_then(Path::file_stem)
+                .map_or("taxonomy".into(), |stem| {
+                    stem.to_string_lossy().into_owned()
+                });
+            ((exporter.name(), exporter.extension()), stem)
+        };
+        let Some(file) = self
+            .pick_export_file(tr!("Export as {}", format.0), &stem, format)
+            .await
+        else {
+            return;
+        };
+
+        let export_result = self.state.borrow().export_as(id, file.path());
+        let exported = tr!("Exported to {}", file.path().display());
+        self.report_export(file.path(), exported, export_result);
+    }
+
+    /// Pick where to save an export in `format`; SVG drawings may be saved as PNG too
+    /// (with the 'png' feature)
+    async fn pick_export_file(
+        &self,
+        title: String,
+        stem: &str,
+        format: (&str, &str),
+    ) -> Option<rfd::FileHandle> {
+        let mut filters = vec![format];
+        if format.1 == "svg" && cfg!(feature = "png") {
+            // The format the settings prefer comes first
+            let png = ("PNG", "png");
+            match self.state.borrow().config.settings.export_format {
+                ExportFormat::Png => filters.insert(0, png),
+                ExportFormat::Svg => filters.push(png),
+            }
+        }
+        let mut dialog = rfd::AsyncFileDialog::new()
+            .set_title(title)
+            .set_file_name(format!("{}.{}", stem, filters[0].1));
+        for (name, extension) in filters {
+            dialog = dialog.add_filter(name, &[extension]);
+        }
+        dialog.save_file().await
+    }
+
+    /// Show `exported` when the export worked, and the error otherwise
+    fn report_export(&self, path: &Path, exported: String, result: Result<(), Box<dyn Error>>) {
+        match result {
+            Ok(()) => set_status(self.window, exported, StatusLevel::Success),
+            Err(e) => show_error(
+                self.window,
+                tr!("Export Error"),
+                tr!("Could not export '{}'", path.display()),
+                e.to_string(),
+            ),
+        }
+    }
+
+    /// Pick a vocabulary file and import its terms into a faceted dimension
+    pub async fn import_vocabulary(&self, dimension: &str) {
+        let Some(file) = rfd::AsyncFileDialog::new()
+            .add_filter("Vocabulary", &["csv", "tsv", "txt", "rdf", "xml", "skos"])
+            .set_title(tr!("Import Vocabulary"))
+            .pick_file()
+            .await
+        else {
+            return;
+        };
+
+        let import_result = self
+            .state
+            .borrow_mut()
+            .import_vocabulary(file.path(), dimension);
+
+        match import_result {
+            Ok(report) => {
+                update_ui_from_state(self.window, self.state);
+
+                let summary = tr!(
+                    "Imported {} value(s) into '{}' ({} duplicate(s) skipped)",
+                    report.added.len(),
+                    dimension,
+                    report.duplicates.len()
+                );
+                if report.conflicts.is_empty() {
+                    set_status(self.window, summary, StatusLevel::Success);
+                } else {
+                    set_status(self.window, summary, StatusLevel::Warning);
+                    show_error(
+                        self.window,
+                        tr!("Vocabulary Conflicts"),
+                        tr!(
+                            "{} term(s) clash with existing values and were not imported.",
+                            report.conflicts.len()
+                        ),
+                        report.conflicts.join("\n"),
+                    );
+                }
+            }
+            Err(e) => {
+                show_error(
+                    self.window,
+                    tr!("Import Error"),
+                    tr!("Could not import '{}'", file.path().display()),
+                    e.to_string(),
+                );
+            }
+        }
+    }
+
+    /// Pick an old and a new version of a taxonomy and compare them in the Compare tab
+    pub async fn compare_files(&self) {
+        let pick = |title| {
+            rfd::AsyncFileDialog::new()
+                .add_filter("JSON", &["json"])
+                .set_title(title)
+                .pick_file()
+        };
+        let Some(old) = pick("Compare: Old Version").await else {
+            return;
+        };
+        let Some(new) = pick("Compare: New Version").await else {
+            return;
+        };
+
+        let compare_result = self
+            .state
+            .borrow_mut()
+            .compare_files(old.path(), new.path());
+        match compare_result {
+            Ok(()) => {
+                update_comparison(self.window, &self.state.borrow());
+                self.window.set_main_tab(5);
+                set_status(
+                    self.window,
+                    tr!(
+                        "Comparing {} with {}",
+                        old.path().display(),
+                        new.path().display()
+                    ),
+                    StatusLevel::Info,
+                );
+            }
+            Err(e) => show_error(
+                self.window,
+                tr!("Compare Error"),
+                tr!("Could not load the files to compare"),
+                e.to_string(),
+            ),
+        }
+    }
+
+    /// Pick a folder and write the current taxonomy into it split by the species of `genus`
+    pub async fn split_by_genus(&self, genus: &str) {
+        let Some(folder) = rfd::AsyncFileDialog::new()
+            .set_title(tr!("Split Into Folder"))
+            .pick_folder()
+            .await
+        else {
+            return;
+        };
+
+        let split_result = self.state.borrow().split_by_genus(genus, folder.path());
+        match split_result {
+            Ok((paths, 0)) => set_status(
+                self.window,
+                tr!(
+                    "Wrote {} file(s) to {}",
+                    paths.len(),
+                    folder.path().display()
+                ),
+                StatusLevel::Success,
+            ),
+            Ok((paths, unassigned)) => set_status(
+                self.window,
+                tr!(
+                    "Wrote {} file(s) to {}; {} item(s) under no species of '{}' were left out",
+                    paths.len(),
+                    folder.path().display(),
+                    unassigned,
+                    genus
+                ),
+                StatusLevel::Warning,
+            ),
+            Err(e) => show_error(
+                self.window,
+                tr!("Split Error"),
+                tr!("Could not split by '{}'", genus),
+                e.to_string(),
+            ),
+        }
+    }
+
+    /// Pick the common version and another version of the current taxonomy, and merge the
+    /// other's changes into it
+    pub async fn merge_changes(&self) {
+        let Some(base) = rfd::AsyncFileDialog::new()
+            .add_filter("JSON", &["json"])
+            .set_title(tr!("Pick the Version Both Started From"))
+            .pick_file()
+            .await
+        else {
+            return;
+        };
+        let Some(theirs) = rfd::AsyncFileDialog::new()
+            .add_filter("JSON", &["json"])
+            .set_title(tr!("Pick the Version to Merge In"))
+            .pick_file()
+            .await
+        else {
+            return;
+        };
+
+        let merge_result = self
+            .state
+            .borrow_mut()
+            .start_merge(base.path(), theirs.path());
+        match merge_result {
+            Ok(conflicts) => self.show_merge(conflicts),
+            Err(e) => show_error(
+                self.window,
+                tr!("Merge Error"),
+                tr!("Could not load the files to merge"),
+                e.to_string(),
+            ),
+        }
+    }
+
+    /// Open the merge dialog for the pending merge's conflicts, or apply it when it has none
+    fn show_merge(&self, conflicts: usize) {
+        if conflicts == 0 {
+            self.apply_merge();
+            return;
+        }
+        update_merge_dialog(self.window, &self.state.borrow());
+        self.window.set_show_merge_dialog(true);
+        set_status(
+            self.window,
+            tr!("{} merge conflict(s) to resolve", conflicts),
+            StatusLevel::Warning,
+        );
+    }
+
+    /// Apply the pending merge with the resolutions picked in the merge dialog
+    pub fn apply_merge(&self) {
+        let apply_result = self.state.borrow_mut().apply_merge();
+
+        match apply_result {
+            Ok(conflicts) => {
+                self.window.set_show_merge_dialog(false);
+                let title = self.state.borrow().get_window_title();
+                self.window.set_window_title(SharedString::from(title));
+                update_ui_from_state(self.window, self.state);
+                set_status(
+                    self.window,
+                    tr!("Merged changes, resolving {} conflict(s)", conflicts),
+                    StatusLevel::Success,
+                );
+            }
+            Err(e) => show_error(
+                self.window,
+                tr!("Merge Error"),
+                tr!("Could not apply the merge"),
+                e.to_string(),
+            ),
+        }
+    }
+
+    /// Pick a CSV/TSV/JSON file of items and open the import preview for it
+    pub async fn import_items(&self) {
+        // Other files are read too when their contents give the format away
+        let extensions = ImporterRegistry::default().extensions();
+        let Some(file) = rfd::AsyncFileDialog::new()
+            .add_filter("Items", &extensions)
+            .add_filter(tr!("All Files"), &["*"])
+            .set_title(tr!("Import Items"))
+            .pick_file()
+            .await
+        else {
+            return;
+        };
+
+        let path = file.path().to_path_buf();
+        let import_result =
+            run_in_background(self.window, &tr!("Reading items"), move |on_progress| {
+                read_import_table_with_progress(&path, on_progress).map_err(|e| e.to_string())
+            })
+            .await
+            .map_err(Box::<dyn Error>::from)
+            .and_then(|table| {
+                self.state
+                    .borrow_mut()
+                    .start_item_import(file.path(), table)
+            });
+
+        match import_result {
+            Ok(()) => {
+                update_import_dialog(self.window, &self.state.borrow());
+                self.window
+                    .set_import_file_name(SharedString::from(file.file_name()));
+                self.window.set_show_import_items_dialog(true);
+            }
+            Err(e) => {
+                show_error(
+                    self.window,
+                    tr!("Import Error"),
+                    tr!("Could not read '{}'", file.path().display()),
+                    e.to_string(),
+                );
+            }
+        }
+    }
+
     /// Revert to the last saved version of the file
     pub async fn revert(&self) {
         let path = self.state.borrow().current_file.clone();
 
         if let Some(file_path) = path {
             // Load the file again
-            let load_result = self.state.borrow_mut().load_from_file(file_path.clone());
+            let schema_locations = self.state.borrow().config.schema_locations.clone();
+            let Some(load_result) = self.read_in_background(&file_path, schema_locations).await
+            else {
+                return;
+            };
 
             match load_result {
-                Ok(_) => {
+                Ok(loaded) => {
+                    // A copy of a URL stays one
+                    let remote = self.state.borrow().remote.clone();
+                    self.state.borrow_mut().install_as(loaded, remote);
+
                     // Update window title
                     let title = self.state.borrow().get_window_title();
                     self.window.set_window_title(SharedString::from(title));
@@ -137,7 +710,7 @@ impl<'a> FileOperations<'a> {
 
                     set_status(
                         self.window,
-                        "Reverted to saved version",
+                        tr!("Reverted to saved version"),
                         StatusLevel::Success,
                     );
                 }
@@ -1,5 +1,13 @@
+pub mod background;
 pub mod file_ops;
+pub mod sync;
 pub mod validation;
 
+pub use background::{run_cancellable_in_background, run_in_background};
 pub use file_ops::FileOperations;
-pub use validation::{collect_facets, validate_item_input};
+pub use sync::publish_sync;
+pub use validation::{
+    apply_path_segment, check_item_form, collect_facets, collect_item_fields,
+    mark_constraint_violations, mark_input_issues, parse_classification_path,
+    suggest_path_segments, validate_item_input, FormIssues,
+};
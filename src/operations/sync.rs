@@ -0,0 +1,38 @@
+use slint::ComponentHandle;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::thread;
+
+use crate::i18n::tr;
+use crate::state::AppState;
+use crate::ui::set_status;
+use crate::{MainWindow, StatusLevel};
+
+/// Send the request publishing the last save, if there is one, on a worker thread; the
+/// outcome shows in the status bar without holding up editing
+pub fn publish_sync(window: &MainWindow, app_state: &Rc<RefCell<AppState>>) {
+    let Some(pending) = app_state.borrow_mut().pending_sync.take() else {
+        return;
+    };
+    let request = match pending {
+        Ok(request) => request,
+        Err(e) => {
+            set_status(
+                window,
+                tr!("Saved, but not published: {}", e),
+                StatusLevel::Warning,
+            );
+            return;
+        }
+    };
+
+    let window_weak = window.as_weak();
+    thread::spawn(move || {
+        let (text, level) = match request.send() {
+            Ok(()) => (tr!("Published to {}", request.url), StatusLevel::Info),
+            Err(e) => (tr!("Publishing failed: {}", e), StatusLevel::Warning),
+        };
+        // The window may be gone by now; there is nothing left to show then
+        let _ = window_weak.upgrade_in_event_loop(move |w| set_status(&w, text, level));
+    });
+}
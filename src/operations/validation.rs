@@ -1,7 +1,11 @@
-use slint::{Model, ModelRc};
+use slint::{Model, ModelRc, SharedString};
 use std::collections::HashMap;
-use taxstud_core::{validate_path_exists, ClassicalHierarchy};
+use taxstud_core::{
+    complete_path, constraint_violations, unique_item_name, validate_path_exists,
+    ClassicalHierarchy, FacetDefinition, Item, ItemField, TaxonomySchema,
+};
 
+use crate::i18n::tr;
 use crate::FacetInput;
 
 /// Validation error with field and message
@@ -20,16 +24,22 @@ impl std::fmt::Display for ValidationError {
 impl std::error::Error for ValidationError {}
 
 /// Validate item input (name and classification path)
+/// The path gets the loader's checks (root first, each step a child of the one before),
+/// so an item that saves here also validates when the file is opened again
+/// The name must not belong to another item of `items`; `original` is the name of the
+/// item being edited, if any
 pub fn validate_item_input(
     name: &str,
     path_str: &str,
     hierarchy: &ClassicalHierarchy,
+    items: &[Item],
+    original: Option<&str>,
 ) -> Result<(String, Vec<String>), ValidationError> {
     // Validate name
-    if name.trim().is_empty() {
+    if let Some(message) = check_name(name, items, original) {
         return Err(ValidationError {
             field: "name".to_string(),
-            message: "Name cannot be empty".to_string(),
+            message,
         });
     }
 
@@ -45,6 +55,16 @@ pub fn validate_item_input(
     Ok((name.trim().to_string(), path))
 }
 
+/// Problem with an item name: empty, or taken by another item
+fn check_name(name: &str, items: &[Item], original: Option<&str>) -> Option<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Some("Name cannot be empty".to_string());
+    }
+    let taken = Some(name) != original && items.iter().any(|item| item.name == name);
+    taken.then(|| tr!("An item named '{}' already exists", name))
+}
+
 /// Parse classification path from comma-separated string
 pub fn parse_classification_path(path_str: &str) -> Result<Vec<String>, ValidationError> {
     let path: Vec<String> = path_str
@@ -56,26 +76,285 @@ pub fn parse_classification_path(path_str: &str) -> Result<Vec<String>, Validati
     if path.is_empty() {
         return Err(ValidationError {
             field: "path".to_string(),
-            message: "Classification path cannot be empty".to_string(),
+            message: tr!("Classification path cannot be empty"),
         });
     }
 
     Ok(path)
 }
 
+/// Suggest the next classification path segments for typed (comma-separated) path text
+/// The segment after the last comma filters the suggestions by case-insensitive prefix
+pub fn suggest_path_segments(path_str: &str, hierarchy: &ClassicalHierarchy) -> Vec<String> {
+    let (prefix, partial) = split_partial_path(path_str, hierarchy);
+    let partial = partial.to_lowercase();
+
+    complete_path(hierarchy, &prefix)
+        .into_iter()
+        .filter(|segment| segment.to_lowercase().starts_with(&partial))
+        .collect()
+}
+
+/// Replace the segment being typed with a picked suggestion
+pub fn apply_path_segment(path_str: &str, segment: &str, hierarchy: &ClassicalHierarchy) -> String {
+    let (mut prefix, _) = split_partial_path(path_str, hierarchy);
+    prefix.push(segment.to_string());
+    prefix.join(", ")
+}
+
+/// Split path text into its completed segments and the partial segment being typed
+/// A last segment that already names a valid next node counts as completed
+fn split_partial_path(path_str: &str, hierarchy: &ClassicalHierarchy) -> (Vec<String>, String) {
+    let mut segments: Vec<String> = path_str.split(',').map(|s| s.trim().to_string()).collect();
+    let partial = segments.pop().unwrap_or_default();
+    segments.retain(|s| !s.is_empty());
+
+    if complete_path(hierarchy, &segments).contains(&partial) {
+        segments.push(partial);
+        return (segments, String::new());
+    }
+
+    (segments, partial)
+}
+
 /// Collect facets from Slint FacetInput model
-pub fn collect_facets(facet_inputs: &ModelRc<FacetInput>) -> HashMap<String, serde_json::Value> {
+/// Values are converted to the type declared by each facet's definition
+pub fn collect_facets(
+    facet_inputs: &ModelRc<FacetInput>,
+    dimensions: &HashMap<String, FacetDefinition>,
+) -> Result<HashMap<String, serde_json::Value>, ValidationError> {
     let mut facets_map = HashMap::new();
 
     for facet_input in facet_inputs.iter() {
+        let name = facet_input.name.to_string();
         let value = facet_input.value.to_string();
-        if !value.trim().is_empty() {
-            facets_map.insert(
-                facet_input.name.to_string(),
-                serde_json::Value::String(value.trim().to_string()),
-            );
+        let definition = dimensions.get(&name);
+
+        if value.trim().is_empty() {
+            if definition.is_some_and(|d| d.required) {
+                return Err(ValidationError {
+                    message: tr!("Facet '{}' is required", name),
+                    field: name,
+                });
+            }
+            continue;
         }
+
+        let typed = match definition {
+            Some(definition) => {
+                definition
+                    .value_from_text(&value)
+                    .map_err(|e| ValidationError {
+                        message: tr!("Facet '{}': {}", name, e),
+                        field: name.clone(),
+                    })?
+            }
+            None => serde_json::Value::String(value.trim().to_string()),
+        };
+        facets_map.insert(name, typed);
     }
 
-    facets_map
+    Ok(facets_map)
+}
+
+/// Collect custom item field values from the field inputs (FacetInput rows)
+/// Empty inputs are left out; values are converted to each field's declared type
+pub fn collect_item_fields(
+    field_inputs: &ModelRc<FacetInput>,
+    fields: &HashMap<String, ItemField>,
+) -> Result<HashMap<String, serde_json::Value>, ValidationError> {
+    let mut values = HashMap::new();
+
+    for field_input in field_inputs.iter() {
+        let name = field_input.name.to_string();
+        let Some(field) = fields.get(&name) else {
+            continue;
+        };
+        let value = field_input.value.to_string();
+
+        if value.trim().is_empty() {
+            if field.required {
+                return Err(ValidationError {
+                    message: tr!("Field '{}' is required", field.display_name(&name)),
+                    field: name,
+                });
+            }
+            continue;
+        }
+
+        let typed = field.value_from_text(&value).map_err(|e| ValidationError {
+            message: tr!("Field '{}': {}", field.display_name(&name), e),
+            field: name.clone(),
+        })?;
+        values.insert(name, typed);
+    }
+
+    Ok(values)
+}
+
+/// Show the schema's broken constraints next to the facet inputs they concern
+/// Inputs that don't parse yet are left out of the check; returns the first violation
+pub fn mark_constraint_violations(
+    facet_inputs: &ModelRc<FacetInput>,
+    schema: &TaxonomySchema,
+) -> Option<String> {
+    let facets: HashMap<String, serde_json::Value> = facet_inputs
+        .iter()
+        .filter(|input| !input.value.trim().is_empty())
+        .filter_map(|input| {
+            let definition = schema.faceted_dimensions.get(input.name.as_str())?;
+            let value = definition.value_from_text(&input.value).ok()?;
+            Some((input.name.to_string(), value))
+        })
+        .collect();
+    let violations = constraint_violations(&facets, schema);
+
+    for row in 0..facet_inputs.row_count() {
+        let Some(mut input) = facet_inputs.row_data(row) else {
+            continue;
+        };
+        let messages: Vec<&str> = violations
+            .iter()
+            .filter(|violation| violation.facet == input.name.as_str())
+            .map(|violation| violation.message.as_str())
+            .collect();
+        let error = SharedString::from(messages.join("; "));
+        if input.error != error {
+            input.error = error;
+            facet_inputs.set_row_data(row, input);
+        }
+    }
+
+    violations
+        .first()
+        .map(|violation| tr!("Facet '{}': {}", violation.facet, violation.message))
+}
+
+/// Problems found in an item form, by input
+/// Facet and field problems are keyed by name, in the order of their input rows
+#[derive(Debug, Default)]
+pub struct FormIssues {
+    pub name: Option<String>,
+    /// A free variant of the name ("Tea (2)") when another item already has it
+    pub unique_name: Option<String>,
+    pub path: Option<String>,
+    pub facets: Vec<(String, String)>,
+    pub fields: Vec<(String, String)>,
+}
+
+impl FormIssues {
+    /// Whether every input passed
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.path.is_none()
+            && self.facets.is_empty()
+            && self.fields.is_empty()
+    }
+
+    /// Number of inputs with a problem
+    pub fn count(&self) -> usize {
+        usize::from(self.name.is_some())
+            + usize::from(self.path.is_some())
+            + self.facets.len()
+            + self.fields.len()
+    }
+}
+
+/// Check every input of an item form as it is being typed: name (against `items` too,
+/// except the `original` item being edited), classification path, facet values (type,
+/// required, schema constraints) and custom fields
+pub fn check_item_form(
+    name: &str,
+    path_str: &str,
+    facet_inputs: &ModelRc<FacetInput>,
+    field_inputs: &ModelRc<FacetInput>,
+    schema: &TaxonomySchema,
+    items: &[Item],
+    original: Option<&str>,
+) -> FormIssues {
+    let mut issues = FormIssues {
+        name: check_name(name, items, original),
+        ..Default::default()
+    };
+
+    if issues.name.is_some() && !name.trim().is_empty() {
+        issues.unique_name = Some(unique_item_name(name.trim(), items));
+    }
+    issues.path = parse_classification_path(path_str)
+        .and_then(|path| {
+            validate_path_exists(&path, &schema.classical_hierarchy).map_err(|e| ValidationError {
+                field: "path".to_string(),
+                message: e,
+            })
+        })
+        .err()
+        .map(|e| e.message);
+
+    let mut facets = HashMap::new();
+    for input in facet_inputs.iter() {
+        let Some(definition) = schema.faceted_dimensions.get(input.name.as_str()) else {
+            continue;
+        };
+        if input.value.trim().is_empty() {
+            if definition.required {
+                issues
+                    .facets
+                    .push((input.name.to_string(), "A value is required".to_string()));
+            }
+            continue;
+        }
+        match definition.value_from_text(&input.value) {
+            Ok(value) => {
+                facets.insert(input.name.to_string(), value);
+            }
+            Err(e) => issues.facets.push((input.name.to_string(), e)),
+        }
+    }
+    for violation in constraint_violations(&facets, schema) {
+        match issues
+            .facets
+            .iter_mut()
+            .find(|(facet, _)| *facet == violation.facet)
+        {
+            Some((_, message)) => {
+                message.push_str("; ");
+                message.push_str(&violation.message);
+            }
+            None => issues.facets.push((violation.facet, violation.message)),
+        }
+    }
+
+    for input in field_inputs.iter() {
+        let Some(field) = schema.item_fields.get(input.name.as_str()) else {
+            continue;
+        };
+        let message = if input.value.trim().is_empty() {
+            field.required.then(|| "A value is required".to_string())
+        } else {
+            field.value_from_text(&input.value).err()
+        };
+        if let Some(message) = message {
+            issues.fields.push((input.name.to_string(), message));
+        }
+    }
+
+    issues
+}
+
+/// Show each input row's problem, if any, beneath it
+pub fn mark_input_issues(inputs: &ModelRc<FacetInput>, issues: &[(String, String)]) {
+    for row in 0..inputs.row_count() {
+        let Some(mut input) = inputs.row_data(row) else {
+            continue;
+        };
+        let error = issues
+            .iter()
+            .find(|(name, _)| *name == input.name.as_str())
+            .map(|(_, message)| SharedString::from(message.as_str()))
+            .unwrap_or_default();
+        if input.error != error {
+            input.error = error;
+            inputs.set_row_data(row, input);
+        }
+    }
 }
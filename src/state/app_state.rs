@@ -1,7 +1,25 @@
-use std::collections::HashMap;
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
 use taxstud_core::*;
 
+use super::config::AppConfig;
+use crate::i18n::tr;
+
+/// How many changes the dashboard lists
+const MAX_RECENT_CHANGES: usize = 20;
+
+/// How many commits of the current file the History tab lists
+const MAX_HISTORY: usize = 100;
+
+/// Importer recorded in the provenance of imported items
+const IMPORTER: &str = "Taxonomy Studio";
+
+/// Data file field the trash is kept in, when it is kept in the file
+const TRASH_FIELD: &str = "trash";
+
 /// Application state management - Domain state only
 /// This struct contains only domain/business logic state
 #[derive(Debug)]
@@ -12,8 +30,10 @@ pub struct AppState {
     pub data: Option<TaxonomyData>,
     /// Path to current data file
     pub current_file: Option<PathBuf>,
-    /// Path to current schema file (for reference)
+    /// Path to current schema file (for reference); None when the data file embeds it
     pub schema_file: Option<PathBuf>,
+    /// Whether saves embed the schema in the data file
+    pub embed_schema: bool,
     /// Whether there are unsaved changes
     pub dirty: bool,
     /// Currently selected item index
@@ -22,53 +42,369 @@ pub struct AppState {
     pub filters: Filters,
     /// Active sort field (e.g., "name")
     pub sort_by: Option<String>,
-    /// Currently displayed items (after filtering and sorting) - for index mapping
-    pub displayed_items: Vec<Item>,
+    /// Whether sort_by sorts descending
+    pub sort_descending: bool,
+    /// Indices into the data's items of the displayed items, after filtering and sorting
+    pub displayed: Vec<usize>,
+    /// Facet the item list is grouped by (None = a flat list)
+    pub group_by: Option<String>,
+    /// Whether items without a value for group_by get a group of their own, or are left out
+    pub show_missing_group: bool,
+    /// Facet summarized in each group header: min/max/avg of a number facet, the distinct
+    /// values of any other (None = just the count)
+    pub group_summary_facet: Option<String>,
+    /// Groups whose items the list hides
+    pub collapsed_groups: HashSet<GroupKey>,
+    /// Rows of the item list, in order - maps list indices to displayed
+    pub list_rows: Vec<ListRow>,
+    /// Language to show names and values in (None = canonical names)
+    pub display_lang: Option<String>,
+    /// Descriptions of the edits since the file was opened, newest first
+    pub recent_changes: Vec<String>,
+    /// Item import being previewed, until it is committed or cancelled
+    pub pending_import: Option<ItemImport>,
+    /// Settings kept between runs
+    pub config: AppConfig,
+    /// Open .taxproj project, whose member taxonomies the sidebar lists
+    pub project: Option<Project>,
+    /// Git repository holding the current file (None when it isn't in one)
+    pub git: Option<GitFile>,
+    /// Commits that changed the current file, newest first
+    pub history: Vec<Revision>,
+    /// Version shown read-only in the History tab: its index in `history` and its data
+    pub historical: Option<(usize, TaxonomyData)>,
+    /// Two versions shown side by side in the Compare tab
+    pub comparison: Option<Comparison>,
+    /// Merge whose conflicts are being resolved, until it is applied or cancelled
+    pub pending_merge: Option<PendingMerge>,
+    /// Deleted items, newest first; restoring one doesn't undo later edits
+    pub trash: Vec<TrashedItem>,
+    /// Whether saves keep the trash in the data file; otherwise it lasts for the session
+    pub keep_trash: bool,
+    /// Formats File -> Export offers
+    pub exporters: ExporterRegistry,
+    /// Request publishing the last save to the webhook in the file's sync.json, until
+    /// `publish_sync` sends it; an error when the sync.json couldn't be read
+    pub pending_sync: Option<Result<SyncRequest, String>>,
+    /// The URL the current file was opened from; `current_file` is then its cached copy
+    pub remote: Option<RemoteSource>,
+}
+
+/// Where a taxonomy opened from a URL came from
+#[derive(Debug, Clone)]
+pub struct RemoteSource {
+    pub url: String,
+    /// Whether saves go back to the URL (it is in the config's `writable_urls`)
+    pub writable: bool,
+    /// ETag of the version on the server the copy matches, checked when saving back
+    pub etag: Option<String>,
+}
+
+/// A row of the item list
+#[derive(Debug, Clone, PartialEq)]
+pub enum ListRow {
+    /// Header of a group: its value (or the missing-value bucket) and a summary of its
+    /// displayed items (their count, and group_summary_facet's values or numbers)
+    Group {
+        key: GroupKey,
+        summary: GroupSummary,
+    },
+    /// The item at this index of displayed
+    Item(usize),
+}
+
+/// An item deleted from the taxonomy, and when
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashedItem {
+    pub item: Item,
+    /// Unix seconds
+    pub deleted_at: i64,
+}
+
+/// Two versions of a taxonomy being compared, and the row picked in the Compare tab
+#[derive(Debug)]
+pub struct Comparison {
+    /// Where the old version comes from, e.g. a file name or "data.json at HEAD"
+    pub old_label: String,
+    pub new_label: String,
+    pub diff: TaxonomyDiff,
+    pub selected: Option<usize>,
+}
+
+/// A three-way merge of the current taxonomy with another version of it, and how each of
+/// its conflicts is to be resolved
+#[derive(Debug)]
+pub struct PendingMerge {
+    pub result: MergeResult,
+    /// One per conflict; our side until the user picks another
+    pub resolutions: Vec<Resolution>,
+    /// Whether the current file is in conflict in Git: applying the merge then saves it
+    /// and marks the conflict resolved
+    pub in_git: bool,
+}
+
+/// A data file's schema reference points at a file that doesn't exist
+/// (and the user hasn't located it elsewhere yet)
+#[derive(Debug)]
+pub struct MissingSchema {
+    pub schema_path: PathBuf,
+}
+
+impl fmt::Display for MissingSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Schema file not found: {}", self.schema_path.display())
+    }
+}
+
+impl Error for MissingSchema {}
+
+/// Error of work done on a worker thread
+pub type LoadError = Box<dyn Error + Send + Sync>;
+
+/// A data file read with its schema, ready to become the current file
+/// Reading touches neither the app state nor the UI, so it can run on a worker thread
+pub struct LoadedFile {
+    path: PathBuf,
+    schema_path: PathBuf,
+    schema: TaxonomySchema,
+    data: TaxonomyData,
+    /// The trash kept in the file, when it keeps one
+    trash: Option<Vec<TrashedItem>>,
+}
+
+impl LoadedFile {
+    /// Read a data file and its schema as `options` say, reporting progress
+    /// Fails with MissingSchema when the referenced schema file can't be found, here or
+    /// where the user located it (`schema_locations`)
+    pub fn read(
+        path: PathBuf,
+        schema_locations: &HashMap<PathBuf, PathBuf>,
+        options: &LoadOptions,
+        on_progress: &mut ProgressFn,
+    ) -> Result<Self, LoadError> {
+        let contents = read_to_string_with_progress(&path, on_progress)?;
+        let schema_path = locate_schema(&contents, &path, schema_locations)?;
+
+        on_progress(&Progress::stage("Loading schema"));
+        let schema = load_schema(&schema_path).map_err(sendable)?;
+        let mut data =
+            parse_data_with_options(&contents, &schema, options, on_progress).map_err(sendable)?;
+        let trash = data
+            .extra
+            .remove(TRASH_FIELD)
+            .map(serde_json::from_value)
+            .transpose()?;
+
+        Ok(Self {
+            path,
+            schema_path,
+            schema,
+            data,
+            trash,
+        })
+    }
+}
+
+/// A core error as a LoadError: a JSON parse error as it is, so the error dialog can show
+/// where, anything else as its message
+fn sendable(error: Box<dyn Error>) -> LoadError {
+    match error.downcast::<JsonParseError>() {
+        Ok(parse_error) => parse_error,
+        Err(error) => error.to_string().into(),
+    }
+}
+
+/// The schema file a data file uses: the one it references (or itself, when it embeds
+/// its schema), or where the user located a referenced file that is missing
+fn locate_schema(
+    contents: &str,
+    path: &Path,
+    schema_locations: &HashMap<PathBuf, PathBuf>,
+) -> Result<PathBuf, LoadError> {
+    let schema_path = schema_path_from_contents(contents, path).map_err(sendable)?;
+    if schema_path.is_file() {
+        return Ok(schema_path);
+    }
+    match schema_locations.get(&schema_path) {
+        Some(located) if located.is_file() => Ok(located.clone()),
+        _ => Err(Box::new(MissingSchema { schema_path })),
+    }
+}
+
+/// Header of the group of items without a value for `facet`
+fn missing_group_label(facet: &str) -> String {
+    tr!("Without {}", facet)
 }
 
 #[allow(dead_code)]
 impl AppState {
     pub fn new() -> Self {
+        // The sort picked last time stays
+        let config = AppConfig::load();
         Self {
             schema: None,
             data: None,
             current_file: None,
             schema_file: None,
+            embed_schema: false,
             dirty: false,
             selected_item: None,
-            filters: Filters {
-                genera: Vec::new(),
-                facets: HashMap::new(),
-            },
-            sort_by: None,
-            displayed_items: Vec::new(),
+            filters: Filters::default(),
+            sort_by: config.sort_by.clone(),
+            sort_descending: config.sort_descending,
+            displayed: Vec::new(),
+            group_by: None,
+            show_missing_group: true,
+            group_summary_facet: None,
+            collapsed_groups: HashSet::new(),
+            list_rows: Vec::new(),
+            display_lang: None,
+            recent_changes: Vec::new(),
+            pending_import: None,
+            config,
+            project: None,
+            git: None,
+            history: Vec::new(),
+            historical: None,
+            comparison: None,
+            pending_merge: None,
+            trash: Vec::new(),
+            keep_trash: false,
+            exporters: ExporterRegistry::default(),
+            pending_sync: None,
+            remote: None,
         }
     }
 
     /// Load a data file with its schema
+    /// Fails with MissingSchema when the referenced schema file can't be found
     pub fn load_from_file(&mut self, path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
-        let (data, schema) = load_data_with_auto_schema(&path)?;
+        let options = self.config.settings.load_options();
+        let loaded = LoadedFile::read(path, &self.config.schema_locations, &options, &mut |_| {})
+            .map_err(|e| e as Box<dyn Error>)?;
+        self.install(loaded);
+        Ok(())
+    }
+
+    /// Make a file read with LoadedFile::read the current file
+    pub fn install(&mut self, loaded: LoadedFile) {
+        self.install_as(loaded, None);
+    }
+
+    /// Make a loaded file the current one; `remote` when it is the cached copy of a URL,
+    /// which then stands in for the file (e.g. in the window title) and isn't a recent file
+    pub fn install_as(&mut self, loaded: LoadedFile, remote: Option<RemoteSource>) {
+        let LoadedFile {
+            path,
+            schema_path,
+            schema,
+            data,
+            trash,
+        } = loaded;
+
+        // A trash kept in the file comes back; otherwise the session's trash is dropped
+        self.keep_trash = trash.is_some();
+        self.trash = trash.unwrap_or_default();
 
-        self.data = Some(data.clone());
+        self.data = Some(data);
         self.schema = Some(schema);
         self.current_file = Some(path.clone());
+        if remote.is_none() {
+            self.note_recent_file(&path);
+        }
+        self.remote = remote;
 
-        // Reconstruct schema_file path
-        let data_dir = path.parent().unwrap();
-        self.schema_file = Some(data_dir.join(&data.schema));
+        // A data file that embeds its schema is its own schema file
+        self.embed_schema = schema_path == path;
+        self.schema_file = (!self.embed_schema).then_some(schema_path);
 
         self.dirty = false;
         self.selected_item = None;
+        self.recent_changes.clear();
+        self.pending_import = None;
+        self.pending_merge = None;
+        self.refresh_git();
 
+        // Members of the open project show in its language and sort order
+        let is_member = self.project_member_index().is_some();
+        if let Some(project) = self.project.as_ref().filter(|_| is_member) {
+            if let Some(ref lang) = project.settings.lang {
+                self.display_lang = Some(lang.clone());
+            }
+            if let Some(ref sort) = project.settings.sort {
+                self.sort_by = Some(sort.clone());
+            }
+        }
+    }
+
+    /// Open a .taxproj project; its members are loaded one at a time with load_from_file
+    pub fn open_project(&mut self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        self.project = Some(Project::load(path)?);
         Ok(())
     }
 
+    /// Path of the open project's member taxonomy at `index`
+    pub fn project_member(&self, index: usize) -> Option<PathBuf> {
+        self.project
+            .as_ref()?
+            .taxonomy_paths()
+            .into_iter()
+            .nth(index)
+    }
+
+    /// Index of the current file among the open project's members
+    pub fn project_member_index(&self) -> Option<usize> {
+        let current = self.current_file.as_ref()?;
+        self.project
+            .as_ref()?
+            .taxonomy_paths()
+            .iter()
+            .position(|path| path == current)
+    }
+
+    /// Put a file at the top of File → Open Recent
+    fn note_recent_file(&mut self, path: &Path) {
+        self.config.note_recent_file(path);
+        // Failing to remember only leaves the list as it was next time
+        let _ = self.config.save();
+    }
+
+    /// Sort the item list by `field` ("name" or a facet; None for the file's order), kept
+    /// in the config for the next run
+    pub fn set_sort(&mut self, field: Option<String>, descending: bool) {
+        self.sort_by = field.clone();
+        self.sort_descending = descending;
+        self.config.sort_by = field;
+        self.config.sort_descending = descending;
+        // Failing to remember only means the list starts unsorted next time
+        let _ = self.config.save();
+    }
+
+    /// Remember where the user located a missing schema file, once a file loads with it
+    pub fn remember_schema_location(&mut self, missing: PathBuf, located: PathBuf) {
+        self.config.schema_locations.insert(missing, located);
+        // Failing to remember only means asking again next time
+        let _ = self.config.save();
+    }
+
     /// Save data to current file
     pub fn save(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(ref remote) = self.remote {
+            if !remote.writable {
+                return Err(tr!(
+                    "{} is read-only; use Save As to keep a local copy",
+                    remote.url
+                )
+                .into());
+            }
+        }
         if let Some(ref data) = self.data {
             if let Some(ref path) = self.current_file {
-                save_data(data, path)?;
+                let publisher = SyncPublisher::before_save(path);
+                self.write_data(data, path)?;
+                self.upload_to_remote()?;
                 self.dirty = false;
+                self.queue_sync(publisher);
                 Ok(())
             } else {
                 Err("No file path set".into())
@@ -81,44 +417,922 @@ impl AppState {
     /// Save data to a new file
     pub fn save_as(&mut self, path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
         if let Some(ref data) = self.data {
-            save_data(data, &path)?;
+            let publisher = SyncPublisher::before_save(&path);
+            self.write_data(data, &path)?;
+            self.queue_sync(publisher);
+            self.note_recent_file(&path);
             self.current_file = Some(path);
+            self.remote = None;
             self.dirty = false;
+            self.refresh_git();
             Ok(())
         } else {
             Err("No data to save".into())
         }
     }
 
-    /// Create a new empty taxonomy with default schema
-    pub fn create_new(&mut self) {
-        let default_schema = TaxonomySchema {
-            schema_id: "default".to_string(),
-            title: "Default Schema".to_string(),
-            description: Some("Default taxonomy schema".to_string()),
-            classical_hierarchy: ClassicalHierarchy {
-                root: "Root".to_string(),
-                children: None,
+    /// Whether taxonomies opened from `url` are saved back to it (config `writable_urls`)
+    pub fn is_writable_url(&self, url: &str) -> bool {
+        self.config
+            .writable_urls
+            .iter()
+            .any(|prefix| !prefix.is_empty() && url.starts_with(prefix.as_str()))
+    }
+
+    /// Whether saving would fail because the file is a copy of a read-only URL
+    pub fn is_read_only(&self) -> bool {
+        self.remote.as_ref().is_some_and(|remote| !remote.writable)
+    }
+
+    /// Send the current file, just written, to the URL it was opened from, if any
+    /// When that fails the copy no longer matches the server, so it is downloaded again
+    /// the next time the URL is opened
+    fn upload_to_remote(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let (Some(remote), Some(path)) = (self.remote.as_mut(), self.current_file.as_ref()) else {
+            return Ok(());
+        };
+        let cache = RemoteCache::default();
+        let contents = std::fs::read(path)?;
+        match cache.upload(&remote.url, &contents, remote.etag.as_deref()) {
+            Ok(etag) => {
+                remote.etag = etag;
+                Ok(())
+            }
+            Err(e) => {
+                let _ = cache.invalidate(&remote.url);
+                Err(e)
+            }
+        }
+    }
+
+    /// Keep the request publishing a save for `publish_sync`; a file without a sync.json
+    /// publishes nothing, and neither does a diff payload when no item changed
+    fn queue_sync(&mut self, publisher: Result<Option<SyncPublisher>, Box<dyn Error>>) {
+        self.pending_sync = match publisher {
+            Ok(Some(publisher)) => match (&self.schema, &self.data) {
+                (Some(schema), Some(data)) => publisher.request(schema, data).map(Ok),
+                _ => None,
             },
-            faceted_dimensions: HashMap::from([(
-                "category".to_string(),
-                vec!["uncategorized".to_string()],
-            )]),
-            json_schema: None,
+            Ok(None) => None,
+            Err(e) => Some(Err(e.to_string())),
         };
+    }
+
+    /// Write the data (and the trash, when kept in the file), first backing up the file
+    /// it replaces as the settings say
+    fn write_data(
+        &self,
+        data: &TaxonomyData,
+        path: &Path,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        rotate_backups(path, self.config.settings.backup_count)?;
 
-        let default_data = TaxonomyData {
-            schema: "schema.json".to_string(),
-            items: Vec::new(),
-            extra: HashMap::new(),
+        let with_trash;
+        let data = if self.keep_trash {
+            let mut copy = data.clone();
+            copy.extra
+                .insert(TRASH_FIELD.to_string(), serde_json::to_value(&self.trash)?);
+            with_trash = copy;
+            &with_trash
+        } else {
+            data
         };
 
-        self.schema = Some(default_schema);
-        self.data = Some(default_data);
+        let inline_schema = self.schema.as_ref().filter(|_| self.embed_schema);
+        save_data_with_options(data, inline_schema, path, &self.save_options())
+    }
+
+    /// How saves format the data file: the open project's options for its members,
+    /// the defaults for anything else
+    pub fn save_options(&self) -> SaveOptions {
+        let is_member = self.project_member_index().is_some();
+        self.project
+            .as_ref()
+            .filter(|_| is_member)
+            .map(|project| project.settings.save.clone())
+            .unwrap_or_default()
+    }
+
+    /// Embed the schema in the data file from the next save on, or stop doing so
+    pub fn set_embed_schema(&mut self, embed: bool) {
+        if self.embed_schema != embed {
+            self.embed_schema = embed;
+            self.dirty = true;
+        }
+    }
+
+    /// Keep the trash in the data file from the next save on, or stop doing so
+    pub fn set_keep_trash(&mut self, keep: bool) {
+        if self.keep_trash != keep {
+            self.keep_trash = keep;
+            self.dirty = true;
+        }
+    }
+
+    /// Import a controlled vocabulary file into a faceted dimension of the schema
+    /// The schema file is rewritten when values were added and the schema has one;
+    /// an embedded schema is written with the data on the next save
+    pub fn import_vocabulary(
+        &mut self,
+        path: &Path,
+        dimension: &str,
+    ) -> Result<VocabularyImport, Box<dyn std::error::Error>> {
+        let schema = self.schema.as_mut().ok_or("No schema loaded")?;
+        let terms = read_vocabulary(path)?;
+        let report = schema.add_facet_values(dimension, terms)?;
+
+        if !report.added.is_empty() {
+            if let Some(ref schema_file) = self.schema_file {
+                save_schema(schema, schema_file)?;
+            } else if self.embed_schema {
+                self.dirty = true;
+            }
+            let change = tr!(
+                "Imported {} value(s) into '{}'",
+                report.added.len(),
+                dimension
+            );
+            self.note_change(change);
+        }
+
+        Ok(report)
+    }
+
+    /// Start previewing the import of a table of items read from the CSV/TSV/JSON or
+    /// spreadsheet file at `path`; the items record the file as their source
+    pub fn start_item_import(
+        &mut self,
+        path: &Path,
+        table: ImportTable,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let schema = self.schema.as_ref().ok_or("No schema loaded")?;
+        if table.rows.is_empty() {
+            return Err("The file has no rows to import".into());
+        }
+
+        let mut import = ItemImport::new(table, schema);
+        import.source = Some(Provenance::now(path.display().to_string(), IMPORTER));
+        self.pending_import = Some(import);
+        Ok(())
+    }
+
+    /// Items the pending import would add, with their issues (empty when nothing is pending)
+    pub fn preview_item_import(&self) -> Vec<ImportRow> {
+        match (&self.pending_import, &self.schema) {
+            (Some(import), Some(schema)) => import.preview(
+                schema,
+                self.get_items().map(Vec::as_slice).unwrap_or_default(),
+            ),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Add the pending import's valid items, or all of them with `include_invalid`
+    /// Returns how many items were added and how many of those have issues
+    pub fn commit_item_import(
+        &mut self,
+        include_invalid: bool,
+    ) -> Result<(usize, usize), Box<dyn std::error::Error>> {
+        let rows = self.preview_item_import();
+        let items = self.get_items_mut().ok_or("No data loaded")?;
+
+        let mut added = 0;
+        let mut flagged = 0;
+        for row in rows {
+            if !row.is_valid() {
+                if !include_invalid {
+                    continue;
+                }
+                flagged += 1;
+            }
+            items.push(row.item);
+            added += 1;
+        }
+        self.pending_import = None;
+
+        if added > 0 {
+            let change = match flagged {
+                0 => tr!("Imported {} item(s)", added),
+                _ => tr!("Imported {} item(s), {} with issues", added, flagged),
+            };
+            self.record_change(change);
+        }
+        Ok((added, flagged))
+    }
+
+    /// Delete the item named `name`, moving it to the trash
+    pub fn delete_item(&mut self, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let items = self.get_items_mut().ok_or("No data loaded")?;
+        let pos = items
+            .iter()
+            .position(|item| item.name == name)
+            .ok_or_else(|| tr!("No item named '{}'", name))?;
+        let item = items.remove(pos);
+
+        let deleted_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs() as i64);
+        self.trash.insert(0, TrashedItem { item, deleted_at });
+        self.record_change(tr!("Deleted '{}'", name));
+        Ok(())
+    }
+
+    /// Add a copy of the item named `name` right after it, as "name (2)" or the next free
+    /// name; returns the copy's name
+    pub fn duplicate_item(&mut self, name: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let items = self.get_items_mut().ok_or("No data loaded")?;
+        let pos = items
+            .iter()
+            .position(|item| item.name == name)
+            .ok_or_else(|| tr!("No item named '{}'", name))?;
+
+        let mut copy = items[pos].clone();
+        copy.name = unique_item_name(name, items);
+        let copy_name = copy.name.clone();
+        items.insert(pos + 1, copy);
+
+        self.record_change(tr!("Duplicated '{}' as '{}'", name, copy_name));
+        Ok(copy_name)
+    }
+
+    /// Put the trashed item at `index` back into the taxonomy
+    /// Fails when an item of the same name has been added since
+    pub fn restore_from_trash(
+        &mut self,
+        index: usize,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let name = self
+            .trash
+            .get(index)
+            .map(|trashed| trashed.item.name.clone())
+            .ok_or("No such item in the trash")?;
+        let items = self.get_items().ok_or("No data loaded")?;
+        if items.iter().any(|item| item.name == name) {
+            return Err(tr!("An item named '{}' already exists", name).into());
+        }
+
+        let trashed = self.trash.remove(index);
+        self.get_items_mut()
+            .ok_or("No data loaded")?
+            .push(trashed.item);
+        self.record_change(tr!("Restored '{}' from the trash", name));
+        Ok(name)
+    }
+
+    /// Drop everything in the trash for good
+    pub fn empty_trash(&mut self) {
+        self.trash.clear();
+        if self.keep_trash {
+            self.dirty = true;
+        }
+    }
+
+    /// Show the items at `indices` of the data in the item list, under a header per group
+    /// when grouping
+    /// Groups follow the schema's display order, then their names, with the group of items
+    /// without a value last (or left out, see show_missing_group); items keep their order
+    /// within a group, and ones with several values appear in each group
+    pub fn set_displayed(&mut self, indices: Vec<usize>) {
+        let items = self.get_items().map(Vec::as_slice).unwrap_or_default();
+        let group_by = self
+            .group_by
+            .as_ref()
+            .filter(|facet| self.facet_names().contains(facet));
+        self.list_rows = match group_by {
+            Some(facet) => {
+                let position: HashMap<usize, usize> = indices
+                    .iter()
+                    .enumerate()
+                    .map(|(position, &index)| (index, position))
+                    .collect();
+                let options = GroupOptions {
+                    missing: if self.show_missing_group {
+                        MissingGroup::Bucket(missing_group_label(facet))
+                    } else {
+                        MissingGroup::Skip
+                    },
+                };
+                let groups = group_indices_with_options(items, &indices, facet, &options)
+                    .expect("bucketing or skipping missing values never fails");
+                let mut values = Vec::new();
+                let mut missing = None;
+                for key in get_sorted_group_names(&groups) {
+                    match key {
+                        GroupKey::Value(value) => values.push(value),
+                        key @ GroupKey::Missing(_) => missing = Some(key),
+                    }
+                }
+                if let Some(schema) = &self.schema {
+                    schema.sort_by_display_order(facet, &mut values);
+                }
+                let keys = values.into_iter().map(GroupKey::Value).chain(missing);
+                let summarized = self.group_summary_facet.as_deref();
+                let numeric = summarized.filter(|facet| {
+                    self.get_faceted_dimensions()
+                        .and_then(|dimensions| dimensions.get(*facet))
+                        .is_some_and(|definition| definition.kind == FacetKind::Number)
+                });
+                let distinct = summarized.filter(|_| numeric.is_none());
+                let mut rows = Vec::new();
+                for key in keys {
+                    let members = &groups[&key];
+                    let collapsed = self.collapsed_groups.contains(&key);
+                    let summary =
+                        GroupSummary::of(members.iter().map(|&i| &items[i]), distinct, numeric);
+                    rows.push(ListRow::Group { key, summary });
+                    if !collapsed {
+                        rows.extend(members.iter().map(|index| ListRow::Item(position[index])));
+                    }
+                }
+                rows
+            }
+            None => (0..indices.len()).map(ListRow::Item).collect(),
+        };
+        self.displayed = indices;
+    }
+
+    /// The item at `index` of displayed
+    pub fn displayed_item(&self, index: usize) -> Option<&Item> {
+        self.get_items()?.get(*self.displayed.get(index)?)
+    }
+
+    /// The displayed items, in list order
+    pub fn displayed_items(&self) -> impl Iterator<Item = &Item> {
+        (0..self.displayed.len()).filter_map(|index| self.displayed_item(index))
+    }
+
+    /// Give items without a value their own group, or leave them out of the grouped list
+    pub fn set_show_missing_group(&mut self, show: bool) {
+        self.show_missing_group = show;
+    }
+
+    /// Summarize `facet` in the group headers, or show just their counts
+    pub fn set_group_summary_facet(&mut self, facet: Option<String>) {
+        self.group_summary_facet = facet;
+    }
+
+    /// Group the item list by `facet`, or not at all; all groups start expanded
+    pub fn set_group_by(&mut self, facet: Option<String>) {
+        self.group_by = facet;
+        self.collapsed_groups.clear();
+    }
+
+    /// Collapse the group `key`, or expand it if it is collapsed
+    pub fn toggle_group(&mut self, key: &GroupKey) {
+        if !self.collapsed_groups.remove(key) {
+            self.collapsed_groups.insert(key.clone());
+        }
+    }
+
+    /// Expand the collapsed groups the displayed item named `name` is in
+    /// Returns whether any group was expanded
+    pub fn expand_groups_of(&mut self, name: &str) -> bool {
+        let (Some(facet), Some(item)) = (
+            self.group_by.as_ref(),
+            self.displayed_items().find(|item| item.name == name),
+        ) else {
+            return false;
+        };
+        let mut groups: Vec<GroupKey> = item
+            .get_facet_as_vec(facet)
+            .into_iter()
+            .map(GroupKey::Value)
+            .collect();
+        if groups.is_empty() {
+            groups.push(GroupKey::Missing(missing_group_label(facet)));
+        }
+        let before = self.collapsed_groups.len();
+        self.collapsed_groups
+            .retain(|group| !groups.contains(group));
+        self.collapsed_groups.len() != before
+    }
+
+    /// Index in displayed of the item at `row` of the list (None for a group header)
+    pub fn displayed_index(&self, row: i32) -> Option<usize> {
+        match self.list_rows.get(usize::try_from(row).ok()?)? {
+            ListRow::Item(index) => Some(*index),
+            ListRow::Group { .. } => None,
+        }
+    }
+
+    /// The item at `row` of the list
+    pub fn item_at_row(&self, row: i32) -> Option<&Item> {
+        self.displayed_item(self.displayed_index(row)?)
+    }
+
+    /// The group whose header is at `row` of the list
+    pub fn group_at_row(&self, row: i32) -> Option<&GroupKey> {
+        match self.list_rows.get(usize::try_from(row).ok()?)? {
+            ListRow::Group { key, .. } => Some(key),
+            ListRow::Item(_) => None,
+        }
+    }
+
+    /// First row of the list showing the item named `name`
+    pub fn row_of_item(&self, name: &str) -> Option<usize> {
+        self.list_rows.iter().position(|row| {
+            matches!(row, ListRow::Item(index)
+                if self.displayed_item(*index).is_some_and(|item| item.name == name))
+        })
+    }
+
+    /// Items a bulk edit applies to: the displayed item at `selected`, or all displayed items
+    pub fn batch_targets(&self, selected: Option<usize>) -> Vec<&Item> {
+        match selected {
+            Some(index) => self.displayed_item(index).into_iter().collect(),
+            None => self.displayed_items().collect(),
+        }
+    }
+
+    /// New names of the items a batch rename applies to: the displayed item at `selected`,
+    /// or all displayed items; names other items keep are flagged
+    pub fn preview_batch_rename(
+        &self,
+        pattern: &RenamePattern,
+        selected: Option<usize>,
+    ) -> Result<Vec<Rename>, String> {
+        let mut renames = batch_rename(self.batch_targets(selected), pattern)?;
+        flag_taken_names(
+            &mut renames,
+            self.get_items().map(Vec::as_slice).unwrap_or_default(),
+        );
+        Ok(renames)
+    }
+
+    /// Rename items as previewed by `preview_batch_rename`, skipping flagged ones
+    /// Returns how many items were renamed
+    pub fn apply_batch_rename(
+        &mut self,
+        pattern: &RenamePattern,
+        selected: Option<usize>,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let renames = self.preview_batch_rename(pattern, selected)?;
+        let items = self.get_items_mut().ok_or("No data loaded")?;
+        let renamed = apply_renames(items, &renames);
+        if renamed > 0 {
+            self.record_change(tr!("Renamed {} item(s)", renamed));
+        }
+        Ok(renamed)
+    }
+
+    /// Hierarchy node at `index` of the reclassify dialog's list: the root, then every
+    /// species in pre-order
+    pub fn node_at(&self, index: usize) -> Option<String> {
+        let hierarchy = self.get_classical_hierarchy()?;
+        match index {
+            0 => Some(hierarchy.root.clone()),
+            _ => hierarchy
+                .iter_preorder()
+                .nth(index - 1)
+                .map(|(_, node)| node.species.clone()),
+        }
+    }
+
+    /// Move the displayed item at `selected`, or all displayed items, to `node`
+    /// Returns how many items moved
+    pub fn reclassify_items(
+        &mut self,
+        node: &str,
+        selected: Option<usize>,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let names: HashSet<String> = self
+            .batch_targets(selected)
+            .iter()
+            .map(|item| item.name.clone())
+            .collect();
+        let hierarchy = self
+            .schema
+            .as_ref()
+            .map(|schema| &schema.classical_hierarchy)
+            .ok_or("No schema loaded")?;
+        let items = self
+            .data
+            .as_mut()
+            .map(|data| &mut data.items)
+            .ok_or("No data loaded")?;
+
+        let targets = items.iter_mut().filter(|item| names.contains(&item.name));
+        let moved = reclassify(targets, node, hierarchy)?;
+        if moved > 0 {
+            self.record_change(tr!("Moved {} item(s) to '{}'", moved, node));
+        }
+        Ok(moved)
+    }
+
+    /// Deprecate the item named `name`, or restore it
+    pub fn set_item_deprecated(
+        &mut self,
+        name: &str,
+        deprecated: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let item = self
+            .get_items_mut()
+            .and_then(|items| items.iter_mut().find(|item| item.name == name))
+            .ok_or_else(|| tr!("No item named '{}'", name))?;
+        if item.is_deprecated() == deprecated {
+            return Ok(());
+        }
+        item.set_deprecated(deprecated);
+
+        let change = if deprecated {
+            tr!("Deprecated '{}'", name)
+        } else {
+            tr!("Restored '{}'", name)
+        };
+        self.record_change(change);
+        Ok(())
+    }
+
+    /// Run a Rhai script against the items (see `taxstud_core::script`)
+    /// Nothing changes when the script fails
+    pub fn run_script(&mut self, source: &str) -> Result<ScriptReport, Box<dyn std::error::Error>> {
+        let schema = self.schema.as_ref().ok_or("No schema loaded")?;
+        let items = self
+            .data
+            .as_mut()
+            .map(|data| &mut data.items)
+            .ok_or("No data loaded")?;
+
+        let report = run_script(source, schema, items)?;
+        if report.changed + report.added > 0 {
+            self.record_change(tr!(
+                "Script changed {} item(s), added {}",
+                report.changed,
+                report.added
+            ));
+        }
+        Ok(report)
+    }
+
+    /// Item templates available to the current file
+    /// Members of the open project use the project's, anything else the app config's
+    pub fn item_templates(&self) -> &[ItemTemplate] {
+        let is_member = self.project_member_index().is_some();
+        match self.project.as_ref().filter(|_| is_member) {
+            Some(project) => &project.settings.item_templates,
+            None => &self.config.item_templates,
+        }
+    }
+
+    /// Save the item's path and facets as a template named after it, replacing one of
+    /// the same name; written to the project file or the app config right away
+    pub fn save_item_template(&mut self, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let item = self
+            .get_items()
+            .and_then(|items| items.iter().find(|item| item.name == name))
+            .ok_or_else(|| tr!("No item named '{}'", name))?;
+        let template = ItemTemplate::from_item(name, item);
+
+        let is_member = self.project_member_index().is_some();
+        match self.project.as_mut().filter(|_| is_member) {
+            Some(project) => {
+                store_template(&mut project.settings.item_templates, template);
+                let path = project.path.clone().ok_or("The project has no file")?;
+                project.save(path)?;
+            }
+            None => {
+                store_template(&mut self.config.item_templates, template);
+                self.config.save()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the hierarchy map (treemap, or sunburst) of the current items to a file
+    /// Deprecated items are left out unless they are shown
+    /// A .png path is rasterized when built with the "png" feature; anything else gets SVG
+    pub fn export_hierarchy_map(
+        &self,
+        path: &Path,
+        sunburst: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let schema = self.schema.as_ref().ok_or("No schema loaded")?;
+        let items: Vec<Item> = self
+            .get_items()
+            .into_iter()
+            .flatten()
+            .filter(|item| self.filters.include_deprecated || !item.is_deprecated())
+            .cloned()
+            .collect();
+        let items = items.as_slice();
+        let hierarchy = &schema.classical_hierarchy;
+        let lang = self.display_lang.as_deref();
+
+        let mut svg = Vec::new();
+        if sunburst {
+            write_sunburst_svg(hierarchy, items, viz::SUNBURST_SIZE, lang, &mut svg)?;
+        } else {
+            let (width, height) = (viz::TREEMAP_WIDTH, viz::TREEMAP_HEIGHT);
+            write_treemap_svg(hierarchy, items, width, height, lang, &mut svg)?;
+        }
+        write_image(path, String::from_utf8(svg)?)
+    }
+
+    /// Export the current items with the exporter registered as `id`
+    /// Deprecated items are left out unless they are shown; drawings are rasterized for a
+    /// .png path like `export_hierarchy_map`
+    pub fn export_as(&self, id: &str, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let schema = self.schema.as_ref().ok_or("No schema loaded")?;
+        let data = self.data.as_ref().ok_or("No data loaded")?;
+        let exporter = self
+            .exporters
+            .get(id)
+            .ok_or_else(|| format!("Unknown export format '{}'", id))?;
+        let data = TaxonomyData {
+            schema: data.schema.clone(),
+            items: data
+                .items
+                .iter()
+                .filter(|item| self.filters.include_deprecated || !item.is_deprecated())
+                .cloned()
+                .collect(),
+            extra: data.extra.clone(),
+        };
+        let options = ExportOptions {
+            lang: self.display_lang.clone(),
+            data_dir: self
+                .current_file
+                .as_deref()
+                .and_then(Path::parent)
+                .map(Path::to_path_buf),
+            ..ExportOptions::default()
+        };
+
+        let mut out = Vec::new();
+        exporter.export(schema, &data, &mut out, &options)?;
+        if exporter.extension() == "svg" {
+            return write_image(path, String::from_utf8(out)?);
+        }
+        std::fs::write(path, out)?;
+        Ok(())
+    }
+
+    /// Export the classical hierarchy as a tree diagram, as PNG for a .png path and SVG otherwise
+    pub fn export_hierarchy_diagram(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let schema = self.schema.as_ref().ok_or("No schema loaded")?;
+        let style = HierarchyStyle {
+            lang: self.display_lang.clone(),
+            ..HierarchyStyle::default()
+        };
+        write_image(
+            path,
+            render_hierarchy_svg(&schema.classical_hierarchy, &style),
+        )
+    }
+
+    /// Write one data file per child species of `genus` into `dir`
+    /// The files reference the current schema file, or embed the schema when this file does,
+    /// and are formatted like saves of this file
+    /// Returns the files written and how many items are under no child, so not in any
+    pub fn split_by_genus(
+        &self,
+        genus: &str,
+        dir: &Path,
+    ) -> Result<(Vec<PathBuf>, usize), Box<dyn std::error::Error>> {
+        let schema = self.schema.as_ref().ok_or("No schema loaded")?;
+        let data = self.data.as_ref().ok_or("No data loaded")?;
+        let mut split = split_by_genus(data, &schema.classical_hierarchy, genus)?;
+        let schema_file = self.schema_file.as_deref().filter(|_| !self.embed_schema);
+        let options = self.save_options();
+        let paths = write_split(&mut split.parts, schema, schema_file, dir, &options, false)?;
+        Ok((paths, split.unassigned.len()))
+    }
+
+    /// Look up the Git repository of the current file and the commits that changed it
+    fn refresh_git(&mut self) {
+        self.git = self.current_file.as_ref().and_then(GitFile::discover);
+        self.history = self
+            .git
+            .as_ref()
+            .and_then(|git| git.history(MAX_HISTORY).ok())
+            .unwrap_or_default();
+        self.historical = None;
+    }
+
+    /// Changes since the last commit: the committed file's items against the current ones,
+    /// and its schema against the current one when the schema is in the repository too
+    pub fn uncommitted_changes(&self) -> Result<Vec<Change>, Box<dyn std::error::Error>> {
+        let git = self
+            .git
+            .as_ref()
+            .ok_or("The file is not in a Git repository")?;
+        let committed = git.data_at("HEAD")?.map(|data| data.items);
+        let items = self.get_items().map(Vec::as_slice).unwrap_or_default();
+        let mut changes = diff_items(&committed.unwrap_or_default(), items);
+
+        if let (Some(schema), Some(committed)) = (&self.schema, self.committed_schema()) {
+            changes.extend(diff_schemas(&committed, schema));
+        }
+        Ok(changes)
+    }
+
+    /// The schema as last committed: the schema file's, or the one the file embeds
+    /// None when it isn't in the repository or can't be read
+    fn committed_schema(&self) -> Option<TaxonomySchema> {
+        let file = match self.schema_file {
+            Some(ref path) => GitFile::discover(path)?,
+            None => GitFile::discover(self.current_file.as_ref()?)?,
+        };
+        file.schema_at("HEAD").ok().flatten()
+    }
+
+    /// Commit the saved file, with its schema file when that changed and is in the same
+    /// repository; returns the new commit's abbreviated ID
+    pub fn commit(&mut self, message: &str) -> Result<String, Box<dyn std::error::Error>> {
+        if self.dirty {
+            return Err("Save the file before committing".into());
+        }
+        let git = self
+            .git
+            .as_ref()
+            .ok_or("The file is not in a Git repository")?;
+        let schema_file = self.schema_file.as_deref().filter(|path| {
+            GitFile::discover(path).is_some_and(|file| {
+                file.workdir() == git.workdir() && file.is_modified().unwrap_or(false)
+            })
+        });
+        if !git.is_modified()? && schema_file.is_none() {
+            return Err("Nothing to commit: the file is unchanged since the last commit".into());
+        }
+
+        let author = self.config.settings.author();
+        let id = git.commit_as(message, schema_file.as_slice(), author)?;
+        self.refresh_git();
+        Ok(id[..7].to_string())
+    }
+
+    /// Load the file as of a commit in `history` for the History tab
+    pub fn show_revision(&mut self, index: usize) -> Result<(), Box<dyn std::error::Error>> {
+        let git = self
+            .git
+            .as_ref()
+            .ok_or("The file is not in a Git repository")?;
+        let revision = self.history.get(index).ok_or("No such revision")?;
+        let data = git
+            .data_at(&revision.id)?
+            .ok_or_else(|| tr!("The file is not in commit {}", revision.short_id()))?;
+        self.historical = Some((index, data));
+        Ok(())
+    }
+
+    /// Compare two data files, each with its own schema
+    pub fn compare_files(
+        &mut self,
+        old: &Path,
+        new: &Path,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (old_data, old_schema) = load_data_with_auto_schema(old)?;
+        let (new_data, new_schema) = load_data_with_auto_schema(new)?;
+        self.comparison = Some(Comparison {
+            old_label: file_label(old),
+            new_label: file_label(new),
+            diff: diff_taxonomies(&old_schema, &old_data, &new_schema, &new_data),
+            selected: None,
+        });
+        Ok(())
+    }
+
+    /// Compare the file as last committed with the current (possibly unsaved) taxonomy
+    pub fn compare_with_head(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let git = self
+            .git
+            .as_ref()
+            .ok_or("The file is not in a Git repository")?;
+        let (Some(schema), Some(data)) = (&self.schema, &self.data) else {
+            return Err("No taxonomy loaded".into());
+        };
+        let committed = git
+            .data_at("HEAD")?
+            .ok_or("The file has not been committed yet")?;
+        let committed_schema = self.committed_schema().unwrap_or_else(|| schema.clone());
+
+        let label = file_label(git.path());
+        self.comparison = Some(Comparison {
+            old_label: tr!("{} at HEAD", label),
+            new_label: if self.dirty {
+                tr!("{} (unsaved)", label)
+            } else {
+                label
+            },
+            diff: diff_taxonomies(&committed_schema, &committed, schema, data),
+            selected: None,
+        });
+        Ok(())
+    }
+
+    /// Merge the changes another version of the taxonomy made since `base` into the current
+    /// one; returns how many conflicts are left to resolve before `apply_merge`
+    pub fn start_merge(
+        &mut self,
+        base: &Path,
+        theirs: &Path,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let ours = self.data.as_ref().ok_or("No data loaded")?;
+        let (base, _) = load_data_with_auto_schema(base)?;
+        let (theirs, _) = load_data_with_auto_schema(theirs)?;
+        Ok(self.set_pending_merge(diff::merge(&base, ours, &theirs), false))
+    }
+
+    /// Load a data file Git left in conflict after a merge: our version becomes the current
+    /// taxonomy and the merge with theirs is pending
+    /// Returns None when the file isn't in conflict, else how many conflicts are left
+    pub fn load_merge_conflict(
+        &mut self,
+        path: &Path,
+    ) -> Result<Option<usize>, Box<dyn std::error::Error>> {
+        let Some(versions) = GitFile::discover(path)
+            .map(|git| git.conflict_versions())
+            .transpose()?
+            .flatten()
+        else {
+            return Ok(None);
+        };
+        let (Some(ours), Some(theirs)) = (versions.ours, versions.theirs) else {
+            return Err("The file was deleted on one side of the merge".into());
+        };
+
+        let (data, schema, schema_file) = parse_data_with_auto_schema(&ours, path)?;
+        let theirs = parse_version(&theirs)?;
+        // A file both branches added has no common version
+        let base = match versions.base {
+            Some(ref base) => parse_version(base)?,
+            None => TaxonomyData {
+                items: Vec::new(),
+                ..data.clone()
+            },
+        };
+        let result = diff::merge(&base, &data, &theirs);
+
+        self.embed_schema = schema_file.is_none();
+        self.schema_file = schema_file;
+        self.schema = Some(schema);
+        self.data = Some(data);
+        self.current_file = Some(path.to_path_buf());
+        self.remote = None;
+        self.dirty = false;
+        self.selected_item = None;
+        self.recent_changes.clear();
+        self.pending_import = None;
+        self.refresh_git();
+        Ok(Some(self.set_pending_merge(result, true)))
+    }
+
+    fn set_pending_merge(&mut self, result: MergeResult, in_git: bool) -> usize {
+        let conflicts = result.conflicts.len();
+        self.pending_merge = Some(PendingMerge {
+            resolutions: vec![Resolution::Ours; conflicts],
+            result,
+            in_git,
+        });
+        conflicts
+    }
+
+    /// Replace the current items with the pending merge's, resolved as chosen
+    /// A merge of a file in conflict in Git is saved and staged as resolved
+    pub fn apply_merge(&mut self) -> Result<usize, Box<dyn std::error::Error>> {
+        let pending = self.pending_merge.as_ref().ok_or("No merge in progress")?;
+        let mut result = pending.result.clone();
+        for (index, resolution) in pending.resolutions.iter().enumerate() {
+            result.resolve(index, resolution.clone())?;
+        }
+        let in_git = pending.in_git;
+        self.pending_merge = None;
+
+        let conflicts = result.conflicts.len();
+        self.data = Some(result.merged);
+        self.selected_item = None;
+        self.record_change(match conflicts {
+            0 => tr!("Merged changes"),
+            _ => tr!("Merged changes, resolving {} conflict(s)", conflicts),
+        });
+
+        if in_git {
+            self.save()?;
+            if let Some(ref git) = self.git {
+                git.stage()?;
+            }
+        }
+        Ok(conflicts)
+    }
+
+    /// Create a new empty taxonomy with default schema
+    pub fn create_new(&mut self) {
+        let template = find_template(DEFAULT_TEMPLATE).expect("default template is built in");
+
+        self.schema = Some(template.schema());
+        self.data = Some(template.empty_data("schema.json"));
         self.current_file = None;
+        self.remote = None;
         self.schema_file = None;
+        self.embed_schema = false;
         self.dirty = true;
         self.selected_item = None;
+        self.recent_changes.clear();
+        self.pending_import = None;
+        self.pending_merge = None;
+        self.trash.clear();
+        self.keep_trash = false;
+        self.refresh_git();
     }
 
     /// Mark state as modified
@@ -126,8 +1340,31 @@ impl AppState {
         self.dirty = true;
     }
 
+    /// Mark state as modified and note the change for the dashboard
+    pub fn record_change(&mut self, change: impl Into<String>) {
+        self.dirty = true;
+        self.note_change(change);
+    }
+
+    /// Note a change for the dashboard without marking the state as modified
+    fn note_change(&mut self, change: impl Into<String>) {
+        self.recent_changes.insert(0, change.into());
+        self.recent_changes.truncate(MAX_RECENT_CHANGES);
+    }
+
     /// Get window title with file name and dirty indicator
     pub fn get_window_title(&self) -> String {
+        let dirty_marker = if self.dirty { "*" } else { "" };
+
+        if let Some(ref remote) = self.remote {
+            let access = if remote.writable {
+                String::new()
+            } else {
+                format!(" ({})", tr!("read-only"))
+            };
+            return format!("Taxonomy Studio - {}{}{}", remote.url, dirty_marker, access);
+        }
+
         let file_name = self
             .current_file
             .as_ref()
@@ -135,8 +1372,6 @@ impl AppState {
             .and_then(|n| n.to_str())
             .unwrap_or("Untitled");
 
-        let dirty_marker = if self.dirty { "*" } else { "" };
-
         format!("Taxonomy Studio - {}{}", file_name, dirty_marker)
     }
 
@@ -156,10 +1391,20 @@ impl AppState {
     }
 
     /// Get a reference to faceted dimensions
-    pub fn get_faceted_dimensions(&self) -> Option<&HashMap<String, Vec<String>>> {
+    pub fn get_faceted_dimensions(&self) -> Option<&HashMap<String, FacetDefinition>> {
         self.schema.as_ref().map(|s| &s.faceted_dimensions)
     }
 
+    /// Names of the faceted dimensions, sorted (empty without a schema)
+    pub fn facet_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .get_faceted_dimensions()
+            .map(|dims| dims.keys().cloned().collect())
+            .unwrap_or_default();
+        names.sort();
+        names
+    }
+
     /// Get a reference to an item by index
     #[allow(dead_code)]
     pub fn get_item(&self, index: i32) -> Option<&Item> {
@@ -182,3 +1427,47 @@ impl AppState {
             .and_then(|d| d.items.get_mut(index as usize))
     }
 }
+
+/// Another version of a data file, e.g. from Git, read without validation: it may not
+/// fit the current schema
+fn parse_version(contents: &str) -> Result<TaxonomyData, Box<dyn std::error::Error>> {
+    let mut value: serde_json::Value = serde_json::from_str(contents)?;
+    if let Some(object) = value.as_object_mut() {
+        object.remove(INLINE_SCHEMA_KEY);
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+/// A file's name for display, e.g. "data.json"
+fn file_label(path: &Path) -> String {
+    path.file_name().map_or_else(
+        || path.display().to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    )
+}
+
+/// Add a template, replacing any with the same name
+fn store_template(templates: &mut Vec<ItemTemplate>, template: ItemTemplate) {
+    match templates.iter_mut().find(|t| t.name == template.name) {
+        Some(existing) => *existing = template,
+        None => templates.push(template),
+    }
+}
+
+/// Write an SVG image, rasterized to PNG when the path ends in .png
+fn write_image(path: &Path, svg: String) -> Result<(), Box<dyn std::error::Error>> {
+    let png = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("png"));
+    if png {
+        #[cfg(feature = "png")]
+        {
+            std::fs::write(path, viz::svg_to_png(&svg)?)?;
+            return Ok(());
+        }
+        #[cfg(not(feature = "png"))]
+        return Err("PNG export needs the 'png' feature".into());
+    }
+    std::fs::write(path, svg)?;
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use taxstud_core::{ItemTemplate, LoadOptions, SortOptions, DEFAULT_ARTICLES};
+
+/// How many facets the item list shows as badges
+pub const MAX_BADGE_FACETS: usize = 2;
+
+/// Settings kept between runs, in `taxstud/config.json` under the user's config directory
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    /// Where the user located schema files that data files reference but that have moved,
+    /// keyed by the referenced (missing) path
+    pub schema_locations: HashMap<PathBuf, PathBuf>,
+    /// Prototypes for creating new items outside a project
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub item_templates: Vec<ItemTemplate>,
+    /// Preferences edited in the Settings dialog
+    pub settings: Settings,
+    /// Data files opened lately, most recent first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub recent_files: Vec<PathBuf>,
+    /// What the item list is sorted by ("name" or a facet); None keeps the file's order
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_by: Option<String>,
+    /// Whether the item list is sorted descending
+    pub sort_descending: bool,
+    /// URL prefixes of taxonomies saved back to their server (by HTTP PUT); taxonomies
+    /// opened from other URLs are read-only
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub writable_urls: Vec<String>,
+}
+
+/// Preferences edited in the Settings dialog
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Theme of the window: the OS preference unless the user picked one
+    pub theme: ThemeChoice,
+    /// Language of menus, dialogs and messages (a locale like "de", "en"); empty follows the OS
+    pub language: String,
+    /// Multiplier of all text sizes in the window
+    pub font_scale: f32,
+    /// Black and white palettes with saturated accents instead of the usual ones
+    pub high_contrast: bool,
+    /// Refuse to open data files whose objects repeat a key, instead of keeping the last value
+    pub strict_duplicate_keys: bool,
+    /// Minutes between saves of a file with unsaved changes; 0 turns autosave off
+    pub autosave_minutes: u32,
+    /// Backups kept of a file when it is saved over (`<file>.1.bak` newest); 0 keeps none
+    pub backup_count: usize,
+    /// Format the hierarchy map export suggests
+    pub export_format: ExportFormat,
+    /// Author of Git commits made in the app; empty uses the repository's user.name
+    pub author: String,
+    /// Leading words ignored when sorting names and values
+    pub articles: Vec<String>,
+    /// How many files File → Open Recent lists
+    pub recent_files: usize,
+    /// Facets whose values the item list shows as badges beside names (at most two)
+    pub badge_facets: Vec<String>,
+    /// Badge colors by facet value, as #rrggbb; other values take a chart color
+    pub badge_colors: BTreeMap<String, String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            theme: ThemeChoice::System,
+            language: String::new(),
+            font_scale: 1.0,
+            high_contrast: false,
+            strict_duplicate_keys: false,
+            autosave_minutes: 0,
+            backup_count: 0,
+            export_format: ExportFormat::Svg,
+            author: String::new(),
+            articles: DEFAULT_ARTICLES.iter().map(|a| a.to_string()).collect(),
+            recent_files: 8,
+            badge_facets: Vec::new(),
+            badge_colors: BTreeMap::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// How sorting compares names and values
+    pub fn sort_options(&self) -> SortOptions {
+        SortOptions {
+            articles: self.articles.clone(),
+            ..SortOptions::default()
+        }
+    }
+
+    /// How data files are read
+    pub fn load_options(&self) -> LoadOptions {
+        LoadOptions {
+            strict_duplicate_keys: self.strict_duplicate_keys,
+        }
+    }
+
+    /// The Git commit author, when one is set
+    pub fn author(&self) -> Option<&str> {
+        Some(self.author.trim()).filter(|author| !author.is_empty())
+    }
+}
+
+/// A theme choice kept in the config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeChoice {
+    /// Follow the OS dark/light preference
+    System,
+    Light,
+    Dark,
+}
+
+/// An export file format kept in the config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Svg,
+    Png,
+}
+
+impl AppConfig {
+    /// Read the config file; a missing or unreadable one gives the defaults
+    pub fn load() -> Self {
+        config_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    /// Put a file at the top of the recent files, keeping as many as the settings say
+    pub fn note_recent_file(&mut self, path: &Path) {
+        self.recent_files.retain(|recent| recent != path);
+        self.recent_files.insert(0, path.to_path_buf());
+        self.recent_files.truncate(self.settings.recent_files);
+    }
+
+    /// Write the config file, creating its directory
+    pub fn save(&self) -> io::Result<()> {
+        let path = config_path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No config directory"))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, json)
+    }
+}
+
+/// `$XDG_CONFIG_HOME`, `%APPDATA%` or `~/.config`, followed by `taxstud/config.json`
+fn config_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .or_else(|| std::env::var_os("APPDATA"))
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("taxstud").join("config.json"))
+}
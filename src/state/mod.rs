@@ -1,5 +1,7 @@
 pub mod app_state;
+pub mod config;
 pub mod ui_state;
 
-pub use app_state::AppState;
+pub use app_state::{AppState, ListRow, LoadError, LoadedFile, MissingSchema, RemoteSource};
+pub use config::{ExportFormat, Settings, ThemeChoice, MAX_BADGE_FACETS};
 pub use ui_state::{PendingAction, SimpleConfirmationAction, UiState};
@@ -1,15 +1,26 @@
+use std::path::PathBuf;
+
 /// Represents a pending action waiting for confirmation
 #[derive(Debug, Clone)]
 pub enum PendingAction {
     Open,
     New,
     Exit,
+    OpenProject,
+    /// Switch to the open project's member taxonomy at this index
+    OpenProjectMember(usize),
+    /// Open a file from File -> Open Recent
+    OpenRecent(PathBuf),
+    /// Open a taxonomy published at a URL (File -> Open URL)
+    OpenUrl(String),
 }
 
 /// Represents an action for simple confirmation dialog
 #[derive(Debug, Clone)]
 pub enum SimpleConfirmationAction {
     Revert,
+    /// Clear the filters hiding the named item, then select it (quick open)
+    ShowItem(String),
 }
 
 /// UI flow state management
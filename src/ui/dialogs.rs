@@ -1,14 +1,55 @@
-use slint::SharedString;
+use slint::{Model, SharedString, VecModel};
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use super::types::{StatusLevel, StatusMessage};
+use super::formatting::format_clock;
+use super::types::{Notification, StatusLevel, StatusMessage};
 use crate::MainWindow;
 
+/// Most notifications kept for the session; the oldest go first
+const MAX_NOTIFICATIONS: usize = 500;
+
 /// Helper function to set status message with semantic level
+/// The message is also kept in the notification log
 pub fn set_status(window: &MainWindow, text: impl Into<SharedString>, level: StatusLevel) {
-    window.set_status(StatusMessage {
-        text: text.into(),
+    let text = text.into();
+    log_notification(window, text.clone(), level, SharedString::default());
+    window.set_status(StatusMessage { text, level });
+}
+
+/// Add a timestamped entry at the top of the notification log
+fn log_notification(
+    window: &MainWindow,
+    text: SharedString,
+    level: StatusLevel,
+    details: SharedString,
+) {
+    if text.is_empty() {
+        return;
+    }
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as i64);
+    let entry = Notification {
+        time: format_clock(now).into(),
         level,
-    });
+        text,
+        details,
+    };
+
+    let notifications = window.get_notifications();
+    match notifications
+        .as_any()
+        .downcast_ref::<VecModel<Notification>>()
+    {
+        Some(model) => {
+            model.insert(0, entry);
+            if model.row_count() > MAX_NOTIFICATIONS {
+                model.remove(MAX_NOTIFICATIONS);
+            }
+        }
+        None => window.set_notifications(Rc::new(VecModel::from(vec![entry])).into()),
+    }
 }
 
 /// Helper function to show confirmation dialog
@@ -29,9 +70,16 @@ pub fn show_error(
     message: impl Into<SharedString>,
     details: impl Into<SharedString>,
 ) {
-    window.set_error_title(title.into());
-    window.set_error_message(message.into());
-    window.set_error_details(details.into());
+    let (title, message, details) = (title.into(), message.into(), details.into());
+    log_notification(
+        window,
+        format!("{}: {}", title, message).into(),
+        StatusLevel::Danger,
+        details.clone(),
+    );
+    window.set_error_title(title);
+    window.set_error_message(message);
+    window.set_error_details(details);
     window.set_show_error_dialog(true);
 }
 
@@ -1,24 +1,69 @@
-use slint::SharedString;
+use slint::{Color, Image, Model, ModelRc, SharedString, VecModel};
 use std::collections::HashMap;
-use taxstud_core::{ClassicalHierarchy, HierarchyNode};
+use std::path::Path;
+use std::rc::Rc;
+use taxstud_core::viz::{self, node_color, parse_hex_color, ring_segment_path};
+use taxstud_core::{
+    sunburst, treemap, Attachment, Change, ChangeKind, ClassicalHierarchy, ColumnTarget,
+    DisplayHint, FacetDefinition, FacetDistribution, FacetKind, FieldKind, ImportRow, Item,
+    ItemDiff, ItemField, ItemImport, ItemLink, MergeConflict, Provenance, Rename, Resolution,
+    Revision, SimilarItem, TaxonomyDiff, TaxonomyOverview, TaxonomySchema,
+};
 
-use super::types::{FacetInput, TreeNode};
+use super::types::{
+    AttachmentEntry, Breadcrumb, ChangeEntry, ChartSlice, CompareRow, CoverageBar, DirtyField,
+    FacetInput, ImportColumn, ImportPreviewRow, ItemBadge, LinkEntry, MapShape, MergeConflictRow,
+    RenamePreviewRow, SimilarEntry, TreeNode,
+};
+use crate::i18n::tr;
+use crate::state::{Settings, MAX_BADGE_FACETS};
+
+/// Colors of the chart slices, repeated when a facet has more values
+const CHART_COLORS: [(u8, u8, u8); 8] = [
+    (0x4e, 0x79, 0xa7),
+    (0xf2, 0x8e, 0x2b),
+    (0xe1, 0x57, 0x59),
+    (0x76, 0xb7, 0xb2),
+    (0x59, 0xa1, 0x4f),
+    (0xed, 0xc9, 0x48),
+    (0xb0, 0x7a, 0xa1),
+    (0x9c, 0x75, 0x5f),
+];
 
 /// Format facets into a displayable string
-pub fn format_facets(facets: &HashMap<String, serde_json::Value>) -> String {
+/// With the schema's dimensions, names and values show their labels (in `lang` when
+/// translated) and deprecated values are marked
+pub fn format_facets(
+    facets: &HashMap<String, serde_json::Value>,
+    dimensions: Option<&HashMap<String, FacetDefinition>>,
+    lang: Option<&str>,
+) -> String {
     let mut facet_lines: Vec<String> = facets
         .iter()
         .map(|(key, value)| {
-            let value_str = match value {
-                serde_json::Value::String(s) => s.clone(),
+            let values: Vec<String> = match value {
+                serde_json::Value::String(s) => vec![s.clone()],
                 serde_json::Value::Array(arr) => arr
                     .iter()
                     .filter_map(|v| v.as_str())
-                    .collect::<Vec<_>>()
-                    .join(", "),
-                _ => value.to_string(),
+                    .map(str::to_string)
+                    .collect(),
+                _ => vec![value.to_string()],
             };
-            format!("• {}: {}", key, value_str)
+            let definition = dimensions.and_then(|dims| dims.get(key));
+            let value_str = values
+                .iter()
+                .map(|v| match definition {
+                    Some(def) if def.is_deprecated(v) => {
+                        tr!("{} (deprecated)", def.label_for(v, lang))
+                    }
+                    Some(def) => def.label_for(v, lang).to_string(),
+                    None => v.clone(),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            let name = definition.map_or(key.as_str(), |def| def.display_name(key, lang));
+            format!("• {}: {}", name, value_str)
         })
         .collect();
 
@@ -26,41 +71,612 @@ pub fn format_facets(facets: &HashMap<String, serde_json::Value>) -> String {
     facet_lines.join("\n")
 }
 
+/// Format an item's custom field values, one "• Label: value" line per set field
+pub fn format_item_fields(
+    fields: &HashMap<String, ItemField>,
+    extra: &HashMap<String, serde_json::Value>,
+) -> String {
+    let mut lines: Vec<String> = fields
+        .iter()
+        .filter_map(|(name, field)| {
+            let value = extra.get(name).filter(|value| !value.is_null())?;
+            Some(format!(
+                "• {}: {}",
+                field.display_name(name),
+                field_text(value)
+            ))
+        })
+        .collect();
+
+    lines.sort();
+    lines.join("\n")
+}
+
+/// Create the detail panel's link entries
+pub fn create_link_entries(links: Vec<ItemLink>) -> Vec<LinkEntry> {
+    links
+        .into_iter()
+        .map(|link| LinkEntry {
+            label: SharedString::from(link.label),
+            url: SharedString::from(link.url),
+        })
+        .collect()
+}
+
+/// Create the detail panel's breadcrumbs for a classification path, named in `lang`
+pub fn create_breadcrumbs(
+    path: &[String],
+    hierarchy: Option<&ClassicalHierarchy>,
+    lang: Option<&str>,
+) -> Vec<Breadcrumb> {
+    path.iter()
+        .map(|segment| {
+            let label = match hierarchy {
+                Some(hierarchy) => hierarchy.display_name(segment, lang),
+                None => segment.as_str(),
+            };
+            Breadcrumb {
+                label: SharedString::from(label),
+                species: SharedString::from(segment),
+            }
+        })
+        .collect()
+}
+
+/// Create the detail panel's attachment entries
+/// Local paths are resolved against the data file's directory; images among them that
+/// load get a thumbnail
+pub fn create_attachment_entries(
+    attachments: &[Attachment],
+    data_dir: Option<&Path>,
+) -> Vec<AttachmentEntry> {
+    attachments
+        .iter()
+        .map(|attachment| {
+            let local = data_dir.and_then(|dir| attachment.local_path(dir));
+            let thumbnail = local
+                .as_deref()
+                .filter(|_| attachment.is_image())
+                .and_then(|path| Image::load_from_path(path).ok());
+            let location = match &local {
+                Some(path) => path.display().to_string(),
+                None => attachment.path_or_url.clone(),
+            };
+
+            AttachmentEntry {
+                has_thumbnail: thumbnail.is_some(),
+                thumbnail: thumbnail.unwrap_or_default(),
+                caption: SharedString::from(attachment.display_caption()),
+                location: SharedString::from(location),
+            }
+        })
+        .collect()
+}
+
 /// Format facet dimensions into a displayable string
-pub fn format_facet_dimensions(dimensions: &HashMap<String, Vec<String>>) -> String {
+/// With a display language, names and values are shown translated where the schema has labels
+pub fn format_facet_dimensions(
+    dimensions: &HashMap<String, FacetDefinition>,
+    lang: Option<&str>,
+) -> String {
     let mut dim_lines: Vec<String> = dimensions
         .iter()
-        .map(|(key, values)| format!("{}: {}", key, values.join(", ")))
+        .map(|(key, definition)| {
+            let name = definition.display_name(key, lang);
+            if definition.allowed_values.is_empty() {
+                format!("{}: <{}>", name, definition.kind)
+            } else if lang.is_some() {
+                let values: Vec<&str> = definition
+                    .allowed_values
+                    .iter()
+                    .map(|value| definition.label_for(value, lang))
+                    .collect();
+                format!("{}: {}", name, values.join(", "))
+            } else {
+                format!("{}: {}", name, definition.allowed_values.join(", "))
+            }
+        })
         .collect();
 
     dim_lines.sort();
     dim_lines.join(" • ")
 }
 
+/// Create the dashboard's facet coverage bars, labeled with the facets' display names
+pub fn create_coverage_bars(
+    overview: &TaxonomyOverview,
+    dimensions: &HashMap<String, FacetDefinition>,
+    lang: Option<&str>,
+) -> Vec<CoverageBar> {
+    overview
+        .facet_coverage
+        .iter()
+        .map(|coverage| {
+            let label = dimensions
+                .get(&coverage.facet)
+                .map(|definition| definition.display_name(&coverage.facet, lang))
+                .unwrap_or(&coverage.facet);
+            CoverageBar {
+                label: SharedString::from(label),
+                ratio: coverage.ratio() as f32,
+                detail: SharedString::from(tr!(
+                    "{}/{} items · {} value(s)",
+                    coverage.items_with_value,
+                    coverage.item_count,
+                    coverage.distinct_values
+                )),
+            }
+        })
+        .collect()
+}
+
+/// Create the statistics chart entries of a facet distribution, values labeled in `lang`
+pub fn create_chart_slices(
+    distribution: &FacetDistribution,
+    definition: Option<&FacetDefinition>,
+    lang: Option<&str>,
+) -> Vec<ChartSlice> {
+    let max = distribution.max_count().max(1) as f32;
+    let total = distribution.total().max(1) as f64;
+    let mut start = 0.0;
+
+    distribution
+        .counts
+        .iter()
+        .enumerate()
+        .map(|(idx, value_count)| {
+            let share = value_count.count as f64 / total;
+            let pie = if share > 0.0 {
+                ring_segment_path(50.0, 50.0, 0.0, 50.0, start, start + share)
+            } else {
+                String::new()
+            };
+            start += share;
+
+            let label = definition
+                .map(|def| def.label_for(&value_count.value, lang))
+                .unwrap_or(&value_count.value);
+            let (r, g, b) = CHART_COLORS[idx % CHART_COLORS.len()];
+            ChartSlice {
+                value: SharedString::from(value_count.value.as_str()),
+                label: SharedString::from(label),
+                count: value_count.count as i32,
+                ratio: value_count.count as f32 / max,
+                share: share as f32,
+                pie: SharedString::from(pie),
+                color: Color::from_rgb_u8(r, g, b),
+            }
+        })
+        .collect()
+}
+
+/// Create the badges shown beside an item's name in the list: the values of the settings'
+/// badge facets in their display order, labeled in `lang` after their schema icon; colored
+/// as the settings say, else as the schema's "x-display" says, else in the chart color of
+/// the value's place among the facet's allowed values
+pub fn create_item_badges(
+    item: &Item,
+    settings: &Settings,
+    schema: Option<&TaxonomySchema>,
+    lang: Option<&str>,
+) -> Vec<ItemBadge> {
+    settings
+        .badge_facets
+        .iter()
+        .take(MAX_BADGE_FACETS)
+        .flat_map(|facet| {
+            let definition = schema.and_then(|schema| schema.faceted_dimensions.get(facet));
+            let mut values = item.get_facet_as_vec(facet);
+            if let Some(schema) = schema {
+                schema.sort_by_display_order(facet, &mut values);
+            }
+            values.into_iter().map(move |value| {
+                let hint = schema.and_then(|schema| schema.display_hint(facet, &value));
+                let (r, g, b) = settings
+                    .badge_colors
+                    .get(&value)
+                    .or(hint.and_then(|hint| hint.color.as_ref()))
+                    .and_then(|hex| parse_hex_color(hex))
+                    .unwrap_or_else(|| {
+                        let place = definition
+                            .and_then(|def| def.allowed_values.iter().position(|v| *v == value))
+                            .unwrap_or_else(|| value.bytes().map(usize::from).sum());
+                        CHART_COLORS[place % CHART_COLORS.len()]
+                    });
+                let label = definition
+                    .map(|def| def.label_for(&value, lang))
+                    .unwrap_or(&value);
+                ItemBadge {
+                    text: SharedString::from(with_icon(label, hint)),
+                    color: Color::from_rgb_u8(r, g, b),
+                }
+            })
+        })
+        .collect()
+}
+
+/// A facet value's label after the icon its display hint gives it, if any
+pub fn with_icon(label: &str, hint: Option<&DisplayHint>) -> String {
+    match hint.and_then(|hint| hint.icon.as_deref()) {
+        Some(icon) => format!("{} {}", icon, label),
+        None => label.to_string(),
+    }
+}
+
+/// Create the hierarchy map shapes: each node's treemap rectangle (in the core's default
+/// treemap size) and sunburst segment, labeled in `lang`
+pub fn create_map_shapes(
+    hierarchy: &ClassicalHierarchy,
+    items: &[Item],
+    lang: Option<&str>,
+) -> Vec<MapShape> {
+    let rects = treemap(hierarchy, items, viz::TREEMAP_WIDTH, viz::TREEMAP_HEIGHT);
+    let arcs = sunburst(hierarchy, items);
+    let rings = arcs.iter().map(|arc| arc.depth + 1).max().unwrap_or(1);
+    let ring_width = 50.0 / rings as f64;
+
+    // Both layouts list the same nodes in the same order
+    rects
+        .iter()
+        .zip(&arcs)
+        .map(|(rect, arc)| {
+            let (r, g, b) = node_color(rect.branch, rect.depth);
+            MapShape {
+                label: SharedString::from(hierarchy.display_name(&rect.species, lang)),
+                x: rect.x as f32,
+                y: rect.y as f32,
+                width: rect.width as f32,
+                height: rect.height as f32,
+                path: SharedString::from(ring_segment_path(
+                    50.0,
+                    50.0,
+                    ring_width * arc.depth as f64,
+                    ring_width * (arc.depth + 1) as f64,
+                    arc.start,
+                    arc.end,
+                )),
+                color: Color::from_rgb_u8(r, g, b),
+            }
+        })
+        .collect()
+}
+
+/// Create the detail panel's similar item entries: similarity as a percentage and the facets
+/// the similar item sets that the selected one doesn't, with names and facets labeled in `lang`
+pub fn create_similar_entries(
+    similar: &[SimilarItem],
+    dimensions: Option<&HashMap<String, FacetDefinition>>,
+    lang: Option<&str>,
+) -> Vec<SimilarEntry> {
+    similar
+        .iter()
+        .map(|entry| {
+            let mut detail = format!("{:.0}% similar", entry.score * 100.0);
+            if !entry.unset_facets.is_empty() {
+                let facets: Vec<&str> = entry
+                    .unset_facets
+                    .iter()
+                    .map(|facet| {
+                        dimensions
+                            .and_then(|dims| dims.get(facet))
+                            .map_or(facet.as_str(), |def| def.display_name(facet, lang))
+                    })
+                    .collect();
+                detail.push_str(&tr!(" · also sets {}", facets.join(", ")));
+            }
+            SimilarEntry {
+                name: SharedString::from(&entry.item.name),
+                label: SharedString::from(entry.item.display_name(lang)),
+                detail: SharedString::from(detail),
+            }
+        })
+        .collect()
+}
+
+/// Create the lines of a semantic diff, colored by kind in the UI
+pub fn create_change_entries(changes: &[Change]) -> Vec<ChangeEntry> {
+    changes
+        .iter()
+        .map(|change| ChangeEntry {
+            text: SharedString::from(change.to_string()),
+            kind: SharedString::from(change_kind_name(Some(change.kind))),
+        })
+        .collect()
+}
+
+/// The kind of a change as the UI names it
+fn change_kind_name(kind: Option<ChangeKind>) -> &'static str {
+    match kind {
+        Some(ChangeKind::Added) => "added",
+        Some(ChangeKind::Removed) => "removed",
+        Some(ChangeKind::Changed) => "changed",
+        None => "",
+    }
+}
+
+/// Create the compare view's rows: each item's name in the old and the new version
+pub fn create_compare_rows(diff: &TaxonomyDiff) -> Vec<CompareRow> {
+    diff.items
+        .iter()
+        .map(|item| {
+            let name = |version: &Option<Item>| {
+                SharedString::from(version.as_ref().map_or("", |item| item.name.as_str()))
+            };
+            CompareRow {
+                old: name(&item.old),
+                new: name(&item.new),
+                kind: SharedString::from(change_kind_name(item.kind)),
+            }
+        })
+        .collect()
+}
+
+/// Create the compare view's field differences for one item: every changed field, or the
+/// classification of an item only one version has
+pub fn create_item_diff_entries(item: &ItemDiff) -> Vec<ChangeEntry> {
+    let entry = |text: String| ChangeEntry {
+        text: SharedString::from(text),
+        kind: SharedString::from(change_kind_name(item.kind)),
+    };
+    match item.kind {
+        Some(ChangeKind::Added) | Some(ChangeKind::Removed) => {
+            let version = item.new.as_ref().or(item.old.as_ref());
+            let path = version.map_or_else(String::new, |item| item.classical_path.join(" > "));
+            vec![entry(tr!("item '{}' at {}", item.name, path))]
+        }
+        _ => item.details.iter().cloned().map(entry).collect(),
+    }
+}
+
+/// One commit in the History tab: abbreviated ID, date, summary and author
+pub fn format_revision(revision: &Revision) -> String {
+    format!(
+        "{}  {}  {} ({})",
+        revision.short_id(),
+        format_date(revision.time),
+        revision.summary,
+        revision.author
+    )
+}
+
+/// One deleted item in the Trash tab: name, classification and deletion date
+pub fn format_trashed_item(item: &Item, deleted_at: i64, lang: Option<&str>) -> String {
+    tr!(
+        "{}  ({})  deleted {}",
+        item.display_name(lang),
+        item.classical_path.join(" → "),
+        format_date(deleted_at)
+    )
+}
+
+/// Where an item was imported from, e.g. "vendor.csv, imported 2024-03-09 by Taxonomy Studio"
+pub fn format_provenance(source: &Provenance) -> String {
+    tr!(
+        "{}, imported {} by {}",
+        source.origin_file,
+        format_date(source.imported_at),
+        source.importer
+    )
+}
+
+/// A Unix timestamp as a UTC time of day, e.g. "14:05:09"
+pub fn format_clock(timestamp: i64) -> String {
+    let seconds = timestamp.rem_euclid(86_400);
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// A Unix timestamp as a UTC date, e.g. "2024-03-09"
+fn format_date(timestamp: i64) -> String {
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let days = timestamp.div_euclid(86_400) + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Choices of the import dialog's column targets: ignore, name, path, each facet (in
+/// `facet_names` order), then an additional field named after the column
+pub fn create_import_targets(
+    facet_names: &[String],
+    dimensions: &HashMap<String, FacetDefinition>,
+    lang: Option<&str>,
+) -> Vec<SharedString> {
+    let facets = facet_names.iter().map(|facet| {
+        let label = dimensions
+            .get(facet)
+            .map_or(facet.as_str(), |def| def.display_name(facet, lang));
+        SharedString::from(tr!("Facet: {}", label))
+    });
+
+    ["(ignore)", "Name", "Classification Path"]
+        .into_iter()
+        .map(SharedString::from)
+        .chain(facets)
+        .chain(std::iter::once(SharedString::from(tr!("Additional Field"))))
+        .collect()
+}
+
+/// Position of a column target among the choices of `create_import_targets`
+pub fn import_target_index(target: &ColumnTarget, facet_names: &[String]) -> usize {
+    match target {
+        ColumnTarget::Ignore => 0,
+        ColumnTarget::Name => 1,
+        ColumnTarget::Path => 2,
+        ColumnTarget::Facet(facet) => facet_names
+            .iter()
+            .position(|name| name == facet)
+            .map_or(0, |idx| idx + 3),
+        ColumnTarget::Field(_) => facet_names.len() + 3,
+    }
+}
+
+/// Column target picked from the choices of `create_import_targets`
+pub fn import_target_at(index: usize, column: &str, facet_names: &[String]) -> ColumnTarget {
+    match index {
+        0 => ColumnTarget::Ignore,
+        1 => ColumnTarget::Name,
+        2 => ColumnTarget::Path,
+        idx => match facet_names.get(idx - 3) {
+            Some(facet) => ColumnTarget::Facet(facet.clone()),
+            None => ColumnTarget::Field(column.trim().to_string()),
+        },
+    }
+}
+
+/// Create the import dialog's column list
+pub fn create_import_columns(import: &ItemImport, facet_names: &[String]) -> Vec<ImportColumn> {
+    import
+        .table
+        .columns
+        .iter()
+        .zip(&import.targets)
+        .map(|(column, target)| ImportColumn {
+            name: SharedString::from(column.as_str()),
+            target_index: import_target_index(target, facet_names) as i32,
+        })
+        .collect()
+}
+
+/// Create the import dialog's row preview
+pub fn create_import_rows(rows: &[ImportRow]) -> Vec<ImportPreviewRow> {
+    rows.iter()
+        .map(|row| ImportPreviewRow {
+            row: row.row as i32,
+            name: SharedString::from(row.item.name.as_str()),
+            path: SharedString::from(row.item.classical_path.join(" → ")),
+            issues: SharedString::from(row.issues.join("; ")),
+        })
+        .collect()
+}
+
+/// Create the rename dialog's preview, one row per item
+pub fn create_rename_rows(renames: &[Rename]) -> Vec<RenamePreviewRow> {
+    renames
+        .iter()
+        .map(|rename| RenamePreviewRow {
+            old: SharedString::from(rename.old.as_str()),
+            new: SharedString::from(rename.new.as_str()),
+            issue: SharedString::from(rename.issue.as_deref().unwrap_or_default()),
+        })
+        .collect()
+}
+
+/// Create the merge dialog's rows, one per conflict with the resolution picked for it
+pub fn create_merge_rows(
+    conflicts: &[MergeConflict],
+    resolutions: &[Resolution],
+) -> Vec<MergeConflictRow> {
+    let text = |conflict: &MergeConflict, value: Option<&serde_json::Value>| {
+        let text = conflict.field.value_to_text(value);
+        SharedString::from(if text.is_empty() {
+            "(none)"
+        } else {
+            text.as_str()
+        })
+    };
+    conflicts
+        .iter()
+        .zip(resolutions)
+        .map(|(conflict, resolution)| {
+            let (choice, edited) = match resolution {
+                Resolution::Ours => (0, String::new()),
+                Resolution::Theirs => (1, String::new()),
+                Resolution::Edited(value) => (2, conflict.field.value_to_text(value.as_ref())),
+            };
+            MergeConflictRow {
+                item: SharedString::from(conflict.item.as_str()),
+                field: SharedString::from(conflict.field.to_string()),
+                base: text(conflict, conflict.base.as_ref()),
+                ours: text(conflict, conflict.ours.as_ref()),
+                theirs: text(conflict, conflict.theirs.as_ref()),
+                choice,
+                edited: SharedString::from(edited),
+            }
+        })
+        .collect()
+}
+
 /// Create facet input list from taxonomy dimensions and current facet values
+/// Each input picks its widget from the facet's definition:
+/// "choice" (single-valued enum), "toggle" (boolean) or "text"
 pub fn create_facet_inputs(
-    dimensions: &HashMap<String, Vec<String>>,
+    dimensions: &HashMap<String, FacetDefinition>,
     facets: &HashMap<String, serde_json::Value>,
+    lang: Option<&str>,
 ) -> Vec<FacetInput> {
     let mut facet_inputs: Vec<FacetInput> = dimensions
-        .keys()
-        .map(|key| {
+        .iter()
+        .map(|(key, definition)| {
             let value = facets
                 .get(key)
                 .map(|v| match v {
                     serde_json::Value::String(s) => s.clone(),
                     serde_json::Value::Array(arr) => arr
                         .iter()
-                        .filter_map(|v| v.as_str())
+                        .map(|v| v.as_str().map_or_else(|| v.to_string(), str::to_string))
                         .collect::<Vec<_>>()
                         .join(", "),
                     _ => v.to_string(),
                 })
                 .unwrap_or_default();
 
+            let widget = match definition.kind {
+                FacetKind::Boolean => "toggle",
+                FacetKind::Enum if !definition.multi => "choice",
+                _ => "text",
+            };
+
+            // An optional choice can be cleared by picking the empty entry
+            // Deprecated values are only offered when the item already uses them
+            let mut options: Vec<SharedString> = Vec::new();
+            if !definition.required {
+                options.push(SharedString::default());
+            }
+            options.extend(
+                definition
+                    .value_choices()
+                    .into_iter()
+                    .filter(|choice| !definition.is_deprecated(choice) || *choice == value)
+                    .map(SharedString::from),
+            );
+
+            let placeholder = match definition.kind {
+                FacetKind::Enum if definition.multi => "comma-separated values",
+                FacetKind::Number | FacetKind::Date | FacetKind::Url => definition.kind.expected(),
+                _ => "",
+            };
+            let name = definition.display_name(key, lang);
+            let label = if definition.required {
+                format!("{}*", name)
+            } else {
+                name.to_string()
+            };
+
             FacetInput {
                 name: SharedString::from(key.as_str()),
+                label: SharedString::from(label),
                 value: SharedString::from(value),
+                widget: SharedString::from(widget),
+                options: Rc::new(VecModel::from(options)).into(),
+                placeholder: SharedString::from(placeholder),
+                error: SharedString::default(),
+                suggestions: ModelRc::default(),
             }
         })
         .collect();
@@ -69,36 +685,139 @@ pub fn create_facet_inputs(
     facet_inputs
 }
 
-/// Flatten hierarchy tree into a list of tree nodes with indentation levels
-pub fn flatten_hierarchy(hierarchy: &ClassicalHierarchy) -> Vec<TreeNode> {
-    let mut nodes = Vec::new();
+/// Create the edit form's changed-field rows: the inputs whose text differs from what
+/// the form showed for the stored item, in form order (name, path, facets, fields)
+pub fn create_dirty_fields(
+    item: &Item,
+    name: &str,
+    path: &str,
+    facet_inputs: &ModelRc<FacetInput>,
+    field_inputs: &ModelRc<FacetInput>,
+    schema: &TaxonomySchema,
+    lang: Option<&str>,
+) -> Vec<DirtyField> {
+    let mut dirty = Vec::new();
 
-    if let Some(ref children) = hierarchy.children {
-        for child in children {
-            flatten_node(child, 0, &mut nodes);
+    if item.name != name.trim() {
+        dirty.push(dirty_field("name", "", "Name", &item.name, name));
+    }
+    let old_path = item.classical_path.join(", ");
+    if old_path != list_text(path) {
+        dirty.push(dirty_field("path", "", "Path", &old_path, path));
+    }
+
+    let stored_facets = create_facet_inputs(&schema.faceted_dimensions, &item.facets, lang);
+    let stored_fields = create_field_inputs(&schema.item_fields, &item.extra);
+    for (kind, inputs, stored) in [
+        ("facet", facet_inputs, stored_facets),
+        ("field", field_inputs, stored_fields),
+    ] {
+        for input in inputs.iter() {
+            let old = stored
+                .iter()
+                .find(|stored| stored.name == input.name)
+                .map(|stored| stored.value.to_string())
+                .unwrap_or_default();
+            if list_text(&old) != list_text(&input.value) {
+                let label = input.label.trim_end_matches('*');
+                dirty.push(dirty_field(kind, &input.name, label, &old, &input.value));
+            }
         }
     }
 
-    nodes
+    dirty
 }
 
-/// Recursively flatten a hierarchy node and its children
-fn flatten_node(node: &HierarchyNode, indent_level: i32, nodes: &mut Vec<TreeNode>) {
-    // Format: "species (differentia)"
-    let label = if node.differentia.is_empty() {
-        node.species.clone()
-    } else {
-        format!("{} ({})", node.species, node.differentia)
-    };
-
-    nodes.push(TreeNode {
+fn dirty_field(kind: &str, key: &str, label: &str, old: &str, new: &str) -> DirtyField {
+    DirtyField {
+        kind: SharedString::from(kind),
+        key: SharedString::from(key),
         label: SharedString::from(label),
-        indent_level,
-    });
+        old: SharedString::from(old),
+        new: SharedString::from(new.trim()),
+    }
+}
 
-    if let Some(ref children) = node.children {
-        for child in children {
-            flatten_node(child, indent_level + 1, nodes);
-        }
+/// Comma-separated text with the spacing evened out, for comparing inputs
+fn list_text(text: &str) -> String {
+    text.split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Create input rows for the schema's custom item fields, sorted by name
+/// Rows reuse the facet input: "toggle" for boolean fields, "text" otherwise
+pub fn create_field_inputs(
+    fields: &HashMap<String, ItemField>,
+    extra: &HashMap<String, serde_json::Value>,
+) -> Vec<FacetInput> {
+    let mut field_inputs: Vec<FacetInput> = fields
+        .iter()
+        .map(|(key, field)| {
+            let value = extra.get(key).map(field_text).unwrap_or_default();
+            let widget = match field.kind {
+                FieldKind::Boolean => "toggle",
+                _ => "text",
+            };
+            let placeholder = match field.kind {
+                FieldKind::String | FieldKind::Boolean => "",
+                kind => kind.expected(),
+            };
+            let name = field.display_name(key);
+            let label = if field.required {
+                format!("{}*", name)
+            } else {
+                name.to_string()
+            };
+
+            FacetInput {
+                name: SharedString::from(key.as_str()),
+                label: SharedString::from(label),
+                value: SharedString::from(value),
+                widget: SharedString::from(widget),
+                options: ModelRc::default(),
+                placeholder: SharedString::from(placeholder),
+                error: SharedString::default(),
+                suggestions: ModelRc::default(),
+            }
+        })
+        .collect();
+
+    field_inputs.sort_by(|a, b| a.name.cmp(&b.name));
+    field_inputs
+}
+
+/// A custom field value as text: strings unquoted, null as empty
+fn field_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => String::new(),
+        value => value.to_string(),
     }
 }
+
+/// Flatten hierarchy tree into a list of tree nodes with indentation levels
+pub fn flatten_hierarchy(hierarchy: &ClassicalHierarchy, lang: Option<&str>) -> Vec<TreeNode> {
+    hierarchy
+        .iter_preorder()
+        .map(|(depth, node)| {
+            // Format: "[code] species (differentia)"
+            let species = node.display_name(lang);
+            let mut label = match &node.code {
+                Some(code) => format!("[{}] {}", code, species),
+                None => species.to_string(),
+            };
+            if !node.differentia.is_empty() {
+                label = format!("{} ({})", label, node.differentia);
+            }
+
+            TreeNode {
+                label: SharedString::from(label),
+                indent_level: depth as i32 - 1,
+                species: SharedString::from(&node.species),
+            }
+        })
+        .collect()
+}
@@ -7,5 +7,13 @@ pub use dialogs::{
     hide_confirmation, hide_error, hide_simple_confirmation, set_status, show_confirmation,
     show_error, show_simple_confirmation,
 };
-pub use formatting::{create_facet_inputs, format_facets};
-pub use updates::{refresh_ui_after_state_change, update_ui_from_state};
+pub use formatting::{
+    create_attachment_entries, create_breadcrumbs, create_change_entries, create_dirty_fields,
+    create_facet_inputs, create_field_inputs, create_link_entries, create_rename_rows,
+    create_similar_entries, format_facets, format_item_fields, format_provenance, import_target_at,
+};
+pub use updates::{
+    clear_selected_item, refresh_ui_after_state_change, show_item, update_comparison,
+    update_history, update_import_dialog, update_merge_dialog, update_recent_files,
+    update_statistics, update_ui_from_state,
+};
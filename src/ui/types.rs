@@ -1,6 +1,23 @@
 // Re-export Slint-generated types from crate root
 // These are generated by the slint! macro in main.rs
+pub use crate::AttachmentEntry;
+pub use crate::Breadcrumb;
+pub use crate::ChangeEntry;
+pub use crate::ChartSlice;
+pub use crate::CompareRow;
+pub use crate::CoverageBar;
+pub use crate::DirtyField;
 pub use crate::FacetInput;
+pub use crate::ImportColumn;
+pub use crate::ImportPreviewRow;
+pub use crate::ItemBadge;
+pub use crate::ItemRow;
+pub use crate::LinkEntry;
+pub use crate::MapShape;
+pub use crate::MergeConflictRow;
+pub use crate::Notification;
+pub use crate::RenamePreviewRow;
+pub use crate::SimilarEntry;
 pub use crate::StatusLevel;
 pub use crate::StatusMessage;
 pub use crate::TreeNode;
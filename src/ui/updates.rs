@@ -1,12 +1,25 @@
-use slint::{SharedString, StandardListViewItem, VecModel};
+use slint::{Color, ModelRc, SharedString, StandardListViewItem, VecModel};
 use std::cell::RefCell;
 use std::rc::Rc;
-use taxstud_core::{matches_filters, sort_items};
+use taxstud_core::{
+    diff_items, facet_distribution, filter_indices_with_schema, has_filters, lint_data, overview,
+    sort_indices_with_options, validate_data, viz, ChangeKind, GroupKey, GroupSummary, SortOptions,
+    ValidatorRegistry,
+};
 
-use crate::state::AppState;
+use crate::i18n::tr;
+use crate::state::{AppState, ListRow};
 use crate::ui::dialogs::set_status;
-use crate::ui::formatting::{flatten_hierarchy, format_facet_dimensions};
-use crate::ui::types::{StatusLevel, TreeNode};
+use crate::ui::formatting::{
+    create_change_entries, create_chart_slices, create_compare_rows, create_coverage_bars,
+    create_import_columns, create_import_rows, create_import_targets, create_item_badges,
+    create_item_diff_entries, create_map_shapes, create_merge_rows, flatten_hierarchy,
+    format_facet_dimensions, format_revision, format_trashed_item, with_icon,
+};
+use crate::ui::types::{
+    ChangeEntry, ChartSlice, CompareRow, CoverageBar, ItemRow, MapShape, SimilarEntry, StatusLevel,
+    TreeNode,
+};
 use crate::MainWindow;
 
 /// Refresh UI after a state-changing operation (edit, create, delete)
@@ -28,15 +41,99 @@ pub fn refresh_ui_after_state_change(
     set_status(main_window, status_message, level);
 }
 
+/// Select the item named `name` in the list and scroll to it, expanding its group
+/// False when the filters leave it out of the list
+pub fn show_item(main_window: &MainWindow, state: &Rc<RefCell<AppState>>, name: &str) -> bool {
+    if state.borrow().row_of_item(name).is_none() && state.borrow_mut().expand_groups_of(name) {
+        update_ui_from_state(main_window, state);
+    }
+    match state.borrow().row_of_item(name) {
+        Some(row) => {
+            main_window.set_scroll_to_item(row as i32);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Clear the details of the selected item
+pub fn clear_selected_item(main_window: &MainWindow) {
+    main_window.set_selected_item_name(SharedString::from(""));
+    main_window.set_selected_item_label(SharedString::from(""));
+    main_window.set_selected_item_breadcrumbs(ModelRc::default());
+    main_window.set_selected_item_facets(SharedString::from(""));
+    main_window.set_selected_item_fields(SharedString::from(""));
+    main_window.set_selected_item_attachments(ModelRc::default());
+    main_window.set_selected_item_links(ModelRc::default());
+    main_window.set_selected_item_source(SharedString::from(""));
+    main_window.set_selected_item_origin(SharedString::from(""));
+    main_window.set_selected_item_deprecated(false);
+    main_window.set_selected_item_similar(Rc::new(VecModel::<SimilarEntry>::default()).into());
+}
+
 /// Update the UI from the current application state
 pub fn update_ui_from_state(main_window: &MainWindow, state: &Rc<RefCell<AppState>>) {
     // Clear selected item
     main_window.set_selected_item_index(-1);
-    main_window.set_selected_item_name(SharedString::from(""));
-    main_window.set_selected_item_path(SharedString::from(""));
-    main_window.set_selected_item_facets(SharedString::from(""));
+    clear_selected_item(main_window);
 
     let state_borrow = state.borrow();
+    let lang = state_borrow.display_lang.as_deref();
+    main_window.set_display_language(SharedString::from(lang.unwrap_or("")));
+    main_window.set_embed_schema(state_borrow.embed_schema);
+    main_window.set_show_deprecated(state_borrow.filters.include_deprecated);
+    main_window.set_sort_field(SharedString::from(
+        state_borrow.sort_by.as_deref().unwrap_or_default(),
+    ));
+    main_window.set_sort_descending(state_borrow.sort_descending);
+    let facet_names = state_borrow.facet_names();
+    let sort_facets: Vec<SharedString> = facet_names.iter().map(SharedString::from).collect();
+    main_window.set_sort_facets(Rc::new(VecModel::from(sort_facets)).into());
+
+    // Group by: no grouping, then each facet
+    let group_choices: Vec<SharedString> = std::iter::once(tr!("No Grouping"))
+        .chain(facet_names.iter().cloned())
+        .map(SharedString::from)
+        .collect();
+    main_window.set_group_by_choices(Rc::new(VecModel::from(group_choices)).into());
+    let group_index = state_borrow
+        .group_by
+        .as_ref()
+        .and_then(|facet| facet_names.iter().position(|name| name == facet))
+        .map_or(0, |index| index as i32 + 1);
+    main_window.set_group_by_index(group_index);
+    main_window.set_group_show_missing(state_borrow.show_missing_group);
+    let summary_choices: Vec<SharedString> = std::iter::once(tr!("Count Only"))
+        .chain(facet_names.iter().cloned())
+        .map(SharedString::from)
+        .collect();
+    main_window.set_group_summary_choices(Rc::new(VecModel::from(summary_choices)).into());
+    let summary_index = state_borrow
+        .group_summary_facet
+        .as_ref()
+        .and_then(|facet| facet_names.iter().position(|name| name == facet))
+        .map_or(0, |index| index as i32 + 1);
+    main_window.set_group_summary_index(summary_index);
+
+    // Project sidebar, listing members as the project file names them
+    let (project_name, members): (&str, Vec<SharedString>) = match state_borrow.project {
+        Some(ref project) => (
+            project.name.as_str(),
+            project
+                .taxonomies
+                .iter()
+                .map(|path| SharedString::from(path.to_string_lossy().as_ref()))
+                .collect(),
+        ),
+        None => ("", Vec::new()),
+    };
+    main_window.set_project_name(SharedString::from(project_name));
+    main_window.set_project_members(Rc::new(VecModel::from(members)).into());
+    main_window.set_current_project_member(
+        state_borrow
+            .project_member_index()
+            .map_or(-1, |index| index as i32),
+    );
 
     // Update from schema (if present)
     if let Some(ref schema) = state_borrow.schema {
@@ -45,16 +142,27 @@ pub fn update_ui_from_state(main_window: &MainWindow, state: &Rc<RefCell<AppStat
         main_window.set_taxonomy_description(SharedString::from(description));
 
         // Update hierarchy root
-        main_window.set_hierarchy_root(SharedString::from(&schema.classical_hierarchy.root));
+        let hierarchy = &schema.classical_hierarchy;
+        main_window.set_hierarchy_root(SharedString::from(
+            hierarchy.display_name(&hierarchy.root, lang),
+        ));
 
         // Update hierarchy tree
-        let tree_nodes = flatten_hierarchy(&schema.classical_hierarchy);
+        let tree_nodes = flatten_hierarchy(hierarchy, lang);
         let tree_model = Rc::new(VecModel::from(tree_nodes));
         main_window.set_hierarchy_tree(tree_model.into());
 
         // Update facet dimensions
-        let facet_dims_text = format_facet_dimensions(&schema.faceted_dimensions);
+        let facet_dims_text = format_facet_dimensions(&schema.faceted_dimensions, lang);
         main_window.set_facet_dimensions_text(SharedString::from(facet_dims_text));
+
+        // Languages offered by the View → Language menu
+        let languages: Vec<SharedString> = schema
+            .languages()
+            .into_iter()
+            .map(SharedString::from)
+            .collect();
+        main_window.set_display_languages(Rc::new(VecModel::from(languages)).into());
     } else {
         // Clear schema-related UI
         main_window.set_taxonomy_description(SharedString::from(""));
@@ -62,42 +170,404 @@ pub fn update_ui_from_state(main_window: &MainWindow, state: &Rc<RefCell<AppStat
         let empty_tree_model = Rc::new(VecModel::<TreeNode>::default());
         main_window.set_hierarchy_tree(empty_tree_model.into());
         main_window.set_facet_dimensions_text(SharedString::from(""));
+        main_window.set_display_languages(Rc::new(VecModel::<SharedString>::default()).into());
     }
 
+    update_dashboard(main_window, &state_borrow);
+    update_statistics(main_window, &state_borrow);
+    update_hierarchy_map(main_window, &state_borrow);
+    update_history(main_window, &state_borrow);
+    update_comparison(main_window, &state_borrow);
+    update_trash(main_window, &state_borrow);
+    update_recent_files(main_window, &state_borrow);
+
     // Update items from data (if present)
     if let Some(ref data) = state_borrow.data {
-        // Start with all items
-        let mut items = data.items.clone();
+        main_window.set_total_item_count(data.items.len() as i32);
 
-        // Apply filters if any are active
+        // Apply filters if any are active (deprecated items are filtered out unless shown)
         let has_active_filters =
-            !state_borrow.filters.genera.is_empty() || !state_borrow.filters.facets.is_empty();
-        if has_active_filters {
-            items.retain(|item| matches_filters(item, &state_borrow.filters));
-        }
+            has_filters(&state_borrow.filters) || !state_borrow.filters.include_deprecated;
+        let mut indices = match state_borrow.schema {
+            Some(ref schema) if has_active_filters => {
+                filter_indices_with_schema(&data.items, &state_borrow.filters, schema)
+            }
+            _ => (0..data.items.len()).collect(),
+        };
 
         // Apply sorting if active
         if let Some(ref sort_field) = state_borrow.sort_by {
-            sort_items(&mut items, sort_field);
+            let options = SortOptions {
+                descending: state_borrow.sort_descending,
+                ..state_borrow.config.settings.sort_options()
+            };
+            sort_indices_with_options(
+                &data.items,
+                &mut indices,
+                sort_field,
+                state_borrow.schema.as_ref(),
+                &options,
+            );
         }
 
-        // Store displayed items for index mapping
+        // Store displayed items for index mapping, then list a row per item or group header
         drop(state_borrow);
-        state.borrow_mut().displayed_items = items.clone();
-
-        // Update UI with processed items
-        let items_model = Rc::new(VecModel::from(
-            items
-                .iter()
-                .map(|item| StandardListViewItem::from(SharedString::from(&item.name)))
-                .collect::<Vec<_>>(),
-        ));
-        main_window.set_items_list(items_model.into());
+        state.borrow_mut().set_displayed(indices);
+        let state_borrow = state.borrow();
+        let lang = state_borrow.display_lang.as_deref();
+        let schema = state_borrow.schema.as_ref();
+        let settings = &state_borrow.config.settings;
+        let grouped = matches!(state_borrow.list_rows.first(), Some(ListRow::Group { .. }));
+        let rows = state_borrow
+            .list_rows
+            .iter()
+            .map(|row| match row {
+                ListRow::Group { key, summary } => {
+                    let marker = if state_borrow.collapsed_groups.contains(key) {
+                        "▶"
+                    } else {
+                        "▼"
+                    };
+                    // The group's value in its display hint's icon and color, if any
+                    let hint = match key {
+                        GroupKey::Value(value) => state_borrow
+                            .group_by
+                            .as_deref()
+                            .and_then(|facet| schema?.display_hint(facet, value)),
+                        GroupKey::Missing(_) => None,
+                    };
+                    let color = hint
+                        .and_then(|hint| hint.color.as_deref())
+                        .and_then(viz::parse_hex_color)
+                        .map_or(Color::default(), |(r, g, b)| Color::from_rgb_u8(r, g, b));
+                    let mut text = format!(
+                        "{} {} ({})",
+                        marker,
+                        with_icon(key.name(), hint),
+                        summary.count
+                    );
+                    if let Some(facet) = state_borrow.group_summary_facet.as_deref() {
+                        text = format!("{} · {}: {}", text, facet, describe_summary(summary));
+                    }
+                    ItemRow {
+                        text: SharedString::from(text),
+                        header: true,
+                        color,
+                        badges: ModelRc::default(),
+                    }
+                }
+                ListRow::Item(index) => {
+                    let Some(item) = state_borrow.displayed_item(*index) else {
+                        return ItemRow::default();
+                    };
+                    let name = item.display_name(lang);
+                    let label = if item.is_deprecated() {
+                        tr!("{} (deprecated)", name)
+                    } else {
+                        name.to_string()
+                    };
+                    let text = if grouped {
+                        format!("    {}", label)
+                    } else {
+                        label
+                    };
+                    let badges = create_item_badges(item, settings, schema, lang);
+                    ItemRow {
+                        text: SharedString::from(text),
+                        header: false,
+                        color: Color::default(),
+                        badges: Rc::new(VecModel::from(badges)).into(),
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+        main_window.set_item_count(state_borrow.displayed.len() as i32);
+        main_window.set_items_list(Rc::new(VecModel::from(rows)).into());
     } else {
         drop(state_borrow);
-        state.borrow_mut().displayed_items = Vec::new();
+        state.borrow_mut().set_displayed(Vec::new());
 
-        let empty_model = Rc::new(VecModel::<StandardListViewItem>::default());
+        let empty_model = Rc::new(VecModel::<ItemRow>::default());
+        main_window.set_total_item_count(0);
+        main_window.set_item_count(0);
         main_window.set_items_list(empty_model.into());
     }
 }
+
+/// Most validation findings listed on the dashboard
+const MAX_DASHBOARD_ISSUES: usize = 10;
+
+/// Update the dashboard tab: overview figures, facet coverage, validation and recent changes
+fn update_dashboard(main_window: &MainWindow, state: &AppState) {
+    let changes: Vec<SharedString> = state
+        .recent_changes
+        .iter()
+        .map(SharedString::from)
+        .collect();
+    main_window.set_dashboard_recent_changes(Rc::new(VecModel::from(changes)).into());
+
+    let (Some(schema), Some(data)) = (&state.schema, &state.data) else {
+        main_window.set_dashboard_item_count(0);
+        main_window.set_dashboard_node_count(0);
+        main_window.set_dashboard_depth(0);
+        main_window.set_dashboard_coverage(Rc::new(VecModel::<CoverageBar>::default()).into());
+        main_window.set_dashboard_valid(true);
+        main_window.set_dashboard_validation_summary(SharedString::from(""));
+        main_window.set_dashboard_validation_details(SharedString::from(""));
+        return;
+    };
+
+    let summary = overview(schema, data);
+    main_window.set_dashboard_item_count(summary.item_count as i32);
+    main_window.set_dashboard_node_count(summary.node_count as i32);
+    main_window.set_dashboard_depth(summary.depth as i32);
+    let bars = create_coverage_bars(
+        &summary,
+        &schema.faceted_dimensions,
+        state.display_lang.as_deref(),
+    );
+    main_window.set_dashboard_coverage(Rc::new(VecModel::from(bars)).into());
+
+    let custom = ValidatorRegistry::default().check(schema, data);
+    let mut errors = validate_data(data, schema).err().unwrap_or_default();
+    errors.extend(custom.errors);
+    let mut warnings = lint_data(data, schema);
+    warnings.extend(custom.warnings);
+    main_window.set_dashboard_valid(errors.is_empty());
+    main_window.set_dashboard_validation_summary(SharedString::from(tr!(
+        "{} issue(s), {} warning(s)",
+        errors.len(),
+        warnings.len()
+    )));
+
+    let findings: Vec<&String> = errors.iter().chain(&warnings).collect();
+    let mut details: Vec<String> = findings
+        .iter()
+        .take(MAX_DASHBOARD_ISSUES)
+        .map(|finding| finding.to_string())
+        .collect();
+    if findings.len() > MAX_DASHBOARD_ISSUES {
+        details.push(tr!("… and {} more", findings.len() - MAX_DASHBOARD_ISSUES));
+    }
+    main_window.set_dashboard_validation_details(SharedString::from(details.join("\n")));
+}
+
+/// Update the History tab: the Git branch, the commits that changed the file and the
+/// version picked from them, with the changes made since
+pub fn update_history(main_window: &MainWindow, state: &AppState) {
+    let branch = state
+        .git
+        .as_ref()
+        .map(|git| git.branch().unwrap_or_else(|| "detached HEAD".to_string()));
+    main_window.set_git_branch(SharedString::from(branch.unwrap_or_default()));
+
+    let revisions: Vec<StandardListViewItem> = state
+        .history
+        .iter()
+        .map(|revision| StandardListViewItem::from(SharedString::from(format_revision(revision))))
+        .collect();
+    main_window.set_history_revisions(Rc::new(VecModel::from(revisions)).into());
+
+    let Some((index, ref historical)) = state.historical else {
+        main_window.set_history_current(-1);
+        main_window.set_history_items(Rc::new(VecModel::<StandardListViewItem>::default()).into());
+        main_window.set_history_changes(Rc::new(VecModel::<ChangeEntry>::default()).into());
+        return;
+    };
+    main_window.set_history_current(index as i32);
+
+    let lang = state.display_lang.as_deref();
+    let items: Vec<StandardListViewItem> = historical
+        .items
+        .iter()
+        .map(|item| {
+            let line = format!(
+                "{}  ({})",
+                item.display_name(lang),
+                item.classical_path.join(" → ")
+            );
+            StandardListViewItem::from(SharedString::from(line))
+        })
+        .collect();
+    main_window.set_history_items(Rc::new(VecModel::from(items)).into());
+
+    let current = state.get_items().map(Vec::as_slice).unwrap_or_default();
+    let changes = create_change_entries(&diff_items(&historical.items, current));
+    main_window.set_history_changes(Rc::new(VecModel::from(changes)).into());
+}
+
+/// Update the Trash tab: deleted items, newest first, and whether the file keeps them
+pub fn update_trash(main_window: &MainWindow, state: &AppState) {
+    let lang = state.display_lang.as_deref();
+    let items: Vec<StandardListViewItem> = state
+        .trash
+        .iter()
+        .map(|trashed| {
+            let line = format_trashed_item(&trashed.item, trashed.deleted_at, lang);
+            StandardListViewItem::from(SharedString::from(line))
+        })
+        .collect();
+    main_window.set_trash_items(Rc::new(VecModel::from(items)).into());
+    main_window.set_trash_current(-1);
+    main_window.set_keep_trash(state.keep_trash);
+}
+
+/// Update File → Open Recent
+pub fn update_recent_files(main_window: &MainWindow, state: &AppState) {
+    let files: Vec<SharedString> = state
+        .config
+        .recent_files
+        .iter()
+        .map(|path| SharedString::from(path.display().to_string()))
+        .collect();
+    main_window.set_recent_files(Rc::new(VecModel::from(files)).into());
+}
+
+/// Update the Compare tab: both versions' items side by side, the picked item's field
+/// differences and the schema's
+pub fn update_comparison(main_window: &MainWindow, state: &AppState) {
+    let Some(ref comparison) = state.comparison else {
+        main_window.set_compare_old_label(SharedString::from(""));
+        main_window.set_compare_new_label(SharedString::from(""));
+        main_window.set_compare_summary(SharedString::from(""));
+        main_window.set_compare_rows(Rc::new(VecModel::<CompareRow>::default()).into());
+        main_window.set_compare_current(-1);
+        main_window.set_compare_details(Rc::new(VecModel::<ChangeEntry>::default()).into());
+        main_window.set_compare_schema_changes(Rc::new(VecModel::<ChangeEntry>::default()).into());
+        return;
+    };
+    let diff = &comparison.diff;
+
+    main_window.set_compare_old_label(SharedString::from(&comparison.old_label));
+    main_window.set_compare_new_label(SharedString::from(&comparison.new_label));
+    main_window.set_compare_summary(SharedString::from(tr!(
+        "{} added, {} removed, {} changed, {} unchanged item(s); {} schema change(s)",
+        diff.count(Some(ChangeKind::Added)),
+        diff.count(Some(ChangeKind::Removed)),
+        diff.count(Some(ChangeKind::Changed)),
+        diff.count(None),
+        diff.schema.len()
+    )));
+    main_window.set_compare_rows(Rc::new(VecModel::from(create_compare_rows(diff))).into());
+
+    let selected = comparison
+        .selected
+        .and_then(|index| Some((index, diff.items.get(index)?)));
+    main_window.set_compare_current(selected.map_or(-1, |(index, _)| index as i32));
+    let details = selected.map_or_else(Vec::new, |(_, item)| create_item_diff_entries(item));
+    main_window.set_compare_details(Rc::new(VecModel::from(details)).into());
+    let schema_changes = create_change_entries(&diff.schema);
+    main_window.set_compare_schema_changes(Rc::new(VecModel::from(schema_changes)).into());
+}
+
+/// Update the item import dialog: column targets and the row preview
+/// Hides the dialog when no import is pending
+pub fn update_import_dialog(main_window: &MainWindow, state: &AppState) {
+    let (Some(import), Some(schema)) = (&state.pending_import, &state.schema) else {
+        main_window.set_show_import_items_dialog(false);
+        return;
+    };
+    let facet_names = state.facet_names();
+    let lang = state.display_lang.as_deref();
+
+    let targets = create_import_targets(&facet_names, &schema.faceted_dimensions, lang);
+    main_window.set_import_targets(Rc::new(VecModel::from(targets)).into());
+    let columns = create_import_columns(import, &facet_names);
+    main_window.set_import_columns(Rc::new(VecModel::from(columns)).into());
+
+    let rows = state.preview_item_import();
+    let valid = rows.iter().filter(|row| row.is_valid()).count();
+    main_window.set_import_rows(Rc::new(VecModel::from(create_import_rows(&rows))).into());
+    main_window.set_import_valid_count(valid as i32);
+}
+
+/// Update the merge dialog's conflicts; hides the dialog when no merge is pending
+pub fn update_merge_dialog(main_window: &MainWindow, state: &AppState) {
+    let Some(ref merge) = state.pending_merge else {
+        main_window.set_show_merge_dialog(false);
+        return;
+    };
+    let rows = create_merge_rows(&merge.result.conflicts, &merge.resolutions);
+    main_window.set_merge_rows(Rc::new(VecModel::from(rows)).into());
+}
+
+/// Update the statistics tab: the facet choices and the chosen facet's value distribution
+pub fn update_statistics(main_window: &MainWindow, state: &AppState) {
+    let lang = state.display_lang.as_deref();
+    let facet_names = state.facet_names();
+
+    let (Some(schema), Some(data)) = (&state.schema, &state.data) else {
+        main_window.set_statistics_facets(Rc::new(VecModel::<SharedString>::default()).into());
+        main_window.set_statistics_slices(Rc::new(VecModel::<ChartSlice>::default()).into());
+        main_window.set_statistics_missing(0);
+        return;
+    };
+
+    let labels: Vec<SharedString> = facet_names
+        .iter()
+        .map(|name| SharedString::from(schema.faceted_dimensions[name].display_name(name, lang)))
+        .collect();
+    main_window.set_statistics_facets(Rc::new(VecModel::from(labels)).into());
+
+    // Keep the choice in range when the schema changed
+    let index = main_window.get_statistics_facet_index();
+    let index = if index >= 0 && (index as usize) < facet_names.len() {
+        index as usize
+    } else {
+        0
+    };
+    main_window.set_statistics_facet_index(index as i32);
+
+    let Some(facet) = facet_names.get(index) else {
+        main_window.set_statistics_slices(Rc::new(VecModel::<ChartSlice>::default()).into());
+        main_window.set_statistics_missing(0);
+        return;
+    };
+    let distribution = facet_distribution(schema, &data.items, facet);
+    let slices = create_chart_slices(&distribution, schema.faceted_dimensions.get(facet), lang);
+    main_window.set_statistics_slices(Rc::new(VecModel::from(slices)).into());
+    main_window.set_statistics_missing(distribution.missing as i32);
+}
+
+/// Update the map tab: the treemap and sunburst of the classical hierarchy
+fn update_hierarchy_map(main_window: &MainWindow, state: &AppState) {
+    main_window.set_map_layout_width(viz::TREEMAP_WIDTH as f32);
+    main_window.set_map_layout_height(viz::TREEMAP_HEIGHT as f32);
+    main_window.set_map_hover_text(SharedString::from(""));
+
+    let shapes = match (&state.schema, &state.data) {
+        (Some(schema), Some(data)) => create_map_shapes(
+            &schema.classical_hierarchy,
+            &data.items,
+            state.display_lang.as_deref(),
+        ),
+        _ => Vec::new(),
+    };
+    main_window.set_map_shapes(Rc::new(VecModel::<MapShape>::from(shapes)).into());
+}
+
+/// The summarized facet's part of a group header: "min 2, max 5, avg 3.50" for a number
+/// facet, its first few distinct values otherwise
+fn describe_summary(summary: &GroupSummary) -> String {
+    /// Distinct values a header lists before eliding the rest
+    const MAX_VALUES: usize = 4;
+
+    if let Some(numeric) = &summary.numeric {
+        return tr!(
+            "min {}, max {}, avg {}",
+            numeric.min,
+            numeric.max,
+            format!("{:.2}", numeric.avg)
+        );
+    }
+    let values = &summary.distinct_values;
+    match values.len() {
+        0 => tr!("none"),
+        n if n <= MAX_VALUES => values.join(", "),
+        n => tr!(
+            "{} and {} more",
+            values[..MAX_VALUES].join(", "),
+            n - MAX_VALUES
+        ),
+    }
+}
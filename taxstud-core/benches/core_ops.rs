@@ -0,0 +1,139 @@
+//! Benchmarks of the hot paths: loading, validating, filtering, sorting and grouping
+//!
+//! Run with `cargo bench -p taxstud-core`; pass a filter to run some, e.g.
+//! `cargo bench -p taxstud-core -- sort/.*/10000`
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use taxstud_core::*;
+
+/// Item counts every operation is measured at
+const SIZES: &[usize] = &[1_000, 10_000, 100_000];
+
+const SEED: u64 = 42;
+
+/// The generated schema and data with `size` items
+fn fixture(size: usize) -> (TaxonomySchema, TaxonomyData) {
+    random_taxonomy(SEED, &SizeParams::items(size))
+}
+
+fn bench_load(c: &mut Criterion) {
+    let mut group = c.benchmark_group("load");
+    group.sample_size(10);
+    for &size in SIZES {
+        let (schema, data) = fixture(size);
+        let contents = serde_json::to_string(&data).unwrap();
+        group.bench_with_input(
+            BenchmarkId::from_parameter(size),
+            &contents,
+            |b, contents| {
+                b.iter(|| {
+                    parse_data_with_progress(black_box(contents), &schema, &mut |_| {}).unwrap()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_validate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("validate");
+    group.sample_size(10);
+    for &size in SIZES {
+        let (schema, data) = fixture(size);
+        // Time the success path, where every check runs to the end
+        assert_eq!(validate_data(&data, &schema), Ok(()));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &data, |b, data| {
+            b.iter(|| validate_data(black_box(data), &schema))
+        });
+    }
+    group.finish();
+}
+
+fn bench_filter(c: &mut Criterion) {
+    let filters = Filters {
+        genera: vec!["Coffee".to_string()],
+        facets: parse_facet_filters(&["temperature=hot".to_string()]),
+        include_descendants: true,
+        ..Default::default()
+    };
+    let mut group = c.benchmark_group("filter");
+    for &size in SIZES {
+        let (schema, TaxonomyData { items, .. }) = fixture(size);
+        group.bench_with_input(BenchmarkId::new("items", size), &items, |b, items| {
+            b.iter(|| apply_filters_with_schema(black_box(items), &filters, &schema))
+        });
+        group.bench_with_input(BenchmarkId::new("indices", size), &items, |b, items| {
+            b.iter(|| filter_indices_with_schema(black_box(items), &filters, &schema))
+        });
+    }
+    group.finish();
+}
+
+fn bench_sort(c: &mut Criterion) {
+    let options = SortOptions::default();
+    let mut group = c.benchmark_group("sort");
+    group.sample_size(10);
+    for &size in SIZES {
+        let (schema, TaxonomyData { items, .. }) = fixture(size);
+        for field in ["name", "temperature", "price"] {
+            group.bench_with_input(
+                BenchmarkId::new(format!("items/{}", field), size),
+                &items,
+                |b, items| {
+                    b.iter_batched(
+                        || items.clone(),
+                        |mut items| {
+                            sort_items_with_options(&mut items, field, Some(&schema), &options)
+                        },
+                        BatchSize::LargeInput,
+                    )
+                },
+            );
+            group.bench_with_input(
+                BenchmarkId::new(format!("indices/{}", field), size),
+                &items,
+                |b, items| {
+                    b.iter_batched(
+                        || (0..items.len()).collect::<Vec<_>>(),
+                        |mut indices| {
+                            sort_indices_with_options(
+                                items,
+                                &mut indices,
+                                field,
+                                Some(&schema),
+                                &options,
+                            )
+                        },
+                        BatchSize::LargeInput,
+                    )
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+fn bench_group(c: &mut Criterion) {
+    let mut group = c.benchmark_group("group");
+    for &size in SIZES {
+        let (_, TaxonomyData { items, .. }) = fixture(size);
+        let indices: Vec<usize> = (0..items.len()).collect();
+        group.bench_with_input(BenchmarkId::new("items", size), &items, |b, items| {
+            b.iter(|| group_items_by_facet(black_box(items), "origin"))
+        });
+        group.bench_with_input(BenchmarkId::new("indices", size), &items, |b, items| {
+            b.iter(|| group_indices_by_facet(black_box(items), &indices, "origin"))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_load,
+    bench_validate,
+    bench_filter,
+    bench_sort,
+    bench_group
+);
+criterion_main!(benches);
@@ -0,0 +1,632 @@
+use crate::models::{ClassicalHierarchy, Item, TaxonomyData, TaxonomySchema};
+use std::collections::{HashMap, HashSet};
+
+/// At-a-glance figures about a taxonomy, for dashboards and reports
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaxonomyOverview {
+    pub item_count: usize,
+    /// Species in the classical hierarchy (the root not included)
+    pub node_count: usize,
+    /// Levels below the root
+    pub depth: usize,
+    /// One entry per faceted dimension, sorted by facet name
+    pub facet_coverage: Vec<FacetCoverage>,
+}
+
+/// How many items give a value for a facet
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FacetCoverage {
+    pub facet: String,
+    pub items_with_value: usize,
+    pub item_count: usize,
+    /// Different values in use across the items
+    pub distinct_values: usize,
+}
+
+impl FacetCoverage {
+    /// Share of items with a value, from 0.0 to 1.0 (0.0 when there are no items)
+    pub fn ratio(&self) -> f64 {
+        if self.item_count == 0 {
+            0.0
+        } else {
+            self.items_with_value as f64 / self.item_count as f64
+        }
+    }
+}
+
+/// Summarize a schema and its data
+pub fn overview(schema: &TaxonomySchema, data: &TaxonomyData) -> TaxonomyOverview {
+    let hierarchy = &schema.classical_hierarchy;
+    let item_count = data.items.len();
+
+    let mut facet_coverage: Vec<FacetCoverage> = schema
+        .faceted_dimensions
+        .keys()
+        .map(|facet| {
+            let mut distinct = HashSet::new();
+            let mut items_with_value = 0;
+            for item in &data.items {
+                let values = item.get_facet_as_vec(facet);
+                if !values.is_empty() {
+                    items_with_value += 1;
+                }
+                distinct.extend(values);
+            }
+            FacetCoverage {
+                facet: facet.clone(),
+                items_with_value,
+                item_count,
+                distinct_values: distinct.len(),
+            }
+        })
+        .collect();
+    facet_coverage.sort_by(|a, b| a.facet.cmp(&b.facet));
+
+    TaxonomyOverview {
+        item_count,
+        node_count: hierarchy.iter_preorder().count(),
+        depth: hierarchy.depth(),
+        facet_coverage,
+    }
+}
+
+/// How often each value of a facet is used, for distribution charts
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FacetDistribution {
+    pub facet: String,
+    /// Most used first, ties by value; the schema's allowed values appear even when unused
+    pub counts: Vec<ValueCount>,
+    /// Items with no value for the facet
+    pub missing: usize,
+}
+
+/// One value of a facet and the number of items using it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueCount {
+    pub value: String,
+    pub count: usize,
+}
+
+impl FacetDistribution {
+    /// Highest count of any value (0 when nothing is counted)
+    pub fn max_count(&self) -> usize {
+        self.counts.iter().map(|c| c.count).max().unwrap_or(0)
+    }
+
+    /// Total of all counts; items with several values count once per value
+    pub fn total(&self) -> usize {
+        self.counts.iter().map(|c| c.count).sum()
+    }
+}
+
+/// Count the values of a facet across items
+pub fn facet_distribution(
+    schema: &TaxonomySchema,
+    items: &[Item],
+    facet: &str,
+) -> FacetDistribution {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    if let Some(definition) = schema.faceted_dimensions.get(facet) {
+        for value in &definition.allowed_values {
+            counts.insert(value.clone(), 0);
+        }
+    }
+
+    let mut missing = 0;
+    for item in items {
+        let values = item.get_facet_as_vec(facet);
+        if values.is_empty() {
+            missing += 1;
+        }
+        for value in values {
+            *counts.entry(value).or_insert(0) += 1;
+        }
+    }
+
+    let mut counts: Vec<ValueCount> = counts
+        .into_iter()
+        .map(|(value, count)| ValueCount { value, count })
+        .collect();
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+
+    FacetDistribution {
+        facet: facet.to_string(),
+        counts,
+        missing,
+    }
+}
+
+/// How much each kind of agreement counts in `similar_items`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimilarityWeights {
+    /// Weight of the shared classical path prefix
+    pub path: f64,
+    /// Weight of the shared facet values
+    pub facets: f64,
+}
+
+impl Default for SimilarityWeights {
+    fn default() -> Self {
+        SimilarityWeights {
+            path: 1.0,
+            facets: 1.0,
+        }
+    }
+}
+
+/// An item found by `similar_items`
+#[derive(Debug, Clone)]
+pub struct SimilarItem<'a> {
+    pub item: &'a Item,
+    /// From 0.0 (nothing in common) to 1.0 (same path and facet values)
+    pub score: f64,
+    /// Facets this item sets that the compared item leaves empty, sorted
+    pub unset_facets: Vec<String>,
+}
+
+/// Similarity of two items from 0.0 to 1.0: the weighted mean of the share of the longer
+/// classical path they have in common (as a prefix) and the overlap of their facet values
+/// (shared facet=value pairs over all pairs either has)
+pub fn similarity(a: &Item, b: &Item, weights: &SimilarityWeights) -> f64 {
+    let total_weight = weights.path + weights.facets;
+    if total_weight <= 0.0 {
+        return 0.0;
+    }
+
+    let longest = a.classical_path.len().max(b.classical_path.len());
+    let path_score = if longest == 0 {
+        0.0
+    } else {
+        let shared = a
+            .classical_path
+            .iter()
+            .zip(&b.classical_path)
+            .take_while(|(x, y)| x == y)
+            .count();
+        shared as f64 / longest as f64
+    };
+
+    let pairs = |item: &Item| -> HashSet<(String, String)> {
+        item.facets
+            .keys()
+            .flat_map(|facet| {
+                item.get_facet_as_vec(facet)
+                    .into_iter()
+                    .map(move |value| (facet.clone(), value))
+            })
+            .collect()
+    };
+    let (a_pairs, b_pairs) = (pairs(a), pairs(b));
+    let union = a_pairs.union(&b_pairs).count();
+    let facet_score = if union == 0 {
+        0.0
+    } else {
+        a_pairs.intersection(&b_pairs).count() as f64 / union as f64
+    };
+
+    (weights.path * path_score + weights.facets * facet_score) / total_weight
+}
+
+/// Items resembling `item`, most similar first (ties by name)
+/// Items with the same name as `item` (itself, usually) and items with nothing in common are left out
+pub fn similar_items<'a>(
+    item: &Item,
+    items: &'a [Item],
+    weights: &SimilarityWeights,
+) -> Vec<SimilarItem<'a>> {
+    let mut similar: Vec<SimilarItem> = items
+        .iter()
+        .filter(|other| other.name != item.name)
+        .filter_map(|other| {
+            let score = similarity(item, other, weights);
+            if score <= 0.0 {
+                return None;
+            }
+            let mut unset_facets: Vec<String> = other
+                .facets
+                .keys()
+                .filter(|facet| {
+                    !other.get_facet_as_vec(facet).is_empty()
+                        && item.get_facet_as_vec(facet).is_empty()
+                })
+                .cloned()
+                .collect();
+            unset_facets.sort();
+            Some(SimilarItem {
+                item: other,
+                score,
+                unset_facets,
+            })
+        })
+        .collect();
+
+    similar.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.item.name.cmp(&b.item.name))
+    });
+    similar
+}
+
+/// Likely facet values for a new item classified under `path`, from its siblings: the items
+/// with the same path or, when there are none, the items under the nearest ancestor that has any
+/// Each value's score is the share of those items using it; most likely first (ties by value)
+pub fn suggest_facets(path: &[String], items: &[Item]) -> HashMap<String, Vec<(String, f64)>> {
+    let siblings: Vec<&Item> = (1..=path.len())
+        .rev()
+        .map(|len| {
+            items
+                .iter()
+                .filter(|item| {
+                    let item_path = &item.classical_path;
+                    if len == path.len() {
+                        item_path.as_slice() == path
+                    } else {
+                        item_path.starts_with(&path[..len])
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .find(|siblings| !siblings.is_empty())
+        .unwrap_or_default();
+
+    let mut counts: HashMap<String, HashMap<String, usize>> = HashMap::new();
+    for item in &siblings {
+        for facet in item.facets.keys() {
+            let values: HashSet<String> = item.get_facet_as_vec(facet).into_iter().collect();
+            for value in values {
+                *counts
+                    .entry(facet.clone())
+                    .or_default()
+                    .entry(value)
+                    .or_insert(0) += 1;
+            }
+        }
+    }
+
+    counts
+        .into_iter()
+        .map(|(facet, values)| {
+            let mut scored: Vec<(String, f64)> = values
+                .into_iter()
+                .map(|(value, count)| (value, count as f64 / siblings.len() as f64))
+                .collect();
+            scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            (facet, scored)
+        })
+        .collect()
+}
+
+/// Propose classical paths for a new item from its name, best first (ties by path)
+/// A species scores the share of its name's (or a synonym's) words found in the item name;
+/// a classified item lends its path the word overlap (Jaccard) of the two names
+pub fn suggest_path(
+    name: &str,
+    hierarchy: &ClassicalHierarchy,
+    items: &[Item],
+) -> Vec<(Vec<String>, f32)> {
+    let words = name_words(name);
+    if words.is_empty() {
+        return Vec::new();
+    }
+
+    let mut scores: HashMap<Vec<String>, f32> = HashMap::new();
+    let mut vote = |path: Vec<String>, score: f32| {
+        if score > 0.0 {
+            let best = scores.entry(path).or_insert(0.0);
+            *best = best.max(score);
+        }
+    };
+
+    for (_, node) in hierarchy.iter_preorder() {
+        let score = std::iter::once(&node.species)
+            .chain(&node.synonyms)
+            .map(|known| {
+                let known = name_words(known);
+                let found = known.intersection(&words).count();
+                found as f32 / known.len().max(1) as f32
+            })
+            .fold(0.0, f32::max);
+        if let Some(path) = hierarchy.path_to(&node.species) {
+            vote(path, score);
+        }
+    }
+
+    for item in items.iter().filter(|item| !item.classical_path.is_empty()) {
+        let other = name_words(&item.name);
+        let shared = other.intersection(&words).count();
+        let union = other.union(&words).count();
+        vote(item.classical_path.clone(), shared as f32 / union as f32);
+    }
+
+    let mut suggestions: Vec<(Vec<String>, f32)> = scores.into_iter().collect();
+    suggestions.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    suggestions
+}
+
+/// How well `query` fuzzy-matches `text`: None unless its characters all appear in `text`
+/// in order (ignoring case); more when they run together or start words, less the longer
+/// `text` is. An empty query matches everything equally
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    if query.is_empty() {
+        return Some(0);
+    }
+    let mut query = query.chars().flat_map(char::to_lowercase).peekable();
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    let mut previous_matched = false;
+    let mut length = 0;
+
+    for c in text.chars() {
+        length += 1;
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        let matched = query.peek() == Some(&lower);
+        if matched {
+            query.next();
+            score += 10;
+            if previous_matched {
+                score += 15;
+            }
+            if !previous.is_some_and(char::is_alphanumeric) {
+                score += 20;
+            }
+        }
+        previous_matched = matched;
+        previous = Some(c);
+    }
+
+    if query.peek().is_some() {
+        return None;
+    }
+    Some(score - length)
+}
+
+/// The items whose name, or its translation into `lang`, fuzzy-matches `query`, best
+/// first (ties in item order), at most `limit` of them
+pub fn fuzzy_find_items<'a>(
+    query: &str,
+    items: &'a [Item],
+    lang: Option<&str>,
+    limit: usize,
+) -> Vec<&'a Item> {
+    let mut matches: Vec<(&Item, i64)> = items
+        .iter()
+        .filter_map(|item| {
+            let name = fuzzy_score(query, &item.name);
+            let label = fuzzy_score(query, item.display_name(lang));
+            name.max(label).map(|score| (item, score))
+        })
+        .collect();
+    // Stable, so equal scores keep the items' order
+    matches.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    matches
+        .into_iter()
+        .take(limit)
+        .map(|(item, _)| item)
+        .collect()
+}
+
+/// Lowercased words of a name, split at anything that isn't a letter or digit
+fn name_words(name: &str) -> HashSet<String> {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{HierarchyBuilder, ItemBuilder, TaxonomySchemaBuilder};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_similar_items() {
+        let latte = ItemBuilder::new("Latte")
+            .path(["Beverage", "Hot Beverage", "Coffee"])
+            .facet("temperature", "hot")
+            .build();
+        let items = vec![
+            latte.clone(),
+            ItemBuilder::new("Caffe Latte")
+                .path(["Beverage", "Hot Beverage", "Coffee"])
+                .facet("temperature", "hot")
+                .facet("flavor", "vanilla")
+                .build(),
+            ItemBuilder::new("Green Tea")
+                .path(["Beverage", "Hot Beverage", "Tea"])
+                .build(),
+            ItemBuilder::new("Rock").build(),
+        ];
+
+        let similar = similar_items(&latte, &items, &SimilarityWeights::default());
+        let names: Vec<&str> = similar.iter().map(|s| s.item.name.as_str()).collect();
+        assert_eq!(names, vec!["Caffe Latte", "Green Tea"]);
+        // Same path (1.0) and one of two facet pairs (0.5)
+        assert_eq!(similar[0].score, 0.75);
+        assert_eq!(similar[0].unset_facets, vec!["flavor"]);
+        // Two of three path levels, no facets
+        assert!((similar[1].score - 1.0 / 3.0).abs() < 1e-9);
+
+        let facets_only = SimilarityWeights {
+            path: 0.0,
+            facets: 1.0,
+        };
+        let similar = similar_items(&latte, &items, &facets_only);
+        assert_eq!(similar.len(), 1);
+    }
+
+    #[test]
+    fn test_suggest_facets() {
+        let coffee = |name: &str, flavor: &str| {
+            ItemBuilder::new(name)
+                .path(["Beverage", "Hot Beverage", "Coffee"])
+                .facet("temperature", "hot")
+                .facet("flavor", flavor)
+                .build()
+        };
+        let items = vec![
+            coffee("Latte", "vanilla"),
+            coffee("Mocha", "chocolate"),
+            coffee("Breve", "vanilla"),
+            ItemBuilder::new("Iced Tea")
+                .path(["Beverage", "Cold Beverage"])
+                .facet("temperature", "iced")
+                .build(),
+        ];
+        let path =
+            |segments: &[&str]| -> Vec<String> { segments.iter().map(|s| s.to_string()).collect() };
+
+        let suggestions = suggest_facets(&path(&["Beverage", "Hot Beverage", "Coffee"]), &items);
+        assert_eq!(suggestions["temperature"], vec![("hot".to_string(), 1.0)]);
+        assert_eq!(suggestions["flavor"][0].0, "vanilla");
+        assert!((suggestions["flavor"][0].1 - 2.0 / 3.0).abs() < 1e-9);
+
+        // No tea yet: fall back to everything under Hot Beverage
+        let suggestions = suggest_facets(&path(&["Beverage", "Hot Beverage", "Tea"]), &items);
+        assert_eq!(suggestions["temperature"], vec![("hot".to_string(), 1.0)]);
+
+        assert!(suggest_facets(&[], &items).is_empty());
+    }
+
+    #[test]
+    fn test_suggest_path() {
+        let hierarchy = HierarchyBuilder::new()
+            .root("Beverage")
+            .child("Hot Beverage", "served heated", |c| {
+                c.child("Coffee", "brewed from beans", |c| c.synonym("Java"))
+                    .leaf("Tea", "steeped from leaves")
+            })
+            .build();
+        let items = vec![ItemBuilder::new("Vanilla Latte")
+            .path(["Beverage", "Hot Beverage", "Coffee"])
+            .build()];
+        let path =
+            |segments: &[&str]| -> Vec<String> { segments.iter().map(|s| s.to_string()).collect() };
+
+        let suggestions = suggest_path("Green tea", &hierarchy, &items);
+        assert_eq!(
+            suggestions,
+            vec![(path(&["Beverage", "Hot Beverage", "Tea"]), 1.0)]
+        );
+
+        // Two of three words shared with an existing item
+        let suggestions = suggest_path("Iced Vanilla Latte", &hierarchy, &items);
+        assert_eq!(
+            suggestions[0].0,
+            path(&["Beverage", "Hot Beverage", "Coffee"])
+        );
+        assert!((suggestions[0].1 - 2.0 / 3.0).abs() < 1e-6);
+
+        let suggestions = suggest_path("Java Chip", &hierarchy, &items);
+        assert_eq!(
+            suggestions[0].0,
+            path(&["Beverage", "Hot Beverage", "Coffee"])
+        );
+
+        // One of the two words of "Hot Beverage"
+        let suggestions = suggest_path("Hot Chocolate", &hierarchy, &items);
+        assert_eq!(
+            suggestions,
+            vec![(path(&["Beverage", "Hot Beverage"]), 0.5)]
+        );
+
+        assert!(suggest_path("Rock", &hierarchy, &items).is_empty());
+        assert!(suggest_path("", &hierarchy, &items).is_empty());
+    }
+
+    #[test]
+    fn test_fuzzy_find_items() {
+        let items = vec![
+            ItemBuilder::new("Earl Grey").build(),
+            ItemBuilder::new("English Breakfast").build(),
+            ItemBuilder::new("Green Tea").build(),
+        ];
+        let names = |found: Vec<&Item>| -> Vec<String> {
+            found.iter().map(|item| item.name.clone()).collect()
+        };
+
+        // Word starts beat letters scattered through a name
+        assert_eq!(
+            names(fuzzy_find_items("eg", &items, None, 10)),
+            vec!["Earl Grey", "English Breakfast"]
+        );
+        assert_eq!(
+            names(fuzzy_find_items("GREEN", &items, None, 10)),
+            vec!["Green Tea"]
+        );
+        assert_eq!(
+            names(fuzzy_find_items("bfast", &items, None, 10)),
+            vec!["English Breakfast"]
+        );
+        assert!(fuzzy_find_items("xyz", &items, None, 10).is_empty());
+
+        // An empty query lists the items in order, up to the limit
+        assert_eq!(
+            names(fuzzy_find_items("", &items, None, 2)),
+            vec!["Earl Grey", "English Breakfast"]
+        );
+
+        assert!(fuzzy_score("ab", "ba").is_none());
+        assert!(fuzzy_score("tea", "Tea").unwrap() > fuzzy_score("tea", "Tear gas").unwrap());
+    }
+
+    #[test]
+    fn test_overview() {
+        let schema = TaxonomySchemaBuilder::new("Beverages")
+            .hierarchy(
+                HierarchyBuilder::new()
+                    .root("Beverage")
+                    .child("Hot Beverage", "served heated", |c| {
+                        c.leaf("Coffee", "brewed from beans")
+                    })
+                    .build(),
+            )
+            .facet("temperature", ["hot", "iced"])
+            .facet("flavor", ["vanilla", "caramel"])
+            .build();
+        let data = TaxonomyData {
+            schema: "schema.json".to_string(),
+            items: vec![
+                ItemBuilder::new("Latte")
+                    .facet("temperature", "hot")
+                    .facet_values("flavor", ["vanilla", "caramel"])
+                    .build(),
+                ItemBuilder::new("Cold Brew")
+                    .facet("temperature", "iced")
+                    .build(),
+            ],
+            extra: HashMap::new(),
+        };
+
+        let overview = overview(&schema, &data);
+        assert_eq!(overview.item_count, 2);
+        assert_eq!(overview.node_count, 2);
+        assert_eq!(overview.depth, 2);
+
+        let flavor = &overview.facet_coverage[0];
+        assert_eq!(flavor.facet, "flavor");
+        assert_eq!(flavor.items_with_value, 1);
+        assert_eq!(flavor.distinct_values, 2);
+        assert_eq!(flavor.ratio(), 0.5);
+        assert_eq!(overview.facet_coverage[1].ratio(), 1.0);
+
+        let flavors = facet_distribution(&schema, &data.items, "flavor");
+        assert_eq!(flavors.missing, 1);
+        assert_eq!(flavors.max_count(), 1);
+        let temperatures = facet_distribution(&schema, &data.items[..1], "temperature");
+        assert_eq!(
+            temperatures.counts,
+            vec![
+                ValueCount {
+                    value: "hot".to_string(),
+                    count: 1
+                },
+                ValueCount {
+                    value: "iced".to_string(),
+                    count: 0
+                },
+            ]
+        );
+    }
+}
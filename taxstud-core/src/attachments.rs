@@ -0,0 +1,96 @@
+use crate::fields::is_uri;
+use crate::models::Attachment;
+use std::path::{Path, PathBuf};
+
+/// File extensions shown as images when an attachment gives no kind
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "svg", "bmp"];
+
+impl Attachment {
+    /// An attachment of a URL or a path relative to the data file
+    pub fn new(path_or_url: impl Into<String>) -> Self {
+        Self {
+            path_or_url: path_or_url.into(),
+            kind: String::new(),
+            caption: None,
+            extra: Default::default(),
+        }
+    }
+
+    /// Whether it points at a web (or other URL) resource rather than a local file
+    /// A one-letter scheme is taken for a Windows drive, e.g. C:\photos\mug.jpg
+    pub fn is_url(&self) -> bool {
+        is_uri(&self.path_or_url)
+            && self
+                .path_or_url
+                .split_once(':')
+                .is_some_and(|(scheme, _)| scheme.len() > 1)
+    }
+
+    /// Whether to show it as an image: kind "image", or an image file extension
+    /// when no kind is given
+    pub fn is_image(&self) -> bool {
+        if !self.kind.is_empty() {
+            return self.kind.eq_ignore_ascii_case("image");
+        }
+        let name = self
+            .path_or_url
+            .split(['?', '#'])
+            .next()
+            .unwrap_or_default();
+        Path::new(name)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                IMAGE_EXTENSIONS
+                    .iter()
+                    .any(|image| ext.eq_ignore_ascii_case(image))
+            })
+    }
+
+    /// The local file, resolved against the directory of the item's data file
+    /// None for URLs
+    pub fn local_path(&self, data_dir: &Path) -> Option<PathBuf> {
+        (!self.is_url()).then(|| data_dir.join(&self.path_or_url))
+    }
+
+    /// Caption to show, falling back to the file name or URL
+    pub fn display_caption(&self) -> &str {
+        match &self.caption {
+            Some(caption) if !caption.is_empty() => caption,
+            _ => self
+                .path_or_url
+                .rsplit(['/', '\\'])
+                .find(|part| !part.is_empty())
+                .unwrap_or(&self.path_or_url),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attachment_location() {
+        let photo = Attachment::new("photos/mug.jpg");
+        assert!(!photo.is_url());
+        assert!(photo.is_image());
+        assert_eq!(
+            photo.local_path(Path::new("/data")),
+            Some(PathBuf::from("/data/photos/mug.jpg"))
+        );
+        assert_eq!(photo.display_caption(), "mug.jpg");
+
+        let sheet = Attachment {
+            kind: "document".to_string(),
+            caption: Some("Spec sheet".to_string()),
+            ..Attachment::new("https://example.com/mug.png")
+        };
+        assert!(sheet.is_url());
+        assert!(!sheet.is_image());
+        assert_eq!(sheet.local_path(Path::new("/data")), None);
+        assert_eq!(sheet.display_caption(), "Spec sheet");
+
+        assert!(!Attachment::new(r"C:\photos\mug.jpg").is_url());
+    }
+}
@@ -0,0 +1,407 @@
+use crate::hierarchy::flatten_facet_values;
+use crate::models::{
+    Attachment, ClassicalHierarchy, FacetConstraint, FacetDefinition, FacetValueNode,
+    HierarchyNode, Item, ItemField, Relation, TaxonomySchema,
+};
+use crate::schema_validation::JsonSchemaDocument;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Fluent construction of a classical hierarchy
+/// Genus links are filled in from the nesting, so they can't disagree with the parent
+///
+/// ```
+/// use taxstud_core::HierarchyBuilder;
+///
+/// let hierarchy = HierarchyBuilder::new()
+///     .root("Beverage")
+///     .child("Hot Beverage", "served heated", |c| {
+///         c.leaf("Coffee", "brewed from roasted coffee beans")
+///             .leaf("Tea", "steeped from tea leaves")
+///     })
+///     .leaf("Cold Beverage", "served chilled")
+///     .build();
+///
+/// assert_eq!(hierarchy.root, "Beverage");
+/// ```
+#[derive(Debug, Default)]
+pub struct HierarchyBuilder {
+    root: String,
+    children: Vec<HierarchyNode>,
+    labels: HashMap<String, String>,
+}
+
+impl HierarchyBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the root; call before adding children, which take it as their genus
+    pub fn root(mut self, root: impl Into<String>) -> Self {
+        self.root = root.into();
+        self
+    }
+
+    /// Set the root name's translation for a language
+    pub fn root_label(mut self, lang: impl Into<String>, label: impl Into<String>) -> Self {
+        self.labels.insert(lang.into(), label.into());
+        self
+    }
+
+    /// Add a child of the root, populated by `build`
+    pub fn child(
+        mut self,
+        species: impl Into<String>,
+        differentia: impl Into<String>,
+        build: impl FnOnce(NodeBuilder) -> NodeBuilder,
+    ) -> Self {
+        let node = NodeBuilder::new(&self.root, species.into(), differentia.into());
+        self.children.push(build(node).build());
+        self
+    }
+
+    /// Add a child of the root with no children of its own
+    pub fn leaf(self, species: impl Into<String>, differentia: impl Into<String>) -> Self {
+        self.child(species, differentia, |c| c)
+    }
+
+    pub fn build(self) -> ClassicalHierarchy {
+        ClassicalHierarchy {
+            root: self.root,
+            children: non_empty(self.children),
+            labels: self.labels,
+        }
+    }
+}
+
+/// Builder for a hierarchy node, handed to the closures of `child`
+#[derive(Debug)]
+pub struct NodeBuilder {
+    genus: String,
+    species: String,
+    differentia: String,
+    children: Vec<HierarchyNode>,
+    synonyms: Vec<String>,
+    code: Option<String>,
+    external_ids: HashMap<String, String>,
+    labels: HashMap<String, String>,
+}
+
+impl NodeBuilder {
+    fn new(genus: &str, species: String, differentia: String) -> Self {
+        Self {
+            genus: genus.to_string(),
+            species,
+            differentia,
+            children: Vec::new(),
+            synonyms: Vec::new(),
+            code: None,
+            external_ids: HashMap::new(),
+            labels: HashMap::new(),
+        }
+    }
+
+    /// Add another name the species is known by
+    pub fn synonym(mut self, synonym: impl Into<String>) -> Self {
+        self.synonyms.push(synonym.into());
+        self
+    }
+
+    /// Set the node's code
+    pub fn code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+
+    /// Set the species name's translation for a language
+    pub fn label(mut self, lang: impl Into<String>, label: impl Into<String>) -> Self {
+        self.labels.insert(lang.into(), label.into());
+        self
+    }
+
+    /// Set the node's identifier in an external classification system
+    pub fn external_id(mut self, system: impl Into<String>, id: impl Into<String>) -> Self {
+        self.external_ids.insert(system.into(), id.into());
+        self
+    }
+
+    /// Add a child of this node, populated by `build`
+    pub fn child(
+        mut self,
+        species: impl Into<String>,
+        differentia: impl Into<String>,
+        build: impl FnOnce(NodeBuilder) -> NodeBuilder,
+    ) -> Self {
+        let node = NodeBuilder::new(&self.species, species.into(), differentia.into());
+        self.children.push(build(node).build());
+        self
+    }
+
+    /// Add a child of this node with no children of its own
+    pub fn leaf(self, species: impl Into<String>, differentia: impl Into<String>) -> Self {
+        self.child(species, differentia, |c| c)
+    }
+
+    fn build(self) -> HierarchyNode {
+        HierarchyNode {
+            genus: self.genus,
+            species: self.species,
+            differentia: self.differentia,
+            children: non_empty(self.children),
+            synonyms: self.synonyms,
+            code: self.code,
+            external_ids: self.external_ids,
+            labels: self.labels,
+        }
+    }
+}
+
+/// Fluent construction of a TaxonomySchema
+/// The result has no raw JSON Schema, so loading data against it skips JSON Schema validation
+#[derive(Debug)]
+pub struct TaxonomySchemaBuilder {
+    schema_id: String,
+    title: String,
+    description: Option<String>,
+    hierarchy: ClassicalHierarchy,
+    facets: HashMap<String, FacetDefinition>,
+    facet_trees: HashMap<String, Vec<FacetValueNode>>,
+    constraints: Vec<FacetConstraint>,
+    item_fields: HashMap<String, ItemField>,
+}
+
+impl TaxonomySchemaBuilder {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            schema_id: "unknown".to_string(),
+            title: title.into(),
+            description: None,
+            hierarchy: HierarchyBuilder::new().build(),
+            facets: HashMap::new(),
+            facet_trees: HashMap::new(),
+            constraints: Vec::new(),
+            item_fields: HashMap::new(),
+        }
+    }
+
+    pub fn id(mut self, schema_id: impl Into<String>) -> Self {
+        self.schema_id = schema_id.into();
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn hierarchy(mut self, hierarchy: ClassicalHierarchy) -> Self {
+        self.hierarchy = hierarchy;
+        self
+    }
+
+    /// Add an enum faceted dimension with its allowed values
+    pub fn facet<I, S>(self, name: impl Into<String>, values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.facet_definition(name, FacetDefinition::enumerated(values))
+    }
+
+    /// Add a faceted dimension with a full definition (kind, required, multi)
+    pub fn facet_definition(
+        mut self,
+        name: impl Into<String>,
+        definition: FacetDefinition,
+    ) -> Self {
+        self.facets.insert(name.into(), definition);
+        self
+    }
+
+    /// Add a faceted dimension whose values are nested
+    pub fn facet_tree(mut self, name: impl Into<String>, values: Vec<FacetValueNode>) -> Self {
+        let name = name.into();
+        self.facets.insert(
+            name.clone(),
+            FacetDefinition::enumerated(flatten_facet_values(&values)),
+        );
+        self.facet_trees.insert(name, values);
+        self
+    }
+
+    /// Declare a custom per-item field
+    pub fn item_field(mut self, name: impl Into<String>, field: ItemField) -> Self {
+        self.item_fields.insert(name.into(), field);
+        self
+    }
+
+    /// Add a rule between facets, e.g. `FacetConstraint::implies(...)`
+    pub fn constraint(mut self, constraint: FacetConstraint) -> Self {
+        self.constraints.push(constraint);
+        self
+    }
+
+    pub fn build(self) -> TaxonomySchema {
+        TaxonomySchema {
+            schema_id: self.schema_id,
+            title: self.title,
+            description: self.description,
+            classical_hierarchy: self.hierarchy,
+            faceted_dimensions: self.facets,
+            facet_value_trees: self.facet_trees,
+            constraints: self.constraints,
+            item_fields: self.item_fields,
+            display_hints: HashMap::new(),
+            json: JsonSchemaDocument::default(),
+        }
+    }
+}
+
+/// Fluent construction of an Item
+#[derive(Debug)]
+pub struct ItemBuilder {
+    item: Item,
+}
+
+impl ItemBuilder {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            item: Item {
+                name: name.into(),
+                classical_path: Vec::new(),
+                facets: HashMap::new(),
+                labels: HashMap::new(),
+                relations: Vec::new(),
+                attachments: Vec::new(),
+                extra: HashMap::new(),
+            },
+        }
+    }
+
+    /// Set the classical path, starting at the root
+    pub fn path<I, S>(mut self, path: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.item.classical_path = path.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set a single-valued facet
+    pub fn facet(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.item
+            .facets
+            .insert(name.into(), Value::String(value.into()));
+        self
+    }
+
+    /// Set a multi-valued facet
+    pub fn facet_values<I, S>(mut self, name: impl Into<String>, values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let values = values
+            .into_iter()
+            .map(|v| Value::String(v.into()))
+            .collect();
+        self.item.facets.insert(name.into(), Value::Array(values));
+        self
+    }
+
+    /// Set the item name's translation for a language
+    pub fn label(mut self, lang: impl Into<String>, label: impl Into<String>) -> Self {
+        self.item.labels.insert(lang.into(), label.into());
+        self
+    }
+
+    /// Add a link to another item or hierarchy node
+    pub fn relation(mut self, relation: Relation) -> Self {
+        self.item.relations.push(relation);
+        self
+    }
+
+    /// Attach an image or other file (a URL, or a path relative to the data file)
+    pub fn attachment(mut self, attachment: Attachment) -> Self {
+        self.item.attachments.push(attachment);
+        self
+    }
+
+    /// Set an additional field outside name/path/facets
+    pub fn extra(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.item.extra.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn build(self) -> Item {
+        self.item
+    }
+}
+
+fn non_empty(nodes: Vec<HierarchyNode>) -> Option<Vec<HierarchyNode>> {
+    if nodes.is_empty() {
+        None
+    } else {
+        Some(nodes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::validate_path_exists;
+
+    #[test]
+    fn test_hierarchy_builder_links_genus() {
+        let hierarchy = HierarchyBuilder::new()
+            .root("Beverage")
+            .child("Hot Beverage", "served heated", |c| {
+                c.leaf("Coffee", "brewed from beans")
+            })
+            .leaf("Cold Beverage", "served chilled")
+            .build();
+
+        let children = hierarchy.children.as_ref().unwrap();
+        assert_eq!(children[0].genus, "Beverage");
+        assert!(children[1].children.is_none());
+
+        let coffee = &children[0].children.as_ref().unwrap()[0];
+        assert_eq!(coffee.genus, "Hot Beverage");
+
+        let path: Vec<String> = vec!["Beverage".into(), "Hot Beverage".into(), "Coffee".into()];
+        assert!(validate_path_exists(&path, &hierarchy).is_ok());
+    }
+
+    #[test]
+    fn test_schema_builder() {
+        let schema = TaxonomySchemaBuilder::new("Beverages")
+            .description("Drinks")
+            .hierarchy(HierarchyBuilder::new().root("Beverage").build())
+            .facet("temperature", ["hot", "cold"])
+            .build();
+
+        assert_eq!(schema.title, "Beverages");
+        assert_eq!(schema.classical_hierarchy.root, "Beverage");
+        assert_eq!(
+            schema.faceted_dimensions["temperature"].allowed_values,
+            vec!["hot", "cold"]
+        );
+        assert!(schema.json_schema().is_none());
+    }
+
+    #[test]
+    fn test_item_builder() {
+        let item = ItemBuilder::new("Latte")
+            .path(["Beverage", "Hot Beverage", "Coffee"])
+            .facet("temperature", "hot")
+            .facet_values("flavor", ["vanilla", "caramel"])
+            .extra("price", 4.5)
+            .build();
+
+        assert_eq!(item.classical_path.len(), 3);
+        assert_eq!(item.get_facet_as_string("temperature").unwrap(), "hot");
+        assert_eq!(item.get_facet_as_vec("flavor"), vec!["vanilla", "caramel"]);
+        assert_eq!(item.extra["price"], 4.5);
+    }
+}
@@ -0,0 +1,813 @@
+use crate::models::{ClassicalHierarchy, FacetDefinition, Item, TaxonomyData, TaxonomySchema};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+/// Whether something was added, removed or changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// One difference between two versions of a taxonomy
+/// Displayed like a diff line, e.g. `+ item 'Latte'` or `~ item 'Latte': facet 'flavor' ...`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub kind: ChangeKind,
+    /// What changed, e.g. "item 'Latte'" or "facet 'region' value 'EU'"
+    pub subject: String,
+    /// How a changed subject differs
+    pub detail: Option<String>,
+}
+
+impl Change {
+    fn added(subject: String) -> Self {
+        Change {
+            kind: ChangeKind::Added,
+            subject,
+            detail: None,
+        }
+    }
+
+    fn removed(subject: String) -> Self {
+        Change {
+            kind: ChangeKind::Removed,
+            subject,
+            detail: None,
+        }
+    }
+
+    fn changed(subject: String, detail: String) -> Self {
+        Change {
+            kind: ChangeKind::Changed,
+            subject,
+            detail: Some(detail),
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = match self.kind {
+            ChangeKind::Added => '+',
+            ChangeKind::Removed => '-',
+            ChangeKind::Changed => '~',
+        };
+        write!(f, "{} {}", sign, self.subject)?;
+        if let Some(detail) = &self.detail {
+            write!(f, ": {}", detail)?;
+        }
+        Ok(())
+    }
+}
+
+/// Differences between two lists of items, matched by name
+/// Removed items come first (in old order), then added and changed ones (in new order)
+pub fn diff_items(old: &[Item], new: &[Item]) -> Vec<Change> {
+    let old_by_name: HashMap<&str, &Item> = old.iter().map(|i| (i.name.as_str(), i)).collect();
+    let new_names: BTreeSet<&str> = new.iter().map(|i| i.name.as_str()).collect();
+
+    let mut changes: Vec<Change> = old
+        .iter()
+        .filter(|item| !new_names.contains(item.name.as_str()))
+        .map(|item| Change::removed(format!("item '{}'", item.name)))
+        .collect();
+
+    for item in new {
+        let subject = format!("item '{}'", item.name);
+        let Some(before) = old_by_name.get(item.name.as_str()) else {
+            changes.push(Change::added(subject));
+            continue;
+        };
+        for detail in item_changes(before, item) {
+            changes.push(Change::changed(subject.clone(), detail));
+        }
+    }
+
+    changes
+}
+
+/// One item of a taxonomy comparison, matched by name across the two versions
+#[derive(Debug, Clone)]
+pub struct ItemDiff {
+    pub name: String,
+    /// Added, removed or changed; None when both versions are the same
+    pub kind: Option<ChangeKind>,
+    /// The item in the old version (None when added)
+    pub old: Option<Item>,
+    /// The item in the new version (None when removed)
+    pub new: Option<Item>,
+    /// How a changed item differs, field by field, e.g. `facet 'flavor' "vanilla" → "caramel"`
+    pub details: Vec<String>,
+}
+
+/// Item-by-item comparison of two versions of a taxonomy, with their schema changes
+#[derive(Debug, Clone, Default)]
+pub struct TaxonomyDiff {
+    /// Every item of either version: the old version's in order, then the added ones
+    pub items: Vec<ItemDiff>,
+    pub schema: Vec<Change>,
+}
+
+impl TaxonomyDiff {
+    /// Number of items added, removed or changed (`Some(kind)`), or the same (`None`)
+    pub fn count(&self, kind: Option<ChangeKind>) -> usize {
+        self.items.iter().filter(|item| item.kind == kind).count()
+    }
+
+    /// Whether the two versions have the same items and schema
+    pub fn is_empty(&self) -> bool {
+        self.schema.is_empty() && self.items.iter().all(|item| item.kind.is_none())
+    }
+}
+
+/// Compare two versions of a taxonomy item by item (matched by name) for side-by-side display
+pub fn diff_taxonomies(
+    old_schema: &TaxonomySchema,
+    old: &TaxonomyData,
+    new_schema: &TaxonomySchema,
+    new: &TaxonomyData,
+) -> TaxonomyDiff {
+    let new_by_name: HashMap<&str, &Item> =
+        new.items.iter().map(|i| (i.name.as_str(), i)).collect();
+    let old_names: BTreeSet<&str> = old.items.iter().map(|i| i.name.as_str()).collect();
+
+    let mut items: Vec<ItemDiff> = old
+        .items
+        .iter()
+        .map(|before| {
+            let after = new_by_name.get(before.name.as_str()).copied();
+            let details = after.map_or_else(Vec::new, |after| item_changes(before, after));
+            let kind = match after {
+                None => Some(ChangeKind::Removed),
+                Some(_) if !details.is_empty() => Some(ChangeKind::Changed),
+                Some(_) => None,
+            };
+            ItemDiff {
+                name: before.name.clone(),
+                kind,
+                old: Some(before.clone()),
+                new: after.cloned(),
+                details,
+            }
+        })
+        .collect();
+    items.extend(
+        new.items
+            .iter()
+            .filter(|item| !old_names.contains(item.name.as_str()))
+            .map(|item| ItemDiff {
+                name: item.name.clone(),
+                kind: Some(ChangeKind::Added),
+                old: None,
+                new: Some(item.clone()),
+                details: Vec::new(),
+            }),
+    );
+
+    TaxonomyDiff {
+        items,
+        schema: diff_schemas(old_schema, new_schema),
+    }
+}
+
+/// A part of an item that a three-way merge compares on its own
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MergeField {
+    /// The whole item: kept on one side, deleted on the other
+    Item,
+    Path,
+    Facet(String),
+    Label(String),
+    /// An additional field, or the item's relations (`"relations"`) or attachments
+    /// (`"attachments"`)
+    Field(String),
+}
+
+impl fmt::Display for MergeField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeField::Item => write!(f, "item"),
+            MergeField::Path => write!(f, "path"),
+            MergeField::Facet(name) => write!(f, "facet '{}'", name),
+            MergeField::Label(lang) => write!(f, "label '{}'", lang),
+            MergeField::Field(name) => write!(f, "field '{}'", name),
+        }
+    }
+}
+
+impl MergeField {
+    /// A value of the field as text for display and editing: a path as "A > B", text as
+    /// is, anything else as JSON; "" when the field is unset
+    pub fn value_to_text(&self, value: Option<&Value>) -> String {
+        match (self, value) {
+            (_, None) => String::new(),
+            (MergeField::Path, Some(Value::Array(segments))) => segments
+                .iter()
+                .map(|segment| segment.as_str().unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join(" > "),
+            (_, Some(Value::String(text))) => text.clone(),
+            (_, Some(value)) => value.to_string(),
+        }
+    }
+
+    /// Parse text written like `value_to_text` gives it; "" unsets the field
+    /// Facets and fields take JSON, falling back to plain text
+    pub fn value_from_text(&self, text: &str) -> Result<Option<Value>, String> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        let value = match self {
+            MergeField::Item => serde_json::from_str(text)
+                .map_err(|e| format!("An item must be a JSON object: {}", e))?,
+            MergeField::Path => Value::from(
+                text.split('>')
+                    .map(|segment| segment.trim().to_string())
+                    .collect::<Vec<_>>(),
+            ),
+            MergeField::Label(_) => Value::String(text.to_string()),
+            MergeField::Facet(_) | MergeField::Field(_) => {
+                serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_string()))
+            }
+        };
+        Ok(Some(value))
+    }
+}
+
+/// A field both sides of a merge changed differently since their common version
+/// Values are None where the field (or, for `MergeField::Item`, the item) is missing
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeConflict {
+    /// Name of the item
+    pub item: String,
+    pub field: MergeField,
+    pub base: Option<Value>,
+    pub ours: Option<Value>,
+    pub theirs: Option<Value>,
+}
+
+/// How to settle a merge conflict
+#[derive(Debug, Clone, PartialEq)]
+pub enum Resolution {
+    Ours,
+    Theirs,
+    /// A value of one's own (None unsets the field, or deletes the item)
+    Edited(Option<Value>),
+}
+
+/// Outcome of a three-way merge: the merged data, taking our side of every conflict until
+/// it is resolved otherwise
+#[derive(Debug, Clone)]
+pub struct MergeResult {
+    pub merged: TaxonomyData,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl MergeResult {
+    /// Settle the conflict at `index` in the merged data
+    pub fn resolve(&mut self, index: usize, resolution: Resolution) -> Result<(), String> {
+        let conflict = self.conflicts.get(index).ok_or("No such conflict")?;
+        let value = match resolution {
+            Resolution::Ours => conflict.ours.clone(),
+            Resolution::Theirs => conflict.theirs.clone(),
+            Resolution::Edited(value) => value,
+        };
+        let items = &mut self.merged.items;
+        let position = items.iter().position(|item| item.name == conflict.item);
+
+        if conflict.field == MergeField::Item {
+            let item = value
+                .map(serde_json::from_value::<Item>)
+                .transpose()
+                .map_err(|e| format!("Invalid item '{}': {}", conflict.item, e))?;
+            match (position, item) {
+                (Some(position), Some(item)) => items[position] = item,
+                (Some(position), None) => {
+                    items.remove(position);
+                }
+                (None, Some(item)) => items.push(item),
+                (None, None) => {}
+            }
+            return Ok(());
+        }
+
+        let position = position
+            .ok_or_else(|| format!("Item '{}' is not in the merged result", conflict.item))?;
+        set_field(&mut items[position], &conflict.field, value)
+    }
+}
+
+/// Three-way merge of two versions of a taxonomy's items that both started from `base`
+/// Items are matched by name and merged field by field: a field only one side changed takes
+/// that change; fields both changed differently are conflicts. The merged data keeps our
+/// order (their new items at the end) and our schema reference.
+pub fn merge(base: &TaxonomyData, ours: &TaxonomyData, theirs: &TaxonomyData) -> MergeResult {
+    let by_name = |data: &'_ TaxonomyData| -> HashMap<String, Item> {
+        data.items
+            .iter()
+            .map(|item| (item.name.clone(), item.clone()))
+            .collect()
+    };
+    let (base_items, our_items, their_items) = (by_name(base), by_name(ours), by_name(theirs));
+    let names = ours.items.iter().chain(
+        theirs
+            .items
+            .iter()
+            .filter(|item| !our_items.contains_key(&item.name)),
+    );
+
+    let mut items = Vec::new();
+    let mut conflicts = Vec::new();
+    for name in names.map(|item| &item.name) {
+        let base = base_items.get(name);
+        let unchanged =
+            |item: &Item| base.is_some_and(|base| item_fields(base) == item_fields(item));
+        let conflict = |ours: Option<&Item>, theirs: Option<&Item>| MergeConflict {
+            item: name.clone(),
+            field: MergeField::Item,
+            base: base.and_then(|item| serde_json::to_value(item).ok()),
+            ours: ours.and_then(|item| serde_json::to_value(item).ok()),
+            theirs: theirs.and_then(|item| serde_json::to_value(item).ok()),
+        };
+
+        match (our_items.get(name), their_items.get(name)) {
+            (Some(our), Some(their)) => {
+                let (item, item_conflicts) = merge_item(base, our, their);
+                items.push(item);
+                conflicts.extend(item_conflicts);
+            }
+            // They deleted it: agree unless we changed it
+            (Some(our), None) => {
+                if base.is_none() || !unchanged(our) {
+                    if base.is_some() {
+                        conflicts.push(conflict(Some(our), None));
+                    }
+                    items.push(our.clone());
+                }
+            }
+            // We deleted it: agree unless they changed it
+            (None, Some(their)) => {
+                if base.is_none() {
+                    items.push(their.clone());
+                } else if !unchanged(their) {
+                    conflicts.push(conflict(None, Some(their)));
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    MergeResult {
+        merged: TaxonomyData {
+            schema: ours.schema.clone(),
+            items,
+            extra: ours.extra.clone(),
+        },
+        conflicts,
+    }
+}
+
+/// Merge an item both sides kept, starting from ours and taking the fields only they changed
+fn merge_item(base: Option<&Item>, ours: &Item, theirs: &Item) -> (Item, Vec<MergeConflict>) {
+    let base_fields = base.map(item_fields).unwrap_or_default();
+    let (our_fields, their_fields) = (item_fields(ours), item_fields(theirs));
+    let keys: BTreeSet<&MergeField> = our_fields.keys().chain(their_fields.keys()).collect();
+
+    let mut item = ours.clone();
+    let mut conflicts = Vec::new();
+    for key in keys {
+        let (base, ours, theirs) = (
+            base_fields.get(key),
+            our_fields.get(key),
+            their_fields.get(key),
+        );
+        if ours == theirs || theirs == base {
+            continue;
+        }
+        if ours == base {
+            // Fields come from valid items, so setting them can't fail
+            let _ = set_field(&mut item, key, theirs.cloned());
+            continue;
+        }
+        conflicts.push(MergeConflict {
+            item: item.name.clone(),
+            field: key.clone(),
+            base: base.cloned(),
+            ours: ours.cloned(),
+            theirs: theirs.cloned(),
+        });
+    }
+    (item, conflicts)
+}
+
+/// An item's fields as a three-way merge compares them
+fn item_fields(item: &Item) -> BTreeMap<MergeField, Value> {
+    let mut fields = BTreeMap::new();
+    fields.insert(MergeField::Path, Value::from(item.classical_path.clone()));
+    for (name, value) in &item.facets {
+        fields.insert(MergeField::Facet(name.clone()), value.clone());
+    }
+    for (lang, label) in &item.labels {
+        fields.insert(
+            MergeField::Label(lang.clone()),
+            Value::String(label.clone()),
+        );
+    }
+    if let Ok(relations @ Value::Array(_)) = serde_json::to_value(&item.relations) {
+        if !item.relations.is_empty() {
+            fields.insert(MergeField::Field("relations".to_string()), relations);
+        }
+    }
+    if let Ok(attachments @ Value::Array(_)) = serde_json::to_value(&item.attachments) {
+        if !item.attachments.is_empty() {
+            fields.insert(MergeField::Field("attachments".to_string()), attachments);
+        }
+    }
+    for (name, value) in &item.extra {
+        fields.insert(MergeField::Field(name.clone()), value.clone());
+    }
+    fields
+}
+
+/// Set (or, with None, unset) one field of an item
+fn set_field(item: &mut Item, field: &MergeField, value: Option<Value>) -> Result<(), String> {
+    let invalid = |e: serde_json::Error| format!("Invalid {} of '{}': {}", field, item.name, e);
+    match field {
+        MergeField::Item => return Err("An item is not one of its own fields".to_string()),
+        MergeField::Path => {
+            let path = value.unwrap_or_else(|| Value::Array(Vec::new()));
+            item.classical_path = serde_json::from_value(path).map_err(invalid)?;
+        }
+        MergeField::Facet(name) => match value {
+            Some(value) => {
+                item.facets.insert(name.clone(), value);
+            }
+            None => {
+                item.facets.remove(name);
+            }
+        },
+        MergeField::Label(lang) => match value {
+            Some(Value::String(label)) => {
+                item.labels.insert(lang.clone(), label);
+            }
+            Some(_) => return Err(format!("A label of '{}' must be text", item.name)),
+            None => {
+                item.labels.remove(lang);
+            }
+        },
+        MergeField::Field(name) if name == "relations" => {
+            let relations = value.unwrap_or_else(|| Value::Array(Vec::new()));
+            item.relations = serde_json::from_value(relations).map_err(invalid)?;
+        }
+        MergeField::Field(name) if name == "attachments" => {
+            let attachments = value.unwrap_or_else(|| Value::Array(Vec::new()));
+            item.attachments = serde_json::from_value(attachments).map_err(invalid)?;
+        }
+        MergeField::Field(name) => match value {
+            Some(value) => {
+                item.extra.insert(name.clone(), value);
+            }
+            None => {
+                item.extra.remove(name);
+            }
+        },
+    }
+    Ok(())
+}
+
+/// How one item's versions differ: path, facets, additional fields and labels
+fn item_changes(before: &Item, after: &Item) -> Vec<String> {
+    let mut details = Vec::new();
+    if before.classical_path != after.classical_path {
+        details.push(format!(
+            "path {} → {}",
+            before.classical_path.join(" > "),
+            after.classical_path.join(" > ")
+        ));
+    }
+    details.extend(map_changes("facet", &before.facets, &after.facets));
+    details.extend(map_changes("field", &before.extra, &after.extra));
+    let labels = |item: &Item| -> HashMap<String, Value> {
+        item.labels
+            .iter()
+            .map(|(lang, label)| (lang.clone(), Value::String(label.clone())))
+            .collect()
+    };
+    details.extend(map_changes("label", &labels(before), &labels(after)));
+    details
+}
+
+/// Differences between two schemas: hierarchy nodes, faceted dimensions and their allowed values
+pub fn diff_schemas(old: &TaxonomySchema, new: &TaxonomySchema) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    if old.title != new.title {
+        let detail = format!("'{}' → '{}'", old.title, new.title);
+        changes.push(Change::changed("schema title".to_string(), detail));
+    }
+
+    // Hierarchy nodes, keyed by species
+    let old_hierarchy = &old.classical_hierarchy;
+    let new_hierarchy = &new.classical_hierarchy;
+    if old_hierarchy.root != new_hierarchy.root {
+        let detail = format!("'{}' → '{}'", old_hierarchy.root, new_hierarchy.root);
+        changes.push(Change::changed("hierarchy root".to_string(), detail));
+    }
+    let old_nodes: Vec<&str> = old_hierarchy
+        .iter_preorder()
+        .map(|(_, node)| node.species.as_str())
+        .collect();
+    let new_nodes: Vec<&str> = new_hierarchy
+        .iter_preorder()
+        .map(|(_, node)| node.species.as_str())
+        .collect();
+    for species in old_nodes.iter().filter(|s| !new_nodes.contains(s)) {
+        changes.push(Change::removed(format!("species '{}'", species)));
+    }
+    for species in &new_nodes {
+        let subject = format!("species '{}'", species);
+        if !old_nodes.contains(species) {
+            changes.push(Change::added(subject));
+            continue;
+        }
+        let parent =
+            |hierarchy: &ClassicalHierarchy| hierarchy.ancestors_of(species).into_iter().next();
+        let (before, after) = (parent(old_hierarchy), parent(new_hierarchy));
+        if before != after {
+            let detail = format!(
+                "moved from '{}' to '{}'",
+                before.unwrap_or_default(),
+                after.unwrap_or_default()
+            );
+            changes.push(Change::changed(subject, detail));
+        }
+    }
+
+    // Faceted dimensions, by name
+    let facets: BTreeSet<&String> = old
+        .faceted_dimensions
+        .keys()
+        .chain(new.faceted_dimensions.keys())
+        .collect();
+    for facet in facets {
+        let subject = format!("facet '{}'", facet);
+        match (
+            old.faceted_dimensions.get(facet),
+            new.faceted_dimensions.get(facet),
+        ) {
+            (Some(_), None) => changes.push(Change::removed(subject)),
+            (None, Some(after)) => {
+                changes.push(Change::added(subject));
+                for value in &after.allowed_values {
+                    let subject = format!("facet '{}' value '{}'", facet, value);
+                    changes.push(Change::added(subject));
+                }
+            }
+            (Some(before), Some(after)) => {
+                for detail in definition_changes(before, after) {
+                    changes.push(Change::changed(subject.clone(), detail));
+                }
+                for value in &before.allowed_values {
+                    if !after.allowed_values.contains(value) {
+                        let subject = format!("facet '{}' value '{}'", facet, value);
+                        changes.push(Change::removed(subject));
+                    }
+                }
+                for value in &after.allowed_values {
+                    if !before.allowed_values.contains(value) {
+                        let subject = format!("facet '{}' value '{}'", facet, value);
+                        changes.push(Change::added(subject));
+                    }
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    changes
+}
+
+/// Settings of a faceted dimension that differ (allowed values aside)
+fn definition_changes(before: &FacetDefinition, after: &FacetDefinition) -> Vec<String> {
+    let mut details = Vec::new();
+    if before.kind != after.kind {
+        details.push(format!("kind {} → {}", before.kind, after.kind));
+    }
+    if before.required != after.required {
+        details.push(format!("required {} → {}", before.required, after.required));
+    }
+    if before.multi != after.multi {
+        details.push(format!("multi {} → {}", before.multi, after.multi));
+    }
+    if before.default != after.default {
+        details.push(format!(
+            "default {} → {}",
+            shown(before.default.as_ref()),
+            shown(after.default.as_ref())
+        ));
+    }
+    details
+}
+
+/// Keys of two maps whose values differ, described in key order
+fn map_changes(
+    what: &str,
+    before: &HashMap<String, Value>,
+    after: &HashMap<String, Value>,
+) -> Vec<String> {
+    let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .filter_map(|key| match (before.get(key), after.get(key)) {
+            (Some(old), Some(new)) if old == new => None,
+            (old, new) => Some(format!(
+                "{} '{}' {} → {}",
+                what,
+                key,
+                shown(old),
+                shown(new)
+            )),
+        })
+        .collect()
+}
+
+/// A value as JSON, or "(none)"
+fn shown(value: Option<&Value>) -> String {
+    value.map_or_else(|| "(none)".to_string(), Value::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{HierarchyBuilder, ItemBuilder, TaxonomySchemaBuilder};
+
+    #[test]
+    fn test_diff() {
+        let old_items = vec![
+            ItemBuilder::new("Latte")
+                .path(["Beverage", "Coffee"])
+                .facet("flavor", "vanilla")
+                .build(),
+            ItemBuilder::new("Mocha").path(["Beverage"]).build(),
+        ];
+        let new_items = vec![
+            ItemBuilder::new("Latte")
+                .path(["Beverage", "Coffee"])
+                .facet("flavor", "caramel")
+                .facet("temperature", "hot")
+                .build(),
+            ItemBuilder::new("Cortado").path(["Beverage"]).build(),
+        ];
+        let lines: Vec<String> = diff_items(&old_items, &new_items)
+            .iter()
+            .map(Change::to_string)
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "- item 'Mocha'",
+                "~ item 'Latte': facet 'flavor' \"vanilla\" → \"caramel\"",
+                "~ item 'Latte': facet 'temperature' (none) → \"hot\"",
+                "+ item 'Cortado'",
+            ]
+        );
+
+        let old_schema = TaxonomySchemaBuilder::new("Beverages")
+            .hierarchy(
+                HierarchyBuilder::new()
+                    .root("Beverage")
+                    .leaf("Coffee", "brewed from beans")
+                    .build(),
+            )
+            .facet("flavor", ["vanilla", "caramel"])
+            .build();
+        let mut new_schema = old_schema.clone();
+        new_schema.classical_hierarchy = HierarchyBuilder::new()
+            .root("Beverage")
+            .child("Hot Beverage", "served heated", |c| {
+                c.leaf("Coffee", "brewed from beans")
+            })
+            .build();
+        let flavor = new_schema.faceted_dimensions.get_mut("flavor").unwrap();
+        flavor.allowed_values = vec!["vanilla".to_string(), "hazelnut".to_string()];
+        flavor.required = true;
+
+        let lines: Vec<String> = diff_schemas(&old_schema, &new_schema)
+            .iter()
+            .map(Change::to_string)
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "+ species 'Hot Beverage'",
+                "~ species 'Coffee': moved from 'Beverage' to 'Hot Beverage'",
+                "~ facet 'flavor': required false → true",
+                "- facet 'flavor' value 'caramel'",
+                "+ facet 'flavor' value 'hazelnut'",
+            ]
+        );
+        assert!(diff_schemas(&old_schema, &old_schema).is_empty());
+
+        let data = |items: &[Item]| TaxonomyData {
+            schema: "schema.json".to_string(),
+            items: items.to_vec(),
+            extra: HashMap::new(),
+        };
+        let (old, new) = (data(&old_items), data(&new_items));
+        let diff = diff_taxonomies(&old_schema, &old, &new_schema, &new);
+        let rows: Vec<(&str, Option<ChangeKind>, usize)> = diff
+            .items
+            .iter()
+            .map(|item| (item.name.as_str(), item.kind, item.details.len()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("Latte", Some(ChangeKind::Changed), 2),
+                ("Mocha", Some(ChangeKind::Removed), 0),
+                ("Cortado", Some(ChangeKind::Added), 0),
+            ]
+        );
+        assert_eq!(diff.schema.len(), 5);
+        assert_eq!(diff.count(Some(ChangeKind::Added)), 1);
+
+        let same = diff_taxonomies(&old_schema, &old, &old_schema, &old);
+        assert!(same.is_empty());
+        assert_eq!(same.count(None), 2);
+    }
+
+    #[test]
+    fn test_merge() {
+        let data = |items: Vec<Item>| TaxonomyData {
+            schema: "schema.json".to_string(),
+            items,
+            extra: HashMap::new(),
+        };
+        let latte = |flavor: &str, size: &str| {
+            ItemBuilder::new("Latte")
+                .path(["Beverage", "Coffee"])
+                .facet("flavor", flavor)
+                .facet("size", size)
+                .build()
+        };
+        let mocha = ItemBuilder::new("Mocha").path(["Beverage"]).build();
+        let tea = ItemBuilder::new("Tea").path(["Beverage"]).build();
+
+        let base = data(vec![latte("vanilla", "small"), mocha.clone(), tea.clone()]);
+        // We change the flavor and edit Mocha; they change flavor and size, delete Mocha
+        // and Tea and add Cortado
+        let mut our_mocha = mocha.clone();
+        our_mocha
+            .labels
+            .insert("de".to_string(), "Mokka".to_string());
+        let ours = data(vec![latte("caramel", "small"), our_mocha, tea]);
+        let theirs = data(vec![
+            latte("hazelnut", "large"),
+            ItemBuilder::new("Cortado").path(["Beverage"]).build(),
+        ]);
+
+        let mut result = merge(&base, &ours, &theirs);
+        let conflicts: Vec<String> = result
+            .conflicts
+            .iter()
+            .map(|c| format!("{} {}", c.item, c.field))
+            .collect();
+        assert_eq!(conflicts, vec!["Latte facet 'flavor'", "Mocha item"]);
+        let names: Vec<&str> = result
+            .merged
+            .items
+            .iter()
+            .map(|i| i.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Latte", "Mocha", "Cortado"]);
+        let merged_latte = &result.merged.items[0];
+        assert_eq!(merged_latte.facets["size"], "large");
+        assert_eq!(merged_latte.facets["flavor"], "caramel");
+
+        result.resolve(0, Resolution::Theirs).unwrap();
+        assert_eq!(result.merged.items[0].facets["flavor"], "hazelnut");
+        result.resolve(1, Resolution::Theirs).unwrap();
+        assert_eq!(result.merged.items.len(), 2);
+        result.resolve(1, Resolution::Ours).unwrap();
+        assert_eq!(result.merged.items[2].name, "Mocha");
+
+        let flavor = &result.conflicts[0].field;
+        let value = flavor.value_from_text("mocha").unwrap();
+        assert_eq!(value, Some(Value::from("mocha")));
+        result.resolve(0, Resolution::Edited(value)).unwrap();
+        assert_eq!(result.merged.items[0].facets["flavor"], "mocha");
+        result.resolve(0, Resolution::Edited(None)).unwrap();
+        assert!(!result.merged.items[0].facets.contains_key("flavor"));
+
+        let path = MergeField::Path.value_from_text("Beverage > Tea").unwrap();
+        assert_eq!(
+            MergeField::Path.value_to_text(path.as_ref()),
+            "Beverage > Tea"
+        );
+        assert!(MergeField::Item.value_from_text("not json").is_err());
+        assert!(merge(&base, &base, &base).conflicts.is_empty());
+    }
+}
@@ -0,0 +1,688 @@
+use crate::models::{
+    ClassicalHierarchy, FacetCondition, FacetConstraint, FacetPredicate, FacetValueNode, Filters,
+    Item, TaxonomySchema,
+};
+use crate::schema::{rename_facet_in_json, rename_facet_value_in_json};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// How `batch_rename` derives an item's new name
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenamePattern {
+    /// Replace every occurrence of `find` in the name (case-sensitive); an empty `find`
+    /// leaves names as they are
+    Replace { find: String, replace: String },
+    /// Build the name from text and placeholders: `{name}`, `{path}` (the classical path
+    /// joined with " → "), `{path[i]}` (a path segment; negative counts from the end) and
+    /// `{<facet>}` (a facet's or additional field's values, joined with ", ")
+    /// `{{` and `}}` stand for literal braces; an empty template leaves names as they are
+    Template(String),
+}
+
+/// One item's name before and after a batch rename
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rename {
+    pub old: String,
+    pub new: String,
+    /// Why the new name can't be used (empty, or another item's); such renames are skipped
+    pub issue: Option<String>,
+}
+
+impl Rename {
+    /// Whether applying the rename changes anything
+    pub fn changes(&self) -> bool {
+        self.issue.is_none() && self.old != self.new
+    }
+}
+
+/// Work out the new names of `items` under `pattern`, without renaming anything
+/// Renames are flagged when the new name is empty or two items would share it; use
+/// `flag_taken_names` to check against the items that aren't renamed
+/// Fails on a malformed template
+pub fn batch_rename<'a>(
+    items: impl IntoIterator<Item = &'a Item>,
+    pattern: &RenamePattern,
+) -> Result<Vec<Rename>, String> {
+    let parts = match pattern {
+        RenamePattern::Replace { .. } => None,
+        RenamePattern::Template(template) => Some(parse_template(template)?),
+    };
+
+    let mut renames: Vec<Rename> = items
+        .into_iter()
+        .map(|item| {
+            let new = match pattern {
+                RenamePattern::Replace { find, .. } if find.is_empty() => item.name.clone(),
+                RenamePattern::Replace { find, replace } => item.name.replace(find, replace),
+                RenamePattern::Template(template) if template.trim().is_empty() => {
+                    item.name.clone()
+                }
+                RenamePattern::Template(_) => render(parts.as_deref().unwrap_or_default(), item),
+            };
+            let new = new.trim().to_string();
+            let issue = new.is_empty().then(|| "The new name is empty".to_string());
+            Rename {
+                old: item.name.clone(),
+                new,
+                issue,
+            }
+        })
+        .collect();
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for rename in &renames {
+        *counts.entry(rename.new.clone()).or_default() += 1;
+    }
+    for rename in renames.iter_mut().filter(|rename| rename.issue.is_none()) {
+        if counts[&rename.new] > 1 {
+            rename.issue = Some(format!("Several items would be named '{}'", rename.new));
+        }
+    }
+    Ok(renames)
+}
+
+/// Flag renames whose new name belongs to an item of `items` that keeps its name
+pub fn flag_taken_names(renames: &mut [Rename], items: &[Item]) {
+    let renamed: HashSet<&str> = renames.iter().map(|rename| rename.old.as_str()).collect();
+    let kept: HashSet<&str> = items
+        .iter()
+        .map(|item| item.name.as_str())
+        .filter(|name| !renamed.contains(name))
+        .collect();
+
+    for rename in renames.iter_mut().filter(|rename| rename.issue.is_none()) {
+        if kept.contains(rename.new.as_str()) {
+            rename.issue = Some(format!("An item named '{}' already exists", rename.new));
+        }
+    }
+}
+
+/// `name`, or when an item of `items` already has it, the first free "name (2)", "name (3)"...
+pub fn unique_item_name(name: &str, items: &[Item]) -> String {
+    let taken: HashSet<&str> = items.iter().map(|item| item.name.as_str()).collect();
+    if !taken.contains(name) {
+        return name.to_string();
+    }
+    (2..)
+        .map(|n| format!("{} ({})", name, n))
+        .find(|candidate| !taken.contains(candidate.as_str()))
+        .unwrap_or_default()
+}
+
+/// Rename the items of `items` as planned by `renames`, skipping flagged ones
+/// Returns how many items were renamed
+pub fn apply_renames(items: &mut [Item], renames: &[Rename]) -> usize {
+    let new_names: HashMap<&str, &str> = renames
+        .iter()
+        .filter(|rename| rename.changes())
+        .map(|rename| (rename.old.as_str(), rename.new.as_str()))
+        .collect();
+
+    let mut renamed = 0;
+    for item in items.iter_mut() {
+        if let Some(new) = new_names.get(item.name.as_str()) {
+            item.name = new.to_string();
+            renamed += 1;
+        }
+    }
+    renamed
+}
+
+/// Move `items` to the hierarchy node known as `node` (the root, or a species by name,
+/// synonym or code), rewriting their classical paths to the path of that node
+/// Returns how many items moved; fails when the hierarchy has no such node
+pub fn reclassify<'a>(
+    items: impl IntoIterator<Item = &'a mut Item>,
+    node: &str,
+    hierarchy: &ClassicalHierarchy,
+) -> Result<usize, String> {
+    let path = hierarchy
+        .resolve_species(node)
+        .and_then(|species| hierarchy.path_to(species))
+        .ok_or_else(|| format!("'{}' is not in the hierarchy", node))?;
+
+    let mut moved = 0;
+    for item in items {
+        if item.classical_path != path {
+            item.classical_path = path.clone();
+            moved += 1;
+        }
+    }
+    Ok(moved)
+}
+
+/// Rename a faceted dimension, cascading into its value tree, the schema's constraints,
+/// the raw JSON Schema (ready for `save_schema`), the items' facets and `filters`
+/// Returns how many items were touched; fails when the schema has no `old` dimension or
+/// already has a `new` one
+pub fn rename_facet(
+    schema: &mut TaxonomySchema,
+    items: &mut [Item],
+    filters: &mut Filters,
+    old: &str,
+    new: &str,
+) -> Result<usize, String> {
+    if !schema.faceted_dimensions.contains_key(old) {
+        return Err(format!("Facet '{}' is not in the schema", old));
+    }
+    if new.trim().is_empty() {
+        return Err("The new name is empty".to_string());
+    }
+    if old == new {
+        return Ok(0);
+    }
+    if schema.faceted_dimensions.contains_key(new) {
+        return Err(format!("Facet '{}' is already in the schema", new));
+    }
+
+    rename_key(&mut schema.faceted_dimensions, old, new);
+    rename_key(&mut schema.facet_value_trees, old, new);
+    for constraint in &mut schema.constraints {
+        for conditions in [
+            &mut constraint.when,
+            &mut constraint.then,
+            &mut constraint.forbid,
+        ] {
+            if let Some(condition) = conditions.remove(old) {
+                conditions.insert(new.to_string(), condition);
+            }
+        }
+    }
+    let constraints = schema.constraints.clone();
+    if let Some(json_schema) = schema.json_schema_mut() {
+        rename_facet_in_json(json_schema, old, new);
+        sync_constraints(json_schema, &constraints)?;
+    }
+
+    rename_key(&mut filters.facets, old, new);
+    rename_key(&mut filters.excluded_facets, old, new);
+
+    let mut touched = 0;
+    for item in items {
+        touched += usize::from(rename_key(&mut item.facets, old, new));
+    }
+    Ok(touched)
+}
+
+/// Rename an allowed value of a faceted dimension, cascading into its value info, default
+/// and value tree, the schema's constraints, the raw JSON Schema (ready for
+/// `save_schema`), the items using it and the `filters` on it
+/// Returns how many items were touched; fails when `old` isn't an allowed value of the
+/// dimension or `new` already is
+pub fn rename_facet_value(
+    schema: &mut TaxonomySchema,
+    items: &mut [Item],
+    filters: &mut Filters,
+    facet: &str,
+    old: &str,
+    new: &str,
+) -> Result<usize, String> {
+    let definition = schema
+        .faceted_dimensions
+        .get_mut(facet)
+        .ok_or_else(|| format!("Facet '{}' is not in the schema", facet))?;
+    if !definition.allowed_values.iter().any(|value| value == old) {
+        return Err(format!("'{}' is not a value of facet '{}'", old, facet));
+    }
+    if new.trim().is_empty() {
+        return Err("The new value is empty".to_string());
+    }
+    if old == new {
+        return Ok(0);
+    }
+    if definition.allowed_values.iter().any(|value| value == new) {
+        return Err(format!("'{}' is already a value of facet '{}'", new, facet));
+    }
+
+    for value in definition.allowed_values.iter_mut().filter(|v| *v == old) {
+        *value = new.to_string();
+    }
+    rename_key(&mut definition.value_info, old, new);
+    if definition.default.as_ref().and_then(Value::as_str) == Some(old) {
+        definition.default = Some(Value::from(new));
+    }
+    if let Some(tree) = schema.facet_value_trees.get_mut(facet) {
+        rename_tree_value(tree, old, new);
+    }
+    for constraint in &mut schema.constraints {
+        for conditions in [
+            &mut constraint.when,
+            &mut constraint.then,
+            &mut constraint.forbid,
+        ] {
+            match conditions.get_mut(facet) {
+                Some(FacetCondition::Value(value)) if value == old => *value = new.to_string(),
+                Some(FacetCondition::AnyOf(values)) => {
+                    for value in values.iter_mut().filter(|v| *v == old) {
+                        *value = new.to_string();
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    let constraints = schema.constraints.clone();
+    if let Some(json_schema) = schema.json_schema_mut() {
+        rename_facet_value_in_json(json_schema, facet, old, new);
+        sync_constraints(json_schema, &constraints)?;
+    }
+
+    for predicates in [
+        filters.facets.get_mut(facet),
+        filters.excluded_facets.get_mut(facet),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        for predicate in predicates.iter_mut() {
+            if *predicate == FacetPredicate::Equals(old.to_string()) {
+                *predicate = FacetPredicate::Equals(new.to_string());
+            }
+        }
+    }
+
+    let mut touched = 0;
+    for item in items {
+        let renamed = match item.facets.get_mut(facet) {
+            Some(Value::String(value)) if value == old => {
+                *value = new.to_string();
+                true
+            }
+            Some(Value::Array(values)) => {
+                let mut renamed = false;
+                for value in values.iter_mut().filter(|v| v.as_str() == Some(old)) {
+                    *value = Value::from(new);
+                    renamed = true;
+                }
+                renamed
+            }
+            _ => false,
+        };
+        touched += usize::from(renamed);
+    }
+    Ok(touched)
+}
+
+/// Move a map entry to a new key; returns whether there was one
+fn rename_key<V>(map: &mut HashMap<String, V>, old: &str, new: &str) -> bool {
+    match map.remove(old) {
+        Some(value) => {
+            map.insert(new.to_string(), value);
+            true
+        }
+        None => false,
+    }
+}
+
+fn rename_tree_value(nodes: &mut [FacetValueNode], old: &str, new: &str) {
+    for node in nodes {
+        if node.value == old {
+            node.value = new.to_string();
+        }
+        rename_tree_value(&mut node.children, old, new);
+    }
+}
+
+/// Write the schema's constraints back into its JSON Schema document, if it has any there
+fn sync_constraints(
+    json_schema: &mut Value,
+    constraints: &[FacetConstraint],
+) -> Result<(), String> {
+    if let Some(json_constraints) = json_schema.get_mut("constraints") {
+        *json_constraints = serde_json::to_value(constraints).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// A piece of a parsed rename template
+#[derive(Debug, Clone, PartialEq)]
+enum TemplatePart {
+    Text(String),
+    Name,
+    Path,
+    PathSegment(isize),
+    Field(String),
+}
+
+fn parse_template(template: &str) -> Result<Vec<TemplatePart>, String> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => return Err(format!("Unclosed placeholder '{{{}'", placeholder)),
+                    }
+                }
+                if !text.is_empty() {
+                    parts.push(TemplatePart::Text(std::mem::take(&mut text)));
+                }
+                parts.push(parse_placeholder(placeholder.trim())?);
+            }
+            '}' => return Err("Unmatched '}' (write '}}' for a literal brace)".to_string()),
+            c => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        parts.push(TemplatePart::Text(text));
+    }
+    Ok(parts)
+}
+
+fn parse_placeholder(placeholder: &str) -> Result<TemplatePart, String> {
+    match placeholder {
+        "" => Err("Empty placeholder '{}'".to_string()),
+        "name" => Ok(TemplatePart::Name),
+        "path" => Ok(TemplatePart::Path),
+        _ => match placeholder
+            .strip_prefix("path[")
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            Some(index) => index
+                .trim()
+                .parse()
+                .map(TemplatePart::PathSegment)
+                .map_err(|_| format!("Invalid path index in '{{{}}}'", placeholder)),
+            None => Ok(TemplatePart::Field(placeholder.to_string())),
+        },
+    }
+}
+
+fn render(parts: &[TemplatePart], item: &Item) -> String {
+    let path = &item.classical_path;
+    parts
+        .iter()
+        .map(|part| match part {
+            TemplatePart::Text(text) => text.clone(),
+            TemplatePart::Name => item.name.clone(),
+            TemplatePart::Path => path.join(" → "),
+            TemplatePart::PathSegment(index) => {
+                let index = if *index < 0 {
+                    path.len().checked_sub(index.unsigned_abs())
+                } else {
+                    Some(*index as usize)
+                };
+                index
+                    .and_then(|index| path.get(index))
+                    .cloned()
+                    .unwrap_or_default()
+            }
+            TemplatePart::Field(name) => {
+                let mut values = item.get_facet_as_vec(name);
+                if values.is_empty() {
+                    values = item.get_extra_as_vec(name);
+                }
+                values.join(", ")
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::HierarchyBuilder;
+    use crate::filtering::parse_facet_filters;
+    use crate::schema::build_schema_from_json;
+    use serde_json::json;
+
+    fn item(name: &str, path: &[&str]) -> Item {
+        Item {
+            name: name.to_string(),
+            classical_path: path.iter().map(|s| s.to_string()).collect(),
+            facets: HashMap::from([("temperature".to_string(), json!("hot"))]),
+            labels: HashMap::new(),
+            relations: Vec::new(),
+            attachments: Vec::new(),
+            extra: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_unique_item_name() {
+        let items = vec![item("Tea", &[]), item("Tea (2)", &[])];
+        assert_eq!(unique_item_name("Coffee", &items), "Coffee");
+        assert_eq!(unique_item_name("Tea", &items), "Tea (3)");
+    }
+
+    #[test]
+    fn test_batch_rename_replace() {
+        let items = vec![item("Espresso Shot", &[]), item("Tea", &[])];
+        let pattern = RenamePattern::Replace {
+            find: "Shot".to_string(),
+            replace: "Double".to_string(),
+        };
+
+        let renames = batch_rename(&items, &pattern).unwrap();
+        assert_eq!(renames[0].new, "Espresso Double");
+        assert!(renames[0].changes());
+        assert!(!renames[1].changes());
+
+        let pattern = RenamePattern::Replace {
+            find: String::new(),
+            replace: "x".to_string(),
+        };
+        let renames = batch_rename(&items, &pattern).unwrap();
+        assert!(renames.iter().all(|rename| !rename.changes()));
+    }
+
+    #[test]
+    fn test_batch_rename_template() {
+        let items = vec![item("Latte", &["Beverage", "Coffee"])];
+        let template =
+            |t: &str| batch_rename(&items, &RenamePattern::Template(t.to_string())).unwrap();
+
+        assert_eq!(template("{path[-1]} – {name}")[0].new, "Coffee – Latte");
+        assert_eq!(
+            template("{path[0]}: {name} ({temperature})")[0].new,
+            "Beverage: Latte (hot)"
+        );
+        assert_eq!(template("{{{name}}}")[0].new, "{Latte}");
+        assert_eq!(template("{name}{path[5]}")[0].new, "Latte");
+
+        let pattern = RenamePattern::Template("{name".to_string());
+        assert!(batch_rename(&items, &pattern).is_err());
+        let pattern = RenamePattern::Template("{path[x]}".to_string());
+        assert!(batch_rename(&items, &pattern).is_err());
+    }
+
+    #[test]
+    fn test_batch_rename_flags_clashes() {
+        let items = vec![item("Latte", &[]), item("Mocha", &[]), item("Tea", &[])];
+        let pattern = RenamePattern::Template("Coffee".to_string());
+
+        let mut renames = batch_rename(&items[..2], &pattern).unwrap();
+        assert!(renames.iter().all(|rename| rename.issue.is_some()));
+
+        let pattern = RenamePattern::Replace {
+            find: "Latte".to_string(),
+            replace: "Tea".to_string(),
+        };
+        renames = batch_rename(&items[..1], &pattern).unwrap();
+        flag_taken_names(&mut renames, &items);
+        assert!(renames[0].issue.is_some());
+
+        let mut items = items;
+        assert_eq!(apply_renames(&mut items, &renames), 0);
+        assert_eq!(items[0].name, "Latte");
+
+        let pattern = RenamePattern::Template("{name} Special".to_string());
+        let renames = batch_rename(&items, &pattern).unwrap();
+        assert_eq!(apply_renames(&mut items, &renames), 3);
+        assert_eq!(items[2].name, "Tea Special");
+    }
+
+    #[test]
+    fn test_reclassify() {
+        let hierarchy = HierarchyBuilder::new()
+            .root("Beverage")
+            .child("Hot Beverage", "served heated", |c| {
+                c.leaf("Coffee", "brewed from beans")
+            })
+            .leaf("Soda", "carbonated")
+            .build();
+        let mut items = vec![
+            item("Latte", &["Beverage", "Hot Beverage"]),
+            item("Mocha", &["Beverage", "Hot Beverage", "Coffee"]),
+            item("Cola", &["Beverage", "Soda"]),
+        ];
+
+        let moved = reclassify(items.iter_mut().take(2), "Coffee", &hierarchy).unwrap();
+        assert_eq!(moved, 1);
+        assert_eq!(
+            items[0].classical_path,
+            ["Beverage", "Hot Beverage", "Coffee"]
+        );
+        assert_eq!(items[2].classical_path, ["Beverage", "Soda"]);
+
+        assert_eq!(reclassify(&mut items, "Beverage", &hierarchy), Ok(3));
+        assert!(reclassify(&mut items, "Juice", &hierarchy).is_err());
+    }
+
+    fn facet_schema() -> TaxonomySchema {
+        build_schema_from_json(json!({
+            "classical_hierarchy": { "root": "Beverage" },
+            "faceted_dimensions": {
+                "temperature": ["hot", "iced"],
+                "origin": {
+                    "allowed_values": [{ "value": "Americas", "children": ["Brazil"] }],
+                    "default": "Brazil"
+                }
+            },
+            "constraints": [{ "if": { "temperature": "iced" }, "then": { "origin": "Brazil" } }],
+            "properties": {
+                "items": {
+                    "items": {
+                        "properties": {
+                            "facets": {
+                                "properties": {
+                                    "temperature": { "enum": ["hot", "iced"] },
+                                    "origin": { "enum": ["Americas", "Brazil"] }
+                                },
+                                "required": ["temperature"]
+                            }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_rename_facet() {
+        let mut schema = facet_schema();
+        let mut items = vec![item("Latte", &[]), item("Tea", &[])];
+        items[1].facets.clear();
+        let mut filters = Filters {
+            facets: parse_facet_filters(&["temperature=hot".to_string()]),
+            ..Filters::default()
+        };
+
+        let touched = rename_facet(
+            &mut schema,
+            &mut items,
+            &mut filters,
+            "temperature",
+            "serving",
+        )
+        .unwrap();
+        assert_eq!(touched, 1);
+        assert!(schema.faceted_dimensions.contains_key("serving"));
+        assert!(schema.constraints[0].when.contains_key("serving"));
+        assert_eq!(
+            items[0].get_facet_as_string("serving").as_deref(),
+            Some("hot")
+        );
+        assert!(filters.facets.contains_key("serving"));
+
+        let json = schema.json_schema().unwrap();
+        assert!(json["faceted_dimensions"].get("serving").is_some());
+        assert_eq!(json["constraints"][0]["if"]["serving"], "iced");
+        let facets = &json["properties"]["items"]["items"]["properties"]["facets"];
+        assert!(facets["properties"].get("serving").is_some());
+        assert_eq!(facets["required"], json!(["serving"]));
+
+        let rename = |schema: &mut TaxonomySchema, old, new| {
+            rename_facet(schema, &mut [], &mut Filters::default(), old, new)
+        };
+        assert!(rename(&mut schema, "temperature", "heat").is_err());
+        assert!(rename(&mut schema, "serving", "origin").is_err());
+    }
+
+    #[test]
+    fn test_rename_facet_value() {
+        let mut schema = facet_schema();
+        let mut items = vec![item("Latte", &[]), item("Mocha", &[])];
+        items[0]
+            .facets
+            .insert("origin".to_string(), json!(["Brazil", "Peru"]));
+        let mut filters = Filters {
+            excluded_facets: parse_facet_filters(&["origin=Brazil".to_string()]),
+            ..Filters::default()
+        };
+
+        let touched = rename_facet_value(
+            &mut schema,
+            &mut items,
+            &mut filters,
+            "origin",
+            "Brazil",
+            "Brasil",
+        )
+        .unwrap();
+        assert_eq!(touched, 1);
+        assert_eq!(items[0].get_facet_as_vec("origin"), ["Brasil", "Peru"]);
+        assert_eq!(
+            filters.excluded_facets["origin"],
+            [FacetPredicate::Equals("Brasil".into())]
+        );
+
+        let origin = &schema.faceted_dimensions["origin"];
+        assert_eq!(origin.allowed_values, ["Americas", "Brasil"]);
+        assert_eq!(origin.default, Some(json!("Brasil")));
+        assert_eq!(
+            schema.facet_value_trees["origin"][0].children[0].value,
+            "Brasil"
+        );
+        assert_eq!(
+            schema.constraints[0].then["origin"],
+            FacetCondition::Value("Brasil".into())
+        );
+
+        let json = schema.json_schema().unwrap();
+        let dimension = &json["faceted_dimensions"]["origin"];
+        assert_eq!(
+            dimension["allowed_values"][0]["children"],
+            json!(["Brasil"])
+        );
+        assert_eq!(dimension["default"], "Brasil");
+        let facets = &json["properties"]["items"]["items"]["properties"]["facets"];
+        assert_eq!(
+            facets["properties"]["origin"]["enum"],
+            json!(["Americas", "Brasil"])
+        );
+
+        let mut filters = Filters::default();
+        let mut rename = |old, new| {
+            rename_facet_value(&mut schema, &mut [], &mut filters, "temperature", old, new)
+        };
+        assert!(rename("warm", "tepid").is_err());
+        assert!(rename("hot", "iced").is_err());
+        assert_eq!(rename("hot", "steaming"), Ok(0));
+    }
+}
@@ -0,0 +1,617 @@
+use crate::html::write_html;
+use crate::io::export_sql;
+use crate::models::{TaxonomyData, TaxonomySchema};
+use crate::search_index::{search_index_mapping, write_bulk_ndjson};
+use crate::viz::{
+    render_hierarchy_svg, write_sunburst_svg, write_treemap_svg, HierarchyStyle, SUNBURST_SIZE,
+    TREEMAP_HEIGHT, TREEMAP_WIDTH,
+};
+use crate::vocabulary::write_skos;
+use std::fmt;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+/// Options shared by all exporters; each uses the ones that apply to its format
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExportOptions {
+    /// Language of names, where translated; `None` for the names as written
+    pub lang: Option<String>,
+    /// Directory of the data file, which local attachment paths are relative to
+    pub data_dir: Option<PathBuf>,
+    /// Size of drawings; `None` for each format's default
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+}
+
+/// A format the taxonomy can be exported to
+pub trait Exporter {
+    /// Name the format is chosen by, e.g. "skos"
+    fn id(&self) -> &'static str;
+
+    /// Name of the format in menus, e.g. "SKOS (RDF/XML)"
+    fn name(&self) -> &'static str;
+
+    /// One line about what is exported, for help texts
+    fn description(&self) -> &'static str;
+
+    /// Extension of the files written, without the dot
+    fn extension(&self) -> &'static str;
+
+    fn export(
+        &self,
+        schema: &TaxonomySchema,
+        data: &TaxonomyData,
+        out: &mut dyn Write,
+        options: &ExportOptions,
+    ) -> io::Result<()>;
+}
+
+/// The exporters available, in the order they were registered
+/// `ExporterRegistry::default()` has the built-in formats; `register` adds more
+pub struct ExporterRegistry {
+    exporters: Vec<Box<dyn Exporter>>,
+}
+
+impl ExporterRegistry {
+    /// A registry with no exporters
+    pub fn new() -> Self {
+        Self {
+            exporters: Vec::new(),
+        }
+    }
+
+    /// Add an exporter, replacing any with the same id
+    pub fn register(&mut self, exporter: Box<dyn Exporter>) {
+        match self.exporters.iter_mut().find(|e| e.id() == exporter.id()) {
+            Some(existing) => *existing = exporter,
+            None => self.exporters.push(exporter),
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&dyn Exporter> {
+        self.iter().find(|exporter| exporter.id() == id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Exporter> {
+        self.exporters.iter().map(|exporter| exporter.as_ref())
+    }
+
+    /// Export with the exporter registered as `id`
+    pub fn export(
+        &self,
+        id: &str,
+        schema: &TaxonomySchema,
+        data: &TaxonomyData,
+        out: &mut dyn Write,
+        options: &ExportOptions,
+    ) -> io::Result<()> {
+        let exporter = self.get(id).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Unknown export format '{}'", id),
+            )
+        })?;
+        exporter.export(schema, data, out, options)
+    }
+}
+
+impl fmt::Debug for ExporterRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.iter().map(|exporter| exporter.id()))
+            .finish()
+    }
+}
+
+impl Default for ExporterRegistry {
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry.register(Box::new(CsvExporter));
+        registry.register(Box::new(DotExporter));
+        registry.register(Box::new(ElasticsearchExporter));
+        registry.register(Box::new(ElasticsearchMappingExporter));
+        registry.register(Box::new(HtmlExporter));
+        registry.register(Box::new(SkosExporter));
+        registry.register(Box::new(SqlExporter));
+        registry.register(Box::new(SunburstExporter));
+        registry.register(Box::new(TreeExporter));
+        registry.register(Box::new(TreemapExporter));
+        registry
+    }
+}
+
+/// The items as CSV: name, path and one column per facet, in the layout item import reads
+pub struct CsvExporter;
+
+impl Exporter for CsvExporter {
+    fn id(&self) -> &'static str {
+        "csv"
+    }
+
+    fn name(&self) -> &'static str {
+        "CSV"
+    }
+
+    fn description(&self) -> &'static str {
+        "The items as CSV, one column per facet (multiple values comma-separated)"
+    }
+
+    fn extension(&self) -> &'static str {
+        "csv"
+    }
+
+    fn export(
+        &self,
+        schema: &TaxonomySchema,
+        data: &TaxonomyData,
+        out: &mut dyn Write,
+        _options: &ExportOptions,
+    ) -> io::Result<()> {
+        let mut facets: Vec<&String> = schema.faceted_dimensions.keys().collect();
+        facets.sort();
+
+        let mut writer = csv::Writer::from_writer(out);
+        let mut header = vec!["name", "path"];
+        header.extend(facets.iter().map(|facet| facet.as_str()));
+        writer.write_record(&header)?;
+        for item in &data.items {
+            let mut record = vec![item.name.clone(), item.classical_path.join(" > ")];
+            record.extend(
+                facets
+                    .iter()
+                    .map(|facet| item.get_facet_as_vec(facet).join(", ")),
+            );
+            writer.write_record(&record)?;
+        }
+        writer.flush()
+    }
+}
+
+/// The classical hierarchy as a Graphviz DOT digraph
+pub struct DotExporter;
+
+impl Exporter for DotExporter {
+    fn id(&self) -> &'static str {
+        "dot"
+    }
+
+    fn name(&self) -> &'static str {
+        "Graphviz DOT"
+    }
+
+    fn description(&self) -> &'static str {
+        "The classical hierarchy as a Graphviz digraph (DOT)"
+    }
+
+    fn extension(&self) -> &'static str {
+        "dot"
+    }
+
+    fn export(
+        &self,
+        schema: &TaxonomySchema,
+        _data: &TaxonomyData,
+        out: &mut dyn Write,
+        options: &ExportOptions,
+    ) -> io::Result<()> {
+        let hierarchy = &schema.classical_hierarchy;
+        let lang = options.lang.as_deref();
+
+        writeln!(out, "digraph taxonomy {{")?;
+        writeln!(out, "  node [shape=box];")?;
+        writeln!(
+            out,
+            "  \"{}\" [label=\"{}\"];",
+            escape_dot(&hierarchy.root),
+            escape_dot(hierarchy.display_name(&hierarchy.root, lang))
+        )?;
+        for (_, node) in hierarchy.iter_preorder() {
+            writeln!(
+                out,
+                "  \"{}\" [label=\"{}\", tooltip=\"{}\"];",
+                escape_dot(&node.species),
+                escape_dot(node.display_name(lang)),
+                escape_dot(&node.differentia)
+            )?;
+            writeln!(
+                out,
+                "  \"{}\" -> \"{}\";",
+                escape_dot(&node.genus),
+                escape_dot(&node.species)
+            )?;
+        }
+        writeln!(out, "}}")
+    }
+}
+
+/// Text in a double-quoted DOT string
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// The items as an Elasticsearch/OpenSearch bulk-index request body (`write_bulk_ndjson`)
+pub struct ElasticsearchExporter;
+
+impl Exporter for ElasticsearchExporter {
+    fn id(&self) -> &'static str {
+        "elasticsearch"
+    }
+
+    fn name(&self) -> &'static str {
+        "Elasticsearch Bulk (NDJSON)"
+    }
+
+    fn description(&self) -> &'static str {
+        "The items as bulk-index documents for Elasticsearch/OpenSearch _bulk (NDJSON)"
+    }
+
+    fn extension(&self) -> &'static str {
+        "ndjson"
+    }
+
+    fn export(
+        &self,
+        _schema: &TaxonomySchema,
+        data: &TaxonomyData,
+        out: &mut dyn Write,
+        _options: &ExportOptions,
+    ) -> io::Result<()> {
+        write_bulk_ndjson(data, out)
+    }
+}
+
+/// The index mapping for the bulk documents (`search_index_mapping`)
+pub struct ElasticsearchMappingExporter;
+
+impl Exporter for ElasticsearchMappingExporter {
+    fn id(&self) -> &'static str {
+        "elasticsearch-mapping"
+    }
+
+    fn name(&self) -> &'static str {
+        "Elasticsearch Index Mapping"
+    }
+
+    fn description(&self) -> &'static str {
+        "Index mappings for the elasticsearch documents, derived from the schema's facets"
+    }
+
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+
+    fn export(
+        &self,
+        schema: &TaxonomySchema,
+        _data: &TaxonomyData,
+        out: &mut dyn Write,
+        _options: &ExportOptions,
+    ) -> io::Result<()> {
+        serde_json::to_writer_pretty(&mut *out, &search_index_mapping(schema))?;
+        writeln!(out)
+    }
+}
+
+/// The items as a standalone HTML page (`write_html`)
+pub struct HtmlExporter;
+
+impl Exporter for HtmlExporter {
+    fn id(&self) -> &'static str {
+        "html"
+    }
+
+    fn name(&self) -> &'static str {
+        "HTML Page"
+    }
+
+    fn description(&self) -> &'static str {
+        "The items as an HTML page, with their facets, fields and attachments (images inline)"
+    }
+
+    fn extension(&self) -> &'static str {
+        "html"
+    }
+
+    fn export(
+        &self,
+        schema: &TaxonomySchema,
+        data: &TaxonomyData,
+        out: &mut dyn Write,
+        options: &ExportOptions,
+    ) -> io::Result<()> {
+        let data_dir = options.data_dir.as_deref();
+        write_html(data, schema, data_dir, options.lang.as_deref(), out)
+    }
+}
+
+/// The classical hierarchy as a SKOS concept scheme (`write_skos`)
+pub struct SkosExporter;
+
+impl Exporter for SkosExporter {
+    fn id(&self) -> &'static str {
+        "skos"
+    }
+
+    fn name(&self) -> &'static str {
+        "SKOS (RDF/XML)"
+    }
+
+    fn description(&self) -> &'static str {
+        "The classical hierarchy as a SKOS concept scheme (RDF/XML)"
+    }
+
+    fn extension(&self) -> &'static str {
+        "rdf"
+    }
+
+    fn export(
+        &self,
+        schema: &TaxonomySchema,
+        _data: &TaxonomyData,
+        out: &mut dyn Write,
+        _options: &ExportOptions,
+    ) -> io::Result<()> {
+        write_skos(schema, out)
+    }
+}
+
+/// The taxonomy as SQL table definitions and inserts (`export_sql`)
+pub struct SqlExporter;
+
+impl Exporter for SqlExporter {
+    fn id(&self) -> &'static str {
+        "sql"
+    }
+
+    fn name(&self) -> &'static str {
+        "SQL (PostgreSQL)"
+    }
+
+    fn description(&self) -> &'static str {
+        "CREATE TABLE statements for the hierarchy, facets and items, with INSERTs of their rows"
+    }
+
+    fn extension(&self) -> &'static str {
+        "sql"
+    }
+
+    fn export(
+        &self,
+        schema: &TaxonomySchema,
+        data: &TaxonomyData,
+        out: &mut dyn Write,
+        _options: &ExportOptions,
+    ) -> io::Result<()> {
+        export_sql(schema, data, out)
+    }
+}
+
+/// The classical hierarchy as an SVG sunburst sized by item counts
+/// The size is the smaller of the width and height asked for
+pub struct SunburstExporter;
+
+impl Exporter for SunburstExporter {
+    fn id(&self) -> &'static str {
+        "sunburst"
+    }
+
+    fn name(&self) -> &'static str {
+        "Sunburst"
+    }
+
+    fn description(&self) -> &'static str {
+        "The classical hierarchy as a sunburst sized by item counts (SVG)"
+    }
+
+    fn extension(&self) -> &'static str {
+        "svg"
+    }
+
+    fn export(
+        &self,
+        schema: &TaxonomySchema,
+        data: &TaxonomyData,
+        out: &mut dyn Write,
+        options: &ExportOptions,
+    ) -> io::Result<()> {
+        let size = match (options.width, options.height) {
+            (Some(width), Some(height)) => width.min(height),
+            (Some(size), None) | (None, Some(size)) => size,
+            (None, None) => SUNBURST_SIZE,
+        };
+        let hierarchy = &schema.classical_hierarchy;
+        write_sunburst_svg(hierarchy, &data.items, size, options.lang.as_deref(), out)
+    }
+}
+
+/// The classical hierarchy as an SVG tree diagram (`render_hierarchy_svg`)
+pub struct TreeExporter;
+
+impl Exporter for TreeExporter {
+    fn id(&self) -> &'static str {
+        "tree"
+    }
+
+    fn name(&self) -> &'static str {
+        "Tree Diagram"
+    }
+
+    fn description(&self) -> &'static str {
+        "The classical hierarchy as a tree diagram (SVG)"
+    }
+
+    fn extension(&self) -> &'static str {
+        "svg"
+    }
+
+    fn export(
+        &self,
+        schema: &TaxonomySchema,
+        _data: &TaxonomyData,
+        out: &mut dyn Write,
+        options: &ExportOptions,
+    ) -> io::Result<()> {
+        let style = HierarchyStyle {
+            lang: options.lang.clone(),
+            ..HierarchyStyle::default()
+        };
+        let svg = render_hierarchy_svg(&schema.classical_hierarchy, &style);
+        out.write_all(svg.as_bytes())
+    }
+}
+
+/// The classical hierarchy as an SVG treemap sized by item counts
+pub struct TreemapExporter;
+
+impl Exporter for TreemapExporter {
+    fn id(&self) -> &'static str {
+        "treemap"
+    }
+
+    fn name(&self) -> &'static str {
+        "Treemap"
+    }
+
+    fn description(&self) -> &'static str {
+        "The classical hierarchy as a treemap sized by item counts (SVG)"
+    }
+
+    fn extension(&self) -> &'static str {
+        "svg"
+    }
+
+    fn export(
+        &self,
+        schema: &TaxonomySchema,
+        data: &TaxonomyData,
+        out: &mut dyn Write,
+        options: &ExportOptions,
+    ) -> io::Result<()> {
+        let width = options.width.unwrap_or(TREEMAP_WIDTH);
+        let height = options.height.unwrap_or(TREEMAP_HEIGHT);
+        let hierarchy = &schema.classical_hierarchy;
+        let lang = options.lang.as_deref();
+        write_treemap_svg(hierarchy, &data.items, width, height, lang, out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{HierarchyBuilder, ItemBuilder, TaxonomySchemaBuilder};
+    use std::collections::HashMap;
+
+    fn sample() -> (TaxonomySchema, TaxonomyData) {
+        let schema = TaxonomySchemaBuilder::new("Beverages")
+            .hierarchy(
+                HierarchyBuilder::new()
+                    .root("Beverage")
+                    .leaf("Coffee", "brewed from \"roasted\" beans")
+                    .build(),
+            )
+            .facet("temperature", ["hot", "cold"])
+            .facet("flavor", ["vanilla", "caramel"])
+            .build();
+        let data = TaxonomyData {
+            schema: String::new(),
+            items: vec![ItemBuilder::new("Latte")
+                .path(["Beverage", "Coffee"])
+                .facet("temperature", "hot")
+                .facet_values("flavor", ["vanilla", "caramel"])
+                .build()],
+            extra: HashMap::new(),
+        };
+        (schema, data)
+    }
+
+    fn export(registry: &ExporterRegistry, id: &str) -> String {
+        let (schema, data) = sample();
+        let mut out = Vec::new();
+        registry
+            .export(id, &schema, &data, &mut out, &ExportOptions::default())
+            .unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_registry_builtins() {
+        let registry = ExporterRegistry::default();
+        let ids: Vec<&str> = registry.iter().map(|e| e.id()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "csv",
+                "dot",
+                "elasticsearch",
+                "elasticsearch-mapping",
+                "html",
+                "skos",
+                "sql",
+                "sunburst",
+                "tree",
+                "treemap"
+            ]
+        );
+        assert_eq!(registry.get("skos").unwrap().extension(), "rdf");
+
+        assert_eq!(
+            export(&registry, "csv"),
+            "name,path,flavor,temperature\n\
+             Latte,Beverage > Coffee,\"vanilla, caramel\",hot\n"
+        );
+        let dot = export(&registry, "dot");
+        assert!(dot.contains(r#""Beverage" -> "Coffee";"#));
+        assert!(dot.contains(r#"tooltip="brewed from \"roasted\" beans""#));
+
+        let (schema, data) = sample();
+        let mut out = Vec::new();
+        let options = ExportOptions::default();
+        assert!(registry
+            .export("pdf", &schema, &data, &mut out, &options)
+            .is_err());
+    }
+
+    struct NamesExporter;
+
+    impl Exporter for NamesExporter {
+        fn id(&self) -> &'static str {
+            "csv"
+        }
+
+        fn name(&self) -> &'static str {
+            "Names"
+        }
+
+        fn description(&self) -> &'static str {
+            "Item names, one per line"
+        }
+
+        fn extension(&self) -> &'static str {
+            "txt"
+        }
+
+        fn export(
+            &self,
+            _schema: &TaxonomySchema,
+            data: &TaxonomyData,
+            out: &mut dyn Write,
+            _options: &ExportOptions,
+        ) -> io::Result<()> {
+            for item in &data.items {
+                writeln!(out, "{}", item.name)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_register_replaces_same_id() {
+        let mut registry = ExporterRegistry::default();
+        let count = registry.iter().count();
+        registry.register(Box::new(NamesExporter));
+
+        assert_eq!(registry.iter().count(), count);
+        assert_eq!(registry.get("csv").unwrap().extension(), "txt");
+        assert_eq!(export(&registry, "csv"), "Latte\n");
+    }
+}
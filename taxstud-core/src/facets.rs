@@ -0,0 +1,637 @@
+use crate::fields::is_uri;
+use crate::localization::localized_label;
+use crate::models::{
+    value_as_vec, ConstraintViolation, DisplayHint, FacetCondition, FacetConstraint,
+    FacetDefinition, FacetKind, FacetValueInfo, FacetValueNode, TaxonomySchema, VocabularyImport,
+};
+use crate::schema::add_facet_values_to_json;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+
+impl FacetKind {
+    /// What a value of this kind looks like, for error messages and input hints
+    pub fn expected(self) -> &'static str {
+        match self {
+            FacetKind::Enum => "one of the allowed values",
+            FacetKind::Text => "text",
+            FacetKind::Number => "a number",
+            FacetKind::Date => "a date like 2024-01-31",
+            FacetKind::Boolean => "true or false",
+            FacetKind::Url => "a URL like https://example.com",
+        }
+    }
+}
+
+impl fmt::Display for FacetKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FacetKind::Enum => "enum",
+            FacetKind::Text => "text",
+            FacetKind::Number => "number",
+            FacetKind::Date => "date",
+            FacetKind::Boolean => "boolean",
+            FacetKind::Url => "url",
+        })
+    }
+}
+
+impl FacetDefinition {
+    /// An optional, multi-valued facet of the given kind with no value restriction
+    pub fn new(kind: FacetKind) -> Self {
+        Self {
+            kind,
+            allowed_values: Vec::new(),
+            required: false,
+            multi: true,
+            default: None,
+            value_info: HashMap::new(),
+            labels: HashMap::new(),
+        }
+    }
+
+    /// An enum facet with these allowed values (the original list form)
+    pub fn enumerated<I, S>(values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            allowed_values: values.into_iter().map(Into::into).collect(),
+            ..Self::new(FacetKind::Enum)
+        }
+    }
+
+    /// Values to offer when picking (allowed values, or true/false for booleans)
+    pub fn value_choices(&self) -> Vec<String> {
+        if self.allowed_values.is_empty() && self.kind == FacetKind::Boolean {
+            vec!["true".to_string(), "false".to_string()]
+        } else {
+            self.allowed_values.clone()
+        }
+    }
+
+    /// Values to offer for new items: the choices minus deprecated values
+    pub fn active_choices(&self) -> Vec<String> {
+        self.value_choices()
+            .into_iter()
+            .filter(|value| !self.is_deprecated(value))
+            .collect()
+    }
+
+    /// Whether the schema marks this allowed value as deprecated
+    pub fn is_deprecated(&self, value: &str) -> bool {
+        self.value_info
+            .get(value)
+            .is_some_and(|info| info.deprecated)
+    }
+
+    /// Display label of a value in a language, falling back to its label, then the value itself
+    pub fn label_for<'a>(&'a self, value: &'a str, lang: Option<&str>) -> &'a str {
+        let Some(info) = self.value_info.get(value) else {
+            return value;
+        };
+        localized_label(&info.labels, lang)
+            .or(info.label.as_deref())
+            .unwrap_or(value)
+    }
+
+    /// Check an item's facet value, which may be an array when the facet is multi-valued
+    /// Each error reads after "facet 'name' "
+    pub fn check_facet(&self, value: &Value) -> Vec<String> {
+        let mut errors = Vec::new();
+        let values = match value {
+            Value::Array(arr) => {
+                if arr.is_empty() {
+                    errors.push("has empty array".to_string());
+                }
+                if !self.multi {
+                    errors.push("takes a single value, found an array".to_string());
+                }
+                arr.iter().collect()
+            }
+            value => vec![value],
+        };
+
+        errors.extend(values.into_iter().filter_map(|v| self.check_value(v).err()));
+        errors
+    }
+
+    /// Check a single (non-array) facet value against the kind and allowed values
+    /// The error reads after "facet 'name' ", e.g. "has invalid value 'x' (expected a number)"
+    pub fn check_value(&self, value: &Value) -> Result<(), String> {
+        let kind_matches = match (self.kind, value) {
+            // Numeric enum values are compared against the allowed values below
+            (FacetKind::Enum, Value::String(_) | Value::Number(_)) => true,
+            (FacetKind::Text, Value::String(_)) => true,
+            (FacetKind::Number, Value::Number(_)) => true,
+            (FacetKind::Date, Value::String(s)) => is_iso_date(s),
+            (FacetKind::Boolean, Value::Bool(_)) => true,
+            (FacetKind::Url, Value::String(s)) => is_uri(s),
+            _ => false,
+        };
+
+        let shown = value
+            .as_str()
+            .map_or_else(|| value.to_string(), str::to_string);
+        if !kind_matches {
+            return Err(format!(
+                "has invalid value '{}' (expected {})",
+                shown,
+                self.kind.expected()
+            ));
+        }
+
+        if !self.allowed_values.is_empty() && !self.allows(value) {
+            return Err(format!(
+                "has invalid value '{}' (not in allowed values)",
+                shown
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Convert typed-in text to a facet value of this kind
+    /// Multi-valued facets (except text) split on commas into an array
+    pub fn value_from_text(&self, text: &str) -> Result<Value, String> {
+        let text = text.trim();
+        if self.multi && self.kind != FacetKind::Text && text.contains(',') {
+            let values = text
+                .split(',')
+                .map(str::trim)
+                .filter(|part| !part.is_empty())
+                .map(|part| self.single_value_from_text(part))
+                .collect::<Result<Vec<_>, _>>()?;
+            return Ok(Value::Array(values));
+        }
+
+        self.single_value_from_text(text)
+    }
+
+    fn single_value_from_text(&self, text: &str) -> Result<Value, String> {
+        let invalid = || format!("'{}' is not {}", text, self.kind.expected());
+        match self.kind {
+            FacetKind::Enum | FacetKind::Text => Ok(Value::String(text.to_string())),
+            FacetKind::Number => text
+                .parse::<i64>()
+                .map(Value::from)
+                .or_else(|_| text.parse::<f64>().map(Value::from))
+                .map_err(|_| invalid()),
+            FacetKind::Date if is_iso_date(text) => Ok(Value::String(text.to_string())),
+            FacetKind::Date => Err(invalid()),
+            FacetKind::Url if is_uri(text) => Ok(Value::String(text.to_string())),
+            FacetKind::Url => Err(invalid()),
+            FacetKind::Boolean => match text.to_lowercase().as_str() {
+                "true" | "yes" => Ok(Value::Bool(true)),
+                "false" | "no" => Ok(Value::Bool(false)),
+                _ => Err(invalid()),
+            },
+        }
+    }
+
+    /// Add vocabulary terms to the allowed values, skipping duplicates and reporting conflicts
+    /// A term conflicts when it differs from an existing value only in case, describes an
+    /// existing value differently, or is not a valid value of this kind
+    pub fn merge_values(&mut self, terms: Vec<FacetValueNode>) -> VocabularyImport {
+        let mut report = VocabularyImport::default();
+
+        for term in terms {
+            let value = term.value.trim().to_string();
+            if value.is_empty() {
+                continue;
+            }
+
+            if self.allowed_values.contains(&value) {
+                let existing = self.value_info.get(&value).cloned().unwrap_or_default();
+                let described = term.info != FacetValueInfo::default();
+                if described && existing != FacetValueInfo::default() && existing != term.info {
+                    report
+                        .conflicts
+                        .push(format!("'{}' is already described differently", value));
+                } else {
+                    report.duplicates.push(value);
+                }
+                continue;
+            }
+
+            if let Some(existing) = self
+                .allowed_values
+                .iter()
+                .find(|existing| existing.to_lowercase() == value.to_lowercase())
+            {
+                report.conflicts.push(format!(
+                    "'{}' differs only in case from existing value '{}'",
+                    value, existing
+                ));
+                continue;
+            }
+
+            if let Err(e) = self.single_value_from_text(&value) {
+                report.conflicts.push(e);
+                continue;
+            }
+
+            self.allowed_values.push(value.clone());
+            if term.info != FacetValueInfo::default() {
+                self.value_info.insert(value.clone(), term.info.clone());
+            }
+            report
+                .added
+                .push(FacetValueNode::new(value).with_info(term.info));
+        }
+
+        report
+    }
+
+    fn allows(&self, value: &Value) -> bool {
+        match value {
+            Value::String(s) => self.allowed_values.contains(s),
+            Value::Number(n) => allows_number(&self.allowed_values, n),
+            Value::Bool(b) => self.allowed_values.contains(&b.to_string()),
+            _ => false,
+        }
+    }
+}
+
+impl TaxonomySchema {
+    /// Merge vocabulary terms into a dimension (created as an enum facet if missing)
+    /// Added values are also written into the raw JSON Schema, ready for `save_schema`
+    pub fn add_facet_values(
+        &mut self,
+        dimension: &str,
+        terms: Vec<FacetValueNode>,
+    ) -> Result<VocabularyImport, String> {
+        let report = self
+            .faceted_dimensions
+            .entry(dimension.to_string())
+            .or_insert_with(|| FacetDefinition::new(FacetKind::Enum))
+            .merge_values(terms);
+
+        if let Some(tree) = self.facet_value_trees.get_mut(dimension) {
+            tree.extend(report.added.iter().cloned());
+        }
+        if let Some(json_schema) = self.json_schema_mut() {
+            add_facet_values_to_json(json_schema, dimension, &report.added)?;
+        }
+
+        Ok(report)
+    }
+
+    /// Display hint the schema's "x-display" gives a facet value
+    pub fn display_hint(&self, facet: &str, value: &str) -> Option<&DisplayHint> {
+        self.display_hints.get(facet)?.get(value)
+    }
+
+    /// Put a facet's values in their "x-display" order; values without one keep their
+    /// order after them
+    pub fn sort_by_display_order(&self, facet: &str, values: &mut [String]) {
+        values.sort_by_key(|value| {
+            let order = self.display_hint(facet, value).and_then(|hint| hint.order);
+            (order.is_none(), order)
+        });
+    }
+
+    /// Facet values to pre-fill for a new item, from the dimensions that declare a default
+    pub fn default_facets(&self) -> HashMap<String, Value> {
+        self.faceted_dimensions
+            .iter()
+            .filter_map(|(name, definition)| Some((name.clone(), definition.default.clone()?)))
+            .collect()
+    }
+}
+
+impl FacetCondition {
+    /// The values the condition accepts
+    pub fn values(&self) -> &[String] {
+        match self {
+            FacetCondition::Value(value) => std::slice::from_ref(value),
+            FacetCondition::AnyOf(values) => values,
+        }
+    }
+
+    /// Whether any of a facet's values is accepted
+    fn matches(&self, facet: Option<&Value>) -> bool {
+        facet
+            .map(value_as_vec)
+            .unwrap_or_default()
+            .iter()
+            .any(|value| self.values().contains(value))
+    }
+}
+
+impl fmt::Display for FacetCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FacetCondition::Value(value) => f.write_str(value),
+            FacetCondition::AnyOf(values) => write!(f, "one of {}", values.join(", ")),
+        }
+    }
+}
+
+impl FacetConstraint {
+    /// "If `facet` is `value`, `then_facet` must be `then_value`"
+    pub fn implies(
+        facet: impl Into<String>,
+        value: impl Into<String>,
+        then_facet: impl Into<String>,
+        then_value: impl Into<String>,
+    ) -> Self {
+        FacetConstraint {
+            when: [(facet.into(), FacetCondition::Value(value.into()))].into(),
+            then: [(then_facet.into(), FacetCondition::Value(then_value.into()))].into(),
+            ..Default::default()
+        }
+    }
+
+    /// "If `facet` is `value`, `other_facet` must not be `other_value`"
+    pub fn excludes(
+        facet: impl Into<String>,
+        value: impl Into<String>,
+        other_facet: impl Into<String>,
+        other_value: impl Into<String>,
+    ) -> Self {
+        FacetConstraint {
+            when: [(facet.into(), FacetCondition::Value(value.into()))].into(),
+            forbid: [(
+                other_facet.into(),
+                FacetCondition::Value(other_value.into()),
+            )]
+            .into(),
+            ..Default::default()
+        }
+    }
+
+    /// Whether an item with these facets triggers the rule
+    pub fn applies_to(&self, facets: &HashMap<String, Value>) -> bool {
+        self.when
+            .iter()
+            .all(|(facet, condition)| condition.matches(facets.get(facet)))
+    }
+
+    /// Every facet the rule mentions, in "if", "then", "not" order
+    pub fn facets(&self) -> impl Iterator<Item = (&String, &FacetCondition)> {
+        self.when.iter().chain(&self.then).chain(&self.forbid)
+    }
+
+    /// How an item with these facets breaks the rule (empty when it holds)
+    pub fn violations(&self, facets: &HashMap<String, Value>) -> Vec<ConstraintViolation> {
+        if !self.applies_to(facets) {
+            return Vec::new();
+        }
+
+        let premise: Vec<String> = self
+            .when
+            .iter()
+            .map(|(facet, condition)| format!("{} is {}", facet, condition))
+            .collect();
+        let violation = |facet: &String, requirement: String| ConstraintViolation {
+            facet: facet.clone(),
+            message: self.message.clone().unwrap_or_else(|| {
+                format!("if {}, {} {}", premise.join(" and "), facet, requirement)
+            }),
+        };
+
+        let missing = self
+            .then
+            .iter()
+            .filter(|(facet, condition)| !condition.matches(facets.get(*facet)))
+            .map(|(facet, condition)| violation(facet, format!("must be {}", condition)));
+        let forbidden = self
+            .forbid
+            .iter()
+            .filter(|(facet, condition)| condition.matches(facets.get(*facet)))
+            .map(|(facet, condition)| violation(facet, format!("must not be {}", condition)));
+        missing.chain(forbidden).collect()
+    }
+}
+
+/// A numeric facet value is allowed when an allowed value is the same number ("5" or "5.0" for 5)
+fn allows_number(allowed_values: &[String], n: &serde_json::Number) -> bool {
+    let Some(n) = n.as_f64() else {
+        return false;
+    };
+    allowed_values
+        .iter()
+        .any(|allowed| allowed.trim().parse::<f64>() == Ok(n))
+}
+
+/// Whether the string is an ISO 8601 calendar date (YYYY-MM-DD)
+/// Checks the shape and month/day ranges, not month lengths
+pub fn is_iso_date(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    if bytes.len() != 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+        return false;
+    }
+    let number = |range: std::ops::Range<usize>| {
+        let part = &s[range];
+        if part.bytes().all(|b| b.is_ascii_digit()) {
+            part.parse::<u32>().ok()
+        } else {
+            None
+        }
+    };
+
+    matches!(
+        (number(0..4), number(5..7), number(8..10)),
+        (Some(_), Some(1..=12), Some(1..=31))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_legacy_list_parses_as_enum() {
+        let definition: FacetDefinition = serde_json::from_value(json!(["hot", "iced"])).unwrap();
+        assert_eq!(definition, FacetDefinition::enumerated(["hot", "iced"]));
+        assert!(definition.multi);
+        assert!(!definition.required);
+    }
+
+    #[test]
+    fn test_typed_definition() {
+        let definition: FacetDefinition =
+            serde_json::from_value(json!({"kind": "number", "required": true, "multi": false}))
+                .unwrap();
+        assert_eq!(definition.kind, FacetKind::Number);
+        assert!(definition.required);
+
+        assert!(definition.check_value(&json!(4.5)).is_ok());
+        assert!(definition.check_value(&json!("4.5")).is_err());
+        assert_eq!(definition.value_from_text("12").unwrap(), json!(12));
+        assert!(definition.value_from_text("twelve").is_err());
+    }
+
+    #[test]
+    fn test_required_and_default_facets() {
+        use crate::builder::{ItemBuilder, TaxonomySchemaBuilder};
+        use crate::models::TaxonomyData;
+        use crate::validation::validate_data;
+
+        let status: FacetDefinition = serde_json::from_value(json!({
+            "allowed_values": ["draft", "published"],
+            "required": true,
+            "default": "draft"
+        }))
+        .unwrap();
+        let schema = TaxonomySchemaBuilder::new("Articles")
+            .facet_definition("status", status)
+            .facet("topic", ["news", "sports"])
+            .build();
+        assert_eq!(schema.default_facets()["status"], json!("draft"));
+        assert_eq!(schema.default_facets().len(), 1);
+
+        let data = TaxonomyData {
+            schema: "schema.json".to_string(),
+            items: vec![ItemBuilder::new("Scores").facet("topic", "sports").build()],
+            extra: HashMap::new(),
+        };
+        let errors = validate_data(&data, &schema).unwrap_err();
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("missing required facet 'status'")));
+    }
+
+    #[test]
+    fn test_date_and_boolean_values() {
+        let date = FacetDefinition::new(FacetKind::Date);
+        assert!(date.check_value(&json!("2024-02-29")).is_ok());
+        assert!(date.check_value(&json!("2024-13-01")).is_err());
+        assert!(date.check_value(&json!("Feb 29")).is_err());
+        assert!(!is_iso_date("2024-1-01"));
+
+        let flag = FacetDefinition::new(FacetKind::Boolean);
+        assert!(flag.check_value(&json!(true)).is_ok());
+        assert!(flag.check_value(&json!("true")).is_err());
+        assert_eq!(flag.value_from_text("Yes").unwrap(), json!(true));
+        assert_eq!(flag.value_choices(), vec!["true", "false"]);
+    }
+
+    #[test]
+    fn test_enum_accepts_numeric_allowed_value() {
+        let sizes = FacetDefinition::enumerated(["8", "12", "16"]);
+        assert!(sizes.check_value(&json!(12)).is_ok());
+        assert!(sizes.check_value(&json!(10)).is_err());
+        assert_eq!(sizes.value_from_text("8, 12").unwrap(), json!(["8", "12"]));
+    }
+
+    #[test]
+    fn test_value_metadata() {
+        let definition: FacetDefinition = serde_json::from_value(json!([
+            "hot",
+            {"value": "iced", "label": "Iced (over ice)", "description": "Served cold"},
+            {"value": "lukewarm", "deprecated": true}
+        ]))
+        .unwrap();
+        assert_eq!(definition.allowed_values, vec!["hot", "iced", "lukewarm"]);
+        assert_eq!(definition.label_for("iced", None), "Iced (over ice)");
+        assert_eq!(definition.label_for("hot", None), "hot");
+        assert!(definition.is_deprecated("lukewarm"));
+        assert_eq!(definition.active_choices(), vec!["hot", "iced"]);
+        // Deprecated values are still valid
+        assert!(definition.check_value(&json!("lukewarm")).is_ok());
+    }
+
+    #[test]
+    fn test_merge_values_reports_duplicates_and_conflicts() {
+        let mut definition = FacetDefinition::enumerated(["hot", "iced"]);
+        let report = definition.merge_values(vec![
+            FacetValueNode::new("warm"),
+            FacetValueNode::new("hot"),
+            FacetValueNode::new("warm"),
+            FacetValueNode::new("Iced"),
+        ]);
+        assert_eq!(report.added, vec![FacetValueNode::new("warm")]);
+        assert_eq!(report.duplicates, vec!["hot", "warm"]);
+        assert_eq!(report.conflicts.len(), 1);
+        assert!(report.conflicts[0].contains("differs only in case"));
+        assert_eq!(definition.allowed_values, vec!["hot", "iced", "warm"]);
+
+        let mut sizes = FacetDefinition::new(FacetKind::Number);
+        let report = sizes.merge_values(vec![FacetValueNode::new("8"), FacetValueNode::new("big")]);
+        assert_eq!(report.added.len(), 1);
+        assert_eq!(report.conflicts, vec!["'big' is not a number"]);
+    }
+
+    #[test]
+    fn test_constraints() {
+        use crate::builder::{ItemBuilder, TaxonomySchemaBuilder};
+        use crate::validation::check_constraints;
+
+        let constraints: Vec<FacetConstraint> = serde_json::from_value(json!([
+            {"if": {"temperature": "iced"}, "then": {"serving_vessel": "glass"}},
+            {"if": {"temperature": ["hot", "warm"]}, "not": {"serving_vessel": "glass"},
+             "message": "hot drinks crack glasses"}
+        ]))
+        .unwrap();
+        assert_eq!(
+            constraints[0],
+            FacetConstraint::implies("temperature", "iced", "serving_vessel", "glass")
+        );
+
+        let mut schema = TaxonomySchemaBuilder::new("Beverages")
+            .facet("temperature", ["hot", "warm", "iced"])
+            .facet("serving_vessel", ["mug", "glass"])
+            .build();
+        schema.constraints = constraints;
+
+        let items = vec![
+            ItemBuilder::new("Iced Latte")
+                .facet("temperature", "iced")
+                .facet("serving_vessel", "mug")
+                .build(),
+            ItemBuilder::new("Toddy")
+                .facet("temperature", "warm")
+                .facet("serving_vessel", "glass")
+                .build(),
+            ItemBuilder::new("Espresso")
+                .facet("temperature", "hot")
+                .facet("serving_vessel", "mug")
+                .build(),
+        ];
+        let errors = check_constraints(&items, &schema).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                "Item #1 ('Iced Latte'): if temperature is iced, serving_vessel must be glass",
+                "Item #2 ('Toddy'): hot drinks crack glasses",
+            ]
+        );
+
+        let violations = schema.constraints[1].violations(&items[1].facets);
+        assert_eq!(violations[0].facet, "serving_vessel");
+
+        schema.constraints.push(FacetConstraint::excludes(
+            "temperature",
+            "cold",
+            "size",
+            "large",
+        ));
+        let errors = check_constraints(&[], &schema).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                "Constraint #3: facet 'temperature' has invalid value 'cold' (not in allowed values)",
+                "Constraint #3: refers to undefined facet 'size'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sort_by_display_order() {
+        let schema = crate::schema::build_schema_from_json(json!({
+            "classical_hierarchy": {"root": "Beverage"},
+            "faceted_dimensions": {"temperature": ["hot", "iced", "warm"]},
+            "x-display": {"temperature": {"iced": {"order": 1}, "hot": {"order": 2}}}
+        }))
+        .unwrap();
+
+        let mut values = vec!["warm".to_string(), "hot".to_string(), "iced".to_string()];
+        schema.sort_by_display_order("temperature", &mut values);
+        assert_eq!(values, vec!["iced", "hot", "warm"]);
+        assert!(schema.display_hint("temperature", "warm").is_none());
+    }
+}
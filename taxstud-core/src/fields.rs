@@ -0,0 +1,379 @@
+use crate::facets::is_iso_date;
+use crate::models::{FacetKind, FieldKind, Item, ItemField, TaxonomySchema};
+use serde_json::Value;
+use std::fmt;
+
+impl FieldKind {
+    /// What a value of this kind looks like, for error messages and input hints
+    pub fn expected(self) -> &'static str {
+        match self {
+            FieldKind::String => "text",
+            FieldKind::Number => "a number",
+            FieldKind::Boolean => "true or false",
+            FieldKind::Date => "a date like 2024-01-31",
+            FieldKind::Uri => "a URL like https://example.com",
+        }
+    }
+}
+
+impl fmt::Display for FieldKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FieldKind::String => "string",
+            FieldKind::Number => "number",
+            FieldKind::Boolean => "boolean",
+            FieldKind::Date => "date",
+            FieldKind::Uri => "uri",
+        })
+    }
+}
+
+impl ItemField {
+    /// An optional field of the given kind, shown under its name
+    pub fn new(kind: FieldKind) -> Self {
+        Self {
+            kind,
+            ..Self::default()
+        }
+    }
+
+    /// Name shown in forms and exports: the label, or the field name
+    pub fn display_name<'a>(&'a self, name: &'a str) -> &'a str {
+        self.label.as_deref().unwrap_or(name)
+    }
+
+    /// Check an item's value for this field
+    /// The error reads after "field 'name' ", e.g. "has invalid value 'x' (expected a number)"
+    pub fn check_value(&self, value: &Value) -> Result<(), String> {
+        let matches = match (self.kind, value) {
+            (FieldKind::String, Value::String(_)) => true,
+            (FieldKind::Number, Value::Number(_)) => true,
+            (FieldKind::Boolean, Value::Bool(_)) => true,
+            (FieldKind::Date, Value::String(s)) => is_iso_date(s),
+            (FieldKind::Uri, Value::String(s)) => is_uri(s),
+            _ => false,
+        };
+        if matches {
+            return Ok(());
+        }
+
+        let shown = value
+            .as_str()
+            .map_or_else(|| value.to_string(), str::to_string);
+        Err(format!(
+            "has invalid value '{}' (expected {})",
+            shown,
+            self.kind.expected()
+        ))
+    }
+
+    /// Convert typed-in text to a value of this field's kind
+    pub fn value_from_text(&self, text: &str) -> Result<Value, String> {
+        let text = text.trim();
+        let invalid = || format!("'{}' is not {}", text, self.kind.expected());
+        match self.kind {
+            FieldKind::String => Ok(Value::String(text.to_string())),
+            FieldKind::Number => text
+                .parse::<i64>()
+                .map(Value::from)
+                .or_else(|_| text.parse::<f64>().map(Value::from))
+                .map_err(|_| invalid()),
+            FieldKind::Boolean => match text.to_lowercase().as_str() {
+                "true" | "yes" => Ok(Value::Bool(true)),
+                "false" | "no" => Ok(Value::Bool(false)),
+                _ => Err(invalid()),
+            },
+            FieldKind::Date if is_iso_date(text) => Ok(Value::String(text.to_string())),
+            FieldKind::Uri if is_uri(text) => Ok(Value::String(text.to_string())),
+            FieldKind::Date | FieldKind::Uri => Err(invalid()),
+        }
+    }
+}
+
+impl TaxonomySchema {
+    /// The custom item fields, sorted by name
+    pub fn sorted_item_fields(&self) -> Vec<(&String, &ItemField)> {
+        let mut fields: Vec<_> = self.item_fields.iter().collect();
+        fields.sort_by_key(|(name, _)| *name);
+        fields
+    }
+}
+
+/// Check items' custom field values against the schema's item fields
+/// Fields the schema doesn't declare are left alone
+pub fn check_item_fields(items: &[Item], schema: &TaxonomySchema) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+
+    for (idx, item) in items.iter().enumerate() {
+        for (name, field) in schema.sorted_item_fields() {
+            match item.extra.get(name) {
+                None | Some(Value::Null) if field.required => errors.push(format!(
+                    "Item #{} ('{}'): missing required field '{}'",
+                    idx + 1,
+                    item.name,
+                    name
+                )),
+                None | Some(Value::Null) => {}
+                Some(value) => {
+                    if let Err(e) = field.check_value(value) {
+                        errors.push(format!(
+                            "Item #{} ('{}'): field '{}' {}",
+                            idx + 1,
+                            item.name,
+                            name,
+                            e
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Whether a string is an absolute URI: a scheme (letter, then letters, digits, '+', '-'
+/// or '.') followed by ':' and something more, without whitespace
+pub fn is_uri(s: &str) -> bool {
+    let Some((scheme, rest)) = s.split_once(':') else {
+        return false;
+    };
+    let mut chars = scheme.chars();
+    let scheme_ok = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    let rest_ok = match rest.strip_prefix("//") {
+        // An authority-based URI (http, ftp, file...) names a host or a path
+        Some(authority) => !authority.is_empty(),
+        None => !rest.is_empty(),
+    };
+    scheme_ok && rest_ok && !s.chars().any(char::is_whitespace)
+}
+
+/// Whether a string is a web link: http(s), a host of letters, digits, '-', '.' (and an
+/// optional port or credentials), then anything without whitespace
+pub fn is_web_url(s: &str) -> bool {
+    let lower = s.to_ascii_lowercase();
+    let Some(rest) = lower
+        .strip_prefix("https://")
+        .or_else(|| lower.strip_prefix("http://"))
+    else {
+        return false;
+    };
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority.rsplit('@').next().unwrap_or_default();
+    let host = match host.rsplit_once(':') {
+        Some((host, port)) if port.bytes().all(|b| b.is_ascii_digit()) => host,
+        _ => host,
+    };
+    !host.is_empty()
+        && !host.starts_with(['.', '-'])
+        && !host.ends_with('-')
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.'))
+        && !host.contains("..")
+        && !s.chars().any(char::is_whitespace)
+}
+
+/// Whether text is meant as a web link: it starts with http://, https:// or www.
+pub fn looks_like_url(s: &str) -> bool {
+    let lower = s.trim_start().to_ascii_lowercase();
+    ["http://", "https://", "www."]
+        .iter()
+        .any(|prefix| lower.starts_with(prefix))
+}
+
+/// A link found among an item's values, for showing as clickable
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemLink {
+    /// Facet or field name shown with the link (its label when it has one)
+    pub label: String,
+    pub url: String,
+}
+
+/// The item's links: values of url facets and uri fields, and any other facet or
+/// additional field value that is a web link, ordered by name
+/// Facet names are shown in `lang` where translated
+pub fn item_links(item: &Item, schema: &TaxonomySchema, lang: Option<&str>) -> Vec<ItemLink> {
+    let mut links = Vec::new();
+
+    let mut facet_names: Vec<_> = item.facets.keys().collect();
+    facet_names.sort();
+    for facet_name in facet_names {
+        let definition = schema.faceted_dimensions.get(facet_name);
+        let url_typed = definition.is_some_and(|definition| definition.kind == FacetKind::Url);
+        let label = definition.map_or(facet_name.as_str(), |definition| {
+            definition.display_name(facet_name, lang)
+        });
+        for value in item.get_facet_as_vec(facet_name) {
+            if (url_typed && is_uri(&value)) || is_web_url(&value) {
+                links.push(ItemLink {
+                    label: label.to_string(),
+                    url: value,
+                });
+            }
+        }
+    }
+
+    let mut field_names: Vec<_> = item.extra.keys().collect();
+    field_names.sort();
+    for name in field_names {
+        let Some(value) = item.extra[name].as_str() else {
+            continue;
+        };
+        let field = schema.item_fields.get(name);
+        let url_typed = field.is_some_and(|field| field.kind == FieldKind::Uri);
+        if (url_typed && is_uri(value)) || is_web_url(value) {
+            links.push(ItemLink {
+                label: field
+                    .map_or(name.as_str(), |field| field.display_name(name))
+                    .to_string(),
+                url: value.to_string(),
+            });
+        }
+    }
+
+    links
+}
+
+/// Warnings about values meant as web links that aren't valid ones (e.g. "www.example.com",
+/// missing its scheme, or "https://exa mple.com"), in facets and additional fields
+/// Reads after "Item #n ('name'): "
+pub fn malformed_urls(item: &Item) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    let mut facet_names: Vec<_> = item.facets.keys().collect();
+    facet_names.sort();
+    for facet_name in facet_names {
+        for value in item.get_facet_as_vec(facet_name) {
+            if looks_like_url(&value) && !is_web_url(&value) {
+                warnings.push(format!(
+                    "facet '{}' has malformed URL '{}'",
+                    facet_name, value
+                ));
+            }
+        }
+    }
+
+    let mut field_names: Vec<_> = item.extra.keys().collect();
+    field_names.sort();
+    for name in field_names {
+        if let Some(value) = item.extra[name].as_str() {
+            if looks_like_url(value) && !is_web_url(value) {
+                warnings.push(format!("field '{}' has malformed URL '{}'", name, value));
+            }
+        }
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{ItemBuilder, TaxonomySchemaBuilder};
+    use serde_json::json;
+
+    #[test]
+    fn test_field_values() {
+        let price = ItemField::new(FieldKind::Number);
+        assert_eq!(price.value_from_text("4.5").unwrap(), json!(4.5));
+        assert!(price.check_value(&json!("4.5")).is_err());
+
+        let url = ItemField::new(FieldKind::Uri);
+        assert!(url.check_value(&json!("https://example.com/x")).is_ok());
+        assert!(url.check_value(&json!("mailto:shop@example.com")).is_ok());
+        assert!(url.value_from_text("example.com").is_err());
+        assert!(url.value_from_text("https://").is_err());
+        assert!(!is_uri("https://example.com/a b"));
+        assert!(!is_uri("1http://example.com"));
+    }
+
+    #[test]
+    fn test_check_item_fields() {
+        let schema = TaxonomySchemaBuilder::new("Products")
+            .item_field(
+                "sku",
+                ItemField {
+                    label: Some("SKU".to_string()),
+                    required: true,
+                    ..ItemField::default()
+                },
+            )
+            .item_field("url", ItemField::new(FieldKind::Uri))
+            .build();
+        assert_eq!(schema.item_fields["sku"].display_name("sku"), "SKU");
+
+        let items = vec![
+            ItemBuilder::new("Mug")
+                .extra("sku", "M-1")
+                .extra("url", "https://example.com/mug")
+                .build(),
+            ItemBuilder::new("Cup").extra("url", "cup").build(),
+        ];
+        let errors = check_item_fields(&items, &schema).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                "Item #2 ('Cup'): missing required field 'sku'",
+                "Item #2 ('Cup'): field 'url' has invalid value 'cup' (expected a URL like https://example.com)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_item_links() {
+        let schema = TaxonomySchemaBuilder::new("Products")
+            .facet_definition(
+                "homepage",
+                crate::models::FacetDefinition::new(FacetKind::Url),
+            )
+            .item_field(
+                "manual",
+                ItemField {
+                    label: Some("Manual".to_string()),
+                    ..ItemField::new(FieldKind::Uri)
+                },
+            )
+            .build();
+        let item = ItemBuilder::new("Mug")
+            .facet("homepage", "https://example.com/mug")
+            .extra("manual", "ftp://example.com/mug.pdf")
+            .extra("notes", "see https://example.com")
+            .extra("vendor", "http://vendor.example:8080/mugs")
+            .extra("legacy", "www.example.com/mug")
+            .build();
+
+        let links: Vec<_> = item_links(&item, &schema, None)
+            .into_iter()
+            .map(|link| (link.label, link.url))
+            .collect();
+        assert_eq!(
+            links,
+            vec![
+                (
+                    "homepage".to_string(),
+                    "https://example.com/mug".to_string()
+                ),
+                (
+                    "Manual".to_string(),
+                    "ftp://example.com/mug.pdf".to_string()
+                ),
+                (
+                    "vendor".to_string(),
+                    "http://vendor.example:8080/mugs".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            malformed_urls(&item),
+            vec!["field 'legacy' has malformed URL 'www.example.com/mug'"]
+        );
+        assert!(!is_web_url("https://exa mple.com"));
+        assert!(!is_web_url("https://"));
+    }
+}
@@ -1,23 +1,61 @@
-use crate::models::{Filters, Item};
+use crate::facets::is_iso_date;
+use crate::models::{
+    ClassicalHierarchy, CompareOp, FacetPredicate, FilterOptions, Filters, Item, TaxonomySchema,
+};
 use std::collections::HashMap;
 
-/// Parse facet filter strings in the format "key=value" into a filter map
-/// Multiple values for the same key are collected into a vector
-pub fn parse_facet_filters(facet_strings: &[String]) -> HashMap<String, Vec<String>> {
+/// Parse facet (or field) filter strings ("key=value", "key>=10", "key<5", ...) into a filter map
+/// Multiple predicates for the same key are collected into a vector; invalid strings are skipped
+pub fn parse_facet_filters(facet_strings: &[String]) -> HashMap<String, Vec<FacetPredicate>> {
     let mut facet_map = HashMap::new();
 
     for facet_str in facet_strings {
-        if let Some((key, value)) = facet_str.split_once('=') {
+        if let Some((key, predicate)) = parse_facet_filter(facet_str) {
             facet_map
-                .entry(key.trim().to_string())
+                .entry(key)
                 .or_insert_with(Vec::new)
-                .push(value.trim().to_string());
+                .push(predicate);
         }
     }
 
     facet_map
 }
 
+/// Parse a single facet filter string into its facet name and predicate
+/// Returns None without an operator, an empty name, or a comparison against something
+/// that is neither a number nor an ISO date
+pub fn parse_facet_filter(facet_str: &str) -> Option<(String, FacetPredicate)> {
+    let op_start = facet_str.find(['<', '>', '='])?;
+    let (key, rest) = facet_str.split_at(op_start);
+    let key = key.trim();
+    if key.is_empty() {
+        return None;
+    }
+
+    let (op, value) = if let Some(value) = rest.strip_prefix(">=") {
+        (Some(CompareOp::Ge), value)
+    } else if let Some(value) = rest.strip_prefix("<=") {
+        (Some(CompareOp::Le), value)
+    } else if let Some(value) = rest.strip_prefix('>') {
+        (Some(CompareOp::Gt), value)
+    } else if let Some(value) = rest.strip_prefix('<') {
+        (Some(CompareOp::Lt), value)
+    } else {
+        (None, &rest[1..])
+    };
+
+    let value = value.trim();
+    let predicate = match op {
+        Some(op) => match value.parse() {
+            Ok(number) => FacetPredicate::Compare(op, number),
+            Err(_) if is_iso_date(value) => FacetPredicate::CompareDate(op, value.to_string()),
+            Err(_) => return None,
+        },
+        None => FacetPredicate::Equals(value.to_string()),
+    };
+    Some((key.to_string(), predicate))
+}
+
 /// Apply filters to a list of items, returning only those that match
 pub fn apply_filters(items: &[Item], filters: &Filters) -> Vec<Item> {
     items
@@ -27,15 +65,198 @@ pub fn apply_filters(items: &[Item], filters: &Filters) -> Vec<Item> {
         .collect()
 }
 
+/// Apply filters using the classical hierarchy
+/// With `include_descendants`, a genus matches items under any of its descendant species
+pub fn apply_filters_in_hierarchy(
+    items: &[Item],
+    filters: &Filters,
+    hierarchy: &ClassicalHierarchy,
+) -> Vec<Item> {
+    let resolved = resolve_in_hierarchy(filters, hierarchy);
+    apply_filters(items, &resolved)
+}
+
+/// Check if an item matches the given filters using the classical hierarchy
+/// Prefer apply_filters_in_hierarchy for many items; this resolves the hierarchy on every call
+pub fn matches_filters_in_hierarchy(
+    item: &Item,
+    filters: &Filters,
+    hierarchy: &ClassicalHierarchy,
+) -> bool {
+    matches_filters(item, &resolve_in_hierarchy(filters, hierarchy))
+}
+
+/// Apply filters using everything the schema knows
+/// Resolves genera like apply_filters_in_hierarchy, and a facet value also matches the values nested beneath it
+pub fn apply_filters_with_schema(
+    items: &[Item],
+    filters: &Filters,
+    schema: &TaxonomySchema,
+) -> Vec<Item> {
+    apply_filters(items, &resolve_with_schema(filters, schema))
+}
+
+/// Indices of the items matching the filters, in order; like apply_filters without
+/// cloning the items, for views over a large list
+pub fn filter_indices(items: &[Item], filters: &Filters) -> Vec<usize> {
+    items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| matches_filters(item, filters))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Indices of the items apply_filters_with_schema would return
+pub fn filter_indices_with_schema(
+    items: &[Item],
+    filters: &Filters,
+    schema: &TaxonomySchema,
+) -> Vec<usize> {
+    filter_indices(items, &resolve_with_schema(filters, schema))
+}
+
+/// Check if an item matches the given filters using the schema
+/// Prefer apply_filters_with_schema for many items; this resolves the schema on every call
+pub fn matches_filters_with_schema(
+    item: &Item,
+    filters: &Filters,
+    schema: &TaxonomySchema,
+) -> bool {
+    matches_filters(item, &resolve_with_schema(filters, schema))
+}
+
+fn resolve_with_schema(filters: &Filters, schema: &TaxonomySchema) -> Filters {
+    let mut resolved = resolve_in_hierarchy(filters, &schema.classical_hierarchy);
+    for (facet_name, predicates) in resolved
+        .facets
+        .iter_mut()
+        .chain(resolved.excluded_facets.iter_mut())
+    {
+        let allowed = schema
+            .faceted_dimensions
+            .get(facet_name.as_str())
+            .map_or(&[][..], |definition| &definition.allowed_values);
+        let options = &filters.options;
+        let nested: Vec<FacetPredicate> = predicates
+            .iter()
+            .filter_map(|p| match p {
+                FacetPredicate::Equals(value) => Some(value),
+                _ => None,
+            })
+            // The allowed value a loosely matching filter value stands for, e.g. "europe"
+            // for "Europe"
+            .map(|value| {
+                allowed
+                    .iter()
+                    .find(|allowed| options.text_eq(allowed, value))
+                    .unwrap_or(value)
+            })
+            .flat_map(|value| schema.facet_value_descendants(facet_name, value))
+            .map(FacetPredicate::Equals)
+            .collect();
+        predicates.extend(nested);
+    }
+    resolved
+}
+
+/// Map genus synonyms and codes to species names, then expand each genus with its
+/// descendant species when `include_descendants` is set
+fn resolve_in_hierarchy(filters: &Filters, hierarchy: &ClassicalHierarchy) -> Filters {
+    let options = &filters.options;
+    let canonical = |genera: &[String]| -> Vec<String> {
+        genera
+            .iter()
+            .map(|genus| {
+                hierarchy
+                    .resolve_species(genus)
+                    .or_else(|| {
+                        // e.g. "Cafe" for "Café", when accents are ignored
+                        let node = hierarchy
+                            .iter_preorder()
+                            .map(|(_, node)| node)
+                            .find(|node| node.names().any(|name| options.text_eq(name, genus)))?;
+                        Some(node.species.as_str())
+                    })
+                    .map_or_else(|| genus.clone(), str::to_string)
+            })
+            .collect()
+    };
+
+    let mut resolved = filters.clone();
+    resolved.genera = canonical(&filters.genera);
+    resolved.excluded_genera = canonical(&filters.excluded_genera);
+    if filters.include_descendants {
+        resolved.genera = with_descendants(&resolved.genera, hierarchy);
+        resolved.excluded_genera = with_descendants(&resolved.excluded_genera, hierarchy);
+        resolved.include_descendants = false;
+    }
+    resolved
+}
+
+fn with_descendants(genera: &[String], hierarchy: &ClassicalHierarchy) -> Vec<String> {
+    let mut expanded = genera.to_vec();
+    for genus in genera {
+        expanded.extend(
+            hierarchy
+                .descendants_of(genus)
+                .into_iter()
+                .map(|node| node.species.clone()),
+        );
+    }
+    expanded.sort();
+    expanded.dedup();
+    expanded
+}
+
 /// Check if an item matches the given filters
-/// AND logic between different filter types (genus AND facets)
+/// AND logic between different filter types (name, genus, facets, fields)
+/// Exclusions win: an item matching any excluded genus or facet value never matches
+/// Deprecated items only match with `include_deprecated`
 pub fn matches_filters(item: &Item, filters: &Filters) -> bool {
+    if item.is_deprecated() && !filters.include_deprecated {
+        return false;
+    }
+
+    // Check exclusions first
+    let options = &filters.options;
+    let excluded_genus = filters.excluded_genera.iter().any(|genus| {
+        item.classical_path
+            .iter()
+            .any(|path_item| options.text_eq(path_item, genus))
+    });
+    if excluded_genus {
+        return false;
+    }
+
+    for (facet_name, excluded) in &filters.excluded_facets {
+        let item_values = item.get_facet_as_vec(facet_name);
+        if excluded.iter().any(|predicate| {
+            item_values
+                .iter()
+                .any(|v| predicate.matches_with_options(v, options))
+        }) {
+            return false;
+        }
+    }
+
+    if let Some(needle) = &filters.name_contains {
+        // Always ignores case
+        let options = FilterOptions {
+            case_insensitive: true,
+            ..*options
+        };
+        if !options.fold(&item.name).contains(&*options.fold(needle)) {
+            return false;
+        }
+    }
+
     // Check genus filter (OR within genera)
     if !filters.genera.is_empty() {
         let matches_genus = filters.genera.iter().any(|genus| {
             item.classical_path
                 .iter()
-                .any(|path_item| path_item == genus)
+                .any(|path_item| options.text_eq(path_item, genus))
         });
 
         if !matches_genus {
@@ -43,8 +264,8 @@ pub fn matches_filters(item: &Item, filters: &Filters) -> bool {
         }
     }
 
-    // Check facet filters (AND between different facet names, OR within same facet name)
-    for (facet_name, required_values) in &filters.facets {
+    // Check facet filters (AND between different facet names)
+    for (facet_name, predicates) in &filters.facets {
         let item_values = item.get_facet_as_vec(facet_name);
 
         if item_values.is_empty() {
@@ -52,12 +273,15 @@ pub fn matches_filters(item: &Item, filters: &Filters) -> bool {
             return false;
         }
 
-        // Check if any required value matches any item value (OR within same facet name)
-        let matches = required_values
-            .iter()
-            .any(|rv| item_values.iter().any(|iv| iv == rv));
+        if !matches_facet_predicates(predicates, &item_values, options) {
+            return false;
+        }
+    }
 
-        if !matches {
+    // Check additional field filters (same semantics as facets)
+    for (field_name, predicates) in &filters.fields {
+        let item_values = item.get_extra_as_vec(field_name);
+        if item_values.is_empty() || !matches_facet_predicates(predicates, &item_values, options) {
             return false;
         }
     }
@@ -65,7 +289,470 @@ pub fn matches_filters(item: &Item, filters: &Filters) -> bool {
     true
 }
 
+/// Within one facet or field, equality values are OR'ed and comparisons are AND'ed,
+/// so "temperature=hot temperature=iced" is either and "price>=10 price<20" is a range
+fn matches_facet_predicates(
+    predicates: &[FacetPredicate],
+    item_values: &[String],
+    options: &FilterOptions,
+) -> bool {
+    let matches_any_value = |predicate: &FacetPredicate| {
+        item_values
+            .iter()
+            .any(|v| predicate.matches_with_options(v, options))
+    };
+
+    let mut equals = predicates
+        .iter()
+        .filter(|p| matches!(p, FacetPredicate::Equals(_)))
+        .peekable();
+    let equals_ok = equals.peek().is_none() || equals.any(matches_any_value);
+
+    let compares_ok = predicates
+        .iter()
+        .filter(|p| !matches!(p, FacetPredicate::Equals(_)))
+        .all(matches_any_value);
+
+    equals_ok && compares_ok
+}
+
+/// Human-readable form of one facet's predicates, mirroring how they combine
+/// e.g. "hot OR iced" or ">=10 AND <20"
+pub fn describe_facet_predicates(predicates: &[FacetPredicate]) -> String {
+    let (equals, compares): (Vec<_>, Vec<_>) = predicates
+        .iter()
+        .partition(|p| matches!(p, FacetPredicate::Equals(_)));
+
+    let join = |predicates: &[&FacetPredicate], sep: &str| {
+        predicates
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>()
+            .join(sep)
+    };
+
+    let mut parts = Vec::new();
+    if !equals.is_empty() {
+        parts.push(join(&equals, " OR "));
+    }
+    if !compares.is_empty() {
+        parts.push(join(&compares, " AND "));
+    }
+    parts.join(" AND ")
+}
+
 /// Check if filters are empty
 pub fn has_filters(filters: &Filters) -> bool {
-    !filters.genera.is_empty() || !filters.facets.is_empty()
+    !filters.genera.is_empty()
+        || !filters.facets.is_empty()
+        || !filters.excluded_genera.is_empty()
+        || !filters.excluded_facets.is_empty()
+        || !filters.fields.is_empty()
+        || filters.name_contains.is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{HierarchyBuilder, ItemBuilder};
+    use crate::models::Provenance;
+
+    fn hierarchy() -> ClassicalHierarchy {
+        HierarchyBuilder::new()
+            .root("Beverage")
+            .child("Hot Beverage", "served heated", |c| {
+                c.synonym("Warm Drink")
+                    .code("HOT")
+                    .leaf("Coffee", "brewed from beans")
+            })
+            .leaf("Cold Beverage", "served chilled")
+            .build()
+    }
+
+    #[test]
+    fn test_genus_matches_literal_path_segment() {
+        // Path lists only the leaf, as in loosely classified data
+        let latte = ItemBuilder::new("Latte").path(["Coffee"]).build();
+        let filters = Filters {
+            genera: vec!["Hot Beverage".to_string()],
+            ..Default::default()
+        };
+
+        assert!(!matches_filters(&latte, &filters));
+        assert!(!matches_filters_in_hierarchy(
+            &latte,
+            &filters,
+            &hierarchy()
+        ));
+    }
+
+    #[test]
+    fn test_include_descendants() {
+        let items = vec![
+            ItemBuilder::new("Latte").path(["Coffee"]).build(),
+            ItemBuilder::new("Lemonade")
+                .path(["Beverage", "Cold Beverage"])
+                .build(),
+        ];
+        let filters = Filters {
+            genera: vec!["Hot Beverage".to_string()],
+            include_descendants: true,
+            ..Default::default()
+        };
+
+        let matched = apply_filters_in_hierarchy(&items, &filters, &hierarchy());
+        assert_eq!(matched.len(), 1);
+        assert_eq!(matched[0].name, "Latte");
+        assert!(matches_filters_in_hierarchy(
+            &items[0],
+            &filters,
+            &hierarchy()
+        ));
+    }
+
+    #[test]
+    fn test_genus_synonym_and_code() {
+        let latte = ItemBuilder::new("Latte")
+            .path(["Beverage", "Hot Beverage", "Coffee"])
+            .build();
+        for genus in ["warm drink", "HOT"] {
+            let filters = Filters {
+                genera: vec![genus.to_string()],
+                ..Default::default()
+            };
+            assert!(matches_filters_in_hierarchy(&latte, &filters, &hierarchy()));
+        }
+    }
+
+    #[test]
+    fn test_exclusions() {
+        let items = vec![
+            ItemBuilder::new("Latte")
+                .path(["Beverage", "Hot Beverage", "Coffee"])
+                .facet_values("flavor", ["vanilla", "caramel"])
+                .build(),
+            ItemBuilder::new("Lemonade")
+                .path(["Beverage", "Cold Beverage"])
+                .facet("flavor", "lemon")
+                .build(),
+        ];
+
+        let filters = Filters {
+            excluded_genera: vec!["Coffee".to_string()],
+            ..Default::default()
+        };
+        let matched = apply_filters(&items, &filters);
+        assert_eq!(matched.len(), 1);
+        assert_eq!(matched[0].name, "Lemonade");
+
+        let filters = Filters {
+            excluded_facets: parse_facet_filters(&["flavor=caramel".to_string()]),
+            ..Default::default()
+        };
+        assert!(!matches_filters(&items[0], &filters));
+        assert!(matches_filters(&items[1], &filters));
+        assert!(has_filters(&filters));
+    }
+
+    #[test]
+    fn test_filter_options() {
+        let items = vec![
+            ItemBuilder::new("Café Latte")
+                .path(["Beverage", "Hot Beverage", "Coffee"])
+                .facet("origin", "Perú")
+                .build(),
+            ItemBuilder::new("Lemonade")
+                .path(["Beverage", "Cold Beverage"])
+                .facet("origin", "Spain")
+                .build(),
+        ];
+        let loose = FilterOptions {
+            case_insensitive: true,
+            strip_diacritics: true,
+        };
+        let filters = |facet: &str, options| Filters {
+            facets: parse_facet_filters(&[facet.to_string()]),
+            options,
+            ..Default::default()
+        };
+
+        // Exact by default
+        assert!(
+            apply_filters(&items, &filters("origin=peru", FilterOptions::default())).is_empty()
+        );
+        let case_only = FilterOptions {
+            case_insensitive: true,
+            ..Default::default()
+        };
+        assert!(apply_filters(&items, &filters("origin=peru", case_only)).is_empty());
+        assert_eq!(
+            apply_filters(&items, &filters("origin=PERÚ", case_only)).len(),
+            1
+        );
+        let matched = apply_filters(&items, &filters("origin=peru", loose));
+        assert_eq!(matched.len(), 1);
+        assert_eq!(matched[0].name, "Café Latte");
+
+        let filters = Filters {
+            excluded_facets: parse_facet_filters(&["origin=peru".to_string()]),
+            options: loose,
+            ..Default::default()
+        };
+        assert_eq!(apply_filters(&items, &filters)[0].name, "Lemonade");
+
+        let filters = Filters {
+            genera: vec!["hot beverage".to_string()],
+            name_contains: Some("cafe".to_string()),
+            options: loose,
+            ..Default::default()
+        };
+        assert!(matches_filters(&items[0], &filters));
+
+        // Genera resolve to the hierarchy's spelling before descendants are found
+        let latte = ItemBuilder::new("Latte").path(["Coffee"]).build();
+        let filters = Filters {
+            genera: vec!["hôt bevérage".to_string()],
+            include_descendants: true,
+            options: loose,
+            ..Default::default()
+        };
+        assert!(matches_filters_in_hierarchy(&latte, &filters, &hierarchy()));
+    }
+
+    #[test]
+    fn test_parse_facet_filter() {
+        assert_eq!(
+            parse_facet_filter("temperature = hot"),
+            Some((
+                "temperature".to_string(),
+                FacetPredicate::Equals("hot".to_string())
+            ))
+        );
+        assert_eq!(
+            parse_facet_filter("price>=10"),
+            Some((
+                "price".to_string(),
+                FacetPredicate::Compare(CompareOp::Ge, 10.0)
+            ))
+        );
+        assert_eq!(
+            parse_facet_filter("abv<5.5"),
+            Some((
+                "abv".to_string(),
+                FacetPredicate::Compare(CompareOp::Lt, 5.5)
+            ))
+        );
+        assert_eq!(
+            parse_facet_filter("released>=2020-01-01"),
+            Some((
+                "released".to_string(),
+                FacetPredicate::CompareDate(CompareOp::Ge, "2020-01-01".to_string())
+            ))
+        );
+        assert!(parse_facet_filter("price>cheap").is_none());
+        assert!(parse_facet_filter("temperature").is_none());
+        assert!(parse_facet_filter("=hot").is_none());
+
+        let (name, predicate) = parse_facet_filter("price<=9.5").unwrap();
+        assert_eq!(predicate.to_filter_string(&name), "price<=9.5");
+    }
+
+    #[test]
+    fn test_numeric_facet_ranges() {
+        // Prices stored both as JSON numbers and as numeric strings
+        let mut espresso = ItemBuilder::new("Espresso").build();
+        espresso
+            .facets
+            .insert("price".to_string(), serde_json::json!(3));
+        let mut cold_brew = ItemBuilder::new("Cold Brew").build();
+        cold_brew
+            .facets
+            .insert("price".to_string(), serde_json::json!(6.25));
+        let items = vec![
+            espresso,
+            ItemBuilder::new("Latte").facet("price", "4.5").build(),
+            cold_brew,
+        ];
+
+        let names = |filters: &[&str]| -> Vec<String> {
+            let strings: Vec<String> = filters.iter().map(|s| s.to_string()).collect();
+            let filters = Filters {
+                facets: parse_facet_filters(&strings),
+                ..Default::default()
+            };
+            apply_filters(&items, &filters)
+                .into_iter()
+                .map(|item| item.name)
+                .collect()
+        };
+
+        assert_eq!(names(&["price>=4"]), vec!["Latte", "Cold Brew"]);
+        assert_eq!(names(&["price>=4", "price<6"]), vec!["Latte"]);
+        assert_eq!(names(&["price=3"]), vec!["Espresso"]);
+        assert_eq!(
+            names(&["price=3", "price=6.25"]),
+            vec!["Espresso", "Cold Brew"]
+        );
+        assert!(names(&["price>10"]).is_empty());
+    }
+
+    #[test]
+    fn test_date_comparisons() {
+        let items = vec![
+            ItemBuilder::new("Old")
+                .facet("released", "1999-12-31")
+                .build(),
+            ItemBuilder::new("New")
+                .facet("released", "2021-06-01")
+                .build(),
+            ItemBuilder::new("Undated")
+                .facet("released", "unknown")
+                .build(),
+        ];
+        let filters = Filters {
+            facets: parse_facet_filters(&["released>=2000-01-01".to_string()]),
+            ..Default::default()
+        };
+
+        let matched = apply_filters(&items, &filters);
+        assert_eq!(matched.len(), 1);
+        assert_eq!(matched[0].name, "New");
+    }
+
+    #[test]
+    fn test_field_and_name_filters() {
+        let items = vec![
+            ItemBuilder::new("Vanilla Latte")
+                .extra("sku", "ABC123")
+                .extra("year", 2019)
+                .build(),
+            ItemBuilder::new("Cola").extra("sku", "XYZ789").build(),
+        ];
+
+        let filters = Filters {
+            fields: parse_facet_filters(&["sku=ABC123".to_string()]),
+            ..Default::default()
+        };
+        assert!(matches_filters(&items[0], &filters));
+        assert!(!matches_filters(&items[1], &filters));
+
+        // An item without the field never matches a field filter
+        let filters = Filters {
+            fields: parse_facet_filters(&["year>=2000".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(apply_filters(&items, &filters).len(), 1);
+
+        // Fields nested in an object are named by their path
+        let mut imported = items[1].clone();
+        imported.set_source(Some(Provenance::now("vendor.csv", "test import")));
+        let filters = Filters {
+            fields: parse_facet_filters(&["source.origin_file=vendor.csv".to_string()]),
+            ..Default::default()
+        };
+        assert!(matches_filters(&imported, &filters));
+        assert!(!matches_filters(&items[1], &filters));
+
+        let filters = Filters {
+            name_contains: Some("LATTE".to_string()),
+            ..Default::default()
+        };
+        assert!(has_filters(&filters));
+        assert_eq!(apply_filters(&items, &filters)[0].name, "Vanilla Latte");
+    }
+
+    #[test]
+    fn test_deprecated_items_hidden() {
+        let mut items = vec![
+            ItemBuilder::new("Latte").build(),
+            ItemBuilder::new("Frappe").build(),
+        ];
+        items[1].set_deprecated(true);
+        assert!(items[1].is_deprecated());
+
+        let names = |filters: &Filters| -> Vec<String> {
+            apply_filters(&items, filters)
+                .into_iter()
+                .map(|item| item.name)
+                .collect()
+        };
+        assert_eq!(names(&Filters::default()), ["Latte"]);
+        let filters = Filters {
+            include_deprecated: true,
+            ..Default::default()
+        };
+        assert!(!has_filters(&filters));
+        assert_eq!(names(&filters), ["Latte", "Frappe"]);
+
+        items[1].set_deprecated(false);
+        assert!(items[1].extra.is_empty());
+    }
+
+    #[test]
+    fn test_facet_value_tree_matches_children() {
+        use crate::builder::TaxonomySchemaBuilder;
+        use crate::models::FacetValueNode;
+
+        let schema = TaxonomySchemaBuilder::new("Wines")
+            .facet_tree(
+                "region",
+                vec![
+                    FacetValueNode::new("Europe").with_children(vec![
+                        FacetValueNode::new("France")
+                            .with_children(vec![FacetValueNode::new("Bordeaux")]),
+                        FacetValueNode::new("Italy"),
+                    ]),
+                    FacetValueNode::new("Americas"),
+                ],
+            )
+            .build();
+        let items = vec![
+            ItemBuilder::new("Margaux")
+                .facet("region", "Bordeaux")
+                .build(),
+            ItemBuilder::new("Chianti").facet("region", "Italy").build(),
+            ItemBuilder::new("Malbec")
+                .facet("region", "Americas")
+                .build(),
+        ];
+
+        let filters = Filters {
+            facets: parse_facet_filters(&["region=Europe".to_string()]),
+            ..Default::default()
+        };
+        assert!(apply_filters(&items, &filters).is_empty());
+        assert_eq!(
+            apply_filters_with_schema(&items, &filters, &schema).len(),
+            2
+        );
+        assert!(filter_indices(&items, &filters).is_empty());
+        assert_eq!(
+            filter_indices_with_schema(&items, &filters, &schema),
+            vec![0, 1]
+        );
+
+        let filters = Filters {
+            excluded_facets: parse_facet_filters(&["region=France".to_string()]),
+            ..Default::default()
+        };
+        assert!(!matches_filters_with_schema(&items[0], &filters, &schema));
+        assert!(matches_filters_with_schema(&items[1], &filters, &schema));
+    }
+
+    #[test]
+    fn test_excluded_genus_with_descendants() {
+        let latte = ItemBuilder::new("Latte").path(["Coffee"]).build();
+        let filters = Filters {
+            excluded_genera: vec!["Hot Beverage".to_string()],
+            include_descendants: true,
+            ..Default::default()
+        };
+
+        assert!(matches_filters(&latte, &filters));
+        assert!(!matches_filters_in_hierarchy(
+            &latte,
+            &filters,
+            &hierarchy()
+        ));
+    }
 }
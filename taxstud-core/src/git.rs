@@ -0,0 +1,407 @@
+use crate::io::INLINE_SCHEMA_KEY;
+use crate::models::{TaxonomyData, TaxonomySchema};
+use crate::schema::build_schema_from_json;
+use git2::{Commit, IndexAddOption, ObjectType, Oid, Repository, RepositoryState, Signature, Sort};
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// A file inside the working tree of a Git repository
+pub struct GitFile {
+    repo: Repository,
+    /// Path of the file relative to the working tree
+    path: PathBuf,
+}
+
+/// A commit that changed a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Revision {
+    /// Full commit ID
+    pub id: String,
+    /// First line of the commit message
+    pub summary: String,
+    pub author: String,
+    /// Commit time, in seconds since the Unix epoch
+    pub time: i64,
+}
+
+/// The versions of a file Git left in conflict after a merge
+/// None for a side that has no such file (e.g. it was added on only one branch)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConflictVersions {
+    /// Common ancestor of both branches
+    pub base: Option<String>,
+    /// The checked-out branch's version
+    pub ours: Option<String>,
+    /// The merged-in branch's version
+    pub theirs: Option<String>,
+}
+
+impl Revision {
+    /// Abbreviated commit ID, as `git log --oneline` shows it
+    pub fn short_id(&self) -> &str {
+        &self.id[..self.id.len().min(7)]
+    }
+}
+
+impl fmt::Debug for GitFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GitFile")
+            .field("workdir", &self.workdir())
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+impl GitFile {
+    /// The repository whose working tree holds `path`, or None when it isn't in one
+    pub fn discover<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let path = path.as_ref().canonicalize().ok()?;
+        let repo = Repository::discover(path.parent()?).ok()?;
+        let workdir = repo.workdir()?.canonicalize().ok()?;
+        let path = path.strip_prefix(&workdir).ok()?.to_path_buf();
+        Some(GitFile { repo, path })
+    }
+
+    /// Root of the repository's working tree
+    pub fn workdir(&self) -> &Path {
+        self.repo.workdir().unwrap_or_else(|| self.repo.path())
+    }
+
+    /// Path of the file relative to the working tree
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Name of the checked-out branch; None when HEAD is detached
+    pub fn branch(&self) -> Option<String> {
+        let head = self.repo.head().ok()?;
+        if !head.is_branch() {
+            return None;
+        }
+        head.shorthand().map(str::to_string)
+    }
+
+    /// Whether the working copy differs from the committed file (or isn't committed yet)
+    pub fn is_modified(&self) -> Result<bool, Box<dyn Error>> {
+        let status = self.repo.status_file(&self.path)?;
+        Ok(!status.is_empty() && !status.is_ignored())
+    }
+
+    /// The commits that changed the file, newest first, at most `limit` of them
+    pub fn history(&self, limit: usize) -> Result<Vec<Revision>, Box<dyn Error>> {
+        let mut walk = self.repo.revwalk()?;
+        if walk.push_head().is_err() {
+            // No commits yet
+            return Ok(Vec::new());
+        }
+        walk.set_sorting(Sort::TIME)?;
+
+        let mut revisions = Vec::new();
+        for id in walk {
+            let commit = self.repo.find_commit(id?)?;
+            let blob = self.blob_in(&commit);
+            let changed = match commit.parent(0) {
+                Ok(parent) => blob != self.blob_in(&parent),
+                Err(_) => blob.is_some(),
+            };
+            if changed {
+                revisions.push(Revision {
+                    id: commit.id().to_string(),
+                    summary: commit.summary().unwrap_or_default().to_string(),
+                    author: commit.author().name().unwrap_or_default().to_string(),
+                    time: commit.time().seconds(),
+                });
+                if revisions.len() == limit {
+                    break;
+                }
+            }
+        }
+        Ok(revisions)
+    }
+
+    /// The file's contents at a revision ("HEAD", a branch or a commit ID)
+    /// None when the file didn't exist there, or the repository has no commits yet
+    pub fn contents_at(&self, revision: &str) -> Result<Option<String>, Box<dyn Error>> {
+        let Ok(object) = self.repo.revparse_single(revision) else {
+            return Ok(None);
+        };
+        let commit = object.peel_to_commit()?;
+        let Some(id) = self.blob_in(&commit) else {
+            return Ok(None);
+        };
+        let blob = self.repo.find_blob(id)?;
+        Ok(Some(String::from_utf8(blob.content().to_vec())?))
+    }
+
+    /// The file read as taxonomy data at a revision
+    /// Not validated against a schema: older versions may predate the current one
+    pub fn data_at(&self, revision: &str) -> Result<Option<TaxonomyData>, Box<dyn Error>> {
+        let Some(contents) = self.contents_at(revision)? else {
+            return Ok(None);
+        };
+        let mut value: serde_json::Value = serde_json::from_str(&contents)?;
+        if let Some(object) = value.as_object_mut() {
+            object.remove(INLINE_SCHEMA_KEY);
+        }
+        Ok(Some(serde_json::from_value(value)?))
+    }
+
+    /// The file read as a schema at a revision (or the schema it embeds, for a data file)
+    /// `$ref`s to other files are not followed
+    pub fn schema_at(&self, revision: &str) -> Result<Option<TaxonomySchema>, Box<dyn Error>> {
+        let Some(contents) = self.contents_at(revision)? else {
+            return Ok(None);
+        };
+        let mut value: serde_json::Value = serde_json::from_str(&contents)?;
+        if let Some(inline) = value
+            .as_object_mut()
+            .and_then(|object| object.remove(INLINE_SCHEMA_KEY))
+        {
+            value = inline;
+        }
+        Ok(Some(build_schema_from_json(value)?))
+    }
+
+    /// The versions of the file in conflict after a merge; None when it isn't in conflict
+    pub fn conflict_versions(&self) -> Result<Option<ConflictVersions>, Box<dyn Error>> {
+        let mut index = self.repo.index()?;
+        // Pick up changes made by other tools (e.g. `git merge`) since the repository was opened
+        index.read(false)?;
+        if !index.has_conflicts() {
+            return Ok(None);
+        }
+        let path = self.path.to_string_lossy().replace('\\', "/");
+        for conflict in index.conflicts()? {
+            let conflict = conflict?;
+            let entries = [&conflict.ancestor, &conflict.our, &conflict.their];
+            let conflicted = entries.iter().any(|entry| {
+                entry
+                    .as_ref()
+                    .is_some_and(|entry| entry.path == path.as_bytes())
+            });
+            if !conflicted {
+                continue;
+            }
+
+            let read =
+                |entry: &Option<git2::IndexEntry>| -> Result<Option<String>, Box<dyn Error>> {
+                    match entry {
+                        Some(entry) => {
+                            let blob = self.repo.find_blob(entry.id)?;
+                            Ok(Some(String::from_utf8(blob.content().to_vec())?))
+                        }
+                        None => Ok(None),
+                    }
+                };
+            return Ok(Some(ConflictVersions {
+                base: read(&conflict.ancestor)?,
+                ours: read(&conflict.our)?,
+                theirs: read(&conflict.their)?,
+            }));
+        }
+        Ok(None)
+    }
+
+    /// Stage the file as it is in the working tree, which marks a conflict in it resolved
+    pub fn stage(&self) -> Result<(), Box<dyn Error>> {
+        let mut index = self.repo.index()?;
+        index.read(false)?;
+        index.add_path(&self.path)?;
+        index.write()?;
+        Ok(())
+    }
+
+    /// Stage the file, and `others` in the same working tree (e.g. its schema file), and
+    /// commit them on the current branch, concluding a merge in progress
+    /// Returns the new commit's ID
+    pub fn commit(&self, message: &str, others: &[&Path]) -> Result<String, Box<dyn Error>> {
+        self.commit_as(message, others, None)
+    }
+
+    /// Commit like `commit`, under `author` (with the repository's user.email) rather than
+    /// the repository's user.name when given
+    pub fn commit_as(
+        &self,
+        message: &str,
+        others: &[&Path],
+        author: Option<&str>,
+    ) -> Result<String, Box<dyn Error>> {
+        let workdir = self.workdir().canonicalize()?;
+        let mut paths = vec![self.path.clone()];
+        for other in others {
+            let other = other.canonicalize()?;
+            let relative = other
+                .strip_prefix(&workdir)
+                .map_err(|_| format!("{} is outside the repository", other.display()))?;
+            paths.push(relative.to_path_buf());
+        }
+
+        let mut index = self.repo.index()?;
+        index.read(false)?;
+        index.add_all(&paths, IndexAddOption::DEFAULT, None)?;
+        index.write()?;
+        let tree = self.repo.find_tree(index.write_tree()?)?;
+
+        let signature = match author {
+            Some(name) => {
+                let email = self
+                    .repo
+                    .config()?
+                    .get_string("user.email")
+                    .unwrap_or_default();
+                Signature::now(name, &email)?
+            }
+            None => self.repo.signature()?,
+        };
+        let parent = match self.repo.head() {
+            Ok(head) => Some(head.peel_to_commit()?),
+            Err(_) => None,
+        };
+        // Concluding a merge: the merged-in commit is a parent too
+        let merged = match self.repo.state() {
+            RepositoryState::Merge => {
+                Some(self.repo.find_reference("MERGE_HEAD")?.peel_to_commit()?)
+            }
+            _ => None,
+        };
+        let parents: Vec<&Commit> = parent.iter().chain(&merged).collect();
+        let id = self.repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )?;
+        if merged.is_some() {
+            self.repo.cleanup_state()?;
+        }
+        Ok(id.to_string())
+    }
+
+    /// ID of the file's blob in a commit's tree
+    fn blob_in(&self, commit: &Commit) -> Option<Oid> {
+        let entry = commit.tree().ok()?.get_path(&self.path).ok()?;
+        (entry.kind() == Some(ObjectType::Blob)).then(|| entry.id())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::diff_items;
+    use crate::io::parse_data_with_auto_schema;
+    use crate::templates::find_template;
+    use std::fs;
+
+    #[test]
+    fn test_git_file() {
+        let dir = std::env::temp_dir().join(format!("taxstud-git-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let repo = Repository::init(&dir).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Curator").unwrap();
+        config.set_str("user.email", "curator@example.com").unwrap();
+
+        let template = find_template("beverages").unwrap();
+        let schema_path = dir.join("schema.json");
+        let data_path = dir.join("data.json");
+        fs::write(&schema_path, template.schema_json().to_string()).unwrap();
+        let mut data = template.empty_data("schema.json");
+        fs::write(&data_path, serde_json::to_string(&data).unwrap()).unwrap();
+
+        let file = GitFile::discover(&data_path).unwrap();
+        assert_eq!(file.path(), Path::new("data.json"));
+        assert!(file.is_modified().unwrap());
+        assert!(file.history(10).unwrap().is_empty());
+        assert!(file.data_at("HEAD").unwrap().is_none());
+
+        let first = file.commit("Add beverages", &[&schema_path]).unwrap();
+        assert!(!file.is_modified().unwrap());
+        assert!(file.branch().is_some());
+        assert!(file.schema_at("HEAD").is_err());
+        let schema_file = GitFile::discover(&schema_path).unwrap();
+        assert_eq!(
+            schema_file.schema_at("HEAD").unwrap().unwrap().title,
+            template.schema().title
+        );
+
+        // A commit that doesn't touch the file stays out of its history
+        fs::write(dir.join("notes.txt"), "unrelated").unwrap();
+        GitFile::discover(dir.join("notes.txt"))
+            .unwrap()
+            .commit("Add notes", &[])
+            .unwrap();
+
+        let hierarchy = &template.schema().classical_hierarchy;
+        data.items.push(
+            crate::builder::ItemBuilder::new("Latte")
+                .path(hierarchy.path_to("Coffee").unwrap())
+                .build(),
+        );
+        fs::write(&data_path, serde_json::to_string(&data).unwrap()).unwrap();
+        file.commit_as("Add Latte", &[], Some("Archivist")).unwrap();
+
+        let history = file.history(10).unwrap();
+        let summaries: Vec<&str> = history.iter().map(|r| r.summary.as_str()).collect();
+        assert_eq!(summaries, vec!["Add Latte", "Add beverages"]);
+        assert_eq!(history[1].id, first);
+        assert_eq!(history[1].author, "Curator");
+        assert_eq!(history[0].author, "Archivist");
+
+        let old = file.data_at(&history[1].id).unwrap().unwrap();
+        let changes: Vec<String> = diff_items(&old.items, &data.items)
+            .iter()
+            .map(|change| change.to_string())
+            .collect();
+        assert_eq!(changes, vec!["+ item 'Latte'"]);
+        assert!(file.commit("Outside", &[Path::new("/")]).is_err());
+
+        // Different edits on two branches leave the file in conflict after merging
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("theirs", &head, false).unwrap();
+        let mut edit = |temperature: &str| {
+            data.items[0]
+                .facets
+                .insert("temperature".to_string(), temperature.into());
+            fs::write(&data_path, serde_json::to_string_pretty(&data).unwrap()).unwrap();
+        };
+        let checkout = |branch: &str| {
+            repo.set_head(&format!("refs/heads/{}", branch)).unwrap();
+            repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+                .unwrap();
+        };
+        edit("hot");
+        file.commit("Hot latte", &[]).unwrap();
+        checkout("theirs");
+        edit("iced");
+        let theirs = file.commit("Iced latte", &[]).unwrap();
+        checkout(&branch);
+        assert!(file.conflict_versions().unwrap().is_none());
+
+        let theirs = repo
+            .find_annotated_commit(Oid::from_str(&theirs).unwrap())
+            .unwrap();
+        repo.merge(&[&theirs], None, None).unwrap();
+        let versions = file.conflict_versions().unwrap().unwrap();
+        let (ours, _, schema_file) =
+            parse_data_with_auto_schema(&versions.ours.unwrap(), &data_path).unwrap();
+        assert_eq!(ours.items[0].facets["temperature"], "hot");
+        assert_eq!(schema_file, Some(dir.join("schema.json")));
+        assert!(versions.theirs.unwrap().contains("iced"));
+        assert!(versions.base.is_some());
+
+        edit("warm");
+        file.stage().unwrap();
+        assert!(file.conflict_versions().unwrap().is_none());
+        file.commit("Merge theirs", &[]).unwrap();
+        let merge = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(merge.parent_count(), 2);
+        assert_eq!(repo.state(), RepositoryState::Clean);
+
+        assert!(GitFile::discover(std::env::temp_dir().join("no-such-file.json")).is_none());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
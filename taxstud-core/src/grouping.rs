@@ -1,35 +1,350 @@
 use crate::models::Item;
 use std::collections::HashMap;
+use std::fmt;
+
+/// Name of the group that collects items without a value, unless configured otherwise
+pub const UNSPECIFIED_GROUP: &str = "Unspecified";
+
+/// What happens to items that have no value for the grouping facet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MissingGroup {
+    /// Leave them out of the groups
+    Skip,
+    /// Collect them in a group with this name
+    Bucket(String),
+    /// Refuse to group
+    Error,
+}
+
+impl Default for MissingGroup {
+    fn default() -> Self {
+        MissingGroup::Bucket(UNSPECIFIED_GROUP.to_string())
+    }
+}
+
+/// The group an item falls in: one of the facet's values, or the bucket of items without one
+/// The bucket stays apart from a value spelled like its label; it sorts after the values
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum GroupKey {
+    /// Items with this value of the facet
+    Value(String),
+    /// Items without a value, under the `MissingGroup::Bucket` label
+    Missing(String),
+}
+
+impl GroupKey {
+    /// The value, or the label of the missing-value bucket
+    pub fn name(&self) -> &str {
+        match self {
+            GroupKey::Value(name) | GroupKey::Missing(name) => name,
+        }
+    }
+
+    /// Whether this is the bucket of items without a value
+    pub fn is_missing(&self) -> bool {
+        matches!(self, GroupKey::Missing(_))
+    }
+}
+
+impl fmt::Display for GroupKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Options for `group_items_with_options`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GroupOptions {
+    pub missing: MissingGroup,
+}
 
 /// Group items by a facet name
 /// Items with multiple values for the facet appear in multiple groups
-/// Returns a HashMap where keys are group names and values are vectors of items
-pub fn group_items_by_facet(items: &[Item], group_field: &str) -> HashMap<String, Vec<Item>> {
-    let mut groups: HashMap<String, Vec<Item>> = HashMap::new();
+/// Returns a HashMap where keys are groups and values are vectors of items;
+/// items without the facet go to the "Unspecified" bucket
+pub fn group_items_by_facet(items: &[Item], group_field: &str) -> HashMap<GroupKey, Vec<Item>> {
+    group_items_with_options(items, group_field, &GroupOptions::default())
+        .expect("bucketing missing values never fails")
+}
+
+/// Group items by a facet name, handling items without a value as `options` says
+/// With `MissingGroup::Error`, the error names every item missing the facet
+pub fn group_items_with_options(
+    items: &[Item],
+    group_field: &str,
+    options: &GroupOptions,
+) -> Result<HashMap<GroupKey, Vec<Item>>, String> {
+    let groups = group_with_options(items.iter().map(|item| (item, item)), group_field, options)?;
+    Ok(groups
+        .into_iter()
+        .map(|(name, items)| (name, items.into_iter().cloned().collect()))
+        .collect())
+}
 
-    for item in items {
+/// Group indices into `items` like group_items_by_facet, without cloning the items
+pub fn group_indices_by_facet(
+    items: &[Item],
+    indices: &[usize],
+    group_field: &str,
+) -> HashMap<GroupKey, Vec<usize>> {
+    group_indices_with_options(items, indices, group_field, &GroupOptions::default())
+        .expect("bucketing missing values never fails")
+}
+
+/// Group indices into `items` like group_items_with_options, without cloning the items
+pub fn group_indices_with_options(
+    items: &[Item],
+    indices: &[usize],
+    group_field: &str,
+    options: &GroupOptions,
+) -> Result<HashMap<GroupKey, Vec<usize>>, String> {
+    group_with_options(
+        indices.iter().map(|&index| (index, &items[index])),
+        group_field,
+        options,
+    )
+}
+
+/// Group the entries by their items' values for the facet
+fn group_with_options<'a, T: Copy>(
+    entries: impl IntoIterator<Item = (T, &'a Item)>,
+    group_field: &str,
+    options: &GroupOptions,
+) -> Result<HashMap<GroupKey, Vec<T>>, String> {
+    let mut groups: HashMap<GroupKey, Vec<T>> = HashMap::new();
+    let mut missing = Vec::new();
+
+    for (entry, item) in entries {
         let facet_values = item.get_facet_as_vec(group_field);
 
         if facet_values.is_empty() {
-            // Items without this facet go to "unspecified" group
-            groups
-                .entry("_unspecified_".to_string())
-                .or_default()
-                .push(item.clone());
+            match &options.missing {
+                MissingGroup::Skip => {}
+                MissingGroup::Bucket(name) => groups
+                    .entry(GroupKey::Missing(name.clone()))
+                    .or_default()
+                    .push(entry),
+                MissingGroup::Error => missing.push(item.name.as_str()),
+            }
         } else {
             // Items with multiple values appear in multiple groups
             for value in facet_values {
-                groups.entry(value).or_default().push(item.clone());
+                groups
+                    .entry(GroupKey::Value(value))
+                    .or_default()
+                    .push(entry);
             }
         }
     }
 
+    if !missing.is_empty() {
+        return Err(format!(
+            "{} item(s) have no value for '{}': {}",
+            missing.len(),
+            group_field,
+            missing.join(", ")
+        ));
+    }
+
+    Ok(groups)
+}
+
+/// Statistics of one group, for headers in grouped output
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GroupSummary {
+    /// Number of items in the group
+    pub count: usize,
+    /// Distinct values of the facet asked for, sorted (empty when none was asked for)
+    pub distinct_values: Vec<String>,
+    /// Aggregates of the numeric facet asked for, if any item has a number for it
+    pub numeric: Option<NumericSummary>,
+}
+
+/// Min, max and mean of a facet's numeric values
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumericSummary {
+    /// Number of values aggregated (items may give several, or none)
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub avg: f64,
+}
+
+impl GroupSummary {
+    /// Summarize a group, listing the values of `distinct_facet` and aggregating `numeric_facet`
+    /// Values of the numeric facet that don't parse as numbers are ignored
+    /// `items` is anything that lists them again, e.g. `&Vec<Item>` or indices mapped to items
+    pub fn of<'a, I>(items: I, distinct_facet: Option<&str>, numeric_facet: Option<&str>) -> Self
+    where
+        I: IntoIterator<Item = &'a Item> + Clone,
+    {
+        let mut distinct_values: Vec<String> = distinct_facet
+            .map(|facet| {
+                items
+                    .clone()
+                    .into_iter()
+                    .flat_map(|item| item.get_facet_as_vec(facet))
+                    .collect()
+            })
+            .unwrap_or_default();
+        distinct_values.sort();
+        distinct_values.dedup();
+
+        let numbers: Vec<f64> = numeric_facet
+            .map(|facet| {
+                items
+                    .clone()
+                    .into_iter()
+                    .flat_map(|item| item.get_facet_as_vec(facet))
+                    .filter_map(|value| value.trim().parse::<f64>().ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let numeric = (!numbers.is_empty()).then(|| NumericSummary {
+            count: numbers.len(),
+            min: numbers.iter().copied().fold(f64::INFINITY, f64::min),
+            max: numbers.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            avg: numbers.iter().sum::<f64>() / numbers.len() as f64,
+        });
+
+        GroupSummary {
+            count: items.into_iter().count(),
+            distinct_values,
+            numeric,
+        }
+    }
+}
+
+/// Summarize every group of a grouped items map
+pub fn summarize_groups(
+    groups: &HashMap<GroupKey, Vec<Item>>,
+    distinct_facet: Option<&str>,
+    numeric_facet: Option<&str>,
+) -> HashMap<GroupKey, GroupSummary> {
     groups
+        .iter()
+        .map(|(name, items)| {
+            let summary = GroupSummary::of(items, distinct_facet, numeric_facet);
+            (name.clone(), summary)
+        })
+        .collect()
 }
 
-/// Get sorted group names from a grouped items map
-pub fn get_sorted_group_names(groups: &HashMap<String, Vec<Item>>) -> Vec<String> {
-    let mut group_names: Vec<String> = groups.keys().cloned().collect();
+/// Get the groups of a grouped items map sorted by name, the missing-value bucket last
+pub fn get_sorted_group_names<V>(groups: &HashMap<GroupKey, V>) -> Vec<GroupKey> {
+    let mut group_names: Vec<GroupKey> = groups.keys().cloned().collect();
     group_names.sort();
     group_names
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::ItemBuilder;
+
+    #[test]
+    fn test_missing_group_options() {
+        let items = vec![
+            ItemBuilder::new("Latte")
+                .facet("temperature", "hot")
+                .build(),
+            ItemBuilder::new("Water").build(),
+        ];
+
+        let hot = GroupKey::Value("hot".to_string());
+        let unspecified = GroupKey::Missing(UNSPECIFIED_GROUP.to_string());
+        let groups = group_items_by_facet(&items, "temperature");
+        assert_eq!(
+            get_sorted_group_names(&groups),
+            vec![hot.clone(), unspecified.clone()]
+        );
+        let indices = group_indices_by_facet(&items, &[1, 0], "temperature");
+        assert_eq!(indices[&unspecified], vec![1]);
+        assert_eq!(indices[&hot], vec![0]);
+
+        let skip = GroupOptions {
+            missing: MissingGroup::Skip,
+        };
+        let groups = group_items_with_options(&items, "temperature", &skip).unwrap();
+        assert_eq!(get_sorted_group_names(&groups), vec![hot.clone()]);
+        let indices = group_indices_with_options(&items, &[0, 1], "temperature", &skip).unwrap();
+        assert_eq!(indices.len(), 1);
+
+        let bucket = GroupOptions {
+            missing: MissingGroup::Bucket("(none)".to_string()),
+        };
+        let groups = group_items_with_options(&items, "temperature", &bucket).unwrap();
+        assert_eq!(
+            groups[&GroupKey::Missing("(none)".to_string())][0].name,
+            "Water"
+        );
+
+        let error = GroupOptions {
+            missing: MissingGroup::Error,
+        };
+        assert_eq!(
+            group_items_with_options(&items, "temperature", &error).unwrap_err(),
+            "1 item(s) have no value for 'temperature': Water"
+        );
+    }
+
+    #[test]
+    fn test_missing_bucket_apart_from_same_named_value() {
+        let items = vec![
+            ItemBuilder::new("Mystery")
+                .facet("temperature", UNSPECIFIED_GROUP)
+                .build(),
+            ItemBuilder::new("Water").build(),
+        ];
+
+        let groups = group_items_by_facet(&items, "temperature");
+        assert_eq!(groups.len(), 2);
+        let names = get_sorted_group_names(&groups);
+        assert_eq!(names[0], GroupKey::Value(UNSPECIFIED_GROUP.to_string()));
+        assert!(names[1].is_missing());
+        assert_eq!(names[0].name(), names[1].name());
+        assert_eq!(groups[&names[1]][0].name, "Water");
+    }
+
+    #[test]
+    fn test_group_summary() {
+        let items = vec![
+            ItemBuilder::new("Latte")
+                .facet_values("flavor", ["vanilla", "caramel"])
+                .facet("price", "4.5")
+                .build(),
+            ItemBuilder::new("Mocha")
+                .facet("flavor", "chocolate")
+                .facet("price", "3.5")
+                .build(),
+            ItemBuilder::new("Americano")
+                .facet("flavor", "vanilla")
+                .build(),
+        ];
+
+        let summary = GroupSummary::of(&items, Some("flavor"), Some("price"));
+        assert_eq!(summary.count, 3);
+        assert_eq!(
+            summary.distinct_values,
+            vec!["caramel", "chocolate", "vanilla"]
+        );
+        assert_eq!(
+            summary.numeric,
+            Some(NumericSummary {
+                count: 2,
+                min: 3.5,
+                max: 4.5,
+                avg: 4.0
+            })
+        );
+
+        let summary = GroupSummary::of(&items, None, Some("flavor"));
+        assert!(summary.distinct_values.is_empty());
+        assert_eq!(summary.numeric, None);
+
+        // Indices into the items summarize the same as the items
+        let of_indices = GroupSummary::of([1, 0].iter().map(|&i| &items[i]), None, Some("price"));
+        assert_eq!(of_indices.count, 2);
+        assert_eq!(of_indices.numeric.unwrap().avg, 4.0);
+    }
+}
@@ -0,0 +1,620 @@
+use crate::models::{ClassicalHierarchy, FacetValueNode, HierarchyNode, Item, TaxonomySchema};
+use std::collections::{BTreeMap, HashSet};
+
+/// Pre-order walk over a classical hierarchy
+/// Yields each node with its depth; the root's direct children are at depth 1
+pub struct Preorder<'a> {
+    stack: Vec<(usize, &'a HierarchyNode)>,
+}
+
+impl<'a> Iterator for Preorder<'a> {
+    type Item = (usize, &'a HierarchyNode);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (depth, node) = self.stack.pop()?;
+        self.stack
+            .extend(node_children(node).iter().rev().map(|c| (depth + 1, c)));
+        Some((depth, node))
+    }
+}
+
+impl ClassicalHierarchy {
+    /// Walk every node below the root in pre-order (parents before children, siblings in order)
+    pub fn iter_preorder(&self) -> Preorder<'_> {
+        Preorder {
+            stack: self.root_children().iter().rev().map(|c| (1, c)).collect(),
+        }
+    }
+
+    /// Find the node for a species
+    /// The root is not a node, so `find_node(root)` is None
+    pub fn find_node(&self, species: &str) -> Option<&HierarchyNode> {
+        self.iter_preorder()
+            .map(|(_, node)| node)
+            .find(|node| node.species == species)
+    }
+
+    /// Canonical species for a name, synonym or code (names and synonyms ignore case)
+    /// Returns None if nothing in the hierarchy is known by that name
+    pub fn resolve_species(&self, name: &str) -> Option<&str> {
+        if name == self.root {
+            return Some(&self.root);
+        }
+        self.find_node(name)
+            .or_else(|| {
+                self.iter_preorder()
+                    .map(|(_, node)| node)
+                    .find(|node| node.is_known_as(name))
+            })
+            .map(|node| node.species.as_str())
+    }
+
+    /// Nodes whose species, synonyms, code or external IDs contain the query (ignoring case)
+    /// Results are in pre-order
+    pub fn search(&self, query: &str) -> Vec<&HierarchyNode> {
+        let query = query.to_lowercase();
+        self.iter_preorder()
+            .map(|(_, node)| node)
+            .filter(|node| {
+                node.names()
+                    .chain(node.external_ids.values().map(String::as_str))
+                    .any(|name| name.to_lowercase().contains(&query))
+            })
+            .collect()
+    }
+
+    /// Whether the name is the root or a species in the hierarchy
+    pub fn contains(&self, name: &str) -> bool {
+        name == self.root || self.find_node(name).is_some()
+    }
+
+    /// Direct children of the root or of a species (empty if it has none or doesn't exist)
+    pub fn children_of(&self, name: &str) -> &[HierarchyNode] {
+        if name == self.root {
+            self.root_children()
+        } else {
+            self.find_node(name).map(node_children).unwrap_or_default()
+        }
+    }
+
+    /// Path from the root down to and including `name`
+    /// Returns None if the name isn't in the hierarchy
+    pub fn path_to(&self, name: &str) -> Option<Vec<String>> {
+        if name == self.root {
+            return Some(vec![self.root.clone()]);
+        }
+
+        let mut path = vec![self.root.clone()];
+        find_path(self.root_children(), name, &mut path).then_some(path)
+    }
+
+    /// Ancestors of `name`, nearest first (parent, grandparent, ..., root)
+    /// Empty for the root or an unknown name
+    pub fn ancestors_of(&self, name: &str) -> Vec<String> {
+        let mut path = self.path_to(name).unwrap_or_default();
+        path.pop();
+        path.reverse();
+        path
+    }
+
+    /// Every node below `name` in pre-order; for the root, the whole hierarchy
+    pub fn descendants_of(&self, name: &str) -> Vec<&HierarchyNode> {
+        let mut descendants = Vec::new();
+        collect_descendants(self.children_of(name), &mut descendants);
+        descendants
+    }
+
+    /// Number of levels below the root (0 when the root has no children)
+    pub fn depth(&self) -> usize {
+        self.iter_preorder()
+            .map(|(depth, _)| depth)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn root_children(&self) -> &[HierarchyNode] {
+        self.children.as_deref().unwrap_or_default()
+    }
+}
+
+impl HierarchyNode {
+    /// The species, its synonyms and its code
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.species.as_str())
+            .chain(self.synonyms.iter().map(String::as_str))
+            .chain(self.code.as_deref())
+    }
+
+    /// Whether the node goes by this name, synonym (ignoring case) or code
+    pub fn is_known_as(&self, name: &str) -> bool {
+        self.code.as_deref() == Some(name)
+            || std::iter::once(&self.species)
+                .chain(&self.synonyms)
+                .any(|known| known.to_lowercase() == name.to_lowercase())
+    }
+}
+
+/// Valid next segments for a partial classical path
+/// An empty prefix completes to the root; a prefix that isn't a valid path has no completions
+pub fn complete_path(hierarchy: &ClassicalHierarchy, prefix: &[String]) -> Vec<String> {
+    let Some((first, rest)) = prefix.split_first() else {
+        return vec![hierarchy.root.clone()];
+    };
+    if *first != hierarchy.root {
+        return Vec::new();
+    }
+
+    let mut children = hierarchy.root_children();
+    for segment in rest {
+        match children.iter().find(|node| node.species == *segment) {
+            Some(node) => children = node_children(node),
+            None => return Vec::new(),
+        }
+    }
+
+    children.iter().map(|node| node.species.clone()).collect()
+}
+
+/// Rename the root or a species of the hierarchy, rewriting the old name wherever it
+/// appears in the items' classical paths
+/// Returns how many items were touched; fails when `old` isn't in the hierarchy or `new`
+/// already is
+pub fn rename_node_cascade(
+    hierarchy: &mut ClassicalHierarchy,
+    items: &mut [Item],
+    old: &str,
+    new: &str,
+) -> Result<usize, String> {
+    if !hierarchy.contains(old) {
+        return Err(format!("'{}' is not in the hierarchy", old));
+    }
+    if new.trim().is_empty() {
+        return Err("The new name is empty".to_string());
+    }
+    if old == new {
+        return Ok(0);
+    }
+    if hierarchy.contains(new) {
+        return Err(format!("'{}' is already in the hierarchy", new));
+    }
+
+    if hierarchy.root == old {
+        hierarchy.root = new.to_string();
+    }
+    let renamed = BTreeMap::from([(old.to_string(), new.to_string())]);
+    let root = hierarchy.root.clone();
+    if let Some(ref mut children) = hierarchy.children {
+        rename_species(children, &root, &renamed);
+    }
+
+    let mut touched = 0;
+    for item in items {
+        let mut uses_old = false;
+        for segment in item.classical_path.iter_mut().filter(|s| *s == old) {
+            *segment = new.to_string();
+            uses_old = true;
+        }
+        touched += usize::from(uses_old);
+    }
+    Ok(touched)
+}
+
+impl TaxonomySchema {
+    /// Values nested beneath `value` in a facet's value tree, in pre-order
+    /// Empty for flat dimensions, leaf values, and unknown values
+    pub fn facet_value_descendants(&self, facet_name: &str, value: &str) -> Vec<String> {
+        self.facet_value_trees
+            .get(facet_name)
+            .and_then(|nodes| find_facet_value(nodes, value))
+            .map(|node| flatten_facet_values(&node.children))
+            .unwrap_or_default()
+    }
+}
+
+/// Every value in a facet value tree, parents before their children
+pub fn flatten_facet_values(nodes: &[FacetValueNode]) -> Vec<String> {
+    let mut values = Vec::new();
+    for node in nodes {
+        values.push(node.value.clone());
+        values.extend(flatten_facet_values(&node.children));
+    }
+    values
+}
+
+fn find_facet_value<'a>(nodes: &'a [FacetValueNode], value: &str) -> Option<&'a FacetValueNode> {
+    nodes.iter().find_map(|node| {
+        if node.value == value {
+            Some(node)
+        } else {
+            find_facet_value(&node.children, value)
+        }
+    })
+}
+
+/// How `merge` combines two classical hierarchies
+#[derive(Debug, Clone, Default)]
+pub struct MergeOptions {
+    /// Name of the common root; defaults to the first hierarchy's root
+    pub root: Option<String>,
+    /// Rename species of the second hierarchy that clash with the first as "prefix:species"
+    /// instead of failing
+    pub prefix_conflicts: bool,
+    /// Prefix for renamed species; defaults to the second hierarchy's root
+    pub prefix: Option<String>,
+}
+
+/// Two hierarchies combined by `merge`
+#[derive(Debug, Clone)]
+pub struct HierarchyMerge {
+    pub hierarchy: ClassicalHierarchy,
+    /// Species of the second hierarchy renamed to avoid a clash, old name → new name
+    pub renamed: BTreeMap<String, String>,
+}
+
+impl HierarchyMerge {
+    /// Where an item classified under `path` in one of the merged hierarchies now belongs
+    /// Returns None for an empty path or one whose last species is unknown
+    pub fn map_path(&self, path: &[String], from_second: bool) -> Option<Vec<String>> {
+        let last = path.last()?;
+        let species = match self.renamed.get(last) {
+            Some(renamed) if from_second => renamed,
+            _ => last,
+        };
+        self.hierarchy.path_to(species)
+    }
+}
+
+/// Combine two classical hierarchies under a common root
+/// A hierarchy whose root isn't the common root becomes a node beneath it; otherwise its
+/// top-level species go directly under it, the first hierarchy's before the second's.
+/// Species names must stay unique: clashes are listed as errors, or with
+/// `prefix_conflicts` the second hierarchy's clashing species are renamed.
+pub fn merge(
+    first: &ClassicalHierarchy,
+    second: &ClassicalHierarchy,
+    options: &MergeOptions,
+) -> Result<HierarchyMerge, Vec<String>> {
+    let root = options.root.clone().unwrap_or_else(|| first.root.clone());
+    let prefix = options.prefix.as_deref().unwrap_or(&second.root);
+
+    let mut taken: HashSet<String> = HashSet::from([root.clone()]);
+    let mut children = lift_under(first, &root);
+    taken.extend(children.iter().flat_map(subtree_species));
+
+    let mut renamed = BTreeMap::new();
+    let mut errors = Vec::new();
+    let mut second_children = lift_under(second, &root);
+    for species in second_children.iter().flat_map(subtree_species) {
+        if !taken.contains(&species) {
+            taken.insert(species);
+            continue;
+        }
+        let new_name = format!("{}:{}", prefix, species);
+        if !options.prefix_conflicts {
+            errors.push(format!("Species '{}' is in both hierarchies", species));
+        } else if taken.contains(&new_name) {
+            errors.push(format!(
+                "Species '{}' clashes even when renamed to '{}'",
+                species, new_name
+            ));
+        } else {
+            taken.insert(new_name.clone());
+            renamed.insert(species, new_name);
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    rename_species(&mut second_children, &root, &renamed);
+    children.extend(second_children);
+
+    let labels = if root == first.root {
+        first.labels.clone()
+    } else {
+        Default::default()
+    };
+    Ok(HierarchyMerge {
+        hierarchy: ClassicalHierarchy {
+            root,
+            children: (!children.is_empty()).then_some(children),
+            labels,
+        },
+        renamed,
+    })
+}
+
+/// The top-level nodes a hierarchy contributes under `root`: its own top-level species,
+/// or its root as a node when that isn't `root`
+fn lift_under(hierarchy: &ClassicalHierarchy, root: &str) -> Vec<HierarchyNode> {
+    if hierarchy.root == root {
+        return hierarchy.root_children().to_vec();
+    }
+    vec![HierarchyNode {
+        genus: root.to_string(),
+        species: hierarchy.root.clone(),
+        differentia: format!("merged from the '{}' hierarchy", hierarchy.root),
+        children: hierarchy.children.clone(),
+        synonyms: Vec::new(),
+        code: None,
+        external_ids: Default::default(),
+        labels: hierarchy.labels.clone(),
+    }]
+}
+
+fn subtree_species(node: &HierarchyNode) -> Vec<String> {
+    let mut species = vec![node.species.clone()];
+    let mut descendants = Vec::new();
+    collect_descendants(node_children(node), &mut descendants);
+    species.extend(descendants.iter().map(|node| node.species.clone()));
+    species
+}
+
+/// Apply renames to a subtree, keeping each node's genus its parent's species
+fn rename_species(nodes: &mut [HierarchyNode], genus: &str, renamed: &BTreeMap<String, String>) {
+    for node in nodes {
+        node.genus = genus.to_string();
+        if let Some(new_name) = renamed.get(&node.species) {
+            node.species = new_name.clone();
+        }
+        if let Some(ref mut children) = node.children {
+            rename_species(children, &node.species, renamed);
+        }
+    }
+}
+
+fn node_children(node: &HierarchyNode) -> &[HierarchyNode] {
+    node.children.as_deref().unwrap_or_default()
+}
+
+fn find_path(nodes: &[HierarchyNode], name: &str, path: &mut Vec<String>) -> bool {
+    for node in nodes {
+        path.push(node.species.clone());
+        if node.species == name || find_path(node_children(node), name, path) {
+            return true;
+        }
+        path.pop();
+    }
+    false
+}
+
+fn collect_descendants<'a>(nodes: &'a [HierarchyNode], out: &mut Vec<&'a HierarchyNode>) {
+    for node in nodes {
+        out.push(node);
+        collect_descendants(node_children(node), out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{complete_path, merge, rename_node_cascade, MergeOptions};
+    use crate::builder::{HierarchyBuilder, ItemBuilder};
+    use crate::models::ClassicalHierarchy;
+
+    fn beverages() -> ClassicalHierarchy {
+        HierarchyBuilder::new()
+            .root("Beverage")
+            .child("Hot Beverage", "served heated", |c| {
+                c.leaf("Coffee", "brewed from beans")
+                    .leaf("Tea", "steeped from leaves")
+            })
+            .child("Cold Beverage", "served chilled", |c| {
+                c.child("Soda", "carbonated", |c| c.leaf("Cola", "cola-flavored"))
+            })
+            .build()
+    }
+
+    #[test]
+    fn test_resolve_and_search_node_metadata() {
+        let hierarchy = HierarchyBuilder::new()
+            .root("Business")
+            .child("Food Manufacturing", "makes food", |c| {
+                c.code("311").external_id("NAICS", "311").child(
+                    "Coffee and Tea Manufacturing",
+                    "roasts and blends",
+                    |c| {
+                        c.synonym("Coffee Roasting")
+                            .code("311920")
+                            .external_id("ISIC", "C1079")
+                    },
+                )
+            })
+            .build();
+
+        assert_eq!(
+            hierarchy.resolve_species("coffee roasting"),
+            Some("Coffee and Tea Manufacturing")
+        );
+        assert_eq!(hierarchy.resolve_species("311"), Some("Food Manufacturing"));
+        assert_eq!(hierarchy.resolve_species("Mining"), None);
+
+        let found: Vec<_> = hierarchy
+            .search("c107")
+            .iter()
+            .map(|n| n.code.clone())
+            .collect();
+        assert_eq!(found, vec![Some("311920".to_string())]);
+        assert_eq!(hierarchy.search("311").len(), 2);
+    }
+
+    #[test]
+    fn test_iter_preorder() {
+        let hierarchy = beverages();
+        let visited: Vec<_> = hierarchy
+            .iter_preorder()
+            .map(|(depth, node)| (depth, node.species.as_str()))
+            .collect();
+        assert_eq!(
+            visited,
+            vec![
+                (1, "Hot Beverage"),
+                (2, "Coffee"),
+                (2, "Tea"),
+                (1, "Cold Beverage"),
+                (2, "Soda"),
+                (3, "Cola"),
+            ]
+        );
+        assert_eq!(hierarchy.depth(), 3);
+    }
+
+    #[test]
+    fn test_find_node_and_children() {
+        let hierarchy = beverages();
+        assert_eq!(hierarchy.find_node("Tea").unwrap().genus, "Hot Beverage");
+        assert!(hierarchy.find_node("Beverage").is_none());
+        assert!(hierarchy.contains("Beverage"));
+        assert_eq!(hierarchy.children_of("Beverage").len(), 2);
+        assert_eq!(hierarchy.children_of("Soda")[0].species, "Cola");
+        assert!(hierarchy.children_of("Milk").is_empty());
+    }
+
+    #[test]
+    fn test_path_and_ancestors() {
+        let hierarchy = beverages();
+        assert_eq!(
+            hierarchy.path_to("Cola").unwrap(),
+            vec!["Beverage", "Cold Beverage", "Soda", "Cola"]
+        );
+        assert_eq!(hierarchy.path_to("Beverage").unwrap(), vec!["Beverage"]);
+        assert!(hierarchy.path_to("Milk").is_none());
+
+        assert_eq!(
+            hierarchy.ancestors_of("Cola"),
+            vec!["Soda", "Cold Beverage", "Beverage"]
+        );
+        assert!(hierarchy.ancestors_of("Beverage").is_empty());
+    }
+
+    #[test]
+    fn test_descendants_of() {
+        let hierarchy = beverages();
+        let names: Vec<_> = hierarchy
+            .descendants_of("Cold Beverage")
+            .iter()
+            .map(|node| node.species.as_str())
+            .collect();
+        assert_eq!(names, vec!["Soda", "Cola"]);
+        assert_eq!(hierarchy.descendants_of("Beverage").len(), 6);
+    }
+
+    #[test]
+    fn test_complete_path() {
+        let hierarchy = beverages();
+        let path =
+            |segments: &[&str]| -> Vec<String> { segments.iter().map(|s| s.to_string()).collect() };
+
+        assert_eq!(complete_path(&hierarchy, &[]), vec!["Beverage"]);
+        assert_eq!(
+            complete_path(&hierarchy, &path(&["Beverage"])),
+            vec!["Hot Beverage", "Cold Beverage"]
+        );
+        assert_eq!(
+            complete_path(&hierarchy, &path(&["Beverage", "Cold Beverage", "Soda"])),
+            vec!["Cola"]
+        );
+        assert!(complete_path(&hierarchy, &path(&["Beverage", "Hot Beverage", "Tea"])).is_empty());
+        assert!(complete_path(&hierarchy, &path(&["Beverage", "Soda"])).is_empty());
+        assert!(complete_path(&hierarchy, &path(&["Drink"])).is_empty());
+    }
+
+    #[test]
+    fn test_merge() {
+        let acquired = HierarchyBuilder::new()
+            .root("Drinks Co")
+            .child("Tea", "steeped leaves", |c| {
+                c.leaf("Iced Tea", "served cold")
+            })
+            .leaf("Juice", "pressed fruit")
+            .build();
+
+        let clash = merge(&beverages(), &acquired, &MergeOptions::default()).unwrap_err();
+        assert_eq!(clash, vec!["Species 'Tea' is in both hierarchies"]);
+
+        let options = MergeOptions {
+            prefix_conflicts: true,
+            ..MergeOptions::default()
+        };
+        let merged = merge(&beverages(), &acquired, &options).unwrap();
+        let hierarchy = &merged.hierarchy;
+        assert_eq!(hierarchy.root, "Beverage");
+        assert_eq!(
+            hierarchy.path_to("Iced Tea").unwrap(),
+            vec!["Beverage", "Drinks Co", "Drinks Co:Tea", "Iced Tea"]
+        );
+        assert_eq!(
+            hierarchy.find_node("Iced Tea").unwrap().genus,
+            "Drinks Co:Tea"
+        );
+        assert_eq!(hierarchy.find_node("Tea").unwrap().genus, "Hot Beverage");
+        assert_eq!(merged.renamed["Tea"], "Drinks Co:Tea");
+
+        let path =
+            |segments: &[&str]| -> Vec<String> { segments.iter().map(|s| s.to_string()).collect() };
+        assert_eq!(
+            merged.map_path(&path(&["Drinks Co", "Tea"]), true).unwrap(),
+            vec!["Beverage", "Drinks Co", "Drinks Co:Tea"]
+        );
+        assert_eq!(
+            merged
+                .map_path(&path(&["Beverage", "Hot Beverage", "Tea"]), false)
+                .unwrap(),
+            vec!["Beverage", "Hot Beverage", "Tea"]
+        );
+
+        // Under a new root both hierarchies become nodes
+        let options = MergeOptions {
+            root: Some("Catalog".to_string()),
+            prefix_conflicts: true,
+            prefix: Some("dc".to_string()),
+        };
+        let merged = merge(&beverages(), &acquired, &options).unwrap();
+        let top: Vec<_> = merged
+            .hierarchy
+            .children_of("Catalog")
+            .iter()
+            .map(|node| node.species.as_str())
+            .collect();
+        assert_eq!(top, vec!["Beverage", "Drinks Co"]);
+        assert!(merged.hierarchy.contains("dc:Tea"));
+        assert_eq!(merged.hierarchy.depth(), 4);
+    }
+
+    #[test]
+    fn test_rename_node_cascade() {
+        let mut hierarchy = beverages();
+        let mut items = vec![
+            ItemBuilder::new("Cola Zero")
+                .path(["Beverage", "Cold Beverage", "Soda", "Cola"])
+                .build(),
+            ItemBuilder::new("Lemonade")
+                .path(["Beverage", "Cold Beverage"])
+                .build(),
+            ItemBuilder::new("Latte")
+                .path(["Beverage", "Hot Beverage", "Coffee"])
+                .build(),
+        ];
+
+        let touched =
+            rename_node_cascade(&mut hierarchy, &mut items, "Cold Beverage", "Chilled").unwrap();
+        assert_eq!(touched, 2);
+        assert_eq!(
+            items[0].classical_path,
+            ["Beverage", "Chilled", "Soda", "Cola"]
+        );
+        assert_eq!(
+            hierarchy.path_to("Cola").unwrap(),
+            ["Beverage", "Chilled", "Soda", "Cola"]
+        );
+        assert_eq!(hierarchy.find_node("Soda").unwrap().genus, "Chilled");
+
+        assert_eq!(
+            rename_node_cascade(&mut hierarchy, &mut items, "Beverage", "Drink"),
+            Ok(3)
+        );
+        assert_eq!(hierarchy.find_node("Chilled").unwrap().genus, "Drink");
+
+        assert!(rename_node_cascade(&mut hierarchy, &mut items, "Juice", "Smoothie").is_err());
+        assert!(rename_node_cascade(&mut hierarchy, &mut items, "Soda", "Coffee").is_err());
+    }
+}
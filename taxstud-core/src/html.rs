@@ -0,0 +1,228 @@
+use crate::models::{Attachment, DisplayHint, Item, TaxonomyData, TaxonomySchema};
+use crate::progress::{report_step, ProgressFn};
+use crate::vocabulary::escape_xml;
+use std::io::{self, Write};
+use std::path::Path;
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em;max-width:60em}\
+.path{color:#555}\
+dt{font-weight:bold;float:left;clear:left;width:10em}\
+dd{margin-left:11em}\
+figure{display:inline-block;margin:0 1em 1em 0}\
+figure img{max-width:12em;max-height:12em}\
+.badge{color:#fff;border-radius:.8em;padding:0 .5em}";
+
+/// Write the items as a standalone HTML page: path, facets, custom fields and attachments
+/// (images inline) of each item, in order
+/// Local attachment paths are resolved against `data_dir`, the directory of the data file,
+/// when given; otherwise they are written as they are
+pub fn write_html(
+    data: &TaxonomyData,
+    schema: &TaxonomySchema,
+    data_dir: Option<&Path>,
+    lang: Option<&str>,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    write_html_with_progress(data, schema, data_dir, lang, out, &mut |_| {})
+}
+
+/// Write the items as HTML like `write_html`, reporting each item written
+pub fn write_html_with_progress(
+    data: &TaxonomyData,
+    schema: &TaxonomySchema,
+    data_dir: Option<&Path>,
+    lang: Option<&str>,
+    out: &mut dyn Write,
+    on_progress: &mut ProgressFn,
+) -> io::Result<()> {
+    writeln!(out, "<!DOCTYPE html>")?;
+    writeln!(out, r#"<html lang="{}">"#, escape_xml(lang.unwrap_or("en")))?;
+    writeln!(out, "<head>")?;
+    writeln!(out, r#"<meta charset="utf-8">"#)?;
+    writeln!(out, "<title>{}</title>", escape_xml(&schema.title))?;
+    writeln!(out, "<style>{}</style>", STYLE)?;
+    writeln!(out, "</head>")?;
+    writeln!(out, "<body>")?;
+    writeln!(out, "<h1>{}</h1>", escape_xml(&schema.title))?;
+    if let Some(description) = &schema.description {
+        writeln!(out, "<p>{}</p>", escape_xml(description))?;
+    }
+    writeln!(out, "<p>{} item(s)</p>", data.items.len())?;
+
+    let total = data.items.len() as u64;
+    for (index, item) in data.items.iter().enumerate() {
+        write_item(out, item, schema, data_dir, lang)?;
+        report_step(on_progress, "Writing items", index as u64 + 1, total);
+    }
+
+    writeln!(out, "</body>")?;
+    writeln!(out, "</html>")
+}
+
+fn write_item(
+    out: &mut dyn Write,
+    item: &Item,
+    schema: &TaxonomySchema,
+    data_dir: Option<&Path>,
+    lang: Option<&str>,
+) -> io::Result<()> {
+    writeln!(out, "<section>")?;
+    writeln!(out, "<h2>{}</h2>", escape_xml(item.display_name(lang)))?;
+
+    let hierarchy = &schema.classical_hierarchy;
+    let path: Vec<String> = item
+        .classical_path
+        .iter()
+        .map(|segment| escape_xml(hierarchy.display_name(segment, lang)))
+        .collect();
+    writeln!(out, r#"<p class="path">{}</p>"#, path.join(" → "))?;
+
+    // Names, and values as HTML
+    let mut rows: Vec<(String, String)> = Vec::new();
+    let mut facet_names: Vec<_> = item.facets.keys().collect();
+    facet_names.sort();
+    for facet_name in facet_names {
+        let definition = schema.faceted_dimensions.get(facet_name);
+        let mut values = item.get_facet_as_vec(facet_name);
+        schema.sort_by_display_order(facet_name, &mut values);
+        let values: Vec<String> = values
+            .iter()
+            .map(|value| {
+                let label = match definition {
+                    Some(definition) => definition.label_for(value, lang),
+                    None => value,
+                };
+                facet_value_html(label, schema.display_hint(facet_name, value))
+            })
+            .collect();
+        let name = definition.map_or(facet_name.as_str(), |definition| {
+            definition.display_name(facet_name, lang)
+        });
+        rows.push((name.to_string(), values.join(", ")));
+    }
+    for (name, field) in schema.sorted_item_fields() {
+        if let Some(value) = item.extra.get(name).filter(|value| !value.is_null()) {
+            let text = value
+                .as_str()
+                .map_or_else(|| value.to_string(), str::to_string);
+            rows.push((field.display_name(name).to_string(), escape_xml(&text)));
+        }
+    }
+    if !rows.is_empty() {
+        writeln!(out, "<dl>")?;
+        for (name, value) in rows {
+            writeln!(out, "<dt>{}</dt><dd>{}</dd>", escape_xml(&name), value)?;
+        }
+        writeln!(out, "</dl>")?;
+    }
+
+    for attachment in &item.attachments {
+        write_attachment(out, attachment, data_dir)?;
+    }
+
+    writeln!(out, "</section>")
+}
+
+/// A facet value as HTML: its "x-display" icon before it, on a badge of its color
+fn facet_value_html(label: &str, hint: Option<&DisplayHint>) -> String {
+    let text = match hint.and_then(|hint| hint.icon.as_deref()) {
+        Some(icon) => format!("{} {}", escape_xml(icon), escape_xml(label)),
+        None => escape_xml(label),
+    };
+    // Colors are checked to be #rrggbb when the schema is read
+    match hint.and_then(|hint| hint.color.as_deref()) {
+        Some(color) => format!(
+            r#"<span class="badge" style="background:{}">{}</span>"#,
+            escape_xml(color),
+            text
+        ),
+        None => text,
+    }
+}
+
+fn write_attachment(
+    out: &mut dyn Write,
+    attachment: &Attachment,
+    data_dir: Option<&Path>,
+) -> io::Result<()> {
+    let href = match data_dir.and_then(|dir| attachment.local_path(dir)) {
+        Some(path) => path.display().to_string(),
+        None => attachment.path_or_url.clone(),
+    };
+    let href = escape_xml(&href);
+    let caption = escape_xml(attachment.display_caption());
+
+    if attachment.is_image() {
+        writeln!(
+            out,
+            r#"<figure><img src="{}" alt="{}"><figcaption>{}</figcaption></figure>"#,
+            href, caption, caption
+        )
+    } else {
+        writeln!(out, r#"<p><a href="{}">{}</a></p>"#, href, caption)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{ItemBuilder, TaxonomySchemaBuilder};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_write_html_attachments() {
+        let schema = TaxonomySchemaBuilder::new("Mugs & Cups")
+            .facet("color", ["red"])
+            .build();
+        let data = TaxonomyData {
+            schema: "schema.json".to_string(),
+            items: vec![ItemBuilder::new("Mug")
+                .path(["Root"])
+                .facet("color", "red")
+                .attachment(Attachment::new("photos/mug.jpg"))
+                .attachment(Attachment {
+                    caption: Some("Care <guide>".to_string()),
+                    ..Attachment::new("https://example.com/care.pdf")
+                })
+                .build()],
+            extra: HashMap::new(),
+        };
+
+        let mut out = Vec::new();
+        write_html(&data, &schema, Some(Path::new("data")), None, &mut out).unwrap();
+        let html = String::from_utf8(out).unwrap();
+
+        assert!(html.contains("<title>Mugs &amp; Cups</title>"));
+        assert!(html.contains("<dt>color</dt><dd>red</dd>"));
+        let photo = Path::new("data").join("photos/mug.jpg");
+        assert!(html.contains(&format!(r#"<img src="{}""#, photo.display())));
+        assert!(html.contains(r#"<a href="https://example.com/care.pdf">Care &lt;guide&gt;</a>"#));
+    }
+
+    #[test]
+    fn test_write_html_display_hints() {
+        let schema = crate::schema::build_schema_from_json(serde_json::json!({
+            "title": "Drinks",
+            "classical_hierarchy": {"root": "Beverage"},
+            "faceted_dimensions": {"temperature": ["hot", "iced"]},
+            "x-display": {"temperature": {"iced": {"color": "#5bc0de", "icon": "🧊", "order": 1}}}
+        }))
+        .unwrap();
+        let data = TaxonomyData {
+            schema: "schema.json".to_string(),
+            items: vec![ItemBuilder::new("Toddy")
+                .path(["Beverage"])
+                .facet_values("temperature", ["hot", "iced"])
+                .build()],
+            extra: HashMap::new(),
+        };
+
+        let mut out = Vec::new();
+        write_html(&data, &schema, None, None, &mut out).unwrap();
+        let html = String::from_utf8(out).unwrap();
+
+        assert!(html.contains(
+            r#"<dd><span class="badge" style="background:#5bc0de">🧊 iced</span>, hot</dd>"#
+        ));
+    }
+}
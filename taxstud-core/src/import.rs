@@ -0,0 +1,702 @@
+use crate::models::{Item, Provenance, TaxonomySchema};
+use crate::validation::{constraint_violations, validate_path_exists};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+
+/// Rows read from a CSV/TSV or JSON file, before they are mapped onto items
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportTable {
+    /// Column names, from the header row or the objects' keys
+    pub columns: Vec<String>,
+    /// One cell per column; empty and missing cells are null
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl ImportTable {
+    /// Parse CSV (or, with a tab delimiter, TSV) whose first row names the columns
+    pub fn from_csv(contents: &str, delimiter: u8) -> Result<Self, Box<dyn Error>> {
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .delimiter(delimiter)
+            .from_reader(contents.as_bytes());
+
+        let columns: Vec<String> = reader.headers()?.iter().map(str::to_string).collect();
+        let mut rows = Vec::new();
+        for record in reader.records() {
+            let record = record?;
+            let row = (0..columns.len())
+                .map(|i| match record.get(i) {
+                    Some(cell) if !cell.is_empty() => Value::String(cell.to_string()),
+                    _ => Value::Null,
+                })
+                .collect();
+            rows.push(row);
+        }
+
+        Ok(ImportTable { columns, rows })
+    }
+
+    /// Parse a JSON array of objects, or a data document's {"items": [...]}
+    /// Keys of a nested "facets" object become columns of their own
+    pub fn from_json(contents: &str) -> Result<Self, Box<dyn Error>> {
+        let records = match serde_json::from_str(contents)? {
+            Value::Array(records) => records,
+            Value::Object(mut document) => match document.remove("items") {
+                Some(Value::Array(records)) => records,
+                _ => return Err("Expected an \"items\" array".into()),
+            },
+            _ => return Err("Expected an array of objects".into()),
+        };
+
+        let mut objects: Vec<Map<String, Value>> = Vec::new();
+        for (idx, record) in records.into_iter().enumerate() {
+            let Value::Object(mut fields) = record else {
+                return Err(format!("Row {} is not an object", idx + 1).into());
+            };
+            if let Some(Value::Object(facets)) = fields.remove("facets") {
+                fields.extend(facets);
+            }
+            objects.push(fields);
+        }
+
+        let mut columns: Vec<String> = Vec::new();
+        for key in objects.iter().flat_map(Map::keys) {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+        let rows = objects
+            .iter()
+            .map(|fields| {
+                columns
+                    .iter()
+                    .map(|column| fields.get(column).cloned().unwrap_or(Value::Null))
+                    .collect()
+            })
+            .collect();
+
+        Ok(ImportTable { columns, rows })
+    }
+}
+
+/// What an import column fills in on each item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnTarget {
+    Ignore,
+    Name,
+    /// The classical path, as an array or text split at '>' or ','
+    Path,
+    Facet(String),
+    /// An additional field, kept as is
+    Field(String),
+}
+
+/// A table being imported as items, with the target of each column
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ItemImport {
+    pub table: ImportTable,
+    /// One target per column of the table
+    pub targets: Vec<ColumnTarget>,
+    /// Recorded on every item the import makes (replacing a "source" column)
+    pub source: Option<Provenance>,
+}
+
+/// One row of an import preview: the item it makes and what is wrong with it
+#[derive(Debug, Clone)]
+pub struct ImportRow {
+    /// Row number in the table, from 1
+    pub row: usize,
+    pub item: Item,
+    pub issues: Vec<String>,
+}
+
+/// Outcome of an import, row by row
+#[derive(Debug, Clone, Default)]
+pub struct ImportReport {
+    /// Valid rows, whose items are added
+    pub created: Vec<ImportRow>,
+    /// Rows naming an item that already exists, or an earlier row's item
+    pub skipped: Vec<ImportRow>,
+    /// Rows with other problems; their issues say which
+    pub errored: Vec<ImportRow>,
+}
+
+impl ImportReport {
+    /// Items of the created rows, in table order
+    pub fn items(&self) -> impl Iterator<Item = &Item> {
+        self.created.iter().map(|row| &row.item)
+    }
+}
+
+impl ImportRow {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl ItemImport {
+    /// Start an import, guessing each column's target from its name
+    /// "name" and "path" (or "classical_path") columns are recognized, as are facet names
+    /// (ignoring case); other columns become additional fields
+    pub fn new(table: ImportTable, schema: &TaxonomySchema) -> Self {
+        let mut targets: Vec<ColumnTarget> = Vec::new();
+        for column in &table.columns {
+            let target = match column.trim().to_lowercase().as_str() {
+                "name" | "item" => ColumnTarget::Name,
+                "path" | "classical_path" | "classification" => ColumnTarget::Path,
+                key => schema
+                    .faceted_dimensions
+                    .keys()
+                    .find(|facet| facet.to_lowercase() == key)
+                    .map(|facet| ColumnTarget::Facet(facet.clone()))
+                    .unwrap_or_else(|| ColumnTarget::Field(column.trim().to_string())),
+            };
+            // Only the first name or path column is used as such
+            let taken = matches!(target, ColumnTarget::Name | ColumnTarget::Path)
+                && targets.contains(&target);
+            targets.push(if taken {
+                ColumnTarget::Field(column.trim().to_string())
+            } else {
+                target
+            });
+        }
+
+        ItemImport {
+            table,
+            targets,
+            source: None,
+        }
+    }
+
+    /// Build an item from every row and check it against the schema
+    /// Names already used by `existing` items, or by an earlier row, are reported too
+    pub fn preview(&self, schema: &TaxonomySchema, existing: &[Item]) -> Vec<ImportRow> {
+        let existing_names: HashSet<&str> = existing.iter().map(|i| i.name.as_str()).collect();
+        let mut seen: HashMap<String, usize> = HashMap::new();
+
+        self.table
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(idx, cells)| {
+                let mut row = self.build_row(idx + 1, cells, schema);
+                let name = row.item.name.clone();
+                if existing_names.contains(name.as_str()) {
+                    row.issues
+                        .push(format!("an item named '{}' already exists", name));
+                } else if let Some(first) = seen.get(&name) {
+                    row.issues.push(format!("same name as row {}", first));
+                }
+                if !name.is_empty() {
+                    seen.entry(name).or_insert(row.row);
+                }
+                row
+            })
+            .collect()
+    }
+
+    /// Sort the rows of `preview` into items to create, rows skipped because their name is
+    /// taken (by an existing item or an earlier row), and rows with other problems
+    pub fn report(&self, schema: &TaxonomySchema, existing: &[Item]) -> ImportReport {
+        let mut names: HashSet<String> = existing.iter().map(|i| i.name.clone()).collect();
+        let mut report = ImportReport::default();
+        for row in self.preview(schema, existing) {
+            let taken = !names.insert(row.item.name.clone()) && !row.item.name.is_empty();
+            if taken {
+                report.skipped.push(row);
+            } else if row.is_valid() {
+                report.created.push(row);
+            } else {
+                report.errored.push(row);
+            }
+        }
+        report
+    }
+
+    fn build_row(&self, row: usize, cells: &[Value], schema: &TaxonomySchema) -> ImportRow {
+        let mut item = Item {
+            name: String::new(),
+            classical_path: Vec::new(),
+            facets: HashMap::new(),
+            labels: HashMap::new(),
+            relations: Vec::new(),
+            attachments: Vec::new(),
+            extra: HashMap::new(),
+        };
+        let mut issues = Vec::new();
+        let root = &schema.classical_hierarchy.root;
+
+        for (target, cell) in self.targets.iter().zip(cells) {
+            if cell.is_null() {
+                continue;
+            }
+            match target {
+                ColumnTarget::Ignore => {}
+                ColumnTarget::Name => item.name = cell_text(cell).trim().to_string(),
+                ColumnTarget::Path => item.classical_path = parse_path(cell, root),
+                ColumnTarget::Facet(facet) => {
+                    let Some(definition) = schema.faceted_dimensions.get(facet) else {
+                        issues.push(format!("unknown facet '{}'", facet));
+                        continue;
+                    };
+                    let value = match cell {
+                        Value::String(text) => definition.value_from_text(text),
+                        value => Ok(value.clone()),
+                    };
+                    match value {
+                        Ok(value) => {
+                            for e in definition.check_facet(&value) {
+                                issues.push(format!("facet '{}' {}", facet, e));
+                            }
+                            item.facets.insert(facet.clone(), value);
+                        }
+                        Err(e) => issues.push(format!("facet '{}': {}", facet, e)),
+                    }
+                }
+                ColumnTarget::Field(field) => {
+                    item.extra.insert(field.clone(), cell.clone());
+                }
+            }
+        }
+
+        if self.source.is_some() {
+            item.set_source(self.source.clone());
+        }
+        if item.name.is_empty() {
+            issues.push("missing name".to_string());
+        }
+        if let Err(e) = validate_path_exists(&item.classical_path, &schema.classical_hierarchy) {
+            issues.push(e);
+        }
+        if item.facets.is_empty() {
+            issues.push("no facet values".to_string());
+        }
+        let mut required: Vec<&String> = schema
+            .faceted_dimensions
+            .iter()
+            .filter(|(facet, definition)| definition.required && !item.facets.contains_key(*facet))
+            .map(|(facet, _)| facet)
+            .collect();
+        required.sort();
+        for facet in required {
+            issues.push(format!("missing required facet '{}'", facet));
+        }
+        for violation in constraint_violations(&item.facets, schema) {
+            issues.push(violation.message);
+        }
+
+        ImportRow { row, item, issues }
+    }
+}
+
+/// A file format items can be imported from
+pub trait Importer {
+    /// Name the format is chosen by, e.g. "csv"
+    fn id(&self) -> &'static str;
+
+    /// Name of the format in file dialogs, e.g. "CSV"
+    fn name(&self) -> &'static str;
+
+    /// Extensions of files in this format, lowercase and without the dot
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// Whether `contents` look like this format, for when the extension doesn't tell
+    fn sniff(&self, contents: &[u8]) -> bool;
+
+    fn read(&self, contents: &[u8]) -> Result<ImportTable, Box<dyn Error>>;
+}
+
+/// The importers available, in the order they were registered
+/// `ImporterRegistry::default()` has the built-in formats; `register` adds more
+pub struct ImporterRegistry {
+    importers: Vec<Box<dyn Importer>>,
+}
+
+impl ImporterRegistry {
+    /// A registry with no importers
+    pub fn new() -> Self {
+        Self {
+            importers: Vec::new(),
+        }
+    }
+
+    /// Add an importer, replacing any with the same id
+    pub fn register(&mut self, importer: Box<dyn Importer>) {
+        match self.importers.iter_mut().find(|i| i.id() == importer.id()) {
+            Some(existing) => *existing = importer,
+            None => self.importers.push(importer),
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&dyn Importer> {
+        self.iter().find(|importer| importer.id() == id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Importer> {
+        self.importers.iter().map(|importer| importer.as_ref())
+    }
+
+    /// Every extension some importer reads, for file dialog filters
+    pub fn extensions(&self) -> Vec<&'static str> {
+        let mut extensions: Vec<&str> = Vec::new();
+        for extension in self.iter().flat_map(|importer| importer.extensions()) {
+            if !extensions.contains(extension) {
+                extensions.push(extension);
+            }
+        }
+        extensions
+    }
+
+    /// The importer for a file: one that claims both its extension and its contents, else
+    /// one that claims the extension, else one that recognizes the contents
+    pub fn detect(&self, path: Option<&Path>, contents: &[u8]) -> Option<&dyn Importer> {
+        let extension = path
+            .and_then(Path::extension)
+            .and_then(|ext| ext.to_str())
+            .map(str::to_lowercase);
+        let claims_extension = |importer: &dyn Importer| {
+            extension
+                .as_deref()
+                .is_some_and(|ext| importer.extensions().contains(&ext))
+        };
+        self.iter()
+            .find(|importer| claims_extension(*importer) && importer.sniff(contents))
+            .or_else(|| self.iter().find(|importer| claims_extension(*importer)))
+            .or_else(|| self.iter().find(|importer| importer.sniff(contents)))
+    }
+
+    /// Read a file's rows with the importer `detect` picks
+    pub fn read(
+        &self,
+        path: Option<&Path>,
+        contents: &[u8],
+    ) -> Result<ImportTable, Box<dyn Error>> {
+        let importer = self.detect(path, contents).ok_or_else(|| {
+            let expected: Vec<String> = self
+                .extensions()
+                .iter()
+                .map(|e| format!(".{}", e))
+                .collect();
+            let source = path.map_or("input".into(), |path| path.display().to_string());
+            format!(
+                "Unsupported import format '{}' (expected {})",
+                source,
+                expected.join(", ")
+            )
+        })?;
+        importer.read(contents)
+    }
+}
+
+impl fmt::Debug for ImporterRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.iter().map(|importer| importer.id()))
+            .finish()
+    }
+}
+
+impl Default for ImporterRegistry {
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry.register(Box::new(CsvImporter));
+        registry.register(Box::new(TsvImporter));
+        registry.register(Box::new(JsonImporter));
+        #[cfg(feature = "xlsx")]
+        registry.register(Box::new(SpreadsheetImporter));
+        registry
+    }
+}
+
+/// First line of a text file, or None when it isn't UTF-8
+fn first_line(contents: &[u8]) -> Option<&str> {
+    let text = std::str::from_utf8(contents).ok()?;
+    text.lines().find(|line| !line.trim().is_empty())
+}
+
+/// CSV with a header row naming the columns
+pub struct CsvImporter;
+
+impl Importer for CsvImporter {
+    fn id(&self) -> &'static str {
+        "csv"
+    }
+
+    fn name(&self) -> &'static str {
+        "CSV"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["csv", "txt"]
+    }
+
+    fn sniff(&self, contents: &[u8]) -> bool {
+        first_line(contents).is_some_and(|line| line.contains(',') && !line.contains('\t'))
+    }
+
+    fn read(&self, contents: &[u8]) -> Result<ImportTable, Box<dyn Error>> {
+        ImportTable::from_csv(std::str::from_utf8(contents)?, b',')
+    }
+}
+
+/// Tab-separated values with a header row naming the columns
+pub struct TsvImporter;
+
+impl Importer for TsvImporter {
+    fn id(&self) -> &'static str {
+        "tsv"
+    }
+
+    fn name(&self) -> &'static str {
+        "TSV"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["tsv", "tab", "txt"]
+    }
+
+    fn sniff(&self, contents: &[u8]) -> bool {
+        first_line(contents).is_some_and(|line| line.contains('\t'))
+    }
+
+    fn read(&self, contents: &[u8]) -> Result<ImportTable, Box<dyn Error>> {
+        ImportTable::from_csv(std::str::from_utf8(contents)?, b'\t')
+    }
+}
+
+/// A JSON array of objects, or a data document (`ImportTable::from_json`)
+pub struct JsonImporter;
+
+impl Importer for JsonImporter {
+    fn id(&self) -> &'static str {
+        "json"
+    }
+
+    fn name(&self) -> &'static str {
+        "JSON"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["json"]
+    }
+
+    fn sniff(&self, contents: &[u8]) -> bool {
+        let start = contents.iter().find(|byte| !byte.is_ascii_whitespace());
+        matches!(start, Some(b'[' | b'{'))
+    }
+
+    fn read(&self, contents: &[u8]) -> Result<ImportTable, Box<dyn Error>> {
+        ImportTable::from_json(std::str::from_utf8(contents)?)
+    }
+}
+
+/// The first sheet of a spreadsheet (Excel or OpenDocument), whose first row names the columns
+#[cfg(feature = "xlsx")]
+pub struct SpreadsheetImporter;
+
+#[cfg(feature = "xlsx")]
+impl Importer for SpreadsheetImporter {
+    fn id(&self) -> &'static str {
+        "spreadsheet"
+    }
+
+    fn name(&self) -> &'static str {
+        "Spreadsheet"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["xlsx", "xlsm", "xlsb", "xls", "ods"]
+    }
+
+    fn sniff(&self, contents: &[u8]) -> bool {
+        // Zip (xlsx, ods) and OLE compound (xls) files
+        contents.starts_with(b"PK\x03\x04") || contents.starts_with(&[0xd0, 0xcf, 0x11, 0xe0])
+    }
+
+    fn read(&self, contents: &[u8]) -> Result<ImportTable, Box<dyn Error>> {
+        use calamine::{Data, Reader};
+
+        let mut workbook = calamine::open_workbook_auto_from_rs(std::io::Cursor::new(contents))?;
+        let sheet = workbook
+            .worksheet_range_at(0)
+            .ok_or("The spreadsheet has no sheets")??;
+        let mut rows = sheet.rows();
+        let columns: Vec<String> = rows
+            .next()
+            .map(|header| {
+                header
+                    .iter()
+                    .map(|cell| cell.to_string().trim().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let cell_value = |cell: &Data| match cell {
+            Data::Empty | Data::Error(_) => Value::Null,
+            Data::Int(n) => Value::from(*n),
+            Data::Float(x) if x.fract() == 0.0 && x.abs() < 1e15 => Value::from(*x as i64),
+            Data::Float(x) => Value::from(*x),
+            Data::Bool(b) => Value::Bool(*b),
+            other => match other.to_string().trim() {
+                "" => Value::Null,
+                text => Value::String(text.to_string()),
+            },
+        };
+        let rows = rows
+            .map(|cells| {
+                (0..columns.len())
+                    .map(|i| cells.get(i).map_or(Value::Null, cell_value))
+                    .collect()
+            })
+            .collect();
+
+        Ok(ImportTable { columns, rows })
+    }
+}
+
+/// Text of a cell; arrays are joined with ", "
+fn cell_text(cell: &Value) -> String {
+    match cell {
+        Value::String(text) => text.clone(),
+        Value::Array(values) => values.iter().map(cell_text).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
+
+/// A path cell as segments, with the root put in front when the cell leaves it out
+fn parse_path(cell: &Value, root: &str) -> Vec<String> {
+    let mut path: Vec<String> = match cell {
+        Value::Array(segments) => segments.iter().map(cell_text).collect(),
+        cell => cell_text(cell)
+            .split(['>', ','])
+            .map(str::to_string)
+            .collect(),
+    };
+    path = path
+        .iter()
+        .map(|segment| segment.trim().to_string())
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    if path.first().is_some_and(|first| first != root) {
+        path.insert(0, root.to_string());
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{HierarchyBuilder, ItemBuilder, TaxonomySchemaBuilder};
+
+    #[test]
+    fn test_import_preview() {
+        let schema = TaxonomySchemaBuilder::new("Beverages")
+            .hierarchy(
+                HierarchyBuilder::new()
+                    .root("Beverage")
+                    .child("Hot Beverage", "served heated", |c| {
+                        c.leaf("Coffee", "brewed from beans")
+                    })
+                    .build(),
+            )
+            .facet("temperature", ["hot", "iced"])
+            .build();
+        let existing = vec![ItemBuilder::new("Latte")
+            .path(["Beverage", "Hot Beverage", "Coffee"])
+            .facet("temperature", "hot")
+            .build()];
+
+        let csv = "Name,Path,Temperature,SKU\n\
+                   Mocha,Hot Beverage > Coffee,hot,A1\n\
+                   Latte,Hot Beverage > Coffee,hot,A2\n\
+                   Frappe,Hot Beverage > Coffee,frozen,A3\n\
+                   Mocha,Cold Beverage,iced,A4\n";
+        let table = ImportTable::from_csv(csv, b',').unwrap();
+        let mut import = ItemImport::new(table, &schema);
+        assert_eq!(
+            import.targets,
+            vec![
+                ColumnTarget::Name,
+                ColumnTarget::Path,
+                ColumnTarget::Facet("temperature".to_string()),
+                ColumnTarget::Field("SKU".to_string()),
+            ]
+        );
+
+        let rows = import.preview(&schema, &existing);
+        assert!(rows[0].is_valid());
+        assert_eq!(
+            rows[0].item.classical_path,
+            ["Beverage", "Hot Beverage", "Coffee"]
+        );
+        assert_eq!(rows[0].item.extra["SKU"], "A1");
+        assert_eq!(rows[1].issues, ["an item named 'Latte' already exists"]);
+        assert_eq!(
+            rows[2].issues,
+            ["facet 'temperature' has invalid value 'frozen' (not in allowed values)"]
+        );
+        assert_eq!(
+            rows[3].issues,
+            [
+                "'Cold Beverage' is not a valid child of 'Beverage' in the hierarchy",
+                "same name as row 1",
+            ]
+        );
+
+        let report = import.report(&schema, &existing);
+        let rows_of = |rows: &[ImportRow]| rows.iter().map(|r| r.row).collect::<Vec<_>>();
+        assert_eq!(rows_of(&report.created), [1]);
+        assert_eq!(rows_of(&report.skipped), [2, 4]);
+        assert_eq!(rows_of(&report.errored), [3]);
+        assert_eq!(report.items().next().unwrap().name, "Mocha");
+
+        import.targets[3] = ColumnTarget::Ignore;
+        assert!(import.preview(&schema, &existing)[0].item.extra.is_empty());
+
+        // Every row records where it came from
+        import.source = Some(Provenance::now("vendor.csv", "test import"));
+        let source = import.preview(&schema, &existing)[0].item.source().unwrap();
+        assert_eq!(source.origin_file, "vendor.csv");
+        assert!(source.imported_at > 0);
+
+        let json = r#"{"items": [{"name": "Cortado", "classical_path": ["Beverage"],
+                       "facets": {"temperature": "hot"}}]}"#;
+        let import = ItemImport::new(ImportTable::from_json(json).unwrap(), &schema);
+        let rows = import.preview(&schema, &existing);
+        assert!(rows[0].is_valid());
+        assert_eq!(rows[0].item.facets["temperature"], "hot");
+    }
+
+    #[test]
+    fn test_importer_detection() {
+        let registry = ImporterRegistry::default();
+        let detect = |path: &str, contents: &str| {
+            registry
+                .detect(Some(Path::new(path)), contents.as_bytes())
+                .map(|importer| importer.id())
+        };
+
+        assert_eq!(detect("items.csv", "name,path\n"), Some("csv"));
+        // .txt is claimed by both; the contents decide
+        assert_eq!(detect("items.txt", "name\tpath\n"), Some("tsv"));
+        assert_eq!(detect("items.txt", "name,path\n"), Some("csv"));
+        // No extension to go by
+        assert_eq!(detect("export", "  [{\"name\": \"Mocha\"}]"), Some("json"));
+        assert_eq!(detect("items.dat", "name\tpath\n"), Some("tsv"));
+        assert_eq!(detect("notes.dat", "just some words"), None);
+
+        let table = registry
+            .read(Some(Path::new("items.tab")), b"name\tpath\nMocha\tCoffee\n")
+            .unwrap();
+        assert_eq!(table.columns, ["name", "path"]);
+        assert_eq!(table.rows[0][1], "Coffee");
+        let err = registry.read(None, b"just some words").unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Unsupported import format 'input'"));
+    }
+}
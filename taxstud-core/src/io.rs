@@ -1,19 +1,140 @@
-use crate::models::{TaxonomyData, TaxonomySchema};
+use crate::import::{ImportTable, ImporterRegistry};
+use crate::models::{
+    FacetDefinition, FacetValueNode, Item, TaxonomyData, TaxonomySchema, VocabularyImport,
+};
+use crate::progress::{read_to_string_with_progress, read_with_progress, Progress, ProgressFn};
+use crate::refs::resolve_refs;
+use crate::rules::RuleSet;
 use crate::schema::build_schema_from_json;
-use crate::schema_validation::validate_against_schema;
+use crate::sorting::sort_items;
+use crate::validation::check_duplicate_keys;
+use crate::vocabulary::{parse_csv_vocabulary, parse_skos_vocabulary};
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
+use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Key under which a data file may carry its own schema, so it stands alone
+pub const INLINE_SCHEMA_KEY: &str = "$schema_inline";
+
+/// Characters shown either side of the error in a `JsonParseError` snippet
+const SNIPPET_CONTEXT: usize = 30;
+
+/// Text that isn't well-formed JSON, located to its line and column (both 1-based, as
+/// serde_json counts them) with a snippet of the line showing where
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonParseError {
+    /// What went wrong, e.g. "expected `,` or `}`"
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+    /// The offending line, cut around the column, and a caret under the column
+    pub snippet: String,
+}
+
+impl JsonParseError {
+    fn new(err: &serde_json::Error, contents: &str) -> Self {
+        let location = format!(" at line {} column {}", err.line(), err.column());
+        let message = err.to_string();
+        Self {
+            message: message
+                .strip_suffix(&location)
+                .unwrap_or(&message)
+                .to_string(),
+            line: err.line(),
+            column: err.column(),
+            snippet: snippet(contents, err.line(), err.column()),
+        }
+    }
+}
+
+impl fmt::Display for JsonParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid JSON at line {}, column {}: {}\n{}",
+            self.line, self.column, self.message, self.snippet
+        )
+    }
+}
+
+impl Error for JsonParseError {}
+
+/// Line `line` of `contents` after its number, and a caret under `column` on the next line
+/// serde_json counts columns in bytes; long lines show SNIPPET_CONTEXT characters either side
+fn snippet(contents: &str, line: usize, column: usize) -> String {
+    let text = contents
+        .lines()
+        .nth(line.saturating_sub(1))
+        .unwrap_or_default()
+        .replace('\t', " ");
+    let mut end_byte = column.min(text.len());
+    while !text.is_char_boundary(end_byte) {
+        end_byte -= 1;
+    }
+    let chars: Vec<char> = text.chars().collect();
+    let caret = text[..end_byte].chars().count().saturating_sub(1);
+    let start = caret.saturating_sub(SNIPPET_CONTEXT);
+    let end = (caret + SNIPPET_CONTEXT + 1).min(chars.len());
+
+    let mut shown: String = chars[start..end].iter().collect();
+    let mut offset = caret - start;
+    if start > 0 {
+        shown.insert(0, '…');
+        offset += 1;
+    }
+    if end < chars.len() {
+        shown.push('…');
+    }
+    let number = line.to_string();
+    format!(
+        "{} | {}\n{} | {}^",
+        number,
+        shown,
+        " ".repeat(number.len()),
+        " ".repeat(offset)
+    )
+}
+
+/// Parse JSON text, reporting syntax errors as a `JsonParseError`
+/// Other errors (a value of the wrong shape) are serde_json's own
+pub(crate) fn parse_json<T: DeserializeOwned>(contents: &str) -> Result<T, Box<dyn Error>> {
+    serde_json::from_str(contents).map_err(|err| match err.classify() {
+        serde_json::error::Category::Syntax | serde_json::error::Category::Eof => {
+            JsonParseError::new(&err, contents).into()
+        }
+        _ => err.into(),
+    })
+}
 
 /// Load a JSON Schema file and build TaxonomySchema
+/// `$ref`s to other schema files are inlined (see `resolve_refs`); given a data file that
+/// embeds its schema under INLINE_SCHEMA_KEY, the embedded schema is loaded
 pub fn load_schema<P: AsRef<Path>>(path: P) -> Result<TaxonomySchema, Box<dyn Error>> {
     let contents = fs::read_to_string(&path)?;
-    let json_value: serde_json::Value = serde_json::from_str(&contents)?;
+    let mut json_value: serde_json::Value = parse_json(&contents)?;
+    if let Some(inline) = take_inline_schema(&mut json_value) {
+        json_value = inline;
+    }
+    schema_from_json(json_value, path.as_ref())
+}
 
-    let mut schema = build_schema_from_json(json_value.clone())?;
-    schema.json_schema = Some(json_value);
+/// Build a schema read from `path`, inlining its `$ref`s
+fn schema_from_json(
+    json_value: serde_json::Value,
+    path: &Path,
+) -> Result<TaxonomySchema, Box<dyn Error>> {
+    let json_value = resolve_refs(json_value, path)?;
+    Ok(build_schema_from_json(json_value)?)
+}
 
-    Ok(schema)
+/// Remove and return the schema a data document embeds
+fn take_inline_schema(document: &mut serde_json::Value) -> Option<serde_json::Value> {
+    document.as_object_mut()?.remove(INLINE_SCHEMA_KEY)
 }
 
 /// Load a data file and validate it against a provided schema
@@ -22,53 +143,1030 @@ pub fn load_data_with_schema<P: AsRef<Path>>(
     schema: &TaxonomySchema,
 ) -> Result<TaxonomyData, Box<dyn Error>> {
     let contents = fs::read_to_string(&data_path)?;
-    let data_value: serde_json::Value = serde_json::from_str(&contents)?;
+    parse_data(&contents, schema)
+}
+
+/// Read a data document from any reader (e.g. stdin) and validate it against a provided schema
+pub fn load_data_from_reader<R: Read>(
+    mut reader: R,
+    schema: &TaxonomySchema,
+) -> Result<TaxonomyData, Box<dyn Error>> {
+    let mut contents = String::new();
+    reader.read_to_string(&mut contents)?;
+    parse_data(&contents, schema)
+}
+
+fn parse_data(contents: &str, schema: &TaxonomySchema) -> Result<TaxonomyData, Box<dyn Error>> {
+    parse_data_with_progress(contents, schema, &mut |_| {})
+}
+
+/// How data files are read
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoadOptions {
+    /// Fail when an object repeats a key (see `check_duplicate_keys`) instead of keeping
+    /// the last value, as parsing otherwise does
+    pub strict_duplicate_keys: bool,
+}
+
+/// Parse a data document and validate it against a provided schema, reporting each stage
+pub fn parse_data_with_progress(
+    contents: &str,
+    schema: &TaxonomySchema,
+    on_progress: &mut ProgressFn,
+) -> Result<TaxonomyData, Box<dyn Error>> {
+    parse_data_with_options(contents, schema, &LoadOptions::default(), on_progress)
+}
+
+/// Parse a data document like `parse_data_with_progress`, read as `options` say
+pub fn parse_data_with_options(
+    contents: &str,
+    schema: &TaxonomySchema,
+    options: &LoadOptions,
+    on_progress: &mut ProgressFn,
+) -> Result<TaxonomyData, Box<dyn Error>> {
+    on_progress(&Progress::stage("Parsing JSON"));
+    let mut data_value: serde_json::Value = parse_json(contents)?;
+    if options.strict_duplicate_keys {
+        check_duplicate_keys(contents)
+            .map_err(|errors| format!("Duplicate keys:\n{}", errors.join("\n")))?;
+    }
+    take_inline_schema(&mut data_value);
 
     // Validate against JSON Schema if available
-    if let Some(ref json_schema) = schema.json_schema {
-        validate_against_schema(json_schema, &data_value)
+    if schema.json_schema().is_some() {
+        on_progress(&Progress::stage("Validating against the schema"));
+        schema
+            .validate_json(&data_value)
             .map_err(|errors| format!("Validation failed:\n{}", errors.join("\n")))?;
     }
 
     // Deserialize if validation passed
+    on_progress(&Progress::stage("Reading items"));
     let data: TaxonomyData = serde_json::from_value(data_value)?;
 
     Ok(data)
 }
 
-/// Load data file and automatically load its referenced schema
-/// Resolves schema path relative to data file directory
-pub fn load_data_with_auto_schema<P: AsRef<Path>>(
-    data_path: P,
-) -> Result<(TaxonomyData, TaxonomySchema), Box<dyn Error>> {
-    // First, read just to get the schema reference
+/// The top-level fields of a data document that locate its schema
+#[derive(Deserialize)]
+struct SchemaReference {
+    schema: Option<serde_json::Value>,
+    #[serde(rename = "$schema_inline")]
+    inline: Option<IgnoredAny>,
+}
+
+/// Resolve the schema file referenced by a data file
+/// Reads only the `schema` field; the path is resolved relative to the data file directory.
+/// A data file that embeds its schema is its own schema file.
+pub fn resolve_schema_path<P: AsRef<Path>>(data_path: P) -> Result<PathBuf, Box<dyn Error>> {
     let contents = fs::read_to_string(&data_path)?;
-    let data_value: serde_json::Value = serde_json::from_str(&contents)?;
+    schema_path_from_contents(&contents, data_path)
+}
+
+/// Resolve the schema file of a data document already read from `data_path`
+/// Skips over the items without building them, so it is cheap on large files
+pub fn schema_path_from_contents<P: AsRef<Path>>(
+    contents: &str,
+    data_path: P,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let reference: SchemaReference = parse_json(contents)?;
+    if reference.inline.is_some() {
+        return Ok(data_path.as_ref().to_path_buf());
+    }
 
-    let schema_ref = data_value
-        .get("schema")
+    let schema_ref = reference
+        .schema
+        .as_ref()
         .and_then(|v| v.as_str())
         .ok_or("Data file missing 'schema' field")?;
 
-    // Resolve schema path relative to data file
     let data_dir = data_path
         .as_ref()
         .parent()
         .ok_or("Cannot determine data file directory")?;
-    let schema_path = data_dir.join(schema_ref);
 
-    // Load schema
+    Ok(data_dir.join(schema_ref))
+}
+
+/// Load data file and automatically load its referenced schema
+/// Resolves schema path relative to data file directory; a schema the file embeds wins
+pub fn load_data_with_auto_schema<P: AsRef<Path>>(
+    data_path: P,
+) -> Result<(TaxonomyData, TaxonomySchema), Box<dyn Error>> {
+    load_data_with_progress(data_path, &mut |_| {})
+}
+
+/// Load a data file and its referenced schema like `load_data_with_auto_schema`, reporting
+/// the bytes read, then each stage
+/// The file is read once; its schema reference is found without building the items
+pub fn load_data_with_progress<P: AsRef<Path>>(
+    data_path: P,
+    on_progress: &mut ProgressFn,
+) -> Result<(TaxonomyData, TaxonomySchema), Box<dyn Error>> {
+    load_data_with_options(data_path, &LoadOptions::default(), on_progress)
+}
+
+/// Load a data file and its referenced schema like `load_data_with_progress`, read as
+/// `options` say
+pub fn load_data_with_options<P: AsRef<Path>>(
+    data_path: P,
+    options: &LoadOptions,
+    on_progress: &mut ProgressFn,
+) -> Result<(TaxonomyData, TaxonomySchema), Box<dyn Error>> {
+    let contents = read_to_string_with_progress(&data_path, on_progress)?;
+    let schema_path = schema_path_from_contents(&contents, &data_path)?;
+
+    on_progress(&Progress::stage("Loading schema"));
     let schema = load_schema(&schema_path)?;
 
-    // Load and validate data
-    let data = load_data_with_schema(&data_path, &schema)?;
+    let data = parse_data_with_options(&contents, &schema, options, on_progress)?;
+    Ok((data, schema))
+}
+
+/// Parse a version of a data file read from elsewhere (e.g. another Git revision) with the
+/// schema it embeds or references, resolved as if it were at `data_path`
+/// Returns the data, its schema and the schema file (None when the document embeds it)
+pub fn parse_data_with_auto_schema<P: AsRef<Path>>(
+    contents: &str,
+    data_path: P,
+) -> Result<(TaxonomyData, TaxonomySchema, Option<PathBuf>), Box<dyn Error>> {
+    let data_path = data_path.as_ref();
+    let mut data_value: serde_json::Value = parse_json(contents)?;
+
+    let (schema, schema_path) = match take_inline_schema(&mut data_value) {
+        Some(inline) => (schema_from_json(inline, data_path)?, None),
+        None => {
+            let schema_ref = data_value
+                .get("schema")
+                .and_then(|v| v.as_str())
+                .ok_or("Data file missing 'schema' field")?;
+            let data_dir = data_path
+                .parent()
+                .ok_or("Cannot determine data file directory")?;
+            let schema_path = data_dir.join(schema_ref);
+            (load_schema(&schema_path)?, Some(schema_path))
+        }
+    };
+
+    let data = parse_data(contents, &schema)?;
+    Ok((data, schema, schema_path))
+}
+
+/// Load a base data file with its schema, then lay overlay data files over it in order
+/// An overlay item replaces the item with the same ID (see `Item::id`) in place; other
+/// overlay items are added at the end. Overlays are validated against the base's schema.
+pub fn load_with_overlays<P: AsRef<Path>, Q: AsRef<Path>>(
+    base: P,
+    overlays: &[Q],
+) -> Result<(TaxonomyData, TaxonomySchema), Box<dyn Error>> {
+    let (mut data, schema) = load_data_with_auto_schema(&base)?;
+
+    for overlay in overlays {
+        let overlay_data = load_data_with_schema(overlay, &schema)
+            .map_err(|e| format!("Overlay {}: {}", overlay.as_ref().display(), e))?;
+        apply_overlay(&mut data.items, overlay_data.items);
+    }
 
     Ok((data, schema))
 }
 
+fn apply_overlay(items: &mut Vec<Item>, overlay: Vec<Item>) {
+    // Where each ID is first found, kept up to date as items are added
+    let mut positions: HashMap<String, usize> = HashMap::with_capacity(items.len());
+    for (index, item) in items.iter().enumerate() {
+        positions.entry(item.id().to_string()).or_insert(index);
+    }
+    for item in overlay {
+        match positions.get(item.id()) {
+            Some(&index) => items[index] = item,
+            None => {
+                positions.insert(item.id().to_string(), items.len());
+                items.push(item);
+            }
+        }
+    }
+}
+
+/// File in a schema's directory holding house-style rules for its data
+pub const RULES_FILE: &str = "rules.json";
+
+/// Load the rules that apply to a schema's data: its "x-rules", then those of a
+/// `rules.json` next to the schema file (either may be absent)
+pub fn load_rules<P: AsRef<Path>>(
+    schema_path: P,
+    schema: &TaxonomySchema,
+) -> Result<RuleSet, Box<dyn Error>> {
+    let mut rules = match schema.json_schema() {
+        Some(json_schema) => RuleSet::from_json_schema(json_schema)?,
+        None => RuleSet::default(),
+    };
+
+    let rules_path = schema_path
+        .as_ref()
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(RULES_FILE);
+    if rules_path.is_file() {
+        let contents = fs::read_to_string(&rules_path)?;
+        let value: serde_json::Value = parse_json(&contents)?;
+        rules.extend(
+            RuleSet::from_value(value).map_err(|e| format!("{}: {}", rules_path.display(), e))?,
+        );
+    }
+
+    Ok(rules)
+}
+
+/// How data files are written, so a team's files all look alike
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaveOptions {
+    /// Spaces per level of indentation; 0 writes the document on one line
+    pub indent: usize,
+    /// Field to order the items by in the file ("name" or a facet, sorted like
+    /// `sort_items`); None keeps them in the order they are in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_items_by: Option<String>,
+    /// End the file with a newline
+    pub trailing_newline: bool,
+    /// Sort the values of multi-valued facets
+    pub sort_facet_values: bool,
+}
+
+impl Default for SaveOptions {
+    fn default() -> Self {
+        Self {
+            indent: 2,
+            sort_items_by: None,
+            trailing_newline: false,
+            sort_facet_values: false,
+        }
+    }
+}
+
+impl SaveOptions {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 /// Save data to JSON file with pretty printing
 pub fn save_data<P: AsRef<Path>>(data: &TaxonomyData, path: P) -> Result<(), Box<dyn Error>> {
-    let json = serde_json::to_string_pretty(data)?;
+    save_data_with_options(data, None, path, &SaveOptions::default())
+}
+
+/// Save data with the schema's JSON Schema document embedded under INLINE_SCHEMA_KEY
+/// The file then loads without the schema file its `schema` field references
+pub fn save_data_with_inline_schema<P: AsRef<Path>>(
+    data: &TaxonomyData,
+    schema: &TaxonomySchema,
+    path: P,
+) -> Result<(), Box<dyn Error>> {
+    save_data_with_options(data, Some(schema), path, &SaveOptions::default())
+}
+
+/// Save data formatted as `options` say, embedding `inline_schema` if given
+/// (see `save_data_with_inline_schema`)
+pub fn save_data_with_options<P: AsRef<Path>>(
+    data: &TaxonomyData,
+    inline_schema: Option<&TaxonomySchema>,
+    path: P,
+    options: &SaveOptions,
+) -> Result<(), Box<dyn Error>> {
+    fs::write(path, format_data(data, inline_schema, options)?)?;
+    Ok(())
+}
+
+/// The data document as `save_data_with_options` writes it
+/// Every field is kept, known or not, and map keys are sorted, so the same data always
+/// gives the same text
+pub fn format_data(
+    data: &TaxonomyData,
+    inline_schema: Option<&TaxonomySchema>,
+    options: &SaveOptions,
+) -> Result<String, Box<dyn Error>> {
+    let inline_schema = match inline_schema {
+        Some(schema) => Some(
+            schema
+                .json_schema()
+                .ok_or("Schema has no JSON Schema document to embed")?,
+        ),
+        None => None,
+    };
+    write_document(data, inline_schema, options)
+}
+
+/// Reformat a data document's text as `format_data` would write it, keeping a schema it
+/// embeds; the schema isn't needed, nor are the items checked against it
+pub fn reformat_data(contents: &str, options: &SaveOptions) -> Result<String, Box<dyn Error>> {
+    let mut document: serde_json::Value = parse_json(contents)?;
+    let inline_schema = take_inline_schema(&mut document);
+    let data: TaxonomyData = serde_json::from_value(document)?;
+    write_document(&data, inline_schema.as_ref(), options)
+}
+
+fn write_document(
+    data: &TaxonomyData,
+    inline_schema: Option<&serde_json::Value>,
+    options: &SaveOptions,
+) -> Result<String, Box<dyn Error>> {
+    let normalized;
+    let data = if options.sort_items_by.is_some() || options.sort_facet_values {
+        let mut copy = data.clone();
+        if let Some(field) = &options.sort_items_by {
+            sort_items(&mut copy.items, field);
+        }
+        if options.sort_facet_values {
+            copy.items.iter_mut().for_each(sort_facet_values);
+        }
+        normalized = copy;
+        &normalized
+    } else {
+        data
+    };
+
+    let mut json = match inline_schema {
+        Some(json_schema) => {
+            let mut document = serde_json::to_value(data)?;
+            document
+                .as_object_mut()
+                .ok_or("Data is not a JSON object")?
+                .insert(INLINE_SCHEMA_KEY.to_string(), json_schema.clone());
+            to_json_string(&document, options.indent)?
+        }
+        None => to_json_string(data, options.indent)?,
+    };
+    if options.trailing_newline {
+        json.push('\n');
+    }
+    Ok(json)
+}
+
+/// Sort the values of an item's multi-valued facets: strings alphabetically, other values
+/// by their JSON text
+fn sort_facet_values(item: &mut Item) {
+    for value in item.facets.values_mut() {
+        if let serde_json::Value::Array(values) = value {
+            values.sort_by_cached_key(|value| match value {
+                serde_json::Value::String(text) => text.clone(),
+                other => other.to_string(),
+            });
+        }
+    }
+}
+
+/// Serialize with `indent` spaces per level, or on one line for 0
+fn to_json_string<T: Serialize + ?Sized>(value: &T, indent: usize) -> serde_json::Result<String> {
+    if indent == 0 {
+        return serde_json::to_string(value);
+    }
+    let indent = " ".repeat(indent);
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+    let mut out = Vec::new();
+    value.serialize(&mut serde_json::Serializer::with_formatter(
+        &mut out, formatter,
+    ))?;
+    Ok(String::from_utf8(out).expect("serde_json writes UTF-8"))
+}
+
+/// Where the `n`th most recent backup of a file is kept: `<file name>.<n>.bak` beside it
+pub fn backup_path<P: AsRef<Path>>(path: P, n: usize) -> PathBuf {
+    let path = path.as_ref();
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}.bak", n));
+    path.with_file_name(name)
+}
+
+/// Keep a file's current contents as its newest backup before it is overwritten, moving
+/// the older backups along and dropping any past `count`
+/// Does nothing when `count` is 0 or the file doesn't exist yet
+pub fn rotate_backups<P: AsRef<Path>>(path: P, count: usize) -> io::Result<()> {
+    let path = path.as_ref();
+    if count == 0 || !path.is_file() {
+        return Ok(());
+    }
+    let oldest = backup_path(path, count);
+    if oldest.exists() {
+        fs::remove_file(oldest)?;
+    }
+    for n in (1..count).rev() {
+        let backup = backup_path(path, n);
+        if backup.exists() {
+            fs::rename(backup, backup_path(path, n + 1))?;
+        }
+    }
+    fs::copy(path, backup_path(path, 1))?;
+    Ok(())
+}
+
+/// Save a schema's JSON Schema document with pretty printing
+/// Only the raw document is written, so changes must be synced into it first
+/// Fragments a loaded schema pulled in from other files are written inline
+pub fn save_schema<P: AsRef<Path>>(schema: &TaxonomySchema, path: P) -> Result<(), Box<dyn Error>> {
+    let json_schema = schema
+        .json_schema()
+        .ok_or("Schema has no JSON Schema document to save")?;
+    let json = serde_json::to_string_pretty(json_schema)?;
     fs::write(path, json)?;
     Ok(())
 }
+
+/// Write the taxonomy as SQL: CREATE TABLE statements for the hierarchy, facets and items,
+/// and INSERTs of their rows, in one transaction (PostgreSQL; fields are JSONB)
+/// Tables: `hierarchy_nodes` (species, genus, differentia, code, depth), `facets` (name, kind,
+/// multi, required), `facet_values` (facet, value), `items` (id, name, genus, classical_path
+/// joined with " > ", fields) and `item_facets` (item id, facet, value), one row per distinct
+/// value. Item ids are positions in the data from 1, as names may repeat
+pub fn export_sql(
+    schema: &TaxonomySchema,
+    data: &TaxonomyData,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let hierarchy = &schema.classical_hierarchy;
+    let mut facets: Vec<(&String, &FacetDefinition)> = schema.faceted_dimensions.iter().collect();
+    facets.sort_by_key(|(name, _)| *name);
+
+    writeln!(out, "-- {} ({})", schema.title, schema.schema_id)?;
+    writeln!(out, "BEGIN;")?;
+    writeln!(out)?;
+    out.write_all(SQL_TABLES.as_bytes())?;
+
+    let mut nodes = vec![[
+        sql_text(&hierarchy.root),
+        "NULL".to_string(),
+        "NULL".to_string(),
+        "NULL".to_string(),
+        "0".to_string(),
+    ]];
+    nodes.extend(hierarchy.iter_preorder().map(|(depth, node)| {
+        [
+            sql_text(&node.species),
+            sql_text(&node.genus),
+            sql_text(&node.differentia),
+            node.code.as_deref().map_or("NULL".to_string(), sql_text),
+            depth.to_string(),
+        ]
+    }));
+    write_inserts(
+        out,
+        "hierarchy_nodes (species, genus, differentia, code, depth)",
+        &nodes,
+    )?;
+
+    let facet_rows: Vec<[String; 4]> = facets
+        .iter()
+        .map(|(name, definition)| {
+            [
+                sql_text(name),
+                sql_text(&definition.kind.to_string()),
+                definition.multi.to_string().to_uppercase(),
+                definition.required.to_string().to_uppercase(),
+            ]
+        })
+        .collect();
+    write_inserts(out, "facets (name, kind, multi, required)", &facet_rows)?;
+
+    let value_rows: Vec<[String; 2]> = facets
+        .iter()
+        .flat_map(|(name, definition)| {
+            definition
+                .allowed_values
+                .iter()
+                .map(|value| [sql_text(name), sql_text(value)])
+        })
+        .collect();
+    write_inserts(out, "facet_values (facet, value)", &value_rows)?;
+
+    // Items are keyed by their position (from 1): names need not be unique
+    let item_rows: Vec<[String; 5]> = data
+        .items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let fields = if item.extra.is_empty() {
+                "NULL".to_string()
+            } else {
+                let json = serde_json::to_string(&item.extra).unwrap_or_default();
+                sql_text(&json)
+            };
+            [
+                (index + 1).to_string(),
+                sql_text(&item.name),
+                item.classical_path
+                    .last()
+                    .map_or("NULL".to_string(), |genus| sql_text(genus)),
+                sql_text(&item.classical_path.join(" > ")),
+                fields,
+            ]
+        })
+        .collect();
+    write_inserts(
+        out,
+        "items (id, name, genus, classical_path, fields)",
+        &item_rows,
+    )?;
+
+    let item_facet_rows: Vec<[String; 3]> = data
+        .items
+        .iter()
+        .enumerate()
+        .flat_map(|(index, item)| {
+            let mut names: Vec<&String> = item.facets.keys().collect();
+            names.sort();
+            names.into_iter().flat_map(move |facet| {
+                // A value given twice is one row
+                let mut values = item.get_facet_as_vec(facet);
+                let mut seen = HashSet::new();
+                values.retain(|value| seen.insert(value.clone()));
+                values
+                    .into_iter()
+                    .map(move |value| [(index + 1).to_string(), sql_text(facet), sql_text(&value)])
+            })
+        })
+        .collect();
+    write_inserts(out, "item_facets (item, facet, value)", &item_facet_rows)?;
+
+    writeln!(out, "COMMIT;")
+}
+
+const SQL_TABLES: &str = "\
+CREATE TABLE hierarchy_nodes (
+    species TEXT PRIMARY KEY,
+    genus TEXT REFERENCES hierarchy_nodes (species),
+    differentia TEXT,
+    code TEXT,
+    depth INTEGER NOT NULL
+);
+
+CREATE TABLE facets (
+    name TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,
+    multi BOOLEAN NOT NULL,
+    required BOOLEAN NOT NULL
+);
+
+CREATE TABLE facet_values (
+    facet TEXT NOT NULL REFERENCES facets (name),
+    value TEXT NOT NULL,
+    PRIMARY KEY (facet, value)
+);
+
+CREATE TABLE items (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    genus TEXT REFERENCES hierarchy_nodes (species),
+    classical_path TEXT NOT NULL,
+    fields JSONB
+);
+
+CREATE TABLE item_facets (
+    item INTEGER NOT NULL REFERENCES items (id),
+    facet TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (item, facet, value)
+);
+
+";
+
+/// One multi-row INSERT of `rows` (SQL literals) into `table`; nothing when there are none
+fn write_inserts<const N: usize>(
+    out: &mut dyn Write,
+    table: &str,
+    rows: &[[String; N]],
+) -> io::Result<()> {
+    if rows.is_empty() {
+        return Ok(());
+    }
+    writeln!(out, "INSERT INTO {} VALUES", table)?;
+    for (i, row) in rows.iter().enumerate() {
+        let end = if i + 1 == rows.len() { ";" } else { "," };
+        writeln!(out, "    ({}){}", row.join(", "), end)?;
+    }
+    writeln!(out)
+}
+
+/// A single-quoted SQL string literal
+fn sql_text(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+/// Read the terms of a controlled vocabulary file
+/// CSV (.csv, .txt) and TSV (.tsv) give value, label, description columns; SKOS is read
+/// from RDF/XML (.rdf, .xml, .skos)
+pub fn read_vocabulary<P: AsRef<Path>>(path: P) -> Result<Vec<FacetValueNode>, Box<dyn Error>> {
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path)?;
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_lowercase);
+
+    match extension.as_deref() {
+        Some("csv" | "txt") => parse_csv_vocabulary(&contents, b','),
+        Some("tsv") => parse_csv_vocabulary(&contents, b'\t'),
+        Some("rdf" | "xml" | "skos") => parse_skos_vocabulary(&contents),
+        _ => Err(format!(
+            "Unsupported vocabulary format '{}' (expected .csv, .tsv, .txt, .rdf, .xml or .skos)",
+            path.display()
+        )
+        .into()),
+    }
+}
+
+/// Read a file of items to import with the importer `ImporterRegistry::default()` detects
+/// from its extension and contents: CSV, TSV, JSON, and spreadsheets with the `xlsx` feature
+/// CSV, TSV and spreadsheets need a header row naming the columns
+pub fn read_import_table<P: AsRef<Path>>(path: P) -> Result<ImportTable, Box<dyn Error>> {
+    read_import_table_with_progress(path, &mut |_| {})
+}
+
+/// Read a file of items to import like `read_import_table`, reporting the bytes read
+pub fn read_import_table_with_progress<P: AsRef<Path>>(
+    path: P,
+    on_progress: &mut ProgressFn,
+) -> Result<ImportTable, Box<dyn Error>> {
+    let path = path.as_ref();
+    let contents = read_with_progress(path, on_progress)?;
+    on_progress(&Progress::stage("Reading rows"));
+    ImporterRegistry::default().read(Some(path), &contents)
+}
+
+/// Load a controlled vocabulary into a faceted dimension's allowed values
+/// Duplicate terms are skipped and clashing terms are reported, not added
+pub fn import_vocabulary<P: AsRef<Path>>(
+    path: P,
+    dimension: &mut FacetDefinition,
+) -> Result<VocabularyImport, Box<dyn Error>> {
+    let terms = read_vocabulary(path)?;
+    Ok(dimension.merge_values(terms))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_export_sql() {
+        let template = crate::templates::find_template("beverages").unwrap();
+        let mut data = template.empty_data("schema.json");
+        data.items.push(
+            crate::builder::ItemBuilder::new("Barista's Latte")
+                .path(["Beverage", "Hot Beverage", "Coffee"])
+                .facet_values("temperature", ["hot", "warm"])
+                .build(),
+        );
+
+        let mut out = Vec::new();
+        export_sql(&template.schema(), &data, &mut out).unwrap();
+        let sql = String::from_utf8(out).unwrap();
+
+        assert!(sql.starts_with("-- "));
+        assert!(sql.contains("CREATE TABLE item_facets ("));
+        assert!(sql.contains("    ('Beverage', NULL, NULL, NULL, 0),"));
+        assert!(sql.contains(
+            "    (1, 'Barista''s Latte', 'Coffee', 'Beverage > Hot Beverage > Coffee', NULL);"
+        ));
+        assert!(sql.contains("    (1, 'temperature', 'hot'),"));
+        assert!(sql.contains("    (1, 'temperature', 'warm');"));
+        assert!(sql.trim_end().ends_with("COMMIT;"));
+    }
+
+    #[test]
+    fn test_export_sql_keys_are_unique() {
+        let template = crate::templates::find_template("beverages").unwrap();
+        let mut data = template.empty_data("schema.json");
+        // Two items of one name (which loads, and only validation flags) and a repeated value
+        for values in [["hot", "hot"], ["cold", "warm"]] {
+            data.items.push(
+                crate::builder::ItemBuilder::new("Latte")
+                    .path(["Beverage", "Hot Beverage", "Coffee"])
+                    .facet_values("temperature", values)
+                    .build(),
+            );
+        }
+
+        let mut out = Vec::new();
+        export_sql(&template.schema(), &data, &mut out).unwrap();
+        let sql = String::from_utf8(out).unwrap();
+        let rows = |table: &str| -> Vec<String> {
+            sql.split(&format!("INSERT INTO {} (", table))
+                .nth(1)
+                .unwrap()
+                .lines()
+                .skip(1)
+                .take_while(|line| !line.is_empty())
+                .map(|line| line.trim().trim_end_matches([',', ';']).to_string())
+                .collect()
+        };
+
+        let items = rows("items");
+        let ids: HashSet<&str> = items
+            .iter()
+            .map(|row| row.split(',').next().unwrap())
+            .collect();
+        assert_eq!(items.len(), 2);
+        assert_eq!(ids.len(), 2);
+
+        let item_facets = rows("item_facets");
+        assert_eq!(
+            item_facets,
+            vec![
+                "(1, 'temperature', 'hot')",
+                "(2, 'temperature', 'cold')",
+                "(2, 'temperature', 'warm')",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_save_data_is_byte_stable() {
+        let (schema, mut data) =
+            crate::testgen::random_taxonomy(3, &crate::testgen::SizeParams::items(50));
+        for (index, item) in data.items.iter_mut().enumerate() {
+            for field in ["sku", "brand", "notes", "aisle", "supplier"] {
+                item.extra.insert(field.to_string(), json!(index));
+            }
+            item.labels.insert("de".to_string(), item.name.clone());
+            item.labels.insert("fr".to_string(), item.name.clone());
+        }
+        data.extra.insert("version".to_string(), json!(2));
+        data.extra.insert("author".to_string(), json!("tests"));
+
+        let dir = std::env::temp_dir().join(format!("taxstud-stable-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("data.json");
+        save_data(&data, &path).unwrap();
+        let first = fs::read_to_string(&path).unwrap();
+
+        // Load and save again: every map is rebuilt, in whatever order the hasher picks
+        let loaded = load_data_with_schema(&path, &schema).unwrap();
+        save_data(&loaded, &path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), first);
+
+        // Maps filled in the opposite order serialize the same
+        let mut reversed = data.clone();
+        for item in &mut reversed.items {
+            let mut keys: Vec<String> = item.facets.keys().cloned().collect();
+            keys.sort();
+            item.facets = keys
+                .into_iter()
+                .rev()
+                .map(|key| (key.clone(), item.facets[&key].clone()))
+                .collect();
+        }
+        assert_eq!(serde_json::to_string_pretty(&reversed).unwrap(), first);
+
+        let facets = &first[first.find("\"facets\"").unwrap()..];
+        assert!(facets.find("\"added\"").unwrap() < facets.find("\"temperature\"").unwrap());
+        assert!(first.find("\"author\"").unwrap() < first.find("\"version\"").unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_round_trip_keeps_unknown_fields() {
+        let document = json!({
+            "schema": "schema.json",
+            "revision": {"by": "someone", "at": 3},
+            "items": [
+                {
+                    "name": "Zebra Latte",
+                    "classical_path": ["Beverage"],
+                    "facets": {},
+                    "sku": "Z-1",
+                    "relations": [{"type": "see_also", "item": "a", "weight": 0.5}],
+                    "attachments": [{"path_or_url": "z.png", "checksum": "abc"}]
+                },
+                {"name": "Americano", "classical_path": ["Beverage"], "facets": {}}
+            ]
+        });
+        let data: TaxonomyData = serde_json::from_value(document.clone()).unwrap();
+        let saved = format_data(&data, None, &SaveOptions::default()).unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&saved).unwrap(),
+            document
+        );
+        assert!(saved.starts_with("{\n  \"schema\""));
+        assert!(!saved.ends_with('\n'));
+
+        let options = SaveOptions {
+            indent: 4,
+            sort_items_by: Some("name".to_string()),
+            trailing_newline: true,
+            ..SaveOptions::default()
+        };
+        let saved = format_data(&data, None, &options).unwrap();
+        assert!(saved.starts_with("{\n    \"schema\""));
+        assert!(saved.ends_with("}\n"));
+        assert!(saved.find("Americano").unwrap() < saved.find("Zebra Latte").unwrap());
+        // Sorting formats a copy; the data keeps its order
+        assert_eq!(data.items[0].name, "Zebra Latte");
+
+        let compact = SaveOptions {
+            indent: 0,
+            ..SaveOptions::default()
+        };
+        let saved = format_data(&data, None, &compact).unwrap();
+        assert!(!saved.contains('\n'));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&saved).unwrap(),
+            document
+        );
+    }
+
+    #[test]
+    fn test_reformat_data() {
+        let messy = r#"{"items":[
+            {"name":"Mocha","facets":{"flavor":["vanilla","cocoa"],"temperature":"hot"},"classical_path":["Beverage"]},
+            {"facets":{},"name":"Chai","classical_path":["Beverage"]}],
+          "$schema_inline": {"title": "Inline", "type": "object"},
+          "schema":"schema.json"}"#;
+        let options = SaveOptions {
+            sort_items_by: Some("name".to_string()),
+            sort_facet_values: true,
+            trailing_newline: true,
+            ..SaveOptions::default()
+        };
+        let formatted = reformat_data(messy, &options).unwrap();
+        assert!(formatted.find("Chai").unwrap() < formatted.find("Mocha").unwrap());
+        assert!(formatted.find("cocoa").unwrap() < formatted.find("vanilla").unwrap());
+        let document: serde_json::Value = serde_json::from_str(&formatted).unwrap();
+        assert_eq!(document[INLINE_SCHEMA_KEY]["title"], "Inline");
+        assert_eq!(document["schema"], "schema.json");
+
+        // Formatting is idempotent
+        assert_eq!(reformat_data(&formatted, &options).unwrap(), formatted);
+        assert!(reformat_data("{\"items\": [", &options).is_err());
+    }
+
+    #[test]
+    fn test_parse_errors_are_located() {
+        let contents = "{\n  \"schema\": \"schema.json\",\n  \"items\": [{\"name\": \"Latte\",, \"facets\": {}}]\n}";
+        let err = parse_json::<serde_json::Value>(contents).unwrap_err();
+        let err = err.downcast_ref::<JsonParseError>().unwrap();
+        assert_eq!((err.line, err.column), (3, 30));
+        assert_eq!(err.message, "key must be a string");
+        assert_eq!(
+            err.snippet,
+            "3 |   \"items\": [{\"name\": \"Latte\",, \"facets\": {}}]\n  |                              ^"
+        );
+        assert!(err
+            .to_string()
+            .starts_with("Invalid JSON at line 3, column 30: key must be a string\n"));
+
+        // Long lines are cut around the error
+        let long = format!("[{}oops, {}2]", "1, ".repeat(100), "2, ".repeat(100));
+        let err = parse_json::<serde_json::Value>(&long).unwrap_err();
+        let snippet = &err.downcast_ref::<JsonParseError>().unwrap().snippet;
+        let (line, caret) = snippet.split_once('\n').unwrap();
+        assert!(line.starts_with("1 | …") && line.ends_with('…'));
+        assert_eq!(line.chars().nth(caret.len() - 1), Some('o'));
+
+        // Running out of text points past the end of the last line
+        let err = parse_json::<serde_json::Value>("{\"items\": [").unwrap_err();
+        let err = err.downcast_ref::<JsonParseError>().unwrap();
+        assert_eq!(err.snippet, "1 | {\"items\": [\n  |           ^");
+
+        // Well-formed JSON of the wrong shape isn't a parse error
+        let err = parse_json::<SchemaReference>("[]").err().unwrap();
+        assert!(err.downcast_ref::<JsonParseError>().is_none());
+    }
+
+    #[test]
+    fn test_strict_duplicate_keys() {
+        let template = crate::templates::find_template("beverages").unwrap();
+        let schema = template.schema();
+        let contents = r#"{"schema": "schema.json", "items": [{
+            "name": "Latte",
+            "classical_path": ["Beverage", "Hot Beverage", "Coffee"],
+            "facets": {"temperature": "cold", "temperature": "hot"}
+        }]}"#;
+
+        // By default the last value wins
+        let data = parse_data_with_options(contents, &schema, &LoadOptions::default(), &mut |_| {})
+            .unwrap();
+        assert_eq!(data.items[0].facets["temperature"], json!("hot"));
+
+        let strict = LoadOptions {
+            strict_duplicate_keys: true,
+        };
+        let err = parse_data_with_options(contents, &schema, &strict, &mut |_| {}).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Duplicate keys:\nDuplicate key 'temperature' in /items/0/facets"
+        );
+
+        // Syntax errors are still located
+        let err =
+            parse_data_with_options("{\"items\": [", &schema, &strict, &mut |_| {}).unwrap_err();
+        assert!(err.downcast_ref::<JsonParseError>().is_some());
+    }
+
+    #[test]
+    fn test_rotate_backups() {
+        let dir = std::env::temp_dir().join(format!("taxstud-backups-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("data.json");
+        assert_eq!(backup_path(&path, 2), dir.join("data.json.2.bak"));
+
+        for version in ["one", "two", "three"] {
+            rotate_backups(&path, 2).unwrap();
+            fs::write(&path, version).unwrap();
+        }
+
+        let read = |n| fs::read_to_string(backup_path(&path, n)).unwrap();
+        assert_eq!(read(1), "two");
+        assert_eq!(read(2), "one");
+        rotate_backups(&path, 2).unwrap();
+        assert_eq!(read(2), "two");
+        assert!(!backup_path(&path, 3).exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_apply_overlay() {
+        let item = |name: &str| crate::builder::ItemBuilder::new(name).build();
+        let mut items = vec![item("Latte"), item("Mocha"), item("Latte")];
+        // An item the overlay adds is replaced by a later one of the same ID; of two base
+        // items with one ID, the first is replaced
+        let mut cold = item("Cold Brew");
+        cold.extra.insert("id".to_string(), json!("Latte"));
+        apply_overlay(&mut items, vec![item("Freddo"), cold, item("Freddo")]);
+        let names: Vec<&str> = items.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, vec!["Cold Brew", "Mocha", "Latte", "Freddo"]);
+    }
+
+    #[test]
+    fn test_load_with_overlays() {
+        let dir = std::env::temp_dir().join(format!("taxstud-overlay-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let write = |name: &str, value: serde_json::Value| {
+            fs::write(dir.join(name), value.to_string()).unwrap();
+            dir.join(name)
+        };
+        write(
+            "schema.json",
+            json!({
+                "title": "Beverages",
+                "classical_hierarchy": {
+                    "root": "Beverage",
+                    "children": [
+                        {"genus": "Beverage", "species": "Coffee", "differentia": "brewed from beans"}
+                    ]
+                },
+                "faceted_dimensions": {"temperature": ["hot", "iced"]}
+            }),
+        );
+        let item = |name: &str, id: &str, temperature: &str| {
+            json!({"name": name, "id": id, "classical_path": ["Beverage", "Coffee"],
+                   "facets": {"temperature": temperature}})
+        };
+        let base = write(
+            "master.json",
+            json!({"schema": "schema.json", "items": [
+                item("Latte", "C-1", "hot"),
+                item("Mocha", "C-2", "hot"),
+            ]}),
+        );
+        let eu = write(
+            "eu.json",
+            json!({"schema": "schema.json", "items": [
+                item("Caffè Latte", "C-1", "hot"),
+                item("Freddo", "C-3", "iced"),
+            ]}),
+        );
+        let us = write(
+            "us.json",
+            json!({"schema": "schema.json", "items": [item("Mocha", "C-2", "iced")]}),
+        );
+
+        let (data, _) = load_with_overlays(&base, &[eu.clone(), us]).unwrap();
+        let items: Vec<(&str, String)> = data
+            .items
+            .iter()
+            .map(|item| {
+                let temperature = item.get_facet_as_string("temperature").unwrap();
+                (item.name.as_str(), temperature)
+            })
+            .collect();
+        assert_eq!(
+            items,
+            vec![
+                ("Caffè Latte", "hot".to_string()),
+                ("Mocha", "iced".to_string()),
+                ("Freddo", "iced".to_string()),
+            ]
+        );
+
+        let bad = write(
+            "bad.json",
+            json!({"schema": "schema.json", "items": [{"name": "Cold Brew"}]}),
+        );
+        let err = load_with_overlays(&base, &[eu, bad.clone()]).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with(&format!("Overlay {}", bad.display())));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,21 +1,148 @@
 // Public modules
+pub mod analysis;
+pub mod attachments;
+pub mod builder;
+pub mod diff;
+pub mod editing;
+pub mod export;
+pub mod facets;
+pub mod fields;
 pub mod filtering;
+#[cfg(feature = "git")]
+pub mod git;
 pub mod grouping;
+pub mod hierarchy;
+pub mod html;
+pub mod import;
 pub mod io;
+pub mod localization;
 pub mod models;
+pub mod progress;
+pub mod project;
+pub mod refs;
+pub mod relations;
+#[cfg(feature = "remote")]
+pub mod remote;
+pub mod render;
+pub mod rules;
 pub mod schema;
 pub mod schema_validation;
+#[cfg(feature = "scripting")]
+pub mod script;
+pub mod search_index;
 pub mod sorting;
+pub mod split;
+pub mod store;
+#[cfg(feature = "sync")]
+pub mod sync;
+pub mod templates;
+pub mod testgen;
 pub mod validation;
+pub mod validators;
+pub mod viz;
+pub mod vocabulary;
 
 // Re-export commonly used types for convenience
-pub use filtering::{apply_filters, has_filters, matches_filters, parse_facet_filters};
-pub use grouping::{get_sorted_group_names, group_items_by_facet};
-pub use io::{load_data_with_auto_schema, load_data_with_schema, load_schema, save_data};
+pub use analysis::{
+    facet_distribution, fuzzy_find_items, fuzzy_score, overview, similar_items, similarity,
+    suggest_facets, suggest_path, FacetCoverage, FacetDistribution, SimilarItem, SimilarityWeights,
+    TaxonomyOverview, ValueCount,
+};
+pub use builder::{HierarchyBuilder, ItemBuilder, NodeBuilder, TaxonomySchemaBuilder};
+pub use diff::{
+    diff_items, diff_schemas, diff_taxonomies, Change, ChangeKind, ItemDiff, MergeConflict,
+    MergeField, MergeResult, Resolution, TaxonomyDiff,
+};
+pub use editing::{
+    apply_renames, batch_rename, flag_taken_names, reclassify, rename_facet, rename_facet_value,
+    unique_item_name, Rename, RenamePattern,
+};
+pub use export::{ExportOptions, Exporter, ExporterRegistry};
+pub use facets::is_iso_date;
+pub use fields::{
+    check_item_fields, is_uri, is_web_url, item_links, looks_like_url, malformed_urls, ItemLink,
+};
+pub use filtering::{
+    apply_filters, apply_filters_in_hierarchy, apply_filters_with_schema,
+    describe_facet_predicates, filter_indices, filter_indices_with_schema, has_filters,
+    matches_filters, matches_filters_in_hierarchy, matches_filters_with_schema, parse_facet_filter,
+    parse_facet_filters,
+};
+#[cfg(feature = "git")]
+pub use git::{ConflictVersions, GitFile, Revision};
+pub use grouping::{
+    get_sorted_group_names, group_indices_by_facet, group_indices_with_options,
+    group_items_by_facet, group_items_with_options, summarize_groups, GroupKey, GroupOptions,
+    GroupSummary, MissingGroup, NumericSummary, UNSPECIFIED_GROUP,
+};
+pub use hierarchy::{
+    complete_path, flatten_facet_values, rename_node_cascade, HierarchyMerge, MergeOptions,
+    Preorder,
+};
+pub use html::{write_html, write_html_with_progress};
+pub use import::{
+    ColumnTarget, ImportReport, ImportRow, ImportTable, Importer, ImporterRegistry, ItemImport,
+};
+pub use io::{
+    backup_path, export_sql, format_data, import_vocabulary, load_data_from_reader,
+    load_data_with_auto_schema, load_data_with_options, load_data_with_progress,
+    load_data_with_schema, load_rules, load_schema, load_with_overlays,
+    parse_data_with_auto_schema, parse_data_with_options, parse_data_with_progress,
+    read_import_table, read_import_table_with_progress, read_vocabulary, reformat_data,
+    resolve_schema_path, rotate_backups, save_data, save_data_with_inline_schema,
+    save_data_with_options, save_schema, schema_path_from_contents, JsonParseError, LoadOptions,
+    SaveOptions, INLINE_SCHEMA_KEY, RULES_FILE,
+};
+pub use localization::localized_label;
 pub use models::{
-    ClassicalHierarchy, Filters, HierarchyNode, HybridTaxonomy, Item, TaxonomyData, TaxonomySchema,
+    Attachment, ClassicalHierarchy, CompareOp, ConstraintViolation, DisplayHint, FacetCondition,
+    FacetConstraint, FacetDefinition, FacetKind, FacetPredicate, FacetValueInfo, FacetValueNode,
+    FieldKind, FilterOptions, Filters, HierarchyNode, HybridTaxonomy, Item, ItemField, Provenance,
+    Relation, TaxonomyData, TaxonomySchema, VocabularyImport, DEPRECATED_FIELD, SOURCE_FIELD,
+};
+pub use progress::{
+    read_to_string_with_progress, read_with_progress, report_step, Progress, ProgressFn,
+    ProgressReader,
+};
+pub use project::{Project, ProjectSettings, PROJECT_EXTENSION};
+pub use refs::resolve_refs;
+pub use relations::{check_relations, BrokenRelation};
+#[cfg(feature = "remote")]
+pub use remote::{is_url, Freshness, RemoteCache, RemoteTaxonomy};
+pub use render::template::{render_report, render_template, report_context, ReportOptions};
+pub use rules::{Rule, RuleCheck, RuleReport, RuleSet, Severity};
+pub use schema::{
+    add_facet_values_to_json, build_schema_from_json, extract_classical_hierarchy,
+    extract_constraints, extract_display_hints, extract_facet_value_trees,
+    extract_faceted_dimensions, extract_item_fields,
 };
-pub use schema::{build_schema_from_json, extract_classical_hierarchy, extract_faceted_dimensions};
 pub use schema_validation::validate_against_schema;
-pub use sorting::{normalize_for_sorting, sort_items, strip_leading_articles};
-pub use validation::{validate_path_exists, validate_taxonomy};
+#[cfg(feature = "scripting")]
+pub use script::{run_script, ScriptReport};
+pub use search_index::{search_document, search_index_mapping, write_bulk_ndjson};
+pub use sorting::{
+    normalize_for_sorting, sort_indices_with_options, sort_items, sort_items_with_options,
+    sort_items_with_schema, strip_diacritics, strip_leading_articles, SortOptions,
+    DEFAULT_ARTICLES,
+};
+pub use split::{split_by_genus, write_split, Split, SplitPart};
+pub use store::{ChangeEvent, Subscription, TaxonomyStore};
+#[cfg(feature = "sync")]
+pub use sync::{
+    sign, sync_payload, SyncConfig, SyncPayload, SyncPublisher, SyncRequest, SIGNATURE_HEADER,
+    SYNC_FILE,
+};
+pub use templates::{
+    available_templates, find_template, ItemTemplate, TaxonomyTemplate, DEFAULT_TEMPLATE,
+};
+pub use testgen::{random_taxonomy, SizeParams};
+pub use validation::{
+    check_constraints, check_duplicate_keys, constraint_violations, lint_data, validate_data,
+    validate_data_with_progress, validate_path_exists, validate_taxonomy,
+};
+pub use validators::{register_validator, ValidationIssue, Validator, ValidatorRegistry};
+pub use viz::{
+    render_hierarchy_svg, sunburst, treemap, write_sunburst_svg, write_treemap_svg, HierarchyStyle,
+    SunburstArc, TreemapRect,
+};
+pub use vocabulary::write_skos;
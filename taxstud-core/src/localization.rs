@@ -0,0 +1,119 @@
+use crate::models::{ClassicalHierarchy, FacetDefinition, HierarchyNode, Item, TaxonomySchema};
+use std::collections::{BTreeSet, HashMap};
+
+/// Label for a language from a map keyed by language tag
+/// A regional tag falls back to its base language ("de-AT" → "de"); None without a language
+pub fn localized_label<'a>(
+    labels: &'a HashMap<String, String>,
+    lang: Option<&str>,
+) -> Option<&'a str> {
+    let lang = lang?;
+    labels
+        .get(lang)
+        .or_else(|| {
+            let (base, _) = lang.split_once(['-', '_'])?;
+            labels.get(base)
+        })
+        .map(String::as_str)
+}
+
+impl Item {
+    /// Item name in a language, falling back to the canonical name
+    pub fn display_name(&self, lang: Option<&str>) -> &str {
+        localized_label(&self.labels, lang).unwrap_or(&self.name)
+    }
+}
+
+impl HierarchyNode {
+    /// Species name in a language, falling back to the canonical name
+    pub fn display_name(&self, lang: Option<&str>) -> &str {
+        localized_label(&self.labels, lang).unwrap_or(&self.species)
+    }
+}
+
+impl ClassicalHierarchy {
+    /// Name of the root or a species in a language, falling back to the name itself
+    pub fn display_name<'a>(&'a self, name: &'a str, lang: Option<&str>) -> &'a str {
+        if name == self.root {
+            return localized_label(&self.labels, lang).unwrap_or(name);
+        }
+        self.find_node(name)
+            .map_or(name, |node| node.display_name(lang))
+    }
+}
+
+impl FacetDefinition {
+    /// Dimension name in a language, falling back to the canonical name
+    pub fn display_name<'a>(&'a self, name: &'a str, lang: Option<&str>) -> &'a str {
+        localized_label(&self.labels, lang).unwrap_or(name)
+    }
+}
+
+impl TaxonomySchema {
+    /// Every language with a label somewhere in the hierarchy or the facet dimensions, sorted
+    pub fn languages(&self) -> Vec<String> {
+        let hierarchy = &self.classical_hierarchy;
+        let mut languages = BTreeSet::new();
+        languages.extend(hierarchy.labels.keys());
+        for (_, node) in hierarchy.iter_preorder() {
+            languages.extend(node.labels.keys());
+        }
+        for definition in self.faceted_dimensions.values() {
+            languages.extend(definition.labels.keys());
+            for info in definition.value_info.values() {
+                languages.extend(info.labels.keys());
+            }
+        }
+        languages.into_iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{HierarchyBuilder, ItemBuilder, TaxonomySchemaBuilder};
+    use serde_json::json;
+
+    #[test]
+    fn test_labels_fall_back_to_canonical_names() {
+        let hierarchy = HierarchyBuilder::new()
+            .root("Beverage")
+            .root_label("de", "Getränk")
+            .child("Hot Beverage", "served heated", |c| {
+                c.label("de", "Heißgetränk")
+            })
+            .build();
+        assert_eq!(hierarchy.display_name("Beverage", Some("de-AT")), "Getränk");
+        assert_eq!(
+            hierarchy.display_name("Hot Beverage", Some("de")),
+            "Heißgetränk"
+        );
+        assert_eq!(
+            hierarchy.display_name("Hot Beverage", Some("fr")),
+            "Hot Beverage"
+        );
+        assert_eq!(hierarchy.display_name("Hot Beverage", None), "Hot Beverage");
+
+        let latte = ItemBuilder::new("Latte").label("de", "Milchkaffee").build();
+        assert_eq!(latte.display_name(Some("de")), "Milchkaffee");
+        assert_eq!(latte.display_name(Some("en")), "Latte");
+
+        let temperature: FacetDefinition = serde_json::from_value(json!({
+            "labels": {"de": "Temperatur"},
+            "allowed_values": [{"value": "hot", "label": "Hot!", "labels": {"fr": "chaud"}}]
+        }))
+        .unwrap();
+        assert_eq!(
+            temperature.display_name("temperature", Some("de")),
+            "Temperatur"
+        );
+        assert_eq!(temperature.label_for("hot", Some("fr")), "chaud");
+        assert_eq!(temperature.label_for("hot", Some("de")), "Hot!");
+
+        let schema = TaxonomySchemaBuilder::new("Drinks")
+            .hierarchy(hierarchy)
+            .facet_definition("temperature", temperature)
+            .build();
+        assert_eq!(schema.languages(), vec!["de", "fr"]);
+    }
+}
@@ -1,13 +1,45 @@
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use crate::facets::is_iso_date;
+use crate::hierarchy::flatten_facet_values;
+use crate::schema_validation::JsonSchemaDocument;
+use crate::sorting::strip_diacritics;
+use serde::{Deserialize, Serialize, Serializer};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Serialize a map with its keys sorted, so a file saved twice is the same byte for byte
+/// and diffs show only real changes
+fn sorted<S, V>(map: &HashMap<String, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    V: Serialize,
+{
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
+}
+
+/// `sorted` for a map of maps, sorting the keys at both levels
+fn sorted_nested<S, V>(
+    map: &HashMap<String, HashMap<String, V>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    V: Serialize,
+{
+    map.iter()
+        .map(|(key, inner)| (key, inner.iter().collect::<BTreeMap<_, _>>()))
+        .collect::<BTreeMap<_, _>>()
+        .serialize(serializer)
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct HybridTaxonomy {
     pub taxonomy_description: Option<String>,
     pub classical_hierarchy: ClassicalHierarchy,
-    pub faceted_dimensions: HashMap<String, Vec<String>>,
+    #[serde(serialize_with = "sorted")]
+    pub faceted_dimensions: HashMap<String, FacetDefinition>,
     pub example_items: Option<Vec<Item>>,
-    #[serde(flatten)]
+    #[serde(flatten, serialize_with = "sorted")]
     pub extra: HashMap<String, serde_json::Value>,
 }
 
@@ -15,6 +47,13 @@ pub struct HybridTaxonomy {
 pub struct ClassicalHierarchy {
     pub root: String,
     pub children: Option<Vec<HierarchyNode>>,
+    /// Translations of the root name, keyed by language tag
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "sorted"
+    )]
+    pub labels: HashMap<String, String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -23,28 +62,170 @@ pub struct HierarchyNode {
     pub species: String,
     pub differentia: String,
     pub children: Option<Vec<HierarchyNode>>,
+    /// Other names the species is known by
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub synonyms: Vec<String>,
+    /// Short identifier, e.g. a classification code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// Identifiers in external classification systems, keyed by system (e.g. "NAICS" → "311920")
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "sorted"
+    )]
+    pub external_ids: HashMap<String, String>,
+    /// Translations of the species name, keyed by language tag (e.g. "de")
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "sorted"
+    )]
+    pub labels: HashMap<String, String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Item {
     pub name: String,
     pub classical_path: Vec<String>,
+    #[serde(serialize_with = "sorted")]
     pub facets: HashMap<String, serde_json::Value>,
-    #[serde(flatten)]
+    /// Translations of the item name, keyed by language tag
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "sorted"
+    )]
+    pub labels: HashMap<String, String>,
+    /// Links to other items or hierarchy nodes, in this data file or another
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relations: Vec<Relation>,
+    /// Images and other files about the item, e.g. product photos
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+    #[serde(flatten, serialize_with = "sorted")]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Additional field marking an item deprecated (`true`): kept in the file but hidden from
+/// queries and exports unless asked for
+pub const DEPRECATED_FIELD: &str = "deprecated";
+
+/// Additional field of an item holding its `Provenance`
+pub const SOURCE_FIELD: &str = "source";
+
+/// Where an item brought in by an importer came from
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Provenance {
+    /// File the item was imported from
+    pub origin_file: String,
+    /// When it was imported, in seconds since the Unix epoch
+    pub imported_at: i64,
+    /// What imported it, e.g. "Taxonomy Studio import"
+    pub importer: String,
+}
+
+impl Provenance {
+    /// Provenance of an item being imported from `origin_file` now
+    pub fn now(origin_file: impl Into<String>, importer: impl Into<String>) -> Self {
+        let imported_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs() as i64);
+        Provenance {
+            origin_file: origin_file.into(),
+            imported_at,
+            importer: importer.into(),
+        }
+    }
+}
+
+/// A link from an item to an item (by ID) or a hierarchy node (by code)
+/// e.g. `{"type": "see_also", "taxonomy": "tea.json", "item": "GREEN-01"}`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Relation {
+    /// Kind of link, e.g. "see_also" or "replaces"
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Data file holding the target, relative to the item's file; omitted for the same file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub taxonomy: Option<String>,
+    /// ID of the target item (see `Item::id`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item: Option<String>,
+    /// Code of the target hierarchy node
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>,
+    /// Fields Taxonomy Studio doesn't know, kept as they are
+    #[serde(flatten, serialize_with = "sorted")]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// A file or web resource attached to an item
+/// e.g. `{"path_or_url": "photos/mug.jpg", "kind": "image", "caption": "Front view"}`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Attachment {
+    /// A URL, or a local path relative to the item's data file
+    pub path_or_url: String,
+    /// Kind of resource, e.g. "image" or "document"; images are shown as thumbnails
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+    /// Fields Taxonomy Studio doesn't know, kept as they are
+    #[serde(flatten, serialize_with = "sorted")]
     pub extra: HashMap<String, serde_json::Value>,
 }
 
 impl Item {
+    /// The item's `id` field, or its name when it has none
+    pub fn id(&self) -> &str {
+        match self.extra.get("id") {
+            Some(serde_json::Value::String(id)) => id,
+            _ => &self.name,
+        }
+    }
+
+    /// Whether the item is deprecated (see `DEPRECATED_FIELD`)
+    pub fn is_deprecated(&self) -> bool {
+        self.extra.get(DEPRECATED_FIELD) == Some(&serde_json::Value::Bool(true))
+    }
+
+    /// Mark the item deprecated, or current again
+    pub fn set_deprecated(&mut self, deprecated: bool) {
+        if deprecated {
+            self.extra
+                .insert(DEPRECATED_FIELD.to_string(), serde_json::Value::Bool(true));
+        } else {
+            self.extra.remove(DEPRECATED_FIELD);
+        }
+    }
+
+    /// Where the item was imported from; None for items made by hand (or a `source` field
+    /// that isn't a provenance record)
+    pub fn source(&self) -> Option<Provenance> {
+        serde_json::from_value(self.extra.get(SOURCE_FIELD)?.clone()).ok()
+    }
+
+    /// Record where the item was imported from, or with None forget it
+    pub fn set_source(&mut self, source: Option<Provenance>) {
+        match source.and_then(|source| serde_json::to_value(source).ok()) {
+            Some(value) => {
+                self.extra.insert(SOURCE_FIELD.to_string(), value);
+            }
+            None => {
+                self.extra.remove(SOURCE_FIELD);
+            }
+        }
+    }
+
     /// Get a facet value as a string (handles both single values and arrays)
     /// For arrays, values are joined with ", "
     pub fn get_facet_as_string(&self, facet_name: &str) -> Option<String> {
         self.facets.get(facet_name).and_then(|v| match v {
             serde_json::Value::String(s) => Some(s.clone()),
+            serde_json::Value::Number(_) | serde_json::Value::Bool(_) => facet_value_to_string(v),
             serde_json::Value::Array(arr) => {
-                let values: Vec<String> = arr
-                    .iter()
-                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                    .collect();
+                let values: Vec<String> = arr.iter().filter_map(facet_value_to_string).collect();
                 if values.is_empty() {
                     None
                 } else {
@@ -56,21 +237,46 @@ impl Item {
     }
 
     /// Get facet values as a vector (always returns Vec, empty if not found)
-    /// For single string values, returns a Vec with one element
-    /// For arrays, extracts all string values
+    /// For single string or numeric values, returns a Vec with one element
+    /// For arrays, extracts all string and numeric values
     pub fn get_facet_as_vec(&self, facet_name: &str) -> Vec<String> {
         self.facets
             .get(facet_name)
-            .map(|v| match v {
-                serde_json::Value::String(s) => vec![s.clone()],
-                serde_json::Value::Array(arr) => arr
-                    .iter()
-                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                    .collect(),
-                _ => vec![],
-            })
+            .map(value_as_vec)
             .unwrap_or_default()
     }
+
+    /// Get an additional field's values as strings, like `get_facet_as_vec`
+    /// A field nested in an object is named by its path, e.g. "source.origin_file"
+    pub fn get_extra_as_vec(&self, key: &str) -> Vec<String> {
+        let nested = || {
+            let (field, path) = key.split_once('.')?;
+            path.split('.')
+                .try_fold(self.extra.get(field)?, |value, part| value.get(part))
+        };
+        self.extra
+            .get(key)
+            .or_else(nested)
+            .map(value_as_vec)
+            .unwrap_or_default()
+    }
+}
+
+pub(crate) fn value_as_vec(value: &serde_json::Value) -> Vec<String> {
+    match value {
+        serde_json::Value::Array(arr) => arr.iter().filter_map(facet_value_to_string).collect(),
+        _ => facet_value_to_string(value).into_iter().collect(),
+    }
+}
+
+/// Numbers and booleans are rendered with their JSON representation so they round-trip through filters
+fn facet_value_to_string(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        serde_json::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
 }
 
 /// Schema definition - contains classical hierarchy and facet dimensions
@@ -80,23 +286,501 @@ pub struct TaxonomySchema {
     pub title: String,
     pub description: Option<String>,
     pub classical_hierarchy: ClassicalHierarchy,
-    pub faceted_dimensions: HashMap<String, Vec<String>>,
-    /// Raw JSON Schema for validation (not serialized)
+    /// Definition of each facet; allowed values of nested dimensions are flattened in pre-order
+    #[serde(serialize_with = "sorted")]
+    pub faceted_dimensions: HashMap<String, FacetDefinition>,
+    /// Value trees of the dimensions defined as nested values (e.g. region → country → city)
+    /// Filtering by a parent value also matches the values beneath it
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "sorted"
+    )]
+    pub facet_value_trees: HashMap<String, Vec<FacetValueNode>>,
+    /// Rules between facets that every item must satisfy
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub constraints: Vec<FacetConstraint>,
+    /// Typed per-item fields beside the path and facets (e.g. a SKU or a URL)
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "sorted"
+    )]
+    pub item_fields: HashMap<String, ItemField>,
+    /// How facet values are shown (the schema's "x-display"), by facet, then value
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "sorted_nested"
+    )]
+    pub display_hints: HashMap<String, HashMap<String, DisplayHint>>,
+    /// Raw JSON Schema for validation (not serialized), with the compiled copy
+    /// `validate_json` keeps; read and edit it through `json_schema` / `json_schema_mut`
     #[serde(skip)]
-    pub json_schema: Option<serde_json::Value>,
+    pub(crate) json: JsonSchemaDocument,
+}
+
+/// Value type of a faceted dimension
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FacetKind {
+    /// One of the allowed values
+    #[default]
+    Enum,
+    /// Any string
+    Text,
+    /// A JSON number
+    Number,
+    /// An ISO 8601 calendar date string (YYYY-MM-DD)
+    Date,
+    /// A JSON boolean
+    Boolean,
+    /// An absolute URL string, shown as a link
+    Url,
+}
+
+/// Value type of a custom item field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldKind {
+    /// Any string
+    #[default]
+    String,
+    /// A JSON number
+    Number,
+    /// A JSON boolean
+    Boolean,
+    /// An ISO 8601 calendar date string (YYYY-MM-DD)
+    Date,
+    /// An absolute URI string such as https://example.com/item
+    Uri,
+}
+
+/// A custom per-item field declared by the schema
+/// In a schema, e.g. "item_fields": {"sku": {"label": "SKU", "type": "string", "required": true}};
+/// items keep the value as a top-level property next to "name"
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ItemField {
+    /// Name shown in forms and exports (the field name when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(rename = "type", default)]
+    pub kind: FieldKind,
+    /// Every item must set this field
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Definition of a faceted dimension
+/// In a schema, either a list of allowed values (an enum facet) or an object such as
+/// {"kind": "number", "required": true, "multi": false, "default": 0}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "FacetDefinitionRepr")]
+pub struct FacetDefinition {
+    pub kind: FacetKind,
+    /// Allowed values (flattened when nested); required for enum facets, an optional restriction otherwise
+    pub allowed_values: Vec<String>,
+    /// Every item must set this facet
+    pub required: bool,
+    /// Items may give an array of values
+    pub multi: bool,
+    /// Value pre-filled for new items
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
+    /// Label, description and deprecation of allowed values that declare them
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "sorted"
+    )]
+    pub value_info: HashMap<String, FacetValueInfo>,
+    /// Translations of the dimension name, keyed by language tag
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "sorted"
+    )]
+    pub labels: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FacetDefinitionRepr {
+    Values(Vec<FacetValueNode>),
+    Definition {
+        #[serde(default)]
+        kind: FacetKind,
+        #[serde(default)]
+        allowed_values: Vec<FacetValueNode>,
+        #[serde(default)]
+        required: bool,
+        #[serde(default = "default_multi")]
+        multi: bool,
+        #[serde(default)]
+        default: Option<serde_json::Value>,
+        #[serde(default)]
+        value_info: HashMap<String, FacetValueInfo>,
+        #[serde(default)]
+        labels: HashMap<String, String>,
+    },
+}
+
+fn default_multi() -> bool {
+    true
+}
+
+impl From<FacetDefinitionRepr> for FacetDefinition {
+    fn from(repr: FacetDefinitionRepr) -> Self {
+        match repr {
+            FacetDefinitionRepr::Values(values) => FacetDefinition {
+                value_info: collect_value_info(&values),
+                ..FacetDefinition::enumerated(flatten_facet_values(&values))
+            },
+            FacetDefinitionRepr::Definition {
+                kind,
+                allowed_values,
+                required,
+                multi,
+                default,
+                mut value_info,
+                labels,
+            } => {
+                value_info.extend(collect_value_info(&allowed_values));
+                FacetDefinition {
+                    kind,
+                    allowed_values: flatten_facet_values(&allowed_values),
+                    required,
+                    multi,
+                    default,
+                    value_info,
+                    labels,
+                }
+            }
+        }
+    }
+}
+
+/// Metadata of every value in a value tree that declares any
+fn collect_value_info(nodes: &[FacetValueNode]) -> HashMap<String, FacetValueInfo> {
+    let mut info = HashMap::new();
+    for node in nodes {
+        if node.info != FacetValueInfo::default() {
+            info.insert(node.value.clone(), node.info.clone());
+        }
+        info.extend(collect_value_info(&node.children));
+    }
+    info
+}
+
+/// Outcome of merging a vocabulary into a dimension's allowed values
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VocabularyImport {
+    /// Terms added, in vocabulary order
+    pub added: Vec<FacetValueNode>,
+    /// Terms skipped because the dimension (or an earlier term) already has them
+    pub duplicates: Vec<String>,
+    /// Terms skipped because they clash with an existing value, with the reason
+    pub conflicts: Vec<String>,
+}
+
+/// A facet value with narrower values beneath it
+/// In a schema, written as a plain string or as {"value": "Europe", "children": [...]},
+/// optionally with "label", "description" and "deprecated"
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(from = "FacetValueRepr", into = "FacetValueRepr")]
+pub struct FacetValueNode {
+    pub value: String,
+    pub children: Vec<FacetValueNode>,
+    pub info: FacetValueInfo,
+}
+
+/// Optional metadata of an allowed facet value
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct FacetValueInfo {
+    /// Shown instead of the value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Still valid on existing items (with a lint warning) but no longer offered for new ones
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
+    /// Translations of the value, keyed by language tag
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "sorted"
+    )]
+    pub labels: HashMap<String, String>,
+}
+
+/// Display hints of a facet value, declared in the schema's "x-display" block
+/// e.g. "x-display": {"temperature": {"hot": {"color": "#d9534f", "icon": "🔥", "order": 1}}}
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DisplayHint {
+    /// Badge and header color, as #rrggbb
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Shown before the value, typically an emoji
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    /// Place of the value in groups and lists; values without one come after, by name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<i64>,
+}
+
+impl FacetValueNode {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+            children: Vec::new(),
+            info: FacetValueInfo::default(),
+        }
+    }
+
+    pub fn with_children(mut self, children: Vec<FacetValueNode>) -> Self {
+        self.children = children;
+        self
+    }
+
+    pub fn with_info(mut self, info: FacetValueInfo) -> Self {
+        self.info = info;
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+enum FacetValueRepr {
+    Plain(String),
+    Nested {
+        value: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        children: Vec<FacetValueNode>,
+        #[serde(flatten)]
+        info: FacetValueInfo,
+    },
+}
+
+impl From<FacetValueRepr> for FacetValueNode {
+    fn from(repr: FacetValueRepr) -> Self {
+        match repr {
+            FacetValueRepr::Plain(value) => FacetValueNode::new(value),
+            FacetValueRepr::Nested {
+                value,
+                children,
+                info,
+            } => FacetValueNode::new(value)
+                .with_children(children)
+                .with_info(info),
+        }
+    }
+}
+
+impl From<FacetValueNode> for FacetValueRepr {
+    fn from(node: FacetValueNode) -> Self {
+        if node.children.is_empty() && node.info == FacetValueInfo::default() {
+            FacetValueRepr::Plain(node.value)
+        } else {
+            FacetValueRepr::Nested {
+                value: node.value,
+                children: node.children,
+                info: node.info,
+            }
+        }
+    }
+}
+
+/// Declarative rule between facets, checked by `validation::check_constraints`
+/// Written as {"if": {"temperature": "iced"}, "then": {"serving_vessel": "glass"}};
+/// "not" instead of (or next to) "then" lists values the item must not have
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct FacetConstraint {
+    /// Facet values that make the rule apply; every entry must match
+    #[serde(rename = "if")]
+    pub when: BTreeMap<String, FacetCondition>,
+    /// Facet values the item must then have (implication)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub then: BTreeMap<String, FacetCondition>,
+    /// Facet values the item must then not have (exclusion)
+    #[serde(default, rename = "not", skip_serializing_if = "BTreeMap::is_empty")]
+    pub forbid: BTreeMap<String, FacetCondition>,
+    /// Shown instead of the generated description when the rule is broken
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Values a constraint matches for one facet: a single value or any of a list
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum FacetCondition {
+    Value(String),
+    AnyOf(Vec<String>),
+}
+
+/// A broken constraint, attributed to the facet that has to change
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstraintViolation {
+    /// The "then" or "not" facet at fault
+    pub facet: String,
+    pub message: String,
 }
 
 /// Data file - references schema and contains items only
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TaxonomyData {
+    /// Schema file, relative to the data file; may be left out when the schema is embedded
+    #[serde(default)]
     pub schema: String,
     pub items: Vec<Item>,
-    #[serde(flatten)]
+    #[serde(flatten, serialize_with = "sorted")]
     pub extra: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug)]
+/// Comparison operator of a numeric facet filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CompareOp {
+    pub fn holds<T: PartialOrd>(self, lhs: T, rhs: T) -> bool {
+        match self {
+            CompareOp::Lt => lhs < rhs,
+            CompareOp::Le => lhs <= rhs,
+            CompareOp::Gt => lhs > rhs,
+            CompareOp::Ge => lhs >= rhs,
+        }
+    }
+}
+
+impl fmt::Display for CompareOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+        })
+    }
+}
+
+/// A condition on one facet's values, parsed from "name=value", "name>=10" or "name<2024-01-31"
+#[derive(Debug, Clone, PartialEq)]
+pub enum FacetPredicate {
+    /// Value equals the string (numeric strings also compare by number, so "5" matches 5.0)
+    Equals(String),
+    /// Value parses as a number satisfying the comparison
+    Compare(CompareOp, f64),
+    /// Value is an ISO date (YYYY-MM-DD) satisfying the comparison
+    CompareDate(CompareOp, String),
+}
+
+impl FacetPredicate {
+    /// Check a single facet value against the predicate
+    pub fn matches(&self, value: &str) -> bool {
+        self.matches_with_options(value, &FilterOptions::default())
+    }
+
+    /// Check a single facet value against the predicate, comparing text as `options` say
+    pub fn matches_with_options(&self, value: &str, options: &FilterOptions) -> bool {
+        match self {
+            FacetPredicate::Equals(expected) => {
+                options.text_eq(value, expected)
+                    || matches!(
+                        (value.trim().parse::<f64>(), expected.trim().parse::<f64>()),
+                        (Ok(a), Ok(b)) if a == b
+                    )
+            }
+            FacetPredicate::Compare(op, rhs) => value
+                .trim()
+                .parse::<f64>()
+                .is_ok_and(|lhs| op.holds(lhs, *rhs)),
+            FacetPredicate::CompareDate(op, rhs) => {
+                // ISO dates order correctly as strings
+                let value = value.trim();
+                is_iso_date(value) && op.holds(value, rhs.as_str())
+            }
+        }
+    }
+
+    /// Render back into the "name=value" / "name>=10" filter syntax
+    pub fn to_filter_string(&self, facet_name: &str) -> String {
+        match self {
+            FacetPredicate::Equals(value) => format!("{}={}", facet_name, value),
+            FacetPredicate::Compare(op, value) => format!("{}{}{}", facet_name, op, value),
+            FacetPredicate::CompareDate(op, value) => format!("{}{}{}", facet_name, op, value),
+        }
+    }
+}
+
+/// Displays the value for equality ("hot") and operator + operand for comparisons (">=10")
+impl fmt::Display for FacetPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FacetPredicate::Equals(value) => f.write_str(value),
+            FacetPredicate::Compare(op, value) => write!(f, "{}{}", op, value),
+            FacetPredicate::CompareDate(op, value) => write!(f, "{}{}", op, value),
+        }
+    }
+}
+
+/// How filter values compare with the values of items
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FilterOptions {
+    /// "hot" matches "Hot"
+    pub case_insensitive: bool,
+    /// "Cafe" matches "Café": accents and other combining marks are ignored
+    pub strip_diacritics: bool,
+}
+
+impl FilterOptions {
+    /// Whether the options loosen matching at all
+    pub fn is_exact(&self) -> bool {
+        !self.case_insensitive && !self.strip_diacritics
+    }
+
+    /// `s` as these options compare it
+    pub fn fold<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        let s = if self.strip_diacritics {
+            strip_diacritics(s)
+        } else {
+            Cow::Borrowed(s)
+        };
+        if self.case_insensitive {
+            Cow::Owned(s.to_lowercase())
+        } else {
+            s
+        }
+    }
+
+    /// Whether `a` and `b` are the same under these options
+    pub fn text_eq(&self, a: &str, b: &str) -> bool {
+        a == b || (!self.is_exact() && self.fold(a) == self.fold(b))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct Filters {
     pub genera: Vec<String>,
-    pub facets: HashMap<String, Vec<String>>,
+    pub facets: HashMap<String, Vec<FacetPredicate>>,
+    /// Items classified under any of these genera are excluded
+    pub excluded_genera: Vec<String>,
+    /// Items with a facet value matching any of these predicates are excluded
+    pub excluded_facets: HashMap<String, Vec<FacetPredicate>>,
+    /// Predicates over additional (`extra`) fields, combined like facet filters
+    pub fields: HashMap<String, Vec<FacetPredicate>>,
+    /// Case-insensitive substring the item name must contain
+    pub name_contains: Option<String>,
+    /// A genus (included or excluded) also matches items classified under any of its descendant species
+    /// Only honored by the `*_in_hierarchy` filtering functions, which consult the schema
+    pub include_descendants: bool,
+    /// Deprecated items match too; they never do otherwise
+    pub include_deprecated: bool,
+    /// How genera, facet and field values compare
+    pub options: FilterOptions,
 }
@@ -0,0 +1,127 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// How far a long operation has got: the stage it is in and, when known, the units
+/// (bytes, items, steps) done out of the total
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Progress {
+    pub stage: String,
+    pub done: u64,
+    /// 0 when the amount of work isn't known
+    pub total: u64,
+}
+
+impl Progress {
+    pub fn new(stage: impl Into<String>, done: u64, total: u64) -> Self {
+        Self {
+            stage: stage.into(),
+            done,
+            total,
+        }
+    }
+
+    /// A stage whose amount of work isn't known
+    pub fn stage(stage: impl Into<String>) -> Self {
+        Self::new(stage, 0, 0)
+    }
+
+    /// Share of the work done, from 0 to 1; None when the total isn't known
+    pub fn fraction(&self) -> Option<f32> {
+        (self.total > 0).then(|| (self.done.min(self.total) as f64 / self.total as f64) as f32)
+    }
+}
+
+/// Receives the progress of a long operation; `&mut |_| {}` ignores it
+pub type ProgressFn<'a> = dyn FnMut(&Progress) + 'a;
+
+/// Report a loop's progress through `total` units, at most once per percent
+pub fn report_step(on_progress: &mut ProgressFn, stage: &str, done: u64, total: u64) {
+    let step = (total / 100).max(1);
+    if done.is_multiple_of(step) || done == total {
+        on_progress(&Progress::new(stage, done, total));
+    }
+}
+
+/// A reader that reports the bytes read so far out of `total`, at most once per percent
+pub struct ProgressReader<R, F> {
+    inner: R,
+    stage: String,
+    done: u64,
+    total: u64,
+    reported: u64,
+    on_progress: F,
+}
+
+impl<R: Read, F: FnMut(&Progress)> ProgressReader<R, F> {
+    pub fn new(inner: R, stage: impl Into<String>, total: u64, on_progress: F) -> Self {
+        Self {
+            inner,
+            stage: stage.into(),
+            done: 0,
+            total,
+            reported: 0,
+            on_progress,
+        }
+    }
+}
+
+impl<R: Read, F: FnMut(&Progress)> Read for ProgressReader<R, F> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.done += n as u64;
+        let step = (self.total / 100).max(1);
+        if self.done - self.reported >= step || (n == 0 && self.done > self.reported) {
+            self.reported = self.done;
+            (self.on_progress)(&Progress::new(&self.stage, self.done, self.total));
+        }
+        Ok(n)
+    }
+}
+
+/// Read a whole file, reporting the bytes read as "Reading file"
+pub fn read_with_progress(
+    path: impl AsRef<Path>,
+    on_progress: &mut ProgressFn,
+) -> io::Result<Vec<u8>> {
+    let file = File::open(path)?;
+    let total = file.metadata()?.len();
+    let mut contents = Vec::with_capacity(total as usize);
+    ProgressReader::new(file, "Reading file", total, on_progress).read_to_end(&mut contents)?;
+    Ok(contents)
+}
+
+/// Read a whole UTF-8 file, reporting the bytes read as "Reading file"
+pub fn read_to_string_with_progress(
+    path: impl AsRef<Path>,
+    on_progress: &mut ProgressFn,
+) -> io::Result<String> {
+    let file = File::open(path)?;
+    let total = file.metadata()?.len();
+    let mut contents = String::with_capacity(total as usize);
+    ProgressReader::new(file, "Reading file", total, on_progress).read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_reader_reports_bytes() {
+        let bytes = vec![b'x'; 1000];
+        let mut reports = Vec::new();
+        let on_progress = |progress: &Progress| reports.push(progress.clone());
+        let mut contents = String::new();
+        ProgressReader::new(bytes.as_slice(), "Reading", 1000, on_progress)
+            .read_to_string(&mut contents)
+            .unwrap();
+
+        assert_eq!(contents.len(), 1000);
+        let last = reports.last().unwrap();
+        assert_eq!(last.done, 1000);
+        assert_eq!(last.fraction(), Some(1.0));
+        assert!(reports.windows(2).all(|pair| pair[0].done < pair[1].done));
+        assert_eq!(Progress::stage("Parsing").fraction(), None);
+    }
+}
@@ -0,0 +1,132 @@
+use crate::io::SaveOptions;
+use crate::templates::ItemTemplate;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File extension of project files
+pub const PROJECT_EXTENSION: &str = "taxproj";
+
+/// A `.taxproj` project: several taxonomy data files worked on together, the schemas
+/// they share and settings that apply to all of them
+/// Paths are written relative to the project file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Project {
+    pub name: String,
+    /// Member taxonomy data files
+    pub taxonomies: Vec<PathBuf>,
+    /// Schema files shared by the members
+    pub schemas: Vec<PathBuf>,
+    pub settings: ProjectSettings,
+    /// Where the project was loaded from or last saved to
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+/// Settings applied to every member taxonomy of a project
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectSettings {
+    /// Language to show names and values in (e.g. "de")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    /// Field to sort items by ("name" or a facet)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    /// Prototypes for creating new items
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub item_templates: Vec<ItemTemplate>,
+    /// How member data files are formatted when saved
+    #[serde(skip_serializing_if = "SaveOptions::is_default")]
+    pub save: SaveOptions,
+}
+
+impl Project {
+    /// Read a project file
+    /// A project without a name is named after its file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read project {}: {}", path.display(), e))?;
+        let mut project: Project = serde_json::from_str(&contents)
+            .map_err(|e| format!("Invalid project {}: {}", path.display(), e))?;
+        if project.name.is_empty() {
+            project.name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+        }
+        project.path = Some(path.to_path_buf());
+        Ok(project)
+    }
+
+    /// Write the project file with pretty printing
+    pub fn save<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Box<dyn Error>> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path.as_ref(), json)?;
+        self.path = Some(path.as_ref().to_path_buf());
+        Ok(())
+    }
+
+    /// The member data files, resolved against the project's directory
+    pub fn taxonomy_paths(&self) -> Vec<PathBuf> {
+        self.taxonomies.iter().map(|p| self.resolve(p)).collect()
+    }
+
+    /// The shared schema files, resolved against the project's directory
+    pub fn schema_paths(&self) -> Vec<PathBuf> {
+        self.schemas.iter().map(|p| self.resolve(p)).collect()
+    }
+
+    fn resolve(&self, path: &Path) -> PathBuf {
+        match self.path.as_deref().and_then(Path::parent) {
+            Some(dir) => dir.join(path),
+            None => path.to_path_buf(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_round_trip() {
+        let dir = std::env::temp_dir().join(format!("taxstud-project-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("menu.taxproj");
+        fs::write(
+            &path,
+            r#"{
+                "taxonomies": ["coffee.json", "tea/tea.json"],
+                "schemas": ["shared/beverages.json"],
+                "settings": {"lang": "de"}
+            }"#,
+        )
+        .unwrap();
+
+        let mut project = Project::load(&path).unwrap();
+        assert_eq!(project.name, "menu");
+        assert_eq!(
+            project.taxonomy_paths(),
+            vec![dir.join("coffee.json"), dir.join("tea/tea.json")]
+        );
+        assert_eq!(
+            project.schema_paths(),
+            vec![dir.join("shared/beverages.json")]
+        );
+        assert_eq!(project.settings.lang.as_deref(), Some("de"));
+        assert_eq!(project.settings.sort, None);
+
+        project.settings.sort = Some("name".to_string());
+        project.save(&path).unwrap();
+        assert_eq!(Project::load(&path).unwrap(), project);
+
+        fs::write(&path, r#"{"taxonomies": "coffee.json"}"#).unwrap();
+        assert!(Project::load(&path).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,226 @@
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Top-level sections TaxStud reads itself rather than through JSON Schema validation
+/// Pointers within the schema document are inlined only here
+const TAXONOMY_SECTIONS: &[&str] = &[
+    "classical_hierarchy",
+    "faceted_dimensions",
+    "constraints",
+    "x-rules",
+    "x-display",
+];
+
+/// Inline the `$ref`s a schema document read from `path` makes to other files
+/// `"facets.json"` or `"common.json#/definitions/era"` is replaced by that document or
+/// fragment, read relative to the referring file; http(s) URLs need the `remote-refs`
+/// feature. Pointers within the document (`"#/definitions/node"`) are left for JSON Schema
+/// validation, except under the taxonomy sections, which are read without it.
+/// Fragments are inlined whole, so a fragment may not refer to itself recursively.
+pub fn resolve_refs(document: Value, path: &Path) -> Result<Value, String> {
+    let location = path.to_string_lossy().into_owned();
+    let mut resolver = Resolver::default();
+    resolver
+        .documents
+        .insert(location.clone(), document.clone());
+
+    let Value::Object(sections) = document else {
+        return Ok(document);
+    };
+    let mut resolved = Map::new();
+    for (key, value) in sections {
+        let inline_local = TAXONOMY_SECTIONS.contains(&key.as_str());
+        let value = resolver.resolve(value, &location, inline_local)?;
+        resolved.insert(key, value);
+    }
+    Ok(Value::Object(resolved))
+}
+
+#[derive(Default)]
+struct Resolver {
+    /// Documents read so far, by file path or URL
+    documents: HashMap<String, Value>,
+    /// References being inlined, to catch cycles
+    expanding: Vec<String>,
+}
+
+impl Resolver {
+    /// Resolve the refs in part of the document at `location`
+    /// Pointers within that document are only inlined when `inline_local` is set
+    fn resolve(
+        &mut self,
+        value: Value,
+        location: &str,
+        inline_local: bool,
+    ) -> Result<Value, String> {
+        match value {
+            Value::Object(mut map) => {
+                if let Some(Value::String(reference)) = map.get("$ref") {
+                    if inline_local || !reference.starts_with('#') {
+                        let reference = reference.clone();
+                        map.remove("$ref");
+                        let target = self.inline(&reference, location)?;
+                        if map.is_empty() {
+                            return Ok(target);
+                        }
+                        // Keywords next to the $ref apply on top of the target
+                        let Value::Object(mut merged) = target else {
+                            return Err(format!(
+                                "$ref '{}' with other keys is not an object",
+                                reference
+                            ));
+                        };
+                        for (key, value) in map {
+                            merged.insert(key, self.resolve(value, location, inline_local)?);
+                        }
+                        return Ok(Value::Object(merged));
+                    }
+                }
+                let mut resolved = Map::new();
+                for (key, value) in map {
+                    resolved.insert(key, self.resolve(value, location, inline_local)?);
+                }
+                Ok(Value::Object(resolved))
+            }
+            Value::Array(values) => values
+                .into_iter()
+                .map(|value| self.resolve(value, location, inline_local))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Value::Array),
+            value => Ok(value),
+        }
+    }
+
+    /// The target of a reference made from the document at `location`, itself resolved
+    fn inline(&mut self, reference: &str, location: &str) -> Result<Value, String> {
+        let (file, pointer) = reference.split_once('#').unwrap_or((reference, ""));
+        let target = if file.is_empty() {
+            location.to_string()
+        } else {
+            join(location, file)
+        };
+
+        let key = format!("{}#{}", target, pointer);
+        if self.expanding.contains(&key) {
+            return Err(format!("Circular $ref '{}' in {}", reference, location));
+        }
+        let fragment = self
+            .document(&target)?
+            .pointer(pointer)
+            .cloned()
+            .ok_or_else(|| format!("$ref '{}' not found in {}", reference, target))?;
+
+        // Pointers inside another document are only meaningful there, so inline them all
+        self.expanding.push(key);
+        let resolved = self.resolve(fragment, &target, true);
+        self.expanding.pop();
+        resolved
+    }
+
+    fn document(&mut self, location: &str) -> Result<&Value, String> {
+        if !self.documents.contains_key(location) {
+            let text = read(location)?;
+            let document = serde_json::from_str(&text)
+                .map_err(|e| format!("Invalid JSON in {}: {}", location, e))?;
+            self.documents.insert(location.to_string(), document);
+        }
+        Ok(&self.documents[location])
+    }
+}
+
+/// A reference's location, relative to the directory of the referring document
+fn join(base: &str, reference: &str) -> String {
+    if is_url(reference) {
+        reference.to_string()
+    } else if is_url(base) {
+        let dir = base.rfind('/').map_or(base, |i| &base[..=i]);
+        format!("{}{}", dir, reference)
+    } else {
+        let dir = Path::new(base).parent().unwrap_or(Path::new(""));
+        dir.join(reference).to_string_lossy().into_owned()
+    }
+}
+
+fn is_url(location: &str) -> bool {
+    location.starts_with("http://") || location.starts_with("https://")
+}
+
+fn read(location: &str) -> Result<String, String> {
+    if is_url(location) {
+        return fetch(location);
+    }
+    fs::read_to_string(location).map_err(|e| format!("Cannot read {}: {}", location, e))
+}
+
+#[cfg(feature = "remote-refs")]
+fn fetch(url: &str) -> Result<String, String> {
+    reqwest::blocking::get(url)
+        .and_then(|response| response.error_for_status())
+        .and_then(|response| response.text())
+        .map_err(|e| format!("Cannot fetch {}: {}", url, e))
+}
+
+#[cfg(not(feature = "remote-refs"))]
+fn fetch(url: &str) -> Result<String, String> {
+    Err(format!(
+        "Cannot fetch {}: remote $refs need the 'remote-refs' feature",
+        url
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_resolve_refs() {
+        let dir = std::env::temp_dir().join(format!("taxstud-refs-{}", std::process::id()));
+        fs::create_dir_all(dir.join("shared")).unwrap();
+        fs::write(
+            dir.join("shared/facets.json"),
+            json!({
+                "temperature": {"$ref": "#/definitions/temperature"},
+                "definitions": {"temperature": ["hot", "iced"]}
+            })
+            .to_string(),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("shared/hierarchy.json"),
+            json!({"root": "Beverage", "children": []}).to_string(),
+        )
+        .unwrap();
+
+        let document = json!({
+            "classical_hierarchy": {"$ref": "shared/hierarchy.json"},
+            "faceted_dimensions": {
+                "temperature": {"$ref": "shared/facets.json#/temperature"},
+                "size": {"$ref": "#/definitions/sizes"}
+            },
+            "properties": {"items": {"$ref": "#/definitions/items"}},
+            "definitions": {"sizes": ["small", "large"], "items": {"type": "array"}}
+        });
+        let resolved = resolve_refs(document, &dir.join("schema.json")).unwrap();
+        assert_eq!(resolved["classical_hierarchy"]["root"], "Beverage");
+        assert_eq!(
+            resolved["faceted_dimensions"],
+            json!({"temperature": ["hot", "iced"], "size": ["small", "large"]})
+        );
+        // Pointers outside the taxonomy sections are left for JSON Schema validation
+        assert_eq!(
+            resolved["properties"]["items"],
+            json!({"$ref": "#/definitions/items"})
+        );
+
+        let missing = json!({"faceted_dimensions": {"$ref": "shared/none.json"}});
+        assert!(resolve_refs(missing, &dir.join("schema.json")).is_err());
+        let circular = json!({"faceted_dimensions": {"$ref": "#/faceted_dimensions"}});
+        let err = resolve_refs(circular, &dir.join("schema.json")).unwrap_err();
+        assert!(err.starts_with("Circular $ref"), "{}", err);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,195 @@
+use crate::models::{Relation, TaxonomyData, TaxonomySchema};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+
+/// An item relation whose target can't be found
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrokenRelation {
+    /// Data file of the item holding the relation
+    pub file: PathBuf,
+    /// Name of the item holding the relation
+    pub item: String,
+    pub relation: Relation,
+    /// Why the target can't be found
+    pub reason: String,
+}
+
+impl fmt::Display for BrokenRelation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Item '{}': broken '{}' relation: {}",
+            self.item, self.relation.kind, self.reason
+        )
+    }
+}
+
+/// Check the relations of every item against a set of data files (e.g. a project's members)
+/// A relation's `taxonomy` is resolved relative to the file of the item holding it and
+/// must be one of the given files; without one, the target is looked up in the same file.
+pub fn check_relations(
+    taxonomies: &[(PathBuf, TaxonomySchema, TaxonomyData)],
+) -> Vec<BrokenRelation> {
+    let by_path: HashMap<PathBuf, usize> = taxonomies
+        .iter()
+        .enumerate()
+        .map(|(index, (path, _, _))| (normalize(path), index))
+        .collect();
+
+    let mut broken = Vec::new();
+    for (path, _, data) in taxonomies {
+        for item in &data.items {
+            for relation in &item.relations {
+                if let Err(reason) = resolve(relation, path, taxonomies, &by_path) {
+                    broken.push(BrokenRelation {
+                        file: path.clone(),
+                        item: item.name.clone(),
+                        relation: relation.clone(),
+                        reason,
+                    });
+                }
+            }
+        }
+    }
+    broken
+}
+
+/// Find a relation's target, or say why it isn't there
+fn resolve(
+    relation: &Relation,
+    from: &Path,
+    taxonomies: &[(PathBuf, TaxonomySchema, TaxonomyData)],
+    by_path: &HashMap<PathBuf, usize>,
+) -> Result<(), String> {
+    let target_path = match relation.taxonomy {
+        Some(ref taxonomy) => from.parent().unwrap_or(Path::new("")).join(taxonomy),
+        None => from.to_path_buf(),
+    };
+    let Some(&index) = by_path.get(&normalize(&target_path)) else {
+        return Err(format!(
+            "'{}' is not one of the taxonomies being validated",
+            relation.taxonomy.as_deref().unwrap_or_default()
+        ));
+    };
+    let (file, schema, data) = &taxonomies[index];
+
+    match (&relation.item, &relation.node) {
+        (Some(id), None) => {
+            if data.items.iter().any(|item| item.id() == id) {
+                Ok(())
+            } else {
+                Err(format!("no item with ID '{}' in {}", id, file.display()))
+            }
+        }
+        (None, Some(code)) => {
+            let found = schema
+                .classical_hierarchy
+                .iter_preorder()
+                .any(|(_, node)| node.code.as_deref() == Some(code));
+            if found {
+                Ok(())
+            } else {
+                Err(format!(
+                    "no node with code '{}' in {}",
+                    code,
+                    file.display()
+                ))
+            }
+        }
+        _ => Err("a relation names either an 'item' or a 'node'".to_string()),
+    }
+}
+
+/// A path with `.` and `dir/..` components removed, so references written in different
+/// ways compare equal without touching the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(
+                    normalized.components().next_back(),
+                    Some(Component::Normal(_))
+                ) =>
+            {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{HierarchyBuilder, ItemBuilder, TaxonomySchemaBuilder};
+
+    fn relation(taxonomy: Option<&str>, item: Option<&str>, node: Option<&str>) -> Relation {
+        Relation {
+            kind: "see_also".to_string(),
+            taxonomy: taxonomy.map(str::to_string),
+            item: item.map(str::to_string),
+            node: node.map(str::to_string),
+            extra: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_check_relations() {
+        let schema = TaxonomySchemaBuilder::new("Beverages")
+            .hierarchy(
+                HierarchyBuilder::new()
+                    .root("Beverage")
+                    .child("Tea", "steeped leaves", |c| c.code("BEV.TEA"))
+                    .build(),
+            )
+            .build();
+
+        let coffee = TaxonomyData {
+            schema: "schema.json".to_string(),
+            items: vec![ItemBuilder::new("Latte")
+                .path(["Beverage"])
+                .relation(relation(Some("../tea/tea.json"), Some("GREEN-01"), None))
+                .relation(relation(Some("./../tea/tea.json"), None, Some("BEV.TEA")))
+                .relation(relation(None, Some("Mocha"), None))
+                .relation(relation(Some("../tea/tea.json"), Some("Oolong"), None))
+                .relation(relation(Some("juice.json"), Some("Orange"), None))
+                .relation(relation(None, None, None))
+                .build()],
+            extra: HashMap::new(),
+        };
+        let tea = TaxonomyData {
+            schema: "schema.json".to_string(),
+            items: vec![ItemBuilder::new("Green Tea")
+                .path(["Beverage", "Tea"])
+                .extra("id", "GREEN-01")
+                .build()],
+            extra: HashMap::new(),
+        };
+        let taxonomies = vec![
+            (
+                PathBuf::from("menu/coffee/coffee.json"),
+                schema.clone(),
+                coffee,
+            ),
+            (PathBuf::from("menu/tea/tea.json"), schema, tea),
+        ];
+
+        let reasons: Vec<String> = check_relations(&taxonomies)
+            .iter()
+            .map(|broken| broken.reason.clone())
+            .collect();
+        assert_eq!(
+            reasons,
+            vec![
+                "no item with ID 'Mocha' in menu/coffee/coffee.json",
+                "no item with ID 'Oolong' in menu/tea/tea.json",
+                "'juice.json' is not one of the taxonomies being validated",
+                "a relation names either an 'item' or a 'node'",
+            ]
+        );
+    }
+}
@@ -0,0 +1,439 @@
+//! Taxonomies published over HTTP(S) (`remote` feature)
+//!
+//! `RemoteCache::open` downloads a data document and the schema it references into a
+//! local cache that mirrors the URLs (`<cache>/<host>/<path>`), so the usual loaders read
+//! the copies and find the schema next to the data. Each copy keeps the response's ETag
+//! beside it (`<file>.etag`); later opens send `If-None-Match` and reuse the copy on
+//! `304 Not Modified`, or when the server can't be reached. `$ref`s inside the schema
+//! are not fetched
+
+use reqwest::blocking::Client;
+use reqwest::header::{ETAG, IF_MATCH, IF_NONE_MATCH};
+use reqwest::{StatusCode, Url};
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How long a request may take
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Whether `source` is an http(s) URL rather than a file path
+pub fn is_url(source: &str) -> bool {
+    let lower = source.trim_start().to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+/// Where a cached copy came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Freshness {
+    /// Downloaded just now
+    Downloaded,
+    /// The server said the cached copy is current (304)
+    Revalidated,
+    /// The server couldn't be reached or failed (5xx); the cached copy may be out of date
+    Offline,
+}
+
+/// A remote data document with its local copy
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteTaxonomy {
+    pub url: String,
+    /// The cached copy, with its schema beside it; load it like any data file
+    pub data_path: PathBuf,
+    /// How the data document was obtained (the schema's may differ)
+    pub freshness: Freshness,
+}
+
+/// The `schema` reference of a data document, without building its items
+#[derive(Deserialize)]
+struct SchemaReference {
+    schema: Option<serde_json::Value>,
+    #[serde(rename = "$schema_inline")]
+    inline: Option<IgnoredAny>,
+}
+
+/// Local copies of remote documents
+#[derive(Debug, Clone)]
+pub struct RemoteCache {
+    dir: PathBuf,
+}
+
+impl RemoteCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Where the copy of `url` is kept: the host (with its port), then the path segments
+    /// A query string is folded into the file name, so copies of one directory stay together
+    pub fn local_path(&self, url: &str) -> Result<PathBuf, Box<dyn Error>> {
+        let url = parse(url)?;
+        let host = url.host_str().ok_or("URL has no host")?;
+        let mut path = self.dir.join(match url.port() {
+            Some(port) => format!("{}_{}", host, port),
+            None => host.to_string(),
+        });
+
+        let segments: Vec<&str> = url.path_segments().map_or_else(Vec::new, Iterator::collect);
+        let (file, dirs) = match segments.split_last() {
+            Some((file, dirs)) if !file.is_empty() => (file.to_string(), dirs),
+            Some((_, dirs)) => ("index.json".to_string(), dirs),
+            None => ("index.json".to_string(), &[][..]),
+        };
+        for dir in dirs.iter().filter(|dir| !dir.is_empty()) {
+            path.push(sanitize(dir));
+        }
+        let file = match url.query() {
+            Some(query) => {
+                let file = Path::new(&file);
+                let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+                let hash = fnv1a(query.as_bytes());
+                match file.extension() {
+                    Some(ext) => format!("{}-{:016x}.{}", stem, hash, ext.to_string_lossy()),
+                    None => format!("{}-{:016x}", stem, hash),
+                }
+            }
+            None => file,
+        };
+        path.push(sanitize(&file));
+        Ok(path)
+    }
+
+    /// Bring the copy of `url` up to date, revalidating it with its ETag
+    /// Falls back to the copy when the server can't be reached or answers with a server
+    /// error; fails without one
+    pub fn fetch(&self, url: &str) -> Result<(PathBuf, Freshness), Box<dyn Error>> {
+        let path = self.local_path(url)?;
+        let etag_path = etag_path(&path);
+        let etag = path
+            .is_file()
+            .then(|| fs::read_to_string(&etag_path).ok())
+            .flatten();
+
+        let mut request = client()?.get(url);
+        if let Some(ref etag) = etag {
+            request = request.header(IF_NONE_MATCH, etag.trim());
+        }
+        let response = match request.send() {
+            Ok(response) => response,
+            Err(_) if path.is_file() => return Ok((path, Freshness::Offline)),
+            Err(e) => return Err(format!("Cannot fetch {}: {}", url, e).into()),
+        };
+
+        if response.status() == StatusCode::NOT_MODIFIED && etag.is_some() {
+            return Ok((path, Freshness::Revalidated));
+        }
+        if response.status().is_server_error() && path.is_file() {
+            return Ok((path, Freshness::Offline));
+        }
+        let response = response
+            .error_for_status()
+            .map_err(|e| format!("Cannot fetch {}: {}", url, e))?;
+        let new_etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let body = response
+            .bytes()
+            .map_err(|e| format!("Cannot fetch {}: {}", url, e))?;
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, &body)?;
+        match new_etag {
+            Some(etag) => fs::write(&etag_path, etag)?,
+            None if etag_path.exists() => fs::remove_file(&etag_path)?,
+            None => {}
+        }
+        Ok((path, Freshness::Downloaded))
+    }
+
+    /// Fetch a data document and the schema file it references (relative to its URL)
+    pub fn open(&self, url: &str) -> Result<RemoteTaxonomy, Box<dyn Error>> {
+        let (data_path, freshness) = self.fetch(url)?;
+        let contents = fs::read_to_string(&data_path)?;
+        let reference: SchemaReference = serde_json::from_str(&contents)
+            .map_err(|e| format!("{} is not a taxonomy data document: {}", url, e))?;
+        if reference.inline.is_none() {
+            let schema_ref = reference
+                .schema
+                .as_ref()
+                .and_then(|v| v.as_str())
+                .ok_or("Data file missing 'schema' field")?;
+            let schema_url = parse(url)?.join(schema_ref)?;
+            self.fetch(schema_url.as_str())?;
+        }
+        Ok(RemoteTaxonomy {
+            url: url.to_string(),
+            data_path,
+            freshness,
+        })
+    }
+
+    /// The ETag of the copy of `url`, if the server sent one
+    pub fn etag(&self, url: &str) -> Option<String> {
+        let path = self.local_path(url).ok()?;
+        fs::read_to_string(etag_path(&path))
+            .ok()
+            .map(|etag| etag.trim().to_string())
+    }
+
+    /// Forget the copy's ETag, so the next fetch downloads the document again
+    /// For a copy changed locally that the server never got
+    pub fn invalidate(&self, url: &str) -> Result<(), Box<dyn Error>> {
+        let etag_path = etag_path(&self.local_path(url)?);
+        if etag_path.exists() {
+            fs::remove_file(etag_path)?;
+        }
+        Ok(())
+    }
+
+    /// Write `contents` to `url` by HTTP PUT, for servers that accept saves, and make it
+    /// the copy; returns the server's new ETag
+    /// With `if_match` (the ETag the document was fetched with) a document someone else
+    /// changed since is not overwritten: the server answers 412 and this fails
+    pub fn upload(
+        &self,
+        url: &str,
+        contents: &[u8],
+        if_match: Option<&str>,
+    ) -> Result<Option<String>, Box<dyn Error>> {
+        let path = self.local_path(url)?;
+        let mut request = client()?
+            .put(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(contents.to_vec());
+        if let Some(etag) = if_match {
+            request = request.header(IF_MATCH, etag);
+        }
+        let response = request
+            .send()
+            .map_err(|e| format!("Cannot save to {}: {}", url, e))?;
+        if response.status() == StatusCode::PRECONDITION_FAILED {
+            return Err(format!(
+                "{} has changed on the server since it was opened; reopen it before saving",
+                url
+            )
+            .into());
+        }
+        let response = response
+            .error_for_status()
+            .map_err(|e| format!("Cannot save to {}: {}", url, e))?;
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, contents)?;
+        match etag {
+            Some(ref etag) => fs::write(etag_path(&path), etag)?,
+            // Without a new ETag the old one no longer matches
+            None => self.invalidate(url)?,
+        }
+        Ok(etag)
+    }
+}
+
+impl Default for RemoteCache {
+    /// `$XDG_CACHE_HOME`, `%LOCALAPPDATA%` or `~/.cache` (the temporary directory when none
+    /// is known), followed by `taxstud/remote`
+    fn default() -> Self {
+        let base = std::env::var_os("XDG_CACHE_HOME")
+            .or_else(|| std::env::var_os("LOCALAPPDATA"))
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+            .unwrap_or_else(std::env::temp_dir);
+        Self::new(base.join("taxstud").join("remote"))
+    }
+}
+
+fn parse(url: &str) -> Result<Url, Box<dyn Error>> {
+    let url = Url::parse(url.trim()).map_err(|e| format!("Invalid URL '{}': {}", url, e))?;
+    match url.scheme() {
+        "http" | "https" => Ok(url),
+        scheme => Err(format!("Unsupported URL scheme '{}'", scheme).into()),
+    }
+}
+
+fn client() -> Result<Client, Box<dyn Error>> {
+    Ok(Client::builder().timeout(TIMEOUT).build()?)
+}
+
+fn etag_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".etag");
+    path.with_file_name(name)
+}
+
+/// A path segment safe to use as a file name
+fn sanitize(segment: &str) -> String {
+    let name: String = segment
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    match name.as_str() {
+        "" | "." | ".." => "_".to_string(),
+        _ => name,
+    }
+}
+
+/// FNV-1a, stable across runs and platforms
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    #[test]
+    fn test_local_path() {
+        let cache = RemoteCache::new("/cache");
+        assert_eq!(
+            cache
+                .local_path("https://example.com/taxonomies/data.json")
+                .unwrap(),
+            Path::new("/cache/example.com/taxonomies/data.json")
+        );
+        assert_eq!(
+            cache.local_path("http://localhost:8080/").unwrap(),
+            Path::new("/cache/localhost_8080/index.json")
+        );
+        let with_query = cache
+            .local_path("https://example.com/a/data.json?v=2")
+            .unwrap();
+        assert_eq!(
+            with_query.parent().unwrap(),
+            Path::new("/cache/example.com/a")
+        );
+        assert!(with_query.to_string_lossy().ends_with(".json"));
+        assert!(cache.local_path("ftp://example.com/data.json").is_err());
+        assert!(is_url("HTTPS://example.com") && !is_url("data.json"));
+    }
+
+    /// Serves `data.json` (ETag "v1") and `schema.json`, answering 304 to a matching
+    /// If-None-Match, for `requests` requests
+    fn serve(requests: usize) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            for stream in listener.incoming().take(requests) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut revalidating = false;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    revalidating |= line
+                        .to_ascii_lowercase()
+                        .starts_with("if-none-match: \"v1\"");
+                }
+                let body = if request_line.contains("/schema.json") {
+                    r#"{"title": "Remote"}"#
+                } else {
+                    r#"{"schema": "schema.json", "items": []}"#
+                };
+                let response = if revalidating {
+                    "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nContent-Length: 0\r\n\r\n"
+                        .to_string()
+                } else {
+                    format!(
+                        "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: {}\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                };
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        format!("http://{}/taxonomies/data.json", address)
+    }
+
+    #[test]
+    fn test_open_and_revalidate() {
+        let dir = std::env::temp_dir().join(format!("taxstud-remote-{}", std::process::id()));
+        let cache = RemoteCache::new(&dir);
+        let url = serve(4);
+
+        let opened = cache.open(&url).unwrap();
+        assert_eq!(opened.freshness, Freshness::Downloaded);
+        assert!(opened.data_path.with_file_name("schema.json").is_file());
+        assert_eq!(
+            fs::read_to_string(etag_path(&opened.data_path)).unwrap(),
+            "\"v1\""
+        );
+
+        let reopened = cache.open(&url).unwrap();
+        assert_eq!(reopened.freshness, Freshness::Revalidated);
+        assert_eq!(reopened.data_path, opened.data_path);
+
+        // The server is gone now; the copy is still there
+        let (_, freshness) = cache.fetch(&url).unwrap();
+        assert_eq!(freshness, Freshness::Offline);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_server_error_falls_back_to_copy() {
+        // Serves data.json once, then answers 503
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/data.json", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for (i, stream) in listener.incoming().take(3).enumerate() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 2 {
+                    line.clear();
+                }
+                let body = r#"{"items": []}"#;
+                let response = if i == 0 {
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                } else {
+                    "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n".to_string()
+                };
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        let dir = std::env::temp_dir().join(format!("taxstud-remote-5xx-{}", std::process::id()));
+        let cache = RemoteCache::new(&dir);
+
+        assert_eq!(cache.fetch(&url).unwrap().1, Freshness::Downloaded);
+        assert_eq!(cache.fetch(&url).unwrap().1, Freshness::Offline);
+
+        // Without a copy, a server error is an error
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(cache.fetch(&url).is_err());
+    }
+}
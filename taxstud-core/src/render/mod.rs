@@ -0,0 +1,2 @@
+//! Output laid out by the user rather than by a fixed format
+pub mod template;
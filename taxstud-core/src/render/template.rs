@@ -0,0 +1,213 @@
+use crate::analysis::{facet_distribution, overview};
+use crate::grouping::{get_sorted_group_names, group_items_by_facet};
+use crate::models::{Item, TaxonomyData, TaxonomySchema};
+use minijinja::{AutoEscape, Environment};
+use serde_json::{json, Value};
+use std::error::Error;
+
+/// What goes into a report besides the items
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReportOptions {
+    /// Facet whose values the items are grouped by in `groups`
+    pub group_by: Option<String>,
+    /// Language of item names (`display_name`); `None` for the names as written
+    pub lang: Option<String>,
+}
+
+/// The values a report template is rendered with:
+/// - `title`, `description`, `schema_id`: from the schema
+/// - `items`: each item as stored, plus `display_name` and `genus` (the last path element)
+/// - `group_by` and `groups`: `{name, missing, count, items}` per value of the facet, sorted
+///   by name (empty without `group_by`); items without a value go last, to an "Unspecified"
+///   group with `missing` set
+/// - `stats`: `item_count`, `node_count`, `depth`, and per facet `facets` coverage
+///   (`facet`, `items_with_value`, `distinct_values`, `coverage` in percent)
+/// - `distributions`: per facet, its values with their `count`, most used first
+pub fn report_context(
+    schema: &TaxonomySchema,
+    data: &TaxonomyData,
+    options: &ReportOptions,
+) -> Value {
+    let lang = options.lang.as_deref();
+    let items: Vec<Value> = data
+        .items
+        .iter()
+        .map(|item| item_value(item, lang))
+        .collect();
+
+    let groups: Vec<Value> = match &options.group_by {
+        Some(facet) => {
+            let groups = group_items_by_facet(&data.items, facet);
+            get_sorted_group_names(&groups)
+                .into_iter()
+                .map(|key| {
+                    let members = &groups[&key];
+                    json!({
+                        "name": key.name(),
+                        "missing": key.is_missing(),
+                        "count": members.len(),
+                        "items": members.iter().map(|item| item_value(item, lang)).collect::<Vec<_>>(),
+                    })
+                })
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
+    let summary = overview(schema, data);
+    let facets: Vec<Value> = summary
+        .facet_coverage
+        .iter()
+        .map(|coverage| {
+            json!({
+                "facet": coverage.facet,
+                "items_with_value": coverage.items_with_value,
+                "distinct_values": coverage.distinct_values,
+                "coverage": (coverage.ratio() * 100.0).round() as u64,
+            })
+        })
+        .collect();
+    let distributions: serde_json::Map<String, Value> = summary
+        .facet_coverage
+        .iter()
+        .map(|coverage| {
+            let distribution = facet_distribution(schema, &data.items, &coverage.facet);
+            let counts: Vec<Value> = distribution
+                .counts
+                .iter()
+                .map(|c| json!({ "value": c.value, "count": c.count }))
+                .collect();
+            (coverage.facet.clone(), Value::Array(counts))
+        })
+        .collect();
+
+    json!({
+        "title": schema.title,
+        "description": schema.description,
+        "schema_id": schema.schema_id,
+        "items": items,
+        "group_by": options.group_by,
+        "groups": groups,
+        "stats": {
+            "item_count": summary.item_count,
+            "node_count": summary.node_count,
+            "depth": summary.depth,
+            "facets": facets,
+        },
+        "distributions": distributions,
+    })
+}
+
+/// An item as stored, with its display name and genus added
+fn item_value(item: &Item, lang: Option<&str>) -> Value {
+    let mut value = serde_json::to_value(item).unwrap_or(Value::Null);
+    if let Value::Object(fields) = &mut value {
+        fields.insert("display_name".into(), item.display_name(lang).into());
+        let genus = item.classical_path.last().cloned().unwrap_or_default();
+        fields.insert("genus".into(), genus.into());
+    }
+    value
+}
+
+/// Render a MiniJinja (Jinja2 syntax) template with `context`
+/// `name` is the template's file name: values are HTML-escaped when it ends in .html or .htm,
+/// and error messages refer to it. Block tags take their line with them, as with Jinja2's
+/// `trim_blocks` and `lstrip_blocks`, so loops don't leave blank lines behind
+pub fn render_template(
+    name: &str,
+    source: &str,
+    context: &Value,
+) -> Result<String, Box<dyn Error>> {
+    let mut env = Environment::new();
+    env.set_trim_blocks(true);
+    env.set_lstrip_blocks(true);
+    env.set_auto_escape_callback(|name| {
+        let html = [".html", ".htm", ".html.j2", ".htm.j2"]
+            .iter()
+            .any(|ext| name.to_ascii_lowercase().ends_with(ext));
+        if html {
+            AutoEscape::Html
+        } else {
+            AutoEscape::None
+        }
+    });
+    env.add_template(name, source)?;
+    Ok(env.get_template(name)?.render(context)?)
+}
+
+/// Render a report template with the items, groups and statistics of `report_context`
+pub fn render_report(
+    name: &str,
+    source: &str,
+    schema: &TaxonomySchema,
+    data: &TaxonomyData,
+    options: &ReportOptions,
+) -> Result<String, Box<dyn Error>> {
+    render_template(name, source, &report_context(schema, data, options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{HierarchyBuilder, ItemBuilder, TaxonomySchemaBuilder};
+    use std::collections::HashMap;
+
+    fn sample() -> (TaxonomySchema, TaxonomyData) {
+        let schema = TaxonomySchemaBuilder::new("Beverages")
+            .hierarchy(
+                HierarchyBuilder::new()
+                    .root("Beverage")
+                    .leaf("Coffee", "brewed from beans")
+                    .build(),
+            )
+            .facet("temperature", ["hot", "cold"])
+            .build();
+        let data = TaxonomyData {
+            items: vec![
+                ItemBuilder::new("Latte")
+                    .path(["Beverage", "Coffee"])
+                    .facet("temperature", "hot")
+                    .build(),
+                ItemBuilder::new("Iced <Coffee>")
+                    .path(["Beverage", "Coffee"])
+                    .facet("temperature", "cold")
+                    .build(),
+                ItemBuilder::new("Mocha")
+                    .path(["Beverage", "Coffee"])
+                    .facet("temperature", "hot")
+                    .build(),
+            ],
+            schema: String::new(),
+            extra: HashMap::new(),
+        };
+        (schema, data)
+    }
+
+    #[test]
+    fn test_render_report_groups_and_stats() {
+        let (schema, data) = sample();
+        let options = ReportOptions {
+            group_by: Some("temperature".into()),
+            ..ReportOptions::default()
+        };
+        let template = "{{ title }}: {{ stats.item_count }}\n\
+            {% for group in groups %}{{ group.name }}={{ group.count }} {% endfor %}\n\
+            {{ items[0].genus }} {{ distributions.temperature[0].value }}";
+
+        let report = render_report("report.txt", template, &schema, &data, &options).unwrap();
+        assert_eq!(report, "Beverages: 3\ncold=1 hot=2 Coffee hot");
+    }
+
+    #[test]
+    fn test_render_template_escapes_html() {
+        let (schema, data) = sample();
+        let context = report_context(&schema, &data, &ReportOptions::default());
+
+        let html = render_template("report.html", "{{ items[1].name }}", &context).unwrap();
+        assert_eq!(html, "Iced &lt;Coffee&gt;");
+        let text = render_template("report.txt", "{{ items[1].name }}", &context).unwrap();
+        assert_eq!(text, "Iced <Coffee>");
+
+        assert!(render_template("broken.txt", "{% for %}", &context).is_err());
+    }
+}
@@ -0,0 +1,226 @@
+use crate::models::Item;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// House-style rules for items, from a `rules.json` next to the schema or the schema's "x-rules"
+/// Written as a list of rules, or as {"rules": [...]}
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "RuleSetRepr")]
+pub struct RuleSet {
+    pub rules: Vec<Rule>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RuleSetRepr {
+    List(Vec<Rule>),
+    Object { rules: Vec<Rule> },
+}
+
+impl From<RuleSetRepr> for RuleSet {
+    fn from(repr: RuleSetRepr) -> Self {
+        match repr {
+            RuleSetRepr::List(rules) | RuleSetRepr::Object { rules } => RuleSet { rules },
+        }
+    }
+}
+
+/// One declarative rule, e.g. {"check": "max_path_depth", "max": 3, "severity": "warning"}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rule {
+    /// Name shown with each finding, e.g. "title-case"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(flatten)]
+    pub check: RuleCheck,
+    /// Shown instead of the generated description when the rule is broken
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub severity: Severity,
+}
+
+/// What a rule checks on each item
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "check", rename_all = "snake_case")]
+pub enum RuleCheck {
+    /// The item name must match a regular expression
+    NamePattern { pattern: String },
+    /// The classical path may go at most this many levels below the root
+    MaxPathDepth { max: usize },
+    /// Number of values an item gives for a facet (an unset facet has none)
+    FacetCardinality {
+        facet: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<usize>,
+    },
+}
+
+/// Whether a broken rule fails validation or only warns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    #[default]
+    Error,
+    Warning,
+}
+
+/// Findings of a rule set over a list of items, rule by rule in item order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RuleReport {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl RuleSet {
+    /// Parse a rules document (a list of rules or {"rules": [...]})
+    pub fn from_value(value: Value) -> Result<Self, String> {
+        serde_json::from_value(value).map_err(|e| format!("Failed to parse rules: {}", e))
+    }
+
+    /// Rules embedded in a JSON Schema document under "x-rules" (empty when absent)
+    pub fn from_json_schema(json_schema: &Value) -> Result<Self, String> {
+        match json_schema.get("x-rules") {
+            Some(rules) => Self::from_value(rules.clone()),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Add another set's rules after these
+    pub fn extend(&mut self, other: RuleSet) {
+        self.rules.extend(other.rules);
+    }
+
+    /// Check items against every rule
+    /// A rule with an invalid pattern is reported once, as an error
+    pub fn check(&self, items: &[Item]) -> RuleReport {
+        let mut report = RuleReport::default();
+
+        for (idx, rule) in self.rules.iter().enumerate() {
+            let rule_ref = match &rule.id {
+                Some(id) => format!("rule '{}'", id),
+                None => format!("rule #{}", idx + 1),
+            };
+
+            let pattern = match &rule.check {
+                RuleCheck::NamePattern { pattern } => match Regex::new(pattern) {
+                    Ok(regex) => Some(regex),
+                    Err(e) => {
+                        report.errors.push(format!(
+                            "Invalid pattern '{}' in {}: {}",
+                            pattern, rule_ref, e
+                        ));
+                        continue;
+                    }
+                },
+                _ => None,
+            };
+
+            let findings = match rule.severity {
+                Severity::Error => &mut report.errors,
+                Severity::Warning => &mut report.warnings,
+            };
+            for (item_idx, item) in items.iter().enumerate() {
+                if let Some(problem) = rule.check.problem(item, pattern.as_ref()) {
+                    findings.push(format!(
+                        "Item #{} ('{}'): {} ({})",
+                        item_idx + 1,
+                        item.name,
+                        rule.message.as_deref().unwrap_or(&problem),
+                        rule_ref
+                    ));
+                }
+            }
+        }
+
+        report
+    }
+}
+
+impl RuleCheck {
+    /// How the item breaks the check, if it does; `pattern` is the compiled NamePattern
+    fn problem(&self, item: &Item, pattern: Option<&Regex>) -> Option<String> {
+        match self {
+            RuleCheck::NamePattern { pattern: source } => {
+                let regex = pattern?;
+                (!regex.is_match(&item.name))
+                    .then(|| format!("name does not match pattern '{}'", source))
+            }
+            RuleCheck::MaxPathDepth { max } => {
+                let depth = item.classical_path.len().saturating_sub(1);
+                (depth > *max)
+                    .then(|| format!("classical path is {} levels deep (max {})", depth, max))
+            }
+            RuleCheck::FacetCardinality { facet, min, max } => {
+                let count = item.get_facet_as_vec(facet).len();
+                match (min, max) {
+                    (Some(min), _) if count < *min => Some(format!(
+                        "facet '{}' has {} value(s) (min {})",
+                        facet, count, min
+                    )),
+                    (_, Some(max)) if count > *max => Some(format!(
+                        "facet '{}' has {} value(s) (max {})",
+                        facet, count, max
+                    )),
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::ItemBuilder;
+    use serde_json::json;
+
+    #[test]
+    fn test_rules() {
+        let rules = RuleSet::from_value(json!({
+            "rules": [
+                {"id": "title-case", "check": "name_pattern", "pattern": "^[A-Z]"},
+                {"check": "max_path_depth", "max": 2, "severity": "warning"},
+                {"check": "facet_cardinality", "facet": "flavor", "min": 1, "max": 2,
+                 "message": "pick one or two flavors"}
+            ]
+        }))
+        .unwrap();
+        assert_eq!(rules.rules[1].severity, Severity::Warning);
+
+        let items = vec![
+            ItemBuilder::new("Latte")
+                .path(["Beverage", "Hot Beverage", "Coffee"])
+                .facet("flavor", "vanilla")
+                .build(),
+            ItemBuilder::new("cortado")
+                .path(["Beverage", "Hot Beverage", "Coffee", "Espresso"])
+                .facet_values("flavor", ["vanilla", "caramel", "hazelnut"])
+                .build(),
+        ];
+        let report = rules.check(&items);
+        assert_eq!(
+            report.errors,
+            vec![
+                "Item #2 ('cortado'): name does not match pattern '^[A-Z]' (rule 'title-case')",
+                "Item #2 ('cortado'): pick one or two flavors (rule #3)",
+            ]
+        );
+        assert_eq!(
+            report.warnings,
+            vec!["Item #2 ('cortado'): classical path is 3 levels deep (max 2) (rule #2)"]
+        );
+
+        let broken = RuleSet::from_value(json!([{"check": "name_pattern", "pattern": "("}]));
+        let report = broken.unwrap().check(&items);
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].starts_with("Invalid pattern '(' in rule #1"));
+    }
+}
@@ -1,4 +1,9 @@
-use crate::models::{ClassicalHierarchy, TaxonomySchema};
+use crate::models::{
+    ClassicalHierarchy, DisplayHint, FacetConstraint, FacetDefinition, FacetValueNode, ItemField,
+    TaxonomySchema, DEPRECATED_FIELD, SOURCE_FIELD,
+};
+use crate::schema_validation::JsonSchemaDocument;
+use crate::viz::parse_hex_color;
 use serde_json::Value;
 use std::collections::HashMap;
 
@@ -14,16 +19,327 @@ pub fn extract_classical_hierarchy(json_schema: &Value) -> Result<ClassicalHiera
 }
 
 /// Extract faceted dimensions from JSON Schema
-/// Looks for "faceted_dimensions" top-level property
+/// Looks for "faceted_dimensions" top-level property; each is a list of allowed values
+/// (an enum facet, nested values flattened in pre-order) or a typed definition object
 pub fn extract_faceted_dimensions(
     json_schema: &Value,
-) -> Result<HashMap<String, Vec<String>>, String> {
-    let dimensions_value = json_schema
+) -> Result<HashMap<String, FacetDefinition>, String> {
+    serde_json::from_value(faceted_dimensions_value(json_schema)?.clone())
+        .map_err(|e| format!("Failed to parse faceted_dimensions: {}", e))
+}
+
+/// Extract the value trees of dimensions whose allowed values are nested
+/// Dimensions with only flat values are omitted
+pub fn extract_facet_value_trees(
+    json_schema: &Value,
+) -> Result<HashMap<String, Vec<FacetValueNode>>, String> {
+    let dimensions = faceted_dimensions_value(json_schema)?
+        .as_object()
+        .ok_or("Failed to parse faceted_dimensions: expected an object")?;
+
+    let mut trees = HashMap::new();
+    for (name, dimension) in dimensions {
+        // A definition object keeps its (optional) values under "allowed_values"
+        let values = match dimension {
+            Value::Object(definition) => match definition.get("allowed_values") {
+                Some(values) => values,
+                None => continue,
+            },
+            values => values,
+        };
+        let nodes: Vec<FacetValueNode> = serde_json::from_value(values.clone())
+            .map_err(|e| format!("Failed to parse values of facet '{}': {}", name, e))?;
+        if nodes.iter().any(|node| !node.children.is_empty()) {
+            trees.insert(name.clone(), nodes);
+        }
+    }
+    Ok(trees)
+}
+
+/// Extract constraints between facets from JSON Schema
+/// Looks for an optional "constraints" top-level property
+pub fn extract_constraints(json_schema: &Value) -> Result<Vec<FacetConstraint>, String> {
+    match json_schema.get("constraints") {
+        Some(constraints) => serde_json::from_value(constraints.clone())
+            .map_err(|e| format!("Failed to parse constraints: {}", e)),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Extract display hints of facet values from JSON Schema
+/// Looks for an optional "x-display" top-level property: facet → value → hint
+pub fn extract_display_hints(
+    json_schema: &Value,
+) -> Result<HashMap<String, HashMap<String, DisplayHint>>, String> {
+    let Some(display) = json_schema.get("x-display") else {
+        return Ok(HashMap::new());
+    };
+    let hints: HashMap<String, HashMap<String, DisplayHint>> =
+        serde_json::from_value(display.clone())
+            .map_err(|e| format!("Failed to parse x-display: {}", e))?;
+    for (facet, values) in &hints {
+        for (value, hint) in values {
+            if let Some(color) = hint
+                .color
+                .as_deref()
+                .filter(|c| parse_hex_color(c).is_none())
+            {
+                return Err(format!(
+                    "x-display color '{}' of '{}' in facet '{}' is not #rrggbb",
+                    color, value, facet
+                ));
+            }
+        }
+    }
+    Ok(hints)
+}
+
+/// Item properties that custom fields can't be named after
+const RESERVED_FIELDS: &[&str] = &[
+    "name",
+    "classical_path",
+    "facets",
+    "labels",
+    "relations",
+    "attachments",
+    DEPRECATED_FIELD,
+    SOURCE_FIELD,
+];
+
+/// Extract custom per-item fields from JSON Schema
+/// Looks for an optional "item_fields" top-level property
+pub fn extract_item_fields(json_schema: &Value) -> Result<HashMap<String, ItemField>, String> {
+    let Some(fields) = json_schema.get("item_fields") else {
+        return Ok(HashMap::new());
+    };
+    let fields: HashMap<String, ItemField> = serde_json::from_value(fields.clone())
+        .map_err(|e| format!("Failed to parse item_fields: {}", e))?;
+    if let Some(name) = fields
+        .keys()
+        .find(|name| RESERVED_FIELDS.contains(&name.as_str()))
+    {
+        return Err(format!(
+            "Item field '{}' clashes with a built-in item property",
+            name
+        ));
+    }
+    Ok(fields)
+}
+
+fn faceted_dimensions_value(json_schema: &Value) -> Result<&Value, String> {
+    json_schema
         .get("faceted_dimensions")
+        .ok_or_else(|| "JSON Schema missing 'faceted_dimensions' property".to_string())
+}
+
+/// Append facet values to a dimension of a JSON Schema document, creating it as a list if missing
+/// Also extends the `enum` lists that item validation keeps for that facet under "facets"
+pub fn add_facet_values_to_json(
+    json_schema: &mut Value,
+    dimension: &str,
+    values: &[FacetValueNode],
+) -> Result<(), String> {
+    let dimensions = json_schema
+        .get_mut("faceted_dimensions")
+        .and_then(Value::as_object_mut)
         .ok_or("JSON Schema missing 'faceted_dimensions' property")?;
 
-    serde_json::from_value(dimensions_value.clone())
-        .map_err(|e| format!("Failed to parse faceted_dimensions: {}", e))
+    let entry = dimensions
+        .entry(dimension)
+        .or_insert_with(|| Value::Array(Vec::new()));
+    let list = match entry {
+        Value::Object(definition) => definition
+            .entry("allowed_values")
+            .or_insert_with(|| Value::Array(Vec::new())),
+        list => list,
+    }
+    .as_array_mut()
+    .ok_or_else(|| format!("Values of facet '{}' are not a list", dimension))?;
+
+    for value in values {
+        list.push(serde_json::to_value(value).map_err(|e| e.to_string())?);
+    }
+
+    let names: Vec<&str> = values.iter().map(|v| v.value.as_str()).collect();
+    extend_facet_enums(json_schema, dimension, &names);
+    Ok(())
+}
+
+/// Find the facet's property schema under every "facets" object and extend its enums
+fn extend_facet_enums(value: &mut Value, dimension: &str, names: &[&str]) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                if key == "facets" {
+                    if let Some(property) = child
+                        .get_mut("properties")
+                        .and_then(|properties| properties.get_mut(dimension))
+                    {
+                        extend_enums(property, names);
+                    }
+                }
+                extend_facet_enums(child, dimension, names);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                extend_facet_enums(item, dimension, names);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn extend_enums(value: &mut Value, names: &[&str]) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                match child {
+                    Value::Array(list) if key == "enum" => {
+                        for name in names {
+                            if !list.iter().any(|v| v.as_str() == Some(*name)) {
+                                list.push(Value::from(*name));
+                            }
+                        }
+                    }
+                    child => extend_enums(child, names),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                extend_enums(item, names);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Rename a dimension of a JSON Schema document: its "faceted_dimensions" entry and the
+/// property (and "required" entry) item validation keeps for it under "facets"
+pub(crate) fn rename_facet_in_json(json_schema: &mut Value, old: &str, new: &str) {
+    if let Some(dimensions) = json_schema
+        .get_mut("faceted_dimensions")
+        .and_then(Value::as_object_mut)
+    {
+        if let Some(dimension) = dimensions.remove(old) {
+            dimensions.insert(new.to_string(), dimension);
+        }
+    }
+    visit_facet_schemas(json_schema, &mut |facets| {
+        if let Some(properties) = facets.get_mut("properties").and_then(Value::as_object_mut) {
+            if let Some(property) = properties.remove(old) {
+                properties.insert(new.to_string(), property);
+            }
+        }
+        if let Some(required) = facets.get_mut("required").and_then(Value::as_array_mut) {
+            replace_strings(required, old, new);
+        }
+    });
+}
+
+/// Rename an allowed value of a dimension of a JSON Schema document, wherever it is listed
+/// (nested values included), declared as the default, or kept in an `enum` under "facets"
+pub(crate) fn rename_facet_value_in_json(
+    json_schema: &mut Value,
+    dimension: &str,
+    old: &str,
+    new: &str,
+) {
+    if let Some(definition) = json_schema
+        .get_mut("faceted_dimensions")
+        .and_then(|dimensions| dimensions.get_mut(dimension))
+    {
+        let values = match definition {
+            Value::Object(definition) => {
+                if definition.get("default").and_then(Value::as_str) == Some(old) {
+                    definition.insert("default".to_string(), Value::from(new));
+                }
+                definition.get_mut("allowed_values")
+            }
+            values => Some(values),
+        };
+        if let Some(values) = values {
+            rename_value_nodes(values, old, new);
+        }
+    }
+    visit_facet_schemas(json_schema, &mut |facets| {
+        if let Some(property) = facets
+            .get_mut("properties")
+            .and_then(|properties| properties.get_mut(dimension))
+        {
+            rename_enum_values(property, old, new);
+        }
+    });
+}
+
+/// Call `visit` on every "facets" object of a JSON Schema document
+fn visit_facet_schemas(value: &mut Value, visit: &mut impl FnMut(&mut Value)) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                if key == "facets" {
+                    visit(child);
+                }
+                visit_facet_schemas(child, visit);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                visit_facet_schemas(item, visit);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Rename a value in a list of allowed values, given as strings or as
+/// `{"value": ..., "children": [...]}` objects
+fn rename_value_nodes(values: &mut Value, old: &str, new: &str) {
+    let Some(values) = values.as_array_mut() else {
+        return;
+    };
+    for value in values {
+        match value {
+            Value::String(s) if s == old => *s = new.to_string(),
+            Value::Object(node) => {
+                if node.get("value").and_then(Value::as_str) == Some(old) {
+                    node.insert("value".to_string(), Value::from(new));
+                }
+                if let Some(children) = node.get_mut("children") {
+                    rename_value_nodes(children, old, new);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn rename_enum_values(value: &mut Value, old: &str, new: &str) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                match child {
+                    Value::Array(list) if key == "enum" => replace_strings(list, old, new),
+                    child => rename_enum_values(child, old, new),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                rename_enum_values(item, old, new);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn replace_strings(list: &mut [Value], old: &str, new: &str) {
+    for value in list {
+        if value.as_str() == Some(old) {
+            *value = Value::from(new);
+        }
+    }
 }
 
 /// Build TaxonomySchema from a JSON Schema file
@@ -49,6 +365,10 @@ pub fn build_schema_from_json(json_schema: Value) -> Result<TaxonomySchema, Stri
     // Extract classical hierarchy and faceted dimensions
     let classical_hierarchy = extract_classical_hierarchy(&json_schema)?;
     let faceted_dimensions = extract_faceted_dimensions(&json_schema)?;
+    let facet_value_trees = extract_facet_value_trees(&json_schema)?;
+    let constraints = extract_constraints(&json_schema)?;
+    let item_fields = extract_item_fields(&json_schema)?;
+    let display_hints = extract_display_hints(&json_schema)?;
 
     Ok(TaxonomySchema {
         schema_id,
@@ -56,13 +376,18 @@ pub fn build_schema_from_json(json_schema: Value) -> Result<TaxonomySchema, Stri
         description,
         classical_hierarchy,
         faceted_dimensions,
-        json_schema: Some(json_schema),
+        facet_value_trees,
+        constraints,
+        item_fields,
+        display_hints,
+        json: JsonSchemaDocument::new(Some(json_schema)),
     })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::FacetKind;
     use serde_json::json;
 
     #[test]
@@ -95,8 +420,53 @@ mod tests {
 
         let dimensions = extract_faceted_dimensions(&schema).unwrap();
         assert_eq!(dimensions.len(), 2);
-        assert_eq!(dimensions.get("color").unwrap().len(), 3);
-        assert_eq!(dimensions.get("size").unwrap().len(), 3);
+        assert_eq!(dimensions.get("color").unwrap().allowed_values.len(), 3);
+        assert_eq!(dimensions.get("size").unwrap().allowed_values.len(), 3);
+    }
+
+    #[test]
+    fn test_extract_nested_faceted_dimensions() {
+        let schema = json!({
+            "faceted_dimensions": {
+                "color": ["red", "blue"],
+                "region": [
+                    {"value": "Europe", "children": [
+                        "France",
+                        {"value": "Germany", "children": ["Berlin"]}
+                    ]},
+                    "Asia"
+                ]
+            }
+        });
+
+        let dimensions = extract_faceted_dimensions(&schema).unwrap();
+        assert_eq!(
+            dimensions["region"].allowed_values,
+            vec!["Europe", "France", "Germany", "Berlin", "Asia"]
+        );
+
+        let trees = extract_facet_value_trees(&schema).unwrap();
+        assert_eq!(trees.len(), 1);
+        assert_eq!(trees["region"][0].children[1].value, "Germany");
+    }
+
+    #[test]
+    fn test_extract_typed_faceted_dimensions() {
+        let schema = json!({
+            "faceted_dimensions": {
+                "temperature": ["hot", "iced"],
+                "price": {"kind": "number", "required": true, "multi": false},
+                "released": {"kind": "date"}
+            }
+        });
+
+        let dimensions = extract_faceted_dimensions(&schema).unwrap();
+        assert_eq!(dimensions["temperature"].kind, FacetKind::Enum);
+        assert_eq!(dimensions["price"].kind, FacetKind::Number);
+        assert!(dimensions["price"].required);
+        assert!(!dimensions["price"].multi);
+        assert!(dimensions["released"].multi);
+        assert!(extract_facet_value_trees(&schema).unwrap().is_empty());
     }
 
     #[test]
@@ -131,6 +501,43 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_add_facet_values_to_json() {
+        let mut schema = json!({
+            "faceted_dimensions": {
+                "temperature": ["hot"],
+                "size": {"kind": "text"}
+            },
+            "definitions": {
+                "item": {"properties": {"facets": {"properties": {
+                    "temperature": {"oneOf": [
+                        {"type": "string", "enum": ["hot"]},
+                        {"type": "array", "items": {"enum": ["hot"]}}
+                    ]}
+                }}}}
+            }
+        });
+
+        add_facet_values_to_json(&mut schema, "temperature", &[FacetValueNode::new("iced")])
+            .unwrap();
+        add_facet_values_to_json(&mut schema, "size", &[FacetValueNode::new("tall")]).unwrap();
+        add_facet_values_to_json(&mut schema, "origin", &[FacetValueNode::new("Kenya")]).unwrap();
+
+        assert_eq!(
+            schema["faceted_dimensions"]["temperature"],
+            json!(["hot", "iced"])
+        );
+        assert_eq!(
+            schema["faceted_dimensions"]["size"]["allowed_values"],
+            json!(["tall"])
+        );
+        assert_eq!(schema["faceted_dimensions"]["origin"], json!(["Kenya"]));
+        let temperature = &schema["definitions"]["item"]["properties"]["facets"]["properties"]
+            ["temperature"]["oneOf"];
+        assert_eq!(temperature[0]["enum"], json!(["hot", "iced"]));
+        assert_eq!(temperature[1]["items"]["enum"], json!(["hot", "iced"]));
+    }
+
     #[test]
     fn test_missing_faceted_dimensions() {
         let schema = json!({
@@ -140,4 +547,44 @@ mod tests {
         let result = extract_faceted_dimensions(&schema);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_extract_item_fields() {
+        let schema = json!({
+            "item_fields": {
+                "sku": {"label": "SKU", "type": "string", "required": true},
+                "url": {"type": "uri"}
+            }
+        });
+        let fields = extract_item_fields(&schema).unwrap();
+        assert!(fields["sku"].required);
+        assert_eq!(fields["url"].kind, crate::models::FieldKind::Uri);
+        assert!(extract_item_fields(&json!({})).unwrap().is_empty());
+
+        let clash = json!({"item_fields": {"facets": {"type": "string"}}});
+        assert!(extract_item_fields(&clash).is_err());
+    }
+
+    #[test]
+    fn test_extract_display_hints() {
+        let schema = json!({
+            "x-display": {
+                "temperature": {
+                    "hot": {"color": "#d9534f", "icon": "🔥", "order": 2},
+                    "iced": {"order": 1}
+                }
+            }
+        });
+        let hints = extract_display_hints(&schema).unwrap();
+        let hot = &hints["temperature"]["hot"];
+        assert_eq!(hot.color.as_deref(), Some("#d9534f"));
+        assert_eq!(hot.icon.as_deref(), Some("🔥"));
+        assert_eq!(hints["temperature"]["iced"].order, Some(1));
+        assert!(extract_display_hints(&json!({})).unwrap().is_empty());
+
+        let bad_color = json!({"x-display": {"temperature": {"hot": {"color": "red"}}}});
+        assert!(extract_display_hints(&bad_color).is_err());
+        let unknown = json!({"x-display": {"temperature": {"hot": {"shade": "#ffffff"}}}});
+        assert!(extract_display_hints(&unknown).is_err());
+    }
 }
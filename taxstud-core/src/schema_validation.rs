@@ -1,12 +1,22 @@
+use crate::models::TaxonomySchema;
+use jsonschema::Validator;
 use serde_json::Value;
+use std::fmt;
+use std::sync::{Arc, OnceLock};
 
 /// Validate data against JSON Schema
 /// Returns Ok(()) if valid, Err with list of validation errors if invalid
+/// Compiles the schema on every call; `TaxonomySchema::validate_json` compiles it once
 pub fn validate_against_schema(schema: &Value, data: &Value) -> Result<(), Vec<String>> {
-    // Compile the JSON Schema
-    let compiled = jsonschema::validator_for(schema)
-        .map_err(|e| vec![format!("Schema compilation error: {}", e)])?;
+    let compiled = compile(schema).map_err(|e| vec![e])?;
+    validate_with(&compiled, data)
+}
+
+fn compile(schema: &Value) -> Result<Validator, String> {
+    jsonschema::validator_for(schema).map_err(|e| format!("Schema compilation error: {}", e))
+}
 
+fn validate_with(compiled: &Validator, data: &Value) -> Result<(), Vec<String>> {
     // Validate the data - returns Result<(), ValidationError>
     match compiled.validate(data) {
         Ok(()) => Ok(()),
@@ -23,6 +33,75 @@ pub fn validate_against_schema(schema: &Value, data: &Value) -> Result<(), Vec<S
     }
 }
 
+/// A schema's JSON Schema document compiled on first use, shared by its clones
+#[derive(Clone, Default)]
+struct CompiledSchema(OnceLock<Arc<Result<Validator, String>>>);
+
+impl fmt::Debug for CompiledSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = if self.0.get().is_some() {
+            "compiled"
+        } else {
+            "not compiled"
+        };
+        f.debug_tuple("CompiledSchema").field(&state).finish()
+    }
+}
+
+/// A schema's raw JSON Schema document with its compiled copy
+/// The document is only reached through methods that drop a compiled copy it could outdate
+#[derive(Debug, Clone, Default)]
+pub(crate) struct JsonSchemaDocument {
+    document: Option<Value>,
+    compiled: CompiledSchema,
+}
+
+impl JsonSchemaDocument {
+    pub(crate) fn new(document: Option<Value>) -> Self {
+        Self {
+            document,
+            compiled: CompiledSchema::default(),
+        }
+    }
+}
+
+impl TaxonomySchema {
+    /// Validate a document against the JSON Schema, compiling it only the first time
+    /// Ok when the schema has no JSON Schema document
+    pub fn validate_json(&self, data: &Value) -> Result<(), Vec<String>> {
+        let Some(json_schema) = &self.json.document else {
+            return Ok(());
+        };
+        let compiled = self
+            .json
+            .compiled
+            .0
+            .get_or_init(|| Arc::new(compile(json_schema)));
+        match compiled.as_ref() {
+            Ok(compiled) => validate_with(compiled, data),
+            Err(error) => Err(vec![error.clone()]),
+        }
+    }
+
+    /// The raw JSON Schema document the schema was built from, if any
+    pub fn json_schema(&self) -> Option<&Value> {
+        self.json.document.as_ref()
+    }
+
+    /// Replace the JSON Schema document; the next validation compiles the new one
+    /// The hierarchy and facets are not rebuilt from it
+    pub fn set_json_schema(&mut self, document: Option<Value>) {
+        self.json = JsonSchemaDocument::new(document);
+    }
+
+    /// The JSON Schema document to edit; the compiled copy is dropped, so the next
+    /// validation compiles the edited document
+    pub fn json_schema_mut(&mut self) -> Option<&mut Value> {
+        self.json.compiled = CompiledSchema::default();
+        self.json.document.as_mut()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -129,4 +208,51 @@ mod tests {
         let invalid_data = json!({"items": []});
         assert!(validate_against_schema(&schema, &invalid_data).is_err());
     }
+
+    #[test]
+    fn test_validate_json_compiles_once() {
+        use crate::editing::rename_facet;
+        use crate::models::Filters;
+        use crate::testgen::{random_taxonomy, SizeParams};
+
+        let (mut schema, mut data) = random_taxonomy(1, &SizeParams::items(20));
+        let document = serde_json::to_value(&data).unwrap();
+        assert!(schema.json.compiled.0.get().is_none());
+        assert_eq!(schema.validate_json(&document), Ok(()));
+        let compiled = schema.json.compiled.0.get().unwrap().clone();
+
+        // Clones share the compiled schema; validating again doesn't compile
+        let copy = schema.clone();
+        assert_eq!(copy.validate_json(&document), Ok(()));
+        assert!(Arc::ptr_eq(copy.json.compiled.0.get().unwrap(), &compiled));
+
+        // Edits are validated against, not the stale compiled copy
+        schema.json_schema_mut().unwrap()["definitions"]["item"]["properties"]["facets"]
+            ["properties"]["price"] = json!({"type": "string"});
+        assert!(schema.validate_json(&document).is_err());
+
+        let (mut schema, _) = random_taxonomy(1, &SizeParams::items(0));
+        assert_eq!(schema.validate_json(&document), Ok(()));
+        rename_facet(
+            &mut schema,
+            &mut data.items,
+            &mut Filters::default(),
+            "origin",
+            "country",
+        )
+        .unwrap();
+        assert!(schema.validate_json(&document).is_err());
+        let renamed = serde_json::to_value(&data).unwrap();
+        assert_eq!(schema.validate_json(&renamed), Ok(()));
+
+        let mut bare = schema.clone();
+        bare.set_json_schema(None);
+        assert_eq!(bare.validate_json(&json!("anything")), Ok(()));
+
+        // A document set in place of the old one is compiled afresh
+        let mut replaced = copy.clone();
+        replaced.set_json_schema(Some(json!({"type": "string"})));
+        assert!(replaced.validate_json(&document).is_err());
+        assert_eq!(copy.validate_json(&document), Ok(()));
+    }
 }
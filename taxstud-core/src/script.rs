@@ -0,0 +1,371 @@
+//! Rhai scripts run against a taxonomy's items (`scripting` feature)
+//!
+//! A script sees the items and the schema through these functions:
+//!
+//! - `items()` - every item, in document order; `item(name)` - the item named so, or `()`
+//! - `add_item(name, path)` - append an item with a classical path (an array of names)
+//! - `root()`, `facets()`, `facet_values(facet)` - the hierarchy root, the facet names and
+//!   a facet's allowed values
+//!
+//! An item has `name`, `path` and `deprecated` properties (read and write) and `genus`;
+//! `facet(name)`, `set_facet(name, value)` (`()` removes it), `has_facet(name)`,
+//! `field(name)` / `set_field(name, value)` for additional fields, and `is_under(node)`,
+//! true when `node` is on its classical path. `print` output is collected in the report
+//!
+//! ```text
+//! for item in items() {
+//!     if item.is_under("Coffee") && !item.has_facet("origin") {
+//!         item.set_facet("origin", "unknown");
+//!     }
+//! }
+//! ```
+
+use crate::models::{Item, TaxonomySchema};
+use rhai::serde::{from_dynamic, to_dynamic};
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Scope};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::error::Error;
+use std::rc::Rc;
+
+/// Operations a script may run before it is stopped, so a runaway loop ends with an error
+const MAX_OPERATIONS: u64 = 50_000_000;
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// What a script did
+#[derive(Debug, Clone, Default)]
+pub struct ScriptReport {
+    /// Lines the script printed
+    pub output: Vec<String>,
+    /// Items that existed before and were changed
+    pub changed: usize,
+    /// Indices of those items
+    pub changed_items: Vec<usize>,
+    /// Items the script added
+    pub added: usize,
+}
+
+/// The items a script works on, shared by the functions registered with the engine
+type SharedItems = Rc<RefCell<Vec<Item>>>;
+
+/// An item as a script sees it: a handle into the shared items, so changes stick
+#[derive(Clone)]
+struct ItemRef {
+    items: SharedItems,
+    index: usize,
+}
+
+impl ItemRef {
+    fn read<T>(&self, f: impl FnOnce(&Item) -> T) -> T {
+        f(&self.items.borrow()[self.index])
+    }
+
+    fn write(&self, f: impl FnOnce(&mut Item)) {
+        f(&mut self.items.borrow_mut()[self.index])
+    }
+}
+
+/// Run `source` against `items`, which are changed only when the script succeeds
+/// The changes are not validated; check the data before saving it
+pub fn run_script(
+    source: &str,
+    schema: &TaxonomySchema,
+    items: &mut Vec<Item>,
+) -> Result<ScriptReport, Box<dyn Error>> {
+    let shared: SharedItems = Rc::new(RefCell::new(items.clone()));
+    let output = Rc::new(RefCell::new(Vec::new()));
+
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    let printed = output.clone();
+    engine.on_print(move |line| printed.borrow_mut().push(line.to_string()));
+    let printed = output.clone();
+    engine.on_debug(move |line, _, _| printed.borrow_mut().push(line.to_string()));
+    register_items(&mut engine, &shared);
+    register_item_type(&mut engine);
+    register_schema(&mut engine, schema);
+
+    engine
+        .run_with_scope(&mut Scope::new(), source)
+        .map_err(|e| e.to_string())?;
+    drop(engine);
+
+    let updated = std::mem::take(&mut *shared.borrow_mut());
+    let changed_items: Vec<usize> = items
+        .iter()
+        .zip(&updated)
+        .enumerate()
+        .filter(|(_, (before, after))| {
+            serde_json::to_value(before).ok() != serde_json::to_value(after).ok()
+        })
+        .map(|(index, _)| index)
+        .collect();
+    let report = ScriptReport {
+        output: output.take(),
+        changed: changed_items.len(),
+        changed_items,
+        added: updated.len() - items.len(),
+    };
+    *items = updated;
+    Ok(report)
+}
+
+/// `items()`, `item(name)` and `add_item(name, path)`
+fn register_items(engine: &mut Engine, shared: &SharedItems) {
+    let items = shared.clone();
+    engine.register_fn("items", move || -> Array {
+        (0..items.borrow().len())
+            .map(|index| {
+                Dynamic::from(ItemRef {
+                    items: items.clone(),
+                    index,
+                })
+            })
+            .collect()
+    });
+
+    let items = shared.clone();
+    engine.register_fn("item", move |name: &str| -> Dynamic {
+        let index = items.borrow().iter().position(|item| item.name == name);
+        index.map_or(Dynamic::UNIT, |index| {
+            Dynamic::from(ItemRef {
+                items: items.clone(),
+                index,
+            })
+        })
+    });
+
+    let items = shared.clone();
+    engine.register_fn(
+        "add_item",
+        move |name: &str, path: Array| -> ScriptResult<ItemRef> {
+            if items.borrow().iter().any(|item| item.name == name) {
+                return Err(format!("An item named '{}' already exists", name).into());
+            }
+            let item = Item {
+                name: name.to_string(),
+                classical_path: strings(path)?,
+                facets: HashMap::new(),
+                labels: HashMap::new(),
+                relations: Vec::new(),
+                attachments: Vec::new(),
+                extra: HashMap::new(),
+            };
+            items.borrow_mut().push(item);
+            Ok(ItemRef {
+                items: items.clone(),
+                index: items.borrow().len() - 1,
+            })
+        },
+    );
+}
+
+/// The `Item` type's properties and methods
+fn register_item_type(engine: &mut Engine) {
+    engine
+        .register_type_with_name::<ItemRef>("Item")
+        .register_fn("to_string", |item: &mut ItemRef| {
+            item.read(|i| i.name.clone())
+        })
+        .register_fn("to_debug", |item: &mut ItemRef| {
+            item.read(|i| format!("Item({})", i.name))
+        })
+        .register_get("name", |item: &mut ItemRef| item.read(|i| i.name.clone()))
+        .register_set(
+            "name",
+            |item: &mut ItemRef, name: &str| -> ScriptResult<()> {
+                let taken = item.read(|i| i.name != name)
+                    && item.items.borrow().iter().any(|other| other.name == name);
+                if taken {
+                    return Err(format!("An item named '{}' already exists", name).into());
+                }
+                item.write(|i| i.name = name.to_string());
+                Ok(())
+            },
+        )
+        .register_get("path", |item: &mut ItemRef| -> Array {
+            item.read(|i| {
+                i.classical_path
+                    .iter()
+                    .cloned()
+                    .map(Dynamic::from)
+                    .collect()
+            })
+        })
+        .register_set(
+            "path",
+            |item: &mut ItemRef, path: Array| -> ScriptResult<()> {
+                let path = strings(path)?;
+                item.write(|i| i.classical_path = path);
+                Ok(())
+            },
+        )
+        .register_get("genus", |item: &mut ItemRef| {
+            item.read(|i| i.classical_path.last().cloned().unwrap_or_default())
+        })
+        .register_get("deprecated", |item: &mut ItemRef| {
+            item.read(Item::is_deprecated)
+        })
+        .register_set("deprecated", |item: &mut ItemRef, deprecated: bool| {
+            item.write(|i| i.set_deprecated(deprecated))
+        })
+        .register_fn("is_under", |item: &mut ItemRef, node: &str| {
+            item.read(|i| i.classical_path.iter().any(|n| n == node))
+        })
+        .register_fn("has_facet", |item: &mut ItemRef, facet: &str| {
+            item.read(|i| !i.get_facet_as_vec(facet).is_empty())
+        })
+        .register_fn("facet", |item: &mut ItemRef, facet: &str| {
+            item.read(|i| to_script_value(i.facets.get(facet)))
+        })
+        .register_fn(
+            "set_facet",
+            |item: &mut ItemRef, facet: &str, value: Dynamic| -> ScriptResult<()> {
+                let value = from_script_value(value)?;
+                item.write(|i| set_or_remove(&mut i.facets, facet, value));
+                Ok(())
+            },
+        )
+        .register_fn("remove_facet", |item: &mut ItemRef, facet: &str| {
+            item.write(|i| {
+                i.facets.remove(facet);
+            })
+        })
+        .register_fn("field", |item: &mut ItemRef, field: &str| {
+            item.read(|i| to_script_value(i.extra.get(field)))
+        })
+        .register_fn(
+            "set_field",
+            |item: &mut ItemRef, field: &str, value: Dynamic| -> ScriptResult<()> {
+                let value = from_script_value(value)?;
+                item.write(|i| set_or_remove(&mut i.extra, field, value));
+                Ok(())
+            },
+        );
+}
+
+/// `root()`, `facets()` and `facet_values(facet)`
+fn register_schema(engine: &mut Engine, schema: &TaxonomySchema) {
+    let root = schema.classical_hierarchy.root.clone();
+    engine.register_fn("root", move || root.clone());
+
+    let mut names: Vec<String> = schema.faceted_dimensions.keys().cloned().collect();
+    names.sort();
+    engine.register_fn("facets", move || -> Array {
+        names.iter().cloned().map(Dynamic::from).collect()
+    });
+
+    let facets = schema.faceted_dimensions.clone();
+    engine.register_fn("facet_values", move |facet: &str| -> Array {
+        facets
+            .get(facet)
+            .map(|definition| {
+                definition
+                    .allowed_values
+                    .iter()
+                    .cloned()
+                    .map(Dynamic::from)
+                    .collect()
+            })
+            .unwrap_or_default()
+    });
+}
+
+fn strings(array: Array) -> ScriptResult<Vec<String>> {
+    array
+        .into_iter()
+        .map(|value| {
+            value
+                .into_string()
+                .map_err(|kind| format!("Expected a string, got {}", kind).into())
+        })
+        .collect()
+}
+
+fn to_script_value(value: Option<&Value>) -> Dynamic {
+    value
+        .and_then(|value| to_dynamic(value).ok())
+        .unwrap_or(Dynamic::UNIT)
+}
+
+fn from_script_value(value: Dynamic) -> ScriptResult<Value> {
+    from_dynamic(&value)
+}
+
+fn set_or_remove(map: &mut HashMap<String, Value>, key: &str, value: Value) {
+    if value.is_null() {
+        map.remove(key);
+    } else {
+        map.insert(key.to_string(), value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{HierarchyBuilder, ItemBuilder, TaxonomySchemaBuilder};
+
+    fn schema() -> TaxonomySchema {
+        TaxonomySchemaBuilder::new("Beverages")
+            .hierarchy(
+                HierarchyBuilder::new()
+                    .root("Beverage")
+                    .leaf("Coffee", "brewed from beans")
+                    .leaf("Tea", "steeped from leaves")
+                    .build(),
+            )
+            .facet("origin", ["ethiopia", "unknown"])
+            .build()
+    }
+
+    #[test]
+    fn test_run_script() {
+        let mut items = vec![
+            ItemBuilder::new("Espresso")
+                .path(["Beverage", "Coffee"])
+                .build(),
+            ItemBuilder::new("Mocha")
+                .path(["Beverage", "Coffee"])
+                .facet("origin", "ethiopia")
+                .build(),
+            ItemBuilder::new("Sencha").path(["Beverage", "Tea"]).build(),
+        ];
+        let source = r#"
+            for item in items() {
+                if item.is_under("Coffee") && !item.has_facet("origin") {
+                    item.set_facet("origin", "unknown");
+                    print(item.name);
+                }
+            }
+            let latte = add_item("Latte", [root(), "Coffee"]);
+            latte.set_facet("origin", facet_values("origin")[0]);
+            let sencha = item("Sencha");
+            sencha.deprecated = true;
+        "#;
+
+        let report = run_script(source, &schema(), &mut items).unwrap();
+        assert_eq!(report.output, vec!["Espresso"]);
+        assert_eq!(report.changed_items, vec![0, 2]);
+        assert_eq!(report.added, 1);
+        assert_eq!(items[0].get_facet_as_string("origin").unwrap(), "unknown");
+        assert!(items[2].is_deprecated());
+        assert_eq!(items[3].classical_path, vec!["Beverage", "Coffee"]);
+        assert_eq!(items[3].get_facet_as_string("origin").unwrap(), "ethiopia");
+    }
+
+    #[test]
+    fn test_failed_script_changes_nothing() {
+        let mut items = vec![ItemBuilder::new("Espresso")
+            .path(["Beverage", "Coffee"])
+            .build()];
+        let source = r#"
+            items()[0].set_facet("origin", "unknown");
+            add_item("Espresso", []);
+        "#;
+
+        assert!(run_script(source, &schema(), &mut items).is_err());
+        assert!(items[0].facets.is_empty());
+    }
+}
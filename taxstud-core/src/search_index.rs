@@ -0,0 +1,150 @@
+use crate::models::{FacetKind, Item, TaxonomyData, TaxonomySchema};
+use serde_json::{json, Map, Value};
+use std::io::{self, Write};
+
+/// An Elasticsearch/OpenSearch document for an item
+/// `name`, `genus`, `path` (the classical path), `depth`, one `level_<n>` field per path
+/// segment (`level_0` is the root), the facets under `facets` (multiple values as arrays)
+/// and the additional fields, stored but not indexed, under `fields`
+pub fn search_document(item: &Item) -> Value {
+    let mut document = Map::new();
+    document.insert("name".into(), json!(item.name));
+    document.insert("genus".into(), json!(item.classical_path.last()));
+    document.insert("path".into(), json!(item.classical_path));
+    document.insert(
+        "depth".into(),
+        json!(item.classical_path.len().saturating_sub(1)),
+    );
+    for (level, node) in item.classical_path.iter().enumerate() {
+        document.insert(format!("level_{}", level), json!(node));
+    }
+    document.insert("facets".into(), json!(item.facets));
+    if !item.extra.is_empty() {
+        document.insert("fields".into(), json!(item.extra));
+    }
+    Value::Object(document)
+}
+
+/// Write the items as a bulk-index request body: an `index` action with the item's ID
+/// (see `Item::id`) and its `search_document`, one JSON object per line
+/// Send it to `POST /<index>/_bulk` with `Content-Type: application/x-ndjson`
+pub fn write_bulk_ndjson(data: &TaxonomyData, out: &mut dyn Write) -> io::Result<()> {
+    for item in &data.items {
+        let action = json!({ "index": { "_id": item.id() } });
+        serde_json::to_writer(&mut *out, &action)?;
+        writeln!(out)?;
+        serde_json::to_writer(&mut *out, &search_document(item))?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// The index mappings for `search_document`s of the schema's items, as the body of
+/// `PUT /<index>`: names are text with a keyword subfield, hierarchy fields and enum facets
+/// keywords, and number, date and boolean facets typed as such
+pub fn search_index_mapping(schema: &TaxonomySchema) -> Value {
+    let keyword = json!({ "type": "keyword" });
+    let depth = schema
+        .classical_hierarchy
+        .iter_preorder()
+        .map(|(depth, _)| depth)
+        .max()
+        .unwrap_or(0);
+
+    let mut properties = Map::new();
+    properties.insert(
+        "name".into(),
+        json!({ "type": "text", "fields": { "keyword": { "type": "keyword" } } }),
+    );
+    properties.insert("genus".into(), keyword.clone());
+    properties.insert("path".into(), keyword.clone());
+    properties.insert("depth".into(), json!({ "type": "integer" }));
+    for level in 0..=depth {
+        properties.insert(format!("level_{}", level), keyword.clone());
+    }
+
+    let facets: Map<String, Value> = schema
+        .faceted_dimensions
+        .iter()
+        .map(|(name, definition)| {
+            let mapping = match definition.kind {
+                FacetKind::Enum | FacetKind::Url => keyword.clone(),
+                FacetKind::Text => {
+                    json!({ "type": "text", "fields": { "keyword": { "type": "keyword" } } })
+                }
+                FacetKind::Number => json!({ "type": "double" }),
+                FacetKind::Date => json!({ "type": "date", "format": "yyyy-MM-dd" }),
+                FacetKind::Boolean => json!({ "type": "boolean" }),
+            };
+            (name.clone(), mapping)
+        })
+        .collect();
+    properties.insert("facets".into(), json!({ "properties": facets }));
+    properties.insert(
+        "fields".into(),
+        json!({ "type": "object", "enabled": false }),
+    );
+
+    json!({
+        "mappings": {
+            "dynamic": false,
+            "_meta": { "schema_id": schema.schema_id, "title": schema.title },
+            "properties": properties,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{HierarchyBuilder, ItemBuilder, TaxonomySchemaBuilder};
+    use crate::models::FacetDefinition;
+
+    #[test]
+    fn test_bulk_export_and_mapping() {
+        let schema = TaxonomySchemaBuilder::new("Beverages")
+            .hierarchy(
+                HierarchyBuilder::new()
+                    .root("Beverage")
+                    .child("Hot Beverage", "served heated", |c| {
+                        c.leaf("Coffee", "brewed from beans")
+                    })
+                    .build(),
+            )
+            .facet("temperature", ["hot", "cold"])
+            .facet_definition("caffeine", FacetDefinition::new(FacetKind::Number))
+            .build();
+        let data = TaxonomyData {
+            schema: "schema.json".to_string(),
+            items: vec![ItemBuilder::new("Latte")
+                .path(["Beverage", "Hot Beverage", "Coffee"])
+                .facet_values("temperature", ["hot"])
+                .extra("id", "LATTE-1")
+                .build()],
+            extra: Default::default(),
+        };
+
+        let mut out = Vec::new();
+        write_bulk_ndjson(&data, &mut out).unwrap();
+        let lines: Vec<Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], json!({ "index": { "_id": "LATTE-1" } }));
+        assert_eq!(lines[1]["genus"], "Coffee");
+        assert_eq!(lines[1]["depth"], 2);
+        assert_eq!(lines[1]["level_1"], "Hot Beverage");
+        assert_eq!(lines[1]["facets"]["temperature"], json!(["hot"]));
+        assert_eq!(lines[1]["fields"]["id"], "LATTE-1");
+
+        let mapping = search_index_mapping(&schema);
+        let properties = &mapping["mappings"]["properties"];
+        assert_eq!(properties["level_2"]["type"], "keyword");
+        assert!(properties.get("level_3").is_none());
+        let facets = &properties["facets"]["properties"];
+        assert_eq!(facets["temperature"]["type"], "keyword");
+        assert_eq!(facets["caffeine"]["type"], "double");
+    }
+}
@@ -1,67 +1,431 @@
-use crate::models::Item;
-use regex::Regex;
+use crate::facets::is_iso_date;
+use crate::models::{FacetKind, Item, TaxonomySchema};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use unicode_normalization::char::is_combining_mark;
 use unicode_normalization::UnicodeNormalization;
 
+/// Articles stripped from the start of names before sorting, unless configured otherwise:
+/// a, an, the (English) and common articles in other languages
+pub const DEFAULT_ARTICLES: &[&str] = &[
+    "the", "a", "an", "der", "die", "das", "le", "la", "les", "el", "los", "las", "il", "lo", "i",
+    "gli", "un", "une", "een",
+];
+
+/// How names and facet values compare when sorting
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortOptions {
+    /// Leading words ignored when sorting, matched case-insensitively
+    pub articles: Vec<String>,
+    /// Largest or last values first; items lacking a typed facet's value stay last
+    pub descending: bool,
+}
+
+impl Default for SortOptions {
+    fn default() -> Self {
+        Self {
+            articles: DEFAULT_ARTICLES.iter().map(|a| a.to_string()).collect(),
+            descending: false,
+        }
+    }
+}
+
+impl SortOptions {
+    /// The key `s` sorts by: normalize_for_sorting with these articles
+    pub fn sort_key(&self, s: &str) -> String {
+        Collator::new(self).key(s)
+    }
+}
+
 /// Sort items by the specified field (either "name" or a facet name)
 pub fn sort_items(items: &mut [Item], sort_field: &str) {
-    items.sort_by(|a, b| {
-        if sort_field == "name" {
-            // Library science sorting: strip articles, normalize unicode, handle numbers
-            let a_key = normalize_for_sorting(&a.name);
-            let b_key = normalize_for_sorting(&b.name);
-
-            // Primary sort by normalized name
-            match a_key.cmp(&b_key) {
-                std::cmp::Ordering::Equal => {
-                    // Secondary sort: original name for ties
-                    a.name.cmp(&b.name)
-                }
-                other => other,
+    sort_by_text(items, |item| item, sort_field, &SortOptions::default());
+}
+
+/// Sort items using the schema's facet kinds
+/// Number facets sort numerically, date and boolean facets by value, with items lacking a value last
+/// Names and enum/text facets sort like sort_items
+pub fn sort_items_with_schema(items: &mut [Item], sort_field: &str, schema: &TaxonomySchema) {
+    sort_items_with_options(items, sort_field, Some(schema), &SortOptions::default());
+}
+
+/// Sort items like sort_items_with_schema (or sort_items without a schema), stripping
+/// the articles of `options` from names and text values
+pub fn sort_items_with_options(
+    items: &mut [Item],
+    sort_field: &str,
+    schema: Option<&TaxonomySchema>,
+    options: &SortOptions,
+) {
+    sort_with_options(items, |item| item, sort_field, schema, options);
+}
+
+/// Sort indices into `items` the way sort_items_with_options sorts the items themselves,
+/// leaving the items where they are
+pub fn sort_indices_with_options(
+    items: &[Item],
+    indices: &mut [usize],
+    sort_field: &str,
+    schema: Option<&TaxonomySchema>,
+    options: &SortOptions,
+) {
+    let mut entries: Vec<(usize, &Item)> = indices
+        .iter()
+        .map(|&index| (index, &items[index]))
+        .collect();
+    sort_with_options(&mut entries, |entry| entry.1, sort_field, schema, options);
+    for (index, (sorted, _)) in indices.iter_mut().zip(entries) {
+        *index = sorted;
+    }
+}
+
+fn sort_with_options<T>(
+    entries: &mut [T],
+    item_of: impl Fn(&T) -> &Item,
+    sort_field: &str,
+    schema: Option<&TaxonomySchema>,
+    options: &SortOptions,
+) {
+    let kind = schema
+        .and_then(|schema| schema.faceted_dimensions.get(sort_field))
+        .map(|definition| definition.kind);
+
+    match kind {
+        Some(FacetKind::Number) => {
+            sort_by_typed_facet(entries, item_of, sort_field, options, |v| {
+                v.parse::<f64>().ok()
+            })
+        }
+        Some(FacetKind::Date) => sort_by_typed_facet(entries, item_of, sort_field, options, |v| {
+            is_iso_date(v).then(|| v.to_string())
+        }),
+        Some(FacetKind::Boolean) => {
+            sort_by_typed_facet(entries, item_of, sort_field, options, |v| {
+                v.parse::<bool>().ok()
+            })
+        }
+        _ => sort_by_text(entries, item_of, sort_field, options),
+    }
+}
+
+fn sort_by_text<T>(
+    entries: &mut [T],
+    item_of: impl Fn(&T) -> &Item,
+    sort_field: &str,
+    options: &SortOptions,
+) {
+    let collator = Collator::new(options);
+    // Primary key, then the tie-breaker: names tie on the original name, facet values on
+    // the name's key
+    let keys: Vec<(String, String)> = entries
+        .iter()
+        .map(|entry| {
+            let item = item_of(entry);
+            if sort_field == "name" {
+                (collator.key(&item.name), item.name.clone())
+            } else {
+                let value = item.get_facet_as_string(sort_field).unwrap_or_default();
+                (collator.key(&value), collator.key(&item.name))
             }
+        })
+        .collect();
+
+    sort_by_keys(entries, &keys, |a, b| {
+        let ordering = a.cmp(b);
+        if options.descending {
+            ordering.reverse()
         } else {
-            // Sort by facet value
-            let a_val = a.get_facet_as_string(sort_field).unwrap_or_default();
-            let b_val = b.get_facet_as_string(sort_field).unwrap_or_default();
-
-            // Normalize facet values for sorting
-            let a_key = normalize_for_sorting(&a_val);
-            let b_key = normalize_for_sorting(&b_val);
-
-            // Primary sort by normalized facet, secondary by name
-            match a_key.cmp(&b_key) {
-                std::cmp::Ordering::Equal => {
-                    let a_name_key = normalize_for_sorting(&a.name);
-                    let b_name_key = normalize_for_sorting(&b.name);
-                    a_name_key.cmp(&b_name_key)
-                }
-                other => other,
-            }
+            ordering
         }
     });
 }
 
+fn sort_by_typed_facet<T, K: PartialOrd>(
+    entries: &mut [T],
+    item_of: impl Fn(&T) -> &Item,
+    sort_field: &str,
+    options: &SortOptions,
+    key: impl Fn(&str) -> Option<K>,
+) {
+    let collator = Collator::new(options);
+    let keys: Vec<(Option<K>, String)> = entries
+        .iter()
+        .map(|entry| {
+            let item = item_of(entry);
+            let value = item
+                .get_facet_as_vec(sort_field)
+                .first()
+                .and_then(|v| key(v.trim()));
+            (value, collator.key(&item.name))
+        })
+        .collect();
+
+    sort_by_keys(entries, &keys, |(a_value, a_name), (b_value, b_name)| {
+        let ordering = match (a_value, b_value) {
+            (Some(a_key), Some(b_key)) if options.descending => {
+                b_key.partial_cmp(a_key).unwrap_or(Ordering::Equal)
+            }
+            (Some(a_key), Some(b_key)) => a_key.partial_cmp(b_key).unwrap_or(Ordering::Equal),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+        ordering.then_with(|| a_name.cmp(b_name))
+    });
+}
+
+/// Stable sort of `entries` by `keys`, one per entry and worked out beforehand, so each
+/// key is computed once rather than on every comparison
+fn sort_by_keys<T, K>(entries: &mut [T], keys: &[K], compare: impl Fn(&K, &K) -> Ordering) {
+    let mut order: Vec<usize> = (0..entries.len()).collect();
+    order.sort_by(|&a, &b| compare(&keys[a], &keys[b]));
+
+    // Move the entries into place, one cycle of the permutation at a time: position
+    // `current` takes the entry at `order[current]`
+    for start in 0..order.len() {
+        let mut current = start;
+        while order[current] != start {
+            let next = order[current];
+            entries.swap(current, next);
+            order[current] = current;
+            current = next;
+        }
+        order[current] = current;
+    }
+}
+
+/// Sort keys under a SortOptions's articles, lowercased once rather than per key
+struct Collator {
+    articles: Vec<String>,
+}
+
+impl Collator {
+    fn new(options: &SortOptions) -> Self {
+        Self {
+            articles: options.articles.iter().map(|a| a.to_lowercase()).collect(),
+        }
+    }
+
+    fn key(&self, s: &str) -> String {
+        sort_key(s, &self.articles)
+    }
+}
+
 /// Normalize string for library science sorting
 /// - Strip leading articles (a, an, the)
 /// - Normalize unicode (NFD then lowercase)
 /// - Handle punctuation
 /// - Preserve numbers for natural sorting
 pub fn normalize_for_sorting(s: &str) -> String {
+    sort_key(s, DEFAULT_ARTICLES)
+}
+
+/// `articles` are lowercase, as in DEFAULT_ARTICLES and Collator
+fn sort_key<A: AsRef<str>>(s: &str, articles: &[A]) -> String {
     // Strip leading articles (case-insensitive)
-    let without_articles = strip_leading_articles(s);
+    let without_articles = strip_articles(s, articles);
 
     // Unicode normalization (NFD decomposition) and lowercase
-    let normalized: String = without_articles.nfd().collect::<String>().to_lowercase();
+    let normalized = decompose(without_articles).to_lowercase();
 
     // Remove leading/trailing whitespace and collapse internal whitespace
-    normalized.split_whitespace().collect::<Vec<_>>().join(" ")
+    let mut key = String::with_capacity(normalized.len());
+    for word in normalized.split_whitespace() {
+        if !key.is_empty() {
+            key.push(' ');
+        }
+        key.push_str(word);
+    }
+    key
+}
+
+/// `s` without accents and other combining marks: "Café" is "Cafe", "Ñandú" is "Nandu"
+pub fn strip_diacritics(s: &str) -> Cow<'_, str> {
+    match decompose(s) {
+        Cow::Borrowed(s) => Cow::Borrowed(s),
+        Cow::Owned(decomposed) => Cow::Owned(
+            decomposed
+                .chars()
+                .filter(|c| !is_combining_mark(*c))
+                .collect(),
+        ),
+    }
+}
+
+/// `s` in NFD; ASCII already is
+fn decompose(s: &str) -> Cow<'_, str> {
+    if s.is_ascii() {
+        Cow::Borrowed(s)
+    } else {
+        Cow::Owned(s.nfd().collect())
+    }
 }
 
 /// Strip leading articles following library science conventions
 /// Supports: a, an, the (English) and common articles in other languages
 pub fn strip_leading_articles(s: &str) -> String {
-    let re = Regex::new(
-        r"^(?i)(the|a|an|der|die|das|le|la|les|el|la|los|las|il|lo|i|gli|un|une|een)\s+",
-    )
-    .unwrap();
-    re.replace(s, "").to_string()
+    strip_articles(s, DEFAULT_ARTICLES).to_string()
+}
+
+/// `s` without its first word when that is one of `articles` (lowercase) followed by
+/// whitespace
+fn strip_articles<'a, A: AsRef<str>>(s: &'a str, articles: &[A]) -> &'a str {
+    let Some((word, rest)) = s.split_once(char::is_whitespace) else {
+        return s;
+    };
+    let word = word.to_lowercase();
+    if articles.iter().any(|article| article.as_ref() == word) {
+        rest.trim_start()
+    } else {
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{ItemBuilder, TaxonomySchemaBuilder};
+    use crate::models::FacetDefinition;
+
+    #[test]
+    fn test_strip_diacritics() {
+        assert_eq!(strip_diacritics("Café"), "Cafe");
+        assert_eq!(strip_diacritics("Ñandú Crème"), "Nandu Creme");
+        assert!(matches!(strip_diacritics("plain"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_number_facets_sort_numerically() {
+        let schema = TaxonomySchemaBuilder::new("Menu")
+            .facet_definition("price", FacetDefinition::new(FacetKind::Number))
+            .build();
+        let mut items = vec![
+            ItemBuilder::new("Latte").facet("price", "10").build(),
+            ItemBuilder::new("Tea").build(),
+            ItemBuilder::new("Espresso").facet("price", "9.5").build(),
+        ];
+
+        sort_items_with_schema(&mut items, "price", &schema);
+        let names: Vec<_> = items.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, vec!["Espresso", "Latte", "Tea"]);
+
+        // Sorting indices leaves the items in place
+        let unsorted = items.clone();
+        let mut indices = vec![2, 1, 0];
+        sort_indices_with_options(
+            &unsorted,
+            &mut indices,
+            "price",
+            Some(&schema),
+            &SortOptions::default(),
+        );
+        assert_eq!(indices, vec![0, 1, 2]);
+        let descending = SortOptions {
+            descending: true,
+            ..SortOptions::default()
+        };
+        sort_indices_with_options(&unsorted, &mut indices, "name", None, &descending);
+        assert_eq!(indices, vec![2, 1, 0]);
+        assert_eq!(unsorted[0].name, "Espresso");
+
+        // As text, "10" sorts before "9.5"
+        sort_items(&mut items, "price");
+        assert_eq!(items[1].name, "Latte");
+
+        // Descending, the item without a price stays last
+        let options = SortOptions {
+            descending: true,
+            ..SortOptions::default()
+        };
+        sort_items_with_options(&mut items, "price", Some(&schema), &options);
+        let names: Vec<_> = items.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, vec!["Latte", "Espresso", "Tea"]);
+        sort_items_with_options(&mut items, "name", None, &options);
+        let names: Vec<_> = items.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, vec!["Tea", "Latte", "Espresso"]);
+    }
+
+    #[test]
+    fn test_configured_articles() {
+        let mut items = vec![
+            ItemBuilder::new("Mango").build(),
+            ItemBuilder::new("La Paz").build(),
+            ItemBuilder::new("Los Angeles").build(),
+        ];
+
+        sort_items(&mut items, "name");
+        let names: Vec<_> = items.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, vec!["Los Angeles", "Mango", "La Paz"]);
+
+        // Only "the" is an article: the Spanish names sort under L
+        let options = SortOptions {
+            articles: vec!["THE".to_string()],
+            ..SortOptions::default()
+        };
+        sort_items_with_options(&mut items, "name", None, &options);
+        let names: Vec<_> = items.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, vec!["La Paz", "Los Angeles", "Mango"]);
+        assert_eq!(options.sort_key("The  Zebra"), "zebra");
+        assert_eq!(strip_leading_articles("Los Angeles"), "Angeles");
+    }
+
+    fn names(items: &[Item]) -> Vec<&str> {
+        items.iter().map(|item| item.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_sort_keys_computed_once_sort_the_same() {
+        let (schema, data) =
+            crate::testgen::random_taxonomy(5, &crate::testgen::SizeParams::items(300));
+        for descending in [false, true] {
+            let options = SortOptions {
+                descending,
+                ..SortOptions::default()
+            };
+            for field in ["name", "temperature"] {
+                let mut expected = data.items.clone();
+                expected.sort_by(|a, b| {
+                    let ordering = if field == "name" {
+                        options
+                            .sort_key(&a.name)
+                            .cmp(&options.sort_key(&b.name))
+                            .then_with(|| a.name.cmp(&b.name))
+                    } else {
+                        let value = |item: &Item| {
+                            options.sort_key(&item.get_facet_as_string(field).unwrap_or_default())
+                        };
+                        value(a)
+                            .cmp(&value(b))
+                            .then_with(|| options.sort_key(&a.name).cmp(&options.sort_key(&b.name)))
+                    };
+                    if descending {
+                        ordering.reverse()
+                    } else {
+                        ordering
+                    }
+                });
+                let mut items = data.items.clone();
+                sort_items_with_options(&mut items, field, Some(&schema), &options);
+                assert_eq!(names(&items), names(&expected));
+            }
+
+            for field in ["name", "temperature", "price", "added"] {
+                let mut items = data.items.clone();
+                sort_items_with_options(&mut items, field, Some(&schema), &options);
+                let mut indices: Vec<usize> = (0..data.items.len()).collect();
+                sort_indices_with_options(
+                    &data.items,
+                    &mut indices,
+                    field,
+                    Some(&schema),
+                    &options,
+                );
+                let by_index: Vec<&str> = indices
+                    .iter()
+                    .map(|&i| data.items[i].name.as_str())
+                    .collect();
+                assert_eq!(by_index, names(&items));
+            }
+        }
+    }
 }
@@ -0,0 +1,262 @@
+use crate::io::{save_data_with_options, SaveOptions};
+use crate::models::{ClassicalHierarchy, Item, TaxonomyData, TaxonomySchema};
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// One data file of a split taxonomy
+#[derive(Debug, Clone)]
+pub struct SplitPart {
+    /// Species whose subtree the part holds
+    pub species: String,
+    /// File name to write the part to, e.g. "hot-beverage.json"
+    pub file_name: String,
+    pub data: TaxonomyData,
+}
+
+/// A taxonomy's items split by the child species of a genus
+#[derive(Debug, Clone)]
+pub struct Split {
+    /// One part per child subtree with items, in hierarchy order
+    pub parts: Vec<SplitPart>,
+    /// Items under no child of the genus: classified at the genus itself, or outside it
+    pub unassigned: Vec<Item>,
+}
+
+/// Partition a taxonomy's items by the child species of `genus`, one part per child
+/// subtree with items; items not under any child are left out of the parts, in `unassigned`
+pub fn split_by_genus(
+    data: &TaxonomyData,
+    hierarchy: &ClassicalHierarchy,
+    genus: &str,
+) -> Result<Split, String> {
+    if !hierarchy.contains(genus) {
+        return Err(format!("Genus '{}' is not in the hierarchy", genus));
+    }
+    let children = hierarchy.children_of(genus);
+    if children.is_empty() {
+        return Err(format!("Genus '{}' has no species to split by", genus));
+    }
+
+    let mut buckets: Vec<Vec<_>> = vec![Vec::new(); children.len()];
+    let mut unassigned = Vec::new();
+    for item in &data.items {
+        let child = children
+            .iter()
+            .position(|child| item.classical_path.contains(&child.species));
+        match child {
+            Some(child) => buckets[child].push(item.clone()),
+            None => unassigned.push(item.clone()),
+        }
+    }
+
+    let mut file_names = HashSet::new();
+    let parts = children
+        .iter()
+        .map(|child| child.species.as_str())
+        .zip(buckets)
+        .filter(|(_, items)| !items.is_empty())
+        .map(|(species, items)| SplitPart {
+            species: species.to_string(),
+            file_name: unique_file_name(species, &mut file_names),
+            data: TaxonomyData {
+                schema: data.schema.clone(),
+                items,
+                extra: data.extra.clone(),
+            },
+        })
+        .collect();
+    Ok(Split { parts, unassigned })
+}
+
+/// Write split parts into `dir`, each referencing the schema at `schema_path` relative to
+/// `dir`, or embedding `schema` when there is no schema file, formatted as `options` say
+/// Fails before writing anything if a part's file exists, unless `overwrite` is set
+pub fn write_split(
+    parts: &mut [SplitPart],
+    schema: &TaxonomySchema,
+    schema_path: Option<&Path>,
+    dir: &Path,
+    options: &SaveOptions,
+    overwrite: bool,
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let paths: Vec<PathBuf> = parts.iter().map(|part| dir.join(&part.file_name)).collect();
+    if !overwrite {
+        if let Some(existing) = paths.iter().find(|path| path.exists()) {
+            return Err(format!("'{}' already exists", existing.display()).into());
+        }
+    }
+
+    fs::create_dir_all(dir)?;
+    let schema_ref = match schema_path {
+        Some(schema_path) => Some(relative_path(
+            &fs::canonicalize(dir)?,
+            &fs::canonicalize(schema_path)?,
+        )),
+        None => None,
+    };
+
+    for (part, path) in parts.iter_mut().zip(&paths) {
+        if let Some(ref schema_ref) = schema_ref {
+            part.data.schema = schema_ref.to_string_lossy().replace('\\', "/");
+        }
+        let inline_schema = schema_ref.is_none().then_some(schema);
+        save_data_with_options(&part.data, inline_schema, path, options)?;
+    }
+    Ok(paths)
+}
+
+/// "Hot Beverage" → "hot-beverage.json", numbered when two species give the same name
+fn unique_file_name(species: &str, taken: &mut HashSet<String>) -> String {
+    let slug = species
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    let slug = if slug.is_empty() {
+        "part".to_string()
+    } else {
+        slug
+    };
+
+    let mut name = format!("{}.json", slug);
+    let mut n = 2;
+    while !taken.insert(name.clone()) {
+        name = format!("{}-{}.json", slug, n);
+        n += 1;
+    }
+    name
+}
+
+/// Path of `to` relative to the directory `from`; both must be absolute
+fn relative_path(from: &Path, to: &Path) -> PathBuf {
+    let from: Vec<Component> = from.components().collect();
+    let to: Vec<Component> = to.components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+    let mut relative = PathBuf::new();
+    for _ in common..from.len() {
+        relative.push("..");
+    }
+    for component in &to[common..] {
+        relative.push(component);
+    }
+    relative
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{HierarchyBuilder, ItemBuilder};
+    use crate::io::load_data_with_auto_schema;
+    use crate::templates::find_template;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_split_by_genus() {
+        let hierarchy = HierarchyBuilder::new()
+            .root("Beverage")
+            .child("Hot Beverage", "served heated", |c| {
+                c.leaf("Coffee", "brewed from beans")
+            })
+            .leaf("Cold Beverage", "served chilled")
+            .leaf("Juice", "pressed fruit")
+            .build();
+        let data = TaxonomyData {
+            schema: "schema.json".to_string(),
+            items: vec![
+                ItemBuilder::new("Latte")
+                    .path(["Beverage", "Hot Beverage", "Coffee"])
+                    .build(),
+                ItemBuilder::new("Water").path(["Beverage"]).build(),
+                ItemBuilder::new("Cola")
+                    .path(["Beverage", "Cold Beverage"])
+                    .build(),
+                ItemBuilder::new("Cocoa")
+                    .path(["Beverage", "Hot Beverage"])
+                    .build(),
+            ],
+            extra: HashMap::new(),
+        };
+
+        let split = split_by_genus(&data, &hierarchy, "Beverage").unwrap();
+        let summary: Vec<(&str, &str, Vec<&str>)> = split
+            .parts
+            .iter()
+            .map(|part| {
+                let names = part.data.items.iter().map(|i| i.name.as_str()).collect();
+                (part.species.as_str(), part.file_name.as_str(), names)
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Hot Beverage", "hot-beverage.json", vec!["Latte", "Cocoa"]),
+                ("Cold Beverage", "cold-beverage.json", vec!["Cola"]),
+            ]
+        );
+        assert_eq!(split.unassigned[0].name, "Water");
+
+        // Items outside the genus stay out of its parts
+        let split = split_by_genus(&data, &hierarchy, "Hot Beverage").unwrap();
+        assert_eq!(split.parts.len(), 1);
+        assert_eq!(split.parts[0].data.items[0].name, "Latte");
+        let unassigned: Vec<&str> = split.unassigned.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(unassigned, vec!["Water", "Cola", "Cocoa"]);
+        assert!(split_by_genus(&data, &hierarchy, "Coffee").is_err());
+        assert!(split_by_genus(&data, &hierarchy, "Tea").is_err());
+
+        // Written parts reference the schema from their own directory, formatted as asked
+        let dir = std::env::temp_dir().join(format!("taxstud-split-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let template = find_template("beverages").unwrap();
+        let schema = template.schema();
+        let schema_path = dir.join("schema.json");
+        fs::write(&schema_path, template.schema_json().to_string()).unwrap();
+        let hierarchy = &schema.classical_hierarchy;
+        let mut data = template.empty_data("schema.json");
+        data.items.push(
+            ItemBuilder::new("Latte")
+                .path(hierarchy.path_to("Coffee").unwrap())
+                .build(),
+        );
+        let mut parts = split_by_genus(&data, hierarchy, &hierarchy.root)
+            .unwrap()
+            .parts;
+
+        let out = dir.join("parts");
+        let options = SaveOptions {
+            indent: 4,
+            trailing_newline: true,
+            ..SaveOptions::default()
+        };
+        let paths = write_split(
+            &mut parts,
+            &schema,
+            Some(&schema_path),
+            &out,
+            &options,
+            false,
+        )
+        .unwrap();
+        assert_eq!(parts[0].data.schema, "../schema.json");
+        for path in &paths {
+            load_data_with_auto_schema(path).unwrap();
+            let contents = fs::read_to_string(path).unwrap();
+            assert!(contents.starts_with("{\n    \"") && contents.ends_with("}\n"));
+        }
+        assert!(write_split(
+            &mut parts,
+            &schema,
+            Some(&schema_path),
+            &out,
+            &options,
+            false
+        )
+        .is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,618 @@
+use crate::filtering::apply_filters_with_schema;
+use crate::grouping::{group_items_by_facet, group_items_with_options, GroupKey, GroupOptions};
+use crate::io::{
+    load_data_with_options, load_rules, resolve_schema_path, save_data_with_options, LoadOptions,
+    SaveOptions,
+};
+use crate::models::{Filters, Item, TaxonomyData, TaxonomySchema};
+use crate::rules::RuleSet;
+#[cfg(feature = "scripting")]
+use crate::script::{run_script, ScriptReport};
+use crate::sorting::sort_items_with_schema;
+use crate::templates::TaxonomyTemplate;
+use crate::validation::{lint_data, validate_data};
+use crate::validators::ValidatorRegistry;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+/// What changed in a `TaxonomyStore`, as passed to `on_change` subscribers
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeEvent {
+    ItemAdded {
+        index: usize,
+    },
+    ItemUpdated {
+        index: usize,
+    },
+    /// The item was at `index`; the items after it moved up by one
+    ItemRemoved {
+        index: usize,
+        name: String,
+    },
+    /// The items were sorted
+    ItemsReordered,
+    SchemaChanged,
+    /// The store was reloaded from its data file
+    FileLoaded {
+        path: PathBuf,
+    },
+    FileSaved {
+        path: PathBuf,
+    },
+}
+
+/// An `on_change` subscription, for `unsubscribe`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Subscription(usize);
+
+type Observer = Box<dyn FnMut(&ChangeEvent) + Send>;
+
+/// A store's subscribers; a clone of the store starts with none
+///
+/// The mutex keeps the store `Sync` (the Python binding's class needs it) without asking
+/// that of the subscribers; it is only reached through `&mut self`, so it never blocks
+#[derive(Default)]
+struct Observers {
+    next: usize,
+    observers: Mutex<Vec<(Subscription, Observer)>>,
+}
+
+impl Observers {
+    fn list(&mut self) -> &mut Vec<(Subscription, Observer)> {
+        self.observers
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn emit(&mut self, event: ChangeEvent) {
+        for (_, observer) in self.list() {
+            observer(&event);
+        }
+    }
+}
+
+impl Clone for Observers {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl fmt::Debug for Observers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = match self.observers.lock() {
+            Ok(observers) => observers.len(),
+            Err(poisoned) => poisoned.get_ref().len(),
+        };
+        write!(f, "{} observer(s)", count)
+    }
+}
+
+/// A loaded taxonomy: schema + data, where they live on disk, and unsaved-change tracking
+/// High-level entry point for library consumers; the free functions remain available
+#[derive(Debug, Clone)]
+pub struct TaxonomyStore {
+    schema: TaxonomySchema,
+    data: TaxonomyData,
+    rules: RuleSet,
+    /// Custom validators run by `validate` and `lint`
+    validators: ValidatorRegistry,
+    data_path: Option<PathBuf>,
+    schema_path: Option<PathBuf>,
+    /// Whether saves embed the schema in the data file
+    embed_schema: bool,
+    /// How saves format the data file
+    save_options: SaveOptions,
+    /// How the data file was read, and is read again by `reload`
+    load_options: LoadOptions,
+    dirty: bool,
+    observers: Observers,
+}
+
+impl TaxonomyStore {
+    /// Wrap an in-memory schema and data document (not yet associated with a file)
+    pub fn new(schema: TaxonomySchema, data: TaxonomyData) -> Self {
+        Self {
+            schema,
+            data,
+            rules: RuleSet::default(),
+            validators: ValidatorRegistry::default(),
+            data_path: None,
+            schema_path: None,
+            embed_schema: false,
+            save_options: SaveOptions::default(),
+            load_options: LoadOptions::default(),
+            dirty: false,
+            observers: Observers::default(),
+        }
+    }
+
+    /// Start a new, unsaved taxonomy from a built-in template
+    pub fn from_template(template: &TaxonomyTemplate, schema_ref: &str) -> Self {
+        Self {
+            dirty: true,
+            ..Self::new(template.schema(), template.empty_data(schema_ref))
+        }
+    }
+
+    /// Load a data file together with the schema it references (or embeds)
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        Self::load_with_options(path, &LoadOptions::default())
+    }
+
+    /// Load a data file like `load`, read as `options` say
+    pub fn load_with_options<P: AsRef<Path>>(
+        path: P,
+        options: &LoadOptions,
+    ) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let (data, schema) = load_data_with_options(path, options, &mut |_| {})?;
+        let schema_path = resolve_schema_path(path)?;
+
+        Ok(Self {
+            rules: load_rules(&schema_path, &schema)?,
+            embed_schema: schema_path == path,
+            schema_path: Some(schema_path),
+            data_path: Some(path.to_path_buf()),
+            load_options: *options,
+            ..Self::new(schema, data)
+        })
+    }
+
+    /// Read the data file and its schema again, dropping unsaved changes
+    /// Subscribers, validators and load options are kept
+    pub fn reload(&mut self) -> Result<(), Box<dyn Error>> {
+        let path = self.data_path.clone().ok_or("No file path set")?;
+        let loaded = Self::load_with_options(&path, &self.load_options)?;
+        self.schema = loaded.schema;
+        self.data = loaded.data;
+        self.rules = loaded.rules;
+        self.schema_path = loaded.schema_path;
+        self.embed_schema = loaded.embed_schema;
+        self.dirty = false;
+        self.observers.emit(ChangeEvent::FileLoaded { path });
+        Ok(())
+    }
+
+    /// Save data to the file it was loaded from
+    pub fn save(&mut self) -> Result<(), Box<dyn Error>> {
+        let path = self.data_path.clone().ok_or("No file path set")?;
+        self.write(&path)?;
+        self.dirty = false;
+        self.observers.emit(ChangeEvent::FileSaved { path });
+        Ok(())
+    }
+
+    /// Save data to a new file, which becomes the store's file
+    pub fn save_as<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Box<dyn Error>> {
+        let path = path.as_ref().to_path_buf();
+        self.write(&path)?;
+        self.data_path = Some(path.clone());
+        self.dirty = false;
+        self.observers.emit(ChangeEvent::FileSaved { path });
+        Ok(())
+    }
+
+    /// Call `observer` with every change from now on, until `unsubscribe`d
+    ///
+    /// For embedders that mirror the store elsewhere, e.g. into a database. The GUI keeps
+    /// its own `AppState` rather than a `TaxonomyStore`, so it does not use these events
+    pub fn on_change(
+        &mut self,
+        observer: impl FnMut(&ChangeEvent) + Send + 'static,
+    ) -> Subscription {
+        let subscription = Subscription(self.observers.next);
+        self.observers.next += 1;
+        self.observers
+            .list()
+            .push((subscription, Box::new(observer)));
+        subscription
+    }
+
+    /// Stop a subscription; false if it was already stopped
+    pub fn unsubscribe(&mut self, subscription: Subscription) -> bool {
+        let observers = self.observers.list();
+        let before = observers.len();
+        observers.retain(|(other, _)| *other != subscription);
+        observers.len() < before
+    }
+
+    fn write(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let inline_schema = self.embed_schema.then_some(&self.schema);
+        save_data_with_options(&self.data, inline_schema, path, &self.save_options)
+    }
+
+    /// How saves format the data file
+    pub fn save_options(&self) -> &SaveOptions {
+        &self.save_options
+    }
+
+    /// Format the data file as `options` say from the next save on
+    pub fn set_save_options(&mut self, options: SaveOptions) {
+        self.save_options = options;
+    }
+
+    /// How the data file was read, and is read again by `reload`
+    pub fn load_options(&self) -> &LoadOptions {
+        &self.load_options
+    }
+
+    /// Whether saves embed the schema in the data file (set when it was loaded from one)
+    pub fn embeds_schema(&self) -> bool {
+        self.embed_schema
+    }
+
+    /// Embed the schema in the data file from the next save on, or stop doing so
+    pub fn set_embed_schema(&mut self, embed: bool) {
+        if self.embed_schema != embed {
+            self.embed_schema = embed;
+            self.dirty = true;
+        }
+    }
+
+    pub fn schema(&self) -> &TaxonomySchema {
+        &self.schema
+    }
+
+    /// Replace the schema, e.g. after editing it; the data is not checked against it
+    pub fn set_schema(&mut self, schema: TaxonomySchema) {
+        self.schema = schema;
+        self.dirty = true;
+        self.observers.emit(ChangeEvent::SchemaChanged);
+    }
+
+    /// House-style rules checked by `validate` and `lint`
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    pub fn set_rules(&mut self, rules: RuleSet) {
+        self.rules = rules;
+    }
+
+    /// Custom validators, at first those registered with `register_validator`
+    pub fn validators(&self) -> &ValidatorRegistry {
+        &self.validators
+    }
+
+    pub fn validators_mut(&mut self) -> &mut ValidatorRegistry {
+        &mut self.validators
+    }
+
+    pub fn data(&self) -> &TaxonomyData {
+        &self.data
+    }
+
+    pub fn items(&self) -> &[Item] {
+        &self.data.items
+    }
+
+    pub fn item(&self, index: usize) -> Option<&Item> {
+        self.data.items.get(index)
+    }
+
+    pub fn data_path(&self) -> Option<&Path> {
+        self.data_path.as_deref()
+    }
+
+    pub fn schema_path(&self) -> Option<&Path> {
+        self.schema_path.as_deref()
+    }
+
+    /// Whether there are unsaved changes
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Append an item, returning its index
+    pub fn add_item(&mut self, item: Item) -> usize {
+        self.data.items.push(item);
+        self.dirty = true;
+        let index = self.data.items.len() - 1;
+        self.observers.emit(ChangeEvent::ItemAdded { index });
+        index
+    }
+
+    /// Replace the item at `index`, returning the previous item
+    pub fn update_item(&mut self, index: usize, item: Item) -> Result<Item, String> {
+        let slot = self
+            .data
+            .items
+            .get_mut(index)
+            .ok_or_else(|| format!("No item at index {}", index))?;
+        let previous = std::mem::replace(slot, item);
+        self.dirty = true;
+        self.observers.emit(ChangeEvent::ItemUpdated { index });
+        Ok(previous)
+    }
+
+    /// Remove the item at `index`, returning it
+    pub fn remove_item(&mut self, index: usize) -> Result<Item, String> {
+        if index >= self.data.items.len() {
+            return Err(format!("No item at index {}", index));
+        }
+        self.dirty = true;
+        let removed = self.data.items.remove(index);
+        self.observers.emit(ChangeEvent::ItemRemoved {
+            index,
+            name: removed.name.clone(),
+        });
+        Ok(removed)
+    }
+
+    /// Run a Rhai script against the items (see `script`); nothing changes if it fails
+    #[cfg(feature = "scripting")]
+    pub fn run_script(&mut self, source: &str) -> Result<ScriptReport, Box<dyn Error>> {
+        let before = self.data.items.len();
+        let report = run_script(source, &self.schema, &mut self.data.items)?;
+        if report.changed + report.added > 0 {
+            self.dirty = true;
+        }
+        for &index in &report.changed_items {
+            self.observers.emit(ChangeEvent::ItemUpdated { index });
+        }
+        for index in before..self.data.items.len() {
+            self.observers.emit(ChangeEvent::ItemAdded { index });
+        }
+        Ok(report)
+    }
+
+    /// Items matching the filters, in document order
+    pub fn filter(&self, filters: &Filters) -> Vec<Item> {
+        apply_filters_with_schema(&self.data.items, filters, &self.schema)
+    }
+
+    /// Reorder the document's items by name or facet value, respecting the facet's kind
+    pub fn sort(&mut self, sort_field: &str) {
+        sort_items_with_schema(&mut self.data.items, sort_field, &self.schema);
+        self.dirty = true;
+        self.observers.emit(ChangeEvent::ItemsReordered);
+    }
+
+    /// Group all items by a facet name
+    pub fn group(&self, group_field: &str) -> HashMap<GroupKey, Vec<Item>> {
+        group_items_by_facet(&self.data.items, group_field)
+    }
+
+    /// Group all items by a facet name, handling items without it as `options` says
+    pub fn group_with_options(
+        &self,
+        group_field: &str,
+        options: &GroupOptions,
+    ) -> Result<HashMap<GroupKey, Vec<Item>>, String> {
+        group_items_with_options(&self.data.items, group_field, options)
+    }
+
+    /// Validate the data against the JSON Schema, the hierarchy/facet rules, the
+    /// error-severity house-style rules and the custom validators' errors
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        if self.schema.json_schema().is_some() {
+            let data_value = serde_json::to_value(&self.data).map_err(|e| vec![e.to_string()])?;
+            self.schema.validate_json(&data_value)?;
+        }
+
+        let mut errors = validate_data(&self.data, &self.schema)
+            .err()
+            .unwrap_or_default();
+        errors.extend(self.rules.check(&self.data.items).errors);
+        errors.extend(self.validators.check(&self.schema, &self.data).errors);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Warnings that do not fail validation, such as deprecated facet values in use,
+    /// broken warning-severity house-style rules and the custom validators' warnings
+    pub fn lint(&self) -> Vec<String> {
+        let mut warnings = lint_data(&self.data, &self.schema);
+        warnings.extend(self.rules.check(&self.data.items).warnings);
+        warnings.extend(self.validators.check(&self.schema, &self.data).warnings);
+        warnings
+    }
+
+    /// Take back ownership of the schema and data
+    pub fn into_parts(self) -> (TaxonomySchema, TaxonomyData) {
+        (self.schema, self.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::ItemBuilder;
+    use crate::templates::find_template;
+    use std::sync::{Arc, Mutex};
+
+    fn item(name: &str, path: &[&str], temperature: &str) -> Item {
+        ItemBuilder::new(name)
+            .path(path.iter().copied())
+            .facet("temperature", temperature)
+            .build()
+    }
+
+    fn beverage_store() -> TaxonomyStore {
+        let template = find_template("beverages").unwrap();
+        let mut store = TaxonomyStore::new(template.schema(), template.empty_data("schema.json"));
+        store.add_item(item(
+            "Latte",
+            &["Beverage", "Hot Beverage", "Coffee"],
+            "hot",
+        ));
+        store.add_item(item("Cola", &["Beverage", "Cold Beverage", "Soda"], "iced"));
+        store
+    }
+
+    #[test]
+    fn test_edits_mark_dirty() {
+        let template = find_template("beverages").unwrap();
+        let mut store = TaxonomyStore::new(template.schema(), template.empty_data("schema.json"));
+        assert!(!store.is_dirty());
+
+        let index = store.add_item(item("Latte", &["Beverage"], "hot"));
+        assert_eq!(index, 0);
+        assert!(store.is_dirty());
+
+        let previous = store
+            .update_item(0, item("Mocha", &["Beverage"], "hot"))
+            .unwrap();
+        assert_eq!(previous.name, "Latte");
+        assert_eq!(store.item(0).unwrap().name, "Mocha");
+
+        assert!(store.remove_item(1).is_err());
+        assert_eq!(store.remove_item(0).unwrap().name, "Mocha");
+        assert!(store.items().is_empty());
+    }
+
+    #[test]
+    fn test_filter_and_group() {
+        let store = beverage_store();
+        let filters = Filters {
+            genera: vec!["Hot Beverage".to_string()],
+            ..Default::default()
+        };
+        let hot = store.filter(&filters);
+        assert_eq!(hot.len(), 1);
+        assert_eq!(hot[0].name, "Latte");
+
+        let groups = store.group("temperature");
+        assert_eq!(groups[&GroupKey::Value("iced".to_string())][0].name, "Cola");
+    }
+
+    #[test]
+    fn test_validate_reports_bad_path() {
+        let mut store = beverage_store();
+        assert!(store.validate().is_ok());
+
+        store.add_item(item("Mystery", &["Beverage", "Nope"], "hot"));
+        let errors = store.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.contains("Nope")));
+    }
+
+    #[test]
+    fn test_save_as_clears_dirty() {
+        let mut store = beverage_store();
+        let path = std::env::temp_dir().join(format!("taxstud-store-{}.json", std::process::id()));
+
+        store.save_as(&path).unwrap();
+        assert!(!store.is_dirty());
+        assert_eq!(store.data_path(), Some(path.as_path()));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_change_events() {
+        let mut store = beverage_store();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let seen = events.clone();
+        let subscription = store.on_change(move |event| seen.lock().unwrap().push(event.clone()));
+
+        store.add_item(item("Mocha", &["Beverage"], "hot"));
+        store
+            .update_item(0, item("Latte", &["Beverage"], "hot"))
+            .unwrap();
+        store.remove_item(1).unwrap();
+        store.sort("name");
+        let path = std::env::temp_dir().join(format!("taxstud-events-{}.json", std::process::id()));
+        store.set_embed_schema(true);
+        store.save_as(&path).unwrap();
+        store.reload().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                ChangeEvent::ItemAdded { index: 2 },
+                ChangeEvent::ItemUpdated { index: 0 },
+                ChangeEvent::ItemRemoved {
+                    index: 1,
+                    name: "Cola".to_string()
+                },
+                ChangeEvent::ItemsReordered,
+                ChangeEvent::FileSaved { path: path.clone() },
+                ChangeEvent::FileLoaded { path },
+            ]
+        );
+
+        assert!(store.unsubscribe(subscription));
+        assert!(!store.unsubscribe(subscription));
+        store.add_item(item("Tea", &["Beverage"], "hot"));
+        assert_eq!(events.lock().unwrap().len(), 6);
+    }
+
+    #[test]
+    fn test_change_observer_need_not_be_sync() {
+        fn assert_sync<T: Sync>(_: &T) {}
+
+        let mut store = beverage_store();
+        // A `Cell` is `Send` but not `Sync`
+        let count = std::cell::Cell::new(0);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        store.on_change(move |_| {
+            count.set(count.get() + 1);
+            sender.send(count.get()).unwrap();
+        });
+        assert_sync(&store);
+
+        store.add_item(item("Mocha", &["Beverage"], "hot"));
+        store.remove_item(0).unwrap();
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_embedded_schema_round_trip() {
+        let mut store = beverage_store();
+        let path = std::env::temp_dir().join(format!("taxstud-embed-{}.json", std::process::id()));
+
+        store.set_embed_schema(true);
+        store.save_as(&path).unwrap();
+
+        // The sidecar "schema.json" does not exist; the embedded schema is used instead
+        let loaded = TaxonomyStore::load(&path).unwrap();
+        assert!(loaded.embeds_schema());
+        assert_eq!(loaded.schema_path(), Some(path.as_path()));
+        assert_eq!(loaded.schema().title, store.schema().title);
+        assert_eq!(loaded.items().len(), 2);
+        assert!(loaded.validate().is_ok());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_reload_keeps_load_options() {
+        let mut store = beverage_store();
+        let path = std::env::temp_dir().join(format!("taxstud-strict-{}.json", std::process::id()));
+        store.set_embed_schema(true);
+        store.save_as(&path).unwrap();
+
+        let strict = LoadOptions {
+            strict_duplicate_keys: true,
+        };
+        let mut loaded = TaxonomyStore::load_with_options(&path, &strict).unwrap();
+        assert_eq!(loaded.load_options(), &strict);
+
+        // The file gains a repeated key after it was opened
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let contents = contents.replacen(
+            "\"name\": \"Latte\"",
+            "\"name\": \"Mocha\", \"name\": \"Latte\"",
+            1,
+        );
+        std::fs::write(&path, contents).unwrap();
+
+        let err = loaded.reload().unwrap_err();
+        assert!(err.to_string().starts_with("Duplicate keys:"), "{}", err);
+        assert!(TaxonomyStore::load(&path).is_ok());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
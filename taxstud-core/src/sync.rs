@@ -0,0 +1,338 @@
+//! Publishing saves to a webhook (`sync` feature)
+//!
+//! A `sync.json` next to a data file turns it on for that file:
+//!
+//! ```text
+//! {
+//!   "url": "https://example.com/hooks/taxonomy",
+//!   "payload": "diff",
+//!   "secret_env": "TAXSTUD_SYNC_SECRET",
+//!   "timeout_secs": 10
+//! }
+//! ```
+//!
+//! After each save the publisher POSTs JSON with `event`, `file`, `schema_id` and
+//! `saved_at` (Unix seconds), plus either `changes` (`payload: "diff"`, the default; one
+//! entry per added, removed or changed item, and nothing is sent when no item changed) or
+//! `data` (`payload: "full"`, the whole document). With a secret (`secret`, or the
+//! environment variable named by `secret_env`) the body is signed with HMAC-SHA256 in an
+//! `X-Taxstud-Signature: sha256=<hex>` header, for the receiver to check
+
+use crate::diff::{diff_taxonomies, ChangeKind};
+use crate::models::{TaxonomyData, TaxonomySchema};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// File next to a data file holding its sync configuration
+pub const SYNC_FILE: &str = "sync.json";
+
+/// Header carrying the body's HMAC-SHA256 signature
+pub const SIGNATURE_HEADER: &str = "X-Taxstud-Signature";
+
+/// Seconds to wait for the receiver when the configuration doesn't say
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+
+/// What a save sends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncPayload {
+    /// The items added, removed or changed since the file was last saved
+    #[default]
+    Diff,
+    /// The whole document
+    Full,
+}
+
+/// A `sync.json`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SyncConfig {
+    pub url: String,
+    #[serde(default)]
+    pub payload: SyncPayload,
+    /// Signing secret; prefer `secret_env` to keep it out of the file
+    #[serde(default)]
+    pub secret: Option<String>,
+    /// Environment variable holding the signing secret
+    #[serde(default)]
+    pub secret_env: Option<String>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+impl SyncConfig {
+    /// The configuration in the `sync.json` next to `data_path`; None when there is none
+    pub fn for_data_file<P: AsRef<Path>>(data_path: P) -> Result<Option<Self>, Box<dyn Error>> {
+        let path = data_path
+            .as_ref()
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join(SYNC_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let contents = fs::read_to_string(&path)?;
+        let config: SyncConfig =
+            serde_json::from_str(&contents).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(Some(config))
+    }
+
+    /// The signing secret, from `secret_env` when that variable is set, else `secret`
+    pub fn secret(&self) -> Option<String> {
+        self.secret_env
+            .as_deref()
+            .and_then(|name| std::env::var(name).ok())
+            .or_else(|| self.secret.clone())
+            .filter(|secret| !secret.is_empty())
+    }
+}
+
+/// The HMAC-SHA256 of `body` under `secret`, as `sha256=<hex>`
+pub fn sign(secret: &[u8], body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC takes keys of any length");
+    mac.update(body);
+    let digest = mac.finalize().into_bytes();
+    let hex: String = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("sha256={}", hex)
+}
+
+/// The body a save sends; None for a diff payload when no item changed
+/// `previous` is the file as it was before the save (None for a new file)
+pub fn sync_payload(
+    payload: SyncPayload,
+    file: &str,
+    previous: Option<&TaxonomyData>,
+    schema: &TaxonomySchema,
+    data: &TaxonomyData,
+) -> Option<Value> {
+    let saved_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let mut body = json!({
+        "event": "save",
+        "file": file,
+        "schema_id": schema.schema_id,
+        "saved_at": saved_at,
+    });
+
+    match payload {
+        SyncPayload::Full => body["data"] = json!(data),
+        SyncPayload::Diff => {
+            let empty = TaxonomyData {
+                schema: data.schema.clone(),
+                items: Vec::new(),
+                extra: Default::default(),
+            };
+            let diff = diff_taxonomies(schema, previous.unwrap_or(&empty), schema, data);
+            let changes: Vec<Value> = diff
+                .items
+                .iter()
+                .filter_map(|item| {
+                    let mut change = match item.kind? {
+                        ChangeKind::Added => json!({ "change": "added", "item": item.new }),
+                        ChangeKind::Removed => json!({ "change": "removed" }),
+                        ChangeKind::Changed => json!({
+                            "change": "changed",
+                            "item": item.new,
+                            "details": item.details,
+                        }),
+                    };
+                    change["name"] = json!(item.name);
+                    Some(change)
+                })
+                .collect();
+            if changes.is_empty() {
+                return None;
+            }
+            body["changes"] = json!(changes);
+        }
+    }
+    Some(body)
+}
+
+/// A signed POST, ready to send from any thread
+#[derive(Debug, Clone)]
+pub struct SyncRequest {
+    pub url: String,
+    pub body: Vec<u8>,
+    /// `sha256=<hex>`, when a secret is configured
+    pub signature: Option<String>,
+    pub timeout: Duration,
+}
+
+impl SyncRequest {
+    /// POST the body; a non-2xx response is an error
+    pub fn send(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(self.timeout)
+            .build()?;
+        let mut request = client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(self.body.clone());
+        if let Some(signature) = &self.signature {
+            request = request.header(SIGNATURE_HEADER, signature);
+        }
+        request
+            .send()
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("Cannot publish to {}: {}", self.url, e))?;
+        Ok(())
+    }
+}
+
+/// Publishes a file's saves to the URL in its `sync.json`
+///
+/// Make one before saving, so it can remember the file as it was, then ask it for the
+/// request once the save succeeds:
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use taxstud_core::{SyncPublisher, TaxonomyStore};
+///
+/// let mut store = TaxonomyStore::load("data.json")?;
+/// let publisher = SyncPublisher::before_save("data.json")?;
+/// store.save()?;
+/// if let Some(request) = publisher.and_then(|p| p.request(store.schema(), store.data())) {
+///     request.send().map_err(|e| e.to_string())?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SyncPublisher {
+    config: SyncConfig,
+    file: String,
+    previous: Option<TaxonomyData>,
+}
+
+impl SyncPublisher {
+    /// A publisher for `data_path`, or None when it has no `sync.json`
+    /// For a diff payload, reads the file as it is now
+    pub fn before_save<P: AsRef<Path>>(data_path: P) -> Result<Option<Self>, Box<dyn Error>> {
+        let data_path = data_path.as_ref();
+        let Some(config) = SyncConfig::for_data_file(data_path)? else {
+            return Ok(None);
+        };
+        let previous = match config.payload {
+            SyncPayload::Diff if data_path.is_file() => {
+                Some(serde_json::from_str(&fs::read_to_string(data_path)?)?)
+            }
+            _ => None,
+        };
+        let file = data_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Ok(Some(Self {
+            config,
+            file,
+            previous,
+        }))
+    }
+
+    pub fn config(&self) -> &SyncConfig {
+        &self.config
+    }
+
+    /// The request publishing the saved `data`; None when a diff payload has nothing to send
+    pub fn request(&self, schema: &TaxonomySchema, data: &TaxonomyData) -> Option<SyncRequest> {
+        let body = sync_payload(
+            self.config.payload,
+            &self.file,
+            self.previous.as_ref(),
+            schema,
+            data,
+        )?;
+        let body = serde_json::to_vec(&body).ok()?;
+        Some(SyncRequest {
+            url: self.config.url.clone(),
+            signature: self
+                .config
+                .secret()
+                .map(|secret| sign(secret.as_bytes(), &body)),
+            body,
+            timeout: Duration::from_secs(self.config.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{HierarchyBuilder, ItemBuilder, TaxonomySchemaBuilder};
+
+    #[test]
+    fn test_sign() {
+        // RFC 4231, test case 2
+        assert_eq!(
+            sign(b"Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_sync_payload() {
+        let schema = TaxonomySchemaBuilder::new("Beverages")
+            .hierarchy(HierarchyBuilder::new().root("Beverage").build())
+            .build();
+        let data = |items| TaxonomyData {
+            schema: "schema.json".to_string(),
+            items,
+            extra: Default::default(),
+        };
+        let before = data(vec![
+            ItemBuilder::new("Latte").path(["Beverage"]).build(),
+            ItemBuilder::new("Mocha").path(["Beverage"]).build(),
+        ]);
+        let after = data(vec![
+            ItemBuilder::new("Latte")
+                .path(["Beverage"])
+                .facet("temperature", "hot")
+                .build(),
+            ItemBuilder::new("Sencha").path(["Beverage"]).build(),
+        ]);
+
+        let body = sync_payload(
+            SyncPayload::Diff,
+            "data.json",
+            Some(&before),
+            &schema,
+            &after,
+        )
+        .unwrap();
+        assert_eq!(body["file"], "data.json");
+        let changes = body["changes"].as_array().unwrap();
+        let summary: Vec<(&str, &str)> = changes
+            .iter()
+            .map(|c| (c["change"].as_str().unwrap(), c["name"].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("changed", "Latte"),
+                ("removed", "Mocha"),
+                ("added", "Sencha")
+            ]
+        );
+        assert_eq!(changes[0]["item"]["facets"]["temperature"], "hot");
+
+        assert!(sync_payload(
+            SyncPayload::Diff,
+            "data.json",
+            Some(&after),
+            &schema,
+            &after
+        )
+        .is_none());
+        let full = sync_payload(SyncPayload::Full, "data.json", None, &schema, &after).unwrap();
+        assert_eq!(full["data"]["items"].as_array().unwrap().len(), 2);
+        assert!(full.get("changes").is_none());
+    }
+}
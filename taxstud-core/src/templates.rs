@@ -0,0 +1,412 @@
+use crate::models::{Item, TaxonomyData, TaxonomySchema};
+use crate::schema::build_schema_from_json;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+/// A node in a template's classical hierarchy
+pub struct TemplateNode {
+    pub species: &'static str,
+    pub differentia: &'static str,
+    pub children: &'static [TemplateNode],
+}
+
+/// A built-in starter taxonomy
+/// Used by the CLI `init` command and by File → New in the GUI
+pub struct TaxonomyTemplate {
+    pub id: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    pub root: &'static str,
+    pub children: &'static [TemplateNode],
+    pub facets: &'static [(&'static str, &'static [&'static str])],
+}
+
+/// Identifier of the template used when none is specified
+pub const DEFAULT_TEMPLATE: &str = "blank";
+
+static TEMPLATES: &[TaxonomyTemplate] = &[
+    TaxonomyTemplate {
+        id: "blank",
+        title: "Default Schema",
+        description: "Default taxonomy schema",
+        root: "Root",
+        children: &[],
+        facets: &[("category", &["uncategorized"])],
+    },
+    TaxonomyTemplate {
+        id: "products",
+        title: "Product Taxonomy Schema",
+        description:
+            "Schema for a product catalog combining product categories with merchandising facets",
+        root: "Product",
+        children: &[
+            TemplateNode {
+                species: "Physical Goods",
+                differentia: "tangible items that are shipped",
+                children: &[
+                    TemplateNode {
+                        species: "Electronics",
+                        differentia: "powered devices and accessories",
+                        children: &[],
+                    },
+                    TemplateNode {
+                        species: "Apparel",
+                        differentia: "clothing, footwear and accessories",
+                        children: &[],
+                    },
+                    TemplateNode {
+                        species: "Home Goods",
+                        differentia: "furnishings and household supplies",
+                        children: &[],
+                    },
+                ],
+            },
+            TemplateNode {
+                species: "Digital Goods",
+                differentia: "delivered electronically",
+                children: &[
+                    TemplateNode {
+                        species: "Software",
+                        differentia: "licensed applications",
+                        children: &[],
+                    },
+                    TemplateNode {
+                        species: "Media",
+                        differentia: "downloadable or streamed content",
+                        children: &[],
+                    },
+                ],
+            },
+            TemplateNode {
+                species: "Services",
+                differentia: "work performed for the customer",
+                children: &[],
+            },
+        ],
+        facets: &[
+            ("price_tier", &["budget", "standard", "premium", "luxury"]),
+            ("availability", &["in_stock", "backorder", "discontinued"]),
+            ("channel", &["online", "retail", "wholesale"]),
+        ],
+    },
+    TaxonomyTemplate {
+        id: "beverages",
+        title: "Beverage Taxonomy Schema",
+        description: "Schema for a beverage taxonomy combining drink types with serving facets",
+        root: "Beverage",
+        children: &[
+            TemplateNode {
+                species: "Hot Beverage",
+                differentia: "served heated",
+                children: &[
+                    TemplateNode {
+                        species: "Coffee",
+                        differentia: "brewed from roasted coffee beans",
+                        children: &[],
+                    },
+                    TemplateNode {
+                        species: "Tea",
+                        differentia: "steeped from tea leaves",
+                        children: &[],
+                    },
+                ],
+            },
+            TemplateNode {
+                species: "Cold Beverage",
+                differentia: "served chilled or over ice",
+                children: &[
+                    TemplateNode {
+                        species: "Juice",
+                        differentia: "pressed from fruit or vegetables",
+                        children: &[],
+                    },
+                    TemplateNode {
+                        species: "Soda",
+                        differentia: "carbonated and sweetened",
+                        children: &[],
+                    },
+                ],
+            },
+        ],
+        facets: &[
+            ("temperature", &["hot", "warm", "room", "cold", "iced"]),
+            ("caffeine_content", &["none", "low", "medium", "high"]),
+            ("sweetness", &["unsweetened", "lightly_sweet", "sweet"]),
+        ],
+    },
+    TaxonomyTemplate {
+        id: "books",
+        title: "Book Taxonomy Schema",
+        description: "Schema for a book collection combining literary forms with reader facets",
+        root: "Book",
+        children: &[
+            TemplateNode {
+                species: "Fiction",
+                differentia: "narrates imagined events",
+                children: &[
+                    TemplateNode {
+                        species: "Novel",
+                        differentia: "book-length prose narrative",
+                        children: &[],
+                    },
+                    TemplateNode {
+                        species: "Short Story Collection",
+                        differentia: "gathers several short narratives",
+                        children: &[],
+                    },
+                ],
+            },
+            TemplateNode {
+                species: "Nonfiction",
+                differentia: "presents factual content",
+                children: &[
+                    TemplateNode {
+                        species: "Biography",
+                        differentia: "recounts a person's life",
+                        children: &[],
+                    },
+                    TemplateNode {
+                        species: "Reference",
+                        differentia: "consulted rather than read through",
+                        children: &[],
+                    },
+                ],
+            },
+        ],
+        facets: &[
+            ("audience", &["children", "young_adult", "adult"]),
+            ("format", &["hardcover", "paperback", "ebook", "audiobook"]),
+            ("length", &["short", "medium", "long"]),
+        ],
+    },
+];
+
+/// All built-in templates
+pub fn available_templates() -> &'static [TaxonomyTemplate] {
+    TEMPLATES
+}
+
+/// Look up a built-in template by its identifier
+pub fn find_template(id: &str) -> Option<&'static TaxonomyTemplate> {
+    TEMPLATES.iter().find(|template| template.id == id)
+}
+
+impl TaxonomyTemplate {
+    /// Build the JSON Schema document for this template
+    /// Includes the classical hierarchy, faceted dimensions, and item validation rules
+    pub fn schema_json(&self) -> Value {
+        let mut facet_properties = Map::new();
+        for (facet_name, values) in self.facets {
+            facet_properties.insert(
+                facet_name.to_string(),
+                json!({
+                    "oneOf": [
+                        { "type": "string", "enum": values },
+                        {
+                            "type": "array",
+                            "items": { "type": "string", "enum": values },
+                            "minItems": 1
+                        }
+                    ]
+                }),
+            );
+        }
+
+        let mut hierarchy = json!({ "root": self.root });
+        if !self.children.is_empty() {
+            hierarchy["children"] = nodes_to_json(self.root, self.children);
+        }
+
+        let faceted_dimensions: Map<String, Value> = self
+            .facets
+            .iter()
+            .map(|(name, values)| (name.to_string(), json!(values)))
+            .collect();
+
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "$id": format!("https://example.com/schemas/{}-taxonomy.schema.json", self.id),
+            "title": self.title,
+            "description": self.description,
+            "type": "object",
+            "required": ["schema", "items"],
+            "properties": {
+                "schema": { "type": "string" },
+                "items": {
+                    "type": "array",
+                    "items": { "$ref": "#/definitions/item" }
+                }
+            },
+            "classical_hierarchy": hierarchy,
+            "faceted_dimensions": faceted_dimensions,
+            "definitions": {
+                "item": {
+                    "type": "object",
+                    "required": ["name", "classical_path", "facets"],
+                    "properties": {
+                        "name": { "type": "string" },
+                        "classical_path": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "minItems": 1
+                        },
+                        "facets": {
+                            "type": "object",
+                            "properties": facet_properties,
+                            "additionalProperties": false
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    /// Build the TaxonomySchema for this template
+    pub fn schema(&self) -> TaxonomySchema {
+        build_schema_from_json(self.schema_json())
+            .expect("built-in templates always produce a well-formed schema")
+    }
+
+    /// Create an empty data document referencing `schema_ref`
+    pub fn empty_data(&self, schema_ref: &str) -> TaxonomyData {
+        TaxonomyData {
+            schema: schema_ref.to_string(),
+            items: Vec::new(),
+            extra: HashMap::new(),
+        }
+    }
+}
+
+/// A saved item prototype: a classification path and facet values to start new items from
+/// Kept in a project's settings, or in the app config outside a project
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemTemplate {
+    pub name: String,
+    pub classical_path: Vec<String>,
+    #[serde(default)]
+    pub facets: HashMap<String, Value>,
+}
+
+impl ItemTemplate {
+    /// Capture the path and facets of an existing item
+    pub fn from_item(name: &str, item: &Item) -> Self {
+        ItemTemplate {
+            name: name.to_string(),
+            classical_path: item.classical_path.clone(),
+            facets: item.facets.clone(),
+        }
+    }
+
+    /// Create a new item named `name` with the template's path and facets
+    pub fn instantiate(&self, name: &str) -> Item {
+        Item {
+            name: name.to_string(),
+            classical_path: self.classical_path.clone(),
+            facets: self.facets.clone(),
+            labels: HashMap::new(),
+            relations: Vec::new(),
+            attachments: Vec::new(),
+            extra: HashMap::new(),
+        }
+    }
+}
+
+fn nodes_to_json(genus: &str, nodes: &[TemplateNode]) -> Value {
+    Value::Array(
+        nodes
+            .iter()
+            .map(|node| {
+                let mut value = json!({
+                    "genus": genus,
+                    "species": node.species,
+                    "differentia": node.differentia,
+                });
+                if !node.children.is_empty() {
+                    value["children"] = nodes_to_json(node.species, node.children);
+                }
+                value
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_validation::validate_against_schema;
+
+    #[test]
+    fn test_default_template_exists() {
+        let template = find_template(DEFAULT_TEMPLATE).unwrap();
+        let schema = template.schema();
+        assert_eq!(schema.classical_hierarchy.root, "Root");
+        assert!(schema.faceted_dimensions.contains_key("category"));
+    }
+
+    #[test]
+    fn test_templates_accept_their_empty_data() {
+        for template in available_templates() {
+            let schema = template.schema();
+            let data = serde_json::to_value(template.empty_data("schema.json")).unwrap();
+            let json_schema = schema.json_schema().unwrap();
+            assert!(
+                validate_against_schema(json_schema, &data).is_ok(),
+                "template '{}' rejects its own empty data",
+                template.id
+            );
+        }
+    }
+
+    #[test]
+    fn test_template_hierarchy_genus_links() {
+        let schema = find_template("products").unwrap().schema();
+        let children = schema.classical_hierarchy.children.unwrap();
+        assert_eq!(children[0].genus, "Product");
+        let grandchildren = children[0].children.as_ref().unwrap();
+        assert_eq!(grandchildren[0].genus, children[0].species);
+    }
+
+    #[test]
+    fn test_template_rejects_unknown_facet_value() {
+        let schema = find_template("beverages").unwrap().schema();
+        let data = json!({
+            "schema": "schema.json",
+            "items": [{
+                "name": "Latte",
+                "classical_path": ["Beverage", "Hot Beverage", "Coffee"],
+                "facets": { "temperature": "lukewarm" }
+            }]
+        });
+        let json_schema = schema.json_schema().unwrap();
+        assert!(validate_against_schema(json_schema, &data).is_err());
+    }
+
+    #[test]
+    fn test_item_template_round_trip() {
+        let mut item = Item {
+            name: "Espresso".to_string(),
+            classical_path: vec!["Beverages".to_string(), "Coffee".to_string()],
+            facets: HashMap::from([("temperature".to_string(), json!("hot"))]),
+            labels: HashMap::from([("de".to_string(), "Espresso".to_string())]),
+            relations: Vec::new(),
+            attachments: Vec::new(),
+            extra: HashMap::new(),
+        };
+        item.set_deprecated(true);
+
+        let template = ItemTemplate::from_item("Coffee", &item);
+        let json = serde_json::to_value(&template).unwrap();
+        assert_eq!(
+            serde_json::from_value::<ItemTemplate>(json).unwrap(),
+            template
+        );
+
+        let latte = template.instantiate("Latte");
+        assert_eq!(latte.name, "Latte");
+        assert_eq!(latte.classical_path, item.classical_path);
+        assert_eq!(latte.facets, item.facets);
+        assert!(latte.labels.is_empty());
+        assert!(!latte.is_deprecated());
+    }
+}
@@ -0,0 +1,253 @@
+//! Generated taxonomies for load testing and benchmarks
+//!
+//! `random_taxonomy` builds a beverage schema and classifies random items under it. The
+//! same seed and sizes always give the same files, on every platform, and the data
+//! validates against the schema
+
+use crate::builder::{HierarchyBuilder, ItemBuilder, TaxonomySchemaBuilder};
+use crate::models::{FacetDefinition, FacetKind, TaxonomyData, TaxonomySchema};
+use crate::schema::build_schema_from_json;
+use serde_json::{json, Value};
+
+const GENERA: &[&str] = &["Coffee", "Tea", "Juice", "Soda", "Water", "Smoothie"];
+const SPECIES: &[&str] = &["Classic", "Herbal", "Sparkling", "Blended", "Cold Brew"];
+const ADJECTIVES: &[&str] = &[
+    "The", "Golden", "Smoky", "Bright", "Velvet", "Wild", "Midnight", "An", "Rustic", "Royal",
+];
+const NOUNS: &[&str] = &[
+    "Harbor", "Meadow", "Ember", "Orchard", "Summit", "Lagoon", "Canyon", "Grove", "Delta",
+];
+const TEMPERATURES: &[&str] = &["hot", "cold", "iced"];
+const FLAVORS: &[&str] = &[
+    "vanilla", "caramel", "lemon", "mint", "berry", "ginger", "cocoa", "honey",
+];
+const ORIGINS: &[&str] = &[
+    "ethiopia", "kenya", "colombia", "brazil", "india", "japan", "china", "peru",
+];
+
+/// How big a generated taxonomy is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeParams {
+    pub items: usize,
+    /// Children of the root
+    pub genera: usize,
+    /// Children of each genus; items are classified under these
+    pub species_per_genus: usize,
+}
+
+impl Default for SizeParams {
+    fn default() -> Self {
+        Self {
+            items: 1_000,
+            genera: GENERA.len(),
+            species_per_genus: SPECIES.len(),
+        }
+    }
+}
+
+impl SizeParams {
+    /// The default hierarchy with `items` items
+    pub fn items(items: usize) -> Self {
+        Self {
+            items,
+            ..Self::default()
+        }
+    }
+}
+
+/// SplitMix64: small, fast and the same on every platform
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    fn pick<'a>(&mut self, values: &[&'a str]) -> &'a str {
+        values[self.below(values.len())]
+    }
+}
+
+/// The `index`th name of `names`, numbered once they run out: "Coffee", ..., "Coffee 2"
+fn nth_name(names: &[&str], index: usize) -> String {
+    match index / names.len() {
+        0 => names[index].to_string(),
+        round => format!("{} {}", names[index % names.len()], round + 1),
+    }
+}
+
+/// A schema with a root, `size.genera` genera of `size.species_per_genus` species each,
+/// enum (`temperature`, `origin`), multi-valued (`flavor`), number (`price`) and date
+/// (`added`) facets, and `size.items` items classified under it; the same for the same seed
+/// Item names are unique; some items have no origin. The schema carries its JSON Schema
+/// document, so `save_schema` can write it, and the data's `schema` is "schema.json"
+pub fn random_taxonomy(seed: u64, size: &SizeParams) -> (TaxonomySchema, TaxonomyData) {
+    let genera: Vec<String> = (0..size.genera.max(1))
+        .map(|index| nth_name(GENERA, index))
+        .collect();
+    let species: Vec<Vec<String>> = genera
+        .iter()
+        .map(|genus| {
+            (0..size.species_per_genus.max(1))
+                .map(|index| format!("{} {}", nth_name(SPECIES, index), genus))
+                .collect()
+        })
+        .collect();
+
+    let mut hierarchy = HierarchyBuilder::new().root("Beverage");
+    for (genus, species) in genera.iter().zip(&species) {
+        hierarchy = hierarchy.child(genus, format!("a kind of {}", genus), |mut node| {
+            for name in species {
+                node = node.leaf(name, "generated");
+            }
+            node
+        });
+    }
+    let schema = TaxonomySchemaBuilder::new("Generated Beverages")
+        .hierarchy(hierarchy.build())
+        .facet("temperature", TEMPERATURES.iter().copied())
+        .facet_definition(
+            "flavor",
+            FacetDefinition {
+                multi: true,
+                ..FacetDefinition::enumerated(FLAVORS.iter().copied())
+            },
+        )
+        .facet("origin", ORIGINS.iter().copied())
+        .facet_definition("price", FacetDefinition::new(FacetKind::Number))
+        .facet_definition("added", FacetDefinition::new(FacetKind::Date))
+        .build();
+    let schema = build_schema_from_json(schema_document(&schema))
+        .expect("the generated document is always a well-formed schema");
+
+    let mut rng = Rng(seed);
+    let items = (0..size.items)
+        .map(|index| {
+            let genus = rng.below(genera.len());
+            let species = &species[genus][rng.below(species[genus].len())];
+            let flavors: Vec<&str> = (0..=rng.below(2)).map(|_| rng.pick(FLAVORS)).collect();
+            let name = format!("{} {} {}", rng.pick(ADJECTIVES), rng.pick(NOUNS), index);
+            let mut builder = ItemBuilder::new(name)
+                .path(["Beverage", genera[genus].as_str(), species.as_str()])
+                .facet("temperature", rng.pick(TEMPERATURES))
+                .facet_values("flavor", flavors)
+                .facet(
+                    "added",
+                    format!(
+                        "20{:02}-{:02}-{:02}",
+                        15 + rng.below(10),
+                        1 + rng.below(12),
+                        1 + rng.below(28)
+                    ),
+                );
+            if rng.below(5) > 0 {
+                builder = builder.facet("origin", rng.pick(ORIGINS));
+            }
+            let mut item = builder.build();
+            let cents = 100 + rng.below(900);
+            item.facets
+                .insert("price".to_string(), json!(cents as f64 / 100.0));
+            item
+        })
+        .collect();
+
+    let data = TaxonomyData {
+        schema: "schema.json".to_string(),
+        items,
+        extra: Default::default(),
+    };
+    (schema, data)
+}
+
+/// The JSON Schema document of a generated schema, checking items' shape and facet types
+fn schema_document(schema: &TaxonomySchema) -> Value {
+    let enumerated = |values: &[&str]| json!({ "type": "string", "enum": values });
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "$id": "https://example.com/schemas/generated-beverages.schema.json",
+        "title": schema.title,
+        "description": "Generated for load testing",
+        "type": "object",
+        "required": ["schema", "items"],
+        "properties": {
+            "schema": { "type": "string" },
+            "items": { "type": "array", "items": { "$ref": "#/definitions/item" } }
+        },
+        "classical_hierarchy": schema.classical_hierarchy,
+        "faceted_dimensions": schema.faceted_dimensions,
+        "definitions": {
+            "item": {
+                "type": "object",
+                "required": ["name", "classical_path", "facets"],
+                "properties": {
+                    "name": { "type": "string" },
+                    "classical_path": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "minItems": 1
+                    },
+                    "facets": {
+                        "type": "object",
+                        "properties": {
+                            "temperature": enumerated(TEMPERATURES),
+                            "origin": enumerated(ORIGINS),
+                            "flavor": { "type": "array", "items": enumerated(FLAVORS) },
+                            "price": { "type": "number" },
+                            "added": { "type": "string", "pattern": "^\\d{4}-\\d{2}-\\d{2}$" }
+                        },
+                        "additionalProperties": false
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_validation::validate_against_schema;
+    use crate::validation::validate_data;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_random_taxonomy() {
+        for seed in [0, 1, 42, u64::MAX] {
+            let size = SizeParams {
+                items: 500,
+                genera: 8,
+                species_per_genus: 2,
+            };
+            let (schema, data) = random_taxonomy(seed, &size);
+            assert_eq!(validate_data(&data, &schema), Ok(()));
+            let document = serde_json::to_value(&data).unwrap();
+            let json_schema = schema.json_schema().unwrap();
+            assert_eq!(validate_against_schema(json_schema, &document), Ok(()));
+            assert_eq!(data.items.len(), 500);
+            let names: HashSet<&str> = data.items.iter().map(|i| i.name.as_str()).collect();
+            assert_eq!(names.len(), 500);
+            assert!(data.items.iter().any(|i| i.classical_path[1] == "Coffee 2"));
+
+            let (_, again) = random_taxonomy(seed, &size);
+            assert_eq!(
+                serde_json::to_value(&data).unwrap(),
+                serde_json::to_value(&again).unwrap()
+            );
+        }
+        let (_, one) = random_taxonomy(1, &SizeParams::items(10));
+        let (_, two) = random_taxonomy(2, &SizeParams::items(10));
+        assert_ne!(
+            serde_json::to_value(&one).unwrap(),
+            serde_json::to_value(&two).unwrap()
+        );
+    }
+}
@@ -1,5 +1,13 @@
-use crate::models::{HierarchyNode, HybridTaxonomy, Item};
+use crate::fields::{check_item_fields, malformed_urls};
+use crate::models::{
+    ConstraintViolation, FacetKind, HierarchyNode, HybridTaxonomy, Item, TaxonomyData,
+    TaxonomySchema,
+};
+use crate::progress::{Progress, ProgressFn};
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde_json::Value;
 use std::collections::{HashMap, HashSet};
+use std::fmt;
 
 /// Validate the hybrid taxonomy schema
 /// Returns Ok(()) if valid, or Err(Vec<String>) with validation errors
@@ -15,17 +23,31 @@ pub fn validate_taxonomy(taxonomy: &HybridTaxonomy) -> Result<(), Vec<String>> {
         validate_hierarchy_nodes(children, &taxonomy.classical_hierarchy.root, &mut errors);
     }
 
+    // Codes identify nodes, so they must be unique
+    let mut codes: HashMap<&str, &str> = HashMap::new();
+    for (_, node) in taxonomy.classical_hierarchy.iter_preorder() {
+        if let Some(code) = node.code.as_deref() {
+            if let Some(other) = codes.insert(code, &node.species) {
+                errors.push(format!(
+                    "Code '{}' is used by both '{}' and '{}'",
+                    code, other, node.species
+                ));
+            }
+        }
+    }
+
     // Validate faceted dimensions
     if taxonomy.faceted_dimensions.is_empty() {
         errors.push("At least one faceted dimension must be defined".to_string());
     }
 
-    for (facet_name, values) in &taxonomy.faceted_dimensions {
+    for (facet_name, definition) in &taxonomy.faceted_dimensions {
         if facet_name.trim().is_empty() {
             errors.push("Facet names cannot be empty".to_string());
         }
 
-        if values.is_empty() {
+        let values = &definition.allowed_values;
+        if definition.kind == FacetKind::Enum && values.is_empty() {
             errors.push(format!(
                 "Facet '{}' must have at least one value",
                 facet_name
@@ -45,6 +67,25 @@ pub fn validate_taxonomy(taxonomy: &HybridTaxonomy) -> Result<(), Vec<String>> {
                 ));
             }
         }
+
+        let mut described: Vec<_> = definition
+            .value_info
+            .keys()
+            .filter(|value| !values.contains(value))
+            .collect();
+        described.sort();
+        for value in described {
+            errors.push(format!(
+                "Facet '{}' describes unknown value '{}'",
+                facet_name, value
+            ));
+        }
+
+        if let Some(default) = &definition.default {
+            for e in definition.check_facet(default) {
+                errors.push(format!("Facet '{}' default {}", facet_name, e));
+            }
+        }
     }
 
     // Validate example items
@@ -59,6 +100,265 @@ pub fn validate_taxonomy(taxonomy: &HybridTaxonomy) -> Result<(), Vec<String>> {
     }
 }
 
+/// Validate a data document's items against its schema's hierarchy and facets
+/// Runs the same semantic checks as validate_taxonomy, plus constraints and custom item
+/// fields, on top of JSON Schema validation
+pub fn validate_data(data: &TaxonomyData, schema: &TaxonomySchema) -> Result<(), Vec<String>> {
+    validate_data_with_progress(data, schema, &mut |_| {})
+}
+
+/// Validate a data document like `validate_data`, reporting each of its three passes
+pub fn validate_data_with_progress(
+    data: &TaxonomyData,
+    schema: &TaxonomySchema,
+    on_progress: &mut ProgressFn,
+) -> Result<(), Vec<String>> {
+    on_progress(&Progress::new("Checking items", 0, 3));
+    let taxonomy = HybridTaxonomy {
+        taxonomy_description: schema.description.clone(),
+        classical_hierarchy: schema.classical_hierarchy.clone(),
+        faceted_dimensions: schema.faceted_dimensions.clone(),
+        example_items: Some(data.items.clone()),
+        extra: HashMap::new(),
+    };
+
+    let mut errors = validate_taxonomy(&taxonomy).err().unwrap_or_default();
+    on_progress(&Progress::new("Checking constraints", 1, 3));
+    errors.extend(
+        check_constraints(&data.items, schema)
+            .err()
+            .unwrap_or_default(),
+    );
+    on_progress(&Progress::new("Checking item fields", 2, 3));
+    errors.extend(
+        check_item_fields(&data.items, schema)
+            .err()
+            .unwrap_or_default(),
+    );
+    on_progress(&Progress::new("Checking item fields", 3, 3));
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Check a JSON document's text for objects that repeat a key, e.g. an item with two
+/// "temperature" facets; parsing keeps only the last value, so the others are lost silently
+/// Errors name the key and the object, as a JSON pointer. Fails with one error if the text
+/// isn't JSON
+pub fn check_duplicate_keys(contents: &str) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+    let mut deserializer = serde_json::Deserializer::from_str(contents);
+    let walked = KeyWalker {
+        pointer: String::new(),
+        errors: &mut errors,
+    }
+    .deserialize(&mut deserializer)
+    .and_then(|()| deserializer.end());
+    if let Err(err) = walked {
+        return Err(vec![format!("Invalid JSON: {}", err)]);
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Walks a JSON value, recording keys repeated within an object
+struct KeyWalker<'a> {
+    /// JSON pointer of the value
+    pointer: String,
+    errors: &'a mut Vec<String>,
+}
+
+impl KeyWalker<'_> {
+    fn child(&mut self, token: &str) -> KeyWalker<'_> {
+        let token = token.replace('~', "~0").replace('/', "~1");
+        KeyWalker {
+            pointer: format!("{}/{}", self.pointer, token),
+            errors: self.errors,
+        }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for KeyWalker<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for KeyWalker<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a JSON value")
+    }
+
+    fn visit_bool<E: de::Error>(self, _: bool) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_i64<E: de::Error>(self, _: i64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_u64<E: de::Error>(self, _: u64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_f64<E: de::Error>(self, _: f64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_str<E: de::Error>(self, _: &str) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(mut self, mut seq: A) -> Result<(), A::Error> {
+        let mut index = 0;
+        while seq
+            .next_element_seed(self.child(&index.to_string()))?
+            .is_some()
+        {
+            index += 1;
+        }
+        Ok(())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<(), A::Error> {
+        let mut seen = HashSet::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if !seen.insert(key.clone()) {
+                let object = match self.pointer.as_str() {
+                    "" => "the document",
+                    pointer => pointer,
+                };
+                self.errors
+                    .push(format!("Duplicate key '{}' in {}", key, object));
+            }
+            map.next_value_seed(self.child(&key))?;
+        }
+        Ok(())
+    }
+}
+
+/// Check items against the schema's constraints between facets
+/// Also reports constraints that refer to undefined facets or values
+pub fn check_constraints(items: &[Item], schema: &TaxonomySchema) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+
+    for (idx, constraint) in schema.constraints.iter().enumerate() {
+        let constraint_ref = format!("Constraint #{}", idx + 1);
+
+        if constraint.when.is_empty() {
+            errors.push(format!("{}: 'if' names no facets", constraint_ref));
+        }
+        if constraint.then.is_empty() && constraint.forbid.is_empty() {
+            errors.push(format!("{}: needs a 'then' or 'not' rule", constraint_ref));
+        }
+
+        for (facet_name, condition) in constraint.facets() {
+            let Some(definition) = schema.faceted_dimensions.get(facet_name) else {
+                errors.push(format!(
+                    "{}: refers to undefined facet '{}'",
+                    constraint_ref, facet_name
+                ));
+                continue;
+            };
+            // Constraint values are written as text, like values typed into the editor
+            for value in condition.values() {
+                let checked = definition
+                    .value_from_text(value)
+                    .map_err(|_| {
+                        format!(
+                            "has invalid value '{}' (expected {})",
+                            value,
+                            definition.kind.expected()
+                        )
+                    })
+                    .and_then(|typed| definition.check_value(&typed));
+                if let Err(e) = checked {
+                    errors.push(format!("{}: facet '{}' {}", constraint_ref, facet_name, e));
+                }
+            }
+        }
+    }
+
+    for (idx, item) in items.iter().enumerate() {
+        for violation in constraint_violations(&item.facets, schema) {
+            errors.push(format!(
+                "Item #{} ('{}'): {}",
+                idx + 1,
+                item.name,
+                violation.message
+            ));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Constraints broken by an item with these facets, in schema order
+/// Used to flag the offending inputs while an item is being edited
+pub fn constraint_violations(
+    facets: &HashMap<String, Value>,
+    schema: &TaxonomySchema,
+) -> Vec<ConstraintViolation> {
+    schema
+        .constraints
+        .iter()
+        .flat_map(|constraint| constraint.violations(facets))
+        .collect()
+}
+
+/// Find issues that do not make a data document invalid, such as uses of deprecated facet values
+/// or malformed web links
+/// Returns warnings in item order
+pub fn lint_data(data: &TaxonomyData, schema: &TaxonomySchema) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    for (idx, item) in data.items.iter().enumerate() {
+        let mut facet_names: Vec<_> = item.facets.keys().collect();
+        facet_names.sort();
+        for facet_name in facet_names {
+            let Some(definition) = schema.faceted_dimensions.get(facet_name) else {
+                continue;
+            };
+            for value in item.get_facet_as_vec(facet_name) {
+                if definition.is_deprecated(&value) {
+                    warnings.push(format!(
+                        "Item #{} ('{}'): facet '{}' uses deprecated value '{}'",
+                        idx + 1,
+                        item.name,
+                        facet_name,
+                        value
+                    ));
+                }
+            }
+        }
+
+        for warning in malformed_urls(item) {
+            warnings.push(format!("Item #{} ('{}'): {}", idx + 1, item.name, warning));
+        }
+    }
+
+    warnings
+}
+
 pub fn validate_hierarchy_nodes(nodes: &[HierarchyNode], parent: &str, errors: &mut Vec<String>) {
     for node in nodes {
         // Validate required fields are not empty
@@ -91,7 +391,6 @@ pub fn validate_hierarchy_nodes(nodes: &[HierarchyNode], parent: &str, errors: &
 }
 
 pub fn validate_items(items: &[Item], taxonomy: &HybridTaxonomy, errors: &mut Vec<String>) {
-    let defined_facets: HashSet<_> = taxonomy.faceted_dimensions.keys().collect();
     let mut item_names = HashSet::new();
 
     for (idx, item) in items.iter().enumerate() {
@@ -128,57 +427,32 @@ pub fn validate_items(items: &[Item], taxonomy: &HybridTaxonomy, errors: &mut Ve
             errors.push(format!("{}: must have at least one facet", item_ref));
         }
 
+        let mut required: Vec<_> = taxonomy
+            .faceted_dimensions
+            .iter()
+            .filter(|(name, definition)| definition.required && !item.facets.contains_key(*name))
+            .map(|(name, _)| name)
+            .collect();
+        required.sort();
+        for facet_name in required {
+            errors.push(format!(
+                "{}: missing required facet '{}'",
+                item_ref, facet_name
+            ));
+        }
+
         for (facet_name, facet_value) in &item.facets {
             // Check facet is defined in taxonomy
-            if !defined_facets.contains(facet_name) {
+            let Some(definition) = taxonomy.faceted_dimensions.get(facet_name) else {
                 errors.push(format!(
                     "{}: uses undefined facet '{}'",
                     item_ref, facet_name
                 ));
                 continue;
-            }
+            };
 
-            // Get allowed values for this facet
-            if let Some(allowed_values) = taxonomy.faceted_dimensions.get(facet_name) {
-                match facet_value {
-                    serde_json::Value::String(s) => {
-                        if !allowed_values.contains(s) {
-                            errors.push(format!(
-                                "{}: facet '{}' has invalid value '{}' (not in allowed values)",
-                                item_ref, facet_name, s
-                            ));
-                        }
-                    }
-                    serde_json::Value::Array(arr) => {
-                        if arr.is_empty() {
-                            errors.push(format!(
-                                "{}: facet '{}' has empty array",
-                                item_ref, facet_name
-                            ));
-                        }
-                        for val in arr {
-                            if let Some(s) = val.as_str() {
-                                if !allowed_values.contains(&s.to_string()) {
-                                    errors.push(format!(
-                                        "{}: facet '{}' has invalid value '{}' (not in allowed values)",
-                                        item_ref, facet_name, s
-                                    ));
-                                }
-                            } else {
-                                errors.push(format!(
-                                    "{}: facet '{}' array contains non-string value",
-                                    item_ref, facet_name
-                                ));
-                            }
-                        }
-                    }
-                    _ => {
-                        errors.push(format!(
-                            "{}: facet '{}' must be a string or array of strings",
-                            item_ref, facet_name
-                        ));
-                    }
-                }
+            for e in definition.check_facet(facet_value) {
+                errors.push(format!("{}: facet '{}' {}", item_ref, facet_name, e));
             }
         }
     }
@@ -290,3 +564,79 @@ pub fn validate_path_exists(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{HierarchyBuilder, ItemBuilder, TaxonomySchemaBuilder};
+
+    #[test]
+    fn test_path_exists_agrees_with_validate_data() {
+        let schema = TaxonomySchemaBuilder::new("Beverages")
+            .hierarchy(
+                HierarchyBuilder::new()
+                    .root("Beverage")
+                    .child("Hot Beverage", "served heated", |c| {
+                        c.leaf("Coffee", "brewed from beans")
+                    })
+                    .leaf("Cold Beverage", "served chilled")
+                    .build(),
+            )
+            .facet("temperature", ["hot", "cold"])
+            .build();
+
+        let paths: [&[&str]; 5] = [
+            &["Beverage"],
+            &["Beverage", "Hot Beverage", "Coffee"],
+            &["Drink", "Hot Beverage"],
+            &["Beverage", "Coffee"],
+            &["Beverage", "Cold Beverage", "Iced Tea"],
+        ];
+        for path in paths {
+            let path: Vec<String> = path.iter().map(|s| s.to_string()).collect();
+            let data = TaxonomyData {
+                schema: "schema.json".to_string(),
+                items: vec![ItemBuilder::new("Drink")
+                    .path(path.clone())
+                    .facet("temperature", "hot")
+                    .build()],
+                extra: HashMap::new(),
+            };
+            assert_eq!(
+                validate_path_exists(&path, &schema.classical_hierarchy).is_ok(),
+                validate_data(&data, &schema).is_ok(),
+                "{:?}: {:?}",
+                path,
+                validate_data(&data, &schema)
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_duplicate_keys() {
+        let contents = r#"{
+            "schema": "schema.json",
+            "items": [
+                {"name": "Latte", "facets": {"temperature": "hot"}},
+                {"name": "Mocha", "facets": {"temperature": "hot", "temperature": "iced"},
+                 "name": "Iced Mocha", "a/b": {"x": 1, "x": 2}}
+            ],
+            "schema": "other.json"
+        }"#;
+        assert_eq!(
+            check_duplicate_keys(contents),
+            Err(vec![
+                "Duplicate key 'temperature' in /items/1/facets".to_string(),
+                "Duplicate key 'name' in /items/1".to_string(),
+                "Duplicate key 'x' in /items/1/a~1b".to_string(),
+                "Duplicate key 'schema' in the document".to_string(),
+            ])
+        );
+        // The same key in different objects is fine
+        assert_eq!(
+            check_duplicate_keys(r#"[{"name": "a"}, {"name": "b", "facets": {"name": 1}}]"#),
+            Ok(())
+        );
+        assert!(check_duplicate_keys(r#"{"items": ["#).is_err());
+    }
+}
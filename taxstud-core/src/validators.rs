@@ -0,0 +1,225 @@
+use crate::models::{TaxonomyData, TaxonomySchema};
+use crate::rules::{RuleReport, Severity};
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+/// Validators registered with `register_validator`, copied into every `ValidatorRegistry::default()`
+static REGISTERED: RwLock<Vec<Arc<dyn Validator>>> = RwLock::new(Vec::new());
+
+/// A finding of a `Validator`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    /// Name of the item the issue is about; None for the document as a whole
+    pub item: Option<String>,
+    pub message: String,
+}
+
+impl ValidationIssue {
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            item: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::error(message)
+        }
+    }
+
+    /// Tie the issue to an item
+    pub fn on_item(mut self, name: impl Into<String>) -> Self {
+        self.item = Some(name.into());
+        self
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.item {
+            Some(item) => write!(f, "Item '{}': {}", item, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+/// A custom check run alongside the built-in validation, e.g. an organization's own rules
+///
+/// ```
+/// use taxstud_core::{TaxonomyData, TaxonomySchema, ValidationIssue, Validator};
+///
+/// struct NoTodo;
+///
+/// impl Validator for NoTodo {
+///     fn id(&self) -> &str {
+///         "no-todo"
+///     }
+///
+///     fn validate(&self, _: &TaxonomySchema, data: &TaxonomyData) -> Vec<ValidationIssue> {
+///         data.items
+///             .iter()
+///             .filter(|item| item.name.contains("TODO"))
+///             .map(|item| ValidationIssue::warning("name contains TODO").on_item(&item.name))
+///             .collect()
+///     }
+/// }
+///
+/// taxstud_core::register_validator(NoTodo);
+/// ```
+pub trait Validator: Send + Sync {
+    /// Short identifier, shown with each issue
+    fn id(&self) -> &str;
+    fn validate(&self, schema: &TaxonomySchema, data: &TaxonomyData) -> Vec<ValidationIssue>;
+}
+
+/// Validators run by `TaxonomyStore::validate` / `lint`, the CLI's validate and the GUI's
+/// dashboard; `default()` holds the ones registered with `register_validator`
+#[derive(Clone)]
+pub struct ValidatorRegistry {
+    validators: Vec<Arc<dyn Validator>>,
+}
+
+impl ValidatorRegistry {
+    /// A registry with no validators
+    pub fn new() -> Self {
+        Self {
+            validators: Vec::new(),
+        }
+    }
+
+    /// Add a validator, replacing any with the same id
+    pub fn register(&mut self, validator: impl Validator + 'static) {
+        insert(&mut self.validators, Arc::new(validator));
+    }
+
+    pub fn get(&self, id: &str) -> Option<&dyn Validator> {
+        self.validators
+            .iter()
+            .find(|validator| validator.id() == id)
+            .map(|validator| validator.as_ref())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Validator> {
+        self.validators.iter().map(|validator| validator.as_ref())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.validators.is_empty()
+    }
+
+    /// Every validator's issues, validator by validator
+    pub fn issues(&self, schema: &TaxonomySchema, data: &TaxonomyData) -> Vec<ValidationIssue> {
+        self.validators
+            .iter()
+            .flat_map(|validator| validator.validate(schema, data))
+            .collect()
+    }
+
+    /// The issues as errors and warnings, each naming its validator
+    pub fn check(&self, schema: &TaxonomySchema, data: &TaxonomyData) -> RuleReport {
+        let mut report = RuleReport::default();
+        for validator in &self.validators {
+            for issue in validator.validate(schema, data) {
+                let finding = format!("{} ({})", issue, validator.id());
+                match issue.severity {
+                    Severity::Error => report.errors.push(finding),
+                    Severity::Warning => report.warnings.push(finding),
+                }
+            }
+        }
+        report
+    }
+}
+
+impl fmt::Debug for ValidatorRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.validators.iter().map(|validator| validator.id()))
+            .finish()
+    }
+}
+
+impl Default for ValidatorRegistry {
+    fn default() -> Self {
+        Self {
+            validators: REGISTERED
+                .read()
+                .map(|registered| registered.clone())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Register a validator for the whole process, replacing any with the same id
+/// Call at startup; stores and registries made afterwards run it
+pub fn register_validator(validator: impl Validator + 'static) {
+    if let Ok(mut registered) = REGISTERED.write() {
+        insert(&mut registered, Arc::new(validator));
+    }
+}
+
+fn insert(validators: &mut Vec<Arc<dyn Validator>>, validator: Arc<dyn Validator>) {
+    match validators.iter().position(|v| v.id() == validator.id()) {
+        Some(index) => validators[index] = validator,
+        None => validators.push(validator),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{HierarchyBuilder, ItemBuilder, TaxonomySchemaBuilder};
+
+    /// Items must have a `sku` field
+    struct SkuRequired;
+
+    impl Validator for SkuRequired {
+        fn id(&self) -> &str {
+            "sku-required"
+        }
+
+        fn validate(&self, _: &TaxonomySchema, data: &TaxonomyData) -> Vec<ValidationIssue> {
+            data.items
+                .iter()
+                .filter(|item| !item.extra.contains_key("sku"))
+                .map(|item| ValidationIssue::error("missing sku").on_item(&item.name))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_validator_registry() {
+        let schema = TaxonomySchemaBuilder::new("Beverages")
+            .hierarchy(HierarchyBuilder::new().root("Beverage").build())
+            .build();
+        let data = TaxonomyData {
+            schema: "schema.json".to_string(),
+            items: vec![
+                ItemBuilder::new("Latte").extra("sku", "L-1").build(),
+                ItemBuilder::new("Mocha").build(),
+            ],
+            extra: Default::default(),
+        };
+
+        let mut registry = ValidatorRegistry::new();
+        assert!(registry.check(&schema, &data).errors.is_empty());
+
+        registry.register(SkuRequired);
+        registry.register(SkuRequired);
+        assert_eq!(registry.iter().count(), 1);
+        assert_eq!(
+            registry.issues(&schema, &data),
+            vec![ValidationIssue::error("missing sku").on_item("Mocha")]
+        );
+        let report = registry.check(&schema, &data);
+        assert_eq!(
+            report.errors,
+            vec!["Item 'Mocha': missing sku (sku-required)"]
+        );
+        assert!(report.warnings.is_empty());
+    }
+}
@@ -0,0 +1,734 @@
+use crate::models::{ClassicalHierarchy, HierarchyNode, Item};
+use crate::vocabulary::escape_xml;
+use std::collections::{HashMap, HashSet};
+use std::f64::consts::TAU;
+use std::io::{self, Write};
+
+/// Space between a treemap node's edge and its children, in layout units
+pub const TREEMAP_INSET: f64 = 4.0;
+/// Space above a treemap node's children, where its name goes
+pub const TREEMAP_HEADER: f64 = 16.0;
+
+/// Size of exported treemaps, unless asked otherwise
+pub const TREEMAP_WIDTH: f64 = 960.0;
+pub const TREEMAP_HEIGHT: f64 = 600.0;
+/// Width and height of exported sunbursts, unless asked otherwise
+pub const SUNBURST_SIZE: f64 = 600.0;
+
+/// A node of the classical hierarchy placed in a treemap
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreemapRect {
+    pub species: String,
+    /// 0 for the root
+    pub depth: usize,
+    /// Position of the top-level branch the node belongs to (0 for the root and the first branch)
+    pub branch: usize,
+    /// Items classified at or below the node
+    pub count: usize,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// A node of the classical hierarchy placed in a sunburst, as a ring segment
+/// The root is the center disc; each level below it is one ring further out
+#[derive(Debug, Clone, PartialEq)]
+pub struct SunburstArc {
+    pub species: String,
+    pub depth: usize,
+    pub branch: usize,
+    pub count: usize,
+    /// Start and end as fractions of the full circle, clockwise from 12 o'clock
+    pub start: f64,
+    pub end: f64,
+}
+
+impl TreemapRect {
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+}
+
+/// Number of items classified at or below each node, keyed by species (and the root)
+/// Only names on an item's classical path count, so items outside the hierarchy are ignored
+pub fn item_counts(hierarchy: &ClassicalHierarchy, items: &[Item]) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for item in items {
+        let names: HashSet<&String> = item.classical_path.iter().collect();
+        for name in names {
+            if hierarchy.contains(name) {
+                *counts.entry(name.clone()).or_insert(0) += 1;
+            }
+        }
+    }
+    counts
+}
+
+/// Lay out the hierarchy as a treemap of `width` × `height` sized by item counts
+/// Children split their parent's area below its name in proportion to their counts, across at even
+/// depths and down at odd ones; the share of items classified at the parent itself stays empty
+/// Parents come before their children, and nodes without items are left out
+pub fn treemap(
+    hierarchy: &ClassicalHierarchy,
+    items: &[Item],
+    width: f64,
+    height: f64,
+) -> Vec<TreemapRect> {
+    let counts = item_counts(hierarchy, items);
+    let mut rects = Vec::new();
+    let root_count = counts.get(&hierarchy.root).copied().unwrap_or(0);
+    if root_count == 0 {
+        return rects;
+    }
+
+    let root = TreemapRect {
+        species: hierarchy.root.clone(),
+        depth: 0,
+        branch: 0,
+        count: root_count,
+        x: 0.0,
+        y: 0.0,
+        width,
+        height,
+    };
+    rects.push(root.clone());
+    let children = hierarchy.children.as_deref().unwrap_or_default();
+    layout_treemap(children, &counts, &root, None, &mut rects);
+    rects
+}
+
+fn layout_treemap(
+    nodes: &[HierarchyNode],
+    counts: &HashMap<String, usize>,
+    parent: &TreemapRect,
+    branch: Option<usize>,
+    rects: &mut Vec<TreemapRect>,
+) {
+    let inner_width = parent.width - 2.0 * TREEMAP_INSET;
+    let inner_height = parent.height - TREEMAP_HEADER - TREEMAP_INSET;
+    if inner_width <= 0.0 || inner_height <= 0.0 {
+        return;
+    }
+
+    let across = parent.depth.is_multiple_of(2);
+    let mut offset = 0.0;
+    for (idx, node) in nodes.iter().enumerate() {
+        let count = counts.get(&node.species).copied().unwrap_or(0);
+        if count == 0 {
+            continue;
+        }
+
+        let share = count as f64 / parent.count as f64;
+        let (x, y, width, height) = if across {
+            let width = inner_width * share;
+            (
+                parent.x + TREEMAP_INSET + offset,
+                parent.y + TREEMAP_HEADER,
+                width,
+                inner_height,
+            )
+        } else {
+            let height = inner_height * share;
+            (
+                parent.x + TREEMAP_INSET,
+                parent.y + TREEMAP_HEADER + offset,
+                inner_width,
+                height,
+            )
+        };
+        offset += if across { width } else { height };
+
+        let rect = TreemapRect {
+            species: node.species.clone(),
+            depth: parent.depth + 1,
+            branch: branch.unwrap_or(idx),
+            count,
+            x,
+            y,
+            width,
+            height,
+        };
+        rects.push(rect.clone());
+        let children = node.children.as_deref().unwrap_or_default();
+        layout_treemap(children, counts, &rect, Some(rect.branch), rects);
+    }
+}
+
+/// The deepest treemap node at a point
+pub fn treemap_at(rects: &[TreemapRect], x: f64, y: f64) -> Option<&TreemapRect> {
+    rects.iter().rev().find(|rect| rect.contains(x, y))
+}
+
+/// Lay out the hierarchy as a sunburst sized by item counts
+/// Parents come before their children, and nodes without items are left out
+pub fn sunburst(hierarchy: &ClassicalHierarchy, items: &[Item]) -> Vec<SunburstArc> {
+    let counts = item_counts(hierarchy, items);
+    let mut arcs = Vec::new();
+    let root_count = counts.get(&hierarchy.root).copied().unwrap_or(0);
+    if root_count == 0 {
+        return arcs;
+    }
+
+    let root = SunburstArc {
+        species: hierarchy.root.clone(),
+        depth: 0,
+        branch: 0,
+        count: root_count,
+        start: 0.0,
+        end: 1.0,
+    };
+    arcs.push(root.clone());
+    let children = hierarchy.children.as_deref().unwrap_or_default();
+    layout_sunburst(children, &counts, &root, None, &mut arcs);
+    arcs
+}
+
+fn layout_sunburst(
+    nodes: &[HierarchyNode],
+    counts: &HashMap<String, usize>,
+    parent: &SunburstArc,
+    branch: Option<usize>,
+    arcs: &mut Vec<SunburstArc>,
+) {
+    let span = parent.end - parent.start;
+    let mut start = parent.start;
+    for (idx, node) in nodes.iter().enumerate() {
+        let count = counts.get(&node.species).copied().unwrap_or(0);
+        if count == 0 {
+            continue;
+        }
+
+        let end = start + span * count as f64 / parent.count as f64;
+        let arc = SunburstArc {
+            species: node.species.clone(),
+            depth: parent.depth + 1,
+            branch: branch.unwrap_or(idx),
+            count,
+            start,
+            end,
+        };
+        start = end;
+        arcs.push(arc.clone());
+        let children = node.children.as_deref().unwrap_or_default();
+        layout_sunburst(children, counts, &arc, Some(arc.branch), arcs);
+    }
+}
+
+/// The sunburst segment at a point given relative to the center, with the outer edge at radius 1
+pub fn sunburst_at(arcs: &[SunburstArc], x: f64, y: f64) -> Option<&SunburstArc> {
+    let rings = arcs.iter().map(|arc| arc.depth).max()? + 1;
+    let radius = (x * x + y * y).sqrt();
+    if radius >= 1.0 {
+        return None;
+    }
+    let depth = (radius * rings as f64) as usize;
+    let angle = x.atan2(-y).rem_euclid(TAU) / TAU;
+    arcs.iter()
+        .find(|arc| arc.depth == depth && angle >= arc.start && angle < arc.end)
+}
+
+/// SVG path commands of a ring segment around (`cx`, `cy`), from fractions `start` to `end`
+/// of the circle (clockwise from 12 o'clock); an inner radius of 0 gives a pie slice
+pub fn ring_segment_path(cx: f64, cy: f64, inner: f64, outer: f64, start: f64, end: f64) -> String {
+    let point = |radius: f64, fraction: f64| {
+        let angle = fraction * TAU;
+        (cx + radius * angle.sin(), cy - radius * angle.cos())
+    };
+
+    // A full circle can't be drawn with a single arc: go round in two halves
+    if end - start >= 0.9999 {
+        let (top_x, top_y) = point(outer, 0.0);
+        let (bottom_x, bottom_y) = point(outer, 0.5);
+        let mut path = format!(
+            "M {:.2} {:.2} A {o:.2} {o:.2} 0 1 1 {:.2} {:.2} A {o:.2} {o:.2} 0 1 1 {:.2} {:.2} Z",
+            top_x,
+            top_y,
+            bottom_x,
+            bottom_y,
+            top_x,
+            top_y,
+            o = outer
+        );
+        if inner > 0.0 {
+            let (top_x, top_y) = point(inner, 0.0);
+            let (bottom_x, bottom_y) = point(inner, 0.5);
+            path.push_str(&format!(
+                " M {:.2} {:.2} A {i:.2} {i:.2} 0 1 0 {:.2} {:.2} A {i:.2} {i:.2} 0 1 0 {:.2} {:.2} Z",
+                top_x,
+                top_y,
+                bottom_x,
+                bottom_y,
+                top_x,
+                top_y,
+                i = inner
+            ));
+        }
+        return path;
+    }
+
+    let large_arc = u8::from(end - start > 0.5);
+    let (x0, y0) = point(outer, start);
+    let (x1, y1) = point(outer, end);
+    let (x2, y2) = point(inner, end);
+    let (x3, y3) = point(inner, start);
+    format!(
+        "M {:.2} {:.2} A {o:.2} {o:.2} 0 {l} 1 {:.2} {:.2} L {:.2} {:.2} A {i:.2} {i:.2} 0 {l} 0 {:.2} {:.2} Z",
+        x0,
+        y0,
+        x1,
+        y1,
+        x2,
+        y2,
+        x3,
+        y3,
+        o = outer,
+        i = inner,
+        l = large_arc
+    )
+}
+
+/// Fill color of a node as RGB: one hue per top-level branch, lighter further down
+/// The root is grey
+pub fn node_color(branch: usize, depth: usize) -> (u8, u8, u8) {
+    if depth == 0 {
+        return (0x88, 0x88, 0x88);
+    }
+    // Golden-angle steps keep neighbouring branches apart
+    let hue = (branch as f64 * 137.508) % 360.0;
+    let lightness = (0.40 + 0.08 * (depth - 1) as f64).min(0.80);
+    hsl_to_rgb(hue, 0.55, lightness)
+}
+
+fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> (u8, u8, u8) {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let h = hue / 60.0;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    let channel = |c: f64| ((c + m) * 255.0).round() as u8;
+    (channel(r), channel(g), channel(b))
+}
+
+fn hex_color((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Parse a `#rrggbb` color
+pub fn parse_hex_color(hex: &str) -> Option<(u8, u8, u8)> {
+    let digits = hex.trim().strip_prefix('#')?;
+    if digits.len() != 6 || !digits.is_ascii() {
+        return None;
+    }
+    let channel = |at: usize| u8::from_str_radix(&digits[at..at + 2], 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Hover text of a node: its name (in `lang` where translated) and item count
+pub fn node_title(
+    hierarchy: &ClassicalHierarchy,
+    species: &str,
+    count: usize,
+    lang: Option<&str>,
+) -> String {
+    format!(
+        "{} — {} item(s)",
+        hierarchy.display_name(species, lang),
+        count
+    )
+}
+
+/// Write the hierarchy as an SVG treemap of `width` × `height`
+/// Each node has a title (shown on hover) with its item count; names label nodes large enough
+pub fn write_treemap_svg(
+    hierarchy: &ClassicalHierarchy,
+    items: &[Item],
+    width: f64,
+    height: f64,
+    lang: Option<&str>,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="11">"#,
+        w = width,
+        h = height
+    )?;
+    for rect in treemap(hierarchy, items, width, height) {
+        let title = node_title(hierarchy, &rect.species, rect.count, lang);
+        writeln!(
+            out,
+            r##"  <g><title>{}</title><rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="{}" stroke="#ffffff"/>"##,
+            escape_xml(&title),
+            rect.x,
+            rect.y,
+            rect.width,
+            rect.height,
+            hex_color(node_color(rect.branch, rect.depth))
+        )?;
+        if rect.width >= 40.0 && rect.height >= 16.0 {
+            writeln!(
+                out,
+                r##"    <text x="{:.2}" y="{:.2}" fill="#ffffff">{}</text>"##,
+                rect.x + TREEMAP_INSET,
+                rect.y + 12.0,
+                escape_xml(hierarchy.display_name(&rect.species, lang))
+            )?;
+        }
+        writeln!(out, "  </g>")?;
+    }
+    writeln!(out, "</svg>")
+}
+
+/// Write the hierarchy as an SVG sunburst `size` wide and high
+/// Each segment has a title (shown on hover) with its name and item count
+pub fn write_sunburst_svg(
+    hierarchy: &ClassicalHierarchy,
+    items: &[Item],
+    size: f64,
+    lang: Option<&str>,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let arcs = sunburst(hierarchy, items);
+    let rings = arcs.iter().map(|arc| arc.depth + 1).max().unwrap_or(1);
+    let center = size / 2.0;
+    let ring_width = center / rings as f64;
+
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{s}" height="{s}" viewBox="0 0 {s} {s}">"#,
+        s = size
+    )?;
+    for arc in &arcs {
+        let title = node_title(hierarchy, &arc.species, arc.count, lang);
+        let path = ring_segment_path(
+            center,
+            center,
+            ring_width * arc.depth as f64,
+            ring_width * (arc.depth + 1) as f64,
+            arc.start,
+            arc.end,
+        );
+        writeln!(
+            out,
+            r##"  <path d="{}" fill="{}" fill-rule="evenodd" stroke="#ffffff"><title>{}</title></path>"##,
+            path,
+            hex_color(node_color(arc.branch, arc.depth)),
+            escape_xml(&title)
+        )?;
+    }
+    writeln!(out, "</svg>")
+}
+
+/// How `render_hierarchy_svg` lays out and labels the tree
+#[derive(Debug, Clone, PartialEq)]
+pub struct HierarchyStyle {
+    /// Children to the right of their parent rather than below it
+    pub horizontal: bool,
+    /// Size of a node's box
+    pub node_width: f64,
+    pub node_height: f64,
+    /// Space between boxes on the same level, and between levels
+    pub sibling_gap: f64,
+    pub level_gap: f64,
+    /// Language of the names; `None` for the names as written
+    pub lang: Option<String>,
+}
+
+impl Default for HierarchyStyle {
+    fn default() -> Self {
+        Self {
+            horizontal: false,
+            node_width: 140.0,
+            node_height: 32.0,
+            sibling_gap: 12.0,
+            level_gap: 48.0,
+            lang: None,
+        }
+    }
+}
+
+/// Space around the tree in a hierarchy diagram
+const DIAGRAM_MARGIN: f64 = 16.0;
+
+/// A node of the classical hierarchy placed in a tree diagram
+struct DiagramNode {
+    species: String,
+    differentia: String,
+    depth: usize,
+    branch: usize,
+    /// Position across the levels, in leaf slots
+    slot: f64,
+    parent: Option<usize>,
+}
+
+/// Draw the classical hierarchy as an SVG tree diagram: one box per node, linked to its genus
+/// Leaves sit side by side and each parent is centered over its children; boxes are colored
+/// like the treemap, and their titles (shown on hover) give the differentia
+pub fn render_hierarchy_svg(hierarchy: &ClassicalHierarchy, style: &HierarchyStyle) -> String {
+    let mut nodes = vec![DiagramNode {
+        species: hierarchy.root.clone(),
+        differentia: String::new(),
+        depth: 0,
+        branch: 0,
+        slot: 0.0,
+        parent: None,
+    }];
+    let mut next_slot = 0.0;
+    let children = hierarchy.children.as_deref().unwrap_or_default();
+    if !children.is_empty() {
+        let slots = layout_diagram(children, 0, None, &mut next_slot, &mut nodes);
+        nodes[0].slot = slots;
+    } else {
+        next_slot = 1.0;
+    }
+    let levels = nodes.iter().map(|node| node.depth + 1).max().unwrap_or(1);
+
+    // Across runs along the levels, down runs from genus to species
+    let (across_size, down_size) = if style.horizontal {
+        (style.node_height, style.node_width)
+    } else {
+        (style.node_width, style.node_height)
+    };
+    let across = |slot: f64| DIAGRAM_MARGIN + slot * (across_size + style.sibling_gap);
+    let down = |depth: usize| DIAGRAM_MARGIN + depth as f64 * (down_size + style.level_gap);
+    let across_extent = across(next_slot) - style.sibling_gap + DIAGRAM_MARGIN;
+    let down_extent = down(levels) - style.level_gap + DIAGRAM_MARGIN;
+    // Top left corner of a node's box
+    let corner = |node: &DiagramNode| {
+        if style.horizontal {
+            (down(node.depth), across(node.slot))
+        } else {
+            (across(node.slot), down(node.depth))
+        }
+    };
+    let (width, height) = if style.horizontal {
+        (down_extent, across_extent)
+    } else {
+        (across_extent, down_extent)
+    };
+    let lang = style.lang.as_deref();
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="11">"#,
+        w = width,
+        h = height
+    );
+    svg.push('\n');
+    for node in &nodes {
+        let Some(parent) = node.parent else {
+            continue;
+        };
+        let (px, py) = corner(&nodes[parent]);
+        let (x, y) = corner(node);
+        // From the middle of the parent's far edge to the middle of the child's near edge
+        let (x1, y1, x2, y2) = if style.horizontal {
+            let (y1, y2) = (py + style.node_height / 2.0, y + style.node_height / 2.0);
+            (px + style.node_width, y1, x, y2)
+        } else {
+            let (x1, x2) = (px + style.node_width / 2.0, x + style.node_width / 2.0);
+            (x1, py + style.node_height, x2, y)
+        };
+        let (c1, c2) = if style.horizontal {
+            let mid = (x1 + x2) / 2.0;
+            ((mid, y1), (mid, y2))
+        } else {
+            let mid = (y1 + y2) / 2.0;
+            ((x1, mid), (x2, mid))
+        };
+        svg.push_str(&format!(
+            r##"  <path d="M{:.2},{:.2} C{:.2},{:.2} {:.2},{:.2} {:.2},{:.2}" fill="none" stroke="#999999"/>"##,
+            x1, y1, c1.0, c1.1, c2.0, c2.1, x2, y2
+        ));
+        svg.push('\n');
+    }
+    // Roughly how many characters fit in a box
+    let max_chars = ((style.node_width - 8.0) / 6.5).max(1.0) as usize;
+    for node in &nodes {
+        let (x, y) = corner(node);
+        let name = hierarchy.display_name(&node.species, lang);
+        let title = if node.differentia.is_empty() {
+            name.to_string()
+        } else {
+            format!("{} — {}", name, node.differentia)
+        };
+        let label = if name.chars().count() > max_chars {
+            let kept: String = name.chars().take(max_chars.saturating_sub(1)).collect();
+            format!("{}…", kept)
+        } else {
+            name.to_string()
+        };
+        svg.push_str(&format!(
+            r##"  <g><title>{}</title><rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" rx="4" fill="{}"/>"##,
+            escape_xml(&title),
+            x,
+            y,
+            style.node_width,
+            style.node_height,
+            hex_color(node_color(node.branch, node.depth))
+        ));
+        svg.push_str(&format!(
+            r##"<text x="{:.2}" y="{:.2}" fill="#ffffff" text-anchor="middle" dominant-baseline="central">{}</text></g>"##,
+            x + style.node_width / 2.0,
+            y + style.node_height / 2.0,
+            escape_xml(&label)
+        ));
+        svg.push('\n');
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Add the nodes below `parent` to the diagram, and return the slot of their middle
+fn layout_diagram(
+    children: &[HierarchyNode],
+    parent: usize,
+    branch: Option<usize>,
+    next_slot: &mut f64,
+    nodes: &mut Vec<DiagramNode>,
+) -> f64 {
+    let depth = nodes[parent].depth + 1;
+    let mut slots = Vec::with_capacity(children.len());
+    for (idx, child) in children.iter().enumerate() {
+        let at = nodes.len();
+        let branch = branch.unwrap_or(idx);
+        nodes.push(DiagramNode {
+            species: child.species.clone(),
+            differentia: child.differentia.clone(),
+            depth,
+            branch,
+            slot: 0.0,
+            parent: Some(parent),
+        });
+        let grandchildren = child.children.as_deref().unwrap_or_default();
+        nodes[at].slot = if grandchildren.is_empty() {
+            *next_slot += 1.0;
+            *next_slot - 1.0
+        } else {
+            layout_diagram(grandchildren, at, Some(branch), next_slot, nodes)
+        };
+        slots.push(nodes[at].slot);
+    }
+    (slots[0] + slots[slots.len() - 1]) / 2.0
+}
+
+/// Rasterize an SVG document to PNG bytes at its own size
+#[cfg(feature = "png")]
+pub fn svg_to_png(svg: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    use resvg::{tiny_skia, usvg};
+
+    let mut options = usvg::Options::default();
+    options.fontdb_mut().load_system_fonts();
+    let tree = usvg::Tree::from_str(svg, &options)?;
+    let size = tree.size().to_int_size();
+    let mut pixmap =
+        tiny_skia::Pixmap::new(size.width(), size.height()).ok_or("Image has no area")?;
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+    Ok(pixmap.encode_png()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{HierarchyBuilder, ItemBuilder};
+
+    #[test]
+    fn test_treemap_and_sunburst() {
+        let hierarchy = HierarchyBuilder::new()
+            .root("Beverage")
+            .child("Hot Beverage", "served heated", |c| {
+                c.leaf("Coffee", "brewed from beans")
+                    .leaf("Tea", "steeped leaves")
+            })
+            .leaf("Cold Beverage", "served chilled")
+            .build();
+        let items = vec![
+            ItemBuilder::new("Latte")
+                .path(["Beverage", "Hot Beverage", "Coffee"])
+                .build(),
+            ItemBuilder::new("Mocha")
+                .path(["Beverage", "Hot Beverage", "Coffee"])
+                .build(),
+            ItemBuilder::new("Lemonade")
+                .path(["Beverage", "Cold Beverage"])
+                .build(),
+        ];
+
+        let counts = item_counts(&hierarchy, &items);
+        assert_eq!(counts["Beverage"], 3);
+        assert_eq!(counts["Hot Beverage"], 2);
+        assert!(!counts.contains_key("Tea"));
+
+        let rects = treemap(&hierarchy, &items, 308.0, 120.0);
+        let names: Vec<&str> = rects.iter().map(|r| r.species.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["Beverage", "Hot Beverage", "Coffee", "Cold Beverage"]
+        );
+        assert_eq!(rects[1].width, 200.0);
+        assert_eq!(rects[3].x, 204.0);
+        assert_eq!(treemap_at(&rects, 20.0, 40.0).unwrap().species, "Coffee");
+        assert_eq!(
+            treemap_at(&rects, 20.0, 20.0).unwrap().species,
+            "Hot Beverage"
+        );
+        assert_eq!(
+            treemap_at(&rects, 250.0, 20.0).unwrap().species,
+            "Cold Beverage"
+        );
+
+        let arcs = sunburst(&hierarchy, &items);
+        let hot = &arcs[1];
+        assert_eq!((hot.start, hot.end), (0.0, 2.0 / 3.0));
+        assert_eq!(arcs[3].branch, 1);
+        // Right of center, in the middle ring
+        assert_eq!(
+            sunburst_at(&arcs, 0.5, 0.0).unwrap().species,
+            "Hot Beverage"
+        );
+        assert_eq!(sunburst_at(&arcs, 0.1, 0.0).unwrap().species, "Beverage");
+        assert!(sunburst_at(&arcs, 1.0, 1.0).is_none());
+
+        let mut svg = Vec::new();
+        write_sunburst_svg(&hierarchy, &items, 200.0, None, &mut svg).unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+        assert!(svg.contains("<title>Cold Beverage — 1 item(s)</title>"));
+    }
+
+    #[test]
+    fn test_render_hierarchy_svg() {
+        let hierarchy = HierarchyBuilder::new()
+            .root("Beverage")
+            .child("Hot Beverage", "served heated", |c| {
+                c.leaf("Coffee", "brewed from beans")
+                    .leaf("Tea", "steeped leaves")
+            })
+            .leaf("Cold Beverage", "served chilled")
+            .build();
+
+        // Three leaves of 140 with gaps of 12, plus margins; three levels of 32 with gaps of 48
+        let svg = render_hierarchy_svg(&hierarchy, &HierarchyStyle::default());
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="476" height="224""#)
+        );
+        assert_eq!(svg.matches("<rect ").count(), 5);
+        assert_eq!(svg.matches("<path ").count(), 4);
+        assert!(svg.contains("<title>Coffee — brewed from beans</title>"));
+        // Hot Beverage is centered over Coffee and Tea
+        assert!(svg.contains(r#"<rect x="92.00" y="96.00""#));
+
+        let style = HierarchyStyle {
+            horizontal: true,
+            ..HierarchyStyle::default()
+        };
+        let svg = render_hierarchy_svg(&hierarchy, &style);
+        assert!(svg.contains(r#"width="548" height="152""#));
+    }
+}
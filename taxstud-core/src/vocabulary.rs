@@ -0,0 +1,363 @@
+use crate::models::{FacetValueInfo, FacetValueNode, HierarchyNode, TaxonomySchema};
+use std::error::Error;
+use std::io::{self, Write};
+
+const SKOS_NS: &str = "http://www.w3.org/2004/02/skos/core#";
+const RDF_NS: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const OWL_NS: &str = "http://www.w3.org/2002/07/owl#";
+const XML_NS: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// Parse a CSV (or, with a tab delimiter, TSV) vocabulary
+/// Columns are value, label, description; a header row naming them may reorder them
+pub fn parse_csv_vocabulary(
+    contents: &str,
+    delimiter: u8,
+) -> Result<Vec<FacetValueNode>, Box<dyn Error>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .comment(Some(b'#'))
+        .delimiter(delimiter)
+        .from_reader(contents.as_bytes());
+
+    let mut records = reader.records().peekable();
+    let mut columns = [Some(0), Some(1), Some(2)];
+    if let Some(Ok(first)) = records.peek() {
+        let header: Vec<String> = first.iter().map(str::to_lowercase).collect();
+        let find = |names: &[&str]| header.iter().position(|h| names.contains(&h.as_str()));
+        if let Some(value) = find(&["value", "term", "notation"]) {
+            columns = [
+                Some(value),
+                find(&["label", "preflabel", "name"]),
+                find(&["description", "definition"]),
+            ];
+            records.next();
+        }
+    }
+
+    let mut terms = Vec::new();
+    for record in records {
+        let record = record?;
+        let field = |column: Option<usize>| {
+            column
+                .and_then(|i| record.get(i))
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        let Some(value) = field(columns[0]) else {
+            continue;
+        };
+        terms.push(FacetValueNode::new(value).with_info(FacetValueInfo {
+            label: field(columns[1]),
+            description: field(columns[2]),
+            ..FacetValueInfo::default()
+        }));
+    }
+
+    Ok(terms)
+}
+
+/// Parse the concepts of a SKOS vocabulary in RDF/XML
+/// A concept's value is its skos:notation, or its preferred label when it has none
+pub fn parse_skos_vocabulary(contents: &str) -> Result<Vec<FacetValueNode>, Box<dyn Error>> {
+    let document = roxmltree::Document::parse(contents)?;
+
+    let mut terms = Vec::new();
+    for concept in document.descendants().filter(is_skos_concept) {
+        let text_of = |name: &str| {
+            concept
+                .children()
+                .filter(|child| child.tag_name().namespace() == Some(SKOS_NS))
+                .filter(|child| child.tag_name().name() == name)
+                .filter_map(|child| {
+                    let text = child.text()?.trim();
+                    (!text.is_empty()).then(|| (child.attribute((XML_NS, "lang")), text))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Prefer an English or untagged label over other languages
+        let labels = text_of("prefLabel");
+        let pref_label = labels
+            .iter()
+            .find(|(lang, _)| matches!(lang, None | Some("en")))
+            .or(labels.first())
+            .map(|(_, text)| text.to_string());
+        let notation = text_of("notation")
+            .first()
+            .map(|(_, text)| text.to_string());
+        let description = text_of("definition")
+            .into_iter()
+            .chain(text_of("scopeNote"))
+            .next()
+            .map(|(_, text)| text.to_string());
+        let deprecated = concept.children().any(|child| {
+            child.tag_name().namespace() == Some(OWL_NS)
+                && child.tag_name().name() == "deprecated"
+                && child.text().is_some_and(|text| text.trim() == "true")
+        });
+
+        // Language-tagged labels become translations
+        let labels = labels
+            .iter()
+            .filter_map(|(lang, text)| Some((lang.as_ref()?.to_string(), text.to_string())))
+            .collect();
+
+        let (value, label) = match (notation, pref_label) {
+            (Some(notation), label) => (notation, label),
+            (None, Some(label)) => (label, None),
+            (None, None) => continue,
+        };
+        terms.push(FacetValueNode::new(value).with_info(FacetValueInfo {
+            label,
+            description,
+            deprecated,
+            labels,
+        }));
+    }
+
+    Ok(terms)
+}
+
+/// Write the classical hierarchy as a SKOS concept scheme in RDF/XML
+/// Synonyms become altLabels, the code a notation, and external IDs exactMatch links
+/// (when they are URIs) or notes
+pub fn write_skos(schema: &TaxonomySchema, out: &mut dyn Write) -> io::Result<()> {
+    let base = if schema.schema_id.contains(':') {
+        format!("{}#", schema.schema_id.trim_end_matches('#'))
+    } else {
+        "urn:taxstud:".to_string()
+    };
+    let hierarchy = &schema.classical_hierarchy;
+    let root_uri = concept_uri(&base, &hierarchy.root);
+
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        r#"<rdf:RDF xmlns:rdf="{}" xmlns:skos="{}">"#,
+        RDF_NS, SKOS_NS
+    )?;
+    writeln!(
+        out,
+        r#"  <skos:ConceptScheme rdf:about="{}scheme">"#,
+        escape_xml(&base)
+    )?;
+    writeln!(
+        out,
+        "    <skos:prefLabel>{}</skos:prefLabel>",
+        escape_xml(&schema.title)
+    )?;
+    writeln!(
+        out,
+        r#"    <skos:hasTopConcept rdf:resource="{}"/>"#,
+        root_uri
+    )?;
+    writeln!(out, "  </skos:ConceptScheme>")?;
+
+    writeln!(out, r#"  <skos:Concept rdf:about="{}">"#, root_uri)?;
+    writeln!(
+        out,
+        "    <skos:prefLabel>{}</skos:prefLabel>",
+        escape_xml(&hierarchy.root)
+    )?;
+    writeln!(
+        out,
+        r#"    <skos:inScheme rdf:resource="{}scheme"/>"#,
+        escape_xml(&base)
+    )?;
+    writeln!(out, "  </skos:Concept>")?;
+
+    for (_, node) in hierarchy.iter_preorder() {
+        write_skos_concept(out, &base, node)?;
+    }
+
+    writeln!(out, "</rdf:RDF>")
+}
+
+fn write_skos_concept(out: &mut dyn Write, base: &str, node: &HierarchyNode) -> io::Result<()> {
+    writeln!(
+        out,
+        r#"  <skos:Concept rdf:about="{}">"#,
+        concept_uri(base, &node.species)
+    )?;
+    writeln!(
+        out,
+        "    <skos:prefLabel>{}</skos:prefLabel>",
+        escape_xml(&node.species)
+    )?;
+    for synonym in &node.synonyms {
+        writeln!(
+            out,
+            "    <skos:altLabel>{}</skos:altLabel>",
+            escape_xml(synonym)
+        )?;
+    }
+    if let Some(code) = &node.code {
+        writeln!(
+            out,
+            "    <skos:notation>{}</skos:notation>",
+            escape_xml(code)
+        )?;
+    }
+    if !node.differentia.is_empty() {
+        writeln!(
+            out,
+            "    <skos:definition>{}</skos:definition>",
+            escape_xml(&node.differentia)
+        )?;
+    }
+    writeln!(
+        out,
+        r#"    <skos:broader rdf:resource="{}"/>"#,
+        concept_uri(base, &node.genus)
+    )?;
+
+    let mut external_ids: Vec<_> = node.external_ids.iter().collect();
+    external_ids.sort();
+    for (system, id) in external_ids {
+        if id.starts_with("http://") || id.starts_with("https://") {
+            writeln!(
+                out,
+                r#"    <skos:exactMatch rdf:resource="{}"/>"#,
+                escape_xml(id)
+            )?;
+        } else {
+            writeln!(
+                out,
+                "    <skos:note>{}: {}</skos:note>",
+                escape_xml(system),
+                escape_xml(id)
+            )?;
+        }
+    }
+
+    writeln!(
+        out,
+        r#"    <skos:inScheme rdf:resource="{}scheme"/>"#,
+        escape_xml(base)
+    )?;
+    writeln!(out, "  </skos:Concept>")
+}
+
+/// Concept URI from the scheme base and a species name made URI-safe
+fn concept_uri(base: &str, species: &str) -> String {
+    let slug: String = species
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    escape_xml(&format!("{}{}", base, slug))
+}
+
+pub(crate) fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A skos:Concept element, or an rdf:Description typed as one
+fn is_skos_concept(node: &roxmltree::Node) -> bool {
+    let name = node.tag_name();
+    if name.namespace() == Some(SKOS_NS) && name.name() == "Concept" {
+        return true;
+    }
+
+    name.namespace() == Some(RDF_NS)
+        && name.name() == "Description"
+        && node.children().any(|child| {
+            child.tag_name().namespace() == Some(RDF_NS)
+                && child.tag_name().name() == "type"
+                && child
+                    .attribute((RDF_NS, "resource"))
+                    .and_then(|resource| resource.strip_prefix(SKOS_NS))
+                    == Some("Concept")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_with_and_without_header() {
+        let plain = parse_csv_vocabulary("hot\niced, Iced, Served cold\n\n# note\n", b',').unwrap();
+        assert_eq!(plain.len(), 2);
+        assert_eq!(plain[1].info.label.as_deref(), Some("Iced"));
+        assert_eq!(plain[1].info.description.as_deref(), Some("Served cold"));
+
+        let headed = parse_csv_vocabulary("label\tterm\nFrance\tFR\n", b'\t').unwrap();
+        assert_eq!(headed[0].value, "FR");
+        assert_eq!(headed[0].info.label.as_deref(), Some("France"));
+    }
+
+    #[test]
+    fn test_skos_concepts() {
+        let skos = r#"<?xml version="1.0"?>
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+         xmlns:skos="http://www.w3.org/2004/02/skos/core#"
+         xmlns:owl="http://www.w3.org/2002/07/owl#">
+  <skos:Concept rdf:about="http://example.com/hot">
+    <skos:prefLabel xml:lang="fr">Chaud</skos:prefLabel>
+    <skos:prefLabel xml:lang="en">hot</skos:prefLabel>
+    <skos:definition>Served above 60 °C</skos:definition>
+  </skos:Concept>
+  <rdf:Description rdf:about="http://example.com/lukewarm">
+    <rdf:type rdf:resource="http://www.w3.org/2004/02/skos/core#Concept"/>
+    <skos:notation>LW</skos:notation>
+    <skos:prefLabel>lukewarm</skos:prefLabel>
+    <owl:deprecated>true</owl:deprecated>
+  </rdf:Description>
+</rdf:RDF>"#;
+        let terms = parse_skos_vocabulary(skos).unwrap();
+        assert_eq!(terms.len(), 2);
+        assert_eq!(terms[0].value, "hot");
+        assert_eq!(terms[0].info.labels["fr"], "Chaud");
+        assert_eq!(
+            terms[0].info.description.as_deref(),
+            Some("Served above 60 °C")
+        );
+        assert_eq!(terms[1].value, "LW");
+        assert_eq!(terms[1].info.label.as_deref(), Some("lukewarm"));
+        assert!(terms[1].info.deprecated);
+    }
+
+    #[test]
+    fn test_write_skos_round_trips_concepts() {
+        use crate::builder::{HierarchyBuilder, TaxonomySchemaBuilder};
+
+        let hierarchy = HierarchyBuilder::new()
+            .root("Beverage")
+            .child("Hot Beverage", "served heated", |c| {
+                c.synonym("Warm Drink")
+                    .code("HOT")
+                    .external_id("wikidata", "https://www.wikidata.org/wiki/Q2")
+                    .external_id("legacy", "B&7")
+            })
+            .build();
+        let schema = TaxonomySchemaBuilder::new("Beverages")
+            .hierarchy(hierarchy)
+            .build();
+
+        let mut out = Vec::new();
+        write_skos(&schema, &mut out).unwrap();
+        let rdf = String::from_utf8(out).unwrap();
+        assert!(rdf.contains("<skos:altLabel>Warm Drink</skos:altLabel>"));
+        assert!(rdf.contains("<skos:note>legacy: B&amp;7</skos:note>"));
+        assert!(
+            rdf.contains(r#"<skos:exactMatch rdf:resource="https://www.wikidata.org/wiki/Q2"/>"#)
+        );
+
+        // Codes come back as values, labelled with the species
+        let terms = parse_skos_vocabulary(&rdf).unwrap();
+        assert_eq!(terms.len(), 2);
+        assert_eq!(terms[1].value, "HOT");
+        assert_eq!(terms[1].info.label.as_deref(), Some("Hot Beverage"));
+    }
+}
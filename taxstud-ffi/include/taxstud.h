@@ -0,0 +1,56 @@
+#ifndef TAXSTUD_H
+#define TAXSTUD_H
+
+/* Generated by cbindgen from taxstud-ffi/src/lib.rs; do not edit by hand */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Load a data file and the schema it references
+// Returns `{"ok": {"schema": <JSON Schema>, "data": <data>}}`
+//
+// # Safety
+// `data_path` must be null or a valid NUL-terminated string
+char *taxstud_load(const char *data_path);
+
+// Validate data against a schema, including its house-style rules
+// Returns `{"ok": {"valid": bool, "errors": [..], "warnings": [..]}}`
+//
+// # Safety
+// Both arguments must be null or valid NUL-terminated strings
+char *taxstud_validate(const char *schema_json, const char *data_json);
+
+// Items of the data matching a query, such as
+// `{"genera": ["Coffee"], "facets": ["temperature=hot"], "descendants": true}`
+// Deprecated items are left out unless the query has `"deprecated": true`
+// `"ignore_case": true` and `"ignore_accents": true` loosen how values compare
+// Returns `{"ok": [<item>, ..]}`
+//
+// # Safety
+// All arguments must be null or valid NUL-terminated strings
+char *taxstud_filter(const char *schema_json, const char *data_json, const char *query_json);
+
+// Data as the pretty-printed JSON a save writes
+// Returns `{"ok": "<JSON text>"}`
+//
+// # Safety
+// `data_json` must be null or a valid NUL-terminated string
+char *taxstud_serialize(const char *data_json);
+
+// Release a string returned by any taxstud function
+//
+// # Safety
+// `s` must be null or a pointer returned by this library, freed only once
+void taxstud_string_free(char *s);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* TAXSTUD_H */
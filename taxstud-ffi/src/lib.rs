@@ -0,0 +1,263 @@
+//! C ABI for taxstud-core, so other languages (e.g. C# through P/Invoke) use the same
+//! loading, validation, filtering and serialization as the GUI and CLI
+//!
+//! Every function takes NUL-terminated UTF-8 strings and returns a newly allocated JSON
+//! string, either `{"ok": <result>}` or `{"error": "<message>"}`, which the caller must
+//! release with `taxstud_string_free`. Schemas and data are passed as JSON text.
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::panic::{self, UnwindSafe};
+use std::ptr;
+use taxstud_core::{
+    apply_filters_with_schema, build_schema_from_json, load_data_with_auto_schema,
+    parse_facet_filters, FilterOptions, Filters, RuleSet, TaxonomyData, TaxonomySchema,
+    TaxonomyStore,
+};
+
+/// Load a data file and the schema it references
+/// Returns `{"ok": {"schema": <JSON Schema>, "data": <data>}}`
+///
+/// # Safety
+/// `data_path` must be null or a valid NUL-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn taxstud_load(data_path: *const c_char) -> *mut c_char {
+    let data_path = arg(data_path, "data_path");
+    respond(move || {
+        let (data, schema) = load_data_with_auto_schema(data_path?).map_err(|e| e.to_string())?;
+        Ok(json!({ "schema": schema.json_schema(), "data": data }))
+    })
+}
+
+/// Validate data against a schema, including its house-style rules
+/// Returns `{"ok": {"valid": bool, "errors": [..], "warnings": [..]}}`
+///
+/// # Safety
+/// Both arguments must be null or valid NUL-terminated strings
+#[no_mangle]
+pub unsafe extern "C" fn taxstud_validate(
+    schema_json: *const c_char,
+    data_json: *const c_char,
+) -> *mut c_char {
+    let (schema_json, data_json) = (arg(schema_json, "schema"), arg(data_json, "data"));
+    respond(move || {
+        let schema = parse_schema(schema_json?)?;
+        let data_value: Value = parse_json(data_json?, "data")?;
+
+        // Data that does not match the JSON Schema may not even deserialize as items
+        if let Err(errors) = schema.validate_json(&data_value) {
+            return Ok(json!({ "valid": false, "errors": errors, "warnings": [] }));
+        }
+
+        let data: TaxonomyData =
+            serde_json::from_value(data_value).map_err(|e| format!("Invalid data: {}", e))?;
+        let json_schema = schema.json_schema().cloned().unwrap_or_default();
+        let mut store = TaxonomyStore::new(schema, data);
+        store.set_rules(RuleSet::from_json_schema(&json_schema)?);
+
+        let errors = store.validate().err().unwrap_or_default();
+        Ok(json!({
+            "valid": errors.is_empty(),
+            "errors": errors,
+            "warnings": store.lint(),
+        }))
+    })
+}
+
+/// Items of the data matching a query, such as
+/// `{"genera": ["Coffee"], "facets": ["temperature=hot"], "descendants": true}`
+/// Deprecated items are left out unless the query has `"deprecated": true`
+/// `"ignore_case": true` and `"ignore_accents": true` loosen how values compare
+/// Returns `{"ok": [<item>, ..]}`
+///
+/// # Safety
+/// All arguments must be null or valid NUL-terminated strings
+#[no_mangle]
+pub unsafe extern "C" fn taxstud_filter(
+    schema_json: *const c_char,
+    data_json: *const c_char,
+    query_json: *const c_char,
+) -> *mut c_char {
+    let (schema_json, data_json) = (arg(schema_json, "schema"), arg(data_json, "data"));
+    let query_json = arg(query_json, "query");
+    respond(move || {
+        let schema = parse_schema(schema_json?)?;
+        let data: TaxonomyData = parse_json(data_json?, "data")?;
+        let query: Query = parse_json(query_json?, "query")?;
+
+        let items = apply_filters_with_schema(&data.items, &query.filters(), &schema);
+        serde_json::to_value(items).map_err(|e| e.to_string())
+    })
+}
+
+/// Data as the pretty-printed JSON a save writes
+/// Returns `{"ok": "<JSON text>"}`
+///
+/// # Safety
+/// `data_json` must be null or a valid NUL-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn taxstud_serialize(data_json: *const c_char) -> *mut c_char {
+    let data_json = arg(data_json, "data");
+    respond(move || {
+        let data: TaxonomyData = parse_json(data_json?, "data")?;
+        let text = serde_json::to_string_pretty(&data).map_err(|e| e.to_string())?;
+        Ok(Value::String(text))
+    })
+}
+
+/// Release a string returned by any taxstud function
+///
+/// # Safety
+/// `s` must be null or a pointer returned by this library, freed only once
+#[no_mangle]
+pub unsafe extern "C" fn taxstud_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Filters of a `taxstud_filter` query, written like the CLI's `query` options
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Query {
+    genera: Vec<String>,
+    /// "name=value" or numeric comparisons like "abv>=5"
+    facets: Vec<String>,
+    not_genera: Vec<String>,
+    not_facets: Vec<String>,
+    fields: Vec<String>,
+    name_contains: Option<String>,
+    descendants: bool,
+    /// Include deprecated items
+    deprecated: bool,
+    /// Compare genera, facet and field values ignoring case
+    ignore_case: bool,
+    /// ... and accents
+    ignore_accents: bool,
+}
+
+impl Query {
+    fn filters(&self) -> Filters {
+        Filters {
+            genera: self.genera.clone(),
+            facets: parse_facet_filters(&self.facets),
+            excluded_genera: self.not_genera.clone(),
+            excluded_facets: parse_facet_filters(&self.not_facets),
+            fields: parse_facet_filters(&self.fields),
+            name_contains: self.name_contains.clone(),
+            include_descendants: self.descendants,
+            include_deprecated: self.deprecated,
+            options: FilterOptions {
+                case_insensitive: self.ignore_case,
+                strip_diacritics: self.ignore_accents,
+            },
+        }
+    }
+}
+
+/// Borrow a string argument, rejecting null and invalid UTF-8
+unsafe fn arg<'a>(s: *const c_char, name: &str) -> Result<&'a str, String> {
+    if s.is_null() {
+        return Err(format!("'{}' is null", name));
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|_| format!("'{}' is not valid UTF-8", name))
+}
+
+fn parse_json<T: serde::de::DeserializeOwned>(text: &str, name: &str) -> Result<T, String> {
+    serde_json::from_str(text).map_err(|e| format!("Invalid {}: {}", name, e))
+}
+
+fn parse_schema(text: &str) -> Result<TaxonomySchema, String> {
+    build_schema_from_json(parse_json(text, "schema")?)
+}
+
+/// Run a call and wrap its outcome (or a panic) in the JSON envelope
+fn respond(call: impl FnOnce() -> Result<Value, String> + UnwindSafe) -> *mut c_char {
+    let envelope = match panic::catch_unwind(call) {
+        Ok(Ok(value)) => json!({ "ok": value }),
+        Ok(Err(message)) => json!({ "error": message }),
+        Err(_) => json!({ "error": "internal error" }),
+    };
+    // JSON text escapes control characters, so it never holds a NUL byte
+    CString::new(envelope.to_string()).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = r#"{
+        "title": "Beverages",
+        "classical_hierarchy": {
+            "root": "Beverage",
+            "children": [
+                {"genus": "Beverage", "species": "Coffee", "differentia": "brewed from beans"},
+                {"genus": "Beverage", "species": "Tea", "differentia": "steeped leaves"}
+            ]
+        },
+        "faceted_dimensions": {"temperature": ["hot", "iced"]}
+    }"#;
+
+    const DATA: &str = r#"{
+        "schema": "schema.json",
+        "items": [
+            {"name": "Latte", "classical_path": ["Beverage", "Coffee"],
+             "facets": {"temperature": "hot"}},
+            {"name": "Iced Tea", "classical_path": ["Beverage", "Tea"],
+             "facets": {"temperature": "iced"}}
+        ]
+    }"#;
+
+    /// Call through the C ABI and take back the parsed envelope
+    fn call(f: impl FnOnce(&[CString]) -> *mut c_char, args: &[&str]) -> Value {
+        let args: Vec<CString> = args.iter().map(|a| CString::new(*a).unwrap()).collect();
+        let result = f(&args);
+        let text = unsafe { CStr::from_ptr(result) }
+            .to_str()
+            .unwrap()
+            .to_string();
+        unsafe { taxstud_string_free(result) };
+        serde_json::from_str(&text).unwrap()
+    }
+
+    #[test]
+    fn test_ffi() {
+        let report = call(
+            |a| unsafe { taxstud_validate(a[0].as_ptr(), a[1].as_ptr()) },
+            &[SCHEMA, DATA],
+        );
+        assert_eq!(report["ok"]["valid"], true, "{}", report);
+
+        let bad = DATA.replace("\"Tea\"]", "\"Juice\"]");
+        let report = call(
+            |a| unsafe { taxstud_validate(a[0].as_ptr(), a[1].as_ptr()) },
+            &[SCHEMA, &bad],
+        );
+        assert_eq!(report["ok"]["valid"], false);
+        assert!(!report["ok"]["errors"].as_array().unwrap().is_empty());
+
+        let items = call(
+            |a| unsafe { taxstud_filter(a[0].as_ptr(), a[1].as_ptr(), a[2].as_ptr()) },
+            &[SCHEMA, DATA, r#"{"facets": ["temperature=iced"]}"#],
+        );
+        assert_eq!(items["ok"].as_array().unwrap().len(), 1);
+        assert_eq!(items["ok"][0]["name"], "Iced Tea");
+
+        let unknown = call(
+            |a| unsafe { taxstud_filter(a[0].as_ptr(), a[1].as_ptr(), a[2].as_ptr()) },
+            &[SCHEMA, DATA, r#"{"genus": ["Tea"]}"#],
+        );
+        assert!(unknown["error"].as_str().unwrap().contains("genus"));
+
+        let text = call(|a| unsafe { taxstud_serialize(a[0].as_ptr()) }, &[DATA]);
+        let data: TaxonomyData = serde_json::from_str(text["ok"].as_str().unwrap()).unwrap();
+        assert_eq!(data.items.len(), 2);
+
+        let missing = call(|_| unsafe { taxstud_serialize(ptr::null()) }, &[]);
+        assert_eq!(missing["error"], "'data' is null");
+    }
+}
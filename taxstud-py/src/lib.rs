@@ -0,0 +1,266 @@
+//! Python bindings for taxstud-core, so notebooks load, validate, filter, group and
+//! export taxonomies with the same semantics as the GUI and CLI
+//!
+//! ```python
+//! import taxstud
+//! tax = taxstud.load("movies.json")
+//! dramas = tax.filter(genera=["Drama"], facets=["decade=1990s"], descendants=True)
+//! ```
+//! Items, schemas and groups come back as plain dicts and lists.
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::PathBuf;
+use taxstud_core::viz::{SUNBURST_SIZE, TREEMAP_HEIGHT, TREEMAP_WIDTH};
+use taxstud_core::{
+    load_data_with_schema, load_rules, load_schema, parse_facet_filters, write_skos,
+    write_sunburst_svg, write_treemap_svg, FilterOptions, Filters, GroupKey, GroupOptions,
+    MissingGroup, TaxonomyStore, UNSPECIFIED_GROUP,
+};
+
+create_exception!(taxstud, TaxStudError, PyException);
+
+/// A loaded taxonomy: its schema, items and house-style rules
+#[pyclass(module = "taxstud")]
+struct Taxonomy {
+    store: TaxonomyStore,
+}
+
+#[pymethods]
+impl Taxonomy {
+    /// The schema's title
+    #[getter]
+    fn title(&self) -> String {
+        self.store.schema().title.clone()
+    }
+
+    /// The JSON Schema document, as a dict
+    #[getter]
+    fn schema<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_python(py, &self.store.schema().json_schema())
+    }
+
+    /// All items, as dicts
+    #[getter]
+    fn items<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_python(py, self.store.items())
+    }
+
+    fn __len__(&self) -> usize {
+        self.store.items().len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "<Taxonomy '{}' with {} items>",
+            self.store.schema().title,
+            self.store.items().len()
+        )
+    }
+
+    /// Validation errors (JSON Schema, hierarchy, facets and error-severity rules);
+    /// empty when the data is valid
+    fn validate(&self) -> Vec<String> {
+        self.store.validate().err().unwrap_or_default()
+    }
+
+    /// Warnings that do not fail validation
+    fn lint(&self) -> Vec<String> {
+        self.store.lint()
+    }
+
+    /// Items matching every filter given, like the CLI's `query` options
+    /// Facet and field filters are "name=value" or numeric comparisons like "year>=1990";
+    /// deprecated items are left out unless `deprecated` is set. `ignore_case` and
+    /// `ignore_accents` loosen how genera, facet and field values compare
+    #[pyo3(signature = (
+        *,
+        genera = Vec::new(),
+        facets = Vec::new(),
+        not_genera = Vec::new(),
+        not_facets = Vec::new(),
+        fields = Vec::new(),
+        name_contains = None,
+        descendants = false,
+        deprecated = false,
+        ignore_case = false,
+        ignore_accents = false,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn filter<'py>(
+        &self,
+        py: Python<'py>,
+        genera: Vec<String>,
+        facets: Vec<String>,
+        not_genera: Vec<String>,
+        not_facets: Vec<String>,
+        fields: Vec<String>,
+        name_contains: Option<String>,
+        descendants: bool,
+        deprecated: bool,
+        ignore_case: bool,
+        ignore_accents: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let filters = Filters {
+            genera,
+            facets: parse_facet_filters(&facets),
+            excluded_genera: not_genera,
+            excluded_facets: parse_facet_filters(&not_facets),
+            fields: parse_facet_filters(&fields),
+            name_contains,
+            include_descendants: descendants,
+            include_deprecated: deprecated,
+            options: FilterOptions {
+                case_insensitive: ignore_case,
+                strip_diacritics: ignore_accents,
+            },
+        };
+        to_python(py, &self.store.filter(&filters))
+    }
+
+    /// Items grouped by a facet's values, as a dict of lists
+    /// Items without the facet go to the `missing_label` group, are left out
+    /// (`missing="skip"`) or raise TaxStudError (`missing="error"`); a `missing_label` that
+    /// is also one of the facet's values raises TaxStudError rather than merge the two
+    #[pyo3(signature = (facet, *, missing = "bucket", missing_label = UNSPECIFIED_GROUP))]
+    fn group<'py>(
+        &self,
+        py: Python<'py>,
+        facet: &str,
+        missing: &str,
+        missing_label: &str,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let missing = match missing {
+            "bucket" => MissingGroup::Bucket(missing_label.to_string()),
+            "skip" => MissingGroup::Skip,
+            "error" => MissingGroup::Error,
+            other => {
+                return Err(TaxStudError::new_err(format!(
+                    "Unknown missing mode '{}' (expected bucket, skip or error)",
+                    other
+                )))
+            }
+        };
+        let groups = self
+            .store
+            .group_with_options(facet, &GroupOptions { missing })
+            .map_err(error)?;
+        if groups.contains_key(&GroupKey::Value(missing_label.to_string()))
+            && groups.contains_key(&GroupKey::Missing(missing_label.to_string()))
+        {
+            return Err(TaxStudError::new_err(format!(
+                "'{}' is a value of '{}'; pass another missing_label",
+                missing_label, facet
+            )));
+        }
+        let groups: BTreeMap<_, _> = groups
+            .into_iter()
+            .map(|(key, items)| (key.to_string(), items))
+            .collect();
+        to_python(py, &groups)
+    }
+
+    /// The classical hierarchy as a SKOS concept scheme (RDF/XML)
+    fn export_skos(&self) -> PyResult<String> {
+        let mut out = Vec::new();
+        write_skos(self.store.schema(), &mut out).map_err(error)?;
+        String::from_utf8(out).map_err(error)
+    }
+
+    /// The classical hierarchy as a treemap SVG, areas sized by item count
+    #[pyo3(signature = (width = TREEMAP_WIDTH, height = TREEMAP_HEIGHT, lang = None))]
+    fn export_treemap(&self, width: f64, height: f64, lang: Option<&str>) -> PyResult<String> {
+        let mut out = Vec::new();
+        let hierarchy = &self.store.schema().classical_hierarchy;
+        write_treemap_svg(hierarchy, self.store.items(), width, height, lang, &mut out)
+            .map_err(error)?;
+        String::from_utf8(out).map_err(error)
+    }
+
+    /// The classical hierarchy as a sunburst SVG, rings by depth
+    #[pyo3(signature = (size = SUNBURST_SIZE, lang = None))]
+    fn export_sunburst(&self, size: f64, lang: Option<&str>) -> PyResult<String> {
+        let mut out = Vec::new();
+        let hierarchy = &self.store.schema().classical_hierarchy;
+        write_sunburst_svg(hierarchy, self.store.items(), size, lang, &mut out).map_err(error)?;
+        String::from_utf8(out).map_err(error)
+    }
+}
+
+/// Load a data file with the schema it references, or with `schema_path` when given
+#[pyfunction]
+#[pyo3(signature = (data_path, schema_path = None))]
+fn load(data_path: PathBuf, schema_path: Option<PathBuf>) -> PyResult<Taxonomy> {
+    let store = match schema_path {
+        Some(schema_path) => {
+            let schema = load_schema(&schema_path).map_err(error)?;
+            let rules = load_rules(&schema_path, &schema).map_err(error)?;
+            let data = load_data_with_schema(&data_path, &schema).map_err(error)?;
+            let mut store = TaxonomyStore::new(schema, data);
+            store.set_rules(rules);
+            store
+        }
+        None => TaxonomyStore::load(&data_path).map_err(error)?,
+    };
+    Ok(Taxonomy { store })
+}
+
+#[pymodule(name = "taxstud")]
+fn taxstud_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(load, m)?)?;
+    m.add_class::<Taxonomy>()?;
+    m.add("TaxStudError", m.py().get_type::<TaxStudError>())?;
+    Ok(())
+}
+
+fn error(e: impl Display) -> PyErr {
+    TaxStudError::new_err(e.to_string())
+}
+
+/// A value as Python dicts, lists and scalars, by way of JSON
+fn to_python<'py, T: Serialize + ?Sized>(
+    py: Python<'py>,
+    value: &T,
+) -> PyResult<Bound<'py, PyAny>> {
+    let text = serde_json::to_string(value).map_err(error)?;
+    py.import("json")?.call_method1("loads", (text,))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bindings() {
+        Python::initialize();
+        Python::attach(|py| {
+            let module = PyModule::new(py, "taxstud").unwrap();
+            taxstud_py(&module).unwrap();
+            let data = concat!(env!("CARGO_MANIFEST_DIR"), "/../assets/data.json");
+            let tax = module.getattr("load").unwrap().call1((data,)).unwrap();
+
+            let errors: Vec<String> = tax.call_method0("validate").unwrap().extract().unwrap();
+            assert!(errors.is_empty(), "{:?}", errors);
+
+            let kwargs = pyo3::types::PyDict::new(py);
+            kwargs.set_item("name_contains", "knight").unwrap();
+            let items = tax.call_method("filter", (), Some(&kwargs)).unwrap();
+            assert!(items.len().unwrap() > 0);
+            for item in items.try_iter().unwrap() {
+                let name: String = item.unwrap().get_item("name").unwrap().extract().unwrap();
+                assert!(name.to_lowercase().contains("knight"));
+            }
+
+            let svg: String = tax
+                .call_method0("export_treemap")
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(svg.starts_with("<svg"));
+        });
+    }
+}